@@ -38,6 +38,7 @@ fn generate_gl_bindings<W>(dest: &mut W) where W: Write {
             "GL_AMD_depth_clamp_separate",
             "GL_APPLE_vertex_array_object",
             "GL_ARB_bindless_texture",
+            "GL_ARB_blend_func_extended",
             "GL_ARB_buffer_storage",
             "GL_ARB_compute_shader",
             "GL_ARB_copy_buffer",
@@ -45,19 +46,25 @@ fn generate_gl_bindings<W>(dest: &mut W) where W: Write {
             "GL_ARB_depth_texture",
             "GL_ARB_direct_state_access",
             "GL_ARB_draw_buffers",
+            "GL_ARB_draw_buffers_blend",
             "GL_ARB_ES2_compatibility",
             "GL_ARB_ES3_compatibility",
             "GL_ARB_ES3_1_compatibility",
             "GL_ARB_ES3_2_compatibility",
             "GL_ARB_framebuffer_sRGB",
             "GL_ARB_geometry_shader4",
+            "GL_ARB_gl_spirv",
             "GL_ARB_gpu_shader_fp64",
             "GL_ARB_gpu_shader_int64",
             "GL_ARB_invalidate_subdata",
             "GL_ARB_multi_draw_indirect",
             "GL_ARB_occlusion_query",
+            "GL_ARB_parallel_shader_compile",
             "GL_ARB_pixel_buffer_object",
             "GL_ARB_robustness",
+            "GL_ARB_sample_shading",
+            "GL_ARB_separate_shader_objects",
+            "GL_ARB_shader_atomic_counters",
             "GL_ARB_shader_image_load_store",
             "GL_ARB_shader_objects",
             "GL_ARB_texture_buffer_object",
@@ -65,9 +72,11 @@ fn generate_gl_bindings<W>(dest: &mut W) where W: Write {
             "GL_ARB_texture_multisample",
             "GL_ARB_texture_rg",
             "GL_ARB_texture_rgb10_a2ui",
+            "GL_ARB_transform_feedback2",
             "GL_ARB_transform_feedback3",
             "GL_ARB_vertex_buffer_object",
             "GL_ARB_vertex_shader",
+            "GL_ARB_viewport_array",
             "GL_ATI_draw_buffers",
             "GL_ATI_meminfo",
             "GL_EXT_debug_marker",
@@ -76,6 +85,7 @@ fn generate_gl_bindings<W>(dest: &mut W) where W: Write {
             "GL_EXT_framebuffer_multisample",
             "GL_EXT_framebuffer_object",
             "GL_EXT_framebuffer_sRGB",
+            "GL_EXT_geometry_shader4",
             "GL_EXT_gpu_shader4",
             "GL_EXT_packed_depth_stencil",
             "GL_EXT_provoking_vertex",
@@ -87,10 +97,13 @@ fn generate_gl_bindings<W>(dest: &mut W) where W: Write {
             "GL_EXT_texture_sRGB",
             "GL_EXT_transform_feedback",
             "GL_GREMEDY_string_marker",
+            "GL_KHR_parallel_shader_compile",
             "GL_KHR_robustness",
             "GL_NVX_gpu_memory_info",
             "GL_NV_conditional_render",
             "GL_NV_vertex_attrib_integer_64bit",
+            "GL_OVR_multiview",
+            "GL_OVR_multiview2",
         ],
     );
 
@@ -104,27 +117,37 @@ fn generate_gl_bindings<W>(dest: &mut W) where W: Write {
             "GL_APPLE_framebuffer_multisample",
             "GL_APPLE_sync",
             "GL_ARM_rgba8",
+            "GL_EXT_blend_func_extended",
             "GL_EXT_buffer_storage",
+            "GL_EXT_discard_framebuffer",
             "GL_EXT_disjoint_timer_query",
+            "GL_EXT_geometry_shader",
             "GL_EXT_multi_draw_indirect",
             "GL_EXT_multisampled_render_to_texture",
             "GL_EXT_occlusion_query_boolean",
             "GL_EXT_primitive_bounding_box",
             "GL_EXT_robustness",
             "GL_KHR_debug",
+            "GL_KHR_parallel_shader_compile",
             "GL_NV_copy_buffer",
             "GL_NV_framebuffer_multisample",
             "GL_NV_internalformat_sample_query",
             "GL_NV_pixel_buffer_object",
             "GL_OES_depth_texture",
             "GL_OES_draw_elements_base_vertex",
+            "GL_OES_geometry_shader",
+            "GL_OES_EGL_image",
+            "GL_OES_EGL_image_external",
             "GL_OES_packed_depth_stencil",
             "GL_OES_primitive_bounding_box",
             "GL_OES_rgb8_rgba8",
+            "GL_OES_sample_shading",
             "GL_OES_texture_buffer",
             "GL_OES_texture_npot",
             "GL_OES_vertex_array_object",
             "GL_OES_vertex_type_10_10_10_2",
+            "GL_OVR_multiview",
+            "GL_OVR_multiview2",
         ],
     );
 
@@ -324,7 +324,7 @@ fn build_texture<W: Write>(mut dest: &mut W, ty: TextureType, dimensions: Textur
             use image_format::{{CompressedSrgbFormat, SrgbFormat, UncompressedUintFormat}};
 
             use backend::Facade;
-            use uniforms::{{UniformValue, AsUniformValue, Sampler}};
+            use uniforms::{{UniformValue, AsUniformValue, Sampler, SamplerBehavior}};
             use framebuffer;
             use Rect;
 
@@ -453,6 +453,40 @@ fn build_texture<W: Write>(mut dest: &mut W, ty: TextureType, dimensions: Textur
                                 pub fn sampled(&self) -> Sampler<{myname}> {{
                                     Sampler(self, Default::default())
                                 }}
+
+                                /// Builds a `Sampler` marker object that samples the texture
+                                /// with the given behavior.
+                                ///
+                                /// The sampler object that corresponds to the behavior is
+                                /// cached by the context, and the parameters of the texture
+                                /// itself are never modified. The same texture can therefore
+                                /// be sampled differently from one draw call to another.
+                                ///
+                                /// # Example
+                                ///
+                                /// ```no_run
+                                /// # #[macro_use] extern crate glium;
+                                /// # fn main() {{
+                                /// # let texture: glium::texture::Texture2d = unsafe {{
+                                /// # ::std::mem::uninitialized() }};
+                                /// use glium::uniforms::{{SamplerBehavior, MagnifySamplerFilter}};
+                                ///
+                                /// let nearest = SamplerBehavior {{
+                                ///     magnify_filter: MagnifySamplerFilter::Nearest,
+                                ///     .. Default::default()
+                                /// }};
+                                ///
+                                /// let uniforms = uniform! {{
+                                ///     color_texture: texture.sampled_with(nearest)
+                                /// }};
+                                /// # }}
+                                /// ```
+                                #[inline]
+                                pub fn sampled_with(&self, behavior: SamplerBehavior)
+                                                    -> Sampler<{myname}>
+                                {{
+                                    Sampler(self, behavior)
+                                }}
                             }}
                         ", myname = name)).unwrap();
             },
@@ -614,6 +648,61 @@ fn build_texture<W: Write>(mut dest: &mut W, ty: TextureType, dimensions: Textur
                mipmaps = mipmaps_option_ty).unwrap());
     }
 
+    // writing the `with_compressed_mipmaps` function
+    // cubemaps are excluded, like for the other constructors that take data, because there is no
+    // way yet to pass the data of each face
+    if is_compressed && !dimensions.is_multisample() && !dimensions.is_cube() {
+        let (level_param, level_data, layers_doc) = match dimensions {
+            TextureDimensions::Texture1d | TextureDimensions::Texture2d |
+            TextureDimensions::Texture3d => ("&[u8]", "Cow::Borrowed(level)", ""),
+
+            TextureDimensions::Texture1dArray |
+            TextureDimensions::Texture2dArray => {
+                ("Vec<&[u8]>", "Cow::Owned(level.concat())",
+                 "\n                /// Each level contains the data of all the layers, in order.")
+            },
+
+            _ => unreachable!()
+        };
+
+        (writeln!(dest, "
+                /// Builds a new texture from pre-compressed data for each mipmap level.
+                ///
+                /// The first element of `levels` is the main level, the second element the first
+                /// mipmap, and so on. The texture will have exactly `levels.len()` levels.{layers}
+                ///
+                /// Returns an error if `levels` contains more levels than possible for these
+                /// dimensions, or if the size of the data of a level doesn't match the number of
+                /// blocks of this level.
+                ///
+                /// This function isn't available for compressed cubemaps and cubemap arrays.
+                /// Like the other constructors that take data, it would need the data of each
+                /// face, which glium can't upload yet.
+                ///
+                /// ## Panic
+                ///
+                /// Panics if `levels` is empty.
+                pub fn with_compressed_mipmaps<F: ?Sized>(facade: &F, levels: Vec<{level_param}>,
+                                                          {dim_params}, format: {format})
+                                                          -> Result<{name}, TextureCreationError>
+                                                          where F: Facade
+                {{
+                    let levels: Vec<Cow<[u8]>> = levels.into_iter().map(|level| {level_data})
+                                                       .collect();
+                    let client_format = {client_format_any}(format);
+                    let texture = any::new_compressed_texture_with_mipmaps(facade,
+                                                                          {default_format},
+                                                                          client_format, &levels,
+                                                                          {dim_params_passing});
+                    Ok({name}(try!(texture)))
+                }}
+            ", dim_params = dimensions_parameters_input,
+               dim_params_passing = dimensions_parameters_passing, level_param = level_param,
+               level_data = level_data, layers = layers_doc,
+               client_format_any = client_format_any_ty, default_format = default_format,
+               name = name, format = relevant_format).unwrap());
+    }
+
     // writing the `with_format` function
     if !dimensions.is_multisample() && !dimensions.is_cube() {
         let param = match dimensions {
@@ -735,6 +824,45 @@ fn build_texture<W: Write>(mut dest: &mut W, ty: TextureType, dimensions: Textur
         (writeln!(dest, "}}")).unwrap();
     }
 
+    // writing the `empty_with_nearest_format` function
+    if ty == TextureType::Regular {
+        let dimensions_parameters_passing_names = dimensions_parameters_input.replace(": u32", "");
+
+        (writeln!(dest, "
+                /// Creates an empty texture with the format that is the closest to `format` among
+                /// the formats supported by the backend.
+                ///
+                /// If `renderable` is true, the chosen format is also guaranteed to be
+                /// color-renderable. For example on OpenGL ES, requesting `F16F16F16` gives back
+                /// a texture of format `F16F16F16F16`.
+                ///
+                /// Returns the texture and the format that was chosen. See
+                /// `UncompressedFloatFormat::get_nearest_supported` for how the format is chosen.
+                ///
+                /// The texture (and its mipmaps) will contain undefined data.
+                pub fn empty_with_nearest_format<F: ?Sized>(facade: &F, format: {format}, renderable: bool,
+                                                            mipmaps: {mipmaps}, {dim_params})
+                                                            -> Result<({name}, {format}), TextureCreationError>
+                                                            where F: Facade
+                {{
+                    let chosen = if renderable {{
+                        format.get_nearest_renderable(facade)
+                    }} else {{
+                        format.get_nearest_supported(facade)
+                    }};
+
+                    let chosen = match chosen {{
+                        Some(f) => f,
+                        None => return Err(TextureCreationError::FormatNotSupported),
+                    }};
+
+                    let texture = try!({name}::empty_with_format(facade, chosen, mipmaps, {dim_params_passing}));
+                    Ok((texture, chosen))
+                }}
+            ", format = relevant_format, dim_params = dimensions_parameters_input, name = name,
+               dim_params_passing = dimensions_parameters_passing_names, mipmaps = mipmaps_option_ty)).unwrap();
+    }
+
     // writing the `empty_with_format` function
     if true {
         // opening function
@@ -780,11 +908,25 @@ fn build_texture<W: Write>(mut dest: &mut W, ty: TextureType, dimensions: Textur
     }
 
     // writing the 'from_id' function
+    let dimensions_pattern = format!("{} {{ .. }}",
+                                     dimensions_parameters_passing.split(' ').next().unwrap());
     (writeln!(dest, "
                 /// Builds a new texture reference from an existing, externally created OpenGL texture.
                 /// If `owned` is true, this reference will take ownership of the texture and be responsible
                 /// for cleaning it up. Otherwise, the texture must be cleaned up externally, but only
                 /// after this reference's lifetime has ended.
+                ///
+                /// This can be used to sample or draw to textures that were created by other
+                /// libraries that share the same OpenGL context.
+                ///
+                /// ## Panic
+                ///
+                /// Panics if `ty` is not a `{dimensions_pattern}`.
+                ///
+                /// ## Safety
+                ///
+                /// The texture must exist, must have been created with the given format and
+                /// dimensions, and must have the number of mipmaps described by `mipmap`.
                 pub unsafe fn from_id<F: Facade + ?Sized>(facade: &F,
                                                  format: {format},
                                                  id: gl::types::GLuint,
@@ -792,11 +934,17 @@ fn build_texture<W: Write>(mut dest: &mut W, ty: TextureType, dimensions: Textur
                                                  mipmap: MipmapsOption,
                                                  ty: Dimensions)
                                                  -> {name} {{
+                    match ty {{
+                        {dimensions_pattern} => (),
+                        _ => panic!(\"The dimensions don't match the type of texture\")
+                    }};
+
                     let format = format.to_texture_format();
                     let format = TextureFormatRequest::Specific(format);
                     {name}(any::from_id(facade, format, id, owned, mipmap, ty))
                 }}
-        ", format = relevant_format, name = name)).unwrap();
+        ", format = relevant_format, name = name,
+           dimensions_pattern = dimensions_pattern)).unwrap();
 
     // dimensions getters
     write_dimensions_getters(dest, dimensions, "self.0", true);
@@ -1039,6 +1187,19 @@ fn build_texture<W: Write>(mut dest: &mut W, ty: TextureType, dimensions: Textur
             }}
         "#, name = name)).unwrap();
 
+    if dimensions == TextureDimensions::Cubemap {
+        (write!(dest, r#"
+                /// Access a face of the main mipmap level of this cubemap.
+                ///
+                /// The returned object can be written, read, or attached to a framebuffer in
+                /// order to render to this face.
+                #[inline]
+                pub fn face(&self, face: CubeLayer) -> {name}Image {{
+                    self.main_level().image(face)
+                }}
+            "#, name = name)).unwrap();
+    }
+
     // closing `impl Texture` block
     (writeln!(dest, "}}")).unwrap();
 
@@ -1233,6 +1394,30 @@ fn build_texture<W: Write>(mut dest: &mut W, ty: TextureType, dimensions: Textur
                 "#, format = relevant_format, client_format_any = client_format_any_ty)).unwrap();
         }
 
+        // writing the `as_surface` function for mipmaps
+        if (dimensions == TextureDimensions::Texture2d ||
+            dimensions == TextureDimensions::Texture2dMultisample) &&
+           (ty == TextureType::Regular ||
+            ty == TextureType::Integral ||
+            ty == TextureType::Unsigned)
+        {
+            (write!(dest, "
+                    /// Starts drawing on this mipmap level of the texture.
+                    ///
+                    /// This is typically used to generate the mipmaps of a texture on the GPU,
+                    /// by drawing each level while sampling the previous one.
+                    ///
+                    /// ## Panic
+                    ///
+                    /// Panics if the backend doesn't support attaching a mipmap level other than
+                    /// the main level (OpenGL ES 2 without `GL_OES_fbo_render_mipmap`).
+                    #[inline]
+                    pub fn as_surface(&self) -> framebuffer::SimpleFrameBuffer<'t> {{
+                        framebuffer::SimpleFrameBuffer::new(self.0.get_texture().get_context(), *self).unwrap()
+                    }}
+                ")).unwrap();
+        }
+
         // writing the `get_level` and `get_texture` functions
         (write!(dest, "
                 /// Returns the corresponding texture.
@@ -1338,6 +1523,75 @@ fn build_texture<W: Write>(mut dest: &mut W, ty: TextureType, dimensions: Textur
         // dimensions getters
         write_dimensions_getters(dest, dimensions, "self.0", false);
 
+        (write!(dest, "
+                /// Returns the corresponding texture.
+                #[inline]
+                pub fn get_texture(&self) -> &'t {name} {{
+                    self.1
+                }}
+
+                /// Returns the texture level.
+                #[inline]
+                pub fn get_level(&self) -> u32 {{
+                    self.0.get_level()
+                }}
+
+                /// Returns the face of the cubemap.
+                #[inline]
+                pub fn get_cube_layer(&self) -> CubeLayer {{
+                    self.0.get_cubemap_layer().unwrap()
+                }}
+        ", name = name)).unwrap();
+
+        // writing the `write` and `read` functions
+        if ty == TextureType::Regular || ty == TextureType::Srgb {
+            (write!(dest, r#"
+                    /// Uploads some data in this face of the cubemap.
+                    ///
+                    /// Note that this may cause a synchronization if you use the texture right before
+                    /// or right after this call.
+                    ///
+                    /// ## Panic
+                    ///
+                    /// Panics if the the dimensions of `data` don't match the `Rect`.
+                    pub fn write<'a, T>(&self, rect: Rect, data: T) where T: Texture2dDataSource<'a> {{
+                        let RawImage2d {{ data, width, height, format: client_format }} =
+                                                data.into_raw();
+
+                        assert_eq!(width, rect.width);
+                        assert_eq!(height, rect.height);
+
+                        self.0.raw_upload(&rect, data, client_format)
+                    }}
+
+                    /// Reads the content of this face of the cubemap to RAM. This method may only
+                    /// read `U8U8U8U8` data, as it is the only format guaranteed to be supported
+                    /// across all OpenGL versions.
+                    ///
+                    /// You should avoid doing this at all cost during performance-critical
+                    /// operations (for example, while you're drawing).
+                    #[inline]
+                    pub fn read<T>(&self) -> T where T: Texture2dDataSink<(u8, u8, u8, u8)> {{
+                        let rect = Rect {{ left: 0, bottom: 0, width: self.width(),
+                                           height: self.height() }};
+                        self.0.raw_read(&rect)
+                    }}
+
+                    /// Reads the content of this face of the cubemap into a buffer in video memory.
+                    /// This method may only read `U8U8U8U8` data, as it is the only format
+                    /// guaranteed to be supported across all OpenGL versions.
+                    #[inline]
+                    pub fn read_to_pixel_buffer(&self) -> PixelBuffer<(u8, u8, u8, u8)> {{
+                        let rect = Rect {{ left: 0, bottom: 0, width: self.width(),
+                                           height: self.height() }};
+                        let pb = PixelBuffer::new_empty(self.1.get_context(),
+                                                        rect.width as usize * rect.height as usize);
+                        self.0.raw_read_to_pixel_buffer(&rect, &pb);
+                        pb
+                    }}
+                "#)).unwrap();
+        }
+
         // closing `impl Image` block
         (writeln!(dest, "}}")).unwrap();
 
@@ -1404,6 +1658,47 @@ fn build_texture<W: Write>(mut dest: &mut W, ty: TextureType, dimensions: Textur
         }
     }
 
+    // implement the layered attachments traits
+    if dimensions.is_array() || dimensions.is_cube() || dimensions == TextureDimensions::Texture3d {
+        match ty {
+            TextureType::Regular | TextureType::Srgb | TextureType::Integral | TextureType::Unsigned => {
+                (writeln!(dest, "
+                        impl<'t> ::framebuffer::ToLayeredColorAttachment<'t> for {name}Mipmap<'t> {{
+                            #[inline]
+                            fn to_layered_color_attachment(self) -> ::framebuffer::LayeredColorAttachment<'t> {{
+                                ::framebuffer::LayeredColorAttachment::Texture(self.0)
+                            }}
+                        }}
+
+                        impl<'t> ::framebuffer::ToLayeredColorAttachment<'t> for &'t {name} {{
+                            #[inline]
+                            fn to_layered_color_attachment(self) -> ::framebuffer::LayeredColorAttachment<'t> {{
+                                ::framebuffer::LayeredColorAttachment::Texture(self.main_level().0)
+                            }}
+                        }}
+                    ", name = name)).unwrap();
+            },
+            TextureType::Depth => {
+                (writeln!(dest, "
+                        impl<'t> ::framebuffer::ToLayeredDepthAttachment<'t> for {name}Mipmap<'t> {{
+                            #[inline]
+                            fn to_layered_depth_attachment(self) -> ::framebuffer::LayeredDepthAttachment<'t> {{
+                                ::framebuffer::LayeredDepthAttachment::Texture(self.0)
+                            }}
+                        }}
+
+                        impl<'t> ::framebuffer::ToLayeredDepthAttachment<'t> for &'t {name} {{
+                            #[inline]
+                            fn to_layered_depth_attachment(self) -> ::framebuffer::LayeredDepthAttachment<'t> {{
+                                ::framebuffer::LayeredDepthAttachment::Texture(self.main_level().0)
+                            }}
+                        }}
+                    ", name = name)).unwrap();
+            },
+            _ => ()
+        }
+    }
+
     // closing `mod module {`
     writeln!(dest, "}}").unwrap();
 }
@@ -281,7 +281,7 @@ impl Alloc {
     }
 
     /// Makes sure that the buffer is binded to the `GL_QUERY_BUFFER` and calls
-    /// `glMemoryBarrier(GL_PIXEL_BUFFER_BARRIER_BIT)` if necessary.
+    /// `glMemoryBarrier(GL_QUERY_BUFFER_BARRIER_BIT)` if necessary.
     pub fn prepare_and_bind_for_query(&self, mut ctxt: &mut CommandContext) {
         assert!(ctxt.version >= &Version(Api::Gl, 4, 4) ||
                 ctxt.extensions.gl_arb_query_buffer_object ||
@@ -290,7 +290,7 @@ impl Alloc {
         self.assert_unmapped(ctxt);
         self.assert_not_transform_feedback(ctxt);
 
-        if self.latest_shader_write.get() >= ctxt.state.latest_memory_barrier_pixel_buffer {
+        if self.latest_shader_write.get() >= ctxt.state.latest_memory_barrier_query_buffer {
             unsafe { ctxt.gl.MemoryBarrier(gl::QUERY_BUFFER_BARRIER_BIT); }
             ctxt.state.latest_memory_barrier_query_buffer = ctxt.state.next_draw_call_id;
         }
@@ -366,6 +366,24 @@ impl Alloc {
         self.latest_shader_write.set(ctxt.state.next_draw_call_id);        // TODO: put this somewhere else
     }
 
+    /// Makes sure that the buffer is binded to the indexed `GL_ATOMIC_COUNTER_BUFFER` point and
+    /// calls `glMemoryBarrier(GL_ATOMIC_COUNTER_BARRIER_BIT)` if necessary.
+    pub fn prepare_and_bind_for_atomic_counter(&self, ctxt: &mut CommandContext,
+                                               index: gl::types::GLuint, range: Range<usize>)
+    {
+        self.assert_unmapped(ctxt);
+        self.assert_not_transform_feedback(ctxt);
+
+        if self.latest_shader_write.get() >= ctxt.state.latest_memory_barrier_atomic_counter {
+            unsafe { ctxt.gl.MemoryBarrier(gl::ATOMIC_COUNTER_BARRIER_BIT); }
+            ctxt.state.latest_memory_barrier_atomic_counter = ctxt.state.next_draw_call_id;
+        }
+
+        self.indexed_bind(ctxt, BufferType::AtomicCounterBuffer, index, range);
+
+        self.latest_shader_write.set(ctxt.state.next_draw_call_id);
+    }
+
     /// Binds the buffer to `GL_TRANSFORM_FEEDBACk_BUFFER` regardless of the current transform
     /// feedback object.
     #[inline]
@@ -1343,6 +1361,11 @@ fn is_buffer_type_supported(ctxt: &mut CommandContext, ty: BufferType) -> bool {
             ctxt.extensions.gl_ext_texture_buffer || ctxt.extensions.gl_oes_texture_buffer
         },
 
+        BufferType::AtomicCounterBuffer => {
+            ctxt.version >= &Version(Api::Gl, 4, 2) || ctxt.version >= &Version(Api::GlEs, 3, 1) ||
+            ctxt.extensions.gl_arb_shader_atomic_counters
+        },
+
         BufferType::QueryBuffer => {
             ctxt.version >= &Version(Api::Gl, 4, 4) ||
             ctxt.extensions.gl_arb_query_buffer_object ||
@@ -377,6 +377,16 @@ impl<T> Buffer<[T]> where [T]: Content, T: Copy {
     pub fn slice_mut<R: RangeArgument<usize>>(&mut self, range: R) -> Option<BufferMutSlice<[T]>> {
         self.as_mut_slice().slice(range)
     }
+
+    /// Builds a slice that contains only the element at the given index. Returns `None` if out
+    /// of range.
+    ///
+    /// This method builds an object that represents a slice of the buffer. No actual operation
+    /// OpenGL is performed.
+    #[inline]
+    pub fn get(&self, index: usize) -> Option<BufferSlice<T>> {
+        self.as_slice().get(index)
+    }
 }
 
 impl<T> Buffer<[T]> where T: PixelValue {
@@ -485,6 +495,12 @@ impl<T: ?Sized> BufferExt for Buffer<T> where T: Content {
         alloc.prepare_and_bind_for_shared_storage(ctxt, index, 0 .. alloc.get_size());
     }
 
+    #[inline]
+    fn prepare_and_bind_for_atomic_counter(&self, ctxt: &mut CommandContext, index: gl::types::GLuint) {
+        let alloc = self.alloc.as_ref().unwrap();
+        alloc.prepare_and_bind_for_atomic_counter(ctxt, index, 0 .. alloc.get_size());
+    }
+
     #[inline]
     fn bind_to_transform_feedback(&self, ctxt: &mut CommandContext, index: gl::types::GLuint) {
         let alloc = self.alloc.as_ref().unwrap();
@@ -674,6 +690,26 @@ impl<'a, T> BufferSlice<'a, [T]> where [T]: Content + 'a {
             marker: PhantomData,
         })
     }
+
+    /// Builds a slice that contains only the element at the given index. Returns `None` if out
+    /// of range.
+    ///
+    /// This is useful for example to bind one element of an array of uniform blocks stored in
+    /// a single buffer.
+    #[inline]
+    pub fn get(&self, index: usize) -> Option<BufferSlice<'a, T>> where T: Copy {
+        if index >= self.len() {
+            return None;
+        }
+
+        Some(BufferSlice {
+            alloc: self.alloc,
+            bytes_start: self.bytes_start + index * mem::size_of::<T>(),
+            bytes_end: self.bytes_start + (index + 1) * mem::size_of::<T>(),
+            fence: self.fence,
+            marker: PhantomData,
+        })
+    }
 }
 
 impl<'a, T> BufferSlice<'a, [T]> where T: PixelValue + 'a {
@@ -793,17 +829,22 @@ impl<'a, T: ?Sized> BufferExt for BufferSlice<'a, T> where T: Content {
 
     #[inline]
     fn prepare_and_bind_for_uniform(&self, ctxt: &mut CommandContext, index: gl::types::GLuint) {
-        self.alloc.prepare_and_bind_for_uniform(ctxt, index, 0 .. self.alloc.get_size());
+        self.alloc.prepare_and_bind_for_uniform(ctxt, index, self.bytes_start .. self.bytes_end);
     }
 
     #[inline]
     fn prepare_and_bind_for_shared_storage(&self, ctxt: &mut CommandContext, index: gl::types::GLuint) {
-        self.alloc.prepare_and_bind_for_shared_storage(ctxt, index, 0 .. self.alloc.get_size());
+        self.alloc.prepare_and_bind_for_shared_storage(ctxt, index, self.bytes_start .. self.bytes_end);
+    }
+
+    #[inline]
+    fn prepare_and_bind_for_atomic_counter(&self, ctxt: &mut CommandContext, index: gl::types::GLuint) {
+        self.alloc.prepare_and_bind_for_atomic_counter(ctxt, index, self.bytes_start .. self.bytes_end);
     }
 
     #[inline]
     fn bind_to_transform_feedback(&self, ctxt: &mut CommandContext, index: gl::types::GLuint) {
-        self.alloc.bind_to_transform_feedback(ctxt, index, 0 .. self.alloc.get_size());
+        self.alloc.bind_to_transform_feedback(ctxt, index, self.bytes_start .. self.bytes_end);
     }
 }
 
@@ -1242,6 +1283,11 @@ impl BufferExt for BufferAny {
         self.alloc.prepare_and_bind_for_shared_storage(ctxt, index, 0 .. self.alloc.get_size());
     }
 
+    #[inline]
+    fn prepare_and_bind_for_atomic_counter(&self, ctxt: &mut CommandContext, index: gl::types::GLuint) {
+        self.alloc.prepare_and_bind_for_atomic_counter(ctxt, index, 0 .. self.alloc.get_size());
+    }
+
     #[inline]
     fn bind_to_transform_feedback(&self, ctxt: &mut CommandContext, index: gl::types::GLuint) {
         self.alloc.bind_to_transform_feedback(ctxt, index, 0 .. self.alloc.get_size());
@@ -1385,16 +1431,21 @@ impl<'a> BufferExt for BufferAnySlice<'a> {
 
     #[inline]
     fn prepare_and_bind_for_uniform(&self, ctxt: &mut CommandContext, index: gl::types::GLuint) {
-        self.alloc.prepare_and_bind_for_uniform(ctxt, index, 0 .. self.alloc.get_size());
+        self.alloc.prepare_and_bind_for_uniform(ctxt, index, self.bytes_start .. self.bytes_end);
     }
 
     #[inline]
     fn prepare_and_bind_for_shared_storage(&self, ctxt: &mut CommandContext, index: gl::types::GLuint) {
-        self.alloc.prepare_and_bind_for_shared_storage(ctxt, index, 0 .. self.alloc.get_size());
+        self.alloc.prepare_and_bind_for_shared_storage(ctxt, index, self.bytes_start .. self.bytes_end);
+    }
+
+    #[inline]
+    fn prepare_and_bind_for_atomic_counter(&self, ctxt: &mut CommandContext, index: gl::types::GLuint) {
+        self.alloc.prepare_and_bind_for_atomic_counter(ctxt, index, self.bytes_start .. self.bytes_end);
     }
 
     #[inline]
     fn bind_to_transform_feedback(&self, ctxt: &mut CommandContext, index: gl::types::GLuint) {
-        self.alloc.bind_to_transform_feedback(ctxt, index, 0 .. self.alloc.get_size());
+        self.alloc.bind_to_transform_feedback(ctxt, index, self.bytes_start .. self.bytes_end);
     }
 }
@@ -80,6 +80,9 @@ pub struct Capabilities {
     /// Number of bits in the default framebuffer's stencil buffer
     pub stencil_bits: Option<u16>,
 
+    /// Number of samples of the default framebuffer, or `None` if it is not multisampled.
+    pub samples: Option<u16>,
+
     /// Informations about formats when used to create textures.
     pub internal_formats_textures: HashMap<TextureFormat, FormatInfos, BuildHasherDefault<FnvHasher>>,
 
@@ -102,9 +105,24 @@ pub struct Capabilities {
     /// Maximum width and height of `glViewport`.
     pub max_viewport_dims: (gl::types::GLint, gl::types::GLint),
 
+    /// Minimum and maximum width of lines. Widths outside of this range are clamped by glium.
+    pub line_width_range: (gl::types::GLfloat, gl::types::GLfloat),
+
+    /// Maximum number of viewports and scissor boxes that can be used at the same time. `1` if
+    /// viewport arrays are not supported.
+    pub max_viewports: gl::types::GLint,
+
     /// Maximum number of elements that can be passed with `glDrawBuffers`.
     pub max_draw_buffers: gl::types::GLint,
 
+    /// Maximum number of draw buffers that can be used while blending with the second output
+    /// of the fragment shader. `0` if dual-source blending is not supported.
+    pub max_dual_source_draw_buffers: gl::types::GLint,
+
+    /// Maximum number of image units that can be used at the same time. `0` if image load and
+    /// store is not supported.
+    pub max_image_units: gl::types::GLint,
+
     /// Maximum number of vertices per patch. `None` if tessellation is not supported.
     pub max_patch_vertices: Option<gl::types::GLint>,
 
@@ -120,9 +138,23 @@ pub struct Capabilities {
     /// Number of available buffer bind points for `GL_UNIFORM_BUFFER`.
     pub max_indexed_uniform_buffer: gl::types::GLint,
 
+    /// Required alignment in bytes of the offset of a range of a buffer bound to
+    /// `GL_UNIFORM_BUFFER`. `1` if uniform buffers are not supported.
+    pub uniform_buffer_offset_alignment: gl::types::GLint,
+
+    /// Required alignment in bytes of the offset of a range of a buffer bound to
+    /// `GL_SHADER_STORAGE_BUFFER`. `1` if shader storage buffers are not supported.
+    pub shader_storage_buffer_offset_alignment: gl::types::GLint,
+
     /// Number of work groups for compute shaders.
     pub max_compute_work_group_count: (gl::types::GLint, gl::types::GLint, gl::types::GLint),
 
+    /// Maximum size of a local work group for compute shaders.
+    pub max_compute_work_group_size: (gl::types::GLint, gl::types::GLint, gl::types::GLint),
+
+    /// Maximum number of invocations in a local work group for compute shaders.
+    pub max_compute_work_group_invocations: gl::types::GLint,
+
     /// Maximum number of color attachment bind points.
     pub max_color_attachments: gl::types::GLint,
 
@@ -137,6 +169,10 @@ pub struct Capabilities {
 
     /// Maximum samples of an empty framebuffer. `None` if not supported.
     pub max_framebuffer_samples: Option<gl::types::GLint>,
+
+    /// Maximum number of views that can be rendered at once with multiview. `None` if
+    /// multiview is not supported.
+    pub max_views: Option<gl::types::GLint>,
 }
 
 /// Information about an internal format.
@@ -381,6 +417,21 @@ pub unsafe fn get_capabilities(gl: &gl::Gl, version: &Version, extensions: &Exte
             }
         },
 
+        samples: {
+            if version >= &Version(Api::Gl, 1, 3) || version >= &Version(Api::GlEs, 2, 0) {
+                let mut value = mem::uninitialized();
+                gl.GetIntegerv(gl::SAMPLES, &mut value);
+
+                match value {
+                    0 | 1 => None,
+                    v => Some(v as u16),
+                }
+
+            } else {
+                None
+            }
+        },
+
         internal_formats_textures: get_internal_formats(gl, version, extensions, false),
         internal_formats_renderbuffers: get_internal_formats(gl, version, extensions, true),
 
@@ -430,6 +481,28 @@ pub unsafe fn get_capabilities(gl: &gl::Gl, version: &Version, extensions: &Exte
             (val[0], val[1])
         },
 
+        line_width_range: {
+            let mut val: [gl::types::GLfloat; 2] = [1.0, 1.0];
+            gl.GetFloatv(gl::ALIASED_LINE_WIDTH_RANGE, val.as_mut_ptr());
+
+            // forward-compatible contexts produce an error for lines wider than 1.0
+            if forward_compatible {
+                (val[0], 1.0)
+            } else {
+                (val[0], val[1])
+            }
+        },
+
+        max_viewports: {
+            if version >= &Version(Api::Gl, 4, 1) || extensions.gl_arb_viewport_array {
+                let mut val = 1;
+                gl.GetIntegerv(gl::MAX_VIEWPORTS, &mut val);
+                val
+            } else {
+                1
+            }
+        },
+
         max_draw_buffers: {
             if version >= &Version(Api::Gl, 2, 0) ||
                 version >= &Version(Api::GlEs, 3, 0) ||
@@ -443,6 +516,30 @@ pub unsafe fn get_capabilities(gl: &gl::Gl, version: &Version, extensions: &Exte
             }
         },
 
+        max_dual_source_draw_buffers: {
+            if version >= &Version(Api::Gl, 3, 3) || extensions.gl_arb_blend_func_extended ||
+                extensions.gl_ext_blend_func_extended
+            {
+                let mut val = 0;
+                gl.GetIntegerv(gl::MAX_DUAL_SOURCE_DRAW_BUFFERS, &mut val);
+                val
+            } else {
+                0
+            }
+        },
+
+        max_image_units: {
+            if version >= &Version(Api::Gl, 4, 2) || version >= &Version(Api::GlEs, 3, 1) ||
+                extensions.gl_arb_shader_image_load_store
+            {
+                let mut val = 0;
+                gl.GetIntegerv(gl::MAX_IMAGE_UNITS, &mut val);
+                val
+            } else {
+                0
+            }
+        },
+
         max_patch_vertices: if version >= &Version(Api::Gl, 4, 0) ||
             extensions.gl_arb_tessellation_shader
         {
@@ -456,7 +553,9 @@ pub unsafe fn get_capabilities(gl: &gl::Gl, version: &Version, extensions: &Exte
             None
         },
 
-        max_indexed_atomic_counter_buffer: if version >= &Version(Api::Gl, 4, 2) {      // TODO: ARB_shader_atomic_counters   // TODO: GLES
+        max_indexed_atomic_counter_buffer: if version >= &Version(Api::Gl, 4, 2) ||
+            version >= &Version(Api::GlEs, 3, 1) || extensions.gl_arb_shader_atomic_counters
+        {
             let mut val = mem::uninitialized();
             gl.GetIntegerv(gl::MAX_ATOMIC_COUNTER_BUFFER_BINDINGS, &mut val);
             val
@@ -465,7 +564,9 @@ pub unsafe fn get_capabilities(gl: &gl::Gl, version: &Version, extensions: &Exte
         },
 
         max_indexed_shader_storage_buffer: {
-            if version >= &Version(Api::Gl, 4, 3) || extensions.gl_arb_shader_storage_buffer_object {      // TODO: GLES
+            if version >= &Version(Api::Gl, 4, 3) || version >= &Version(Api::GlEs, 3, 1) ||
+               extensions.gl_arb_shader_storage_buffer_object
+            {
                 let mut val = mem::uninitialized();
                 gl.GetIntegerv(gl::MAX_SHADER_STORAGE_BUFFER_BINDINGS, &mut val);
                 val
@@ -489,7 +590,9 @@ pub unsafe fn get_capabilities(gl: &gl::Gl, version: &Version, extensions: &Exte
         },
 
         max_indexed_uniform_buffer: {
-            if version >= &Version(Api::Gl, 3, 1) || extensions.gl_arb_uniform_buffer_object {      // TODO: GLES
+            if version >= &Version(Api::Gl, 3, 1) || version >= &Version(Api::GlEs, 3, 0) ||
+               extensions.gl_arb_uniform_buffer_object
+            {
                 let mut val = mem::uninitialized();
                 gl.GetIntegerv(gl::MAX_UNIFORM_BUFFER_BINDINGS, &mut val);
                 val
@@ -498,6 +601,30 @@ pub unsafe fn get_capabilities(gl: &gl::Gl, version: &Version, extensions: &Exte
             }
         },
 
+        uniform_buffer_offset_alignment: {
+            if version >= &Version(Api::Gl, 3, 1) || version >= &Version(Api::GlEs, 3, 0) ||
+               extensions.gl_arb_uniform_buffer_object
+            {
+                let mut val = mem::uninitialized();
+                gl.GetIntegerv(gl::UNIFORM_BUFFER_OFFSET_ALIGNMENT, &mut val);
+                val
+            } else {
+                1
+            }
+        },
+
+        shader_storage_buffer_offset_alignment: {
+            if version >= &Version(Api::Gl, 4, 3) || version >= &Version(Api::GlEs, 3, 1) ||
+               extensions.gl_arb_shader_storage_buffer_object
+            {
+                let mut val = mem::uninitialized();
+                gl.GetIntegerv(gl::SHADER_STORAGE_BUFFER_OFFSET_ALIGNMENT, &mut val);
+                val
+            } else {
+                1
+            }
+        },
+
         max_compute_work_group_count: if version >= &Version(Api::Gl, 4, 3) ||
                                          version >= &Version(Api::GlEs, 3, 1) ||
                                          extensions.gl_arb_compute_shader
@@ -514,6 +641,34 @@ pub unsafe fn get_capabilities(gl: &gl::Gl, version: &Version, extensions: &Exte
             (0, 0, 0)
         },
 
+        max_compute_work_group_size: if version >= &Version(Api::Gl, 4, 3) ||
+                                        version >= &Version(Api::GlEs, 3, 1) ||
+                                        extensions.gl_arb_compute_shader
+        {
+            let mut val1 = mem::uninitialized();
+            let mut val2 = mem::uninitialized();
+            let mut val3 = mem::uninitialized();
+            gl.GetIntegeri_v(gl::MAX_COMPUTE_WORK_GROUP_SIZE, 0, &mut val1);
+            gl.GetIntegeri_v(gl::MAX_COMPUTE_WORK_GROUP_SIZE, 1, &mut val2);
+            gl.GetIntegeri_v(gl::MAX_COMPUTE_WORK_GROUP_SIZE, 2, &mut val3);
+            (val1, val2, val3)
+
+        } else {
+            (0, 0, 0)
+        },
+
+        max_compute_work_group_invocations: if version >= &Version(Api::Gl, 4, 3) ||
+                                               version >= &Version(Api::GlEs, 3, 1) ||
+                                               extensions.gl_arb_compute_shader
+        {
+            let mut val = mem::uninitialized();
+            gl.GetIntegerv(gl::MAX_COMPUTE_WORK_GROUP_INVOCATIONS, &mut val);
+            val
+
+        } else {
+            0
+        },
+
         max_color_attachments: {
             if version >= &Version(Api::Gl, 3, 0) || version >= &Version(Api::GlEs, 3, 0) ||
                extensions.gl_arb_framebuffer_object || extensions.gl_ext_framebuffer_object ||
@@ -582,6 +737,17 @@ pub unsafe fn get_capabilities(gl: &gl::Gl, version: &Version, extensions: &Exte
             }
         },
 
+        max_views: {
+            if extensions.gl_ovr_multiview {
+                let mut val = 0;
+                gl.GetIntegerv(gl::MAX_VIEWS_OVR, &mut val);
+                Some(val)
+
+            } else {
+                None
+            }
+        },
+
         renderer: renderer,
     }
 }
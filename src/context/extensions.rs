@@ -44,6 +44,21 @@ macro_rules! extensions {
 
             extensions
         }
+
+        impl ExtensionsList {
+            /// Returns true if the extension with the given name, for example
+            /// `"GL_ARB_compute_shader"`, is supported.
+            ///
+            /// Always returns false for extensions that glium doesn't know about.
+            pub fn is_supported(&self, name: &str) -> bool {
+                match name {
+                    $(
+                        $string => self.$field,
+                    )+
+                    _ => false
+                }
+            }
+        }
     }
 }
 
@@ -55,6 +70,7 @@ extensions! {
     "GL_APPLE_sync" => gl_apple_sync,
     "GL_APPLE_vertex_array_object" => gl_apple_vertex_array_object,
     "GL_ARB_bindless_texture" => gl_arb_bindless_texture,
+    "GL_ARB_blend_func_extended" => gl_arb_blend_func_extended,
     "GL_ARB_buffer_storage" => gl_arb_buffer_storage,
     "GL_ARB_compute_shader" => gl_arb_compute_shader,
     "GL_ARB_copy_buffer" => gl_arb_copy_buffer,
@@ -63,6 +79,7 @@ extensions! {
     "GL_ARB_depth_texture" => gl_arb_depth_texture,
     "GL_ARB_direct_state_access" => gl_arb_direct_state_access,
     "GL_ARB_draw_buffers" => gl_arb_draw_buffers,
+    "GL_ARB_draw_buffers_blend" => gl_arb_draw_buffers_blend,
     "GL_ARB_draw_elements_base_vertex" => gl_arb_draw_elements_base_vertex,
     "GL_ARB_compatibility" => gl_arb_compatibility,
     "GL_ARB_ES2_compatibility" => gl_arb_es2_compatibility,
@@ -75,13 +92,16 @@ extensions! {
     "GL_ARB_framebuffer_sRGB" => gl_arb_framebuffer_srgb,
     "GL_ARB_geometry_shader4" => gl_arb_geometry_shader4,
     "GL_ARB_get_program_binary" => gl_arb_get_programy_binary,
+    "GL_ARB_gl_spirv" => gl_arb_gl_spirv,
     "GL_ARB_gpu_shader_fp64" => gl_arb_gpu_shader_fp64,
     "GL_ARB_gpu_shader_int64" => gl_arb_gpu_shader_int64,
     "GL_ARB_instanced_arrays" => gl_arb_instanced_arrays,
     "GL_ARB_internalformat_query" => gl_arb_internalformat_query,
+    "GL_ARB_internalformat_query2" => gl_arb_internalformat_query2,
     "GL_ARB_invalidate_subdata" => gl_arb_invalidate_subdata,
     "GL_ARB_occlusion_query" => gl_arb_occlusion_query,
     "GL_ARB_occlusion_query2" => gl_arb_occlusion_query2,
+    "GL_ARB_parallel_shader_compile" => gl_arb_parallel_shader_compile,
     "GL_ARB_pixel_buffer_object" => gl_arb_pixel_buffer_object,
     "GL_ARB_program_interface_query" => gl_arb_program_interface_query,
     "GL_ARB_query_buffer_object" => gl_arb_query_buffer_object,
@@ -90,7 +110,10 @@ extensions! {
     "GL_ARB_provoking_vertex" => gl_arb_provoking_vertex,
     "GL_ARB_robustness" => gl_arb_robustness,
     "GL_ARB_robust_buffer_access_behavior" => gl_arb_robust_buffer_access_behavior,
+    "GL_ARB_sample_shading" => gl_arb_sample_shading,
     "GL_ARB_sampler_objects" => gl_arb_sampler_objects,
+    "GL_ARB_separate_shader_objects" => gl_arb_separate_shader_objects,
+    "GL_ARB_shader_atomic_counters" => gl_arb_shader_atomic_counters,
     "GL_ARB_shader_image_load_store" => gl_arb_shader_image_load_store,
     "GL_ARB_shader_objects" => gl_arb_shader_objects,
     "GL_ARB_shader_storage_buffer_object" => gl_arb_shader_storage_buffer_object,
@@ -110,6 +133,7 @@ extensions! {
     "GL_ARB_texture_stencil8" => gl_arb_texture_stencil8,
     "GL_ARB_texture_storage" => gl_arb_texture_storage,
     "GL_ARB_timer_query" => gl_arb_timer_query,
+    "GL_ARB_transform_feedback2" => gl_arb_transform_feedback2,
     "GL_ARB_transform_feedback3" => gl_arb_transform_feedback3,
     "GL_ARB_uniform_buffer_object" => gl_arb_uniform_buffer_object,
     "GL_ARB_vertex_array_object" => gl_arb_vertex_array_object,
@@ -118,14 +142,18 @@ extensions! {
     "GL_ARB_vertex_shader" => gl_arb_vertex_shader,
     "GL_ARB_vertex_type_10f_11f_11f_rev" => gl_arb_vertex_type_10f_11f_11f_rev,
     "GL_ARB_vertex_type_2_10_10_10_rev" => gl_arb_vertex_type_2_10_10_10_rev,
+    "GL_ARB_viewport_array" => gl_arb_viewport_array,
     "GL_ARM_rgba8" => gl_arm_rgba8,
     "GL_ATI_meminfo" => gl_ati_meminfo,
     "GL_ATI_draw_buffers" => gl_ati_draw_buffers,
     "GL_ATI_texture_float" => gl_ati_texture_float,
+    "GL_EXT_blend_func_extended" => gl_ext_blend_func_extended,
     "GL_EXT_blend_minmax" => gl_ext_blend_minmax,
     "GL_EXT_buffer_storage" => gl_ext_buffer_storage,
     "GL_EXT_debug_marker" => gl_ext_debug_marker,
+    "GL_EXT_depth_clamp" => gl_ext_depth_clamp,
     "GL_EXT_direct_state_access" => gl_ext_direct_state_access,
+    "GL_EXT_discard_framebuffer" => gl_ext_discard_framebuffer,
     "GL_EXT_disjoint_timer_query" => gl_ext_disjoint_timer_query,
     "GL_EXT_framebuffer_blit" => gl_ext_framebuffer_blit,
     "GL_EXT_framebuffer_object" => gl_ext_framebuffer_object,
@@ -156,9 +184,11 @@ extensions! {
     "GL_EXT_texture_snorm" => gl_ext_texture_snorm,
     "GL_EXT_texture_sRGB" => gl_ext_texture_srgb,
     "GL_EXT_transform_feedback" => gl_ext_transform_feedback,
+    "GL_EXT_unpack_subimage" => gl_ext_unpack_subimage,
     "GL_GREMEDY_string_marker" => gl_gremedy_string_marker,
     "GL_KHR_debug" => gl_khr_debug,
     "GL_KHR_context_flush_control" => gl_khr_context_flush_control,
+    "GL_KHR_parallel_shader_compile" => gl_khr_parallel_shader_compile,
     "GL_KHR_robustness" => gl_khr_robustness,
     "GL_KHR_robust_buffer_access_behavior" => gl_khr_robust_buffer_access_behavior,
     "GL_NV_fbo_color_attachments" => gl_nv_fbo_color_attachments,
@@ -177,12 +207,16 @@ extensions! {
     "GL_NVX_gpu_memory_info" => gl_nvx_gpu_memory_info,
     "GL_OES_depth_texture" => gl_oes_depth_texture,
     "GL_OES_draw_elements_base_vertex" => gl_oes_draw_elements_base_vertex,
+    "GL_OES_EGL_image" => gl_oes_egl_image,
+    "GL_OES_EGL_image_external" => gl_oes_egl_image_external,
     "GL_OES_element_index_uint" => gl_oes_element_index_uint,
+    "GL_OES_fbo_render_mipmap" => gl_oes_fbo_render_mipmap,
     "GL_OES_fixed_point" => gl_oes_fixed_point,
     "GL_OES_geometry_shader" => gl_oes_geometry_shader,
     "GL_OES_packed_depth_stencil" => gl_oes_packed_depth_stencil,
     "GL_OES_primitive_bounding_box" => gl_oes_primitive_bounding_box,
     "GL_OES_rgb8_rgba8" => gl_oes_rgb8_rgba8,
+    "GL_OES_sample_shading" => gl_oes_sample_shading,
     "GL_OES_stencil1" => gl_oes_stencil1,
     "GL_OES_stencil4" => gl_oes_stencil4,
     "GL_OES_tessellation_shader" => gl_oes_tessellation_shader,
@@ -194,6 +228,8 @@ extensions! {
     "GL_OES_vertex_array_object" => gl_oes_vertex_array_object,
     "GL_OES_vertex_half_float" => gl_oes_vertex_half_float,
     "GL_OES_vertex_type_10_10_10_2" => gl_oes_vertex_type_10_10_10_2,
+    "GL_OVR_multiview" => gl_ovr_multiview,
+    "GL_OVR_multiview2" => gl_ovr_multiview2,
 }
 
 /// Returns the list of all extension names supported by the OpenGL implementation.
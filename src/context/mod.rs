@@ -11,13 +11,14 @@ use std::borrow::Cow;
 use std::cell::{Cell, RefCell, RefMut};
 use std::marker::PhantomData;
 use std::ffi::CStr;
-use std::rc::Rc;
+use std::rc::{Rc, Weak};
 use std::os::raw;
-use std::hash::BuildHasherDefault;
+use std::hash::{BuildHasherDefault, Hash, Hasher};
 
 use fnv::FnvHasher;
 
 use GliumCreationError;
+use MemoryBarrier;
 use SwapBuffersError;
 use CapabilitiesSource;
 use ContextExt;
@@ -28,7 +29,9 @@ use version::Version;
 
 use debug;
 use fbo;
+use framebuffer;
 use ops;
+use program::{ComputeShader, DispatchError, Program, ShaderCache};
 use sampler_object;
 use texture;
 use uniforms;
@@ -96,6 +99,26 @@ pub struct Context {
     /// List of images handles that are resident. We need to call `MakeImageHandleResidentARB`
     /// when rebuilding the context.
     resident_image_handles: RefCell<Vec<(gl::types::GLuint64, gl::types::GLenum)>>,
+
+    /// Programs created with `Program::from_source_cached`, indexed by the hash of their source
+    /// code. `None` if the cache is disabled.
+    program_cache: RefCell<Option<HashMap<u64, CachedProgram, BuildHasherDefault<FnvHasher>>>>,
+
+    /// Storage for program binaries used by `Program::from_source`.
+    shader_cache: RefCell<Option<Rc<ShaderCache>>>,
+
+    /// Whether or not programs skip calls to `glUniform` when the value of a uniform didn't
+    /// change since the last draw.
+    uniform_cache_enabled: Cell<bool>,
+}
+
+/// Entry of the program cache.
+struct CachedProgram {
+    // the source code is compared on a hit, as two sources can have the same hash
+    vertex_shader: String,
+    fragment_shader: String,
+    geometry_shader: Option<String>,
+    program: Weak<Program>,
 }
 
 /// This struct is a guard that is returned when you want to access the OpenGL backend.
@@ -135,6 +158,9 @@ pub struct CommandContext<'a> {
     /// List of image handles and their access that need to be made resident.
     pub resident_image_handles: RefMut<'a, Vec<(gl::types::GLuint64, gl::types::GLenum)>>,
 
+    /// Whether or not the values of uniforms are compared with the last uploaded ones.
+    pub uniform_cache_enabled: bool,
+
     /// This marker is here to prevent `CommandContext` from implementing `Send`
     // TODO: use this when possible
     //impl<'a, 'b> !Send for CommandContext<'a, 'b> {}
@@ -209,6 +235,9 @@ impl Context {
             samplers: samplers,
             resident_texture_handles: resident_texture_handles,
             resident_image_handles: resident_image_handles,
+            program_cache: RefCell::new(None),
+            shader_cache: RefCell::new(None),
+            uniform_cache_enabled: Cell::new(true),
         });
 
         if context.debug_callback.is_some() {
@@ -236,6 +265,14 @@ impl Context {
         self.backend.borrow().get_framebuffer_dimensions()
     }
 
+    /// Calls `get_proc_address` on the backend object stored by this context.
+    ///
+    /// The context must be current.
+    #[inline]
+    pub unsafe fn get_proc_address(&self, symbol: &str) -> *const raw::c_void {
+        self.backend.borrow().get_proc_address(symbol)
+    }
+
     /// Changes the OpenGL context associated with this context.
     ///
     /// The new context **must** have lists shared with the old one.
@@ -434,6 +471,24 @@ impl Context {
         lost
     }
 
+    /// Returns the characteristics of the default framebuffer, as they were provided by the
+    /// windowing backend.
+    ///
+    /// These can be different from what was requested when building the window. For example
+    /// you may get more bits of depth than you asked for, or no multisampling at all.
+    #[inline]
+    pub fn get_default_framebuffer_properties(&self) -> framebuffer::DefaultFramebufferProperties {
+        let capabilities = self.capabilities();
+
+        framebuffer::DefaultFramebufferProperties {
+            samples: capabilities.samples,
+            depth_bits: capabilities.depth_bits,
+            stencil_bits: capabilities.stencil_bits,
+            srgb: capabilities.srgb,
+            stereo: capabilities.stereo,
+        }
+    }
+
     /// Returns the behavior when the current OpenGL context is changed.
     ///
     /// The most common value is `Flush`. In order to get `None` you must explicitely request it
@@ -476,6 +531,70 @@ impl Context {
         }
     }
 
+    /// Enables or disables the program cache.
+    ///
+    /// When the cache is enabled, `Program::from_source_cached` returns the existing program
+    /// if it is called multiple times with the same source code, instead of building a new one.
+    /// The cache doesn't keep the programs alive. Disabling the cache clears it.
+    ///
+    /// The cache is disabled by default.
+    pub fn set_program_cache_enabled(&self, enabled: bool) {
+        let mut cache = self.program_cache.borrow_mut();
+
+        if !enabled {
+            *cache = None;
+        } else if cache.is_none() {
+            *cache = Some(HashMap::with_hasher(Default::default()));
+        }
+    }
+
+    /// Returns true if the program cache is enabled.
+    #[inline]
+    pub fn is_program_cache_enabled(&self) -> bool {
+        self.program_cache.borrow().is_some()
+    }
+
+    /// Enables or disables the elimination of redundant uniform updates.
+    ///
+    /// Each program remembers the value of its uniforms, and by default `glUniform` is only
+    /// called when a value differs from the one that was uploaded by the previous draw. If you
+    /// disable this, the values of all the uniforms are uploaded before each draw call. This is
+    /// useful if you modify the uniforms of glium's programs with raw OpenGL calls.
+    ///
+    /// The cache is enabled by default.
+    #[inline]
+    pub fn set_uniform_cache_enabled(&self, enabled: bool) {
+        self.uniform_cache_enabled.set(enabled);
+    }
+
+    /// Returns true if redundant uniform updates are eliminated.
+    #[inline]
+    pub fn is_uniform_cache_enabled(&self) -> bool {
+        self.uniform_cache_enabled.get()
+    }
+
+    /// Sets the storage for program binaries used by `Program::from_source`, or removes it if
+    /// `None` is passed.
+    ///
+    /// If the backend supports program binaries, `Program::from_source` then loads programs
+    /// from the cache instead of compiling them whenever possible. The binaries are indexed by
+    /// a hash of the source code and of the vendor, renderer and version strings of the
+    /// backend, so that updating the driver invalidates them.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # let display: glium::Display = unsafe { std::mem::uninitialized() };
+    /// use std::rc::Rc;
+    /// use glium::program::DirectoryShaderCache;
+    ///
+    /// display.set_shader_cache(Some(Rc::new(DirectoryShaderCache::new("shader-cache"))));
+    /// ```
+    #[inline]
+    pub fn set_shader_cache(&self, cache: Option<Rc<ShaderCache>>) {
+        *self.shader_cache.borrow_mut() = cache;
+    }
+
     /// Returns an estimate of the amount of video memory available in bytes.
     ///
     /// Returns `None` if no estimate is available.
@@ -591,6 +710,79 @@ impl Context {
         unsafe { ctxt.gl.Flush(); }
     }
 
+    /// Calls `glMemoryBarrier()` with the given kinds of accesses.
+    ///
+    /// All the accesses of the given kinds that happen after this call will see the data
+    /// written by shaders before it. Glium takes this barrier into account and doesn't insert
+    /// the same barriers again for the resources written before this call.
+    ///
+    /// Returns `Err` if the backend doesn't support memory barriers (OpenGL 4.2, OpenGL ES 3.1
+    /// or `GL_ARB_shader_image_load_store` is required).
+    pub fn memory_barrier(&self, mut barrier: MemoryBarrier) -> Result<(), ()> {
+        let mut ctxt = self.make_current();
+
+        if !(ctxt.version >= &Version(Api::Gl, 4, 2)) &&
+           !(ctxt.version >= &Version(Api::GlEs, 3, 1)) &&
+           !ctxt.extensions.gl_arb_shader_image_load_store
+        {
+            return Err(());
+        }
+
+        // passing a bit that the backend doesn't know about generates an error, and these
+        // kinds of accesses can't happen anyway if they are not supported
+        if !(ctxt.version >= &Version(Api::Gl, 4, 3)) &&
+           !(ctxt.version >= &Version(Api::GlEs, 3, 1)) &&
+           !ctxt.extensions.gl_arb_shader_storage_buffer_object
+        {
+            barrier.shader_storage = false;
+        }
+
+        if !(ctxt.version >= &Version(Api::Gl, 4, 4)) &&
+           !ctxt.extensions.gl_arb_query_buffer_object &&
+           !ctxt.extensions.gl_amd_query_buffer_object
+        {
+            barrier.query_buffer = false;
+        }
+
+        if barrier.is_empty() {
+            return Ok(());
+        }
+
+        unsafe { ctxt.gl.MemoryBarrier(barrier.to_glbitfield()); }
+
+        let id = ctxt.state.next_draw_call_id;
+        let state = &mut *ctxt.state;
+        if barrier.vertex_attrib_array { state.latest_memory_barrier_vertex_attrib_array = id; }
+        if barrier.element_array { state.latest_memory_barrier_element_array = id; }
+        if barrier.uniform { state.latest_memory_barrier_uniform = id; }
+        if barrier.texture_fetch { state.latest_memory_barrier_texture_fetch = id; }
+        if barrier.shader_image_access { state.latest_memory_barrier_shader_image_access = id; }
+        if barrier.command { state.latest_memory_barrier_command = id; }
+        if barrier.pixel_buffer { state.latest_memory_barrier_pixel_buffer = id; }
+        if barrier.texture_update { state.latest_memory_barrier_texture_update = id; }
+        if barrier.buffer_update { state.latest_memory_barrier_buffer_update = id; }
+        if barrier.framebuffer { state.latest_memory_barrier_framebuffer = id; }
+        if barrier.transform_feedback { state.latest_memory_barrier_transform_feedback = id; }
+        if barrier.atomic_counter { state.latest_memory_barrier_atomic_counter = id; }
+        if barrier.shader_storage { state.latest_memory_barrier_shader_storage = id; }
+        if barrier.query_buffer { state.latest_memory_barrier_query_buffer = id; }
+
+        Ok(())
+    }
+
+    /// Executes a compute shader with `groups[0] * groups[1] * groups[2]` work groups.
+    ///
+    /// This is the same as calling `program.dispatch(uniforms, x, y, z)`. The shader storage
+    /// blocks, atomic counters and image units of the uniforms are bound, and the resources
+    /// written by the shader are tracked so that the memory barriers required before their next
+    /// use are inserted automatically.
+    #[inline]
+    pub fn dispatch<U>(&self, program: &ComputeShader, uniforms: U, groups: [u32; 3])
+                       -> Result<(), DispatchError> where U: uniforms::Uniforms
+    {
+        program.dispatch(uniforms, groups[0], groups[1], groups[2])
+    }
+
     /// Inserts a debugging string in the commands queue. If you use an OpenGL debugger, you will
     /// be able to see that string.
     ///
@@ -657,6 +849,7 @@ impl ContextExt for Context {
             samplers: self.samplers.borrow_mut(),
             resident_texture_handles: self.resident_texture_handles.borrow_mut(),
             resident_image_handles: self.resident_image_handles.borrow_mut(),
+            uniform_cache_enabled: self.uniform_cache_enabled.get(),
             marker: PhantomData,
         }
     }
@@ -665,6 +858,60 @@ impl ContextExt for Context {
     fn capabilities(&self) -> &Capabilities {
         &self.capabilities
     }
+
+    fn get_cached_program(&self, vertex_shader: &str, fragment_shader: &str,
+                          geometry_shader: Option<&str>) -> Option<Rc<Program>>
+    {
+        let cache = self.program_cache.borrow();
+        let cache = match *cache {
+            Some(ref cache) => cache,
+            None => return None,
+        };
+
+        let hash = program_cache_hash(vertex_shader, fragment_shader, geometry_shader);
+
+        match cache.get(&hash) {
+            Some(entry) if entry.vertex_shader == vertex_shader &&
+                           entry.fragment_shader == fragment_shader &&
+                           entry.geometry_shader.as_ref().map(|s| &s[..]) == geometry_shader =>
+            {
+                entry.program.upgrade()
+            },
+            _ => None,
+        }
+    }
+
+    #[inline]
+    fn get_shader_cache(&self) -> Option<Rc<ShaderCache>> {
+        self.shader_cache.borrow().clone()
+    }
+
+    fn cache_program(&self, vertex_shader: &str, fragment_shader: &str,
+                     geometry_shader: Option<&str>, program: &Rc<Program>)
+    {
+        if let Some(ref mut cache) = *self.program_cache.borrow_mut() {
+            // removing the programs that have been destroyed
+            cache.retain(|_, entry| entry.program.upgrade().is_some());
+
+            // in case of a collision, the previous entry is replaced
+            let hash = program_cache_hash(vertex_shader, fragment_shader, geometry_shader);
+            cache.insert(hash, CachedProgram {
+                vertex_shader: vertex_shader.to_owned(),
+                fragment_shader: fragment_shader.to_owned(),
+                geometry_shader: geometry_shader.map(|s| s.to_owned()),
+                program: Rc::downgrade(program),
+            });
+        }
+    }
+}
+
+/// Returns the key of a program in the program cache.
+fn program_cache_hash(vertex_shader: &str, fragment_shader: &str, geometry_shader: Option<&str>)
+                      -> u64
+{
+    let mut hasher = FnvHasher::default();
+    (vertex_shader, fragment_shader, geometry_shader).hash(&mut hasher);
+    hasher.finish()
 }
 
 impl CapabilitiesSource for Context {
@@ -708,6 +955,7 @@ impl Drop for Context {
                 samplers: self.samplers.borrow_mut(),
                 resident_texture_handles: self.resident_texture_handles.borrow_mut(),
                 resident_image_handles: self.resident_image_handles.borrow_mut(),
+                uniform_cache_enabled: self.uniform_cache_enabled.get(),
                 marker: PhantomData,
             };
 
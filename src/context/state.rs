@@ -39,12 +39,21 @@ pub struct GlState {
     /// Whether GL_FRAMEBUFFER_SRGB is enabled
     pub enabled_framebuffer_srgb: bool,
 
+    /// Whether GL_COLOR_LOGIC_OP is enabled
+    pub enabled_color_logic_op: bool,
+
     /// Whether GL_MULTISAMPLE is enabled
     pub enabled_multisample: bool,
 
     /// Whether GL_POLYGON_OFFSET_FILL is enabled
     pub enabled_polygon_offset_fill: bool,
 
+    /// Whether GL_POLYGON_OFFSET_LINE is enabled
+    pub enabled_polygon_offset_line: bool,
+
+    /// Whether GL_POLYGON_OFFSET_POINT is enabled
+    pub enabled_polygon_offset_point: bool,
+
     /// Whether GL_RASTERIZER_DISCARD is enabled
     pub enabled_rasterizer_discard: bool,
 
@@ -54,6 +63,9 @@ pub struct GlState {
     /// Whether GL_SAMPLE_COVERAGE is enabled
     pub enabled_sample_coverage: bool,
 
+    /// Whether GL_SAMPLE_SHADING is enabled
+    pub enabled_sample_shading: bool,
+
     /// Whether GL_SCISSOR_TEST is enabled
     pub enabled_scissor_test: bool,
 
@@ -69,6 +81,9 @@ pub struct GlState {
     /// Whether GL_PROGRAM_POINT_SIZE is enabled
     pub enabled_program_point_size: bool,
 
+    /// Whether GL_POINT_SPRITE is enabled
+    pub enabled_point_sprite: bool,
+
     /// The latest value passed to `glUseProgram`.
     pub program: Handle,
 
@@ -89,6 +104,15 @@ pub struct GlState {
     pub color_mask: (gl::types::GLboolean, gl::types::GLboolean,
                      gl::types::GLboolean, gl::types::GLboolean),
 
+    /// True if the color mask has been set separately for each draw buffer with `glColorMaski`.
+    /// In this situation `color_mask` doesn't reflect the state of each draw buffer.
+    pub color_mask_indexed: bool,
+
+    /// The latest values passed to `glColorMaski` for the indices `0 .. len`. Only valid if
+    /// `color_mask_indexed` is true.
+    pub color_mask_indexed_state: SmallVec<[(gl::types::GLboolean, gl::types::GLboolean,
+                                             gl::types::GLboolean, gl::types::GLboolean); 8]>,
+
     /// The latest buffer bound to `GL_ARRAY_BUFFER`.
     pub array_buffer_binding: gl::types::GLuint,
 
@@ -157,6 +181,18 @@ pub struct GlState {
     pub blend_func: (gl::types::GLenum, gl::types::GLenum,
                      gl::types::GLenum, gl::types::GLenum),
 
+    /// True if blending has been configured separately for each draw buffer with the indexed
+    /// functions. In this situation `enabled_blend`, `blend_equation` and `blend_func` don't
+    /// reflect the state of each draw buffer.
+    pub blend_indexed: bool,
+
+    /// The latest blending state of each draw buffer, for the indices `0 .. len`. Only valid if
+    /// `blend_indexed` is true. Contains whether blending is enabled for the draw buffer, and
+    /// its blending equations and factors.
+    pub blend_indexed_state: SmallVec<[(bool, (gl::types::GLenum, gl::types::GLenum),
+                                        (gl::types::GLenum, gl::types::GLenum,
+                                         gl::types::GLenum, gl::types::GLenum)); 8]>,
+
     /// The latest value passed to `glBlendColor`.
     pub blend_color: (gl::types::GLclampf, gl::types::GLclampf,
                       gl::types::GLclampf, gl::types::GLclampf),
@@ -170,6 +206,10 @@ pub struct GlState {
     /// The latest values passed to `glDepthRange`.
     pub depth_range: (f32, f32),
 
+    /// The latest values passed to `glDepthRangeIndexed` for the indices `0 .. len`, since the
+    /// last call to `glDepthRange`. The ranges with a higher index are equal to `depth_range`.
+    pub depth_range_indexed: SmallVec<[(f32, f32); 16]>,
+
     /// The latest values passed to `glStencilFuncSeparate` with face `GL_FRONT`.
     pub stencil_func_front: (gl::types::GLenum, gl::types::GLint, gl::types::GLuint),
 
@@ -192,16 +232,29 @@ pub struct GlState {
     pub viewport: Option<(gl::types::GLint, gl::types::GLint,
                           gl::types::GLsizei, gl::types::GLsizei)>,
 
+    /// The latest values passed to `glViewportIndexedf` for the indices `0 .. len`, since the
+    /// last call to `glViewport`. The viewports with a higher index are equal to `viewport`.
+    pub viewport_indexed: SmallVec<[(gl::types::GLint, gl::types::GLint,
+                                     gl::types::GLsizei, gl::types::GLsizei); 16]>,
+
     /// The latest values passed to `glScissor`. `None` means unknown.
     pub scissor: Option<(gl::types::GLint, gl::types::GLint,
                          gl::types::GLsizei, gl::types::GLsizei)>,
 
+    /// The latest values passed to `glScissorIndexed` for the indices `0 .. len`, since the
+    /// last call to `glScissor`. The scissor boxes with a higher index are equal to `scissor`.
+    pub scissor_indexed: SmallVec<[(gl::types::GLint, gl::types::GLint,
+                                    gl::types::GLsizei, gl::types::GLsizei); 16]>,
+
     /// The latest value passed to `glLineWidth`.
     pub line_width: gl::types::GLfloat,
 
     /// The latest value passed to `glPointSize`.
     pub point_size: gl::types::GLfloat,
 
+    /// The latest value passed to `glPointParameteri` with `GL_POINT_SPRITE_COORD_ORIGIN`.
+    pub point_sprite_coord_origin: gl::types::GLenum,
+
     /// The latest value passed to `glCullFace`.
     pub cull_face: gl::types::GLenum,
 
@@ -214,15 +267,39 @@ pub struct GlState {
     /// The latest value passed to `glProvokingVertex`.
     pub provoking_vertex: gl::types::GLenum,
 
+    /// The latest value passed to `glLogicOp`.
+    pub logic_op: gl::types::GLenum,
+
+    /// The latest values passed to `glPolygonOffset`.
+    pub polygon_offset: (gl::types::GLfloat, gl::types::GLfloat),
+
+    /// The latest values passed to `glSampleCoverage`.
+    pub sample_coverage: (gl::types::GLclampf, gl::types::GLboolean),
+
+    /// The latest value passed to `glMinSampleShading`.
+    pub min_sample_shading: gl::types::GLfloat,
+
     /// The latest value passed to `glPixelStore` with `GL_UNPACK_ALIGNMENT`.
     pub pixel_store_unpack_alignment: gl::types::GLint,
 
     /// The latest value passed to `glPixelStore` with `GL_PACK_ALIGNMENT`.
     pub pixel_store_pack_alignment: gl::types::GLint,
 
-    /// The latest value passed to `glClampColor`.
+    /// The latest value passed to `glPixelStore` with `GL_UNPACK_ROW_LENGTH`.
+    pub pixel_store_unpack_row_length: gl::types::GLint,
+
+    /// The latest value passed to `glPixelStore` with `GL_UNPACK_SKIP_PIXELS`.
+    pub pixel_store_unpack_skip_pixels: gl::types::GLint,
+
+    /// The latest value passed to `glPixelStore` with `GL_UNPACK_SKIP_ROWS`.
+    pub pixel_store_unpack_skip_rows: gl::types::GLint,
+
+    /// The latest value passed to `glClampColor` with `GL_CLAMP_READ_COLOR`.
     pub clamp_color: gl::types::GLenum,
 
+    /// The latest value passed to `glClampColor` with `GL_CLAMP_FRAGMENT_COLOR`.
+    pub clamp_fragment_color: gl::types::GLenum,
+
     /// The latest value passed to `glPatchParameter` with `GL_PATCH_VERTICES`.
     pub patch_patch_vertices: gl::types::GLint,
 
@@ -249,9 +326,18 @@ pub struct GlState {
     /// Current query being used for GL_TRANSFORM_FEEDBACK_PRIMITIVES_WRITTEN​.
     pub transform_feedback_primitives_written_query: gl::types::GLuint,
 
+    /// Query used by the current transform feedback session to count the primitives it writes,
+    /// or 0 if none is active. Shares the `GL_TRANSFORM_FEEDBACK_PRIMITIVES_WRITTEN​` target with
+    /// `transform_feedback_primitives_written_query`, so only one of them can be non-zero.
+    pub transform_feedback_session_query: gl::types::GLuint,
+
     /// Current query being used for GL_TIME_ELAPSED​.
     pub time_elapsed_query: gl::types::GLuint,
 
+    /// Number of times `GL_GPU_DISJOINT_EXT` has been found to be true. Reading this value
+    /// resets it, so it must only be read through this counter.
+    pub gpu_disjoint_events: usize,
+
     /// Latest value passed to `glBeginConditionalRender​`.
     pub conditional_render: Option<(gl::types::GLuint, gl::types::GLenum)>,
 
@@ -263,6 +349,9 @@ pub struct GlState {
     // TODO: move this inside transform feedback objects
     pub transform_feedback_paused: bool,
 
+    /// The latest transform feedback object bound to `GL_TRANSFORM_FEEDBACK`.
+    pub transform_feedback_object: gl::types::GLuint,
+
     /// The latest value passed to `glPrimitiveBoundingBox`.
     pub primitive_bounding_box: (f32, f32, f32, f32, f32, f32, f32, f32),
 
@@ -371,16 +460,21 @@ impl Default for GlState {
             enabled_depth_clamp_far: false,
             enabled_dither: false,
             enabled_framebuffer_srgb: false,
+            enabled_color_logic_op: false,
             enabled_multisample: true,
             enabled_polygon_offset_fill: false,
+            enabled_polygon_offset_line: false,
+            enabled_polygon_offset_point: false,
             enabled_rasterizer_discard: false,
             enabled_sample_alpha_to_coverage: false,
             enabled_sample_coverage: false,
+            enabled_sample_shading: false,
             enabled_scissor_test: false,
             enabled_stencil_test: false,
             enabled_line_smooth: false,
             enabled_polygon_smooth: false,
             enabled_program_point_size: false,
+            enabled_point_sprite: false,
 
             program: Handle::Id(0),
             vertex_array: 0,
@@ -388,6 +482,8 @@ impl Default for GlState {
             clear_depth: 1.0,
             clear_stencil: 0,
             color_mask: (1, 1, 1, 1),
+            color_mask_indexed: false,
+            color_mask_indexed_state: SmallVec::new(),
             array_buffer_binding: 0,
             pixel_pack_buffer_binding: 0,
             pixel_unpack_buffer_binding: 0,
@@ -411,6 +507,7 @@ impl Default for GlState {
             depth_func: gl::LESS,
             depth_mask: true,
             depth_range: (0.0, 1.0),
+            depth_range_indexed: SmallVec::new(),
             stencil_func_front: (gl::ALWAYS, 0, 0xffffffff),
             stencil_func_back: (gl::ALWAYS, 0, 0xffffffff),
             stencil_mask_front: 0xffffffff,
@@ -419,18 +516,31 @@ impl Default for GlState {
             stencil_op_back: (gl::KEEP, gl::KEEP, gl::KEEP),
             blend_equation: (gl::FUNC_ADD, gl::FUNC_ADD),
             blend_func: (gl::ONE, gl::ZERO, gl::ONE, gl::ZERO),
+            blend_indexed: false,
+            blend_indexed_state: SmallVec::new(),
             blend_color: (0.0, 0.0, 0.0, 0.0),
             viewport: None,
+            viewport_indexed: SmallVec::new(),
             scissor: None,
+            scissor_indexed: SmallVec::new(),
             line_width: 1.0,
             point_size: 1.0,
+            point_sprite_coord_origin: gl::UPPER_LEFT,
             cull_face: gl::BACK,
             polygon_mode: gl::FILL,
             smooth: (gl::DONT_CARE, gl::DONT_CARE),
             provoking_vertex: gl::LAST_VERTEX_CONVENTION,
+            logic_op: gl::COPY,
+            polygon_offset: (0.0, 0.0),
+            sample_coverage: (1.0, gl::FALSE),
+            min_sample_shading: 0.0,
             pixel_store_unpack_alignment: 4,
             pixel_store_pack_alignment: 4,
+            pixel_store_unpack_row_length: 0,
+            pixel_store_unpack_skip_pixels: 0,
+            pixel_store_unpack_skip_rows: 0,
             clamp_color: gl::FIXED_ONLY,
+            clamp_fragment_color: gl::FIXED_ONLY,
             patch_patch_vertices: 3,
             active_texture: 0,
             texture_units: small_vec_one(),
@@ -439,10 +549,13 @@ impl Default for GlState {
             any_samples_passed_conservative_query: 0,
             primitives_generated_query: 0,
             transform_feedback_primitives_written_query: 0,
+            transform_feedback_session_query: 0,
             time_elapsed_query: 0,
+            gpu_disjoint_events: 0,
             conditional_render: None,
             transform_feedback_enabled: None,
             transform_feedback_paused: false,
+            transform_feedback_object: 0,
             primitive_bounding_box: (-1.0, -1.0, -1.0, -1.0, 1.0, 1.0, 1.0, 1.0),
 
             next_draw_call_id: 1,
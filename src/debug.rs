@@ -126,7 +126,7 @@ impl TimestampQuery {
     pub fn new<F: ?Sized>(facade: &F) -> Option<TimestampQuery> where F: Facade {
         let ctxt = facade.get_context().make_current();
 
-        let id = if ctxt.version >= &Version(Api::Gl, 3, 2) {    // TODO: extension
+        let id = if ctxt.version >= &Version(Api::Gl, 3, 3) || ctxt.extensions.gl_arb_timer_query {
             unsafe {
                 let mut id = mem::uninitialized();
                 ctxt.gl.GenQueries(1, &mut id);
@@ -165,7 +165,7 @@ impl TimestampQuery {
 
         let ctxt = self.context.make_current();
 
-        if ctxt.version >= &Version(Api::Gl, 3, 2) {    // TODO: extension
+        if ctxt.version >= &Version(Api::Gl, 3, 3) || ctxt.extensions.gl_arb_timer_query {
             unsafe {
                 let mut value = mem::uninitialized();
                 ctxt.gl.GetQueryObjectiv(self.id, gl::QUERY_RESULT_AVAILABLE, &mut value);
@@ -192,11 +192,10 @@ impl TimestampQuery {
 
         let ctxt = self.context.make_current();
 
-        if ctxt.version >= &Version(Api::Gl, 3, 2) {    // TODO: extension
+        if ctxt.version >= &Version(Api::Gl, 3, 3) || ctxt.extensions.gl_arb_timer_query {
             unsafe {
                 let mut value = mem::uninitialized();
                 ctxt.gl.GetQueryObjectui64v(self.id, gl::QUERY_RESULT, &mut value);
-                ctxt.gl.DeleteQueries(1, [self.id].as_ptr());
                 value
             }
 
@@ -204,7 +203,6 @@ impl TimestampQuery {
             unsafe {
                 let mut value = mem::uninitialized();
                 ctxt.gl.GetQueryObjectui64vEXT(self.id, gl::QUERY_RESULT_EXT, &mut value);
-                ctxt.gl.DeleteQueriesEXT(1, [self.id].as_ptr());
                 value
             }
 
@@ -213,3 +211,40 @@ impl TimestampQuery {
         }
     }
 }
+
+impl Drop for TimestampQuery {
+    fn drop(&mut self) {
+        let ctxt = self.context.make_current();
+
+        unsafe {
+            if ctxt.version >= &Version(Api::Gl, 3, 3) || ctxt.extensions.gl_arb_timer_query {
+                ctxt.gl.DeleteQueries(1, [self.id].as_ptr());
+            } else if ctxt.extensions.gl_ext_disjoint_timer_query {
+                ctxt.gl.DeleteQueriesEXT(1, [self.id].as_ptr());
+            } else {
+                unreachable!();
+            }
+        }
+    }
+}
+
+/// Returns the current value of the GPU's clock in nanoseconds, without waiting for the commands
+/// queue to be executed. Returns `None` if the backend doesn't support it.
+///
+/// Contrary to `TimestampQuery`, this value corresponds to the moment when the function is
+/// called, which makes it possible to correlate the GPU's timeline with the CPU's clock.
+pub fn get_timestamp<F: ?Sized>(facade: &F) -> Option<u64> where F: Facade {
+    let ctxt = facade.get_context().make_current();
+
+    if !(ctxt.version >= &Version(Api::Gl, 3, 3)) && !ctxt.extensions.gl_arb_timer_query &&
+       !(ctxt.version >= &Version(Api::GlEs, 3, 0) && ctxt.extensions.gl_ext_disjoint_timer_query)
+    {
+        return None;
+    }
+
+    unsafe {
+        let mut value = mem::uninitialized();
+        ctxt.gl.GetInteger64v(gl::TIMESTAMP, &mut value);
+        Some(value as u64)
+    }
+}
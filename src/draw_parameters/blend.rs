@@ -4,6 +4,7 @@ use version::Version;
 
 use DrawError;
 use gl;
+use program;
 
 /// Blend effect that the GPU will use for blending.
 ///
@@ -38,6 +39,19 @@ impl Blend {
             constant_value: (0.0, 0.0, 0.0, 0.0)
         }
     }
+
+    /// Returns true if one of the blending factors reads the second output of the fragment
+    /// shader, in other words if dual-source blending is required.
+    pub fn uses_dual_source(&self) -> bool {
+        [self.color, self.alpha].iter().any(|function| match *function {
+            BlendingFunction::Addition { source, destination } |
+            BlendingFunction::Subtraction { source, destination } |
+            BlendingFunction::ReverseSubtraction { source, destination } => {
+                source.is_dual_source() || destination.is_dual_source()
+            },
+            _ => false,
+        })
+    }
 }
 
 impl Default for Blend {
@@ -165,22 +179,57 @@ pub enum LinearBlendingFactor {
     OneMinusDestinationAlpha,
 
     /// Multiply the source or destination component by the corresponding value
-    /// in `Blend::const_value`.
+    /// in `Blend::constant_value`.
     ConstantColor,
 
     /// Multiply the source or destination compoent by `1.0` minus the corresponding
-    /// value in `Blend::const_value`.
+    /// value in `Blend::constant_value`.
     OneMinusConstantColor,
 
-    /// Multiply the source or destination component by the alpha value of `Blend::const_value`.
+    /// Multiply the source or destination component by the alpha value of `Blend::constant_value`.
     ConstantAlpha,
 
     /// Multiply the source or destination componet by `1.0` minus the alpha value of
-    /// `Blend::const_value`.
+    /// `Blend::constant_value`.
     OneMinusConstantAlpha,
+
+    /// Multiply the source or destination component by its corresponding value in the second
+    /// output of the fragment shader.
+    ///
+    /// The second output is the one bound to the index `1` with
+    /// `ProgramBindings::bind_fragment_output`. Requires dual-source
+    /// blending support (see `program::is_dual_source_blending_supported`), and drawing
+    /// returns `DrawError::DualSourceOutputMissing` if the program has no such output.
+    ///
+    /// The framebuffer can't have more color attachments than
+    /// `Capabilities::max_dual_source_draw_buffers`, which is usually `1`, otherwise drawing
+    /// returns `DrawError::TooManyDualSourceDrawBuffers`.
+    SourceOneColor,
+
+    /// Equivalent to `1 - SourceOneColor`.
+    OneMinusSourceOneColor,
+
+    /// Multiply the source or destination component by the alpha value of the second output
+    /// of the fragment shader.
+    SourceOneAlpha,
+
+    /// Equivalent to `1 - SourceOneAlpha`.
+    OneMinusSourceOneAlpha,
 }
 
 impl LinearBlendingFactor {
+    /// Returns true if the factor reads the second output of the fragment shader.
+    #[inline]
+    fn is_dual_source(&self) -> bool {
+        match *self {
+            LinearBlendingFactor::SourceOneColor |
+            LinearBlendingFactor::OneMinusSourceOneColor |
+            LinearBlendingFactor::SourceOneAlpha |
+            LinearBlendingFactor::OneMinusSourceOneAlpha => true,
+            _ => false,
+        }
+    }
+
     fn to_glenum(&self) -> gl::types::GLenum {
         match *self {
             LinearBlendingFactor::Zero => gl::ZERO,
@@ -198,11 +247,221 @@ impl LinearBlendingFactor {
             LinearBlendingFactor::OneMinusConstantColor => gl::ONE_MINUS_CONSTANT_COLOR,
             LinearBlendingFactor::ConstantAlpha => gl::CONSTANT_ALPHA,
             LinearBlendingFactor::OneMinusConstantAlpha => gl::ONE_MINUS_CONSTANT_ALPHA,
+            LinearBlendingFactor::SourceOneColor => gl::SRC1_COLOR,
+            LinearBlendingFactor::OneMinusSourceOneColor => gl::ONE_MINUS_SRC1_COLOR,
+            LinearBlendingFactor::SourceOneAlpha => gl::SRC1_ALPHA,
+            LinearBlendingFactor::OneMinusSourceOneAlpha => gl::ONE_MINUS_SRC1_ALPHA,
         }
     }
 }
 
-pub fn sync_blending(ctxt: &mut CommandContext, blend: Blend) -> Result<(), DrawError> {
+/// Synchronizes the blending state. `draw_buffers` is the number of color attachments of the
+/// framebuffer that is drawn upon.
+pub fn sync_blending(ctxt: &mut CommandContext, mut blend: Blend,
+                     per_attachment: Option<&[Blend]>, draw_buffers: usize)
+                     -> Result<(), DrawError>
+{
+    // dual-source blending can only be used with a limited number of draw buffers ; if the
+    // backend doesn't support it at all, the error is returned by `get_blend_state`
+    let max_dual_source = ctxt.capabilities.max_dual_source_draw_buffers as usize;
+    if max_dual_source != 0 && draw_buffers > max_dual_source {
+        let uses_dual_source = blend.uses_dual_source() || per_attachment.map_or(false, |blends| {
+            blends.iter().any(|blend| blend.uses_dual_source())
+        });
+
+        if uses_dual_source {
+            return Err(DrawError::TooManyDualSourceDrawBuffers);
+        }
+    }
+
+    if let Some(per_attachment) = per_attachment {
+        if is_indexed_blending_supported(ctxt) {
+            return sync_blending_indexed(ctxt, per_attachment);
+        }
+
+        // without the indexed functions, we can only continue if all the draw buffers use
+        // the same blending
+        blend = match try!(get_common_blend(ctxt, per_attachment, draw_buffers)) {
+            Some(blend) => blend,
+            None => return Err(DrawError::PerAttachmentBlendingNotSupported),
+        };
+    }
+
+    // if the blending state was previously set per draw buffer, the cached values are wrong
+    if ctxt.state.blend_indexed {
+        unsafe { ctxt.gl.Disable(gl::BLEND); }
+        ctxt.state.enabled_blend = false;
+        ctxt.state.blend_equation = (0, 0);
+        ctxt.state.blend_func = (0, 0, 0, 0);
+        ctxt.state.blend_indexed = false;
+    }
+
+    match try!(get_blend_state(ctxt, blend)) {
+        None => {
+            // Both color and alpha always replace. This equals no blending.
+            if ctxt.state.enabled_blend {
+                unsafe { ctxt.gl.Disable(gl::BLEND); }
+                ctxt.state.enabled_blend = false;
+            }
+        },
+
+        Some((equation, func, uses_constant)) => {
+            if !ctxt.state.enabled_blend {
+                unsafe { ctxt.gl.Enable(gl::BLEND); }
+                ctxt.state.enabled_blend = true;
+            }
+
+            if ctxt.state.blend_equation != equation {
+                unsafe { ctxt.gl.BlendEquationSeparate(equation.0, equation.1); }
+                ctxt.state.blend_equation = equation;
+            }
+
+            if uses_constant {
+                sync_blend_color(ctxt, blend.constant_value);
+            }
+
+            if ctxt.state.blend_func != func {
+                unsafe { ctxt.gl.BlendFuncSeparate(func.0, func.1, func.2, func.3); }
+                ctxt.state.blend_func = func;
+            }
+        },
+    }
+
+    Ok(())
+}
+
+/// Returns true if the blending state can be set for each draw buffer separately.
+#[inline]
+fn is_indexed_blending_supported(ctxt: &CommandContext) -> bool {
+    // the indexed functions are core since OpenGL 4.0 and OpenGL ES 3.2, and
+    // `GL_ARB_draw_buffers_blend` provides them on top of OpenGL 3.0 which has `glEnablei`
+    ctxt.version >= &Version(Api::Gl, 4, 0) || ctxt.version >= &Version(Api::GlEs, 3, 2) ||
+        (ctxt.version >= &Version(Api::Gl, 3, 0) && ctxt.extensions.gl_arb_draw_buffers_blend)
+}
+
+/// If the per-attachment blending results in the same state for all the draw buffers, returns
+/// a `Blend` that can be used for all of them with the non-indexed functions.
+fn get_common_blend(ctxt: &mut CommandContext, blends: &[Blend], draw_buffers: usize)
+                    -> Result<Option<Blend>, DrawError>
+{
+    let first = match blends.first() {
+        Some(first) => *first,
+        None => return Ok(Some(Default::default())),
+    };
+
+    if blends.iter().any(|b| *b != first) {
+        return Ok(None);
+    }
+
+    // the draw buffers that are not in the list have blending disabled
+    if blends.len() < draw_buffers &&
+       try!(get_blend_state(ctxt, first)).is_some()
+    {
+        return Ok(None);
+    }
+
+    Ok(Some(first))
+}
+
+/// Sets the blending state of each draw buffer separately.
+fn sync_blending_indexed(ctxt: &mut CommandContext, blends: &[Blend]) -> Result<(), DrawError> {
+    let max_draw_buffers = ctxt.capabilities.max_draw_buffers as usize;
+
+    if blends.len() > max_draw_buffers {
+        return Err(DrawError::PerAttachmentBlendingNotSupported);
+    }
+
+    // the non-indexed functions have set the same state for all the draw buffers
+    if !ctxt.state.blend_indexed {
+        let current = (ctxt.state.enabled_blend, ctxt.state.blend_equation,
+                       ctxt.state.blend_func);
+
+        ctxt.state.blend_indexed_state.clear();
+        for _ in 0 .. max_draw_buffers {
+            ctxt.state.blend_indexed_state.push(current);
+        }
+
+        ctxt.state.blend_indexed = true;
+    }
+
+    let use_core = ctxt.version >= &Version(Api::Gl, 4, 0) ||
+                   ctxt.version >= &Version(Api::GlEs, 3, 2);
+
+    // there is only one constant color, so the first one that is needed is used
+    let mut constant_value = None;
+
+    for index in 0 .. max_draw_buffers {
+        // the draw buffers that are not in the list have blending disabled
+        let state = match blends.get(index) {
+            Some(blend) => try!(get_blend_state(ctxt, *blend)).map(|(equation, func, constant)| {
+                if constant && constant_value.is_none() {
+                    constant_value = Some(blend.constant_value);
+                }
+                (equation, func)
+            }),
+            None => None,
+        };
+
+        let gl_index = index as gl::types::GLuint;
+        let (enabled, current_equation, current_func) = ctxt.state.blend_indexed_state[index];
+
+        match state {
+            None => {
+                if enabled {
+                    unsafe { ctxt.gl.Disablei(gl::BLEND, gl_index); }
+                    ctxt.state.blend_indexed_state[index].0 = false;
+                }
+            },
+
+            Some((equation, func)) => unsafe {
+                if !enabled {
+                    ctxt.gl.Enablei(gl::BLEND, gl_index);
+                }
+
+                if current_equation != equation {
+                    if use_core {
+                        ctxt.gl.BlendEquationSeparatei(gl_index, equation.0, equation.1);
+                    } else {
+                        ctxt.gl.BlendEquationSeparateiARB(gl_index, equation.0, equation.1);
+                    }
+                }
+
+                if current_func != func {
+                    if use_core {
+                        ctxt.gl.BlendFuncSeparatei(gl_index, func.0, func.1, func.2, func.3);
+                    } else {
+                        ctxt.gl.BlendFuncSeparateiARB(gl_index, func.0, func.1, func.2, func.3);
+                    }
+                }
+
+                ctxt.state.blend_indexed_state[index] = (true, equation, func);
+            },
+        }
+    }
+
+    if let Some(constant_value) = constant_value {
+        sync_blend_color(ctxt, constant_value);
+    }
+
+    Ok(())
+}
+
+#[inline]
+fn sync_blend_color(ctxt: &mut CommandContext, value: (f32, f32, f32, f32)) {
+    if ctxt.state.blend_color != value {
+        let (r, g, b, a) = value;
+        unsafe { ctxt.gl.BlendColor(r, g, b, a); }
+        ctxt.state.blend_color = value;
+    }
+}
+
+/// Returns the blending equations, the blending factors, and whether the constant color is
+/// used. Returns `None` if blending should be disabled.
+fn get_blend_state(ctxt: &mut CommandContext, blend: Blend)
+                   -> Result<Option<((gl::types::GLenum, gl::types::GLenum),
+                                     (gl::types::GLenum, gl::types::GLenum,
+                                      gl::types::GLenum, gl::types::GLenum), bool)>, DrawError>
+{
     #[inline(always)]
     fn blend_eq(ctxt: &mut CommandContext, blending_function: BlendingFunction)
                 -> Result<gl::types::GLenum, DrawError>
@@ -252,76 +511,41 @@ pub fn sync_blending(ctxt: &mut CommandContext, blend: Blend) -> Result<(), Draw
         }
     }
 
+    #[inline(always)]
+    fn is_constant(factor: LinearBlendingFactor) -> bool {
+        match factor {
+            LinearBlendingFactor::ConstantColor |
+            LinearBlendingFactor::OneMinusConstantColor |
+            LinearBlendingFactor::ConstantAlpha |
+            LinearBlendingFactor::OneMinusConstantAlpha => true,
+            _ => false,
+        }
+    }
+
     if let (BlendingFunction::AlwaysReplace, BlendingFunction::AlwaysReplace) =
            (blend.color, blend.alpha)
     {
-        // Both color and alpha always replace. This equals no blending.
-        if ctxt.state.enabled_blend {
-            unsafe { ctxt.gl.Disable(gl::BLEND); }
-            ctxt.state.enabled_blend = false;
-        }
+        return Ok(None);
+    }
 
-    } else {
-        if !ctxt.state.enabled_blend {
-            unsafe { ctxt.gl.Enable(gl::BLEND); }
-            ctxt.state.enabled_blend = true;
-        }
+    let equation = (try!(blend_eq(ctxt, blend.color)), try!(blend_eq(ctxt, blend.alpha)));
 
-        let (color_eq, alpha_eq) = (try!(blend_eq(ctxt, blend.color)),
-                                    try!(blend_eq(ctxt, blend.alpha)));
-        if ctxt.state.blend_equation != (color_eq, alpha_eq) {
-            unsafe { ctxt.gl.BlendEquationSeparate(color_eq, alpha_eq); }
-            ctxt.state.blend_equation = (color_eq, alpha_eq);
-        }
+    // Map to dummy factors if the blending equation does not use the factors.
+    let (color_factor_src, color_factor_dst) = blending_factors(blend.color)
+        .unwrap_or((LinearBlendingFactor::One, LinearBlendingFactor::Zero));
+    let (alpha_factor_src, alpha_factor_dst) = blending_factors(blend.alpha)
+        .unwrap_or((LinearBlendingFactor::One, LinearBlendingFactor::Zero));
 
-        // Map to dummy factors if the blending equation does not use the factors.
-        let (color_factor_src, color_factor_dst) = blending_factors(blend.color)
-            .unwrap_or((LinearBlendingFactor::One, LinearBlendingFactor::Zero));
-        let (alpha_factor_src, alpha_factor_dst) = blending_factors(blend.alpha)
-            .unwrap_or((LinearBlendingFactor::One, LinearBlendingFactor::Zero));
-
-        // Updating the blending color if necessary.
-        if color_factor_src == LinearBlendingFactor::ConstantColor ||
-           color_factor_src == LinearBlendingFactor::OneMinusConstantColor ||
-           color_factor_dst == LinearBlendingFactor::ConstantColor ||
-           color_factor_dst == LinearBlendingFactor::OneMinusConstantColor ||
-           alpha_factor_src == LinearBlendingFactor::ConstantColor ||
-           alpha_factor_src == LinearBlendingFactor::OneMinusConstantColor ||
-           alpha_factor_dst == LinearBlendingFactor::ConstantColor ||
-           alpha_factor_dst == LinearBlendingFactor::OneMinusConstantColor ||
-           color_factor_src == LinearBlendingFactor::ConstantAlpha ||
-           color_factor_src == LinearBlendingFactor::OneMinusConstantAlpha ||
-           color_factor_dst == LinearBlendingFactor::ConstantAlpha ||
-           color_factor_dst == LinearBlendingFactor::OneMinusConstantAlpha ||
-           alpha_factor_src == LinearBlendingFactor::ConstantAlpha ||
-           alpha_factor_src == LinearBlendingFactor::OneMinusConstantAlpha ||
-           alpha_factor_dst == LinearBlendingFactor::ConstantAlpha ||
-           alpha_factor_dst == LinearBlendingFactor::OneMinusConstantAlpha
-        {
-            if ctxt.state.blend_color != blend.constant_value {
-                let (r, g, b, a) = blend.constant_value;
-                unsafe { ctxt.gl.BlendColor(r, g, b, a); }
-                ctxt.state.blend_color = blend.constant_value;
-            }
-        }
+    let uses_constant = is_constant(color_factor_src) || is_constant(color_factor_dst) ||
+                        is_constant(alpha_factor_src) || is_constant(alpha_factor_dst);
 
-        // Updating the blending function if necessary.
-        let color_factor_src = color_factor_src.to_glenum();
-        let color_factor_dst = color_factor_dst.to_glenum();
-        let alpha_factor_src = alpha_factor_src.to_glenum();
-        let alpha_factor_dst = alpha_factor_dst.to_glenum();
-        if ctxt.state.blend_func != (color_factor_src, color_factor_dst,
-                                     alpha_factor_src, alpha_factor_dst)
-        {
-            unsafe {
-                ctxt.gl.BlendFuncSeparate(color_factor_src, color_factor_dst,
-                                          alpha_factor_src, alpha_factor_dst);
-            }
-
-            ctxt.state.blend_func = (color_factor_src, color_factor_dst,
-                                     alpha_factor_src, alpha_factor_dst);
-        }
+    if blend.uses_dual_source() && !program::is_dual_source_blending_supported(ctxt)
+    {
+        return Err(DrawError::BlendingParameterNotSupported);
     }
 
-    Ok(())
+    let func = (color_factor_src.to_glenum(), color_factor_dst.to_glenum(),
+                alpha_factor_src.to_glenum(), alpha_factor_dst.to_glenum());
+
+    Ok(Some((equation, func, uses_constant)))
 }
@@ -32,11 +32,14 @@ pub struct Depth {
     /// specify here.
     ///
     /// The two values must be between `0.0` and `1.0`, anything outside this range will result
-    /// in a panic. By default the depth range is `(0.0, 1.0)`.
+    /// in an `InvalidDepthRange` error. By default the depth range is `(0.0, 1.0)`.
     ///
     /// The first value of the tuple must be the "near" value, where `-1.0` will be mapped.
     /// The second value must be the "far" value, where `1.0` will be mapped.
-    /// It is possible for the "near" value to be greater than the "far" value.
+    /// It is possible for the "near" value to be greater than the "far" value, for example
+    /// `(1.0, 0.0)` for a reversed depth buffer.
+    ///
+    /// This value is ignored if `DrawParameters::depth_ranges` is set.
     pub range: (f32, f32),
 
     /// Sets whether the depth values of samples should be clamped to `0.0` and `1.0`.
@@ -149,7 +152,9 @@ pub enum DepthClamp {
 
     /// Clamp the depth values. All samples will always be drawn.
     ///
-    /// This value is only supported on OpenGL.
+    /// This is useful for capping shadow volumes, or to avoid clipping geometry that crosses
+    /// the near plane. Requires OpenGL 3.0, `GL_ARB_depth_clamp` or `GL_NV_depth_clamp`, or
+    /// `GL_EXT_depth_clamp` on OpenGL ES.
     Clamp,
 
     /// Depth values inferior to `0.0` will be clamped to `0.0`.
@@ -163,9 +168,19 @@ pub enum DepthClamp {
     ClampFar,
 }
 
-pub fn sync_depth(ctxt: &mut CommandContext, depth: &Depth) -> Result<(), DrawError> {
+/// Returns true if `GL_DEPTH_CLAMP` is supported.
+#[inline]
+fn is_depth_clamp_supported(ctxt: &CommandContext) -> bool {
+    ctxt.version >= &Version(Api::Gl, 3, 0) || ctxt.extensions.gl_arb_depth_clamp ||
+        ctxt.extensions.gl_nv_depth_clamp || ctxt.extensions.gl_ext_depth_clamp
+}
+
+pub fn sync_depth(ctxt: &mut CommandContext, depth: &Depth, ranges: Option<&[(f32, f32)]>)
+                  -> Result<(), DrawError>
+{
     // depth clamp
     {
+        let clamp_supported = is_depth_clamp_supported(ctxt);
         let state = &mut *ctxt.state;
         match (depth.clamp, &mut state.enabled_depth_clamp_near,
                &mut state.enabled_depth_clamp_far)
@@ -174,9 +189,7 @@ pub fn sync_depth(ctxt: &mut CommandContext, depth: &Depth) -> Result<(), DrawEr
             (DepthClamp::Clamp, &mut true, &mut true) => (),
 
             (DepthClamp::NoClamp, near, far) => {
-                if ctxt.version >= &Version(Api::Gl, 3, 0) || ctxt.extensions.gl_arb_depth_clamp ||
-                   ctxt.extensions.gl_nv_depth_clamp
-                {
+                if clamp_supported {
                     unsafe { ctxt.gl.Disable(gl::DEPTH_CLAMP) };
                     *near = false;
                     *far = false;
@@ -186,9 +199,7 @@ pub fn sync_depth(ctxt: &mut CommandContext, depth: &Depth) -> Result<(), DrawEr
             },
 
             (DepthClamp::Clamp, near, far) => {
-                if ctxt.version >= &Version(Api::Gl, 3, 0) || ctxt.extensions.gl_arb_depth_clamp ||
-                   ctxt.extensions.gl_nv_depth_clamp
-                {
+                if clamp_supported {
                     unsafe { ctxt.gl.Enable(gl::DEPTH_CLAMP) };
                     *near = true;
                     *far = true;
@@ -244,18 +255,61 @@ pub fn sync_depth(ctxt: &mut CommandContext, depth: &Depth) -> Result<(), DrawEr
     }
 
     // depth range
-    if depth.range.0 < 0.0 || depth.range.0 > 1.0 ||
-       depth.range.1 < 0.0 || depth.range.1 > 1.0
-    {
-        return Err(DrawError::InvalidDepthRange);
-    }
+    if let Some(ranges) = ranges {
+        if !(ctxt.version >= &Version(Api::Gl, 4, 1)) && !ctxt.extensions.gl_arb_viewport_array {
+            return Err(DrawError::ViewportArrayNotSupported);
+        }
 
-    if depth.range != ctxt.state.depth_range {
-        // TODO: WebGL requires depth.range.1 > depth.range.0
-        unsafe {
-            ctxt.gl.DepthRange(depth.range.0 as f64, depth.range.1 as f64);
+        if ranges.len() > ctxt.capabilities.max_viewports as usize {
+            return Err(DrawError::TooManyViewports);
+        }
+
+        for (index, &range) in ranges.iter().enumerate() {
+            if range.0 < 0.0 || range.0 > 1.0 || range.1 < 0.0 || range.1 > 1.0 {
+                return Err(DrawError::InvalidDepthRange);
+            }
+
+            let current = ctxt.state.depth_range_indexed.get(index).cloned()
+                                                        .unwrap_or(ctxt.state.depth_range);
+            if current != range {
+                unsafe {
+                    ctxt.gl.DepthRangeIndexed(index as gl::types::GLuint, range.0 as f64,
+                                              range.1 as f64);
+                }
+
+                if index < ctxt.state.depth_range_indexed.len() {
+                    ctxt.state.depth_range_indexed[index] = range;
+                } else {
+                    // the skipped indices still contain the value of the last `glDepthRange`
+                    while ctxt.state.depth_range_indexed.len() < index {
+                        let previous = ctxt.state.depth_range;
+                        ctxt.state.depth_range_indexed.push(previous);
+                    }
+
+                    ctxt.state.depth_range_indexed.push(range);
+                }
+            }
+        }
+
+    } else {
+        if depth.range.0 < 0.0 || depth.range.0 > 1.0 ||
+           depth.range.1 < 0.0 || depth.range.1 > 1.0
+        {
+            return Err(DrawError::InvalidDepthRange);
+        }
+
+        if depth.range != ctxt.state.depth_range || !ctxt.state.depth_range_indexed.is_empty() {
+            // TODO: WebGL requires depth.range.1 > depth.range.0
+            unsafe {
+                if ctxt.version >= &Version(Api::GlEs, 2, 0) {
+                    ctxt.gl.DepthRangef(depth.range.0, depth.range.1);
+                } else {
+                    ctxt.gl.DepthRange(depth.range.0 as f64, depth.range.1 as f64);
+                }
+            }
+            ctxt.state.depth_range = depth.range;
+            ctxt.state.depth_range_indexed.clear();
         }
-        ctxt.state.depth_range = depth.range;
     }
 
     if depth.test == DepthTest::Overwrite && !depth.write {
@@ -53,6 +53,9 @@
 //! If you use conditional rendering, glium will submit the draw command but the GPU will execute
 //! it only if the query contains a value different from 0.
 //!
+//! To use the same condition for a whole group of draw commands, call `Surface::conditional`
+//! instead. The closure receives a surface whose draw commands all use the query as condition.
+//!
 //! ## WrongQueryOperation errors
 //!
 //! OpenGL puts some restrictions about the usage of queries. If you draw one or several times
@@ -66,6 +69,7 @@ use context;
 use context::Context;
 use version::Version;
 use version::Api;
+use context::Profile;
 
 use index::PrimitiveType;
 
@@ -80,7 +84,7 @@ use std::ops::Range;
 
 pub use self::blend::{Blend, BlendingFunction, LinearBlendingFactor};
 pub use self::depth::{Depth, DepthTest, DepthClamp};
-pub use self::query::{QueryCreationError};
+pub use self::query::{QueryCreationError, TimeElapsedError};
 pub use self::query::{SamplesPassedQuery, TimeElapsedQuery, PrimitivesGeneratedQuery};
 pub use self::query::{AnySamplesPassedQuery, TransformFeedbackPrimitivesWrittenQuery};
 pub use self::stencil::{StencilTest, StencilOperation, Stencil};
@@ -215,6 +219,60 @@ impl ToGlEnum for Smooth {
     }
 }
 
+/// Offset that is added to the depth value of the fragments of polygons, to avoid z-fighting
+/// when drawing decals or to bias the depth written in shadow maps.
+///
+/// The offset is `factor * dz + units * r`, where `dz` is the slope of the depth of the polygon
+/// and `r` is the smallest value that produces a difference in the depth buffer.
+///
+/// ## Example
+///
+/// ```
+/// let params = glium::DrawParameters {
+///     polygon_offset: glium::PolygonOffset {
+///         factor: -1.0,
+///         units: -1.0,
+///         fill: true,
+///         .. Default::default()
+///     },
+///     .. Default::default()
+/// };
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PolygonOffset {
+    /// Scale of the slope of the depth of the polygon.
+    pub factor: f32,
+
+    /// Multiple of the smallest difference of depth that can be represented.
+    pub units: f32,
+
+    /// Whether the offset applies to polygons drawn with `PolygonMode::Point`.
+    ///
+    /// Not supported by OpenGL ES, in which case drawing returns `PolygonOffsetNotSupported`.
+    pub point: bool,
+
+    /// Whether the offset applies to polygons drawn with `PolygonMode::Line`.
+    ///
+    /// Not supported by OpenGL ES, in which case drawing returns `PolygonOffsetNotSupported`.
+    pub line: bool,
+
+    /// Whether the offset applies to polygons drawn with `PolygonMode::Fill`.
+    pub fill: bool,
+}
+
+impl Default for PolygonOffset {
+    #[inline]
+    fn default() -> PolygonOffset {
+        PolygonOffset {
+            factor: 0.0,
+            units: 0.0,
+            point: false,
+            line: false,
+            fill: false,
+        }
+    }
+}
+
 /// The vertex to use for flat shading.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum ProvokingVertex {
@@ -223,10 +281,104 @@ pub enum ProvokingVertex {
 
     /// Use the first vertex of each primitive.
     ///
-    /// Note that for triangle fans, this is not the first vertex but the second vertex.
+    /// This is the convention of Direct3D and Vulkan, so content authored for these APIs
+    /// usually expects it. Note that for triangle fans, this is not the first vertex but the
+    /// second vertex.
     FirstVertex,
 }
 
+/// Position of the origin of `gl_PointCoord` when drawing points.
+///
+/// `gl_PointCoord` goes from `0.0` to `1.0` over the area of each point, and is usually used as
+/// texture coordinates when drawing particles.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PointSpriteOrigin {
+    /// `(0.0, 0.0)` is the upper-left hand corner of the point. This is the default.
+    UpperLeft,
+
+    /// `(0.0, 0.0)` is the lower-left hand corner of the point, just like the other coordinates
+    /// in OpenGL.
+    ///
+    /// Not supported by OpenGL ES. Drawing returns `PointSpriteOriginNotSupported` if it is
+    /// requested.
+    LowerLeft,
+}
+
+impl ToGlEnum for PointSpriteOrigin {
+    #[inline]
+    fn to_glenum(&self) -> gl::types::GLenum {
+        match *self {
+            PointSpriteOrigin::UpperLeft => gl::UPPER_LEFT,
+            PointSpriteOrigin::LowerLeft => gl::LOWER_LEFT,
+        }
+    }
+}
+
+/// Bitwise operation to apply between the color written by the fragment shader (`src`) and the
+/// color that is already in the framebuffer (`dst`).
+///
+/// Logic operations only apply to normalized and integral color attachments. Floating-point and
+/// sRGB attachments are not affected.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LogicOperation {
+    /// `0`
+    Clear,
+    /// `src & dst`
+    And,
+    /// `src & !dst`
+    AndReverse,
+    /// `src`
+    Copy,
+    /// `!src & dst`
+    AndInverted,
+    /// `dst`
+    Noop,
+    /// `src ^ dst`
+    Xor,
+    /// `src | dst`
+    Or,
+    /// `!(src | dst)`
+    Nor,
+    /// `!(src ^ dst)`
+    Equiv,
+    /// `!dst`
+    Invert,
+    /// `src | !dst`
+    OrReverse,
+    /// `!src`
+    CopyInverted,
+    /// `!src | dst`
+    OrInverted,
+    /// `!(src & dst)`
+    Nand,
+    /// All the bits set to `1`.
+    Set,
+}
+
+impl ToGlEnum for LogicOperation {
+    #[inline]
+    fn to_glenum(&self) -> gl::types::GLenum {
+        match *self {
+            LogicOperation::Clear => gl::CLEAR,
+            LogicOperation::And => gl::AND,
+            LogicOperation::AndReverse => gl::AND_REVERSE,
+            LogicOperation::Copy => gl::COPY,
+            LogicOperation::AndInverted => gl::AND_INVERTED,
+            LogicOperation::Noop => gl::NOOP,
+            LogicOperation::Xor => gl::XOR,
+            LogicOperation::Or => gl::OR,
+            LogicOperation::Nor => gl::NOR,
+            LogicOperation::Equiv => gl::EQUIV,
+            LogicOperation::Invert => gl::INVERT,
+            LogicOperation::OrReverse => gl::OR_REVERSE,
+            LogicOperation::CopyInverted => gl::COPY_INVERTED,
+            LogicOperation::OrInverted => gl::OR_INVERTED,
+            LogicOperation::Nand => gl::NAND,
+            LogicOperation::Set => gl::SET,
+        }
+    }
+}
+
 /// Represents the parameters to use when drawing.
 ///
 /// Example:
@@ -254,26 +406,89 @@ pub struct DrawParameters<'a> {
     /// being written.
     pub blend: Blend,
 
+    /// If set, overrides `blend` with a different blending for each color attachment of
+    /// the framebuffer. The first element corresponds to the first draw buffer, and so on.
+    /// Blending is disabled for the draw buffers that are not in the list.
+    ///
+    /// Since there is only one constant color, the `constant_value` of the first element that
+    /// uses it is used for all the attachments.
+    ///
+    /// Requires OpenGL 4.0, OpenGL ES 3.2, or `GL_ARB_draw_buffers_blend`, unless all the draw
+    /// buffers end up with the same blending. Drawing returns `PerAttachmentBlendingNotSupported`
+    /// otherwise. The default value is `None`.
+    pub blend_per_attachment: Option<&'a [Blend]>,
+
     /// Allows you to disable some color components.
     ///
-    /// This affects all attachments to the framebuffer. It's at the same level as the
-    /// blending function.
+    /// This affects all attachments to the framebuffer, unless `color_mask_per_attachment` is
+    /// set. It's at the same level as the blending function.
     ///
     /// The parameters are in order: red, green, blue, alpha. `true` means that the given
     /// component will be written, `false` means that it will be ignored. The default value
     /// is `(true, true, true, true)`.
     pub color_mask: (bool, bool, bool, bool),
 
+    /// If set, overrides `color_mask` with a different mask for each color attachment of
+    /// the framebuffer. The first element corresponds to the first draw buffer, and so on.
+    /// All the components of the draw buffers that are not in the list are written.
+    ///
+    /// Requires OpenGL 3.0 or OpenGL ES 3.2. Drawing returns
+    /// `PerAttachmentColorMaskNotSupported` otherwise. The default value is `None`.
+    pub color_mask_per_attachment: Option<&'a [(bool, bool, bool, bool)]>,
+
+    /// If set, the color written by the fragment shader is combined with the existing color
+    /// with a bitwise operation instead of being blended. `blend` and `blend_per_attachment`
+    /// are then ignored for the attachments that support logic operations.
+    ///
+    /// Logic operations are not supported by OpenGL ES. Drawing returns
+    /// `LogicOperationNotSupported` in this situation. The default value is `None`.
+    pub logic_operation: Option<LogicOperation>,
+
+    /// Whether the GPU should convert the colors written by the fragment shader from linear
+    /// RGB to sRGB before writing them to sRGB attachments (`GL_FRAMEBUFFER_SRGB`).
+    ///
+    /// `None` means that the value depends on the program: the conversion is enabled unless the
+    /// program was created with `outputs_srgb` set to true. `Some(true)` always enables the
+    /// conversion and `Some(false)` always disables it. The conversion only applies to sRGB
+    /// attachments, other attachments are never affected. The default value is `None`.
+    ///
+    /// Passing `Some` when the backend doesn't support toggling the conversion returns a
+    /// `FramebufferSrgbNotSupported` error.
+    pub framebuffer_srgb: Option<bool>,
+
+    /// Whether the colors written by the fragment shader are clamped to `[0.0, 1.0]`
+    /// (`glClampColor` with `GL_CLAMP_FRAGMENT_COLOR`).
+    ///
+    /// `None` means that only the colors written to normalized attachments are clamped, while
+    /// floating-point attachments receive the unclamped values. `Some(true)` clamps the colors
+    /// for all attachments and `Some(false)` never clamps them. The default value is `None`.
+    ///
+    /// Requires OpenGL 3.0 and the compatibility profile, otherwise passing `Some` returns a
+    /// `ColorClampingNotSupported` error.
+    pub color_clamping: Option<bool>,
+
     /// Width in pixels of the lines to draw when drawing lines.
     ///
+    /// The value is clamped to `Capabilities::line_width_range`. Forward-compatible contexts
+    /// only support a width of `1.0`.
+    ///
     /// `None` means "don't care". Use this when you don't draw lines.
     pub line_width: Option<f32>,
 
     /// Diameter in pixels of the points to draw when drawing points.
     ///
-    /// `None` means "don't care". Use this when you don't draw points.
+    /// This value is ignored if the program writes to `gl_PointSize`, which must be enabled when
+    /// creating the program (see `point_size` in the `program!` macro).
+    ///
+    /// `None` means "don't care". Use this when you don't draw points. OpenGL ES doesn't
+    /// support a fixed point size, and drawing returns `FixedPointSizeNotSupported` if a value
+    /// is passed.
     pub point_size: Option<f32>,
 
+    /// Position of the origin of `gl_PointCoord` when drawing points. The default value is
+    /// `UpperLeft`.
+    pub point_sprite_origin: PointSpriteOrigin,
+
     /// Whether or not the GPU should filter out some faces.
     ///
     /// After the vertex shader stage, the GPU will try to remove the faces that aren't facing
@@ -284,16 +499,52 @@ pub struct DrawParameters<'a> {
 
     /// How to render polygons. The default value is `Fill`.
     ///
-    /// See the documentation of `PolygonMode` for more infos.
+    /// OpenGL ES only supports `Fill`. Drawing returns `PolygonModeNotSupported` if another mode
+    /// is requested. See the documentation of `PolygonMode` for more infos.
     pub polygon_mode: PolygonMode,
 
+    /// Offset to add to the depth of polygons. The offset is disabled by default.
+    ///
+    /// See the documentation of `PolygonOffset` for more infos.
+    pub polygon_offset: PolygonOffset,
+
     /// Whether multisample antialiasing (MSAA) should be used. Default value is `true`.
     ///
     /// Note that you will need to set the appropriate option when creating the window.
     /// The recommended way to do is to leave this to `true`, and adjust the option when
     /// creating the window.
+    ///
+    /// OpenGL ES doesn't support disabling multisampling, and drawing returns a
+    /// `MultisamplingNotSupported` error if this is `false`.
     pub multisampling: bool,
 
+    /// If true, the alpha value written by the fragment shader is converted into a coverage
+    /// mask that determines which samples of the pixel are written. This allows rendering
+    /// transparent cutouts like foliage without the aliasing of a hard alpha test.
+    ///
+    /// Only has an effect when drawing on a multisampled framebuffer with `multisampling`
+    /// enabled. The default value is `false`.
+    pub alpha_to_coverage: bool,
+
+    /// If set, the coverage of each fragment is combined with a coverage mask of the given
+    /// proportion of samples. If the boolean is true, the mask is inverted.
+    ///
+    /// For example passing `Some((0.5, false))` and then `Some((0.5, true))` writes each half
+    /// of the samples of a pixel separately. Only has an effect when drawing on a multisampled
+    /// framebuffer with `multisampling` enabled. The default value is `None`.
+    pub sample_coverage: Option<(f32, bool)>,
+
+    /// If set, forces the fragment shader to be executed for at least this proportion of the
+    /// samples of each pixel, between `0.0` and `1.0`. `Some(1.0)` runs the fragment shader
+    /// once per sample, which improves the antialiasing of alpha-tested or high-frequency
+    /// shaders at the cost of performance.
+    ///
+    /// Only has an effect when drawing on a multisampled framebuffer with `multisampling`
+    /// enabled. Requires OpenGL 4.0, OpenGL ES 3.2, `GL_ARB_sample_shading` or
+    /// `GL_OES_sample_shading`. Drawing returns `SampleShadingNotSupported` otherwise.
+    /// The default value is `None`.
+    pub min_sample_shading: Option<f32>,
+
     /// Whether dithering is activated. Default value is `true`.
     ///
     /// Dithering will smoothen the transition between colors in your color buffer.
@@ -316,16 +567,40 @@ pub struct DrawParameters<'a> {
     /// not the scissor box.
     pub scissor: Option<Rect>,
 
+    /// If specified, overrides `viewport` and sets one viewport per index. Default is `None`.
+    ///
+    /// A geometry shader can then choose in which viewport a primitive is drawn by writing
+    /// to `gl_ViewportIndex`. This is useful for example to render both eyes in VR, or all the
+    /// cascades of a shadow map atlas, with a single draw command.
+    ///
+    /// Requires OpenGL 4.1 or `GL_ARB_viewport_array`, otherwise a `ViewportArrayNotSupported`
+    /// error is returned. The length must not exceed `Capabilities::max_viewports`.
+    pub viewports: Option<&'a [Rect]>,
+
+    /// If specified, overrides `scissor` and sets one scissor box per viewport index. Default
+    /// is `None`.
+    ///
+    /// Has the same requirements as `viewports`.
+    pub scissors: Option<&'a [Rect]>,
+
+    /// If specified, overrides `depth.range` and sets one depth range per viewport index.
+    /// Default is `None`.
+    ///
+    /// Has the same requirements as `viewports`, and the values must be between `0.0` and
+    /// `1.0`.
+    pub depth_ranges: Option<&'a [(f32, f32)]>,
+
     /// If `false`, the pipeline will stop after the primitives generation stage. The default
     /// value is `true`.
     ///
     /// If `false`, the fragment shader of your program won't be executed.
     ///
-    /// If `false`, drawing may return `TransformFeedbackNotSupported` if the backend doesn't
-    /// support this feature.
+    /// If `false`, drawing may return `RasterizerDiscardNotSupported` if the backend doesn't
+    /// support this feature (OpenGL 3.0, OpenGL ES 3.0 or `GL_EXT_transform_feedback`).
     ///
     /// This parameter may seem pointless, but it can be useful when you use transform
-    /// feedback or if you just use your shaders to write to a buffer.
+    /// feedback or if you just use your shaders to write to a buffer, as nothing is written
+    /// to the framebuffer.
     pub draw_primitives: bool,
 
     /// If set, each sample (ie. usually each pixel) written to the output adds one to the
@@ -345,6 +620,10 @@ pub struct DrawParameters<'a> {
 
     /// If set, the commands will only be executed if the specified query contains `true` or
     /// a number different than 0.
+    ///
+    /// The test is done by the GPU and doesn't require reading the query back. Requires
+    /// OpenGL 3.0 or `GL_NV_conditional_render`, otherwise a
+    /// `ConditionalRenderingNotSupported` error is returned.
     pub condition: Option<ConditionalRendering<'a>>,
 
     /// If set, then the generated primitives will be written back to a buffer.
@@ -424,15 +703,28 @@ impl<'a> Default for DrawParameters<'a> {
             depth: Depth::default(),
             stencil: Default::default(),
             blend: Default::default(),
+            blend_per_attachment: None,
             color_mask: (true, true, true, true),
+            color_mask_per_attachment: None,
+            logic_operation: None,
+            framebuffer_srgb: None,
+            color_clamping: None,
             line_width: None,
             point_size: None,
+            point_sprite_origin: PointSpriteOrigin::UpperLeft,
             backface_culling: BackfaceCullingMode::CullingDisabled,
             polygon_mode: PolygonMode::Fill,
+            polygon_offset: Default::default(),
             multisampling: true,
+            alpha_to_coverage: false,
+            sample_coverage: None,
+            min_sample_shading: None,
             dithering: true,
             viewport: None,
             scissor: None,
+            viewports: None,
+            scissors: None,
+            depth_ranges: None,
             draw_primitives: true,
             samples_passed_query: None,
             time_elapsed_query: None,
@@ -455,7 +747,8 @@ pub fn validate(context: &Context, params: &DrawParameters) -> Result<(), DrawEr
         return Err(DrawError::InvalidDepthRange);
     }
 
-    if !params.draw_primitives && context.get_version() < &Version(Api::Gl, 3, 0) &&
+    if !params.draw_primitives && !(context.get_version() >= &Version(Api::Gl, 3, 0)) &&
+        !(context.get_version() >= &Version(Api::GlEs, 3, 0)) &&
         !context.get_extensions().gl_ext_transform_feedback
     {
         return Err(DrawError::RasterizerDiscardNotSupported);
@@ -466,25 +759,38 @@ pub fn validate(context: &Context, params: &DrawParameters) -> Result<(), DrawEr
 
 #[doc(hidden)]
 pub fn sync(ctxt: &mut context::CommandContext, draw_parameters: &DrawParameters,
-            dimensions: (u32, u32), primitives_types: PrimitiveType) -> Result<(), DrawError>
+            dimensions: (u32, u32), draw_buffers: usize, primitives_types: PrimitiveType)
+            -> Result<(), DrawError>
 {
-    try!(depth::sync_depth(ctxt, &draw_parameters.depth));
-    stencil::sync_stencil(ctxt, &draw_parameters.stencil);
-    try!(blend::sync_blending(ctxt, draw_parameters.blend));
-    sync_color_mask(ctxt, draw_parameters.color_mask);
+    try!(depth::sync_depth(ctxt, &draw_parameters.depth, draw_parameters.depth_ranges));
+    try!(stencil::sync_stencil(ctxt, &draw_parameters.stencil));
+    try!(blend::sync_blending(ctxt, draw_parameters.blend, draw_parameters.blend_per_attachment,
+                              draw_buffers));
+    try!(sync_logic_operation(ctxt, draw_parameters.logic_operation));
+    try!(sync_color_mask(ctxt, draw_parameters.color_mask,
+                         draw_parameters.color_mask_per_attachment));
+    try!(sync_framebuffer_srgb(ctxt, draw_parameters.framebuffer_srgb));
+    try!(sync_color_clamping(ctxt, draw_parameters.color_clamping));
     sync_line_width(ctxt, draw_parameters.line_width);
-    sync_point_size(ctxt, draw_parameters.point_size);
-    sync_polygon_mode(ctxt, draw_parameters.backface_culling, draw_parameters.polygon_mode);
-    sync_multisampling(ctxt, draw_parameters.multisampling);
+    try!(sync_point_size(ctxt, draw_parameters.point_size, draw_parameters.point_sprite_origin,
+                         primitives_types));
+    try!(sync_polygon_mode(ctxt, draw_parameters.backface_culling,
+                           draw_parameters.polygon_mode));
+    try!(sync_polygon_offset(ctxt, draw_parameters.polygon_offset));
+    try!(sync_multisampling(ctxt, draw_parameters.multisampling));
+    sync_sample_coverage(ctxt, draw_parameters.alpha_to_coverage,
+                         draw_parameters.sample_coverage);
+    try!(sync_min_sample_shading(ctxt, draw_parameters.min_sample_shading));
     sync_dithering(ctxt, draw_parameters.dithering);
-    sync_viewport_scissor(ctxt, draw_parameters.viewport, draw_parameters.scissor,
-                          dimensions);
+    try!(sync_viewport_scissor(ctxt, draw_parameters.viewport, draw_parameters.scissor,
+                               draw_parameters.viewports, draw_parameters.scissors,
+                               dimensions));
     try!(sync_rasterizer_discard(ctxt, draw_parameters.draw_primitives));
     try!(sync_queries(ctxt, draw_parameters.samples_passed_query,
                       draw_parameters.time_elapsed_query,
                       draw_parameters.primitives_generated_query,
                       draw_parameters.transform_feedback_primitives_written_query));
-    sync_conditional_render(ctxt, draw_parameters.condition);
+    try!(sync_conditional_render(ctxt, draw_parameters.condition));
     try!(sync_smooth(ctxt, draw_parameters.smooth, primitives_types));
     try!(sync_provoking_vertex(ctxt, draw_parameters.provoking_vertex));
     sync_primitive_bounding_box(ctxt, &draw_parameters.primitive_bounding_box);
@@ -492,25 +798,174 @@ pub fn sync(ctxt: &mut context::CommandContext, draw_parameters: &DrawParameters
     Ok(())
 }
 
-fn sync_color_mask(ctxt: &mut context::CommandContext, mask: (bool, bool, bool, bool)) {
-    let mask = (
+#[inline]
+fn color_mask_to_gl(mask: (bool, bool, bool, bool))
+                    -> (gl::types::GLboolean, gl::types::GLboolean,
+                        gl::types::GLboolean, gl::types::GLboolean)
+{
+    (
         if mask.0 { 1 } else { 0 },
         if mask.1 { 1 } else { 0 },
         if mask.2 { 1 } else { 0 },
         if mask.3 { 1 } else { 0 },
-    );
+    )
+}
+
+fn sync_color_mask(ctxt: &mut context::CommandContext, mask: (bool, bool, bool, bool),
+                   per_attachment: Option<&[(bool, bool, bool, bool)]>) -> Result<(), DrawError>
+{
+    if let Some(per_attachment) = per_attachment {
+        // `glColorMaski` is only available since OpenGL 3.0
+        if !(ctxt.version >= &Version(Api::Gl, 3, 0)) &&
+           !(ctxt.version >= &Version(Api::GlEs, 3, 2))
+        {
+            return Err(DrawError::PerAttachmentColorMaskNotSupported);
+        }
+
+        if per_attachment.len() > ctxt.capabilities.max_draw_buffers as usize {
+            return Err(DrawError::PerAttachmentColorMaskNotSupported);
+        }
 
-    if ctxt.state.color_mask != mask {
+        let max_draw_buffers = ctxt.capabilities.max_draw_buffers as usize;
+
+        // `glColorMask` has set the same mask for all the draw buffers
+        if !ctxt.state.color_mask_indexed {
+            let current = ctxt.state.color_mask;
+            ctxt.state.color_mask_indexed_state.clear();
+            for _ in 0 .. max_draw_buffers {
+                ctxt.state.color_mask_indexed_state.push(current);
+            }
+
+            ctxt.state.color_mask_indexed = true;
+        }
+
+        for index in 0 .. max_draw_buffers {
+            let mask = color_mask_to_gl(per_attachment.get(index).cloned()
+                                                      .unwrap_or((true, true, true, true)));
+
+            if ctxt.state.color_mask_indexed_state[index] != mask {
+                unsafe {
+                    ctxt.gl.ColorMaski(index as gl::types::GLuint, mask.0, mask.1, mask.2,
+                                       mask.3);
+                }
+
+                ctxt.state.color_mask_indexed_state[index] = mask;
+            }
+        }
+
+        return Ok(());
+    }
+
+    let mask = color_mask_to_gl(mask);
+
+    if ctxt.state.color_mask != mask || ctxt.state.color_mask_indexed {
         unsafe {
             ctxt.gl.ColorMask(mask.0, mask.1, mask.2, mask.3);
         }
 
         ctxt.state.color_mask = mask;
+        ctxt.state.color_mask_indexed = false;
+    }
+
+    Ok(())
+}
+
+fn sync_logic_operation(ctxt: &mut context::CommandContext, operation: Option<LogicOperation>)
+                        -> Result<(), DrawError>
+{
+    let operation = match operation {
+        Some(operation) => operation.to_glenum(),
+        None => {
+            if ctxt.state.enabled_color_logic_op {
+                unsafe { ctxt.gl.Disable(gl::COLOR_LOGIC_OP); }
+                ctxt.state.enabled_color_logic_op = false;
+            }
+
+            return Ok(());
+        },
+    };
+
+    if ctxt.version.0 == Api::GlEs {
+        return Err(DrawError::LogicOperationNotSupported);
     }
+
+    if !ctxt.state.enabled_color_logic_op {
+        unsafe { ctxt.gl.Enable(gl::COLOR_LOGIC_OP); }
+        ctxt.state.enabled_color_logic_op = true;
+    }
+
+    if ctxt.state.logic_op != operation {
+        unsafe { ctxt.gl.LogicOp(operation); }
+        ctxt.state.logic_op = operation;
+    }
+
+    Ok(())
+}
+
+fn sync_framebuffer_srgb(ctxt: &mut context::CommandContext, framebuffer_srgb: Option<bool>)
+                         -> Result<(), DrawError>
+{
+    // if `None`, the value has already been set when binding the program
+    let framebuffer_srgb = match framebuffer_srgb {
+        Some(v) => v,
+        None => return Ok(())
+    };
+
+    if !(ctxt.version >= &Version(Api::Gl, 3, 0) || ctxt.extensions.gl_arb_framebuffer_srgb ||
+         ctxt.extensions.gl_ext_framebuffer_srgb || ctxt.extensions.gl_ext_srgb_write_control)
+    {
+        return Err(DrawError::FramebufferSrgbNotSupported);
+    }
+
+    if ctxt.state.enabled_framebuffer_srgb != framebuffer_srgb {
+        unsafe {
+            if framebuffer_srgb {
+                ctxt.gl.Enable(gl::FRAMEBUFFER_SRGB);
+            } else {
+                ctxt.gl.Disable(gl::FRAMEBUFFER_SRGB);
+            }
+        }
+
+        ctxt.state.enabled_framebuffer_srgb = framebuffer_srgb;
+    }
+
+    Ok(())
+}
+
+fn sync_color_clamping(ctxt: &mut context::CommandContext, color_clamping: Option<bool>)
+                       -> Result<(), DrawError>
+{
+    let value = match color_clamping {
+        Some(true) => gl::TRUE as gl::types::GLenum,
+        Some(false) => gl::FALSE as gl::types::GLenum,
+        None => gl::FIXED_ONLY,
+    };
+
+    if ctxt.state.clamp_fragment_color == value {
+        return Ok(());
+    }
+
+    // `GL_CLAMP_FRAGMENT_COLOR` was removed from the core profile in OpenGL 3.1
+    let compatibility = match ctxt.capabilities.profile {
+        Some(Profile::Compatibility) => true,
+        _ => !(ctxt.version >= &Version(Api::Gl, 3, 1)),
+    };
+
+    if !(ctxt.version >= &Version(Api::Gl, 3, 0)) || !compatibility {
+        return Err(DrawError::ColorClampingNotSupported);
+    }
+
+    unsafe { ctxt.gl.ClampColor(gl::CLAMP_FRAGMENT_COLOR, value); }
+    ctxt.state.clamp_fragment_color = value;
+
+    Ok(())
 }
 
 fn sync_line_width(ctxt: &mut context::CommandContext, line_width: Option<f32>) {
     if let Some(line_width) = line_width {
+        let (min, max) = ctxt.capabilities.line_width_range;
+        let line_width = line_width.max(min).min(max);
+
         if ctxt.state.line_width != line_width {
             unsafe {
                 ctxt.gl.LineWidth(line_width);
@@ -520,7 +975,23 @@ fn sync_line_width(ctxt: &mut context::CommandContext, line_width: Option<f32>)
     }
 }
 
-fn sync_point_size(ctxt: &mut context::CommandContext, point_size: Option<f32>) {
+fn sync_point_size(ctxt: &mut context::CommandContext, point_size: Option<f32>,
+                   origin: PointSpriteOrigin, primitive_type: PrimitiveType)
+                   -> Result<(), DrawError>
+{
+    // OpenGL ES always draws points as sprites, whose size is written by the vertex shader
+    if ctxt.version.0 == Api::GlEs {
+        if point_size.is_some() {
+            return Err(DrawError::FixedPointSizeNotSupported);
+        }
+
+        if origin != PointSpriteOrigin::UpperLeft {
+            return Err(DrawError::PointSpriteOriginNotSupported);
+        }
+
+        return Ok(());
+    }
+
     if let Some(point_size) = point_size {
         if ctxt.state.point_size != point_size {
             unsafe {
@@ -529,10 +1000,40 @@ fn sync_point_size(ctxt: &mut context::CommandContext, point_size: Option<f32>)
             }
         }
     }
+
+    if primitive_type != PrimitiveType::Points {
+        return Ok(());
+    }
+
+    // with the compatibility profile, `gl_PointCoord` is only defined for point sprites
+    let compatibility = match ctxt.capabilities.profile {
+        Some(Profile::Compatibility) => true,
+        _ => !(ctxt.version >= &Version(Api::Gl, 3, 0)),
+    };
+
+    if compatibility && !ctxt.state.enabled_point_sprite {
+        unsafe { ctxt.gl.Enable(gl::POINT_SPRITE); }
+        ctxt.state.enabled_point_sprite = true;
+    }
+
+    let origin = origin.to_glenum();
+    if ctxt.state.point_sprite_coord_origin != origin {
+        if !(ctxt.version >= &Version(Api::Gl, 2, 0)) {
+            return Err(DrawError::PointSpriteOriginNotSupported);
+        }
+
+        unsafe {
+            ctxt.gl.PointParameteri(gl::POINT_SPRITE_COORD_ORIGIN,
+                                    origin as gl::types::GLint);
+        }
+        ctxt.state.point_sprite_coord_origin = origin;
+    }
+
+    Ok(())
 }
 
 fn sync_polygon_mode(ctxt: &mut context::CommandContext, backface_culling: BackfaceCullingMode,
-                     polygon_mode: PolygonMode)
+                     polygon_mode: PolygonMode) -> Result<(), DrawError>
 {
     // back-face culling
     // note: we never change the value of `glFrontFace`, whose default is GL_CCW
@@ -567,16 +1068,78 @@ fn sync_polygon_mode(ctxt: &mut context::CommandContext, backface_culling: Backf
     }
 
     // polygon mode
+    let polygon_mode = polygon_mode.to_glenum();
+    if ctxt.state.polygon_mode != polygon_mode {
+        // `glPolygonMode` doesn't exist on OpenGL ES, where the mode is always `GL_FILL`
+        if ctxt.version.0 == Api::GlEs {
+            return Err(DrawError::PolygonModeNotSupported);
+        }
+
+        unsafe { ctxt.gl.PolygonMode(gl::FRONT_AND_BACK, polygon_mode); }
+        ctxt.state.polygon_mode = polygon_mode;
+    }
+
+    Ok(())
+}
+
+fn sync_polygon_offset(ctxt: &mut context::CommandContext, offset: PolygonOffset)
+                       -> Result<(), DrawError>
+{
+    if (offset.point || offset.line) && ctxt.version.0 == Api::GlEs {
+        return Err(DrawError::PolygonOffsetNotSupported);
+    }
+
     unsafe {
-        let polygon_mode = polygon_mode.to_glenum();
-        if ctxt.state.polygon_mode != polygon_mode {
-            ctxt.gl.PolygonMode(gl::FRONT_AND_BACK, polygon_mode);
-            ctxt.state.polygon_mode = polygon_mode;
+        if ctxt.state.enabled_polygon_offset_fill != offset.fill {
+            if offset.fill {
+                ctxt.gl.Enable(gl::POLYGON_OFFSET_FILL);
+            } else {
+                ctxt.gl.Disable(gl::POLYGON_OFFSET_FILL);
+            }
+            ctxt.state.enabled_polygon_offset_fill = offset.fill;
+        }
+
+        if ctxt.state.enabled_polygon_offset_line != offset.line {
+            if offset.line {
+                ctxt.gl.Enable(gl::POLYGON_OFFSET_LINE);
+            } else {
+                ctxt.gl.Disable(gl::POLYGON_OFFSET_LINE);
+            }
+            ctxt.state.enabled_polygon_offset_line = offset.line;
+        }
+
+        if ctxt.state.enabled_polygon_offset_point != offset.point {
+            if offset.point {
+                ctxt.gl.Enable(gl::POLYGON_OFFSET_POINT);
+            } else {
+                ctxt.gl.Disable(gl::POLYGON_OFFSET_POINT);
+            }
+            ctxt.state.enabled_polygon_offset_point = offset.point;
+        }
+
+        if (offset.fill || offset.line || offset.point) &&
+           ctxt.state.polygon_offset != (offset.factor, offset.units)
+        {
+            ctxt.gl.PolygonOffset(offset.factor, offset.units);
+            ctxt.state.polygon_offset = (offset.factor, offset.units);
         }
     }
+
+    Ok(())
 }
 
-fn sync_multisampling(ctxt: &mut context::CommandContext, multisampling: bool) {
+fn sync_multisampling(ctxt: &mut context::CommandContext, multisampling: bool)
+                      -> Result<(), DrawError>
+{
+    // OpenGL ES doesn't have `GL_MULTISAMPLE`, multisampling is always enabled
+    if ctxt.version.0 == Api::GlEs {
+        if !multisampling {
+            return Err(DrawError::MultisamplingNotSupported);
+        }
+
+        return Ok(());
+    }
+
     if ctxt.state.enabled_multisample != multisampling {
         unsafe {
             if multisampling {
@@ -588,6 +1151,91 @@ fn sync_multisampling(ctxt: &mut context::CommandContext, multisampling: bool) {
             }
         }
     }
+
+    Ok(())
+}
+
+fn sync_sample_coverage(ctxt: &mut context::CommandContext, alpha_to_coverage: bool,
+                        sample_coverage: Option<(f32, bool)>)
+{
+    if ctxt.state.enabled_sample_alpha_to_coverage != alpha_to_coverage {
+        unsafe {
+            if alpha_to_coverage {
+                ctxt.gl.Enable(gl::SAMPLE_ALPHA_TO_COVERAGE);
+            } else {
+                ctxt.gl.Disable(gl::SAMPLE_ALPHA_TO_COVERAGE);
+            }
+        }
+
+        ctxt.state.enabled_sample_alpha_to_coverage = alpha_to_coverage;
+    }
+
+    match sample_coverage {
+        Some((value, invert)) => {
+            if !ctxt.state.enabled_sample_coverage {
+                unsafe { ctxt.gl.Enable(gl::SAMPLE_COVERAGE); }
+                ctxt.state.enabled_sample_coverage = true;
+            }
+
+            let value = (value, if invert { gl::TRUE } else { gl::FALSE });
+            if ctxt.state.sample_coverage != value {
+                unsafe { ctxt.gl.SampleCoverage(value.0, value.1); }
+                ctxt.state.sample_coverage = value;
+            }
+        },
+
+        None => {
+            if ctxt.state.enabled_sample_coverage {
+                unsafe { ctxt.gl.Disable(gl::SAMPLE_COVERAGE); }
+                ctxt.state.enabled_sample_coverage = false;
+            }
+        },
+    }
+}
+
+fn sync_min_sample_shading(ctxt: &mut context::CommandContext, value: Option<f32>)
+                           -> Result<(), DrawError>
+{
+    let value = match value {
+        Some(value) => value,
+        None => {
+            if ctxt.state.enabled_sample_shading {
+                unsafe { ctxt.gl.Disable(gl::SAMPLE_SHADING); }
+                ctxt.state.enabled_sample_shading = false;
+            }
+
+            return Ok(());
+        },
+    };
+
+    if !(ctxt.version >= &Version(Api::Gl, 4, 0)) && !(ctxt.version >= &Version(Api::GlEs, 3, 2)) &&
+       !ctxt.extensions.gl_arb_sample_shading && !ctxt.extensions.gl_oes_sample_shading
+    {
+        return Err(DrawError::SampleShadingNotSupported);
+    }
+
+    if !ctxt.state.enabled_sample_shading {
+        unsafe { ctxt.gl.Enable(gl::SAMPLE_SHADING); }
+        ctxt.state.enabled_sample_shading = true;
+    }
+
+    if ctxt.state.min_sample_shading != value {
+        unsafe {
+            if ctxt.version >= &Version(Api::Gl, 4, 0) ||
+               ctxt.version >= &Version(Api::GlEs, 3, 2)
+            {
+                ctxt.gl.MinSampleShading(value);
+            } else if ctxt.extensions.gl_arb_sample_shading {
+                ctxt.gl.MinSampleShadingARB(value);
+            } else {
+                ctxt.gl.MinSampleShadingOES(value);
+            }
+        }
+
+        ctxt.state.min_sample_shading = value;
+    }
+
+    Ok(())
 }
 
 fn sync_dithering(ctxt: &mut context::CommandContext, dithering: bool) {
@@ -605,49 +1253,133 @@ fn sync_dithering(ctxt: &mut context::CommandContext, dithering: bool) {
 }
 
 fn sync_viewport_scissor(ctxt: &mut context::CommandContext, viewport: Option<Rect>,
-                         scissor: Option<Rect>, surface_dimensions: (u32, u32))
+                         scissor: Option<Rect>, viewports: Option<&[Rect]>,
+                         scissors: Option<&[Rect]>, surface_dimensions: (u32, u32))
+                         -> Result<(), DrawError>
 {
+    if viewports.is_some() || scissors.is_some() {
+        if !(ctxt.version >= &Version(Api::Gl, 4, 1)) && !ctxt.extensions.gl_arb_viewport_array {
+            return Err(DrawError::ViewportArrayNotSupported);
+        }
+
+        let max = ctxt.capabilities.max_viewports as usize;
+        if viewports.map(|v| v.len()).unwrap_or(0) > max ||
+           scissors.map(|s| s.len()).unwrap_or(0) > max
+        {
+            return Err(DrawError::TooManyViewports);
+        }
+    }
+
     // viewport
-    if let Some(viewport) = viewport {
-        assert!(viewport.width <= ctxt.capabilities.max_viewport_dims.0 as u32,
-                "Viewport dimensions are too large");
-        assert!(viewport.height <= ctxt.capabilities.max_viewport_dims.1 as u32,
-                "Viewport dimensions are too large");
+    if let Some(viewports) = viewports {
+        for (index, viewport) in viewports.iter().enumerate() {
+            if viewport.width > ctxt.capabilities.max_viewport_dims.0 as u32 ||
+               viewport.height > ctxt.capabilities.max_viewport_dims.1 as u32
+            {
+                return Err(DrawError::ViewportTooLarge);
+            }
 
-        let viewport = (viewport.left as gl::types::GLint, viewport.bottom as gl::types::GLint,
-                        viewport.width as gl::types::GLsizei,
-                        viewport.height as gl::types::GLsizei);
+            let viewport = (viewport.left as gl::types::GLint,
+                            viewport.bottom as gl::types::GLint,
+                            viewport.width as gl::types::GLsizei,
+                            viewport.height as gl::types::GLsizei);
+
+            let current = ctxt.state.viewport_indexed.get(index).cloned()
+                                                     .or(ctxt.state.viewport);
+            if current != Some(viewport) {
+                unsafe {
+                    ctxt.gl.ViewportIndexedf(index as gl::types::GLuint,
+                                             viewport.0 as gl::types::GLfloat,
+                                             viewport.1 as gl::types::GLfloat,
+                                             viewport.2 as gl::types::GLfloat,
+                                             viewport.3 as gl::types::GLfloat);
+                }
 
-        if ctxt.state.viewport != Some(viewport) {
-            unsafe { ctxt.gl.Viewport(viewport.0, viewport.1, viewport.2, viewport.3); }
-            ctxt.state.viewport = Some(viewport);
+                if index < ctxt.state.viewport_indexed.len() {
+                    ctxt.state.viewport_indexed[index] = viewport;
+                } else {
+                    // the skipped indices still contain the value of the last `glViewport`
+                    while ctxt.state.viewport_indexed.len() < index {
+                        let previous = ctxt.state.viewport.unwrap();
+                        ctxt.state.viewport_indexed.push(previous);
+                    }
+
+                    ctxt.state.viewport_indexed.push(viewport);
+                }
+            }
         }
 
     } else {
-        assert!(surface_dimensions.0 <= ctxt.capabilities.max_viewport_dims.0 as u32,
-                "Viewport dimensions are too large");
-        assert!(surface_dimensions.1 <= ctxt.capabilities.max_viewport_dims.1 as u32,
-                "Viewport dimensions are too large");
+        let viewport = if let Some(viewport) = viewport {
+            assert!(viewport.width <= ctxt.capabilities.max_viewport_dims.0 as u32,
+                    "Viewport dimensions are too large");
+            assert!(viewport.height <= ctxt.capabilities.max_viewport_dims.1 as u32,
+                    "Viewport dimensions are too large");
+
+            (viewport.left as gl::types::GLint, viewport.bottom as gl::types::GLint,
+             viewport.width as gl::types::GLsizei, viewport.height as gl::types::GLsizei)
 
-        let viewport = (0, 0, surface_dimensions.0 as gl::types::GLsizei,
-                        surface_dimensions.1 as gl::types::GLsizei);
+        } else {
+            assert!(surface_dimensions.0 <= ctxt.capabilities.max_viewport_dims.0 as u32,
+                    "Viewport dimensions are too large");
+            assert!(surface_dimensions.1 <= ctxt.capabilities.max_viewport_dims.1 as u32,
+                    "Viewport dimensions are too large");
+
+            (0, 0, surface_dimensions.0 as gl::types::GLsizei,
+             surface_dimensions.1 as gl::types::GLsizei)
+        };
 
-        if ctxt.state.viewport != Some(viewport) {
+        if ctxt.state.viewport != Some(viewport) || !ctxt.state.viewport_indexed.is_empty() {
             unsafe { ctxt.gl.Viewport(viewport.0, viewport.1, viewport.2, viewport.3); }
             ctxt.state.viewport = Some(viewport);
+            ctxt.state.viewport_indexed.clear();
         }
     }
 
     // scissor
-    if let Some(scissor) = scissor {
+    if let Some(scissors) = scissors {
+        for (index, scissor) in scissors.iter().enumerate() {
+            let scissor = (scissor.left as gl::types::GLint, scissor.bottom as gl::types::GLint,
+                           scissor.width as gl::types::GLsizei,
+                           scissor.height as gl::types::GLsizei);
+
+            let current = ctxt.state.scissor_indexed.get(index).cloned()
+                                                    .or(ctxt.state.scissor);
+            if current != Some(scissor) {
+                unsafe {
+                    ctxt.gl.ScissorIndexed(index as gl::types::GLuint, scissor.0, scissor.1,
+                                           scissor.2, scissor.3);
+                }
+
+                if index < ctxt.state.scissor_indexed.len() {
+                    ctxt.state.scissor_indexed[index] = scissor;
+                } else {
+                    // the skipped indices still contain the value of the last `glScissor`
+                    while ctxt.state.scissor_indexed.len() < index {
+                        let previous = ctxt.state.scissor.unwrap();
+                        ctxt.state.scissor_indexed.push(previous);
+                    }
+
+                    ctxt.state.scissor_indexed.push(scissor);
+                }
+            }
+        }
+
+        if !ctxt.state.enabled_scissor_test {
+            unsafe { ctxt.gl.Enable(gl::SCISSOR_TEST); }
+            ctxt.state.enabled_scissor_test = true;
+        }
+
+    } else if let Some(scissor) = scissor {
         let scissor = (scissor.left as gl::types::GLint, scissor.bottom as gl::types::GLint,
                        scissor.width as gl::types::GLsizei,
                        scissor.height as gl::types::GLsizei);
 
         unsafe {
-            if ctxt.state.scissor != Some(scissor) {
+            if ctxt.state.scissor != Some(scissor) || !ctxt.state.scissor_indexed.is_empty() {
                 ctxt.gl.Scissor(scissor.0, scissor.1, scissor.2, scissor.3);
                 ctxt.state.scissor = Some(scissor);
+                ctxt.state.scissor_indexed.clear();
             }
 
             if !ctxt.state.enabled_scissor_test {
@@ -663,13 +1395,15 @@ fn sync_viewport_scissor(ctxt: &mut context::CommandContext, viewport: Option<Re
             }
         }
     }
+
+    Ok(())
 }
 
 fn sync_rasterizer_discard(ctxt: &mut context::CommandContext, draw_primitives: bool)
                            -> Result<(), DrawError>
 {
     if ctxt.state.enabled_rasterizer_discard == draw_primitives {
-        if ctxt.version >= &Version(Api::Gl, 3, 0) {
+        if ctxt.version >= &Version(Api::Gl, 3, 0) || ctxt.version >= &Version(Api::GlEs, 3, 0) {
             if draw_primitives {
                 unsafe { ctxt.gl.Disable(gl::RASTERIZER_DISCARD); }
                 ctxt.state.enabled_rasterizer_discard = false;
@@ -733,9 +1467,15 @@ fn sync_queries(ctxt: &mut context::CommandContext,
 }
 
 fn sync_conditional_render(ctxt: &mut context::CommandContext,
-                           condition: Option<ConditionalRendering>)
+                           condition: Option<ConditionalRendering>) -> Result<(), DrawError>
 {
     if let Some(ConditionalRendering { query, wait, per_region }) = condition {
+        if !(ctxt.version >= &Version(Api::Gl, 3, 0)) &&
+           !ctxt.extensions.gl_nv_conditional_render
+        {
+            return Err(DrawError::ConditionalRenderingNotSupported);
+        }
+
         match query {
             SamplesQueryParam::SamplesPassedQuery(ref q) => {
                 q.begin_conditional_render(ctxt, wait, per_region);
@@ -748,6 +1488,8 @@ fn sync_conditional_render(ctxt: &mut context::CommandContext,
     } else {
         TimeElapsedQuery::end_conditional_render(ctxt);
     }
+
+    Ok(())
 }
 
 fn sync_smooth(ctxt: &mut context::CommandContext,
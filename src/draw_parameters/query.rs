@@ -30,6 +30,9 @@ pub struct RawQuery {
     // true means that this query has already been used or is being used to get data
     // this is important to know because we want to avoid erasing data
     has_been_used: Cell<bool>,
+
+    // value of `gpu_disjoint_events` when the query was started
+    disjoint_events: Cell<usize>,
 }
 
 pub enum QueryType {
@@ -103,6 +106,29 @@ impl Error for ToBufferError {
     }
 }
 
+/// Error that can happen when getting the result of a `TimeElapsedQuery`.
+#[derive(Copy, Clone, Debug)]
+pub enum TimeElapsedError {
+    /// A disjoint operation, like a change of the GPU frequency, happened while the query was
+    /// active, and the result is meaningless.
+    Disjoint,
+}
+
+impl fmt::Display for TimeElapsedError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.description())
+    }
+}
+
+impl Error for TimeElapsedError {
+    fn description(&self) -> &str {
+        use self::TimeElapsedError::*;
+        match *self {
+            Disjoint => "A disjoint operation happened while the query was active",
+        }
+    }
+}
+
 impl RawQuery {
     /// Builds a new query. Returns `None` if the backend doesn't support this type.
     pub fn new<F: ?Sized>(facade: &F, ty: QueryType) -> Result<RawQuery, QueryCreationError>
@@ -171,14 +197,23 @@ impl RawQuery {
                 match ty {
                     QueryType::AnySamplesPassed | QueryType::AnySamplesPassedConservative |
                     QueryType::TransformFeedbackPrimitivesWritten => (),
+                    QueryType::TimeElapsed if ctxt.extensions.gl_ext_disjoint_timer_query => (),
+                    QueryType::PrimitivesGenerated if
+                            ctxt.version >= &Version(Api::GlEs, 3, 2) ||
+                            ctxt.extensions.gl_ext_geometry_shader ||
+                            ctxt.extensions.gl_oes_geometry_shader => (),
                     _ => return Err(QueryCreationError::NotSupported)
                 };
 
                 ctxt.gl.GenQueries(1, &mut id);
 
-            } else if ctxt.extensions.gl_ext_occlusion_query_boolean {
+            } else if ctxt.extensions.gl_ext_occlusion_query_boolean ||
+                      ctxt.extensions.gl_ext_disjoint_timer_query
+            {
                 match ty {
-                    QueryType::AnySamplesPassed | QueryType::AnySamplesPassedConservative => (),
+                    QueryType::AnySamplesPassed | QueryType::AnySamplesPassedConservative
+                        if ctxt.extensions.gl_ext_occlusion_query_boolean => (),
+                    QueryType::TimeElapsed if ctxt.extensions.gl_ext_disjoint_timer_query => (),
                     _ => return Err(QueryCreationError::NotSupported)
                 };
 
@@ -196,16 +231,20 @@ impl RawQuery {
             id: id,
             ty: ty,
             has_been_used: Cell::new(false),
+            disjoint_events: Cell::new(0),
         })
     }
 
     /// Queries the counter to see if the result is already available.
+    ///
+    /// Always returns true if the query has never been used, as its result is then known to be
+    /// `0` without asking the GPU.
     pub fn is_ready(&self) -> bool {
         let mut ctxt = self.context.make_current();
         self.deactivate(&mut ctxt);
 
         if !self.has_been_used.get() {
-            return false;
+            return true;
         }
 
         Buffer::<u8>::unbind_query(&mut ctxt);
@@ -221,7 +260,9 @@ impl RawQuery {
             } else if ctxt.extensions.gl_arb_occlusion_query {
                 ctxt.gl.GetQueryObjectuivARB(self.id, gl::QUERY_RESULT_AVAILABLE, &mut value);
 
-            } else if ctxt.extensions.gl_ext_occlusion_query_boolean {
+            } else if ctxt.extensions.gl_ext_occlusion_query_boolean ||
+                      ctxt.extensions.gl_ext_disjoint_timer_query
+            {
                 ctxt.gl.GetQueryObjectuivEXT(self.id, gl::QUERY_RESULT_AVAILABLE, &mut value);
 
             } else {
@@ -234,6 +275,15 @@ impl RawQuery {
         }
     }
 
+    /// Returns true if a disjoint operation happened since the query was started.
+    ///
+    /// Must be called after the result of the query is available.
+    pub fn is_disjoint(&self) -> bool {
+        let mut ctxt = self.context.make_current();
+        let events = unsafe { poll_gpu_disjoint(&mut ctxt) };
+        self.has_been_used.get() && events != self.disjoint_events.get()
+    }
+
     /// Returns the value of the query. Blocks until it is available.
     ///
     /// This function doesn't block if `is_ready` returns true.
@@ -264,12 +314,15 @@ impl RawQuery {
             return Err(ToBufferError::NotSupported);
         }
 
-        self.deactivate(&mut ctxt);
-
+        // the result of a query that has never been used is `0`
         if !self.has_been_used.get() {
-            panic!();
+            drop(ctxt);
+            target.write(&0);
+            return Ok(());
         }
 
+        self.deactivate(&mut ctxt);
+
         assert!(target.get_offset_bytes() % 4 == 0);
 
         target.prepare_and_bind_for_query(&mut ctxt);
@@ -289,7 +342,9 @@ impl RawQuery {
         } else if ctxt.extensions.gl_arb_occlusion_query {
             ctxt.gl.GetQueryObjectuivARB(self.id, gl::QUERY_RESULT, target);
 
-        } else if ctxt.extensions.gl_ext_occlusion_query_boolean {
+        } else if ctxt.extensions.gl_ext_occlusion_query_boolean ||
+                  ctxt.extensions.gl_ext_disjoint_timer_query
+        {
             ctxt.gl.GetQueryObjectuivEXT(self.id, gl::QUERY_RESULT, target);
 
         } else {
@@ -398,7 +453,9 @@ impl Drop for RawQuery {
             } else if ctxt.extensions.gl_arb_occlusion_query {
                 ctxt.gl.DeleteQueriesARB(1, [self.id].as_ptr());
 
-            } else if ctxt.extensions.gl_ext_occlusion_query_boolean {
+            } else if ctxt.extensions.gl_ext_occlusion_query_boolean ||
+                      ctxt.extensions.gl_ext_disjoint_timer_query
+            {
                 ctxt.gl.DeleteQueriesEXT(1, [self.id].as_ptr());
 
             } else {
@@ -505,6 +562,7 @@ impl QueryExt for RawQuery {
                         if ctxt.state.time_elapsed_query != 0 {
                             raw_end_query(ctxt, gl::TIME_ELAPSED);
                         }
+                        self.disjoint_events.set(poll_gpu_disjoint(ctxt));
                         raw_begin_query(ctxt, gl::TIME_ELAPSED, self.id);
                     }
 
@@ -543,6 +601,11 @@ impl QueryExt for RawQuery {
                         if ctxt.state.transform_feedback_primitives_written_query != 0 {
                             raw_end_query(ctxt, gl::TRANSFORM_FEEDBACK_PRIMITIVES_WRITTEN);
                         }
+                        // the query of the transform feedback session uses the same target
+                        if ctxt.state.transform_feedback_session_query != 0 {
+                            raw_end_query(ctxt, gl::TRANSFORM_FEEDBACK_PRIMITIVES_WRITTEN);
+                            ctxt.state.transform_feedback_session_query = 0;
+                        }
                         raw_begin_query(ctxt, gl::TRANSFORM_FEEDBACK_PRIMITIVES_WRITTEN, self.id);
                     }
 
@@ -654,6 +717,16 @@ impl QueryExt for RawQuery {
         ctxt.state.conditional_render = None;
     }
 
+    fn end_own_conditional_render(&self) {
+        let mut ctxt = self.context.make_current();
+
+        if let Some((id, _)) = ctxt.state.conditional_render {
+            if id == self.id {
+                RawQuery::end_conditional_render(&mut ctxt);
+            }
+        }
+    }
+
     fn is_unused(&self) -> bool {
         !self.has_been_used.get()
     }
@@ -691,7 +764,9 @@ unsafe fn raw_begin_query(ctxt: &mut CommandContext, ty: gl::types::GLenum, id:
     } else if ctxt.extensions.gl_arb_occlusion_query {
         ctxt.gl.BeginQueryARB(ty, id);
 
-    } else if ctxt.extensions.gl_ext_occlusion_query_boolean {
+    } else if ctxt.extensions.gl_ext_occlusion_query_boolean ||
+              ctxt.extensions.gl_ext_disjoint_timer_query
+    {
         ctxt.gl.BeginQueryEXT(ty, id);
 
     } else {
@@ -704,6 +779,20 @@ unsafe fn raw_begin_query(ctxt: &mut CommandContext, ty: gl::types::GLenum, id:
 /// # Unsafe
 ///
 /// The type of query must be guaranteed to be supported by the backend.
+/// Reads and resets `GL_GPU_DISJOINT_EXT`, and returns the number of disjoint operations that
+/// have been detected so far.
+unsafe fn poll_gpu_disjoint(ctxt: &mut CommandContext) -> usize {
+    if ctxt.extensions.gl_ext_disjoint_timer_query {
+        let mut disjoint = 0;
+        ctxt.gl.GetIntegerv(gl::GPU_DISJOINT_EXT, &mut disjoint);
+        if disjoint != 0 {
+            ctxt.state.gpu_disjoint_events += 1;
+        }
+    }
+
+    ctxt.state.gpu_disjoint_events
+}
+
 unsafe fn raw_end_query(ctxt: &mut CommandContext, ty: gl::types::GLenum) {
     if ctxt.version >= &Version(Api::Gl, 1, 5) ||
        ctxt.version >= &Version(Api::GlEs, 3, 0)
@@ -713,7 +802,9 @@ unsafe fn raw_end_query(ctxt: &mut CommandContext, ty: gl::types::GLenum) {
     } else if ctxt.extensions.gl_arb_occlusion_query {
         ctxt.gl.EndQueryARB(ty);
 
-    } else if ctxt.extensions.gl_ext_occlusion_query_boolean {
+    } else if ctxt.extensions.gl_ext_occlusion_query_boolean ||
+              ctxt.extensions.gl_ext_disjoint_timer_query
+    {
         ctxt.gl.EndQueryEXT(ty);
 
     } else {
@@ -725,6 +816,8 @@ macro_rules! impl_helper {
     ($name:ident, $ret:ty, $get_fn:ident) => {
         impl $name {
             /// Queries the counter to see if the result is already available.
+            ///
+            /// This function never blocks, and can be called every frame until it returns true.
             #[inline]
             pub fn is_ready(&self) -> bool {
                 self.query.is_ready()
@@ -747,9 +840,13 @@ macro_rules! impl_helper {
             /// Writes the result of the query to a buffer when it is available.
             ///
             /// This function doesn't block. Instead it submits a commands to the GPU's commands
-            /// queue and orders the GPU to write the result of the query to a buffer.
+            /// queue and orders the GPU to write the result of the query to a buffer. The buffer
+            /// can then be used as a source of indirect draw or dispatch commands without any
+            /// round-trip through the CPU.
             ///
-            /// This operation is not necessarly supported everywhere.
+            /// This operation requires OpenGL 4.4, `GL_ARB_query_buffer_object` or
+            /// `GL_AMD_query_buffer_object`, otherwise `ToBufferError::NotSupported` is
+            /// returned.
             #[inline]
             pub fn to_buffer_u32(&self, target: BufferSlice<u32>)
                                  -> Result<(), ToBufferError>
@@ -803,6 +900,11 @@ macro_rules! impl_helper {
                 RawQuery::end_conditional_render(ctxt)
             }
 
+            #[inline]
+            fn end_own_conditional_render(&self) {
+                self.query.end_own_conditional_render()
+            }
+
             #[inline]
             fn is_unused(&self) -> bool {
                 self.query.is_unused()
@@ -831,10 +933,12 @@ impl SamplesPassedQuery {
 
 impl_helper!(SamplesPassedQuery, u32, get_u32);
 
-/// A query that allows you to know the number of nanoseconds that have elapsed
-/// during the draw operations.
+/// A query that allows you to know the number of nanoseconds that the GPU has spent executing
+/// the draw operations.
 ///
-/// TODO: not sure that it's nanoseconds
+/// Requires OpenGL 3.3, `GL_ARB_timer_query`, or `GL_EXT_disjoint_timer_query` on OpenGL ES.
+/// With the latter, the result is meaningless if the GPU changed its frequency while the
+/// query was active. Use `get_checked` to detect this situation.
 #[derive(Debug)]
 pub struct TimeElapsedQuery {
     query: RawQuery,
@@ -846,6 +950,21 @@ impl TimeElapsedQuery {
     pub fn new<F: ?Sized>(facade: &F) -> Result<TimeElapsedQuery, QueryCreationError> where F: Facade {
         RawQuery::new(facade, QueryType::TimeElapsed).map(|q| TimeElapsedQuery { query: q })
     }
+
+    /// Returns the value of the query, or an error if a disjoint operation happened while the
+    /// query was active. Blocks until the value is available.
+    ///
+    /// Disjoint operations can only be detected with `GL_EXT_disjoint_timer_query`.
+    #[inline]
+    pub fn get_checked(self) -> Result<u32, TimeElapsedError> {
+        let value = self.query.get_u32();
+
+        if self.query.is_disjoint() {
+            Err(TimeElapsedError::Disjoint)
+        } else {
+            Ok(value)
+        }
+    }
 }
 
 impl_helper!(TimeElapsedQuery, u32, get_u32);
@@ -893,8 +1012,14 @@ impl AnySamplesPassedQuery {
 
 impl_helper!(AnySamplesPassedQuery, bool, get_bool);
 
-/// Query that allows you to know the number of primitives generated by the geometry shader.
-/// Will stay at `0` if you use it without any active geometry shader.
+/// Query that allows you to know the number of primitives generated by the last vertex
+/// processing stage, in other words the geometry shader if there is one, or else the
+/// tessellation or vertex shader.
+///
+/// Contrary to `TransformFeedbackPrimitivesWrittenQuery`, the primitives are counted even if
+/// they don't fit in the transform feedback buffer or if transform feedback is not used.
+///
+/// Requires OpenGL 3.0, `GL_EXT_transform_feedback`, or OpenGL ES 3.2.
 #[derive(Debug)]
 pub struct PrimitivesGeneratedQuery {
     query: RawQuery,
@@ -913,7 +1038,11 @@ impl PrimitivesGeneratedQuery {
 
 impl_helper!(PrimitivesGeneratedQuery, u32, get_u32);
 
-/// Query that allows you to know the number of primitives generated by transform feedback.
+/// Query that allows you to know the number of primitives written to the buffers of transform
+/// feedback.
+///
+/// Multiply the result by the number of vertices per primitive to obtain the number of vertices
+/// that were captured.
 #[derive(Debug)]
 pub struct TransformFeedbackPrimitivesWrittenQuery {
     query: RawQuery,
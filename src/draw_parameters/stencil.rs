@@ -1,8 +1,16 @@
 use gl;
 use context;
+use version::Version;
+use version::Api;
+use DrawError;
 use ToGlEnum;
 
 /// Describes the parameters that must be used for the stencil operations when drawing.
+///
+/// Clockwise and counter-clockwise faces can use different parameters, for example to
+/// increment the stencil buffer for front faces and decrement it for back faces in a single
+/// draw call. This requires OpenGL 2.0 or OpenGL ES 2.0, otherwise drawing returns
+/// `DrawError::StencilSeparateNotSupported`.
 #[derive(Copy, Clone, Debug)]
 pub struct Stencil {
     /// A comparison against the existing value in the stencil buffer.
@@ -220,7 +228,9 @@ impl ToGlEnum for StencilOperation {
     }
 }
 
-pub fn sync_stencil(ctxt: &mut context::CommandContext, params: &Stencil) {
+pub fn sync_stencil(ctxt: &mut context::CommandContext, params: &Stencil)
+                    -> Result<(), DrawError>
+{
     // checks if stencil operations can be disabled
     if params.test_clockwise == StencilTest::AlwaysPass &&
        params.test_counter_clockwise == StencilTest::AlwaysPass &&
@@ -236,18 +246,12 @@ pub fn sync_stencil(ctxt: &mut context::CommandContext, params: &Stencil) {
             ctxt.state.enabled_stencil_test = false;
         }
 
-        return;
+        return Ok(());
     }
 
     // we are now in "stencil enabled land"
 
-    // enabling if necessary
-    if ctxt.state.enabled_stencil_test != true {
-        unsafe { ctxt.gl.Enable(gl::STENCIL_TEST) };
-        ctxt.state.enabled_stencil_test = true;
-    }
-
-    // synchronizing the test and read masks
+    // converting the tests and read masks
     let (test_cw, read_mask_cw) = match params.test_clockwise {
         StencilTest::AlwaysPass => (gl::ALWAYS, 0),
         StencilTest::AlwaysFail => (gl::NEVER, 0),
@@ -270,9 +274,35 @@ pub fn sync_stencil(ctxt: &mut context::CommandContext, params: &Stencil) {
         StencilTest::IfNotEqual { mask } => (gl::NOTEQUAL, mask),
     };
 
+    let op_back = (params.fail_operation_clockwise.to_glenum(),
+                   params.pass_depth_fail_operation_clockwise.to_glenum(),
+                   params.depth_pass_operation_clockwise.to_glenum());
+
+    let op_front = (params.fail_operation_counter_clockwise.to_glenum(),
+                    params.pass_depth_fail_operation_counter_clockwise.to_glenum(),
+                    params.depth_pass_operation_counter_clockwise.to_glenum());
+
     let ref_cw = params.reference_value_clockwise;
     let ref_ccw = params.reference_value_counter_clockwise;
 
+    // the `*Separate` functions are required if the two faces use different parameters
+    let separate = test_cw != test_ccw || ref_cw != ref_ccw || read_mask_cw != read_mask_ccw ||
+                   params.write_mask_clockwise != params.write_mask_counter_clockwise ||
+                   op_back != op_front;
+
+    if separate && !(ctxt.version >= &Version(Api::Gl, 2, 0)) &&
+       !(ctxt.version >= &Version(Api::GlEs, 2, 0))
+    {
+        return Err(DrawError::StencilSeparateNotSupported);
+    }
+
+    // enabling if necessary
+    if ctxt.state.enabled_stencil_test != true {
+        unsafe { ctxt.gl.Enable(gl::STENCIL_TEST) };
+        ctxt.state.enabled_stencil_test = true;
+    }
+
+    // synchronizing the test and read masks
     if (test_cw, ref_cw, read_mask_cw) == (test_ccw, ref_ccw, read_mask_ccw) {
         if ctxt.state.stencil_func_back != (test_cw, ref_cw, read_mask_cw) ||
            ctxt.state.stencil_func_front != (test_ccw, ref_ccw, read_mask_ccw)
@@ -310,21 +340,13 @@ pub fn sync_stencil(ctxt: &mut context::CommandContext, params: &Stencil) {
             ctxt.state.stencil_mask_back = params.write_mask_clockwise;
         }
 
-        if ctxt.state.stencil_mask_front != params.write_mask_clockwise {
-            unsafe { ctxt.gl.StencilMaskSeparate(gl::FRONT, params.write_mask_clockwise) };
-            ctxt.state.stencil_mask_front = params.write_mask_clockwise;
+        if ctxt.state.stencil_mask_front != params.write_mask_counter_clockwise {
+            unsafe { ctxt.gl.StencilMaskSeparate(gl::FRONT, params.write_mask_counter_clockwise) };
+            ctxt.state.stencil_mask_front = params.write_mask_counter_clockwise;
         }
     }
 
     // synchronizing the operation
-    let op_back = (params.fail_operation_clockwise.to_glenum(),
-                   params.pass_depth_fail_operation_clockwise.to_glenum(),
-                   params.depth_pass_operation_clockwise.to_glenum());
-
-    let op_front = (params.fail_operation_counter_clockwise.to_glenum(),
-                    params.pass_depth_fail_operation_counter_clockwise.to_glenum(),
-                    params.depth_pass_operation_counter_clockwise.to_glenum());
-
     if op_back == op_front {
         if ctxt.state.stencil_op_back != op_back || ctxt.state.stencil_op_front != op_front {
             unsafe { ctxt.gl.StencilOp(op_back.0, op_back.1, op_back.2) };
@@ -343,4 +365,6 @@ pub fn sync_stencil(ctxt: &mut context::CommandContext, params: &Stencil) {
             ctxt.state.stencil_op_front = op_front;
         }
     }
+
+    Ok(())
 }
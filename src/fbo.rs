@@ -1,6 +1,10 @@
 /*!
 Contains everything related to the internal handling of framebuffer objects.
 
+Framebuffer objects are created lazily and cached by the `FramebuffersContainer` of each context,
+keyed by their list of attachments. Rendering to the same textures repeatedly reuses the same
+framebuffer object, and it is only destroyed when one of its attachments is destroyed.
+
 */
 /*
 Here are the rules taken from the official wiki:
@@ -52,7 +56,6 @@ If a layered image is attached to one attachment, then all attachments must be l
 use std::{ cmp, mem, fmt };
 use std::error::Error;
 use std::cell::RefCell;
-use std::marker::PhantomData;
 use std::hash::BuildHasherDefault;
 use std::collections::HashMap;
 
@@ -65,9 +68,12 @@ use GlObject;
 use TextureExt;
 
 use texture::CubeLayer;
+use texture::TextureAny;
+use texture::Dimensions;
 use texture::TextureAnyImage;
 use texture::TextureAnyMipmap;
 use texture::TextureKind;
+use texture::InternalFormatType;
 use framebuffer::RenderBufferAny;
 
 use gl;
@@ -95,6 +101,14 @@ pub enum FramebufferAttachments<'a> {
     /// Each attachment is a layer of images.
     Layered(FramebufferSpecificAttachments<LayeredAttachment<'a>>),
 
+    /// Each attachment is a range of layers of a texture array, and each primitive is drawn
+    /// once per layer (see the `GL_OVR_multiview` extension).
+    Multiview {
+        attachments: FramebufferSpecificAttachments<LayeredAttachment<'a>>,
+        base_view: u32,
+        num_views: u32,
+    },
+
     /// An empty framebuffer.
     Empty {
         width: u32,
@@ -127,7 +141,51 @@ impl<'a> RegularAttachment<'a> {
 
 /// Describes a single layered framebuffer attachment.
 #[derive(Copy, Clone)]
-pub struct LayeredAttachment<'a>(TextureAnyMipmap<'a>);
+pub struct LayeredAttachment<'a>(pub TextureAnyMipmap<'a>);
+
+impl<'a> LayeredAttachment<'a> {
+    /// Returns the number of layers of the attachment.
+    ///
+    /// A cubemap has six layers, and each cubemap of a cubemap array also has six layers.
+    fn get_layers(&self) -> u32 {
+        let texture = self.0.get_texture();
+
+        match texture.get_texture_type() {
+            Dimensions::Cubemap { .. } => 6,
+            Dimensions::CubemapArray { array_size, .. } => 6 * array_size,
+            Dimensions::Texture3d { .. } => self.0.get_depth().unwrap_or(1),
+            _ => self.0.get_array_size().unwrap_or(1),
+        }
+    }
+}
+
+/// Returns true if the backend supports layered attachments.
+///
+/// Layered attachments are attached with `glFramebufferTexture`, which is core in OpenGL 3.2 and
+/// OpenGL ES 3.2 and is otherwise provided by the geometry shader extensions.
+pub fn is_layered_supported<C: ?Sized>(context: &C) -> bool where C: CapabilitiesSource {
+    context.get_version() >= &Version(Api::Gl, 3, 2) ||
+    context.get_version() >= &Version(Api::GlEs, 3, 2) ||
+    context.get_extensions().gl_arb_geometry_shader4 ||
+    context.get_extensions().gl_ext_geometry_shader4 ||
+    context.get_extensions().gl_ext_geometry_shader ||
+    context.get_extensions().gl_oes_geometry_shader
+}
+
+/// Returns true if the backend supports rendering to multiple layers of a texture array at once
+/// with multiview.
+pub fn is_multiview_supported<C: ?Sized>(context: &C) -> bool where C: CapabilitiesSource {
+    context.get_extensions().gl_ovr_multiview
+}
+
+/// Returns true if the backend supports attaching mipmap levels other than the main level.
+fn is_mipmap_level_attachment_supported<C: ?Sized>(context: &C) -> bool
+                                                   where C: CapabilitiesSource
+{
+    context.get_version() >= &Version(Api::Gl, 1, 0) ||
+    context.get_version() >= &Version(Api::GlEs, 3, 0) ||
+    context.get_extensions().gl_oes_fbo_render_mipmap
+}
 
 /// Depth and/or stencil attachment to use.
 #[derive(Copy, Clone)]
@@ -168,7 +226,11 @@ impl<'a> FramebufferAttachments<'a> {
     {
         match self {
             FramebufferAttachments::Regular(a) => FramebufferAttachments::validate_regular(context, a),
-            FramebufferAttachments::Layered(a) => FramebufferAttachments::validate_layered(context, a),
+            FramebufferAttachments::Layered(a) =>
+                FramebufferAttachments::validate_layered(context, a, None),
+            FramebufferAttachments::Multiview { attachments, base_view, num_views } =>
+                FramebufferAttachments::validate_layered(context, attachments,
+                                                         Some((base_view, num_views))),
 
             FramebufferAttachments::Empty { width, height, layers, samples, fixed_samples } => {
                 if context.get_version() >= &Version(Api::Gl, 4, 3) ||
@@ -204,9 +266,11 @@ impl<'a> FramebufferAttachments<'a> {
                         },
                         dimensions: (width, height),
                         layers: layers,
+                        color_kinds: Vec::new(),
+                        read_buffer: 0,
                         depth_buffer_bits: None,
                         stencil_buffer_bits: None,
-                        marker: PhantomData,
+                        textures: Vec::new(),
                     })
 
                 } else {
@@ -216,12 +280,21 @@ impl<'a> FramebufferAttachments<'a> {
         }
     }
 
+    /// Validates layered attachments. If `views` is `Some`, it contains the first view and the
+    /// number of views, and the attachments are used for multiview rendering instead.
     fn validate_layered<C: ?Sized>(context: &C, FramebufferSpecificAttachments { colors, depth_stencil }:
-                           FramebufferSpecificAttachments<LayeredAttachment<'a>>)
+                           FramebufferSpecificAttachments<LayeredAttachment<'a>>,
+                           views: Option<(u32, u32)>)
                            -> Result<ValidatedAttachments<'a>, ValidationError>
                            where C: CapabilitiesSource
     {
-        // TODO: make sure that all attachments are layered
+        if views.is_some() {
+            if !is_multiview_supported(context) {
+                return Err(ValidationError::MultiviewNotSupported);
+            }
+        } else if !is_layered_supported(context) {
+            return Err(ValidationError::LayeredFramebuffersNotSupported);
+        }
 
         macro_rules! handle_tex {
             ($tex:ident, $dim:ident, $samples:ident, $num_bits:ident) => ({
@@ -234,6 +307,10 @@ impl<'a> FramebufferAttachments<'a> {
                 // TODO: check that internal format is renderable
                 let context = $tex.get_texture().get_context();
 
+                if $tex.get_level() != 0 && !is_mipmap_level_attachment_supported(context) {
+                    return Err(ValidationError::MipmapLevelNotSupported);
+                }
+
                 match &mut $samples {
                     &mut Some(samples) => {
                         if samples != $tex.get_samples().unwrap_or(0) {
@@ -264,12 +341,27 @@ impl<'a> FramebufferAttachments<'a> {
                     },
                 }
 
-                RawAttachment::Texture {
-                    texture: $tex.get_texture().get_id(),
-                    bind_point: $tex.get_texture().get_bind_point(),
-                    layer: None,
-                    level: $tex.get_level(),
-                    cubemap_layer: None,
+                if let Some((base_view, num_views)) = views {
+                    // multiview only works with regular 2D texture arrays
+                    if $tex.get_texture().get_bind_point() != gl::TEXTURE_2D_ARRAY {
+                        return Err(ValidationError::MultiviewNotSupported);
+                    }
+
+                    RawAttachment::MultiviewTexture {
+                        texture: $tex.get_texture().get_id(),
+                        level: $tex.get_level(),
+                        base_view: base_view,
+                        num_views: num_views,
+                    }
+
+                } else {
+                    RawAttachment::Texture {
+                        texture: $tex.get_texture().get_id(),
+                        bind_point: $tex.get_texture().get_bind_point(),
+                        layer: None,
+                        level: $tex.get_level(),
+                        cubemap_layer: None,
+                    }
                 }
             });
         }
@@ -299,6 +391,42 @@ impl<'a> FramebufferAttachments<'a> {
         let mut stencil_bits = None;
         let mut samples = None;     // contains `0` if not multisampling and `None` if unknown
 
+        // the number of layers of the framebuffer is the minimum of all the attachments
+        let mut layers = None;
+        {
+            let mut count_layers = |attachment: &LayeredAttachment| {
+                let l = attachment.get_layers();
+                layers = Some(layers.map_or(l, |layers| cmp::min(layers, l)));
+            };
+
+            for &(_, ref attachment) in colors.iter() {
+                count_layers(attachment);
+            }
+
+            match depth_stencil {
+                DepthStencilAttachments::None => (),
+                DepthStencilAttachments::DepthAttachment(ref a) |
+                DepthStencilAttachments::StencilAttachment(ref a) |
+                DepthStencilAttachments::DepthStencilAttachment(ref a) => count_layers(a),
+                DepthStencilAttachments::DepthAndStencilAttachments(ref d, ref s) => {
+                    count_layers(d);
+                    count_layers(s);
+                },
+            }
+        }
+
+        // when using multiview, the framebuffer has one layer per view
+        if let Some((base_view, num_views)) = views {
+            let max_views = context.get_capabilities().max_views.unwrap_or(0) as u32;
+            if num_views == 0 || num_views > max_views ||
+               base_view + num_views > layers.unwrap_or(0)
+            {
+                return Err(ValidationError::InvalidViews);
+            }
+
+            layers = Some(num_views);
+        }
+
         for &(index, LayeredAttachment(ref attachment)) in colors.iter() {
             if index >= max_color_attachments as u32 {
                 return Err(ValidationError::TooManyColorAttachments{
@@ -339,10 +467,14 @@ impl<'a> FramebufferAttachments<'a> {
         Ok(ValidatedAttachments {
             raw: raw_attachments,
             dimensions: dimensions,
-            layers: None,       // FIXME: count layers
+            layers: layers,
+            color_kinds: colors.iter().map(|&(index, ref a)| (index, a.0.get_texture().kind()))
+                               .collect(),
+            read_buffer: colors.first().map(|&(index, _)| index).unwrap_or(0),
             depth_buffer_bits: depth_bits,
             stencil_buffer_bits: stencil_bits,
-            marker: PhantomData,
+            textures: attachments_textures(&colors[..], &depth_stencil,
+                                           |a| Some(a.0.get_texture())),
         })
     }
 
@@ -362,6 +494,10 @@ impl<'a> FramebufferAttachments<'a> {
                 // TODO: check that internal format is renderable
                 let context = $tex.get_texture().get_context();
 
+                if $tex.get_level() != 0 && !is_mipmap_level_attachment_supported(context) {
+                    return Err(ValidationError::MipmapLevelNotSupported);
+                }
+
                 match &mut $samples {
                     &mut Some(samples) => {
                         if samples != $tex.get_samples().unwrap_or(0) {
@@ -404,7 +540,7 @@ impl<'a> FramebufferAttachments<'a> {
 
         macro_rules! handle_rb {
             ($rb:ident, $dim:ident, $samples:ident, $num_bits:ident) => ({
-                $num_bits = Some(24);       // FIXME: totally arbitrary
+                $num_bits = Some($rb.get_depth_or_stencil_bits().unwrap_or(24));
                 handle_rb!($rb, $dim, $samples)
             });
 
@@ -519,31 +655,176 @@ impl<'a> FramebufferAttachments<'a> {
             raw: raw_attachments,
             dimensions: dimensions,
             layers: None,
+            color_kinds: colors.iter().map(|&(index, ref a)| (index, a.kind())).collect(),
+            read_buffer: colors.first().map(|&(index, _)| index).unwrap_or(0),
             depth_buffer_bits: depth_bits,
             stencil_buffer_bits: stencil_bits,
-            marker: PhantomData,
+            textures: attachments_textures(&colors[..], &depth_stencil, |a| match a {
+                &RegularAttachment::Texture(ref tex) => Some(tex.get_texture()),
+                &RegularAttachment::RenderBuffer(_) => None,
+            }),
         })
     }
 }
 
+/// Returns the list of textures used by the attachments. `texture` returns the texture of an
+/// attachment, or `None` if it is not a texture.
+fn attachments_textures<'a, T, F>(colors: &[(u32, T)], depth_stencil: &DepthStencilAttachments<T>,
+                                  texture: F) -> Vec<&'a TextureAny>
+                                  where F: Fn(&T) -> Option<&'a TextureAny>
+{
+    let mut textures: Vec<&'a TextureAny> = colors.iter().filter_map(|&(_, ref a)| texture(a))
+                                                  .collect();
+
+    match *depth_stencil {
+        DepthStencilAttachments::None => (),
+        DepthStencilAttachments::DepthAttachment(ref a) |
+        DepthStencilAttachments::StencilAttachment(ref a) |
+        DepthStencilAttachments::DepthStencilAttachment(ref a) => textures.extend(texture(a)),
+        DepthStencilAttachments::DepthAndStencilAttachments(ref d, ref s) => {
+            textures.extend(texture(d));
+            textures.extend(texture(s));
+        },
+    }
+
+    textures
+}
+
 /// Represents attachments that have been validated and are usable.
 #[derive(Clone)]
 pub struct ValidatedAttachments<'a> {
     raw: RawAttachments,
     dimensions: (u32, u32),
     layers: Option<u32>,
+    color_kinds: Vec<(u32, TextureKind)>,
+    read_buffer: u32,
     depth_buffer_bits: Option<u16>,
     stencil_buffer_bits: Option<u16>,
-    marker: PhantomData<&'a ()>,
+    // textures of the attachments, used to wait for shader writes before using them
+    textures: Vec<&'a TextureAny>,
 }
 
 impl<'a> ValidatedAttachments<'a> {
+    /// Returns the same attachments, except that layered attachments are replaced with their
+    /// first layer and multiview attachments with their first view.
+    ///
+    /// Reading from a layered framebuffer only ever reads its first layer. Using these
+    /// attachments instead attaches this layer explicitly with `glFramebufferTextureLayer`.
+    pub fn first_layer(&self) -> ValidatedAttachments<'a> {
+        fn first_layer(attachment: &RawAttachment) -> RawAttachment {
+            match *attachment {
+                RawAttachment::Texture { bind_point, texture, layer: None, level, .. } => {
+                    let cubemap_layer = match bind_point {
+                        gl::TEXTURE_CUBE_MAP | gl::TEXTURE_CUBE_MAP_ARRAY => {
+                            Some(CubeLayer::PositiveX)
+                        },
+                        _ => None,
+                    };
+
+                    RawAttachment::Texture {
+                        bind_point: bind_point,
+                        texture: texture,
+                        layer: Some(0),
+                        level: level,
+                        cubemap_layer: cubemap_layer,
+                    }
+                },
+
+                RawAttachment::MultiviewTexture { texture, level, base_view, .. } => {
+                    RawAttachment::Texture {
+                        bind_point: gl::TEXTURE_2D_ARRAY,
+                        texture: texture,
+                        layer: Some(base_view),
+                        level: level,
+                        cubemap_layer: None,
+                    }
+                },
+
+                a => a,
+            }
+        }
+
+        ValidatedAttachments {
+            raw: RawAttachments {
+                color: self.raw.color.iter().map(|&(i, ref a)| (i, first_layer(a))).collect(),
+                depth: self.raw.depth.as_ref().map(first_layer),
+                stencil: self.raw.stencil.as_ref().map(first_layer),
+                depth_stencil: self.raw.depth_stencil.as_ref().map(first_layer),
+                default_width: None,
+                default_height: None,
+                default_layers: None,
+                default_samples: None,
+                default_samples_fixed: None,
+            },
+            dimensions: self.dimensions,
+            layers: None,
+            color_kinds: self.color_kinds.clone(),
+            read_buffer: self.read_buffer,
+            depth_buffer_bits: self.depth_buffer_bits,
+            stencil_buffer_bits: self.stencil_buffer_bits,
+            textures: self.textures.clone(),
+        }
+    }
+
     /// Returns `true` if the framebuffer is layered.
     #[inline]
     pub fn is_layered(&self) -> bool {
         self.layers.is_some()
     }
 
+    /// Returns the number of layers of the framebuffer, or `None` if it is not layered.
+    #[inline]
+    pub fn get_layers(&self) -> Option<u32> {
+        self.layers
+    }
+
+    /// Returns the number of color attachments.
+    #[inline]
+    pub fn get_color_attachments_count(&self) -> usize {
+        self.raw.color.len()
+    }
+
+    /// Returns the kind of the color attachment bound to the given fragment output, or `None`
+    /// if there is no such attachment.
+    #[inline]
+    pub fn get_color_attachment_kind(&self, index: u32) -> Option<TextureKind> {
+        self.color_kinds.iter().find(|&&(i, _)| i == index).map(|&(_, kind)| kind)
+    }
+
+    /// Returns the fragment output whose color attachment is the source of reads and blits.
+    ///
+    /// This is the first color attachment unless `set_read_buffer` has been called.
+    #[inline]
+    pub fn get_read_buffer(&self) -> u32 {
+        self.read_buffer
+    }
+
+    /// Returns the value to pass to `glReadBuffer`, which is `GL_NONE` if there is no color
+    /// attachment.
+    ///
+    /// The color attachments are attached in order regardless of the fragment output they are
+    /// bound to, so the enum is determined by the position of the attachment and not by its
+    /// location.
+    #[inline]
+    pub fn get_read_buffer_enum(&self) -> gl::types::GLenum {
+        match self.raw.color.iter().position(|&(location, _)| location == self.read_buffer) {
+            Some(position) => gl::COLOR_ATTACHMENT0 + position as u32,
+            None => gl::NONE,
+        }
+    }
+
+    /// Sets the fragment output whose color attachment is the source of reads and blits.
+    ///
+    /// # Panic
+    ///
+    /// Panics if there is no color attachment bound to this fragment output.
+    #[inline]
+    pub fn set_read_buffer(&mut self, index: u32) {
+        assert!(self.get_color_attachment_kind(index).is_some(),
+                "There is no color attachment bound to the fragment output {}", index);
+        self.read_buffer = index;
+    }
+
     /// Returns the dimensions that the framebuffer will have if you use these attachments.
     #[inline]
     pub fn get_dimensions(&self) -> (u32, u32) {
@@ -590,6 +871,138 @@ pub enum ValidationError {
         /// Number of attachments that were given.
         obtained: usize,
     },
+
+    /// Layered attachments are not supported by the backend.
+    LayeredFramebuffersNotSupported,
+
+    /// Attaching a mipmap level other than the main level is not supported by the backend.
+    MipmapLevelNotSupported,
+
+    /// Multiview rendering is not supported by the backend, or one of the attachments is not
+    /// a 2D texture array.
+    MultiviewNotSupported,
+
+    /// The requested views are out of the range of the layers of the attachments, or there are
+    /// more views than the backend supports.
+    InvalidViews,
+
+    /// The backend reported the framebuffer object as incomplete.
+    IncompleteFramebuffer {
+        /// Why the framebuffer is incomplete.
+        reason: IncompleteFramebufferReason,
+        /// The formats of the attachments, as reported by the backend.
+        attachments: AttachmentsFormatInfos,
+    },
+}
+
+/// Reason why the backend considers a framebuffer object incomplete.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum IncompleteFramebufferReason {
+    /// One of the attachments is incomplete, for example because its format is not renderable
+    /// or because one of its dimensions is zero.
+    IncompleteAttachment,
+
+    /// The framebuffer doesn't have any attachment.
+    MissingAttachment,
+
+    /// A draw buffer designates an attachment point that has no attachment.
+    IncompleteDrawBuffer,
+
+    /// The read buffer designates an attachment point that has no attachment.
+    IncompleteReadBuffer,
+
+    /// The combination of internal formats of the attachments is not supported by the
+    /// implementation. This happens for example with separate depth and stencil attachments.
+    UnsupportedCombination,
+
+    /// The attachments don't have the same number of samples, or don't all use fixed
+    /// sample locations.
+    MultisampleMismatch,
+
+    /// Some attachments are layered and others are not, or the layered attachments don't have
+    /// compatible types.
+    LayerTargetsMismatch,
+
+    /// The attachments don't have the same dimensions, and the backend requires it.
+    DimensionsMismatch,
+
+    /// The backend returned a status that glium doesn't know about.
+    Unknown(u32),
+}
+
+impl IncompleteFramebufferReason {
+    fn from_glenum(status: gl::types::GLenum) -> IncompleteFramebufferReason {
+        use self::IncompleteFramebufferReason::*;
+        match status {
+            gl::FRAMEBUFFER_INCOMPLETE_ATTACHMENT => IncompleteAttachment,
+            gl::FRAMEBUFFER_INCOMPLETE_MISSING_ATTACHMENT => MissingAttachment,
+            gl::FRAMEBUFFER_INCOMPLETE_DRAW_BUFFER => IncompleteDrawBuffer,
+            gl::FRAMEBUFFER_INCOMPLETE_READ_BUFFER => IncompleteReadBuffer,
+            gl::FRAMEBUFFER_UNSUPPORTED => UnsupportedCombination,
+            gl::FRAMEBUFFER_INCOMPLETE_MULTISAMPLE => MultisampleMismatch,
+            gl::FRAMEBUFFER_INCOMPLETE_LAYER_TARGETS => LayerTargetsMismatch,
+            gl::FRAMEBUFFER_INCOMPLETE_DIMENSIONS => DimensionsMismatch,
+            s => Unknown(s),
+        }
+    }
+}
+
+/// Point of a framebuffer object where an attachment is bound.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum AttachmentPoint {
+    /// A color attachment. The value is the index of the attachment.
+    Color(u32),
+    /// The depth attachment.
+    Depth,
+    /// The stencil attachment.
+    Stencil,
+    /// The depth-stencil attachment.
+    DepthStencil,
+}
+
+/// Format of a framebuffer attachment, as reported by the backend.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct AttachmentFormatInfos {
+    /// Where the attachment is bound.
+    pub point: AttachmentPoint,
+
+    /// Type of the components of the attachment, or `None` for stencil attachments.
+    pub component_type: Option<InternalFormatType>,
+
+    /// Number of bits of the red component.
+    pub red_bits: u32,
+    /// Number of bits of the green component.
+    pub green_bits: u32,
+    /// Number of bits of the blue component.
+    pub blue_bits: u32,
+    /// Number of bits of the alpha component.
+    pub alpha_bits: u32,
+    /// Number of bits of the depth component.
+    pub depth_bits: u32,
+    /// Number of bits of the stencil component.
+    pub stencil_bits: u32,
+
+    /// True if the attachment stores sRGB colors.
+    pub srgb: bool,
+}
+
+/// Formats of the attachments of a framebuffer object, as reported by the backend.
+///
+/// All the fields are `None` if the backend can't report them (OpenGL 3.0 or OpenGL ES 3.0 is
+/// required).
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct AttachmentsFormatInfos {
+    /// The color attachments, in the order in which they are attached.
+    ///
+    /// Only the first eight attachments are reported, so that `ValidationError` doesn't have to
+    /// allocate.
+    pub colors: [Option<AttachmentFormatInfos>; 8],
+    /// The depth attachment.
+    pub depth: Option<AttachmentFormatInfos>,
+    /// The stencil attachment.
+    pub stencil: Option<AttachmentFormatInfos>,
+    /// The depth-stencil attachment.
+    pub depth_stencil: Option<AttachmentFormatInfos>,
 }
 
 impl fmt::Display for ValidationError {
@@ -598,6 +1011,8 @@ impl fmt::Display for ValidationError {
         match *self {
             TooManyColorAttachments{ ref maximum, ref obtained } =>
                 write!(fmt, "{}: found {}, maximum: {}", self.description(), obtained, maximum),
+            IncompleteFramebuffer { ref reason, .. } =>
+                write!(fmt, "{}: {:?}", self.description(), reason),
             _ =>
                 write!(fmt, "{}", self.description()),
         }
@@ -618,6 +1033,16 @@ impl Error for ValidationError {
                 "All attachments must have the same number of samples",
             TooManyColorAttachments {..} =>
                 "Backends only support a certain number of color attachments",
+            LayeredFramebuffersNotSupported =>
+                "Layered attachments are not supported by the backend",
+            MipmapLevelNotSupported =>
+                "Attaching a mipmap level other than the main level is not supported by the backend",
+            MultiviewNotSupported =>
+                "Multiview rendering is not supported by the backend for these attachments",
+            InvalidViews =>
+                "The requested views are out of range",
+            IncompleteFramebuffer { .. } =>
+                "The backend reported the framebuffer object as incomplete",
         }
     }
 }
@@ -659,6 +1084,18 @@ enum RawAttachment {
         cubemap_layer: Option<CubeLayer>,
     },
 
+    /// A range of layers of a 2D texture array used for multiview rendering.
+    MultiviewTexture {
+        // id of the texture
+        texture: gl::types::GLuint,
+        // mipmap level
+        level: u32,
+        // first layer to render to
+        base_view: u32,
+        // number of layers to render to
+        num_views: u32,
+    },
+
     /// A renderbuffer with its ID.
     RenderBuffer(gl::types::GLuint),
 }
@@ -727,12 +1164,41 @@ impl FramebuffersContainer {
         }
     }
 
+    /// Makes sure that the framebuffer object corresponding to the attachments exists, and
+    /// that the backend considers it complete.
+    ///
+    /// The status is only queried when the framebuffer object is created, which makes calling
+    /// this function cheap for attachments that have already been used. Incomplete framebuffer
+    /// objects are destroyed immediately.
+    pub fn check_completeness(ctxt: &mut CommandContext, attachments: &ValidatedAttachments)
+                              -> Result<(), ValidationError>
+    {
+        if ctxt.framebuffer_objects.framebuffers.borrow().contains_key(&attachments.raw) {
+            return Ok(());
+        }
+
+        let new_fbo = FrameBufferObject::new(ctxt, &attachments.raw, attachments.dimensions);
+
+        match unsafe { new_fbo.check_status(ctxt, &attachments.raw) } {
+            Ok(()) => {
+                ctxt.framebuffer_objects.framebuffers.borrow_mut()
+                                                     .insert(attachments.raw.clone(), new_fbo);
+                Ok(())
+            },
+            Err(err) => {
+                new_fbo.destroy(ctxt);
+                Err(err)
+            },
+        }
+    }
+
     /// Destroys all framebuffer objects that contain a precise texture.
     #[inline]
     pub fn purge_texture(ctxt: &mut CommandContext, texture: gl::types::GLuint) {
         FramebuffersContainer::purge_if(ctxt, |a| {
             match a {
                 &RawAttachment::Texture { texture: id, .. } if id == texture => true,
+                &RawAttachment::MultiviewTexture { texture: id, .. } if id == texture => true,
                 _ => false
             }
         });
@@ -833,23 +1299,50 @@ impl FramebuffersContainer {
     /// After calling this function, you **must** make sure to call `purge_texture`
     /// and/or `purge_renderbuffer` when one of the attachment is destroyed.
     pub unsafe fn bind_framebuffer_for_reading(ctxt: &mut CommandContext, attachment: &RegularAttachment) {
-        // TODO: restore this optimisation
-        /*for (attachments, fbo) in ctxt.framebuffer_objects.framebuffers.borrow_mut().iter() {
-            for &(key, ref atc) in attachments.color.iter() {
-                if atc == attachment {
-                    return (fbo.get_id(), gl::COLOR_ATTACHMENT0 + key);
-                }
-            }
-        }*/
-
         let attachments = FramebufferAttachments::Regular(FramebufferSpecificAttachments {
             colors: { let mut v = SmallVec::new(); v.push((0, attachment.clone())); v },
             depth_stencil: DepthStencilAttachments::None,
         }).validate(ctxt).unwrap();
 
-        let framebuffer = FramebuffersContainer::get_framebuffer_for_drawing(ctxt, Some(&attachments));
+        // if the attachment is already part of a framebuffer object, for example because it has
+        // been drawn to, we reuse it instead of creating a new one
+        // the other attachments of the framebuffer object must not be smaller, otherwise
+        // some pixels wouldn't be readable
+        let existing = {
+            let raw = &attachments.raw.color[0].1;
+            let framebuffers = ctxt.framebuffer_objects.framebuffers.borrow();
+            framebuffers.iter().filter(|&(_, fbo)| fbo.dimensions == attachments.dimensions)
+                        .filter_map(|(key, fbo)| {
+                            key.color.iter().position(|&(_, ref a)| a == raw)
+                                     .map(|pos| (fbo.id, gl::COLOR_ATTACHMENT0 + pos as u32))
+                        }).next()
+        };
+
+        let (framebuffer, read_buffer) = match existing {
+            Some(existing) => existing,
+            None => {
+                let framebuffer = FramebuffersContainer::get_framebuffer(ctxt, &attachments);
+                (framebuffer, gl::COLOR_ATTACHMENT0)
+            },
+        };
+
         bind_framebuffer(ctxt, framebuffer, false, true);
-        ctxt.gl.ReadBuffer(gl::COLOR_ATTACHMENT0);     // TODO: cache
+        ctxt.gl.ReadBuffer(read_buffer);     // TODO: cache
+    }
+
+    /// Binds a framebuffer to `GL_READ_FRAMEBUFFER` or `GL_FRAMEBUFFER` and selects its read
+    /// buffer as the source of `glReadPixels`.
+    ///
+    /// # Unsafety
+    ///
+    /// After calling this function, you **must** make sure to call `purge_texture`
+    /// and/or `purge_renderbuffer` when one of the attachment is destroyed.
+    pub unsafe fn bind_validated_framebuffer_for_reading(ctxt: &mut CommandContext,
+                                                         attachments: &ValidatedAttachments)
+    {
+        let framebuffer = FramebuffersContainer::get_framebuffer(ctxt, attachments);
+        bind_framebuffer(ctxt, framebuffer, false, true);
+        ctxt.gl.ReadBuffer(attachments.get_read_buffer_enum());     // TODO: cache
     }
 
     /// Calls `glClearBuffer` on a framebuffer that contains the attachment.
@@ -914,13 +1407,18 @@ impl FramebuffersContainer {
     fn get_framebuffer(ctxt: &mut CommandContext, attachments: &ValidatedAttachments)
                        -> gl::types::GLuint
     {
+        // shader writes to the attachments must be visible before the framebuffer is used
+        for texture in attachments.textures.iter() {
+            texture.prepare_for_framebuffer(ctxt);
+        }
+
         // TODO: use entries API
         let mut framebuffers = ctxt.framebuffer_objects.framebuffers.borrow_mut();
         if let Some(value) = framebuffers.get(&attachments.raw) {
             return value.id;
         }
 
-        let new_fbo = FrameBufferObject::new(ctxt, &attachments.raw);
+        let new_fbo = FrameBufferObject::new(ctxt, &attachments.raw, attachments.dimensions);
         let new_fbo_id = new_fbo.id.clone();
         framebuffers.insert(attachments.raw.clone(), new_fbo);
         new_fbo_id
@@ -940,6 +1438,8 @@ impl Drop for FramebuffersContainer {
 struct FrameBufferObject {
     id: gl::types::GLuint,
     current_read_buffer: gl::types::GLenum,
+    // dimensions of the intersection of all the attachments
+    dimensions: (u32, u32),
 }
 
 impl FrameBufferObject {
@@ -949,7 +1449,9 @@ impl FrameBufferObject {
     ///
     /// Panicks if anything wrong or not supported is detected with the raw attachments.
     ///
-    fn new(mut ctxt: &mut CommandContext, attachments: &RawAttachments) -> FrameBufferObject {
+    fn new(mut ctxt: &mut CommandContext, attachments: &RawAttachments, dimensions: (u32, u32))
+           -> FrameBufferObject
+    {
         if attachments.color.len() > ctxt.capabilities.max_draw_buffers as usize {
             panic!("Trying to attach {} color buffers, but the hardware only supports {}",
                    attachments.color.len(), ctxt.capabilities.max_draw_buffers);
@@ -1073,8 +1575,36 @@ impl FrameBufferObject {
             unsafe { attach(&mut ctxt, gl::DEPTH_STENCIL_ATTACHMENT, id, depth_stencil) };
         }
 
+        // without any color attachment, the draw and read buffers must be set to `GL_NONE` or
+        // the framebuffer is incomplete on older implementations
+        if raw_attachments.is_empty() {
+            if ctxt.version >= &Version(Api::Gl, 4, 5) ||
+               ctxt.extensions.gl_arb_direct_state_access
+            {
+                unsafe {
+                    ctxt.gl.NamedFramebufferDrawBuffer(id, gl::NONE);
+                    ctxt.gl.NamedFramebufferReadBuffer(id, gl::NONE);
+                }
+
+            } else if ctxt.version >= &Version(Api::Gl, 1, 0) {
+                unsafe {
+                    bind_framebuffer(&mut ctxt, id, true, true);
+                    ctxt.gl.DrawBuffer(gl::NONE);
+                    ctxt.gl.ReadBuffer(gl::NONE);
+                }
+
+            } else if ctxt.version >= &Version(Api::GlEs, 3, 0) {
+                unsafe {
+                    bind_framebuffer(&mut ctxt, id, true, true);
+                    ctxt.gl.DrawBuffers(1, [gl::NONE].as_ptr());
+                    ctxt.gl.ReadBuffer(gl::NONE);
+                }
+            }
+
+            // OpenGL ES 2 doesn't have draw and read buffers
+
         // calling `glDrawBuffers` if necessary
-        if raw_attachments != &[gl::COLOR_ATTACHMENT0] {
+        } else if raw_attachments != &[gl::COLOR_ATTACHMENT0] {
             if ctxt.version >= &Version(Api::Gl, 4, 5) ||
                ctxt.extensions.gl_arb_direct_state_access
             {
@@ -1117,9 +1647,67 @@ impl FrameBufferObject {
         FrameBufferObject {
             id: id,
             current_read_buffer: gl::BACK,
+            dimensions: dimensions,
         }
     }
 
+    /// Calls `glCheckFramebufferStatus` and, if the FBO is incomplete, gathers the formats of
+    /// its attachments.
+    unsafe fn check_status(&self, mut ctxt: &mut CommandContext, attachments: &RawAttachments)
+                           -> Result<(), ValidationError>
+    {
+        let status = if ctxt.version >= &Version(Api::Gl, 4, 5) ||
+                        ctxt.extensions.gl_arb_direct_state_access
+        {
+            ctxt.gl.CheckNamedFramebufferStatus(self.id, gl::FRAMEBUFFER)
+
+        } else if ctxt.version >= &Version(Api::Gl, 3, 0) ||
+                  ctxt.version >= &Version(Api::GlEs, 2, 0) ||
+                  ctxt.extensions.gl_arb_framebuffer_object
+        {
+            bind_framebuffer(&mut ctxt, self.id, true, true);
+            ctxt.gl.CheckFramebufferStatus(gl::FRAMEBUFFER)
+
+        } else if ctxt.extensions.gl_ext_framebuffer_object {
+            bind_framebuffer(&mut ctxt, self.id, true, true);
+            ctxt.gl.CheckFramebufferStatusEXT(gl::FRAMEBUFFER_EXT)
+
+        } else {
+            unreachable!();
+        };
+
+        if status == gl::FRAMEBUFFER_COMPLETE {
+            return Ok(());
+        }
+
+        // the sizes and types of attachments can only be queried with OpenGL 3 and OpenGL ES 3
+        let mut infos = AttachmentsFormatInfos::default();
+        if ctxt.version >= &Version(Api::Gl, 3, 0) || ctxt.version >= &Version(Api::GlEs, 3, 0) {
+            bind_framebuffer(&mut ctxt, self.id, true, true);
+
+            let num_colors = cmp::min(attachments.color.len(), infos.colors.len());
+            for pos in 0 .. num_colors {
+                let point = AttachmentPoint::Color(pos as u32);
+                infos.colors[pos] = Some(get_attachment_format(ctxt, point));
+            }
+            if attachments.depth.is_some() {
+                infos.depth = Some(get_attachment_format(ctxt, AttachmentPoint::Depth));
+            }
+            if attachments.stencil.is_some() {
+                infos.stencil = Some(get_attachment_format(ctxt, AttachmentPoint::Stencil));
+            }
+            if attachments.depth_stencil.is_some() {
+                let point = AttachmentPoint::DepthStencil;
+                infos.depth_stencil = Some(get_attachment_format(ctxt, point));
+            }
+        }
+
+        Err(ValidationError::IncompleteFramebuffer {
+            reason: IncompleteFramebufferReason::from_glenum(status),
+            attachments: infos,
+        })
+    }
+
     /// Destroys the FBO. Must be called, or things will leak.
     fn destroy(self, mut ctxt: &mut CommandContext) {
         // unbinding framebuffer
@@ -1154,6 +1742,64 @@ impl GlObject for FrameBufferObject {
     }
 }
 
+/// Queries the format of an attachment of the framebuffer currently bound to `GL_FRAMEBUFFER`.
+unsafe fn get_attachment_format(ctxt: &mut CommandContext, point: AttachmentPoint)
+                                -> AttachmentFormatInfos
+{
+    unsafe fn query(ctxt: &mut CommandContext, attachment: gl::types::GLenum,
+                    pname: gl::types::GLenum) -> gl::types::GLint
+    {
+        let mut value = 0;
+        ctxt.gl.GetFramebufferAttachmentParameteriv(gl::FRAMEBUFFER, attachment, pname,
+                                                    &mut value);
+        value
+    }
+
+    // querying the component type of a depth-stencil attachment is an error, so we query the
+    // depth and stencil aspects separately
+    let (main, stencil) = match point {
+        AttachmentPoint::Color(pos) => (gl::COLOR_ATTACHMENT0 + pos, None),
+        AttachmentPoint::Depth => (gl::DEPTH_ATTACHMENT, None),
+        AttachmentPoint::Stencil => (gl::STENCIL_ATTACHMENT, None),
+        AttachmentPoint::DepthStencil => (gl::DEPTH_ATTACHMENT, Some(gl::STENCIL_ATTACHMENT)),
+    };
+
+    let component_type = match point {
+        AttachmentPoint::Stencil => gl::NONE,
+        _ => query(ctxt, main, gl::FRAMEBUFFER_ATTACHMENT_COMPONENT_TYPE) as gl::types::GLenum,
+    };
+
+    let component_type = match component_type {
+        gl::SIGNED_NORMALIZED => Some(InternalFormatType::SignedNormalized),
+        gl::UNSIGNED_NORMALIZED => Some(InternalFormatType::UnsignedNormalized),
+        gl::FLOAT => Some(InternalFormatType::Float),
+        gl::INT => Some(InternalFormatType::Int),
+        gl::UNSIGNED_INT => Some(InternalFormatType::UnsignedInt),
+        _ => None,
+    };
+
+    let srgb = match point {
+        AttachmentPoint::Color(_) => {
+            query(ctxt, main, gl::FRAMEBUFFER_ATTACHMENT_COLOR_ENCODING) as gl::types::GLenum
+                                                                                    == gl::SRGB
+        },
+        _ => false,
+    };
+
+    AttachmentFormatInfos {
+        point: point,
+        component_type: component_type,
+        red_bits: query(ctxt, main, gl::FRAMEBUFFER_ATTACHMENT_RED_SIZE) as u32,
+        green_bits: query(ctxt, main, gl::FRAMEBUFFER_ATTACHMENT_GREEN_SIZE) as u32,
+        blue_bits: query(ctxt, main, gl::FRAMEBUFFER_ATTACHMENT_BLUE_SIZE) as u32,
+        alpha_bits: query(ctxt, main, gl::FRAMEBUFFER_ATTACHMENT_ALPHA_SIZE) as u32,
+        depth_bits: query(ctxt, main, gl::FRAMEBUFFER_ATTACHMENT_DEPTH_SIZE) as u32,
+        stencil_bits: query(ctxt, stencil.unwrap_or(main),
+                            gl::FRAMEBUFFER_ATTACHMENT_STENCIL_SIZE) as u32,
+        srgb: srgb,
+    }
+}
+
 /// Binds a framebuffer object, either for drawing, reading, or both.
 ///
 /// # Safety
@@ -1406,11 +2052,30 @@ unsafe fn attach(ctxt: &mut CommandContext, slot: gl::types::GLenum,
                         ctxt.gl.NamedFramebufferTextureEXT(id, slot, tex_id,
                                                            level as gl::types::GLint);
 
-                    } else if ctxt.version >= &Version(Api::Gl, 3, 2) {
+                    } else if ctxt.version >= &Version(Api::Gl, 3, 2) ||
+                              ctxt.version >= &Version(Api::GlEs, 3, 2)
+                    {
                         bind_framebuffer(ctxt, id, true, false);
                         ctxt.gl.FramebufferTexture(gl::DRAW_FRAMEBUFFER,
                                                    slot, tex_id, level as gl::types::GLint);
 
+                    } else if ctxt.extensions.gl_arb_geometry_shader4 {
+                        bind_framebuffer(ctxt, id, true, true);
+                        ctxt.gl.FramebufferTextureARB(gl::FRAMEBUFFER, slot, tex_id,
+                                                      level as gl::types::GLint);
+
+                    } else if ctxt.extensions.gl_ext_geometry_shader4 ||
+                              ctxt.extensions.gl_ext_geometry_shader
+                    {
+                        bind_framebuffer(ctxt, id, true, true);
+                        ctxt.gl.FramebufferTextureEXT(gl::FRAMEBUFFER, slot, tex_id,
+                                                      level as gl::types::GLint);
+
+                    } else if ctxt.extensions.gl_oes_geometry_shader {
+                        bind_framebuffer(ctxt, id, true, true);
+                        ctxt.gl.FramebufferTextureOES(gl::FRAMEBUFFER, slot, tex_id,
+                                                      level as gl::types::GLint);
+
                     } else {
                         // note that this should have been detected earlier
                         panic!("Layered framebuffers are not supported");
@@ -1461,11 +2126,30 @@ unsafe fn attach(ctxt: &mut CommandContext, slot: gl::types::GLenum,
                         ctxt.gl.NamedFramebufferTextureEXT(id, slot, tex_id,
                                                            level as gl::types::GLint);
 
-                    } else if ctxt.version >= &Version(Api::Gl, 3, 2) {
+                    } else if ctxt.version >= &Version(Api::Gl, 3, 2) ||
+                              ctxt.version >= &Version(Api::GlEs, 3, 2)
+                    {
                         bind_framebuffer(ctxt, id, true, false);
                         ctxt.gl.FramebufferTexture(gl::DRAW_FRAMEBUFFER,
                                                    slot, tex_id, level as gl::types::GLint);
 
+                    } else if ctxt.extensions.gl_arb_geometry_shader4 {
+                        bind_framebuffer(ctxt, id, true, true);
+                        ctxt.gl.FramebufferTextureARB(gl::FRAMEBUFFER, slot, tex_id,
+                                                      level as gl::types::GLint);
+
+                    } else if ctxt.extensions.gl_ext_geometry_shader4 ||
+                              ctxt.extensions.gl_ext_geometry_shader
+                    {
+                        bind_framebuffer(ctxt, id, true, true);
+                        ctxt.gl.FramebufferTextureEXT(gl::FRAMEBUFFER, slot, tex_id,
+                                                      level as gl::types::GLint);
+
+                    } else if ctxt.extensions.gl_oes_geometry_shader {
+                        bind_framebuffer(ctxt, id, true, true);
+                        ctxt.gl.FramebufferTextureOES(gl::FRAMEBUFFER, slot, tex_id,
+                                                      level as gl::types::GLint);
+
                     } else {
                         // note that this should have been detected earlier
                         panic!("Layered framebuffers are not supported");
@@ -1476,6 +2160,21 @@ unsafe fn attach(ctxt: &mut CommandContext, slot: gl::types::GLenum,
             }
         },
 
+        // multiview attachments can only be created with the extension's function
+        RawAttachment::MultiviewTexture { texture, level, base_view, num_views } => {
+            if ctxt.extensions.gl_ovr_multiview {
+                bind_framebuffer(ctxt, id, true, false);
+                ctxt.gl.FramebufferTextureMultiviewOVR(gl::DRAW_FRAMEBUFFER, slot, texture,
+                                                       level as gl::types::GLint,
+                                                       base_view as gl::types::GLint,
+                                                       num_views as gl::types::GLsizei);
+
+            } else {
+                // note that this should have been detected earlier
+                panic!("Multiview framebuffers are not supported");
+            }
+        },
+
         // renderbuffers are straight-forward
         RawAttachment::RenderBuffer(renderbuffer) => {
             if ctxt.version >= &Version(Api::Gl, 4, 5) ||
@@ -0,0 +1,149 @@
+use texture::{PixelValue, Texture2dDataSink};
+
+use draw_parameters::ConditionalRendering;
+use draw_parameters::SamplesQueryParam;
+
+use BlitMask;
+use BlitTarget;
+use DrawError;
+use QueryExt;
+use Rect;
+use Surface;
+use uniforms;
+
+use super::{ClearBufferData, ClearBufferError, MultiOutputFrameBuffer, ReadError};
+use super::SimpleFrameBuffer;
+
+/// A surface whose draw commands are only executed if a samples query has passed.
+///
+/// This is obtained by calling `Surface::conditional`. Each draw command on this surface that
+/// doesn't have its own `condition` uses the condition of the surface. Since all these draw
+/// commands share the same condition, conditional rendering is only started once for the whole
+/// group and ended when the closure returns.
+///
+/// Clears, invalidations and blits are not affected by the condition.
+pub struct ConditionalSurface<'s, 'q, S: ?Sized + 's> {
+    surface: &'s mut S,
+    condition: ConditionalRendering<'q>,
+}
+
+impl<'s, 'q, S: ?Sized + 's> ConditionalSurface<'s, 'q, S> where S: Surface {
+    /// Builds a new `ConditionalSurface` that wraps around `surface`.
+    #[inline]
+    pub fn new(surface: &'s mut S, condition: ConditionalRendering<'q>)
+               -> ConditionalSurface<'s, 'q, S>
+    {
+        ConditionalSurface {
+            surface: surface,
+            condition: condition,
+        }
+    }
+
+    /// Returns the condition used by the draw commands on this surface.
+    #[inline]
+    pub fn get_condition(&self) -> ConditionalRendering<'q> {
+        self.condition
+    }
+}
+
+impl<'s, 'q, S: ?Sized + 's> Drop for ConditionalSurface<'s, 'q, S> {
+    fn drop(&mut self) {
+        match self.condition.query {
+            SamplesQueryParam::SamplesPassedQuery(q) => q.end_own_conditional_render(),
+            SamplesQueryParam::AnySamplesPassedQuery(q) => q.end_own_conditional_render(),
+        }
+    }
+}
+
+impl<'s, 'q, S: ?Sized + 's> Surface for ConditionalSurface<'s, 'q, S> where S: Surface {
+    #[inline]
+    fn clear(&mut self, rect: Option<&Rect>, color: Option<(f32, f32, f32, f32)>, color_srgb: bool,
+             depth: Option<f32>, stencil: Option<i32>)
+    {
+        self.surface.clear(rect, color, color_srgb, depth, stencil)
+    }
+
+    #[inline]
+    fn clear_buffer(&mut self, rect: Option<&Rect>, index: u32, data: ClearBufferData)
+                    -> Result<(), ClearBufferError>
+    {
+        self.surface.clear_buffer(rect, index, data)
+    }
+
+    #[inline]
+    fn invalidate(&mut self, colors: Option<&[u32]>, depth: bool, stencil: bool) {
+        self.surface.invalidate(colors, depth, stencil)
+    }
+
+    #[inline]
+    fn read_pixels<P, T>(&self, rect: &Rect) -> Result<T, ReadError>
+                         where P: PixelValue, T: Texture2dDataSink<P>
+    {
+        self.surface.read_pixels(rect)
+    }
+
+    #[inline]
+    fn get_dimensions(&self) -> (u32, u32) {
+        self.surface.get_dimensions()
+    }
+
+    #[inline]
+    fn get_depth_buffer_bits(&self) -> Option<u16> {
+        self.surface.get_depth_buffer_bits()
+    }
+
+    #[inline]
+    fn get_stencil_buffer_bits(&self) -> Option<u16> {
+        self.surface.get_stencil_buffer_bits()
+    }
+
+    fn draw<'b, 'v, V, I, U>(&mut self, vb: V, ib: I, program: &::Program,
+        uniforms: &U, draw_parameters: &::DrawParameters) -> Result<(), DrawError>
+        where I: Into<::index::IndicesSource<'b>>, U: ::uniforms::Uniforms,
+        V: ::vertex::MultiVerticesSource<'v>
+    {
+        if draw_parameters.condition.is_some() {
+            return self.surface.draw(vb, ib, program, uniforms, draw_parameters);
+        }
+
+        let draw_parameters = ::DrawParameters {
+            condition: Some(self.condition),
+            .. draw_parameters.clone()
+        };
+
+        self.surface.draw(vb, ib, program, uniforms, &draw_parameters)
+    }
+
+    #[inline]
+    fn blit_buffers<T>(&self, source_rect: &Rect, target: &T, target_rect: &BlitTarget,
+                       filter: uniforms::MagnifySamplerFilter, mask: BlitMask) where T: Surface
+    {
+        self.surface.blit_buffers(source_rect, target, target_rect, filter, mask)
+    }
+
+    #[inline]
+    fn blit_buffers_from_frame(&self, source_rect: &Rect, target_rect: &BlitTarget,
+                               filter: uniforms::MagnifySamplerFilter, mask: BlitMask)
+    {
+        self.surface.blit_buffers_from_frame(source_rect, target_rect, filter, mask)
+    }
+
+    #[inline]
+    fn blit_buffers_from_simple_framebuffer(&self, source: &SimpleFrameBuffer,
+                                            source_rect: &Rect, target_rect: &BlitTarget,
+                                            filter: uniforms::MagnifySamplerFilter, mask: BlitMask)
+    {
+        self.surface.blit_buffers_from_simple_framebuffer(source, source_rect, target_rect,
+                                                          filter, mask)
+    }
+
+    #[inline]
+    fn blit_buffers_from_multioutput_framebuffer(&self, source: &MultiOutputFrameBuffer,
+                                                 source_rect: &Rect, target_rect: &BlitTarget,
+                                                 filter: uniforms::MagnifySamplerFilter,
+                                                 mask: BlitMask)
+    {
+        self.surface.blit_buffers_from_multioutput_framebuffer(source, source_rect, target_rect,
+                                                               filter, mask)
+    }
+}
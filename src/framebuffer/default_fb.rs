@@ -3,23 +3,29 @@
 use std::rc::Rc;
 use TextureExt;
 
+use texture::{PixelValue, Texture2dDataSink};
+
 use backend::Facade;
 use context::Context;
 
 use DrawParameters;
 use FboAttachments;
 use Rect;
+use BlitMask;
 use BlitTarget;
 use ContextExt;
-use ToGlEnum;
 use ops;
 use uniforms;
 
 use {Program, Surface};
 use DrawError;
 
-use {fbo, gl};
+use fbo;
+use gl;
+use fbo::ClearBufferData;
 use framebuffer;
+use framebuffer::ReadError;
+use framebuffer::ClearBufferError;
 use index;
 use vertex;
 
@@ -37,6 +43,28 @@ pub enum DefaultFramebufferAttachment {
     FrontRight,
 }
 
+/// Characteristics of the default framebuffer.
+///
+/// Obtained with `get_default_framebuffer_properties` on the context, or `get_properties` on a
+/// `Frame` or a `DefaultFramebuffer`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct DefaultFramebufferProperties {
+    /// Number of samples per pixel, or `None` if the default framebuffer is not multisampled.
+    pub samples: Option<u16>,
+
+    /// Number of bits of the depth buffer, or `None` if there is no depth buffer.
+    pub depth_bits: Option<u16>,
+
+    /// Number of bits of the stencil buffer, or `None` if there is no stencil buffer.
+    pub stencil_bits: Option<u16>,
+
+    /// True if the color buffers are capable of sRGB conversions.
+    pub srgb: bool,
+
+    /// True if there are left and right buffers.
+    pub stereo: bool,
+}
+
 /// A framebuffer which has only one color attachment.
 pub struct DefaultFramebuffer {
     context: Rc<Context>,
@@ -52,6 +80,68 @@ impl DefaultFramebuffer {
             attachment: DefaultFramebufferAttachment::BackLeft,
         }
     }
+
+    /// Creates a `DefaultFramebuffer` with the back right buffer.
+    ///
+    /// Drawing on a `DefaultFramebuffer` created with `back_left` only draws on the left buffer,
+    /// while drawing on a `Frame` draws on both buffers.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the default framebuffer doesn't have left and right buffers. You can check this
+    /// with the `stereo` field of `get_default_framebuffer_properties`.
+    #[inline]
+    pub fn back_right<F: ?Sized>(facade: &F) -> DefaultFramebuffer where F: Facade {
+        assert!(facade.get_context().capabilities().stereo,
+                "The default framebuffer doesn't support stereoscopy");
+
+        DefaultFramebuffer {
+            context: facade.get_context().clone(),
+            attachment: DefaultFramebufferAttachment::BackRight,
+        }
+    }
+
+    /// Returns the characteristics of the default framebuffer.
+    #[inline]
+    pub fn get_properties(&self) -> DefaultFramebufferProperties {
+        self.context.get_default_framebuffer_properties()
+    }
+
+    /// Returns the `GLenum` corresponding to the attachment.
+    fn get_gl_buffer(&self) -> gl::types::GLenum {
+        match self.attachment {
+            DefaultFramebufferAttachment::BackLeft => gl::BACK_LEFT,
+            DefaultFramebufferAttachment::BackRight => gl::BACK_RIGHT,
+            DefaultFramebufferAttachment::FrontLeft => gl::FRONT_LEFT,
+            DefaultFramebufferAttachment::FrontRight => gl::FRONT_RIGHT,
+        }
+    }
+
+    /// Calls `f` with the draw buffer of the default framebuffer set to the attachment, then
+    /// restores the back buffer.
+    ///
+    /// Without stereoscopy there is only one back buffer, so nothing needs to be done.
+    fn with_draw_buffer<R, F>(&self, f: F) -> R where F: FnOnce() -> R {
+        if !self.context.capabilities().stereo {
+            return f();
+        }
+
+        unsafe {
+            let mut ctxt = self.context.make_current();
+            fbo::bind_framebuffer(&mut ctxt, 0, true, false);
+            ctxt.gl.DrawBuffer(self.get_gl_buffer());
+        }
+
+        let result = f();
+
+        unsafe {
+            let mut ctxt = self.context.make_current();
+            fbo::bind_framebuffer(&mut ctxt, 0, true, false);
+            ctxt.gl.DrawBuffer(gl::BACK);
+        }
+
+        result
+    }
 }
 
 impl Surface for DefaultFramebuffer {
@@ -59,8 +149,28 @@ impl Surface for DefaultFramebuffer {
     fn clear(&mut self, rect: Option<&Rect>, color: Option<(f32, f32, f32, f32)>, color_srgb: bool,
              depth: Option<f32>, stencil: Option<i32>)
     {
-        // TODO: wrong attachment
-        ops::clear(&self.context, None, None, color, color_srgb, depth, stencil);
+        self.with_draw_buffer(|| {
+            ops::clear(&self.context, None, rect, color, color_srgb, depth, stencil)
+        })
+    }
+
+    #[inline]
+    fn clear_buffer(&mut self, rect: Option<&Rect>, index: u32, data: ClearBufferData)
+                    -> Result<(), ClearBufferError>
+    {
+        self.with_draw_buffer(|| ops::clear_buffer(&self.context, None, rect, index, data))
+    }
+
+    #[inline]
+    fn invalidate(&mut self, colors: Option<&[u32]>, depth: bool, stencil: bool) {
+        ops::invalidate(&self.context, None, colors, depth, stencil);
+    }
+
+    fn read_pixels<P, T>(&self, rect: &Rect) -> Result<T, ReadError>
+                         where P: PixelValue, T: Texture2dDataSink<P>
+    {
+        ops::read_to_sink(&self.context, ops::Source::DefaultFramebuffer(self.get_gl_buffer()),
+                          rect)
     }
 
     fn get_dimensions(&self) -> (u32, u32) {
@@ -100,42 +210,50 @@ impl Surface for DefaultFramebuffer {
             }
         }
 
-        // TODO: wrong attachment
-        ops::draw(&self.context, None, vertex_buffer, index_buffer.into(), program,
-                  uniforms, draw_parameters, self.get_dimensions())
+        let dimensions = self.get_dimensions();
+        self.with_draw_buffer(|| {
+            ops::draw(&self.context, None, vertex_buffer, index_buffer.into(), program,
+                      uniforms, draw_parameters, dimensions)
+        })
     }
 
     #[inline]
-    fn blit_color<S>(&self, source_rect: &Rect, target: &S, target_rect: &BlitTarget,
-                     filter: uniforms::MagnifySamplerFilter) where S: Surface
+    fn blit_buffers<S>(&self, source_rect: &Rect, target: &S, target_rect: &BlitTarget,
+                       filter: uniforms::MagnifySamplerFilter, mask: BlitMask) where S: Surface
     {
-        target.blit_from_frame(source_rect, target_rect, filter)
+        target.blit_buffers_from_frame(source_rect, target_rect, filter, mask)
     }
 
     #[inline]
-    fn blit_from_frame(&self, source_rect: &Rect, target_rect: &BlitTarget,
-                       filter: uniforms::MagnifySamplerFilter)
+    fn blit_buffers_from_frame(&self, source_rect: &Rect, target_rect: &BlitTarget,
+                               filter: uniforms::MagnifySamplerFilter, mask: BlitMask)
     {
-        ops::blit(&self.context, None, self.get_attachments(),
-                  gl::COLOR_BUFFER_BIT, source_rect, target_rect, filter.to_glenum())
+        self.with_draw_buffer(|| {
+            ops::blit(&self.context, None, self.get_attachments(),
+                      mask, source_rect, target_rect, filter)
+        })
     }
 
     #[inline]
-    fn blit_from_simple_framebuffer(&self, source: &framebuffer::SimpleFrameBuffer,
-                                    source_rect: &Rect, target_rect: &BlitTarget,
-                                    filter: uniforms::MagnifySamplerFilter)
+    fn blit_buffers_from_simple_framebuffer(&self, source: &framebuffer::SimpleFrameBuffer,
+                                            source_rect: &Rect, target_rect: &BlitTarget,
+                                            filter: uniforms::MagnifySamplerFilter, mask: BlitMask)
     {
-        ops::blit(&self.context, source.get_attachments(), self.get_attachments(),
-                  gl::COLOR_BUFFER_BIT, source_rect, target_rect, filter.to_glenum())
+        self.with_draw_buffer(|| {
+            ops::blit(&self.context, source.get_attachments(), self.get_attachments(),
+                      mask, source_rect, target_rect, filter)
+        })
     }
 
     #[inline]
-    fn blit_from_multioutput_framebuffer(&self, source: &framebuffer::MultiOutputFrameBuffer,
-                                         source_rect: &Rect, target_rect: &BlitTarget,
-                                         filter: uniforms::MagnifySamplerFilter)
+    fn blit_buffers_from_multioutput_framebuffer(&self, source: &framebuffer::MultiOutputFrameBuffer,
+                                                 source_rect: &Rect, target_rect: &BlitTarget,
+                                                 filter: uniforms::MagnifySamplerFilter, mask: BlitMask)
     {
-        ops::blit(&self.context, source.get_attachments(), self.get_attachments(),
-                  gl::COLOR_BUFFER_BIT, source_rect, target_rect, filter.to_glenum())
+        self.with_draw_buffer(|| {
+            ops::blit(&self.context, source.get_attachments(), self.get_attachments(),
+                      mask, source_rect, target_rect, filter)
+        })
     }
 }
 
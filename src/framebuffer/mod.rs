@@ -54,6 +54,13 @@ Some restrictions apply when you use framebuffers:
    framebuffer that you are using. This is not enforced by glium as it depends on your shader's
    source code.
 
+# Multisampling
+
+Multisampled textures (like `Texture2dMultisample`) and multisampled render buffers (created with
+`RenderBuffer::new_multisample`) can be used as attachments in order to do antialiased offscreen
+rendering. They can't be sampled directly like regular textures: call
+`SimpleFrameBuffer::resolve_to` to copy their averaged content to a regular texture first.
+
 # Empty framebuffers
 
 Modern OpenGL implementations support empty framebuffers. This is handled by glium with the
@@ -63,13 +70,26 @@ You can check whether they are supported by calling `EmptyFrameBuffer::is_suppor
 
 # Layered framebuffers
 
-Not yet supported
+A layered framebuffer is a framebuffer whose attachments are whole texture arrays, 3D textures or
+cubemaps instead of single images. A geometry shader can then choose the layer to draw each
+primitive on by writing to `gl_Layer`. This allows you for example to render all the faces of a
+cubemap or all the cascades of a shadow map in a single draw call.
+
+This is handled by glium with the `LayeredFrameBuffer` struct. You can check whether layered
+framebuffers are supported by calling `LayeredFrameBuffer::is_supported(&display)`.
+
+If you only want to draw on a single layer, pass the corresponding layer or cubemap face to a
+`SimpleFrameBuffer` instead.
 
 */
+use std::fmt;
+use std::error::Error;
 use std::rc::Rc;
 use smallvec::SmallVec;
 
 use texture::TextureAnyImage;
+use texture::TextureAnyMipmap;
+use texture::{PixelValue, Texture2dDataSink};
 use TextureExt;
 
 use backend::Facade;
@@ -80,24 +100,32 @@ use version::Api;
 
 use FboAttachments;
 use Rect;
+use BlitMask;
 use BlitTarget;
 use ContextExt;
-use ToGlEnum;
 use ops;
 use uniforms;
 
 use {Program, Surface};
 use DrawError;
 
-use {fbo, gl};
+use fbo;
 
+pub use self::conditional::ConditionalSurface;
 pub use self::default_fb::{DefaultFramebufferAttachment, DefaultFramebuffer};
+pub use self::default_fb::DefaultFramebufferProperties;
 pub use self::render_buffer::{RenderBuffer, RenderBufferAny, DepthRenderBuffer};
 pub use self::render_buffer::{StencilRenderBuffer, DepthStencilRenderBuffer};
 pub use self::render_buffer::CreationError as RenderBufferCreationError;
 pub use fbo::is_dimensions_mismatch_supported;
 pub use fbo::ValidationError;
+pub use fbo::{IncompleteFramebufferReason, AttachmentPoint, AttachmentFormatInfos};
+pub use fbo::AttachmentsFormatInfos;
+pub use fbo::ClearBufferData;
+pub use ops::ReadError;
+pub use ops::ClearBufferError;
 
+mod conditional;
 mod default_fb;
 mod render_buffer;
 
@@ -110,6 +138,11 @@ pub struct SimpleFrameBuffer<'a> {
 impl<'a> SimpleFrameBuffer<'a> {
     /// Creates a `SimpleFrameBuffer` with a single color attachment and no depth
     /// nor stencil buffer.
+    ///
+    /// Like all the constructors of `SimpleFrameBuffer`, this immediately creates the framebuffer
+    /// object in order to ask the backend whether it is complete. The object is then cached and
+    /// reused by the draw commands, so only the first framebuffer with given attachments pays
+    /// this cost.
     #[inline]
     pub fn new<F: ?Sized, C>(facade: &F, color: C) -> Result<SimpleFrameBuffer<'a>, ValidationError>
                      where C: ToColorAttachment<'a>, F: Facade
@@ -129,8 +162,11 @@ impl<'a> SimpleFrameBuffer<'a> {
                                     Some(depth.to_depth_attachment()), None, None)
     }
 
-    /// Creates a `SimpleFrameBuffer` with a single color attachment and no depth
-    /// nor stencil buffer.
+    /// Creates a `SimpleFrameBuffer` with a depth buffer and no color attachment.
+    ///
+    /// This is what you need for shadow mapping passes, where only the depth of the scene is
+    /// rendered. The draw and read buffers of the framebuffer are set to `GL_NONE`, and the
+    /// fragment shader outputs are discarded.
     #[inline]
     pub fn depth_only<F: ?Sized, D>(facade: &F, depth: D)
                             -> Result<SimpleFrameBuffer<'a>, ValidationError>
@@ -257,12 +293,64 @@ impl<'a> SimpleFrameBuffer<'a> {
         });
 
         let attachments = try!(attachments.validate(facade));
+        try!(check_completeness(facade.get_context(), &attachments));
 
         Ok(SimpleFrameBuffer {
             context: facade.get_context().clone(),
             attachments: attachments,
         })
     }
+
+    /// Resolves the content of this framebuffer into `target`.
+    ///
+    /// This is used to turn the result of multisampled rendering into a regular image, for
+    /// example to post-process it or to sample from it. Each pixel of the target receives the
+    /// average of the samples of the corresponding pixel of this framebuffer.
+    ///
+    /// Only the color buffer is copied. Use `resolve_buffers_to` to copy the depth or stencil
+    /// buffers as well.
+    ///
+    /// ## Panic
+    ///
+    /// Panics if the target doesn't have the same dimensions as this framebuffer.
+    ///
+    /// ## Example
+    ///
+    /// ```no_run
+    /// # use glium::Surface;
+    /// # let display: glium::Display = unsafe { std::mem::uninitialized() };
+    /// let multisampled = glium::texture::Texture2dMultisample::empty(&display, 1024, 1024, 4).unwrap();
+    /// let resolved = glium::Texture2d::empty(&display, 1024, 1024).unwrap();
+    ///
+    /// let mut surface = multisampled.as_surface();
+    /// surface.clear_color(0.0, 0.0, 0.0, 1.0);
+    /// // draw on `surface`
+    ///
+    /// surface.resolve_to(&resolved.as_surface());
+    /// // `resolved` can now be sampled in a post-processing pass
+    /// ```
+    #[inline]
+    pub fn resolve_to<S>(&self, target: &S) where S: Surface {
+        self.resolve_buffers_to(target, BlitMask::color())
+    }
+
+    /// Resolves the buffers designated by `mask` into `target`. See `resolve_to`.
+    ///
+    /// ## Panic
+    ///
+    /// Panics if the target doesn't have the same dimensions as this framebuffer.
+    pub fn resolve_buffers_to<S>(&self, target: &S, mask: BlitMask) where S: Surface {
+        let (width, height) = self.get_dimensions();
+        assert_eq!(target.get_dimensions(), (width, height));
+
+        let source_rect = Rect { left: 0, bottom: 0, width: width, height: height };
+        let target_rect = BlitTarget { left: 0, bottom: 0, width: width as i32,
+                                       height: height as i32 };
+
+        // resolving multisampled buffers requires the nearest filter
+        self.blit_buffers(&source_rect, target, &target_rect,
+                          uniforms::MagnifySamplerFilter::Nearest, mask)
+    }
 }
 
 impl<'a> Surface for SimpleFrameBuffer<'a> {
@@ -273,6 +361,25 @@ impl<'a> Surface for SimpleFrameBuffer<'a> {
         ops::clear(&self.context, Some(&self.attachments), rect, color, color_srgb, depth, stencil);
     }
 
+    #[inline]
+    fn clear_buffer(&mut self, rect: Option<&Rect>, index: u32, data: ClearBufferData)
+                    -> Result<(), ClearBufferError>
+    {
+        ops::clear_buffer(&self.context, self.get_attachments(), rect, index, data)
+    }
+
+    #[inline]
+    fn invalidate(&mut self, colors: Option<&[u32]>, depth: bool, stencil: bool) {
+        ops::invalidate(&self.context, self.get_attachments(), colors, depth, stencil);
+    }
+
+    #[inline]
+    fn read_pixels<P, T>(&self, rect: &Rect) -> Result<T, ReadError>
+                         where P: PixelValue, T: Texture2dDataSink<P>
+    {
+        ops::read_to_sink(&self.context, ops::Source::Framebuffer(&self.attachments), rect)
+    }
+
     #[inline]
     fn get_dimensions(&self) -> (u32, u32) {
         self.attachments.get_dimensions()
@@ -317,39 +424,49 @@ impl<'a> Surface for SimpleFrameBuffer<'a> {
     }
 
     #[inline]
-    fn blit_color<S>(&self, source_rect: &Rect, target: &S, target_rect: &BlitTarget,
-                     filter: uniforms::MagnifySamplerFilter) where S: Surface
+    fn blit_buffers<S>(&self, source_rect: &Rect, target: &S, target_rect: &BlitTarget,
+                       filter: uniforms::MagnifySamplerFilter, mask: BlitMask) where S: Surface
     {
-        target.blit_from_simple_framebuffer(self, source_rect, target_rect, filter)
+        target.blit_buffers_from_simple_framebuffer(self, source_rect, target_rect, filter, mask)
     }
 
     #[inline]
-    fn blit_from_frame(&self, source_rect: &Rect, target_rect: &BlitTarget,
-                       filter: uniforms::MagnifySamplerFilter)
+    fn blit_buffers_from_frame(&self, source_rect: &Rect, target_rect: &BlitTarget,
+                               filter: uniforms::MagnifySamplerFilter, mask: BlitMask)
     {
         ops::blit(&self.context, None, self.get_attachments(),
-                  gl::COLOR_BUFFER_BIT, source_rect, target_rect, filter.to_glenum())
+                  mask, source_rect, target_rect, filter)
     }
 
     #[inline]
-    fn blit_from_simple_framebuffer(&self, source: &SimpleFrameBuffer,
-                                    source_rect: &Rect, target_rect: &BlitTarget,
-                                    filter: uniforms::MagnifySamplerFilter)
+    fn blit_buffers_from_simple_framebuffer(&self, source: &SimpleFrameBuffer,
+                                            source_rect: &Rect, target_rect: &BlitTarget,
+                                            filter: uniforms::MagnifySamplerFilter, mask: BlitMask)
     {
         ops::blit(&self.context, source.get_attachments(), self.get_attachments(),
-                  gl::COLOR_BUFFER_BIT, source_rect, target_rect, filter.to_glenum())
+                  mask, source_rect, target_rect, filter)
     }
 
     #[inline]
-    fn blit_from_multioutput_framebuffer(&self, source: &MultiOutputFrameBuffer,
-                                         source_rect: &Rect, target_rect: &BlitTarget,
-                                         filter: uniforms::MagnifySamplerFilter)
+    fn blit_buffers_from_multioutput_framebuffer(&self, source: &MultiOutputFrameBuffer,
+                                                 source_rect: &Rect, target_rect: &BlitTarget,
+                                                 filter: uniforms::MagnifySamplerFilter, mask: BlitMask)
     {
         ops::blit(&self.context, source.get_attachments(), self.get_attachments(),
-                  gl::COLOR_BUFFER_BIT, source_rect, target_rect, filter.to_glenum())
+                  mask, source_rect, target_rect, filter)
     }
 }
 
+/// Asks the backend whether the framebuffer object corresponding to the attachments
+/// is complete.
+#[inline]
+fn check_completeness(context: &Rc<Context>, attachments: &fbo::ValidatedAttachments)
+                      -> Result<(), ValidationError>
+{
+    let mut ctxt = context.make_current();
+    fbo::FramebuffersContainer::check_completeness(&mut ctxt, attachments)
+}
+
 impl<'a> FboAttachments for SimpleFrameBuffer<'a> {
     #[inline]
     fn get_attachments(&self) -> Option<&fbo::ValidatedAttachments> {
@@ -357,17 +474,65 @@ impl<'a> FboAttachments for SimpleFrameBuffer<'a> {
     }
 }
 
-/// This struct is useless for the moment.
+/// A framebuffer with multiple color attachments.
+///
+/// Each color attachment corresponds to an output of the fragment shader. Outputs are either
+/// designated by their name, in which case their location is queried from the program at each
+/// draw call, or directly by their location.
+///
+/// ```no_run
+/// # let display: glium::Display = unsafe { std::mem::uninitialized() };
+/// # let albedo: glium::Texture2d = unsafe { std::mem::uninitialized() };
+/// # let normals: glium::Texture2d = unsafe { std::mem::uninitialized() };
+/// let outputs = vec![("albedo", &albedo), ("normal", &normals)];
+/// let framebuffer = glium::framebuffer::MultiOutputFrameBuffer::new(&display, outputs);
+/// ```
 pub struct MultiOutputFrameBuffer<'a> {
     context: Rc<Context>,
     example_attachments: fbo::ValidatedAttachments<'a>,
-    color_attachments: Vec<(String, fbo::RegularAttachment<'a>)>,
+    color_attachments: Vec<(FragmentOutput, fbo::RegularAttachment<'a>)>,
     depth_stencil_attachments: fbo::DepthStencilAttachments<fbo::RegularAttachment<'a>>,
 }
 
+/// Error that can happen when selecting the read buffer of a framebuffer.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ReadBufferError {
+    /// No color attachment is bound to this fragment output location.
+    NoSuchAttachment(u32),
+}
+
+impl fmt::Display for ReadBufferError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ReadBufferError::NoSuchAttachment(location) =>
+                write!(fmt, "{}: {}", self.description(), location),
+        }
+    }
+}
+
+impl Error for ReadBufferError {
+    fn description(&self) -> &str {
+        match *self {
+            ReadBufferError::NoSuchAttachment(_) =>
+                "No color attachment is bound to this fragment output location",
+        }
+    }
+}
+
+/// Designates an output of the fragment shader.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum FragmentOutput {
+    Name(String),
+    Location(u32),
+}
+
 impl<'a> MultiOutputFrameBuffer<'a> {
     /// Creates a new `MultiOutputFrameBuffer`.
     ///
+    /// Like `with_locations`, this immediately creates a framebuffer object in order to ask the
+    /// backend whether it is complete. The object is then cached and reused by the draw commands
+    /// whose program puts the outputs at the same locations.
+    ///
     /// # Panic
     ///
     /// Panics if all attachments don't have the same dimensions.
@@ -381,6 +546,28 @@ impl<'a> MultiOutputFrameBuffer<'a> {
         MultiOutputFrameBuffer::new_impl(facade, color_attachments, None, None, None)
     }
 
+    /// Creates a new `MultiOutputFrameBuffer` whose outputs are designated by their location
+    /// instead of their name.
+    ///
+    /// This is useful with shaders that write to `gl_FragData[n]` or whose outputs have an
+    /// explicit `layout(location = n)`. Locations that are not in the list are discarded.
+    ///
+    /// # Panic
+    ///
+    /// Panics if all attachments don't have the same dimensions.
+    pub fn with_locations<F: ?Sized, I, A>(facade: &F, color_attachments: I)
+                                           -> Result<MultiOutputFrameBuffer<'a>, ValidationError>
+        where F: Facade,
+              I: IntoIterator<Item = (u32, A)>,
+              A: ToColorAttachment<'a>,
+    {
+        let color = color_attachments.into_iter().map(|(location, atch)| {
+            (FragmentOutput::Location(location), atch.to_color_attachment())
+        }).collect::<Vec<_>>();
+
+        MultiOutputFrameBuffer::new_impl_outputs(facade, color, None, None, None)
+    }
+
     /// Creates a `MultiOutputFrameBuffer` with a depth buffer.
     ///
     /// # Panic
@@ -459,16 +646,38 @@ impl<'a> MultiOutputFrameBuffer<'a> {
               I: IntoIterator<Item = (&'a str, A)>,
               A: ToColorAttachment<'a>,
     {
-        let color = color.into_iter().map(|(name, tex)| {
-            let atch = tex.to_color_attachment();
-            let atch = if let ColorAttachment::Texture(t) = atch { t } else { panic!() };
-            (name.to_owned(), fbo::RegularAttachment::Texture(atch))
+        let color = color.into_iter().map(|(name, atch)| {
+            (FragmentOutput::Name(name.to_owned()), atch.to_color_attachment())
+        }).collect::<Vec<_>>();
+
+        MultiOutputFrameBuffer::new_impl_outputs(facade, color, depth, stencil, depthstencil)
+    }
+
+    fn new_impl_outputs<F: ?Sized>(facade: &F, color: Vec<(FragmentOutput, ColorAttachment<'a>)>,
+                                   depth: Option<DepthAttachment<'a>>,
+                                   stencil: Option<StencilAttachment<'a>>,
+                                   depthstencil: Option<DepthStencilAttachment<'a>>)
+                                   -> Result<MultiOutputFrameBuffer<'a>, ValidationError>
+        where F: Facade
+    {
+        let color = color.into_iter().map(|(output, atch)| {
+            let atch = match atch {
+                ColorAttachment::Texture(tex) => fbo::RegularAttachment::Texture(tex),
+                ColorAttachment::RenderBuffer(buffer) => fbo::RegularAttachment::RenderBuffer(buffer),
+            };
+
+            (output, atch)
         }).collect::<Vec<_>>();
 
         let example_color = {
             let mut v = SmallVec::new();
-            for e in color.iter().enumerate().map(|(index, &(_, tex))| { (index as u32, tex) }) {
-                v.push(e);
+            for (index, &(ref output, atch)) in color.iter().enumerate() {
+                let location = match *output {
+                    FragmentOutput::Location(location) => location,
+                    FragmentOutput::Name(_) => index as u32,
+                };
+
+                v.push((location, atch));
             }
             v
         };
@@ -504,6 +713,7 @@ impl<'a> MultiOutputFrameBuffer<'a> {
             colors: example_color,
             depth_stencil: depth_stencil_attachments,
         }).validate(facade));
+        try!(check_completeness(facade.get_context(), &example_attachments));
 
         Ok(MultiOutputFrameBuffer {
             context: facade.get_context().clone(),
@@ -513,13 +723,34 @@ impl<'a> MultiOutputFrameBuffer<'a> {
         })
     }
 
+    /// Selects the color attachment that is read by `read_pixels` and that is the source of
+    /// blits. The default is the first attachment.
+    ///
+    /// `location` is the location of the fragment output that the attachment is bound to, which
+    /// is also the index that `Surface::clear_buffer` and `Surface::clear_color_attachment` expect.
+    /// Attachments created with `with_locations` use the location that was passed alongside them,
+    /// while attachments created with `new` use their position in the list.
+    ///
+    /// Returns an error if no color attachment is bound to this location.
+    pub fn set_read_buffer(&mut self, location: u32) -> Result<(), ReadBufferError> {
+        if self.example_attachments.get_color_attachment_kind(location).is_none() {
+            return Err(ReadBufferError::NoSuchAttachment(location));
+        }
+
+        self.example_attachments.set_read_buffer(location);
+        Ok(())
+    }
+
     fn build_attachments(&self, program: &Program) -> fbo::ValidatedAttachments {
         let mut colors = SmallVec::new();
 
-        for &(ref name, attachment) in self.color_attachments.iter() {
-            let location = match program.get_frag_data_location(&name) {
-                Some(l) => l,
-                None => panic!("The fragment output `{}` was not found in the program", name)
+        for &(ref output, attachment) in self.color_attachments.iter() {
+            let location = match *output {
+                FragmentOutput::Location(location) => location,
+                FragmentOutput::Name(ref name) => match program.get_frag_data_location(&name) {
+                    Some(l) => l,
+                    None => panic!("The fragment output `{}` was not found in the program", name)
+                },
             };
 
             colors.push((location, attachment));
@@ -541,6 +772,25 @@ impl<'a> Surface for MultiOutputFrameBuffer<'a> {
                    color, color_srgb, depth, stencil);
     }
 
+    #[inline]
+    fn clear_buffer(&mut self, rect: Option<&Rect>, index: u32, data: ClearBufferData)
+                    -> Result<(), ClearBufferError>
+    {
+        ops::clear_buffer(&self.context, self.get_attachments(), rect, index, data)
+    }
+
+    #[inline]
+    fn invalidate(&mut self, colors: Option<&[u32]>, depth: bool, stencil: bool) {
+        ops::invalidate(&self.context, self.get_attachments(), colors, depth, stencil);
+    }
+
+    #[inline]
+    fn read_pixels<P, T>(&self, rect: &Rect) -> Result<T, ReadError>
+                         where P: PixelValue, T: Texture2dDataSink<P>
+    {
+        ops::read_to_sink(&self.context, ops::Source::Framebuffer(&self.example_attachments), rect)
+    }
+
     #[inline]
     fn get_dimensions(&self) -> (u32, u32) {
         self.example_attachments.get_dimensions()
@@ -585,52 +835,345 @@ impl<'a> Surface for MultiOutputFrameBuffer<'a> {
     }
 
     #[inline]
-    fn blit_color<S>(&self, source_rect: &Rect, target: &S, target_rect: &BlitTarget,
-                     filter: uniforms::MagnifySamplerFilter) where S: Surface
+    fn blit_buffers<S>(&self, source_rect: &Rect, target: &S, target_rect: &BlitTarget,
+                       filter: uniforms::MagnifySamplerFilter, mask: BlitMask) where S: Surface
     {
-        target.blit_from_multioutput_framebuffer(self, source_rect, target_rect, filter)
+        target.blit_buffers_from_multioutput_framebuffer(self, source_rect, target_rect, filter, mask)
     }
 
     #[inline]
-    fn blit_from_frame(&self, source_rect: &Rect, target_rect: &BlitTarget,
-                       filter: uniforms::MagnifySamplerFilter)
+    fn blit_buffers_from_frame(&self, source_rect: &Rect, target_rect: &BlitTarget,
+                               filter: uniforms::MagnifySamplerFilter, mask: BlitMask)
     {
         ops::blit(&self.context, None, self.get_attachments(),
-                  gl::COLOR_BUFFER_BIT, source_rect, target_rect, filter.to_glenum())
+                  mask, source_rect, target_rect, filter)
     }
 
     #[inline]
-    fn blit_from_simple_framebuffer(&self, source: &SimpleFrameBuffer,
-                                    source_rect: &Rect, target_rect: &BlitTarget,
-                                    filter: uniforms::MagnifySamplerFilter)
+    fn blit_buffers_from_simple_framebuffer(&self, source: &SimpleFrameBuffer,
+                                            source_rect: &Rect, target_rect: &BlitTarget,
+                                            filter: uniforms::MagnifySamplerFilter, mask: BlitMask)
     {
         ops::blit(&self.context, source.get_attachments(), self.get_attachments(),
-                  gl::COLOR_BUFFER_BIT, source_rect, target_rect, filter.to_glenum())
+                  mask, source_rect, target_rect, filter)
     }
 
     #[inline]
-    fn blit_from_multioutput_framebuffer(&self, source: &MultiOutputFrameBuffer,
-                                         source_rect: &Rect, target_rect: &BlitTarget,
-                                         filter: uniforms::MagnifySamplerFilter)
+    fn blit_buffers_from_multioutput_framebuffer(&self, source: &MultiOutputFrameBuffer,
+                                                 source_rect: &Rect, target_rect: &BlitTarget,
+                                                 filter: uniforms::MagnifySamplerFilter, mask: BlitMask)
     {
         ops::blit(&self.context, source.get_attachments(), self.get_attachments(),
-                  gl::COLOR_BUFFER_BIT, source_rect, target_rect, filter.to_glenum())
+                  mask, source_rect, target_rect, filter)
     }
 }
 
 impl<'a> FboAttachments for MultiOutputFrameBuffer<'a> {
+    /// Returns the attachments with the color attachments in the order in which they were
+    /// passed at construction.
+    #[inline]
+    fn get_attachments(&self) -> Option<&fbo::ValidatedAttachments> {
+        Some(&self.example_attachments)
+    }
+}
+
+/// A framebuffer whose attachments are whole texture arrays, 3D textures or cubemaps.
+///
+/// The layer that each primitive is drawn on is chosen by writing to `gl_Layer` in a geometry
+/// shader. Without a geometry shader, everything is drawn on the first layer.
+///
+/// Clearing the framebuffer clears all the layers.
+/// Blitting from the framebuffer or reading its pixels only reads the first layer.
+///
+/// # Multiview
+///
+/// A `LayeredFrameBuffer` created with `multiview` instead draws each primitive once per layer,
+/// without any geometry shader. This is typically used to render both eyes of a VR headset in a
+/// single draw call. The vertex shader must enable the `GL_OVR_multiview2` extension, declare the
+/// number of views, and use `gl_ViewID_OVR` to pick the per-view data, for example:
+///
+/// ```notrust
+/// #version 300 es
+/// #extension GL_OVR_multiview2 : require
+/// layout(num_views = 2) in;
+///
+/// uniform mat4 view_matrices[2];
+/// in vec3 position;
+///
+/// void main() {
+///     gl_Position = view_matrices[gl_ViewID_OVR] * vec4(position, 1.0);
+/// }
+/// ```
+pub struct LayeredFrameBuffer<'a> {
+    context: Rc<Context>,
+    attachments: fbo::ValidatedAttachments<'a>,
+}
+
+impl<'a> LayeredFrameBuffer<'a> {
+    /// Returns true if layered framebuffers are supported by the backend.
+    #[inline]
+    pub fn is_supported<C: ?Sized>(context: &C) -> bool where C: CapabilitiesSource {
+        fbo::is_layered_supported(context)
+    }
+
+    /// Returns true if multiview framebuffers are supported by the backend.
+    #[inline]
+    pub fn is_multiview_supported<C: ?Sized>(context: &C) -> bool where C: CapabilitiesSource {
+        fbo::is_multiview_supported(context)
+    }
+
+    /// Creates a `LayeredFrameBuffer` with a single color attachment and no depth
+    /// nor stencil buffer.
+    #[inline]
+    pub fn new<F: ?Sized, C>(facade: &F, color: C) -> Result<LayeredFrameBuffer<'a>, ValidationError>
+                             where C: ToLayeredColorAttachment<'a>, F: Facade
+    {
+        LayeredFrameBuffer::new_impl(facade, Some(color.to_layered_color_attachment()), None,
+                                     None)
+    }
+
+    /// Creates a `LayeredFrameBuffer` with a single color attachment and a depth buffer.
+    #[inline]
+    pub fn with_depth_buffer<F: ?Sized, C, D>(facade: &F, color: C, depth: D)
+                                              -> Result<LayeredFrameBuffer<'a>, ValidationError>
+                                              where C: ToLayeredColorAttachment<'a>,
+                                                    D: ToLayeredDepthAttachment<'a>, F: Facade
+    {
+        LayeredFrameBuffer::new_impl(facade, Some(color.to_layered_color_attachment()),
+                                     Some(depth.to_layered_depth_attachment()), None)
+    }
+
+    /// Creates a `LayeredFrameBuffer` with only a depth buffer.
+    ///
+    /// This is typically used to render the cascades of a shadow map or the faces of an
+    /// omnidirectional shadow map.
+    #[inline]
+    pub fn depth_only<F: ?Sized, D>(facade: &F, depth: D)
+                                    -> Result<LayeredFrameBuffer<'a>, ValidationError>
+                                    where D: ToLayeredDepthAttachment<'a>, F: Facade
+    {
+        LayeredFrameBuffer::new_impl(facade, None, Some(depth.to_layered_depth_attachment()),
+                                     None)
+    }
+
+    /// Creates a multiview `LayeredFrameBuffer` that renders to the layers `base_view` to
+    /// `base_view + num_views - 1` of a single color attachment, with no depth nor stencil
+    /// buffer.
+    ///
+    /// The attachment must be a 2D texture array.
+    #[inline]
+    pub fn multiview<F: ?Sized, C>(facade: &F, color: C, base_view: u32, num_views: u32)
+                                   -> Result<LayeredFrameBuffer<'a>, ValidationError>
+                                   where C: ToLayeredColorAttachment<'a>, F: Facade
+    {
+        LayeredFrameBuffer::new_impl(facade, Some(color.to_layered_color_attachment()), None,
+                                     Some((base_view, num_views)))
+    }
+
+    /// Creates a multiview `LayeredFrameBuffer` with a single color attachment and a depth
+    /// buffer. The same layers of both attachments are used.
+    ///
+    /// The attachments must be 2D texture arrays.
+    #[inline]
+    pub fn multiview_with_depth_buffer<F: ?Sized, C, D>(facade: &F, color: C, depth: D,
+                                                        base_view: u32, num_views: u32)
+                                                        -> Result<LayeredFrameBuffer<'a>,
+                                                                  ValidationError>
+                                                        where C: ToLayeredColorAttachment<'a>,
+                                                              D: ToLayeredDepthAttachment<'a>,
+                                                              F: Facade
+    {
+        LayeredFrameBuffer::new_impl(facade, Some(color.to_layered_color_attachment()),
+                                     Some(depth.to_layered_depth_attachment()),
+                                     Some((base_view, num_views)))
+    }
+
+    fn new_impl<F: ?Sized>(facade: &F, color: Option<LayeredColorAttachment<'a>>,
+                           depth: Option<LayeredDepthAttachment<'a>>, views: Option<(u32, u32)>)
+                           -> Result<LayeredFrameBuffer<'a>, ValidationError> where F: Facade
+    {
+        let color = color.map(|color| match color {
+            LayeredColorAttachment::Texture(tex) => fbo::LayeredAttachment(tex),
+        });
+
+        let depth = depth.map(|depth| match depth {
+            LayeredDepthAttachment::Texture(tex) => fbo::LayeredAttachment(tex),
+        });
+
+        let specific = fbo::FramebufferSpecificAttachments {
+            colors: if let Some(color) = color {
+                let mut v = SmallVec::new(); v.push((0, color)); v
+            } else {
+                SmallVec::new()
+            },
+            depth_stencil: if let Some(depth) = depth {
+                fbo::DepthStencilAttachments::DepthAttachment(depth)
+            } else {
+                fbo::DepthStencilAttachments::None
+            },
+        };
+
+        let attachments = match views {
+            Some((base_view, num_views)) => fbo::FramebufferAttachments::Multiview {
+                attachments: specific,
+                base_view: base_view,
+                num_views: num_views,
+            },
+            None => fbo::FramebufferAttachments::Layered(specific),
+        };
+
+        let attachments = try!(attachments.validate(facade));
+        try!(check_completeness(facade.get_context(), &attachments));
+
+        Ok(LayeredFrameBuffer {
+            context: facade.get_context().clone(),
+            attachments: attachments,
+        })
+    }
+
+    /// Returns the number of layers of the framebuffer.
+    ///
+    /// If the attachments don't have the same number of layers, this is the smallest one. For
+    /// multiview framebuffers, this is the number of views.
+    #[inline]
+    pub fn get_layers(&self) -> u32 {
+        self.attachments.get_layers().unwrap()
+    }
+}
+
+impl<'a> Surface for LayeredFrameBuffer<'a> {
+    #[inline]
+    fn clear(&mut self, rect: Option<&Rect>, color: Option<(f32, f32, f32, f32)>, color_srgb: bool,
+             depth: Option<f32>, stencil: Option<i32>)
+    {
+        ops::clear(&self.context, Some(&self.attachments), rect, color, color_srgb, depth, stencil);
+    }
+
+    #[inline]
+    fn clear_buffer(&mut self, rect: Option<&Rect>, index: u32, data: ClearBufferData)
+                    -> Result<(), ClearBufferError>
+    {
+        ops::clear_buffer(&self.context, self.get_attachments(), rect, index, data)
+    }
+
+    #[inline]
+    fn invalidate(&mut self, colors: Option<&[u32]>, depth: bool, stencil: bool) {
+        ops::invalidate(&self.context, self.get_attachments(), colors, depth, stencil);
+    }
+
+    #[inline]
+    fn read_pixels<P, T>(&self, rect: &Rect) -> Result<T, ReadError>
+                         where P: PixelValue, T: Texture2dDataSink<P>
+    {
+        ops::read_to_sink(&self.context, ops::Source::Framebuffer(&self.attachments), rect)
+    }
+
+    #[inline]
+    fn get_dimensions(&self) -> (u32, u32) {
+        self.attachments.get_dimensions()
+    }
+
+    #[inline]
+    fn get_depth_buffer_bits(&self) -> Option<u16> {
+        self.attachments.get_depth_buffer_bits()
+    }
+
+    #[inline]
+    fn get_stencil_buffer_bits(&self) -> Option<u16> {
+        self.attachments.get_stencil_buffer_bits()
+    }
+
+    fn draw<'b, 'v, V, I, U>(&mut self, vb: V, ib: I, program: &::Program,
+        uniforms: &U, draw_parameters: &::DrawParameters) -> Result<(), DrawError>
+        where I: Into<::index::IndicesSource<'b>>, U: ::uniforms::Uniforms,
+        V: ::vertex::MultiVerticesSource<'v>
+    {
+        if !self.has_depth_buffer() && (draw_parameters.depth.test.requires_depth_buffer() ||
+                        draw_parameters.depth.write)
+        {
+            return Err(DrawError::NoDepthBuffer);
+        }
+
+        if let Some(viewport) = draw_parameters.viewport {
+            if viewport.width > self.context.capabilities().max_viewport_dims.0
+                    as u32
+            {
+                return Err(DrawError::ViewportTooLarge);
+            }
+            if viewport.height > self.context.capabilities().max_viewport_dims.1
+                    as u32
+            {
+                return Err(DrawError::ViewportTooLarge);
+            }
+        }
+
+        ops::draw(&self.context, Some(&self.attachments), vb,
+                  ib.into(), program, uniforms, draw_parameters, self.get_dimensions())
+    }
+
+    #[inline]
+    fn blit_buffers<S>(&self, source_rect: &Rect, target: &S, target_rect: &BlitTarget,
+                       filter: uniforms::MagnifySamplerFilter, mask: BlitMask) where S: Surface
+    {
+        // only the first layer is read from, which is attached on its own for the blit
+        let source = SimpleFrameBuffer {
+            context: self.context.clone(),
+            attachments: self.attachments.first_layer(),
+        };
+
+        target.blit_buffers_from_simple_framebuffer(&source, source_rect, target_rect, filter,
+                                                    mask)
+    }
+
+    #[inline]
+    fn blit_buffers_from_frame(&self, source_rect: &Rect, target_rect: &BlitTarget,
+                               filter: uniforms::MagnifySamplerFilter, mask: BlitMask)
+    {
+        ops::blit(&self.context, None, self.get_attachments(),
+                  mask, source_rect, target_rect, filter)
+    }
+
+    #[inline]
+    fn blit_buffers_from_simple_framebuffer(&self, source: &SimpleFrameBuffer,
+                                            source_rect: &Rect, target_rect: &BlitTarget,
+                                            filter: uniforms::MagnifySamplerFilter, mask: BlitMask)
+    {
+        ops::blit(&self.context, source.get_attachments(), self.get_attachments(),
+                  mask, source_rect, target_rect, filter)
+    }
+
+    #[inline]
+    fn blit_buffers_from_multioutput_framebuffer(&self, source: &MultiOutputFrameBuffer,
+                                                 source_rect: &Rect, target_rect: &BlitTarget,
+                                                 filter: uniforms::MagnifySamplerFilter, mask: BlitMask)
+    {
+        ops::blit(&self.context, source.get_attachments(), self.get_attachments(),
+                  mask, source_rect, target_rect, filter)
+    }
+}
+
+impl<'a> FboAttachments for LayeredFrameBuffer<'a> {
     #[inline]
     fn get_attachments(&self) -> Option<&fbo::ValidatedAttachments> {
-        unimplemented!();
+        Some(&self.attachments)
     }
 }
 
 /// A framebuffer with no attachment at all.
 ///
+/// Drawing on an empty framebuffer runs the fragment shader for the given dimensions, layers
+/// and samples, but doesn't write any color, depth or stencil value. This is useful for passes
+/// whose only outputs are image stores, atomic counters or shader storage buffers, as it avoids
+/// allocating a dummy color attachment.
+///
+/// Since it has no buffer, an empty framebuffer can be the target of a blit, which has no
+/// effect, but not the source of one.
+///
 /// Note that this is only supported on recent hardware.
 pub struct EmptyFrameBuffer {
     context: Rc<Context>,
     attachments: fbo::ValidatedAttachments<'static>,
+    samples: Option<u32>,
 }
 
 impl<'a> EmptyFrameBuffer {
@@ -694,12 +1237,26 @@ impl<'a> EmptyFrameBuffer {
         };
 
         let attachments = try!(attachments.validate(context));
+        try!(check_completeness(context, &attachments));
 
         Ok(EmptyFrameBuffer {
             context: context.clone(),
             attachments: attachments,
+            samples: samples,
         })
     }
+
+    /// Returns the number of layers of the framebuffer, or `None` if it is not layered.
+    #[inline]
+    pub fn get_layers(&self) -> Option<u32> {
+        self.attachments.get_layers()
+    }
+
+    /// Returns the number of samples of the framebuffer, or `None` if it is not multisampled.
+    #[inline]
+    pub fn get_samples(&self) -> Option<u32> {
+        self.samples
+    }
 }
 
 impl Surface for EmptyFrameBuffer {
@@ -710,6 +1267,25 @@ impl Surface for EmptyFrameBuffer {
         ops::clear(&self.context, Some(&self.attachments), rect, color, color_srgb, depth, stencil);
     }
 
+    #[inline]
+    fn clear_buffer(&mut self, rect: Option<&Rect>, index: u32, data: ClearBufferData)
+                    -> Result<(), ClearBufferError>
+    {
+        ops::clear_buffer(&self.context, self.get_attachments(), rect, index, data)
+    }
+
+    #[inline]
+    fn invalidate(&mut self, colors: Option<&[u32]>, depth: bool, stencil: bool) {
+        ops::invalidate(&self.context, self.get_attachments(), colors, depth, stencil);
+    }
+
+    #[inline]
+    fn read_pixels<P, T>(&self, rect: &Rect) -> Result<T, ReadError>
+                         where P: PixelValue, T: Texture2dDataSink<P>
+    {
+        ops::read_to_sink(&self.context, ops::Source::Framebuffer(&self.attachments), rect)
+    }
+
     #[inline]
     fn get_dimensions(&self) -> (u32, u32) {
         self.attachments.get_dimensions()
@@ -753,37 +1329,43 @@ impl Surface for EmptyFrameBuffer {
                   ib.into(), program, uniforms, draw_parameters, self.get_dimensions())
     }
 
+    /// An empty framebuffer has no buffer to copy from, so this function doesn't do anything.
+    ///
+    /// # Panic
+    ///
+    /// In debug builds, panics if `mask` requests any buffer, as this is most likely a mistake.
     #[inline]
-    fn blit_color<S>(&self, source_rect: &Rect, target: &S, target_rect: &BlitTarget,
-                     filter: uniforms::MagnifySamplerFilter) where S: Surface
+    fn blit_buffers<S>(&self, _: &Rect, _: &S, _: &BlitTarget,
+                       _: uniforms::MagnifySamplerFilter, mask: BlitMask) where S: Surface
     {
-        unimplemented!()        // TODO:
+        debug_assert!(!mask.color && !mask.depth && !mask.stencil,
+                      "Can't blit from an empty framebuffer, as it has no buffer to copy from");
     }
 
     #[inline]
-    fn blit_from_frame(&self, source_rect: &Rect, target_rect: &BlitTarget,
-                       filter: uniforms::MagnifySamplerFilter)
+    fn blit_buffers_from_frame(&self, source_rect: &Rect, target_rect: &BlitTarget,
+                               filter: uniforms::MagnifySamplerFilter, mask: BlitMask)
     {
         ops::blit(&self.context, None, self.get_attachments(),
-                  gl::COLOR_BUFFER_BIT, source_rect, target_rect, filter.to_glenum())
+                  mask, source_rect, target_rect, filter)
     }
 
     #[inline]
-    fn blit_from_simple_framebuffer(&self, source: &SimpleFrameBuffer,
-                                    source_rect: &Rect, target_rect: &BlitTarget,
-                                    filter: uniforms::MagnifySamplerFilter)
+    fn blit_buffers_from_simple_framebuffer(&self, source: &SimpleFrameBuffer,
+                                            source_rect: &Rect, target_rect: &BlitTarget,
+                                            filter: uniforms::MagnifySamplerFilter, mask: BlitMask)
     {
         ops::blit(&self.context, source.get_attachments(), self.get_attachments(),
-                  gl::COLOR_BUFFER_BIT, source_rect, target_rect, filter.to_glenum())
+                  mask, source_rect, target_rect, filter)
     }
 
     #[inline]
-    fn blit_from_multioutput_framebuffer(&self, source: &MultiOutputFrameBuffer,
-                                         source_rect: &Rect, target_rect: &BlitTarget,
-                                         filter: uniforms::MagnifySamplerFilter)
+    fn blit_buffers_from_multioutput_framebuffer(&self, source: &MultiOutputFrameBuffer,
+                                                 source_rect: &Rect, target_rect: &BlitTarget,
+                                                 filter: uniforms::MagnifySamplerFilter, mask: BlitMask)
     {
         ops::blit(&self.context, source.get_attachments(), self.get_attachments(),
-                  gl::COLOR_BUFFER_BIT, source_rect, target_rect, filter.to_glenum())
+                  mask, source_rect, target_rect, filter)
     }
 }
 
@@ -881,3 +1463,43 @@ impl<'a> ToDepthStencilAttachment<'a> for DepthStencilAttachment<'a> {
         self
     }
 }
+
+/// Describes a layered attachment for a color buffer.
+#[derive(Copy, Clone)]
+pub enum LayeredColorAttachment<'a> {
+    /// A mipmap level of a texture array, a 3D texture or a cubemap.
+    Texture(TextureAnyMipmap<'a>),
+}
+
+/// Trait for objects that can be used as layered color attachments.
+pub trait ToLayeredColorAttachment<'a> {
+    /// Builds the `LayeredColorAttachment`.
+    fn to_layered_color_attachment(self) -> LayeredColorAttachment<'a>;
+}
+
+impl<'a> ToLayeredColorAttachment<'a> for LayeredColorAttachment<'a> {
+    #[inline]
+    fn to_layered_color_attachment(self) -> LayeredColorAttachment<'a> {
+        self
+    }
+}
+
+/// Describes a layered attachment for a depth buffer.
+#[derive(Copy, Clone)]
+pub enum LayeredDepthAttachment<'a> {
+    /// A mipmap level of a texture array or a cubemap.
+    Texture(TextureAnyMipmap<'a>),
+}
+
+/// Trait for objects that can be used as layered depth attachments.
+pub trait ToLayeredDepthAttachment<'a> {
+    /// Builds the `LayeredDepthAttachment`.
+    fn to_layered_depth_attachment(self) -> LayeredDepthAttachment<'a>;
+}
+
+impl<'a> ToLayeredDepthAttachment<'a> for LayeredDepthAttachment<'a> {
+    #[inline]
+    fn to_layered_depth_attachment(self) -> LayeredDepthAttachment<'a> {
+        self
+    }
+}
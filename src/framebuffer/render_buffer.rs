@@ -25,6 +25,7 @@ use GlObject;
 use fbo::FramebuffersContainer;
 use backend::Facade;
 use context::Context;
+use CapabilitiesSource;
 use ContextExt;
 use version::Version;
 use version::Api;
@@ -34,6 +35,12 @@ use version::Api;
 pub enum CreationError {
     /// The requested format is not supported.
     FormatNotSupported,
+
+    /// Multisampled render buffers are not supported by the backend.
+    MultisampleNotSupported,
+
+    /// The requested number of samples is not supported for this format.
+    SamplesCountNotSupported,
 }
 
 impl fmt::Display for CreationError {
@@ -47,6 +54,8 @@ impl Error for CreationError {
         use self::CreationError::*;
         match *self {
             FormatNotSupported => "The requested format is not supported",
+            MultisampleNotSupported => "Multisampled render buffers are not supported by the backend",
+            SamplesCountNotSupported => "The requested number of samples is not supported for this format",
         }
     }
 }
@@ -73,7 +82,25 @@ impl RenderBuffer {
         let format = try!(image_format::format_request_to_glenum(&facade.get_context(), format, image_format::RequestType::Renderbuffer));
 
         Ok(RenderBuffer {
-            buffer: RenderBufferAny::new(facade, format, TextureKind::Float, width, height, None)
+            buffer: RenderBufferAny::new(facade, format, TextureKind::Float, None, width, height,
+                                         None)
+        })
+    }
+
+    /// Builds a new multisampled render buffer.
+    pub fn new_multisample<F: ?Sized>(facade: &F, format: UncompressedFloatFormat, width: u32, height: u32,
+                                      samples: u32) -> Result<RenderBuffer, CreationError>
+                                      where F: Facade
+    {
+        let format = image_format::TextureFormat::UncompressedFloat(format);
+        try!(check_multisample(facade, format, samples));
+
+        let format = image_format::TextureFormatRequest::Specific(format);
+        let format = try!(image_format::format_request_to_glenum(&facade.get_context(), format, image_format::RequestType::Renderbuffer));
+
+        Ok(RenderBuffer {
+            buffer: RenderBufferAny::new(facade, format, TextureKind::Float, None, width, height,
+                                         Some(samples))
         })
     }
 }
@@ -122,11 +149,31 @@ impl DepthRenderBuffer {
     pub fn new<F: ?Sized>(facade: &F, format: DepthFormat, width: u32, height: u32)
                   -> Result<DepthRenderBuffer, CreationError> where F: Facade
     {
+        let bits = depth_format_bits(format);
         let format = image_format::TextureFormatRequest::Specific(image_format::TextureFormat::DepthFormat(format));
         let format = try!(image_format::format_request_to_glenum(&facade.get_context(), format, image_format::RequestType::Renderbuffer));
 
         Ok(DepthRenderBuffer {
-            buffer: RenderBufferAny::new(facade, format, TextureKind::Depth, width, height, None)
+            buffer: RenderBufferAny::new(facade, format, TextureKind::Depth, Some(bits), width,
+                                         height, None)
+        })
+    }
+
+    /// Builds a new multisampled render buffer.
+    pub fn new_multisample<F: ?Sized>(facade: &F, format: DepthFormat, width: u32, height: u32,
+                                      samples: u32) -> Result<DepthRenderBuffer, CreationError>
+                                      where F: Facade
+    {
+        let bits = depth_format_bits(format);
+        let format = image_format::TextureFormat::DepthFormat(format);
+        try!(check_multisample(facade, format, samples));
+
+        let format = image_format::TextureFormatRequest::Specific(format);
+        let format = try!(image_format::format_request_to_glenum(&facade.get_context(), format, image_format::RequestType::Renderbuffer));
+
+        Ok(DepthRenderBuffer {
+            buffer: RenderBufferAny::new(facade, format, TextureKind::Depth, Some(bits), width,
+                                         height, Some(samples))
         })
     }
 }
@@ -174,11 +221,31 @@ impl StencilRenderBuffer {
     pub fn new<F: ?Sized>(facade: &F, format: StencilFormat, width: u32, height: u32)
                   -> Result<StencilRenderBuffer, CreationError> where F: Facade
     {
+        let bits = stencil_format_bits(format);
         let format = image_format::TextureFormatRequest::Specific(image_format::TextureFormat::StencilFormat(format));
         let format = try!(image_format::format_request_to_glenum(&facade.get_context(), format, image_format::RequestType::Renderbuffer));
 
         Ok(StencilRenderBuffer {
-            buffer: RenderBufferAny::new(facade, format, TextureKind::Stencil, width, height, None)
+            buffer: RenderBufferAny::new(facade, format, TextureKind::Stencil, Some(bits), width,
+                                         height, None)
+        })
+    }
+
+    /// Builds a new multisampled render buffer.
+    pub fn new_multisample<F: ?Sized>(facade: &F, format: StencilFormat, width: u32, height: u32,
+                                      samples: u32) -> Result<StencilRenderBuffer, CreationError>
+                                      where F: Facade
+    {
+        let bits = stencil_format_bits(format);
+        let format = image_format::TextureFormat::StencilFormat(format);
+        try!(check_multisample(facade, format, samples));
+
+        let format = image_format::TextureFormatRequest::Specific(format);
+        let format = try!(image_format::format_request_to_glenum(&facade.get_context(), format, image_format::RequestType::Renderbuffer));
+
+        Ok(StencilRenderBuffer {
+            buffer: RenderBufferAny::new(facade, format, TextureKind::Stencil, Some(bits), width,
+                                         height, Some(samples))
         })
     }
 }
@@ -231,7 +298,25 @@ impl DepthStencilRenderBuffer {
         let format = try!(image_format::format_request_to_glenum(&facade.get_context(), format, image_format::RequestType::Renderbuffer));
 
         Ok(DepthStencilRenderBuffer {
-            buffer: RenderBufferAny::new(facade, format, TextureKind::DepthStencil, width, height, None)
+            buffer: RenderBufferAny::new(facade, format, TextureKind::DepthStencil, None, width, height,
+                                         None)
+        })
+    }
+
+    /// Builds a new multisampled render buffer.
+    pub fn new_multisample<F: ?Sized>(facade: &F, format: DepthStencilFormat, width: u32, height: u32,
+                                      samples: u32) -> Result<DepthStencilRenderBuffer, CreationError>
+                                      where F: Facade
+    {
+        let format = image_format::TextureFormat::DepthStencilFormat(format);
+        try!(check_multisample(facade, format, samples));
+
+        let format = image_format::TextureFormatRequest::Specific(format);
+        let format = try!(image_format::format_request_to_glenum(&facade.get_context(), format, image_format::RequestType::Renderbuffer));
+
+        Ok(DepthStencilRenderBuffer {
+            buffer: RenderBufferAny::new(facade, format, TextureKind::DepthStencil, None, width, height,
+                                         Some(samples))
         })
     }
 }
@@ -268,6 +353,58 @@ impl GlObject for DepthStencilRenderBuffer {
     }
 }
 
+/// Returns the number of bits of a depth format.
+fn depth_format_bits(format: DepthFormat) -> u16 {
+    match format {
+        DepthFormat::I16 => 16,
+        DepthFormat::I24 => 24,
+        DepthFormat::I32 | DepthFormat::F32 => 32,
+    }
+}
+
+/// Returns the number of bits of a stencil format.
+fn stencil_format_bits(format: StencilFormat) -> u16 {
+    match format {
+        StencilFormat::I1 => 1,
+        StencilFormat::I4 => 4,
+        StencilFormat::I8 => 8,
+        StencilFormat::I16 => 16,
+    }
+}
+
+/// Checks whether a multisampled render buffer with this format and this number of samples can
+/// be created.
+fn check_multisample<F: ?Sized>(facade: &F, format: image_format::TextureFormat, samples: u32)
+                                -> Result<(), CreationError> where F: Facade
+{
+    let context = facade.get_context();
+    let version = context.get_version();
+    let extensions = context.get_extensions();
+
+    if !(version >= &Version(Api::Gl, 3, 0) || version >= &Version(Api::GlEs, 3, 0) ||
+         extensions.gl_apple_framebuffer_multisample ||
+         extensions.gl_angle_framebuffer_multisample ||
+         extensions.gl_ext_multisampled_render_to_texture ||
+         extensions.gl_nv_framebuffer_multisample ||
+         (extensions.gl_ext_framebuffer_object && extensions.gl_ext_framebuffer_multisample))
+    {
+        return Err(CreationError::MultisampleNotSupported);
+    }
+
+    // `None` means that we don't know the supported values, in which case we let the driver
+    // decide
+    let supported = context.get_capabilities().internal_formats_renderbuffers.get(&format)
+                           .and_then(|infos| infos.multisamples.as_ref());
+
+    if let Some(supported) = supported {
+        if !supported.iter().any(|&s| s as u32 == samples) {
+            return Err(CreationError::SamplesCountNotSupported);
+        }
+    }
+
+    Ok(())
+}
+
 /// A RenderBuffer of indeterminate type.
 pub struct RenderBufferAny {
     context: Rc<Context>,
@@ -276,12 +413,13 @@ pub struct RenderBufferAny {
     height: u32,
     samples: Option<u32>,
     kind: TextureKind,
+    bits: Option<u16>,
 }
 
 impl RenderBufferAny {
     /// Builds a new render buffer.
-    fn new<F: ?Sized>(facade: &F, format: gl::types::GLenum, kind: TextureKind, width: u32, height: u32,
-              samples: Option<u32>) -> RenderBufferAny
+    fn new<F: ?Sized>(facade: &F, format: gl::types::GLenum, kind: TextureKind, bits: Option<u16>,
+              width: u32, height: u32, samples: Option<u32>) -> RenderBufferAny
         where F: Facade
     {
         unsafe {
@@ -400,6 +538,7 @@ impl RenderBufferAny {
                 height: height,
                 samples: samples,
                 kind: kind,
+                bits: bits,
             }
         }
     }
@@ -428,6 +567,13 @@ impl RenderBufferAny {
     pub fn kind(&self) -> TextureKind {
         self.kind
     }
+
+    /// Returns the number of bits of precision of a depth or stencil render buffer, or `None`
+    /// for other kinds of render buffers.
+    #[inline]
+    pub fn get_depth_or_stencil_bits(&self) -> Option<u16> {
+        self.bits
+    }
 }
 
 impl Drop for RenderBufferAny {
@@ -580,6 +580,105 @@ impl UncompressedFloatFormat {
         }
     }
 
+    /// Returns this format if it is supported by the backend, or else the supported format
+    /// that is the closest to it.
+    ///
+    /// The replacement format always stores the same kind of data (unsigned normalized,
+    /// signed normalized or floating-point), with at least as many components and at least as
+    /// many bits per component. For example `F16F16F16` can be replaced with `F16F16F16F16` or
+    /// `F32F32F32`. Returns `None` if no such format is supported.
+    #[inline]
+    pub fn get_nearest_supported<C: ?Sized>(&self, context: &C) -> Option<UncompressedFloatFormat>
+                                            where C: CapabilitiesSource
+    {
+        self.find_nearest(|format| format.is_supported(context))
+    }
+
+    /// Same as `get_nearest_supported`, but the returned format is also guaranteed to be
+    /// color-renderable.
+    ///
+    /// For example OpenGL ES can't render to `F16F16F16`, in which case `F16F16F16F16` is
+    /// returned instead.
+    #[inline]
+    pub fn get_nearest_renderable<C: ?Sized>(&self, context: &C) -> Option<UncompressedFloatFormat>
+                                             where C: CapabilitiesSource
+    {
+        self.find_nearest(|format| format.is_color_renderable(context))
+    }
+
+    /// Returns this format or the closest format for which `filter` returns true.
+    fn find_nearest<F>(&self, filter: F) -> Option<UncompressedFloatFormat>
+                       where F: Fn(&UncompressedFloatFormat) -> bool
+    {
+        if filter(self) {
+            return Some(*self);
+        }
+
+        let (kind, bits) = self.get_layout();
+
+        let mut candidates = UncompressedFloatFormat::get_formats_list().into_iter()
+                                .filter(|f| f != self)
+                                .filter(|f| {
+                                    let (k, b) = f.get_layout();
+                                    k == kind && b.iter().zip(bits.iter()).all(|(b, r)| b >= r)
+                                })
+                                .collect::<Vec<_>>();
+
+        // we pick the smallest format, and prefer adding components rather than adding
+        // precision, as this keeps the same precision for the existing components
+        candidates.sort_by_key(|f| {
+            let (_, b) = f.get_layout();
+            let total = b.iter().fold(0u32, |t, &b| t + b as u32);
+            let missing_components = b.iter().filter(|&&b| b == 0).count();
+            (total, missing_components)
+        });
+        candidates.into_iter().find(|f| filter(f))
+    }
+
+    /// Returns the kind of data (`0` for unsigned normalized, `1` for signed normalized and
+    /// `2` for floating-point), and the number of bits of the red, green, blue and alpha
+    /// components. Components that don't exist have `0` bits.
+    fn get_layout(&self) -> (u8, [u8; 4]) {
+        match self {
+            &UncompressedFloatFormat::U8 => (0, [8, 0, 0, 0]),
+            &UncompressedFloatFormat::I8 => (1, [8, 0, 0, 0]),
+            &UncompressedFloatFormat::U16 => (0, [16, 0, 0, 0]),
+            &UncompressedFloatFormat::I16 => (1, [16, 0, 0, 0]),
+            &UncompressedFloatFormat::U8U8 => (0, [8, 8, 0, 0]),
+            &UncompressedFloatFormat::I8I8 => (1, [8, 8, 0, 0]),
+            &UncompressedFloatFormat::U16U16 => (0, [16, 16, 0, 0]),
+            &UncompressedFloatFormat::I16I16 => (1, [16, 16, 0, 0]),
+            &UncompressedFloatFormat::U3U3U2 => (0, [3, 3, 2, 0]),
+            &UncompressedFloatFormat::U4U4U4 => (0, [4, 4, 4, 0]),
+            &UncompressedFloatFormat::U5U5U5 => (0, [5, 5, 5, 0]),
+            &UncompressedFloatFormat::U8U8U8 => (0, [8, 8, 8, 0]),
+            &UncompressedFloatFormat::I8I8I8 => (1, [8, 8, 8, 0]),
+            &UncompressedFloatFormat::U10U10U10 => (0, [10, 10, 10, 0]),
+            &UncompressedFloatFormat::U12U12U12 => (0, [12, 12, 12, 0]),
+            &UncompressedFloatFormat::U16U16U16 => (0, [16, 16, 16, 0]),
+            &UncompressedFloatFormat::I16I16I16 => (1, [16, 16, 16, 0]),
+            &UncompressedFloatFormat::U2U2U2U2 => (0, [2, 2, 2, 2]),
+            &UncompressedFloatFormat::U4U4U4U4 => (0, [4, 4, 4, 4]),
+            &UncompressedFloatFormat::U5U5U5U1 => (0, [5, 5, 5, 1]),
+            &UncompressedFloatFormat::U8U8U8U8 => (0, [8, 8, 8, 8]),
+            &UncompressedFloatFormat::I8I8I8I8 => (1, [8, 8, 8, 8]),
+            &UncompressedFloatFormat::U10U10U10U2 => (0, [10, 10, 10, 2]),
+            &UncompressedFloatFormat::U12U12U12U12 => (0, [12, 12, 12, 12]),
+            &UncompressedFloatFormat::U16U16U16U16 => (0, [16, 16, 16, 16]),
+            &UncompressedFloatFormat::I16I16I16I16 => (1, [16, 16, 16, 16]),
+            &UncompressedFloatFormat::F16 => (2, [16, 0, 0, 0]),
+            &UncompressedFloatFormat::F16F16 => (2, [16, 16, 0, 0]),
+            &UncompressedFloatFormat::F16F16F16 => (2, [16, 16, 16, 0]),
+            &UncompressedFloatFormat::F16F16F16F16 => (2, [16, 16, 16, 16]),
+            &UncompressedFloatFormat::F32 => (2, [32, 0, 0, 0]),
+            &UncompressedFloatFormat::F32F32 => (2, [32, 32, 0, 0]),
+            &UncompressedFloatFormat::F32F32F32 => (2, [32, 32, 32, 0]),
+            &UncompressedFloatFormat::F32F32F32F32 => (2, [32, 32, 32, 32]),
+            &UncompressedFloatFormat::F11F11F10 => (2, [11, 11, 10, 0]),
+            &UncompressedFloatFormat::F9F9F9 => (2, [9, 9, 9, 0]),
+        }
+    }
+
     fn to_glenum(&self) -> gl::types::GLenum {
         match self {
             &UncompressedFloatFormat::U8 => gl::R8,
@@ -2270,3 +2369,57 @@ pub fn client_format_to_glenum(context: &Context, client: ClientFormatAny,
         value
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::UncompressedFloatFormat;
+
+    /// Returns the format that replaces `format` if `format` itself isn't available.
+    fn replacement(format: UncompressedFloatFormat) -> Option<UncompressedFloatFormat> {
+        format.find_nearest(|f| *f != format)
+    }
+
+    /// Checks that every component of `replacement` has at least as many bits as in `format`.
+    fn assert_enough_bits(format: UncompressedFloatFormat, replacement: UncompressedFloatFormat) {
+        let (kind, bits) = format.get_layout();
+        let (replacement_kind, replacement_bits) = replacement.get_layout();
+        assert_eq!(kind, replacement_kind);
+        for (b, r) in replacement_bits.iter().zip(bits.iter()) {
+            assert!(b >= r, "{:?} can't replace {:?}", replacement, format);
+        }
+    }
+
+    #[test]
+    fn nearest_u10u10u10u2() {
+        let format = UncompressedFloatFormat::U10U10U10U2;
+        let nearest = replacement(format).unwrap();
+        assert_enough_bits(format, nearest);
+        assert_eq!(nearest, UncompressedFloatFormat::U12U12U12U12);
+    }
+
+    #[test]
+    fn nearest_u5u5u5u1() {
+        let format = UncompressedFloatFormat::U5U5U5U1;
+        let nearest = replacement(format).unwrap();
+        assert_enough_bits(format, nearest);
+        assert!(nearest == UncompressedFloatFormat::U8U8U8U8 ||
+                nearest == UncompressedFloatFormat::U10U10U10U2);
+    }
+
+    #[test]
+    fn nearest_u3u3u2() {
+        let format = UncompressedFloatFormat::U3U3U2;
+        let nearest = replacement(format).unwrap();
+        assert_enough_bits(format, nearest);
+        assert_eq!(nearest, UncompressedFloatFormat::U4U4U4);
+    }
+
+    #[test]
+    fn nearest_formats_have_enough_bits() {
+        for format in UncompressedFloatFormat::get_formats_list() {
+            if let Some(nearest) = replacement(format) {
+                assert_enough_bits(format, nearest);
+            }
+        }
+    }
+}
@@ -66,7 +66,8 @@ result to the user.
    or one or several references to vertex buffers wrapped inside a `PerInstance` struct. See the
    `vertex` module for more infos.
  - **Memory barriers**: Calling `glMemoryBarrier` is automatically handled by glium, however you
-   still need to call `memoryBarrier()` in your GLSL code in some situations.
+   still need to call `memoryBarrier()` in your GLSL code in some situations. Barriers can also
+   be inserted manually with `Context::memory_barrier`.
  - **Programs**: See the `program` module.
  - **Query objects**: The corresponding structs are in the `draw_parameters` module. They are
    passed as draw parameters.
@@ -97,12 +98,22 @@ extern crate backtrace;
 extern crate smallvec;
 extern crate fnv;
 
+#[cfg(feature = "image")]
+extern crate image;
+
+#[cfg(feature = "cgmath")]
+extern crate cgmath;
+#[cfg(feature = "nalgebra")]
+extern crate nalgebra;
+#[cfg(feature = "glam")]
+extern crate glam;
+
 #[cfg(feature = "glutin")]
 pub use backend::glutin_backend::glutin;
 pub use context::Profile;
 pub use draw_parameters::{Blend, BlendingFunction, LinearBlendingFactor, BackfaceCullingMode};
 pub use draw_parameters::{Depth, DepthTest, PolygonMode, DrawParameters, StencilTest, StencilOperation};
-pub use draw_parameters::{Smooth};
+pub use draw_parameters::{Smooth, LogicOperation, PolygonOffset};
 pub use index::IndexBuffer;
 pub use vertex::{VertexBuffer, Vertex, VertexFormat};
 pub use program::{Program, ProgramCreationError};
@@ -250,6 +261,10 @@ trait BufferExt {
     /// `glMemoryBarrier(GL_SHADER_STORAGE_BARRIER_BIT)` if necessary.
     fn prepare_and_bind_for_shared_storage(&self, &mut CommandContext, index: gl::types::GLuint);
 
+    /// Makes sure that the buffer is binded to the indexed `GL_ATOMIC_COUNTER_BUFFER` point and
+    /// calls `glMemoryBarrier(GL_ATOMIC_COUNTER_BARRIER_BIT)` if necessary.
+    fn prepare_and_bind_for_atomic_counter(&self, &mut CommandContext, index: gl::types::GLuint);
+
     /// Binds the buffer to `GL_TRANSFORM_FEEDBACk_BUFFER` regardless of the current transform
     /// feedback object.
     fn bind_to_transform_feedback(&self, &mut CommandContext, index: gl::types::GLuint);
@@ -273,6 +288,18 @@ trait ContextExt {
 
     /// Returns the capabilities of the backend.
     fn capabilities(&self) -> &context::Capabilities;
+
+    /// Returns the program of the program cache that was built from the given source code, if
+    /// the cache is enabled and the program is still alive.
+    fn get_cached_program(&self, vertex_shader: &str, fragment_shader: &str,
+                          geometry_shader: Option<&str>) -> Option<Rc<Program>>;
+
+    /// Inserts a program in the program cache. Does nothing if the cache is disabled.
+    fn cache_program(&self, vertex_shader: &str, fragment_shader: &str,
+                     geometry_shader: Option<&str>, program: &Rc<Program>);
+
+    /// Returns the storage for program binaries set with `Context::set_shader_cache`.
+    fn get_shader_cache(&self) -> Option<Rc<program::ShaderCache>>;
 }
 
 /// Internal trait for programs.
@@ -285,6 +312,11 @@ trait ProgramExt {
     fn set_uniform(&self, ctxt: &mut context::CommandContext, uniform_location: gl::types::GLint,
                    value: &RawUniformValue);
 
+    /// Changes the values of the elements of an array of uniforms, starting at the given
+    /// location.
+    fn set_uniform_array(&self, ctxt: &mut context::CommandContext,
+                         uniform_location: gl::types::GLint, value: &RawUniformArray);
+
     /// Changes the uniform block binding of the program.
     fn set_uniform_block_binding(&self, ctxt: &mut context::CommandContext,
                                  block_location: gl::types::GLuint, value: gl::types::GLuint);
@@ -301,10 +333,15 @@ trait ProgramExt {
 
     fn get_uniform(&self, name: &str) -> Option<&program::Uniform>;
 
+    /// Returns the texture units assigned to the samplers of the program, sorted by unit.
+    fn get_texture_units(&self) -> &[program::TextureUnit];
+
     fn get_uniform_blocks(&self) -> &HashMap<String, program::UniformBlock, BuildHasherDefault<FnvHasher>>;
 
     fn get_shader_storage_blocks(&self) -> &HashMap<String, program::UniformBlock, BuildHasherDefault<FnvHasher>>;
 
+    fn get_atomic_counters(&self) -> &HashMap<String, program::AtomicCounter, BuildHasherDefault<FnvHasher>>;
+
     fn get_subroutine_data(&self) -> &program::SubroutineData;
 }
 
@@ -324,6 +361,9 @@ trait QueryExt {
 
     fn end_conditional_render(ctxt: &mut CommandContext);
 
+    /// Ends conditional rendering if it is currently active with this query.
+    fn end_own_conditional_render(&self);
+
     /// Returns true if the query has never been used.
     fn is_unused(&self) -> bool;
 }
@@ -342,6 +382,25 @@ trait TextureExt {
     /// Makes sure that the texture is binded to the current texture unit and returns the
     /// bind point to use to access the texture (eg. `GL_TEXTURE_2D`, `GL_TEXTURE_3D`, etc.).
     fn bind_to_current(&self, &mut CommandContext) -> gl::types::GLenum;
+
+    /// Calls `glMemoryBarrier(GL_TEXTURE_FETCH_BARRIER_BIT)` if the texture has been written
+    /// through an image unit since the latest barrier.
+    fn prepare_for_texture_fetch(&self, &mut CommandContext);
+
+    /// Calls `glMemoryBarrier(GL_SHADER_IMAGE_ACCESS_BARRIER_BIT)` if necessary before binding
+    /// the texture to an image unit. If `write` is true, the texture is then considered to be
+    /// written by the next draw call.
+    fn prepare_for_image_unit(&self, &mut CommandContext, write: bool);
+
+    /// Calls `glMemoryBarrier(GL_TEXTURE_UPDATE_BARRIER_BIT)` if the texture has been written
+    /// through an image unit since the latest barrier. Must be called before uploading data to
+    /// the texture or downloading data from it.
+    fn prepare_for_texture_update(&self, &mut CommandContext);
+
+    /// Calls `glMemoryBarrier(GL_FRAMEBUFFER_BARRIER_BIT)` if the texture has been written
+    /// through an image unit since the latest barrier. Must be called before reading from the
+    /// texture or drawing to it through a framebuffer.
+    fn prepare_for_framebuffer(&self, &mut CommandContext);
 }
 
 /// Internal trait for textures.
@@ -361,12 +420,22 @@ trait TextureMipmapExt {
 trait TransformFeedbackSessionExt {
     /// Updates the state of OpenGL to make the transform feedback session current.
     ///
-    /// The second parameter must be the primitive type of the input vertex data.
-    fn bind(&self, &mut CommandContext, index::PrimitiveType);
+    /// The second parameter must be the primitive type of the input vertex data. If the session
+    /// is already active or paused, it is resumed so that data continues to be appended.
+    ///
+    /// Returns an error if the session is resumed with a different type of primitives.
+    fn bind(&self, &mut CommandContext, index::PrimitiveType) -> Result<(), DrawError>;
 
-    /// Ensures that transform feedback is disabled.
+    /// Returns true if this session is the one that is active or paused.
+    fn is_current(&self, &CommandContext) -> bool;
+
+    /// Ensures that transform feedback is not active. The current session is paused if
+    /// possible, otherwise it is ended.
     fn unbind(&mut CommandContext);
 
+    /// Ensures that transform feedback is disabled.
+    fn end(&mut CommandContext);
+
     /// Ensures that a buffer isn't used by transform feedback.
     fn ensure_buffer_out_of_transform_feedback(&mut CommandContext, gl::types::GLuint);
 }
@@ -378,6 +447,12 @@ trait UniformsExt {
     /// Will replace texture and buffer bind points.
     fn bind_uniforms<'a, P>(&'a self, &mut CommandContext, &P, &mut Vec<buffer::Inserter<'a>>)
                             -> Result<(), DrawError> where P: ProgramExt;
+
+    /// Updates the values of the uniforms of a program, without binding it if possible.
+    ///
+    /// Textures, blocks and subroutines are ignored.
+    fn set_uniform_values<P>(&self, &mut CommandContext, &P) -> Result<(), DrawError>
+                             where P: ProgramExt;
 }
 
 
@@ -396,6 +471,18 @@ pub enum RawUniformValue {
     Mat3([[gl::types::GLfloat; 3]; 3]),
     /// 4x4 column-major matrix.
     Mat4([[gl::types::GLfloat; 4]; 4]),
+    /// Column-major matrix with 2 columns and 3 rows.
+    Mat2x3([[gl::types::GLfloat; 3]; 2]),
+    /// Column-major matrix with 2 columns and 4 rows.
+    Mat2x4([[gl::types::GLfloat; 4]; 2]),
+    /// Column-major matrix with 3 columns and 2 rows.
+    Mat3x2([[gl::types::GLfloat; 2]; 3]),
+    /// Column-major matrix with 3 columns and 4 rows.
+    Mat3x4([[gl::types::GLfloat; 4]; 3]),
+    /// Column-major matrix with 4 columns and 2 rows.
+    Mat4x2([[gl::types::GLfloat; 2]; 4]),
+    /// Column-major matrix with 4 columns and 3 rows.
+    Mat4x3([[gl::types::GLfloat; 3]; 4]),
     Vec2([gl::types::GLfloat; 2]),
     Vec3([gl::types::GLfloat; 3]),
     Vec4([gl::types::GLfloat; 4]),
@@ -422,6 +509,57 @@ pub enum RawUniformValue {
     UnsignedInt64Vec2([gl::types::GLuint64; 2]),
     UnsignedInt64Vec3([gl::types::GLuint64; 3]),
     UnsignedInt64Vec4([gl::types::GLuint64; 4]),
+
+    /// Handle of a bindless texture.
+    Handle(gl::types::GLuint64),
+}
+
+/// An array of raw values passed with a single call to the count form of `glUniform`.
+#[derive(Copy, Clone, Debug)]
+enum RawUniformArray<'a> {
+    Float(&'a [gl::types::GLfloat]),
+    Vec2(&'a [[gl::types::GLfloat; 2]]),
+    Vec3(&'a [[gl::types::GLfloat; 3]]),
+    Vec4(&'a [[gl::types::GLfloat; 4]]),
+    /// 2x2 column-major matrices.
+    Mat2(&'a [[[gl::types::GLfloat; 2]; 2]]),
+    /// 3x3 column-major matrices.
+    Mat3(&'a [[[gl::types::GLfloat; 3]; 3]]),
+    /// 4x4 column-major matrices.
+    Mat4(&'a [[[gl::types::GLfloat; 4]; 4]]),
+    SignedInt(&'a [gl::types::GLint]),
+    IntVec2(&'a [[gl::types::GLint; 2]]),
+    IntVec3(&'a [[gl::types::GLint; 3]]),
+    IntVec4(&'a [[gl::types::GLint; 4]]),
+    UnsignedInt(&'a [gl::types::GLuint]),
+    UnsignedIntVec2(&'a [[gl::types::GLuint; 2]]),
+    UnsignedIntVec3(&'a [[gl::types::GLuint; 3]]),
+    UnsignedIntVec4(&'a [[gl::types::GLuint; 4]]),
+    Handle(&'a [gl::types::GLuint64]),
+}
+
+impl<'a> RawUniformArray<'a> {
+    /// Returns the number of elements of the array.
+    fn len(&self) -> usize {
+        match *self {
+            RawUniformArray::Float(v) => v.len(),
+            RawUniformArray::Vec2(v) => v.len(),
+            RawUniformArray::Vec3(v) => v.len(),
+            RawUniformArray::Vec4(v) => v.len(),
+            RawUniformArray::Mat2(v) => v.len(),
+            RawUniformArray::Mat3(v) => v.len(),
+            RawUniformArray::Mat4(v) => v.len(),
+            RawUniformArray::SignedInt(v) => v.len(),
+            RawUniformArray::IntVec2(v) => v.len(),
+            RawUniformArray::IntVec3(v) => v.len(),
+            RawUniformArray::IntVec4(v) => v.len(),
+            RawUniformArray::UnsignedInt(v) => v.len(),
+            RawUniformArray::UnsignedIntVec2(v) => v.len(),
+            RawUniformArray::UnsignedIntVec3(v) => v.len(),
+            RawUniformArray::UnsignedIntVec4(v) => v.len(),
+            RawUniformArray::Handle(v) => v.len(),
+        }
+    }
 }
 
 /// Area of a surface in pixels.
@@ -458,6 +596,148 @@ pub struct BlitTarget {
     pub height: i32,
 }
 
+/// Buffers to copy when blitting from a surface to another.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BlitMask {
+    /// Whether to copy the color buffer.
+    pub color: bool,
+    /// Whether to copy the depth buffer.
+    pub depth: bool,
+    /// Whether to copy the stencil buffer.
+    pub stencil: bool,
+}
+
+impl BlitMask {
+    /// Copies only the color buffer.
+    #[inline]
+    pub fn color() -> BlitMask {
+        BlitMask { color: true, depth: false, stencil: false }
+    }
+
+    /// Copies only the depth buffer.
+    #[inline]
+    pub fn depth() -> BlitMask {
+        BlitMask { color: false, depth: true, stencil: false }
+    }
+
+    /// Copies only the stencil buffer.
+    #[inline]
+    pub fn stencil() -> BlitMask {
+        BlitMask { color: false, depth: false, stencil: true }
+    }
+
+    /// Copies the depth and stencil buffers.
+    #[inline]
+    pub fn depth_and_stencil() -> BlitMask {
+        BlitMask { color: false, depth: true, stencil: true }
+    }
+
+    /// Copies the color, depth and stencil buffers.
+    #[inline]
+    pub fn all() -> BlitMask {
+        BlitMask { color: true, depth: true, stencil: true }
+    }
+
+    /// Returns true if no buffer is copied.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        !self.color && !self.depth && !self.stencil
+    }
+}
+
+/// Kinds of accesses that must see the data written by shaders before the barrier.
+///
+/// Each field corresponds to one of the bits of `glMemoryBarrier`. Glium already inserts the
+/// barriers that it knows are needed when buffers or textures written by shaders are used
+/// again, so you only need this if you access these resources in a way that glium can't track,
+/// for example when sharing them with raw OpenGL code. See `Context::memory_barrier`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct MemoryBarrier {
+    /// Vertex attributes sourced from buffers (`GL_VERTEX_ATTRIB_ARRAY_BARRIER_BIT`).
+    pub vertex_attrib_array: bool,
+    /// Indices sourced from buffers (`GL_ELEMENT_ARRAY_BARRIER_BIT`).
+    pub element_array: bool,
+    /// Uniform blocks sourced from buffers (`GL_UNIFORM_BARRIER_BIT`).
+    pub uniform: bool,
+    /// Textures fetched by shaders (`GL_TEXTURE_FETCH_BARRIER_BIT`).
+    pub texture_fetch: bool,
+    /// Image load and store operations (`GL_SHADER_IMAGE_ACCESS_BARRIER_BIT`).
+    pub shader_image_access: bool,
+    /// Indirect draw and dispatch commands (`GL_COMMAND_BARRIER_BIT`).
+    pub command: bool,
+    /// Transfers between pixel buffers and textures (`GL_PIXEL_BUFFER_BARRIER_BIT`).
+    pub pixel_buffer: bool,
+    /// Uploads to textures and downloads from textures (`GL_TEXTURE_UPDATE_BARRIER_BIT`).
+    pub texture_update: bool,
+    /// Writes, copies and mappings of buffers (`GL_BUFFER_UPDATE_BARRIER_BIT`).
+    pub buffer_update: bool,
+    /// Reads and writes through framebuffers (`GL_FRAMEBUFFER_BARRIER_BIT`).
+    pub framebuffer: bool,
+    /// Transform feedback writes (`GL_TRANSFORM_FEEDBACK_BARRIER_BIT`).
+    pub transform_feedback: bool,
+    /// Atomic counters sourced from buffers (`GL_ATOMIC_COUNTER_BARRIER_BIT`).
+    pub atomic_counter: bool,
+    /// Shader storage blocks sourced from buffers (`GL_SHADER_STORAGE_BARRIER_BIT`).
+    pub shader_storage: bool,
+    /// Query results written to buffers (`GL_QUERY_BUFFER_BARRIER_BIT`).
+    pub query_buffer: bool,
+}
+
+impl MemoryBarrier {
+    /// No access at all.
+    #[inline]
+    pub fn none() -> MemoryBarrier {
+        Default::default()
+    }
+
+    /// All kinds of accesses.
+    #[inline]
+    pub fn all() -> MemoryBarrier {
+        MemoryBarrier {
+            vertex_attrib_array: true,
+            element_array: true,
+            uniform: true,
+            texture_fetch: true,
+            shader_image_access: true,
+            command: true,
+            pixel_buffer: true,
+            texture_update: true,
+            buffer_update: true,
+            framebuffer: true,
+            transform_feedback: true,
+            atomic_counter: true,
+            shader_storage: true,
+            query_buffer: true,
+        }
+    }
+
+    /// Returns true if no kind of access is included.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        *self == MemoryBarrier::none()
+    }
+
+    /// Returns the bits to pass to `glMemoryBarrier`.
+    fn to_glbitfield(&self) -> gl::types::GLbitfield {
+        let mut bits = 0;
+        if self.vertex_attrib_array { bits |= gl::VERTEX_ATTRIB_ARRAY_BARRIER_BIT; }
+        if self.element_array { bits |= gl::ELEMENT_ARRAY_BARRIER_BIT; }
+        if self.uniform { bits |= gl::UNIFORM_BARRIER_BIT; }
+        if self.texture_fetch { bits |= gl::TEXTURE_FETCH_BARRIER_BIT; }
+        if self.shader_image_access { bits |= gl::SHADER_IMAGE_ACCESS_BARRIER_BIT; }
+        if self.command { bits |= gl::COMMAND_BARRIER_BIT; }
+        if self.pixel_buffer { bits |= gl::PIXEL_BUFFER_BARRIER_BIT; }
+        if self.texture_update { bits |= gl::TEXTURE_UPDATE_BARRIER_BIT; }
+        if self.buffer_update { bits |= gl::BUFFER_UPDATE_BARRIER_BIT; }
+        if self.framebuffer { bits |= gl::FRAMEBUFFER_BARRIER_BIT; }
+        if self.transform_feedback { bits |= gl::TRANSFORM_FEEDBACK_BARRIER_BIT; }
+        if self.atomic_counter { bits |= gl::ATOMIC_COUNTER_BARRIER_BIT; }
+        if self.shader_storage { bits |= gl::SHADER_STORAGE_BARRIER_BIT; }
+        if self.query_buffer { bits |= gl::QUERY_BUFFER_BARRIER_BIT; }
+        bits
+    }
+}
+
 /// Object that can be drawn upon.
 ///
 /// # What does the GPU do when you draw?
@@ -727,6 +1007,155 @@ pub trait Surface {
         self.clear(None, Some(color), true, Some(depth), Some(stencil));
     }
 
+    /// Clears a single buffer of the target with a value of a specific type.
+    ///
+    /// `index` is the location of the fragment output whose color attachment is cleared, and is
+    /// ignored when clearing the depth or stencil buffer. This is the same value that
+    /// `MultiOutputFrameBuffer::set_read_buffer` expects. Contrary to `clear`, this allows
+    /// clearing the attachments of a multi-output framebuffer to different values, and clearing
+    /// integral attachments.
+    ///
+    /// Returns `NotSupported` if the backend doesn't support clearing individual buffers
+    /// (OpenGL 3.0 or OpenGL ES 3.0 are required). This is also what the default
+    /// implementation returns.
+    #[inline]
+    fn clear_buffer(&mut self, _rect: Option<&Rect>, _index: u32,
+                    _data: framebuffer::ClearBufferData)
+                    -> Result<(), framebuffer::ClearBufferError>
+    {
+        Err(framebuffer::ClearBufferError::NotSupported)
+    }
+
+    /// Clears a floating-point or normalized color attachment of the target. See `clear_buffer`.
+    #[inline]
+    fn clear_color_attachment(&mut self, index: u32, color: (f32, f32, f32, f32))
+        -> Result<(), framebuffer::ClearBufferError>
+    {
+        let data = framebuffer::ClearBufferData::Float([color.0, color.1, color.2, color.3]);
+        self.clear_buffer(None, index, data)
+    }
+
+    /// Clears a signed integral color attachment of the target. See `clear_buffer`.
+    #[inline]
+    fn clear_color_attachment_int(&mut self, index: u32, color: [i32; 4])
+        -> Result<(), framebuffer::ClearBufferError>
+    {
+        self.clear_buffer(None, index, framebuffer::ClearBufferData::Integral(color))
+    }
+
+    /// Clears an unsigned integral color attachment of the target. See `clear_buffer`.
+    #[inline]
+    fn clear_color_attachment_uint(&mut self, index: u32, color: [u32; 4])
+        -> Result<(), framebuffer::ClearBufferError>
+    {
+        self.clear_buffer(None, index, framebuffer::ClearBufferData::Unsigned(color))
+    }
+
+    /// Clears the depth buffer of the target with `glClearBuffer`. See `clear_buffer`.
+    #[inline]
+    fn clear_depth_value(&mut self, value: f32) -> Result<(), framebuffer::ClearBufferError> {
+        self.clear_buffer(None, 0, framebuffer::ClearBufferData::Depth(value))
+    }
+
+    /// Clears the stencil buffer of the target with `glClearBuffer`. See `clear_buffer`.
+    #[inline]
+    fn clear_stencil_value(&mut self, value: i32)
+        -> Result<(), framebuffer::ClearBufferError>
+    {
+        self.clear_buffer(None, 0, framebuffer::ClearBufferData::Stencil(value))
+    }
+
+    /// Hints the backend that the content of some buffers of the target doesn't need to be
+    /// preserved.
+    ///
+    /// `colors` contains the indices of the color attachments to invalidate, or is `None` to
+    /// invalidate all of them. The content of the invalidated buffers becomes undefined.
+    ///
+    /// Doing this when you are going to entirely overwrite a buffer, or at the end of a frame
+    /// for buffers that are not going to be read again (like the depth buffer), allows tiled
+    /// GPUs to avoid loading or storing their content. This does nothing if the backend
+    /// doesn't support invalidating.
+    ///
+    /// Since invalidating is only a hint, the default implementation does nothing.
+    #[inline]
+    fn invalidate(&mut self, _colors: Option<&[u32]>, _depth: bool, _stencil: bool) {
+    }
+
+    /// Invalidates a color attachment of the target. See `invalidate`.
+    #[inline]
+    fn invalidate_color(&mut self, index: u32) {
+        self.invalidate(Some(&[index][..]), false, false);
+    }
+
+    /// Invalidates the depth buffer of the target. See `invalidate`.
+    #[inline]
+    fn invalidate_depth(&mut self) {
+        self.invalidate(Some(&[][..]), true, false);
+    }
+
+    /// Invalidates the stencil buffer of the target. See `invalidate`.
+    #[inline]
+    fn invalidate_stencil(&mut self) {
+        self.invalidate(Some(&[][..]), false, true);
+    }
+
+    /// Invalidates the depth and stencil buffers of the target. See `invalidate`.
+    #[inline]
+    fn invalidate_depth_and_stencil(&mut self) {
+        self.invalidate(Some(&[][..]), true, true);
+    }
+
+    /// Invalidates all the buffers of the target. See `invalidate`.
+    #[inline]
+    fn invalidate_all(&mut self) {
+        self.invalidate(None, true, true);
+    }
+
+    /// Reads the content of a rectangle of the target.
+    ///
+    /// The pixels are read from the first color attachment of the target, or from the back
+    /// buffer when drawing to the default framebuffer. The format of the result is determined
+    /// by `P`, for example `(u8, u8, u8, u8)` or `(f32, f32, f32, f32)`. Integral attachments
+    /// must be read with an integral format.
+    ///
+    /// Reading only the region you are interested in, like the pixel under the cursor when doing
+    /// GPU picking, avoids transferring the whole framebuffer.
+    ///
+    /// OpenGL ES only guarantees that `(u8, u8, u8, u8)` is supported. An error is returned
+    /// if the format is not supported by the backend.
+    ///
+    /// The default implementation returns `ReadError::NotSupported`.
+    #[inline]
+    fn read_pixels<P, T>(&self, _rect: &Rect) -> Result<T, framebuffer::ReadError>
+                         where P: texture::PixelValue, T: texture::Texture2dDataSink<P>
+    {
+        Err(framebuffer::ReadError::NotSupported)
+    }
+
+    /// Reads the whole content of the target as RGBA bytes.
+    ///
+    /// This reads the same pixels as `read_pixels`. The first row of the image is the bottom
+    /// of the target, like everywhere else in glium, and rows are tightly packed whatever the
+    /// width of the target. The values are returned as they are stored: if the target contains
+    /// sRGB colors, for example because it is the default framebuffer or an sRGB texture, then
+    /// the data is directly usable as an sRGB image.
+    fn capture(&self) -> Result<texture::RawImage2d<'static, u8>, framebuffer::ReadError> {
+        let (width, height) = self.get_dimensions();
+        let rect = Rect { left: 0, bottom: 0, width: width, height: height };
+        self.read_pixels::<(u8, u8, u8, u8), _>(&rect)
+    }
+
+    /// Reads the whole content of the target into an RGBA image of the `image` crate.
+    ///
+    /// Same as `capture`, except that the image is flipped so that its first row is the top of
+    /// the target. Only available if the `image` feature is enabled.
+    #[cfg(feature = "image")]
+    fn to_image(&self) -> Result<image::DynamicImage, framebuffer::ReadError> {
+        let (width, height) = self.get_dimensions();
+        let rect = Rect { left: 0, bottom: 0, width: width, height: height };
+        self.read_pixels::<(u8, u8, u8, u8), _>(&rect)
+    }
+
     /// Returns the dimensions in pixels of the target.
     fn get_dimensions(&self) -> (u32, u32);
 
@@ -762,19 +1191,139 @@ pub trait Surface {
         V: vertex::MultiVerticesSource<'b>, I: Into<index::IndicesSource<'a>>,
         U: uniforms::Uniforms;
 
+    /// Calls `f` with a surface whose draw commands are only executed if `query` has passed.
+    ///
+    /// This is an alternative to setting the `condition` member of the draw parameters of
+    /// each draw command, and allows a whole group of draw commands to be predicated on the
+    /// same query. See `ConditionalRendering` for the meaning of `wait` and `per_region`.
+    ///
+    /// Requires OpenGL 3.0 or `GL_NV_conditional_render`, otherwise the draw commands return
+    /// a `ConditionalRenderingNotSupported` error.
+    ///
+    /// ## Example
+    ///
+    /// ```no_run
+    /// # use glium::Surface;
+    /// # let display: glium::Display = unsafe { std::mem::uninitialized() };
+    /// # let mut target = display.draw();
+    /// # let query: glium::draw_parameters::AnySamplesPassedQuery = unsafe {
+    /// #     std::mem::uninitialized() };
+    /// target.conditional(&query, true, false, |surface| {
+    ///     // surface.draw(...);
+    /// });
+    /// # target.finish().unwrap();
+    /// ```
+    fn conditional<'q, Q, F, R>(&mut self, query: Q, wait: bool, per_region: bool, f: F) -> R
+        where Q: Into<draw_parameters::SamplesQueryParam<'q>>, Self: Sized,
+              F: for<'s> FnOnce(&mut framebuffer::ConditionalSurface<'s, 'q, Self>) -> R
+    {
+        let condition = draw_parameters::ConditionalRendering {
+            query: query.into(),
+            wait: wait,
+            per_region: per_region,
+        };
+
+        let mut surface = framebuffer::ConditionalSurface::new(self, condition);
+        f(&mut surface)
+    }
+
     /// Blits from the default framebuffer.
+    ///
+    /// The default implementation calls `blit_buffers_from_frame`.
+    #[inline]
     fn blit_from_frame(&self, source_rect: &Rect, target_rect: &BlitTarget,
-                       filter: uniforms::MagnifySamplerFilter);
+                       filter: uniforms::MagnifySamplerFilter)
+    {
+        self.blit_buffers_from_frame(source_rect, target_rect, filter, BlitMask::color())
+    }
 
     /// Blits from a simple framebuffer.
+    ///
+    /// The default implementation calls `blit_buffers_from_simple_framebuffer`.
+    #[inline]
     fn blit_from_simple_framebuffer(&self, source: &framebuffer::SimpleFrameBuffer,
                                     source_rect: &Rect, target_rect: &BlitTarget,
-                                    filter: uniforms::MagnifySamplerFilter);
+                                    filter: uniforms::MagnifySamplerFilter)
+    {
+        self.blit_buffers_from_simple_framebuffer(source, source_rect, target_rect, filter,
+                                                  BlitMask::color())
+    }
 
     /// Blits from a multi-output framebuffer.
+    ///
+    /// The default implementation calls `blit_buffers_from_multioutput_framebuffer`.
+    #[inline]
     fn blit_from_multioutput_framebuffer(&self, source: &framebuffer::MultiOutputFrameBuffer,
                                          source_rect: &Rect, target_rect: &BlitTarget,
-                                         filter: uniforms::MagnifySamplerFilter);
+                                         filter: uniforms::MagnifySamplerFilter)
+    {
+        self.blit_buffers_from_multioutput_framebuffer(source, source_rect, target_rect, filter,
+                                                       BlitMask::color())
+    }
+
+    /// Blits some buffers from the default framebuffer.
+    ///
+    /// The default implementation only supports copying the color buffer and calls
+    /// `blit_from_frame`. Implementations must override either this method or
+    /// `blit_from_frame`.
+    ///
+    /// ## Panic
+    ///
+    /// The default implementation panics if `mask` contains the depth or stencil buffer.
+    #[inline]
+    fn blit_buffers_from_frame(&self, source_rect: &Rect, target_rect: &BlitTarget,
+                               filter: uniforms::MagnifySamplerFilter, mask: BlitMask)
+    {
+        assert!(!mask.depth && !mask.stencil,
+                "This surface doesn't support blitting the depth or stencil buffer");
+        if mask.color {
+            self.blit_from_frame(source_rect, target_rect, filter)
+        }
+    }
+
+    /// Blits some buffers from a simple framebuffer.
+    ///
+    /// The default implementation only supports copying the color buffer and calls
+    /// `blit_from_simple_framebuffer`. Implementations must override either this method or
+    /// `blit_from_simple_framebuffer`.
+    ///
+    /// ## Panic
+    ///
+    /// The default implementation panics if `mask` contains the depth or stencil buffer.
+    #[inline]
+    fn blit_buffers_from_simple_framebuffer(&self, source: &framebuffer::SimpleFrameBuffer,
+                                            source_rect: &Rect, target_rect: &BlitTarget,
+                                            filter: uniforms::MagnifySamplerFilter, mask: BlitMask)
+    {
+        assert!(!mask.depth && !mask.stencil,
+                "This surface doesn't support blitting the depth or stencil buffer");
+        if mask.color {
+            self.blit_from_simple_framebuffer(source, source_rect, target_rect, filter)
+        }
+    }
+
+    /// Blits some buffers from a multi-output framebuffer.
+    ///
+    /// The default implementation only supports copying the color buffer and calls
+    /// `blit_from_multioutput_framebuffer`. Implementations must override either this method
+    /// or `blit_from_multioutput_framebuffer`.
+    ///
+    /// ## Panic
+    ///
+    /// The default implementation panics if `mask` contains the depth or stencil buffer.
+    #[inline]
+    fn blit_buffers_from_multioutput_framebuffer(&self,
+                                                 source: &framebuffer::MultiOutputFrameBuffer,
+                                                 source_rect: &Rect, target_rect: &BlitTarget,
+                                                 filter: uniforms::MagnifySamplerFilter,
+                                                 mask: BlitMask)
+    {
+        assert!(!mask.depth && !mask.stencil,
+                "This surface doesn't support blitting the depth or stencil buffer");
+        if mask.color {
+            self.blit_from_multioutput_framebuffer(source, source_rect, target_rect, filter)
+        }
+    }
 
     /// Copies a rectangle of pixels from this surface to another surface.
     ///
@@ -788,8 +1337,76 @@ pub trait Surface {
     ///
     /// Note that there is no alpha blending, depth/stencil checking, etc. This function just
     /// copies pixels.
+    ///
+    /// The default implementation calls `blit_buffers`.
+    #[inline]
     fn blit_color<S>(&self, source_rect: &Rect, target: &S, target_rect: &BlitTarget,
-                     filter: uniforms::MagnifySamplerFilter) where S: Surface;
+                     filter: uniforms::MagnifySamplerFilter) where S: Surface
+    {
+        self.blit_buffers(source_rect, target, target_rect, filter, BlitMask::color())
+    }
+
+    /// Copies a rectangle of the buffers designated by `mask` from this surface to
+    /// another surface. See `blit_color`.
+    ///
+    /// Depth and stencil values are copied as they are. The source and the target must have
+    /// depth or stencil buffers of the same format for the copy to be valid. `filter` only
+    /// applies to the color buffer, as the depth and stencil buffers are always copied with
+    /// the `Nearest` filter.
+    ///
+    /// The default implementation only supports copying the color buffer and calls
+    /// `blit_color`. Implementations must override either this method or `blit_color`.
+    ///
+    /// ## Panic
+    ///
+    /// The default implementation panics if `mask` contains the depth or stencil buffer.
+    #[inline]
+    fn blit_buffers<S>(&self, source_rect: &Rect, target: &S, target_rect: &BlitTarget,
+                       filter: uniforms::MagnifySamplerFilter, mask: BlitMask) where S: Surface
+    {
+        assert!(!mask.depth && !mask.stencil,
+                "This surface doesn't support blitting the depth or stencil buffer");
+        if mask.color {
+            self.blit_color(source_rect, target, target_rect, filter)
+        }
+    }
+
+    /// Copies a rectangle of pixels from another surface to this surface. See `blit_color`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use glium::Surface;
+    /// # let display: glium::Display = unsafe { std::mem::uninitialized() };
+    /// # let texture: glium::Texture2d = unsafe { std::mem::uninitialized() };
+    /// let source = texture.as_surface();
+    /// let target = display.draw();
+    ///
+    /// // stretches the top-left quarter of the texture over the whole window
+    /// let (w, h) = source.get_dimensions();
+    /// let (tw, th) = target.get_dimensions();
+    /// target.blit_from(&source,
+    ///                  &glium::Rect { left: 0, bottom: h / 2, width: w / 2, height: h / 2 },
+    ///                  &glium::BlitTarget { left: 0, bottom: 0, width: tw as i32, height: th as i32 },
+    ///                  glium::uniforms::MagnifySamplerFilter::Linear);
+    /// target.finish().unwrap();
+    /// ```
+    #[inline]
+    fn blit_from<S>(&self, source: &S, source_rect: &Rect, target_rect: &BlitTarget,
+                    filter: uniforms::MagnifySamplerFilter) where S: Surface, Self: Sized
+    {
+        source.blit_color(source_rect, self, target_rect, filter)
+    }
+
+    /// Copies a rectangle of the buffers designated by `mask` from another surface to this
+    /// surface. See `blit_buffers`.
+    #[inline]
+    fn blit_buffers_from<S>(&self, source: &S, source_rect: &Rect, target_rect: &BlitTarget,
+                            filter: uniforms::MagnifySamplerFilter, mask: BlitMask)
+                            where S: Surface, Self: Sized
+    {
+        source.blit_buffers(source_rect, self, target_rect, filter, mask)
+    }
 
     /// Copies the entire surface to a target surface. See `blit_color`.
     #[inline]
@@ -834,6 +1451,19 @@ pub enum DrawError {
     /// not used by the program.
     AttributeMissing,
 
+    /// An attribute of the vertex shader is missing from the vertex formats of the vertices
+    /// sources, or doesn't have the same number of components.
+    VertexInterfaceMismatch {
+        /// Name of the attribute.
+        name: String,
+        /// Location of the attribute in the program.
+        location: i32,
+        /// Type of the attribute in the program.
+        expected: vertex::AttributeType,
+        /// Type of the attribute in the vertex format, or `None` if it is missing.
+        obtained: Option<vertex::AttributeType>,
+    },
+
     /// The viewport's dimensions are not supported by the backend.
     ViewportTooLarge,
 
@@ -902,6 +1532,17 @@ pub enum DrawError {
     /// Using a program which contains tessellation shaders, but without submitting patches.
     TessellationWithoutPatches,
 
+    /// Submitting patches, but the program doesn't contain a tessellation evaluation shader.
+    PatchesWithoutTessellation,
+
+    /// The type of primitives being drawn doesn't match the input of the geometry shader.
+    GeometryInputPrimitivesMismatch {
+        /// The input expected by the geometry shader.
+        expected: program::GeometryInputPrimitives,
+        /// The primitives that were drawn.
+        obtained: index::PrimitiveType,
+    },
+
     /// Trying to use a sampler, but they are not supported by the backend.
     SamplersNotSupported,
 
@@ -914,6 +1555,13 @@ pub enum DrawError {
     /// You requested not to draw primitives, but this is not supported by the backend.
     TransformFeedbackNotSupported,
 
+    /// The type of primitives written by this draw command is not the same as the type of
+    /// primitives written by the previous draw commands of the transform feedback session.
+    TransformFeedbackPrimitivesMismatch,
+
+    /// The program outputs quads, which can't be captured by transform feedback.
+    TransformFeedbackQuadsNotSupported,
+
     /// See the documentation of the `draw_parameters` module for infos.
     WrongQueryOperation,
 
@@ -931,6 +1579,95 @@ pub enum DrawError {
 
     /// One of the blending parameters is not supported by the backend.
     BlendingParameterNotSupported,
+
+    /// Blending can't be set for each color attachment separately with this backend, or there
+    /// are more elements than draw buffers.
+    PerAttachmentBlendingNotSupported,
+
+    /// The color mask can't be set for each color attachment separately with this backend, or
+    /// there are more elements than draw buffers.
+    PerAttachmentColorMaskNotSupported,
+
+    /// The blending reads the second output of the fragment shader, but the program doesn't
+    /// bind any output to the index `1`.
+    DualSourceOutputMissing,
+
+    /// The blending reads the second output of the fragment shader, but the framebuffer has
+    /// more color attachments than `Capabilities::max_dual_source_draw_buffers`.
+    TooManyDualSourceDrawBuffers,
+
+    /// Logic operations are not supported by the backend.
+    LogicOperationNotSupported,
+
+    /// Per-sample shading is not supported by the backend.
+    SampleShadingNotSupported,
+
+    /// The polygon offset was enabled for lines or points, but the backend only supports it
+    /// for filled polygons.
+    PolygonOffsetNotSupported,
+
+    /// A polygon mode other than `Fill` was requested, but the backend doesn't support it.
+    PolygonModeNotSupported,
+
+    /// Enabling or disabling the sRGB conversion is not supported by the backend.
+    FramebufferSrgbNotSupported,
+
+    /// The stencil parameters differ between clockwise and counter-clockwise faces, but the
+    /// backend doesn't support two-sided stencil (OpenGL 2.0 or OpenGL ES 2.0).
+    StencilSeparateNotSupported,
+
+    /// Conditional rendering was requested, but the backend doesn't support it (OpenGL 3.0 or
+    /// `GL_NV_conditional_render`).
+    ConditionalRenderingNotSupported,
+
+    /// A fixed point size was requested, but the backend only supports writing the size of
+    /// points to `gl_PointSize` (OpenGL ES).
+    FixedPointSizeNotSupported,
+
+    /// Multisampling was disabled, but the backend doesn't support disabling it (OpenGL ES).
+    MultisamplingNotSupported,
+
+    /// Color clamping was requested, but the backend doesn't support it (OpenGL 3.0 with the
+    /// compatibility profile).
+    ColorClampingNotSupported,
+
+    /// The requested origin of `gl_PointCoord` is not supported by the backend.
+    PointSpriteOriginNotSupported,
+
+    /// Viewport, scissor or depth range arrays were requested, but the backend doesn't support
+    /// them (OpenGL 4.1 or `GL_ARB_viewport_array`).
+    ViewportArrayNotSupported,
+
+    /// More viewports or scissor boxes were passed than the maximum supported by the backend.
+    TooManyViewports,
+
+    /// More images were bound to the program than `Capabilities::max_image_units`.
+    TooManyImageUnits,
+
+    /// A double-precision value was passed to a uniform, but the backend doesn't support
+    /// double precision (OpenGL 4.0 or `GL_ARB_gpu_shader_fp64`).
+    DoublePrecisionNotSupported {
+        /// Name of the uniform.
+        name: String,
+    },
+
+    /// The handle of a bindless texture was passed to a uniform, but the texture is not
+    /// resident.
+    TextureHandleNotResident {
+        /// Name of the uniform.
+        name: String,
+    },
+
+    /// The offset of the buffer slice bound to a uniform or shader storage block is not a
+    /// multiple of the alignment required by the backend.
+    BufferOffsetNotAligned {
+        /// Name of the block.
+        name: String,
+        /// Offset of the slice in bytes.
+        offset: usize,
+        /// Alignment required by the backend in bytes.
+        alignment: usize,
+    },
 }
 
 impl Error for DrawError {
@@ -943,6 +1680,8 @@ impl Error for DrawError {
                 "The type of a vertex attribute in the vertices source doesn't match what the program requires",
             AttributeMissing =>
                 "One of the attributes required by the program is missing from the vertex format",
+            VertexInterfaceMismatch { .. } =>
+                "An attribute of the vertex shader doesn't match the vertex format",
             ViewportTooLarge =>
                 "The viewport's dimensions are not supported by the backend",
             InvalidDepthRange =>
@@ -967,6 +1706,10 @@ impl Error for DrawError {
                 "Trying to use tessellation, but this is not supported by the underlying hardware",
             TessellationWithoutPatches =>
                 "Using a program which contains tessellation shaders, but without submitting patches",
+            PatchesWithoutTessellation =>
+                "Submitting patches, but the program doesn't contain a tessellation evaluation shader",
+            GeometryInputPrimitivesMismatch { .. } =>
+                "The type of primitives being drawn doesn't match the input of the geometry shader",
             SamplersNotSupported => "
                 Trying to use a sampler, but they are not supported by the backend",
             InstancesCountMismatch =>
@@ -975,6 +1718,10 @@ impl Error for DrawError {
                 "If you don't use indices, then all vertices sources must have the same size",
             TransformFeedbackNotSupported =>
                 "Requested not to draw primitves, but this is not supported by the backend",
+            TransformFeedbackPrimitivesMismatch =>
+                "The type of primitives can't change during a transform feedback session",
+            TransformFeedbackQuadsNotSupported =>
+                "The program outputs quads, which can't be captured by transform feedback",
             WrongQueryOperation =>
                 "Wrong query operation",
             SmoothingNotSupported =>
@@ -987,6 +1734,48 @@ impl Error for DrawError {
                 "The depth clamp mode is not supported by the backend",
             BlendingParameterNotSupported =>
                 "One the blending parameters is not supported by the backend",
+            PerAttachmentBlendingNotSupported =>
+                "Per-attachment blending is not supported by the backend",
+            PerAttachmentColorMaskNotSupported =>
+                "Per-attachment color masks are not supported by the backend",
+            DualSourceOutputMissing =>
+                "The blending uses dual-source factors, but the program has no output with index 1",
+            TooManyDualSourceDrawBuffers =>
+                "The blending uses dual-source factors with too many draw buffers",
+            LogicOperationNotSupported =>
+                "Logic operations are not supported by the backend",
+            SampleShadingNotSupported =>
+                "Per-sample shading is not supported by the backend",
+            PolygonOffsetNotSupported =>
+                "Polygon offset for lines and points is not supported by the backend",
+            PolygonModeNotSupported =>
+                "The requested polygon mode is not supported by the backend",
+            FramebufferSrgbNotSupported =>
+                "Enabling or disabling the sRGB conversion is not supported by the backend",
+            StencilSeparateNotSupported =>
+                "Two-sided stencil is not supported by the backend",
+            ConditionalRenderingNotSupported =>
+                "Conditional rendering is not supported by the backend",
+            FixedPointSizeNotSupported =>
+                "A fixed point size is not supported by the backend",
+            MultisamplingNotSupported =>
+                "Disabling multisampling is not supported by the backend",
+            ColorClampingNotSupported =>
+                "Controlling the clamping of colors is not supported by the backend",
+            PointSpriteOriginNotSupported =>
+                "The requested point sprite origin is not supported by the backend",
+            ViewportArrayNotSupported =>
+                "Viewport arrays are not supported by the backend",
+            TooManyViewports =>
+                "The number of viewports exceeds the maximum supported by the backend",
+            TooManyImageUnits =>
+                "The number of images exceeds the number of image units of the backend",
+            DoublePrecisionNotSupported { .. } =>
+                "Double-precision uniforms are not supported by the backend",
+            TextureHandleNotResident { .. } =>
+                "The texture of a bindless texture handle is not resident",
+            BufferOffsetNotAligned { .. } =>
+                "The offset of the buffer slice bound to a block is not correctly aligned",
         }
     }
 
@@ -1026,6 +1815,39 @@ impl fmt::Display for DrawError {
                     self.description(),
                     name,
                 ),
+            DoublePrecisionNotSupported { ref name } =>
+                write!(
+                    fmt,
+                    "{}: {}",
+                    self.description(),
+                    name,
+                ),
+            TextureHandleNotResident { ref name } =>
+                write!(
+                    fmt,
+                    "{}: {}",
+                    self.description(),
+                    name,
+                ),
+            BufferOffsetNotAligned { ref name, offset, alignment } =>
+                write!(
+                    fmt,
+                    "{}: {} (offset {} is not a multiple of {})",
+                    self.description(),
+                    name,
+                    offset,
+                    alignment,
+                ),
+            VertexInterfaceMismatch { ref name, location, expected, obtained } =>
+                write!(
+                    fmt,
+                    "{}: `{}` (location {}), expected: {:?}, got: {:?}",
+                    self.description(),
+                    name,
+                    location,
+                    expected,
+                    obtained,
+                ),
             UniformBlockLayoutMismatch { ref name, ref err } =>
                 write!(
                     fmt,
@@ -1034,6 +1856,14 @@ impl fmt::Display for DrawError {
                     name,
                     err,
                 ),
+            GeometryInputPrimitivesMismatch { ref expected, ref obtained } =>
+                write!(
+                    fmt,
+                    "{}, expected: {:?}, got: {:?}",
+                    self.description(),
+                    expected,
+                    obtained,
+                ),
             _ =>
                 write!(fmt, "{}", self.description()),
         }
@@ -1101,6 +1931,12 @@ impl Frame {
         }
     }
 
+    /// Returns the characteristics of the default framebuffer, like its number of samples.
+    #[inline]
+    pub fn get_properties(&self) -> framebuffer::DefaultFramebufferProperties {
+        self.context.get_default_framebuffer_properties()
+    }
+
     /// Stop drawing, swap the buffers, and consume the Frame.
     ///
     /// See the documentation of `SwapBuffersError` about what is being returned.
@@ -1132,6 +1968,26 @@ impl Surface for Frame {
         ops::clear(&self.context, None, rect, color, color_srgb, depth, stencil);
     }
 
+    #[inline]
+    fn clear_buffer(&mut self, rect: Option<&Rect>, index: u32,
+                    data: framebuffer::ClearBufferData)
+                    -> Result<(), framebuffer::ClearBufferError>
+    {
+        ops::clear_buffer(&self.context, None, rect, index, data)
+    }
+
+    #[inline]
+    fn invalidate(&mut self, colors: Option<&[u32]>, depth: bool, stencil: bool) {
+        ops::invalidate(&self.context, None, colors, depth, stencil);
+    }
+
+    #[inline]
+    fn read_pixels<P, T>(&self, rect: &Rect) -> Result<T, framebuffer::ReadError>
+                         where P: texture::PixelValue, T: texture::Texture2dDataSink<P>
+    {
+        ops::read_to_sink(&self.context, ops::Source::DefaultFramebuffer(gl::BACK), rect)
+    }
+
     fn get_dimensions(&self) -> (u32, u32) {
         self.dimensions
     }
@@ -1174,36 +2030,36 @@ impl Surface for Frame {
     }
 
     #[inline]
-    fn blit_color<S>(&self, source_rect: &Rect, target: &S, target_rect: &BlitTarget,
-                     filter: uniforms::MagnifySamplerFilter) where S: Surface
+    fn blit_buffers<S>(&self, source_rect: &Rect, target: &S, target_rect: &BlitTarget,
+                       filter: uniforms::MagnifySamplerFilter, mask: BlitMask) where S: Surface
     {
-        target.blit_from_frame(source_rect, target_rect, filter)
+        target.blit_buffers_from_frame(source_rect, target_rect, filter, mask)
     }
 
     #[inline]
-    fn blit_from_frame(&self, source_rect: &Rect, target_rect: &BlitTarget,
-                       filter: uniforms::MagnifySamplerFilter)
+    fn blit_buffers_from_frame(&self, source_rect: &Rect, target_rect: &BlitTarget,
+                               filter: uniforms::MagnifySamplerFilter, mask: BlitMask)
     {
         ops::blit(&self.context, None, self.get_attachments(),
-                  gl::COLOR_BUFFER_BIT, source_rect, target_rect, filter.to_glenum())
+                  mask, source_rect, target_rect, filter)
     }
 
     #[inline]
-    fn blit_from_simple_framebuffer(&self, source: &framebuffer::SimpleFrameBuffer,
-                                    source_rect: &Rect, target_rect: &BlitTarget,
-                                    filter: uniforms::MagnifySamplerFilter)
+    fn blit_buffers_from_simple_framebuffer(&self, source: &framebuffer::SimpleFrameBuffer,
+                                            source_rect: &Rect, target_rect: &BlitTarget,
+                                            filter: uniforms::MagnifySamplerFilter, mask: BlitMask)
     {
         ops::blit(&self.context, source.get_attachments(), self.get_attachments(),
-                  gl::COLOR_BUFFER_BIT, source_rect, target_rect, filter.to_glenum())
+                  mask, source_rect, target_rect, filter)
     }
 
     #[inline]
-    fn blit_from_multioutput_framebuffer(&self, source: &framebuffer::MultiOutputFrameBuffer,
-                                         source_rect: &Rect, target_rect: &BlitTarget,
-                                         filter: uniforms::MagnifySamplerFilter)
+    fn blit_buffers_from_multioutput_framebuffer(&self, source: &framebuffer::MultiOutputFrameBuffer,
+                                                 source_rect: &Rect, target_rect: &BlitTarget,
+                                                 filter: uniforms::MagnifySamplerFilter, mask: BlitMask)
     {
         ops::blit(&self.context, source.get_attachments(), self.get_attachments(),
-                  gl::COLOR_BUFFER_BIT, source_rect, target_rect, filter.to_glenum())
+                  mask, source_rect, target_rect, filter)
     }
 }
 
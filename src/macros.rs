@@ -36,6 +36,9 @@ macro_rules! assert_no_gl_error {
 
 /// Returns an implementation-defined type which implements the `Uniform` trait.
 ///
+/// The name of a uniform can also be a string literal, which allows setting the members of
+/// structs and the elements of arrays.
+///
 /// ## Example
 ///
 /// ```rust
@@ -44,7 +47,8 @@ macro_rules! assert_no_gl_error {
 /// # fn main() {
 /// let uniforms = uniform! {
 ///     color: [1.0, 1.0, 0.0, 1.0],
-///     some_value: 12i32
+///     some_value: 12i32,
+///     "lights[2].position": [0.0, 5.0, 0.0f32]
 /// };
 /// # }
 /// ```
@@ -54,21 +58,30 @@ macro_rules! uniform {
         $crate::uniforms::EmptyUniforms
     };
 
-    ($field:ident: $value:expr) => {
-        $crate::uniforms::UniformsStorage::new(stringify!($field), $value)
+    (@name $field:ident) => {
+        stringify!($field)
+    };
+
+    (@name $field:expr) => {
+        $field
     };
 
-    ($field1:ident: $value1:expr, $($field:ident: $value:expr),+) => {
+    ($field:tt: $value:expr) => {
+        $crate::uniforms::UniformsStorage::new(uniform!(@name $field), $value)
+    };
+
+    ($field1:tt: $value1:expr, $($field:tt: $value:expr),+) => {
         {
-            let uniforms = $crate::uniforms::UniformsStorage::new(stringify!($field1), $value1);
+            let uniforms = $crate::uniforms::UniformsStorage::new(uniform!(@name $field1),
+                                                                  $value1);
             $(
-                let uniforms = uniforms.add(stringify!($field), $value);
+                let uniforms = uniforms.add(uniform!(@name $field), $value);
             )+
             uniforms
         }
     };
 
-    ($($field:ident: $value:expr),*,) => {
+    ($($field:tt: $value:expr),*,) => {
         uniform!($($field: $value),*)
     };
 }
@@ -383,12 +396,96 @@ macro_rules! implement_uniform_block {
     );
 }
 
+/// Implements the `glium::uniforms::StdLayout` trait for the given type.
+///
+/// The parameters must be the name of the struct and the names of its fields, in the same order
+/// as the members of the block. The offsets of the fields in the buffer are computed with the
+/// `std140` or `std430` rules, regardless of the layout of the Rust struct.
+///
+/// ## Example
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate glium;
+/// # fn main() {
+/// #[derive(Copy, Clone)]
+/// struct Material {
+///     shininess: f32,
+///     ambient: [f32; 3],
+///     diffuse: [f32; 3],
+/// }
+///
+/// implement_std_layout!(Material, shininess, ambient, diffuse);
+/// # }
+/// ```
+///
+#[macro_export]
+macro_rules! implement_std_layout {
+    ($struct_name:ident, $($field_name:ident),+) => (
+        impl $crate::uniforms::StdLayout for $struct_name {
+            #[inline]
+            fn std_kind() -> $crate::uniforms::StdLayoutKind {
+                $crate::uniforms::StdLayoutKind::Struct
+            }
+
+            fn std_alignment(rules: $crate::uniforms::LayoutRules) -> usize {
+                let mut layout = $crate::uniforms::StdStructLayout::new(rules);
+                $(
+                    layout.push(|s: &$struct_name| &s.$field_name);
+                )+
+                layout.alignment()
+            }
+
+            fn std_size(rules: $crate::uniforms::LayoutRules) -> usize {
+                let mut layout = $crate::uniforms::StdStructLayout::new(rules);
+                $(
+                    layout.push(|s: &$struct_name| &s.$field_name);
+                )+
+                layout.size()
+            }
+
+            fn write_std(&self, rules: $crate::uniforms::LayoutRules, output: &mut [u8]) {
+                let mut layout = $crate::uniforms::StdStructLayout::new(rules);
+                $(
+                    let offset = layout.push(|s: &$struct_name| &s.$field_name);
+                    $crate::uniforms::StdLayout::write_std(&self.$field_name, rules,
+                                                           &mut output[offset ..]);
+                )+
+            }
+
+            fn std_members(rules: $crate::uniforms::LayoutRules, name: &str, offset: usize,
+                           members: &mut Vec<(String, usize, usize)>)
+            {
+                let mut layout = $crate::uniforms::StdStructLayout::new(rules);
+                $(
+                    let field_offset = layout.push(|s: &$struct_name| &s.$field_name);
+                    let field_name = if name.is_empty() {
+                        stringify!($field_name).to_owned()
+                    } else {
+                        format!("{}.{}", name, stringify!($field_name))
+                    };
+                    layout.members(|s: &$struct_name| &s.$field_name, &field_name,
+                                   offset + field_offset, members);
+                )+
+            }
+        }
+    );
+
+    ($struct_name:ident, $($field_name:ident),+,) => (
+        implement_std_layout!($struct_name, $($field_name),+);
+    );
+}
+
 /// Builds a program depending on the GLSL version supported by the backend.
 ///
 /// This is implemented with successive calls to `is_glsl_version_supported()`.
 ///
 /// Returns a `glium::program::ProgramChooserCreationError`.
 ///
+/// Besides the shaders, each version can contain `outputs_srgb`, `point_size` and
+/// `transform_feedback_varyings`. The latter is a `(Vec<String>, TransformFeedbackMode)` that
+/// contains the names of the varyings to capture and how to store them.
+///
 /// ## Example
 ///
 /// ```ignore       // TODO: no_run instead
@@ -485,11 +582,14 @@ macro_rules! program {
             let __fragment_shader: &str = "";
             let __outputs_srgb: bool = false;
             let __uses_point_size: bool = false;
+            let __transform_feedback_varyings: Option<(Vec<String>,
+                                                       $crate::program::TransformFeedbackMode)>
+                                              = None;
 
             $(
                 program!(_program_ty $ty, $src, __vertex_shader, __tessellation_control_shader,
                          __tessellation_evaluation_shader, __geometry_shader, __fragment_shader,
-                         __outputs_srgb, __uses_point_size);
+                         __outputs_srgb, __uses_point_size, __transform_feedback_varyings);
             )+
 
             let input = $crate::program::ProgramCreationInput::SourceCode {
@@ -498,7 +598,7 @@ macro_rules! program {
                 tessellation_evaluation_shader: __tessellation_evaluation_shader,
                 geometry_shader: __geometry_shader,
                 fragment_shader: __fragment_shader,
-                transform_feedback_varyings: None,
+                transform_feedback_varyings: __transform_feedback_varyings,
                 outputs_srgb: __outputs_srgb,
                 uses_point_size: __uses_point_size,
             };
@@ -515,34 +615,38 @@ macro_rules! program {
         program!(_inner, $context, $vers, {$($ty:$src),+} $($rest)*);
     );
 
-    (_program_ty vertex, $src:expr, $vs:ident, $tcs:ident, $tes:ident, $gs:ident, $fs:ident, $srgb:ident, $ps:ident) => (
+    (_program_ty vertex, $src:expr, $vs:ident, $tcs:ident, $tes:ident, $gs:ident, $fs:ident, $srgb:ident, $ps:ident, $tf:ident) => (
         let $vs = $src;
     );
 
-    (_program_ty tessellation_control, $src:expr, $vs:ident, $tcs:ident, $tes:ident, $gs:ident, $fs:ident, $srgb:ident, $ps:ident) => (
+    (_program_ty tessellation_control, $src:expr, $vs:ident, $tcs:ident, $tes:ident, $gs:ident, $fs:ident, $srgb:ident, $ps:ident, $tf:ident) => (
         let $tcs = Some($src);
     );
 
-    (_program_ty tessellation_evaluation, $src:expr, $vs:ident, $tcs:ident, $tes:ident, $gs:ident, $fs:ident, $srgb:ident, $ps:ident) => (
+    (_program_ty tessellation_evaluation, $src:expr, $vs:ident, $tcs:ident, $tes:ident, $gs:ident, $fs:ident, $srgb:ident, $ps:ident, $tf:ident) => (
         let $tes = Some($src);
     );
 
-    (_program_ty geometry, $src:expr, $vs:ident, $tcs:ident, $tes:ident, $gs:ident, $fs:ident, $srgb:ident, $ps:ident) => (
+    (_program_ty geometry, $src:expr, $vs:ident, $tcs:ident, $tes:ident, $gs:ident, $fs:ident, $srgb:ident, $ps:ident, $tf:ident) => (
         let $gs = Some($src);
     );
 
-    (_program_ty fragment, $src:expr, $vs:ident, $tcs:ident, $tes:ident, $gs:ident, $fs:ident, $srgb:ident, $ps:ident) => (
+    (_program_ty fragment, $src:expr, $vs:ident, $tcs:ident, $tes:ident, $gs:ident, $fs:ident, $srgb:ident, $ps:ident, $tf:ident) => (
         let $fs = $src;
     );
 
-    (_program_ty point_size, $src:expr, $vs:ident, $tcs:ident, $tes:ident, $gs:ident, $fs:ident, $srgb:ident, $ps:ident) => (
+    (_program_ty point_size, $src:expr, $vs:ident, $tcs:ident, $tes:ident, $gs:ident, $fs:ident, $srgb:ident, $ps:ident, $tf:ident) => (
         let $ps = $src;
     );
 
-    (_program_ty outputs_srgb, $src:expr, $vs:ident, $tcs:ident, $tes:ident, $gs:ident, $fs:ident, $srgb:ident, $ps:ident) => (
+    (_program_ty outputs_srgb, $src:expr, $vs:ident, $tcs:ident, $tes:ident, $gs:ident, $fs:ident, $srgb:ident, $ps:ident, $tf:ident) => (
         let $srgb = $src;
     );
 
+    (_program_ty transform_feedback_varyings, $src:expr, $vs:ident, $tcs:ident, $tes:ident, $gs:ident, $fs:ident, $srgb:ident, $ps:ident, $tf:ident) => (
+        let $tf = Some($src);
+    );
+
     (_parse_num_gl $num:expr) => (
         if $num == 100 {
             $crate::Version($crate::Api::GlEs, 1, 0)
@@ -1,5 +1,7 @@
+use BlitMask;
 use BlitTarget;
 use Rect;
+use ToGlEnum;
 
 use context::Context;
 use ContextExt;
@@ -8,13 +10,49 @@ use fbo::FramebuffersContainer;
 use fbo::ValidatedAttachments;
 
 use gl;
+use uniforms::MagnifySamplerFilter;
 use version::Version;
 use version::Api;
 
 pub fn blit(context: &Context, source: Option<&ValidatedAttachments>,
-            target: Option<&ValidatedAttachments>, mask: gl::types::GLbitfield,
-            src_rect: &Rect, target_rect: &BlitTarget, filter: gl::types::GLenum)
+            target: Option<&ValidatedAttachments>, mask: BlitMask,
+            src_rect: &Rect, target_rect: &BlitTarget, filter: MagnifySamplerFilter)
 {
+    if mask.is_empty() {
+        return;
+    }
+
+    // the depth and stencil buffers can only be copied with the `Nearest` filter, so they are
+    // copied separately from the color buffer
+    if (mask.depth || mask.stencil) && filter != MagnifySamplerFilter::Nearest {
+        if mask.color {
+            blit(context, source, target, BlitMask::color(), src_rect, target_rect, filter);
+        }
+
+        let mask = BlitMask { color: false, .. mask };
+        return blit(context, source, target, mask, src_rect, target_rect,
+                    MagnifySamplerFilter::Nearest);
+    }
+
+    // the color attachment to copy from
+    let read_buffer = if mask.color {
+        Some(match source {
+            Some(source) => source.get_read_buffer_enum(),
+            None => gl::BACK,
+        })
+    } else {
+        None
+    };
+
+    let filter = filter.to_glenum();
+    let mask = {
+        let mut bits = 0;
+        if mask.color { bits |= gl::COLOR_BUFFER_BIT; }
+        if mask.depth { bits |= gl::DEPTH_BUFFER_BIT; }
+        if mask.stencil { bits |= gl::STENCIL_BUFFER_BIT; }
+        bits
+    };
+
     unsafe {
         let mut ctxt = context.make_current();
 
@@ -30,6 +68,10 @@ pub fn blit(context: &Context, source: Option<&ValidatedAttachments>,
 
         // trying to do a named blit if possible
         if ctxt.version >= &Version(Api::Gl, 4, 5) {
+            if let Some(read_buffer) = read_buffer {
+                ctxt.gl.NamedFramebufferReadBuffer(source, read_buffer);     // TODO: cache
+            }
+
             ctxt.gl.BlitNamedFramebuffer(source, target,
                 src_rect.left as gl::types::GLint,
                 src_rect.bottom as gl::types::GLint,
@@ -54,6 +96,14 @@ pub fn blit(context: &Context, source: Option<&ValidatedAttachments>,
             }
         }
 
+        if let Some(read_buffer) = read_buffer {
+            if ctxt.version >= &Version(Api::Gl, 1, 0) ||
+               ctxt.version >= &Version(Api::GlEs, 3, 0)
+            {
+                ctxt.gl.ReadBuffer(read_buffer);     // TODO: cache
+            }
+        }
+
         // binding target framebuffer
         if ctxt.state.draw_framebuffer != target {
             if ctxt.version >= &Version(Api::Gl, 3, 0) {
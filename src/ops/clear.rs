@@ -1,5 +1,9 @@
-use fbo::{self, ValidatedAttachments};
+use std::fmt;
+use std::error::Error;
 
+use fbo::{self, ClearBufferData, ValidatedAttachments};
+
+use context::CommandContext;
 use context::Context;
 use ContextExt;
 use Rect;
@@ -20,55 +24,7 @@ pub fn clear(context: &Context, framebuffer: Option<&ValidatedAttachments>,
 {
     unsafe {
         let mut ctxt = context.make_current();
-
-        let fbo_id = fbo::FramebuffersContainer::get_framebuffer_for_drawing(&mut ctxt, framebuffer);
-        fbo::bind_framebuffer(&mut ctxt, fbo_id, true, false);
-
-        if ctxt.state.enabled_rasterizer_discard {
-            ctxt.gl.Disable(gl::RASTERIZER_DISCARD);
-            ctxt.state.enabled_rasterizer_discard = false;
-        }
-
-        if ctxt.state.color_mask != (1, 1, 1, 1) {
-            ctxt.state.color_mask = (1, 1, 1, 1);
-            ctxt.gl.ColorMask(1, 1, 1, 1);
-        }
-    
-        if ctxt.version >= &Version(Api::Gl, 3, 0) || ctxt.extensions.gl_arb_framebuffer_srgb ||
-           ctxt.extensions.gl_ext_framebuffer_srgb || ctxt.extensions.gl_ext_srgb_write_control
-        {
-            if !color_srgb && !ctxt.state.enabled_framebuffer_srgb {
-                ctxt.gl.Enable(gl::FRAMEBUFFER_SRGB);
-                ctxt.state.enabled_framebuffer_srgb = true;
-
-            } else if color_srgb && ctxt.state.enabled_framebuffer_srgb {
-                ctxt.gl.Disable(gl::FRAMEBUFFER_SRGB);
-                ctxt.state.enabled_framebuffer_srgb = false;
-            }
-        }
-
-        TimeElapsedQuery::end_conditional_render(&mut ctxt);
-
-        if let Some(rect) = rect {
-            let rect = (rect.left as gl::types::GLint, rect.bottom as gl::types::GLint,
-                        rect.width as gl::types::GLsizei, rect.height as gl::types::GLsizei);
-
-            if ctxt.state.scissor != Some(rect) {
-                ctxt.gl.Scissor(rect.0, rect.1, rect.2, rect.3);
-                ctxt.state.scissor = Some(rect);
-            }
-
-            if !ctxt.state.enabled_scissor_test {
-                ctxt.gl.Enable(gl::SCISSOR_TEST);
-                ctxt.state.enabled_scissor_test = true;
-            }
-
-        } else {
-            if ctxt.state.enabled_scissor_test {
-                ctxt.gl.Disable(gl::SCISSOR_TEST);
-                ctxt.state.enabled_scissor_test = false;
-            }
-        }
+        prepare(&mut ctxt, framebuffer, rect, color_srgb);
 
         let mut flags = 0;
 
@@ -101,10 +57,7 @@ pub fn clear(context: &Context, framebuffer: Option<&ValidatedAttachments>,
                 ctxt.state.clear_depth = depth;
             }
 
-            if !ctxt.state.depth_mask {
-                ctxt.gl.DepthMask(gl::TRUE);
-                ctxt.state.depth_mask = true;
-            }
+            prepare_depth(&mut ctxt);
         }
 
         if let Some(stencil) = stencil {
@@ -121,3 +74,141 @@ pub fn clear(context: &Context, framebuffer: Option<&ValidatedAttachments>,
         ctxt.gl.Clear(flags);
     }
 }
+
+/// Error that can happen when clearing a single buffer of a surface.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ClearBufferError {
+    /// The backend doesn't support clearing individual buffers.
+    ///
+    /// OpenGL 3.0 or OpenGL ES 3.0 is required.
+    NotSupported,
+}
+
+impl fmt::Display for ClearBufferError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.description())
+    }
+}
+
+impl Error for ClearBufferError {
+    fn description(&self) -> &str {
+        use self::ClearBufferError::*;
+        match *self {
+            NotSupported => "The backend doesn't support clearing individual buffers",
+        }
+    }
+}
+
+/// Clears a single buffer of a framebuffer with `glClearBuffer*`.
+///
+/// `draw_buffer` is the location of the fragment output whose color attachment is cleared. It is
+/// ignored when clearing the depth or stencil buffer.
+///
+/// Returns `NotSupported` if the backend doesn't support `glClearBuffer*`.
+pub fn clear_buffer(context: &Context, framebuffer: Option<&ValidatedAttachments>,
+                    rect: Option<&Rect>, draw_buffer: u32, data: ClearBufferData)
+                    -> Result<(), ClearBufferError>
+{
+    unsafe {
+        let mut ctxt = context.make_current();
+
+        if !(ctxt.version >= &Version(Api::Gl, 3, 0)) &&
+           !(ctxt.version >= &Version(Api::GlEs, 3, 0))
+        {
+            return Err(ClearBufferError::NotSupported);
+        }
+
+        prepare(&mut ctxt, framebuffer, rect, false);
+
+        let draw_buffer = draw_buffer as gl::types::GLint;
+
+        match data {
+            ClearBufferData::Float(data) => {
+                ctxt.gl.ClearBufferfv(gl::COLOR, draw_buffer, data.as_ptr());
+            },
+            ClearBufferData::Integral(data) => {
+                ctxt.gl.ClearBufferiv(gl::COLOR, draw_buffer, data.as_ptr());
+            },
+            ClearBufferData::Unsigned(data) => {
+                ctxt.gl.ClearBufferuiv(gl::COLOR, draw_buffer, data.as_ptr());
+            },
+            ClearBufferData::Depth(depth) => {
+                prepare_depth(&mut ctxt);
+                ctxt.gl.ClearBufferfv(gl::DEPTH, 0, &depth);
+            },
+            ClearBufferData::Stencil(stencil) => {
+                ctxt.gl.ClearBufferiv(gl::STENCIL, 0, &stencil);
+            },
+            ClearBufferData::DepthStencil(depth, stencil) => {
+                prepare_depth(&mut ctxt);
+                ctxt.gl.ClearBufferfi(gl::DEPTH_STENCIL, 0, depth, stencil);
+            },
+        }
+    }
+
+    Ok(())
+}
+
+/// Binds the framebuffer and sets the states that influence clearing.
+unsafe fn prepare(ctxt: &mut CommandContext, framebuffer: Option<&ValidatedAttachments>,
+                  rect: Option<&Rect>, color_srgb: bool)
+{
+    let fbo_id = fbo::FramebuffersContainer::get_framebuffer_for_drawing(ctxt, framebuffer);
+    fbo::bind_framebuffer(ctxt, fbo_id, true, false);
+
+    if ctxt.state.enabled_rasterizer_discard {
+        ctxt.gl.Disable(gl::RASTERIZER_DISCARD);
+        ctxt.state.enabled_rasterizer_discard = false;
+    }
+
+    if ctxt.state.color_mask != (1, 1, 1, 1) || ctxt.state.color_mask_indexed {
+        ctxt.state.color_mask = (1, 1, 1, 1);
+        ctxt.state.color_mask_indexed = false;
+        ctxt.gl.ColorMask(1, 1, 1, 1);
+    }
+
+    if ctxt.version >= &Version(Api::Gl, 3, 0) || ctxt.extensions.gl_arb_framebuffer_srgb ||
+       ctxt.extensions.gl_ext_framebuffer_srgb || ctxt.extensions.gl_ext_srgb_write_control
+    {
+        if !color_srgb && !ctxt.state.enabled_framebuffer_srgb {
+            ctxt.gl.Enable(gl::FRAMEBUFFER_SRGB);
+            ctxt.state.enabled_framebuffer_srgb = true;
+
+        } else if color_srgb && ctxt.state.enabled_framebuffer_srgb {
+            ctxt.gl.Disable(gl::FRAMEBUFFER_SRGB);
+            ctxt.state.enabled_framebuffer_srgb = false;
+        }
+    }
+
+    TimeElapsedQuery::end_conditional_render(ctxt);
+
+    if let Some(rect) = rect {
+        let rect = (rect.left as gl::types::GLint, rect.bottom as gl::types::GLint,
+                    rect.width as gl::types::GLsizei, rect.height as gl::types::GLsizei);
+
+        if ctxt.state.scissor != Some(rect) || !ctxt.state.scissor_indexed.is_empty() {
+            ctxt.gl.Scissor(rect.0, rect.1, rect.2, rect.3);
+            ctxt.state.scissor = Some(rect);
+            ctxt.state.scissor_indexed.clear();
+        }
+
+        if !ctxt.state.enabled_scissor_test {
+            ctxt.gl.Enable(gl::SCISSOR_TEST);
+            ctxt.state.enabled_scissor_test = true;
+        }
+
+    } else {
+        if ctxt.state.enabled_scissor_test {
+            ctxt.gl.Disable(gl::SCISSOR_TEST);
+            ctxt.state.enabled_scissor_test = false;
+        }
+    }
+}
+
+/// Enables writing to the depth buffer.
+unsafe fn prepare_depth(ctxt: &mut CommandContext) {
+    if !ctxt.state.depth_mask {
+        ctxt.gl.DepthMask(gl::TRUE);
+        ctxt.state.depth_mask = true;
+    }
+}
@@ -1,5 +1,7 @@
 use std::ptr;
 
+use smallvec::SmallVec;
+
 use BufferExt;
 use BufferSliceExt;
 use ProgramExt;
@@ -21,6 +23,7 @@ use vertex_array_object::VertexAttributesSystem;
 use draw_parameters::DrawParameters;
 
 use {gl, context, draw_parameters};
+use program::is_dual_source_blending_supported;
 use version::Version;
 use version::Api;
 
@@ -46,25 +49,48 @@ pub fn draw<'a, U, V>(context: &Context, framebuffer: Option<&ValidatedAttachmen
                 return Err(DrawError::TessellationNotSupported);
             }
 
-            // TODO: programs created from binaries have the wrong value
-            // for `has_tessellation_shaders`
-            /*if !program.has_tessellation_shaders() {    // TODO:
-                panic!("Default tessellation level is not supported yet");
-            }*/
+            // patches can't be drawn without a tessellation evaluation shader ; the tessellation
+            // control shader is optional and the default tessellation levels are used without it
+            if !program.has_tessellation_evaluation_shader() {
+                return Err(DrawError::PatchesWithoutTessellation);
+            }
 
             Some(vertices_per_patch)
         },
-        _ => {
-            // TODO: programs created from binaries have the wrong value
-            // for `has_tessellation_shaders`
-            /*if program.has_tessellation_shaders() {
+        primitives => {
+            if program.has_tessellation_shaders() {
                 return Err(DrawError::TessellationWithoutPatches);
-            }*/
+            }
+
+            // without tessellation, the primitives are sent directly to the geometry shader
+            if let Some(expected) = program.get_geometry_input_primitives() {
+                if !expected.accepts(primitives) {
+                    return Err(DrawError::GeometryInputPrimitivesMismatch {
+                        expected: expected,
+                        obtained: primitives,
+                    });
+                }
+            }
 
             None
         },
     };
 
+    // dual-source blending factors read the fragment shader output with the index 1 ; if the
+    // backend doesn't support them at all, the error is returned when syncing the parameters
+    if program.has_dual_source_output() == Some(false) &&
+       is_dual_source_blending_supported(context)
+    {
+        let uses_dual_source = draw_parameters.blend.uses_dual_source() ||
+            draw_parameters.blend_per_attachment.map_or(false, |blends| {
+                blends.iter().any(|blend| blend.uses_dual_source())
+            });
+
+        if uses_dual_source {
+            return Err(DrawError::DualSourceOutputMissing);
+        }
+    }
+
     // starting the state changes
     let mut ctxt = context.make_current();
 
@@ -95,6 +121,8 @@ pub fn draw<'a, U, V>(context: &Context, framebuffer: Option<&ValidatedAttachmen
         let mut vertices_count: Option<usize> = None;
         // number of instances to draw
         let mut instances_count: Option<usize> = None;
+        // formats of the vertex buffers, checked against the attributes of the program
+        let mut formats: SmallVec<[_; 2]> = SmallVec::new();
 
         for src in vertex_buffers.iter() {
             match src {
@@ -106,6 +134,7 @@ pub fn draw<'a, U, V>(context: &Context, framebuffer: Option<&ValidatedAttachmen
                     }
 
                     binder = binder.add(&buffer, format, if per_instance { Some(1) } else { None });
+                    formats.push(format);
                 },
                 _ => {}
             }
@@ -153,6 +182,8 @@ pub fn draw<'a, U, V>(context: &Context, framebuffer: Option<&ValidatedAttachmen
             }
         }
 
+        try!(program.validate_vertex_formats(&formats));
+
         (vertices_count, instances_count, binder.bind().unwrap_or(0))
     };
 
@@ -168,13 +199,15 @@ pub fn draw<'a, U, V>(context: &Context, framebuffer: Option<&ValidatedAttachmen
 
     // sync-ing draw_parameters
     unsafe {
-        try!(draw_parameters::sync(&mut ctxt, draw_parameters, dimensions, indices.get_primitives_type()));
+        // the default framebuffer only has one draw buffer
+        let draw_buffers = framebuffer.map_or(1, |fb| fb.get_color_attachments_count());
+        try!(draw_parameters::sync(&mut ctxt, draw_parameters, dimensions, draw_buffers,
+                                   indices.get_primitives_type()));
         sync_vertices_per_patch(&mut ctxt, vertices_per_patch);
 
         // TODO: make sure that the program is the right one
-        // TODO: changing the current transform feedback requires pausing/unbinding before changing the program
         if let Some(ref tf) = draw_parameters.transform_feedback {
-            tf.bind(&mut ctxt, indices.get_primitives_type());
+            try!(tf.bind(&mut ctxt, indices.get_primitives_type()));
         } else {
             TransformFeedbackSession::unbind(&mut ctxt);
         }
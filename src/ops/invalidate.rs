@@ -0,0 +1,89 @@
+use fbo::{self, ValidatedAttachments};
+
+use context::Context;
+use ContextExt;
+
+use Api;
+use version::Version;
+use gl;
+
+/// Hints the backend that the content of some buffers of a framebuffer doesn't need to be
+/// preserved.
+///
+/// `colors` contains the indices of the color attachments to invalidate, or is `None` to
+/// invalidate all of them. Does nothing if the backend doesn't support invalidating.
+pub fn invalidate(context: &Context, framebuffer: Option<&ValidatedAttachments>,
+                  colors: Option<&[u32]>, depth: bool, stencil: bool)
+{
+    let mut attachments = Vec::with_capacity(3);
+
+    // the default framebuffer and framebuffer objects don't use the same enums
+    if let Some(framebuffer) = framebuffer {
+        let count = framebuffer.get_color_attachments_count() as u32;
+
+        match colors {
+            Some(colors) => {
+                for &index in colors.iter().filter(|&&i| i < count) {
+                    attachments.push(gl::COLOR_ATTACHMENT0 + index);
+                }
+            },
+            None => {
+                for index in 0 .. count {
+                    attachments.push(gl::COLOR_ATTACHMENT0 + index);
+                }
+            },
+        }
+
+        if depth && framebuffer.get_depth_buffer_bits().is_some() {
+            attachments.push(gl::DEPTH_ATTACHMENT);
+        }
+
+        if stencil && framebuffer.get_stencil_buffer_bits().is_some() {
+            attachments.push(gl::STENCIL_ATTACHMENT);
+        }
+
+    } else {
+        if colors.map(|c| c.contains(&0)).unwrap_or(true) {
+            attachments.push(gl::COLOR);
+        }
+
+        if depth {
+            attachments.push(gl::DEPTH);
+        }
+
+        if stencil {
+            attachments.push(gl::STENCIL);
+        }
+    }
+
+    if attachments.is_empty() {
+        return;
+    }
+
+    unsafe {
+        let mut ctxt = context.make_current();
+
+        let fbo_id = fbo::FramebuffersContainer::get_framebuffer_for_drawing(&mut ctxt, framebuffer);
+
+        if ctxt.version >= &Version(Api::Gl, 4, 5) || ctxt.extensions.gl_arb_direct_state_access {
+            ctxt.gl.InvalidateNamedFramebufferData(fbo_id, attachments.len() as gl::types::GLsizei,
+                                                   attachments.as_ptr());
+
+        } else if ctxt.version >= &Version(Api::Gl, 4, 3) ||
+                  ctxt.version >= &Version(Api::GlEs, 3, 0) ||
+                  ctxt.extensions.gl_arb_invalidate_subdata
+        {
+            fbo::bind_framebuffer(&mut ctxt, fbo_id, true, false);
+            ctxt.gl.InvalidateFramebuffer(gl::DRAW_FRAMEBUFFER,
+                                          attachments.len() as gl::types::GLsizei,
+                                          attachments.as_ptr());
+
+        } else if ctxt.extensions.gl_ext_discard_framebuffer {
+            // `GL_COLOR_EXT`, `GL_DEPTH_EXT` and `GL_STENCIL_EXT` have the same values as
+            // `GL_COLOR`, `GL_DEPTH` and `GL_STENCIL`
+            fbo::bind_framebuffer(&mut ctxt, fbo_id, true, true);
+            ctxt.gl.DiscardFramebufferEXT(gl::FRAMEBUFFER, attachments.len() as gl::types::GLsizei,
+                                          attachments.as_ptr());
+        }
+    }
+}
@@ -1,9 +1,11 @@
 pub use self::blit::blit;
-pub use self::clear::clear;
+pub use self::clear::{clear, clear_buffer, ClearBufferError};
 pub use self::draw::draw;
-pub use self::read::{read, ReadError, Source, Destination};
+pub use self::invalidate::invalidate;
+pub use self::read::{read, read_to_sink, ReadError, Source, Destination};
 
 mod blit;
 mod clear;
 mod draw;
+mod invalidate;
 mod read;
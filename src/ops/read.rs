@@ -1,3 +1,4 @@
+use std::borrow::Cow;
 use std::ptr;
 use std::fmt;
 use std::error::Error;
@@ -5,6 +6,8 @@ use std::error::Error;
 use pixel_buffer::PixelBuffer;
 use texture::ClientFormat;
 use texture::PixelValue;
+use texture::TextureKind;
+use texture::Texture2dDataSink;
 use image_format::{TextureFormatRequest, TextureFormat};
 
 use fbo;
@@ -12,8 +15,11 @@ use fbo::FramebuffersContainer;
 
 use buffer::BufferAny;
 use BufferExt;
+use TextureExt;
 use Rect;
 use context::CommandContext;
+use context::Context;
+use ContextExt;
 use gl;
 
 use version::Version;
@@ -23,6 +29,8 @@ use version::Api;
 pub enum Source<'a> {
     /// A regular framebuffer attachment.
     Attachment(&'a fbo::RegularAttachment<'a>),
+    /// The read buffer of a framebuffer.
+    Framebuffer(&'a fbo::ValidatedAttachments<'a>),
     // TODO: use a Rust enum
     DefaultFramebuffer(gl::types::GLenum),
 }
@@ -73,6 +81,12 @@ pub enum ReadError {
     /// Clamping the values is not supported by the implementation.
     ClampingNotSupported,
 
+    /// The framebuffer doesn't have any color attachment to read from.
+    NoColorAttachment,
+
+    /// The surface doesn't support reading its content.
+    NotSupported,
+
     // TODO: context lost
 }
 
@@ -92,6 +106,10 @@ impl Error for ReadError {
                 "The implementation doesn't support reading a depth, depth-stencil or stencil attachment",
             ClampingNotSupported =>
                 "Clamping the values is not supported by the implementation",
+            NoColorAttachment =>
+                "The framebuffer doesn't have any color attachment to read from",
+            NotSupported =>
+                "The surface doesn't support reading its content",
         }
     }
 }
@@ -146,8 +164,22 @@ pub fn read<'a, S, D, T>(mut ctxt: &mut CommandContext, source: S, rect: &Rect,
     // binding framebuffer
     match source {
         Source::Attachment(attachment) => {
+            if let &fbo::RegularAttachment::Texture(ref tex) = attachment {
+                tex.get_texture().prepare_for_framebuffer(&mut ctxt);
+            }
+
             unsafe { FramebuffersContainer::bind_framebuffer_for_reading(&mut ctxt, attachment) };
         },
+        Source::Framebuffer(attachments) => {
+            if attachments.get_color_attachments_count() == 0 {
+                return Err(ReadError::NoColorAttachment);
+            }
+
+            unsafe {
+                FramebuffersContainer::bind_validated_framebuffer_for_reading(&mut ctxt,
+                                                                              attachments)
+            };
+        },
         Source::DefaultFramebuffer(read_buffer) => {
             FramebuffersContainer::bind_default_framebuffer_for_reading(&mut ctxt, read_buffer);
         },
@@ -174,6 +206,14 @@ pub fn read<'a, S, D, T>(mut ctxt: &mut CommandContext, source: S, rect: &Rect,
                 },
             }
         },
+        Source::Framebuffer(attachments) => {
+            match attachments.get_color_attachment_kind(attachments.get_read_buffer()) {
+                Some(TextureKind::Integral) | Some(TextureKind::Unsigned) => {
+                    (true, ReadSourceType::Color)
+                },
+                _ => (false, ReadSourceType::Color),
+            }
+        },
         Source::DefaultFramebuffer(read_buffer) => {
             (false, ReadSourceType::Color)       // FIXME: wrong
         },
@@ -261,6 +301,20 @@ pub fn read<'a, S, D, T>(mut ctxt: &mut CommandContext, source: S, rect: &Rect,
     Ok(())
 }
 
+/// Reads a rectangle of pixels from the source and builds a `Texture2dDataSink` out of it.
+///
+/// The format of the pixels is determined by `P`.
+pub fn read_to_sink<'a, P, T>(context: &Context, source: Source<'a>, rect: &Rect)
+                              -> Result<T, ReadError>
+                              where P: PixelValue, T: Texture2dDataSink<P>
+{
+    let mut ctxt = context.make_current();
+
+    let mut data = Vec::with_capacity(0);
+    try!(read(&mut ctxt, source, rect, &mut data, false));
+    Ok(T::from_raw(Cow::Owned(data), rect.width, rect.height))
+}
+
 fn client_format_to_gl_enum(format: &ClientFormat, integer: bool)
                             -> (gl::types::GLenum, gl::types::GLenum)
 {
@@ -0,0 +1,236 @@
+use CapabilitiesSource;
+
+use version::Api;
+use version::Version;
+use version::get_supported_glsl_version;
+
+use program::ShaderStage;
+
+/// Name of the output that replaces `gl_FragColor` in recent versions of GLSL.
+const FRAG_COLOR_OUTPUT: &'static str = "glium_FragColor";
+
+/// Adapts the source code of a shader to the GLSL version supported by the backend.
+///
+/// The source code must be written in the style of GLSL 1.10 or GLSL ES 1.00, in other words
+/// with `attribute`, `varying`, `texture2D` and `gl_FragColor`. Any existing `#version`
+/// directive is removed and this function inserts the `#version` directive that corresponds to
+/// the version of the backend. Then:
+///
+/// - If the backend supports GLSL 1.30 or GLSL ES 3.00 or later, `attribute` and `varying` are
+///   replaced with `in` and `out`, the `texture2D`, `texture3D`, `textureCube` and `shadow2D`
+///   families of functions are replaced with `texture` and its variants, and `gl_FragColor` is
+///   replaced with a user-defined output.
+/// - With OpenGL ES, a default `mediump` precision for floats is added to fragment shaders.
+/// - With desktop OpenGL before GLSL 1.30, precision statements are removed and precision
+///   qualifiers are defined as nothing.
+///
+/// `#extension` directives are moved right after the `#version` directive, before the
+/// declarations inserted by this function, unless they are inside a preprocessor conditional.
+/// Comments are left untouched.
+///
+/// `gl_FragData` is not adapted. Shaders that write to multiple outputs need to be written for
+/// a specific version.
+///
+/// # Example
+///
+/// ```no_run
+/// # let display: glium::Display = unsafe { std::mem::uninitialized() };
+/// use glium::program::ShaderStage;
+///
+/// let vertex = glium::program::adapt_shader_source(&display, ShaderStage::Vertex, "
+///         attribute vec2 position;
+///         varying vec2 v_tex_coords;
+///
+///         void main() {
+///             v_tex_coords = position;
+///             gl_Position = vec4(position, 0.0, 1.0);
+///         }
+///     ");
+/// ```
+pub fn adapt_shader_source<C: ?Sized>(ctxt: &C, stage: ShaderStage, source: &str) -> String
+                                      where C: CapabilitiesSource
+{
+    let glsl = match *ctxt.get_version() {
+        // shaders are only available through extensions before OpenGL 2.0
+        Version(Api::Gl, major, _) if major < 2 => Version(Api::Gl, 1, 1),
+        ref version => get_supported_glsl_version(version),
+    };
+
+    let (modern, es) = match glsl {
+        Version(Api::Gl, major, minor) => ((major, minor) >= (1, 3), false),
+        Version(Api::GlEs, major, _) => (major >= 3, true),
+    };
+
+    // the header inserted before the source code
+    let mut header = match glsl {
+        Version(Api::GlEs, 1, 0) => "#version 100\n".to_owned(),
+        Version(Api::GlEs, major, minor) => format!("#version {}{}0 es\n", major, minor),
+        Version(Api::Gl, major, minor) => format!("#version {}{}0\n", major, minor),
+    };
+
+    if es && stage == ShaderStage::Fragment {
+        header.push_str("precision mediump float;\n");
+    }
+
+    if !modern && !es {
+        header.push_str("#define lowp\n#define mediump\n#define highp\n");
+    }
+
+    let mut uses_frag_color = false;
+    let mut seen_code = false;
+    let mut in_comment = false;
+    let mut conditionals_depth = 0usize;
+    let mut extensions = String::new();
+    let mut body = String::with_capacity(source.len());
+
+    for line in source.lines() {
+        let trimmed = line.trim_left();
+
+        // the name of the preprocessor directive of the line, if any
+        let directive = if !in_comment && trimmed.starts_with('#') {
+            Some(trimmed[1 ..].trim_left())
+        } else {
+            None
+        };
+
+        // `#extension` must come before any declaration, including the ones of the header
+        let is_extension = conditionals_depth == 0 &&
+                           directive.map_or(false, |d| d.starts_with("extension"));
+
+        // lines are blanked instead of being removed in order to keep the line numbers
+        let removed = is_extension ||
+                      (!seen_code && directive.map_or(false, |d| d.starts_with("version"))) ||
+                      (!in_comment && !modern && !es && trimmed.starts_with("precision "));
+
+        if let Some(directive) = directive {
+            if directive.starts_with("if") {
+                conditionals_depth += 1;
+            } else if directive.starts_with("endif") {
+                conditionals_depth = conditionals_depth.saturating_sub(1);
+            }
+        }
+
+        if !trimmed.is_empty() {
+            seen_code = true;
+        }
+
+        if is_extension {
+            extensions.push_str(trimmed);
+            extensions.push('\n');
+        } else if !removed {
+            replace_identifiers(line, &mut body, &mut in_comment, |ident| {
+                if modern { adapt_identifier(ident, stage, &mut uses_frag_color) } else { None }
+            });
+        }
+
+        body.push('\n');
+    }
+
+    header.insert_str(header.find('\n').unwrap() + 1, &extensions);
+
+    if uses_frag_color {
+        header.push_str(&format!("out vec4 {};\n", FRAG_COLOR_OUTPUT));
+    }
+
+    // starting from GLSL 3.30 and GLSL ES 3.00, `#line` gives the number of the next line
+    let line_directive = match glsl {
+        Version(Api::Gl, major, minor) if (major, minor) >= (3, 3) => "#line 1\n",
+        Version(Api::GlEs, major, _) if major >= 3 => "#line 1\n",
+        _ => "#line 0\n",
+    };
+
+    header.push_str(line_directive);
+    header.push_str(&body);
+    header
+}
+
+/// Returns the replacement of an identifier in modern GLSL, or `None` to keep it.
+fn adapt_identifier(ident: &str, stage: ShaderStage, uses_frag_color: &mut bool)
+                    -> Option<&'static str>
+{
+    match (ident, stage) {
+        ("attribute", ShaderStage::Vertex) => Some("in"),
+        ("varying", ShaderStage::Vertex) => Some("out"),
+        ("varying", ShaderStage::Fragment) => Some("in"),
+        ("gl_FragColor", ShaderStage::Fragment) => {
+            *uses_frag_color = true;
+            Some(FRAG_COLOR_OUTPUT)
+        },
+        ("texture2D", _) | ("texture3D", _) | ("textureCube", _) | ("shadow2D", _) => {
+            Some("texture")
+        },
+        ("texture2DLod", _) | ("texture3DLod", _) | ("textureCubeLod", _) |
+        ("shadow2DLod", _) => Some("textureLod"),
+        ("texture2DProj", _) | ("texture3DProj", _) | ("shadow2DProj", _) => Some("textureProj"),
+        _ => None
+    }
+}
+
+/// Copies `line` to `output`, replacing each identifier for which `replacement` returns
+/// something.
+///
+/// Identifiers inside comments are not replaced. `in_comment` indicates whether the line starts
+/// inside a `/* */` comment, and is updated to indicate whether the next line does.
+fn replace_identifiers<F>(line: &str, output: &mut String, in_comment: &mut bool,
+                          mut replacement: F)
+                          where F: FnMut(&str) -> Option<&'static str>
+{
+    let mut rest = line;
+
+    loop {
+        if *in_comment {
+            match rest.find("*/") {
+                Some(end) => {
+                    output.push_str(&rest[.. end + 2]);
+                    rest = &rest[end + 2 ..];
+                    *in_comment = false;
+                },
+                None => {
+                    output.push_str(rest);
+                    return;
+                },
+            }
+        }
+
+        let start = match rest.find(|c: char| c.is_alphanumeric() || c == '_' || c == '/') {
+            Some(start) => start,
+            None => break,
+        };
+
+        output.push_str(&rest[.. start]);
+        rest = &rest[start ..];
+
+        if rest.starts_with("//") {
+            output.push_str(rest);
+            return;
+        }
+
+        if rest.starts_with("/*") {
+            output.push_str("/*");
+            rest = &rest[2 ..];
+            *in_comment = true;
+            continue;
+        }
+
+        if rest.starts_with('/') {
+            output.push('/');
+            rest = &rest[1 ..];
+            continue;
+        }
+
+        let end = rest.find(|c: char| !(c.is_alphanumeric() || c == '_')).unwrap_or(rest.len());
+        let token = &rest[.. end];
+
+        // numbers such as `1e5` must not be treated as identifiers
+        let is_identifier = !token.chars().next().unwrap().is_digit(10);
+
+        match if is_identifier { replacement(token) } else { None } {
+            Some(r) => output.push_str(r),
+            None => output.push_str(token),
+        }
+
+        rest = &rest[end ..];
+    }
+
+    output.push_str(rest);
+}
@@ -0,0 +1,97 @@
+use backend::Facade;
+use version::Version;
+use CapabilitiesSource;
+
+use program::{Program, ProgramCreationInput, ProgramChooserCreationError};
+
+/// Builds a program from the first of multiple alternative sources that is supported by the
+/// backend.
+///
+/// This is similar to the `program!` macro, except that each variant can also require a list of
+/// extensions, and that the index of the chosen variant is returned.
+///
+/// ## Example
+///
+/// ```no_run
+/// # let display: glium::Display = unsafe { std::mem::uninitialized() };
+/// # fn source(_: &str) -> glium::program::SourceCode<'static> {
+/// #     glium::program::SourceCode { vertex_shader: "", tessellation_control_shader: None,
+/// #         tessellation_evaluation_shader: None, geometry_shader: None, fragment_shader: "" }
+/// # }
+/// use glium::program::ProgramChooser;
+/// use glium::{Version, Api};
+///
+/// let (program, variant) = ProgramChooser::new()
+///     .variant(Version(Api::Gl, 4, 3), &[], source("ssbo 4.3"))
+///     .variant(Version(Api::Gl, 3, 3), &["GL_ARB_shader_storage_buffer_object"],
+///              source("ssbo 3.3"))
+///     .variant(Version(Api::Gl, 3, 3), &[], source("fallback 3.3"))
+///     .build(&display).unwrap();
+///
+/// let uses_ssbo = variant < 2;
+/// ```
+pub struct ProgramChooser<'a> {
+    variants: Vec<ProgramVariant<'a>>,
+}
+
+struct ProgramVariant<'a> {
+    glsl_version: Version,
+    extensions: Vec<&'a str>,
+    input: ProgramCreationInput<'a>,
+}
+
+impl<'a> ProgramChooser<'a> {
+    /// Builds a new chooser without any variant.
+    #[inline]
+    pub fn new() -> ProgramChooser<'a> {
+        ProgramChooser {
+            variants: Vec::new(),
+        }
+    }
+
+    /// Adds a variant of the program.
+    ///
+    /// The variant is only used if the backend supports the given GLSL version and all the
+    /// extensions in `extensions` (for example `"GL_ARB_compute_shader"`). Variants are tried
+    /// in the order in which they are added, so the best one should come first.
+    pub fn variant<I>(mut self, glsl_version: Version, extensions: &[&'a str], input: I)
+                      -> ProgramChooser<'a>
+                      where I: Into<ProgramCreationInput<'a>>
+    {
+        self.variants.push(ProgramVariant {
+            glsl_version: glsl_version,
+            extensions: extensions.to_vec(),
+            input: input.into(),
+        });
+
+        self
+    }
+
+    /// Returns the index of the first variant that is supported by the backend, if any.
+    pub fn choose<F: ?Sized>(&self, facade: &F) -> Option<usize> where F: Facade {
+        let context = facade.get_context();
+
+        self.variants.iter().position(|variant| {
+            context.is_glsl_version_supported(&variant.glsl_version) &&
+                variant.extensions.iter().all(|ext| context.get_extensions().is_supported(ext))
+        })
+    }
+
+    /// Builds the program from the first variant that is supported by the backend.
+    ///
+    /// Returns the program and the index of the variant that was used. If the creation of this
+    /// variant fails, the error is returned and the following variants are not tried.
+    pub fn build<F: ?Sized>(mut self, facade: &F)
+                            -> Result<(Program, usize), ProgramChooserCreationError>
+                            where F: Facade
+    {
+        let index = match self.choose(facade) {
+            Some(index) => index,
+            None => return Err(ProgramChooserCreationError::NoVersion),
+        };
+
+        let variant = self.variants.swap_remove(index);
+        let program = try!(Program::new(facade, variant.input));
+        Ok((program, index))
+    }
+}
@@ -11,25 +11,33 @@ use std::hash::BuildHasherDefault;
 use fnv::FnvHasher;
 
 use CapabilitiesSource;
+use ContextExt;
 use GlObject;
 use ProgramExt;
 use Handle;
 use RawUniformValue;
+use RawUniformArray;
+use DrawError;
 
 use program::{COMPILER_GLOBAL_LOCK, ProgramCreationError, Binary, GetBinaryError};
+use program::SpirvEntryPoint;
+use program::DispatchError;
 
 use program::reflection::{Uniform, UniformBlock};
+use program::reflection::{ProgramReflection, TextureUnit, AtomicCounter};
+use program::reflection::reflect_compute_work_group_size;
 use program::reflection::{ShaderStage, SubroutineData};
-use program::shader::{build_shader, check_shader_type_compatibility};
+use program::shader::{build_shader, specialize_shader, check_shader_type_compatibility};
 
 use program::raw::RawProgram;
 
 use buffer::BufferSlice;
-use uniforms::Uniforms;
+use uniforms::{Uniforms, UniformLocation};
 
 /// A combination of compute shaders linked together.
 pub struct ComputeShader {
     raw: RawProgram,
+    work_group_size: (u32, u32, u32),
 }
 
 impl ComputeShader {
@@ -39,6 +47,41 @@ impl ComputeShader {
         check_shader_type_compatibility(ctxt, gl::COMPUTE_SHADER)
     }
 
+    /// Returns the maximum number of work groups that can be started by `dispatch` in each
+    /// dimension.
+    ///
+    /// Returns `(0, 0, 0)` if compute shaders are not supported.
+    #[inline]
+    pub fn get_max_work_group_count<C: ?Sized>(ctxt: &C) -> (u32, u32, u32)
+                                               where C: CapabilitiesSource
+    {
+        let v = ctxt.get_capabilities().max_compute_work_group_count;
+        (v.0 as u32, v.1 as u32, v.2 as u32)
+    }
+
+    /// Returns the maximum size of the local work groups in each dimension, as declared in the
+    /// shader with `layout(local_size_x = ..., local_size_y = ..., local_size_z = ...) in;`.
+    ///
+    /// Returns `(0, 0, 0)` if compute shaders are not supported.
+    #[inline]
+    pub fn get_max_work_group_size<C: ?Sized>(ctxt: &C) -> (u32, u32, u32)
+                                              where C: CapabilitiesSource
+    {
+        let v = ctxt.get_capabilities().max_compute_work_group_size;
+        (v.0 as u32, v.1 as u32, v.2 as u32)
+    }
+
+    /// Returns the maximum number of invocations in a local work group, in other words the
+    /// maximum value of `local_size_x * local_size_y * local_size_z`.
+    ///
+    /// Returns `0` if compute shaders are not supported.
+    #[inline]
+    pub fn get_max_work_group_invocations<C: ?Sized>(ctxt: &C) -> u32
+                                                     where C: CapabilitiesSource
+    {
+        ctxt.get_capabilities().max_compute_work_group_invocations as u32
+    }
+
     /// Builds a new compute shader from some source code.
     #[inline]
     pub fn from_source<F: ?Sized>(facade: &F, src: &str) -> Result<ComputeShader, ProgramCreationError>
@@ -47,10 +90,26 @@ impl ComputeShader {
         let _lock = COMPILER_GLOBAL_LOCK.lock();
 
         let shader = try!(build_shader(facade, gl::COMPUTE_SHADER, src));
+        let raw = try!(RawProgram::from_shaders(facade, &[shader], false, false, false, None,
+                                                &[], &[]));
+        Ok(ComputeShader::from_raw(facade, raw))
+    }
 
-        Ok(ComputeShader {
-            raw: try!(RawProgram::from_shaders(facade, &[shader], false, false, false, None))
-        })
+    /// Builds a new compute shader from the entry point of a SPIR-V module.
+    ///
+    /// The specialization constants of the entry point are applied, which makes it possible to
+    /// create multiple variants of the same module.
+    #[inline]
+    pub fn from_spirv<F: ?Sized>(facade: &F, entry_point: &SpirvEntryPoint)
+                                 -> Result<ComputeShader, ProgramCreationError>
+                                 where F: Facade
+    {
+        let _lock = COMPILER_GLOBAL_LOCK.lock();
+
+        let shader = try!(specialize_shader(facade, gl::COMPUTE_SHADER, entry_point));
+        let raw = try!(RawProgram::from_shaders(facade, &[shader], false, false, false, None,
+                                                &[], &[]));
+        Ok(ComputeShader::from_raw(facade, raw))
     }
 
     /// Builds a new compute shader from some binary.
@@ -60,9 +119,29 @@ impl ComputeShader {
     {
         let _lock = COMPILER_GLOBAL_LOCK.lock();
 
-        Ok(ComputeShader {
-            raw: try!(RawProgram::from_binary(facade, data))
-        })
+        let raw = try!(RawProgram::from_binary(facade, data));
+        Ok(ComputeShader::from_raw(facade, raw))
+    }
+
+    fn from_raw<F: ?Sized>(facade: &F, raw: RawProgram) -> ComputeShader where F: Facade {
+        let work_group_size = unsafe {
+            let mut ctxt = facade.get_context().make_current();
+            reflect_compute_work_group_size(&mut ctxt, raw.get_id())
+        };
+
+        ComputeShader {
+            raw: raw,
+            work_group_size: work_group_size,
+        }
+    }
+
+    /// Returns the size of the local work groups, as declared in the shader with
+    /// `layout(local_size_x = ..., local_size_y = ..., local_size_z = ...) in;`.
+    ///
+    /// Each work group started by `dispatch` contains this number of invocations.
+    #[inline]
+    pub fn get_work_group_size(&self) -> (u32, u32, u32) {
+        self.work_group_size
     }
 
     /// Executes the compute shader.
@@ -70,19 +149,49 @@ impl ComputeShader {
     /// `x * y * z` work groups will be started. The current work group can be retreived with
     /// `gl_WorkGroupID`. Inside each work group, additional local work groups can be started
     /// depending on the attributes of the compute shader itself.
+    ///
+    /// Shader storage blocks, atomic counters and image units are bound through the uniforms,
+    /// like when drawing. Glium keeps track of the buffers and textures written by the shader
+    /// and automatically inserts the memory barriers required before they are used again.
+    #[inline]
+    pub fn dispatch<U>(&self, uniforms: U, x: u32, y: u32, z: u32) -> Result<(), DispatchError>
+                       where U: Uniforms
+    {
+        unsafe { self.raw.dispatch_compute(uniforms, x, y, z) }
+    }
+
+    /// Executes the compute shader.
+    ///
+    /// This is similar to `dispatch`, except that the number of work groups is read by the GPU
+    /// from a buffer. The buffer can be written by a previous compute shader, for example by
+    /// binding it as a shader storage block, in which case the work group counts never go
+    /// through the CPU. Glium automatically inserts the required memory barrier.
+    ///
+    /// The number of work groups is not checked against the limits of the backend. The offset
+    /// of the slice must be a multiple of 4, otherwise `UnalignedIndirectBuffer` is returned.
+    #[inline]
+    pub fn dispatch_indirect<U>(&self, uniforms: U, buffer: BufferSlice<ComputeCommand>)
+                                -> Result<(), DispatchError> where U: Uniforms
+    {
+        unsafe { self.raw.dispatch_compute_indirect(uniforms, buffer) }
+    }
+
+    /// Executes the compute shader.
+    ///
+    /// This is the same as `dispatch`, except that it panics in case of an error.
     #[inline]
     pub fn execute<U>(&self, uniforms: U, x: u32, y: u32, z: u32) where U: Uniforms {
-        unsafe { self.raw.dispatch_compute(uniforms, x, y, z) }.unwrap();       // FIXME: return error
+        self.dispatch(uniforms, x, y, z).unwrap()
     }
 
     /// Executes the compute shader.
     ///
-    /// This is similar to `execute`, except that the parameters are stored in a buffer.
+    /// This is the same as `dispatch_indirect`, except that it panics in case of an error.
     #[inline]
     pub fn execute_indirect<U>(&self, uniforms: U, buffer: BufferSlice<ComputeCommand>)
                                where U: Uniforms
     {
-        unsafe { self.raw.dispatch_compute_indirect(uniforms, buffer) }.unwrap();       // FIXME: return error
+        self.dispatch_indirect(uniforms, buffer).unwrap()
     }
 
     /// Returns the program's compiled binary.
@@ -94,12 +203,80 @@ impl ComputeShader {
         self.raw.get_binary()
     }
 
+    /// Returns everything that is known about the interface of the program.
+    ///
+    /// The uniforms and blocks are also available through the more specific methods of this
+    /// struct.
+    #[inline]
+    pub fn get_reflection(&self) -> &ProgramReflection {
+        self.raw.get_reflection()
+    }
+
     /// Returns informations about a uniform variable, if it exists.
     #[inline]
     pub fn get_uniform(&self, name: &str) -> Option<&Uniform> {
         self.raw.get_uniform(name)
     }
 
+    /// Returns the members of a uniform struct or of an element of an array of structs, sorted
+    /// by name.
+    ///
+    /// The names of the members don't include the name of the struct. Members that are
+    /// themselves structs or arrays are returned with their full path, and can be set with a
+    /// name such as `lights[2].position`.
+    ///
+    /// ## Example
+    ///
+    /// ```no_run
+    /// # let program: glium::Program = unsafe { std::mem::uninitialized() };
+    /// for (member, uniform) in program.get_uniform_struct_members("lights[2]") {
+    ///     println!("lights[2].{} - Type: {:?}", member, uniform.ty);
+    /// }
+    /// ```
+    #[inline]
+    pub fn get_uniform_struct_members(&self, name: &str) -> Vec<(&str, &Uniform)> {
+        self.raw.get_uniform_struct_members(name)
+    }
+
+    /// Returns the location of a uniform variable, if it exists.
+    ///
+    /// The location can be passed to a `LocatedUniformsStorage` in order to set the value of
+    /// the uniform without looking up its name each time you draw.
+    #[inline]
+    pub fn get_uniform_location(&self, name: &str) -> Option<UniformLocation> {
+        self.raw.get_uniform(name).map(|uniform| {
+            UniformLocation {
+                location: uniform.location,
+                ty: uniform.ty,
+            }
+        })
+    }
+
+    /// Returns the texture unit that is used when a texture is bound to the given sampler, if
+    /// it exists.
+    ///
+    /// Each sampler of the program is assigned its own texture unit when the program is
+    /// linked, in the order of their names. Creating the program fails with
+    /// `TooManyTextureUnits` if the backend doesn't have enough units.
+    #[inline]
+    pub fn get_texture_unit(&self, name: &str) -> Option<u32> {
+        self.raw.get_texture_unit(name)
+    }
+
+    /// Returns the texture units assigned to the samplers of the program, sorted by unit.
+    #[inline]
+    pub fn get_texture_units(&self) -> &[TextureUnit] {
+        self.raw.get_texture_units()
+    }
+
+    /// Updates the values of the uniforms of the program ahead of dispatching it.
+    ///
+    /// See `Program::update_uniforms`.
+    #[inline]
+    pub fn update_uniforms<U>(&self, uniforms: &U) -> Result<(), DrawError> where U: Uniforms {
+        self.raw.update_uniforms(uniforms)
+    }
+
     /// Returns an iterator to the list of uniforms.
     ///
     /// ## Example
@@ -146,6 +323,22 @@ impl ComputeShader {
             -> &HashMap<String, UniformBlock, BuildHasherDefault<FnvHasher>> {
         self.raw.get_shader_storage_blocks()
     }
+
+    /// Returns the list of atomic counters.
+    ///
+    /// ## Example
+    ///
+    /// ```no_run
+    /// # let program: glium::Program = unsafe { std::mem::uninitialized() };
+    /// for (name, counter) in program.get_atomic_counters() {
+    ///     println!("Name: {} - Binding: {}", name, counter.binding);
+    /// }
+    /// ```
+    #[inline]
+    pub fn get_atomic_counters(&self)
+            -> &HashMap<String, AtomicCounter, BuildHasherDefault<FnvHasher>> {
+        self.raw.get_atomic_counters()
+    }
 }
 
 impl fmt::Debug for ComputeShader {
@@ -177,6 +370,13 @@ impl ProgramExt for ComputeShader {
         self.raw.set_uniform(ctxt, uniform_location, value)
     }
 
+    #[inline]
+    fn set_uniform_array(&self, ctxt: &mut CommandContext, uniform_location: gl::types::GLint,
+                         value: &RawUniformArray)
+    {
+        self.raw.set_uniform_array(ctxt, uniform_location, value)
+    }
+
     #[inline]
     fn set_uniform_block_binding(&self, ctxt: &mut CommandContext, block_location: gl::types::GLuint,
                                  value: gl::types::GLuint)
@@ -205,6 +405,11 @@ impl ProgramExt for ComputeShader {
         self.raw.get_uniform(name)
     }
 
+    #[inline]
+    fn get_texture_units(&self) -> &[TextureUnit] {
+        self.raw.get_texture_units()
+    }
+
     #[inline]
     fn get_uniform_blocks(&self) -> &HashMap<String, UniformBlock, BuildHasherDefault<FnvHasher>> {
         self.raw.get_uniform_blocks()
@@ -216,6 +421,12 @@ impl ProgramExt for ComputeShader {
         self.raw.get_shader_storage_blocks()
     }
 
+    #[inline]
+    fn get_atomic_counters(&self)
+                           -> &HashMap<String, AtomicCounter, BuildHasherDefault<FnvHasher>> {
+        self.raw.get_atomic_counters()
+    }
+
     #[inline]
     fn get_subroutine_data(&self) -> &SubroutineData {
         self.raw.get_subroutine_data()
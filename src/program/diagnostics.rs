@@ -0,0 +1,191 @@
+/// A message reported by the shader compiler or linker.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ShaderDiagnostic {
+    /// The source string number the message refers to, if any.
+    ///
+    /// This is `0` for the source code of a shader, unless it has been processed by
+    /// `resolve_includes` in which case this designates an included file.
+    pub file: Option<u32>,
+
+    /// The line the message refers to, if any.
+    pub line: Option<u32>,
+
+    /// The column the message refers to, if any. Only some implementations report columns.
+    pub column: Option<u32>,
+
+    /// Severity of the message.
+    pub severity: DiagnosticSeverity,
+
+    /// The message itself, without the location and the severity.
+    pub message: String,
+}
+
+/// Severity of a `ShaderDiagnostic`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum DiagnosticSeverity {
+    /// An error that prevents the shader from being compiled or the program from being linked.
+    Error,
+
+    /// A warning.
+    Warning,
+
+    /// Any other message, for example lines of the log that couldn't be parsed.
+    Info,
+}
+
+/// Parses the info log of a shader or of a program, as returned by `CompilationError` and
+/// `LinkingError`, into a list of messages.
+///
+/// The formats used by the NVIDIA, AMD, Intel, Apple and Mesa implementations are recognized.
+/// Lines that don't match any known format are returned as `Info` messages without a location.
+///
+/// # Example
+///
+/// ```
+/// use glium::program::{parse_info_log, DiagnosticSeverity};
+///
+/// let diagnostics = parse_info_log("0(12) : error C0000: syntax error, unexpected '}'");
+/// assert_eq!(diagnostics[0].line, Some(12));
+/// assert_eq!(diagnostics[0].severity, DiagnosticSeverity::Error);
+/// ```
+pub fn parse_info_log(log: &str) -> Vec<ShaderDiagnostic> {
+    log.lines().filter_map(|line| {
+        let line = line.trim();
+        if line.is_empty() {
+            return None;
+        }
+
+        Some(parse_line(line).unwrap_or_else(|| {
+            ShaderDiagnostic {
+                file: None,
+                line: None,
+                column: None,
+                severity: DiagnosticSeverity::Info,
+                message: line.to_owned(),
+            }
+        }))
+    }).collect()
+}
+
+/// Parses a single non-empty line of an info log.
+fn parse_line(line: &str) -> Option<ShaderDiagnostic> {
+    // AMD, Intel and Apple: `ERROR: 0:12: message`
+    // Mesa and NVIDIA linker: `error: message`
+    if let Some((severity, rest)) = parse_severity(line) {
+        let (file, line_num, message) = match parse_number(rest) {
+            Some((file, rest)) if rest.starts_with(':') => {
+                match parse_number(&rest[1..]) {
+                    Some((line_num, rest)) if rest.starts_with(':') => {
+                        (Some(file), Some(line_num), rest[1..].trim())
+                    },
+                    _ => (None, None, rest)
+                }
+            },
+            _ => (None, None, rest)
+        };
+
+        return Some(ShaderDiagnostic {
+            file: file,
+            line: line_num,
+            column: None,
+            severity: severity,
+            message: message.to_owned(),
+        });
+    }
+
+    let (file, rest) = match parse_number(line) {
+        Some(v) => v,
+        None => return None
+    };
+
+    // NVIDIA: `0(12) : error C0000: message`
+    if rest.starts_with('(') {
+        let (line_num, rest) = match parse_number(&rest[1..]) {
+            Some((line_num, rest)) if rest.starts_with(')') => (line_num, rest[1..].trim_left()),
+            _ => return None
+        };
+
+        if !rest.starts_with(':') {
+            return None;
+        }
+
+        let (severity, message) = match parse_severity(rest[1..].trim_left()) {
+            Some(v) => v,
+            None => return None
+        };
+
+        return Some(ShaderDiagnostic {
+            file: Some(file),
+            line: Some(line_num),
+            column: None,
+            severity: severity,
+            message: message.to_owned(),
+        });
+    }
+
+    // Mesa: `0:12(5): error: message`
+    if rest.starts_with(':') {
+        let (line_num, rest) = match parse_number(&rest[1..]) {
+            Some(v) => v,
+            None => return None
+        };
+
+        let (column, rest) = if rest.starts_with('(') {
+            match parse_number(&rest[1..]) {
+                Some((column, rest)) if rest.starts_with(')') => (Some(column), &rest[1..]),
+                _ => return None
+            }
+        } else {
+            (None, rest)
+        };
+
+        if !rest.starts_with(':') {
+            return None;
+        }
+
+        let rest = rest[1..].trim_left();
+        let (severity, message) = match parse_severity(rest) {
+            Some(v) => v,
+            None => (DiagnosticSeverity::Error, rest)
+        };
+
+        return Some(ShaderDiagnostic {
+            file: Some(file),
+            line: Some(line_num),
+            column: column,
+            severity: severity,
+            message: message.to_owned(),
+        });
+    }
+
+    None
+}
+
+/// If the string starts with a severity followed by `:` (and optionally by an error code
+/// before the `:`), returns the severity and the rest of the string.
+fn parse_severity(s: &str) -> Option<(DiagnosticSeverity, &str)> {
+    let colon = match s.find(':') {
+        Some(c) => c,
+        None => return None
+    };
+
+    let word = s[.. colon].split_whitespace().next().unwrap_or("").to_lowercase();
+    let severity = match &word[..] {
+        "error" | "fatal" => DiagnosticSeverity::Error,
+        "warning" => DiagnosticSeverity::Warning,
+        "info" | "note" => DiagnosticSeverity::Info,
+        _ => return None
+    };
+
+    Some((severity, s[colon + 1 ..].trim()))
+}
+
+/// If the string starts with a number, returns it and the rest of the string.
+fn parse_number(s: &str) -> Option<(u32, &str)> {
+    let end = s.find(|c: char| !c.is_digit(10)).unwrap_or(s.len());
+    if end == 0 {
+        return None;
+    }
+
+    s[.. end].parse().ok().map(|n| (n, &s[end ..]))
+}
@@ -0,0 +1,190 @@
+use std::error::Error;
+use std::fmt;
+
+/// Error that can happen while resolving the `#include` directives of a shader.
+#[derive(Clone, Debug)]
+pub enum IncludeError {
+    /// The resolver didn't find the requested file.
+    NotFound {
+        /// Name of the file, as written in the `#include` directive.
+        name: String,
+    },
+
+    /// A file includes itself, directly or indirectly.
+    RecursiveInclude {
+        /// Name of the file, as written in the `#include` directive.
+        name: String,
+    },
+
+    /// An `#include` directive is not followed by a file name between `"` or `<` and `>`.
+    InvalidDirective {
+        /// The content of the line that contains the directive.
+        line: String,
+    },
+}
+
+impl fmt::Display for IncludeError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        use self::IncludeError::*;
+        match *self {
+            NotFound { ref name } =>
+                write!(fmt, "{}: `{}`", self.description(), name),
+            RecursiveInclude { ref name } =>
+                write!(fmt, "{}: `{}`", self.description(), name),
+            InvalidDirective { ref line } =>
+                write!(fmt, "{}: `{}`", self.description(), line),
+        }
+    }
+}
+
+impl Error for IncludeError {
+    fn description(&self) -> &str {
+        use self::IncludeError::*;
+        match *self {
+            NotFound { .. } =>
+                "Could not find an included file",
+            RecursiveInclude { .. } =>
+                "A file includes itself",
+            InvalidDirective { .. } =>
+                "Invalid #include directive",
+        }
+    }
+}
+
+/// Replaces the `#include "file"` and `#include <file>` directives of some GLSL source code
+/// with the content of the corresponding files.
+///
+/// The `resolver` is called with the name of each included file, and must return its content
+/// or `None` if it doesn't exist. Included files can themselves contain `#include` directives.
+///
+/// In order for the compilation errors to be reported correctly, `#line` directives are
+/// inserted around the content of each file. The main source code has the source string
+/// number `0`, and each included file gets the next number the first time it is included.
+/// The compiler reports the errors as `number(line)` or `number:line` depending on the
+/// implementation.
+///
+/// Note that the `#line` directives follow the semantics of GLSL 3.30 and above. With earlier
+/// versions, the line numbers of the errors in the included files are off by one.
+///
+/// # Example
+///
+/// ```
+/// let source = "
+///     #version 330
+///     #include \"lighting.glsl\"
+///
+///     void main() {
+///     }
+/// ";
+///
+/// let source = glium::program::resolve_includes(source, |name| {
+///     match name {
+///         "lighting.glsl" => Some("vec3 light(vec3 normal) { return normal; }".to_owned()),
+///         _ => None
+///     }
+/// }).unwrap();
+///
+/// assert!(source.contains("vec3 light(vec3 normal)"));
+/// ```
+pub fn resolve_includes<F>(source: &str, mut resolver: F) -> Result<String, IncludeError>
+                           where F: FnMut(&str) -> Option<String>
+{
+    let mut output = String::with_capacity(source.len());
+    let mut files = Vec::new();
+    let mut stack = Vec::new();
+
+    try!(process_source(source, 0, &mut resolver, &mut files, &mut stack, &mut output));
+    Ok(output)
+}
+
+/// Appends `source` to `output` while replacing its `#include` directives.
+///
+/// `files` contains the names of the files that have been included so far, in order to assign
+/// source string numbers. `stack` contains the files that are currently being processed.
+fn process_source<F>(source: &str, source_number: usize, resolver: &mut F,
+                     files: &mut Vec<String>, stack: &mut Vec<String>, output: &mut String)
+                     -> Result<(), IncludeError>
+                     where F: FnMut(&str) -> Option<String>
+{
+    for (line_number, line) in source.lines().enumerate() {
+        let name = match try!(parse_include_directive(line)) {
+            Some(name) => name,
+            None => {
+                output.push_str(line);
+                output.push('\n');
+                continue;
+            }
+        };
+
+        if stack.iter().any(|n| n == name) {
+            return Err(IncludeError::RecursiveInclude { name: name.to_owned() });
+        }
+
+        let content = match resolver(name) {
+            Some(content) => content,
+            None => return Err(IncludeError::NotFound { name: name.to_owned() })
+        };
+
+        let included_number = match files.iter().position(|f| f == name) {
+            Some(index) => index + 1,
+            None => {
+                files.push(name.to_owned());
+                files.len()
+            }
+        };
+
+        output.push_str(&format!("#line 1 {}\n", included_number));
+
+        stack.push(name.to_owned());
+        try!(process_source(&content, included_number, resolver, files, stack, output));
+        stack.pop();
+
+        // `line_number` starts at 0, and we want the number of the line after the directive
+        output.push_str(&format!("#line {} {}\n", line_number + 2, source_number));
+    }
+
+    Ok(())
+}
+
+/// If the line is an `#include` directive, returns the name of the included file.
+fn parse_include_directive(line: &str) -> Result<Option<&str>, IncludeError> {
+    let line_trimmed = line.trim_left();
+    if !line_trimmed.starts_with('#') {
+        return Ok(None);
+    }
+
+    let directive = line_trimmed[1..].trim_left();
+    if !directive.starts_with("include") {
+        return Ok(None);
+    }
+
+    // ignoring identifiers that start with `include`
+    let rest = &directive["include".len()..];
+    match rest.chars().next() {
+        Some(c) if c.is_alphanumeric() || c == '_' => return Ok(None),
+        _ => ()
+    };
+
+    let invalid = || IncludeError::InvalidDirective { line: line.to_owned() };
+
+    let rest = rest.trim_left();
+    let closing = match rest.chars().next() {
+        Some('"') => '"',
+        Some('<') => '>',
+        _ => return Err(invalid())
+    };
+
+    let end = match rest[1..].find(closing) {
+        Some(end) => end + 1,
+        None => return Err(invalid())
+    };
+
+    let name = &rest[1 .. end];
+    let after = rest[end + 1..].trim();
+
+    if name.is_empty() || !(after.is_empty() || after.starts_with("//")) {
+        return Err(invalid());
+    }
+
+    Ok(Some(name))
+}
@@ -6,18 +6,32 @@ use CapabilitiesSource;
 use gl;
 use version::Api;
 use version::Version;
+use vertex::AttributeType;
+use DrawError;
 
+pub use self::adapt::adapt_shader_source;
+pub use self::chooser::ProgramChooser;
 pub use self::compute::{ComputeShader, ComputeCommand};
-pub use self::program::Program;
-pub use self::reflection::{Uniform, UniformBlock, BlockLayout, OutputPrimitives};
+pub use self::diagnostics::{ShaderDiagnostic, DiagnosticSeverity, parse_info_log};
+pub use self::include::{resolve_includes, IncludeError};
+pub use self::program::{Program, PendingProgram};
+pub use self::reflection::{Uniform, UniformBlock, BlockLayout, BlockMember, OutputPrimitives};
+pub use self::reflection::{ProgramReflection, TextureUnit, AtomicCounter};
+pub use self::reflection::GeometryInputPrimitives;
 pub use self::reflection::{Attribute, TransformFeedbackVarying, TransformFeedbackBuffer, TransformFeedbackMode};
-pub use self::reflection::{ShaderStage, SubroutineData, SubroutineUniform};
+pub use self::reflection::{ShaderStage, SubroutineData, SubroutineUniform, Subroutine};
+pub use self::shader_cache::{ShaderCache, DirectoryShaderCache};
 
+mod adapt;
+mod chooser;
 mod compute;
+mod diagnostics;
+mod include;
 mod program;
 mod raw;
 mod reflection;
 mod shader;
+mod shader_cache;
 mod uniforms_storage;
 mod binary_header;
 
@@ -53,6 +67,62 @@ pub fn is_subroutine_supported<C: ?Sized>(ctxt: &C) -> bool where C: Capabilitie
     ctxt.get_version() >= &Version(Api::Gl, 4, 0) || ctxt.get_extensions().gl_arb_shader_subroutine
 }
 
+/// Returns true if the backend can compile and link programs in the background.
+///
+/// If this returns false, `Program::new_async` can still be used but the compilation may happen
+/// synchronously, and `PendingProgram::is_ready` always returns true.
+#[inline]
+pub fn is_parallel_compile_supported<C: ?Sized>(ctxt: &C) -> bool where C: CapabilitiesSource {
+    ctxt.get_extensions().gl_khr_parallel_shader_compile ||
+        ctxt.get_extensions().gl_arb_parallel_shader_compile
+}
+
+/// Returns true if the backend supports creating shaders from SPIR-V modules.
+#[inline]
+pub fn is_spirv_supported<C: ?Sized>(ctxt: &C) -> bool where C: CapabilitiesSource {
+    ctxt.get_extensions().gl_arb_gl_spirv
+}
+
+/// Returns true if the backend supports binding the outputs of the fragment shader to
+/// explicit locations.
+#[inline]
+pub fn is_fragment_output_location_supported<C: ?Sized>(ctxt: &C) -> bool
+    where C: CapabilitiesSource
+{
+    ctxt.get_version() >= &Version(Api::Gl, 3, 0) || ctxt.get_extensions().gl_ext_gpu_shader4 ||
+        ctxt.get_extensions().gl_ext_blend_func_extended
+}
+
+/// Returns true if the backend supports dual-source blending, in other words binding fragment
+/// shader outputs to an index other than `0`.
+#[inline]
+pub fn is_dual_source_blending_supported<C: ?Sized>(ctxt: &C) -> bool
+    where C: CapabilitiesSource
+{
+    ctxt.get_version() >= &Version(Api::Gl, 3, 3) ||
+        ctxt.get_extensions().gl_arb_blend_func_extended ||
+        ctxt.get_extensions().gl_ext_blend_func_extended
+}
+
+/// Returns true if the backend supports programs that write the size of points to
+/// `gl_PointSize`.
+#[inline]
+pub fn is_program_point_size_supported<C: ?Sized>(ctxt: &C) -> bool
+    where C: CapabilitiesSource
+{
+    ctxt.get_version() >= &Version(Api::Gl, 3, 0) ||
+        ctxt.get_version() >= &Version(Api::GlEs, 2, 0)
+}
+
+/// Returns true if the backend supports uniforms of type `double`, `dvecN` and `dmatN`.
+#[inline]
+pub fn is_double_precision_supported<C: ?Sized>(ctxt: &C) -> bool
+    where C: CapabilitiesSource
+{
+    ctxt.get_version() >= &Version(Api::Gl, 4, 0) ||
+        ctxt.get_extensions().gl_arb_gpu_shader_fp64
+}
+
 /// Some shader compilers have race-condition issues, so we lock this mutex
 /// in the GL thread every time we compile a shader or link a program.
 // TODO: replace by a StaticMutex
@@ -77,6 +147,9 @@ pub enum ProgramCreationError {
     /// The OpenGL implementation doesn't provide a compiler.
     CompilationNotSupported,
 
+    /// You have passed SPIR-V modules, but the backend doesn't support them.
+    SpirvNotSupported,
+
     /// You have requested transform feedback varyings, but transform feedback is not supported
     /// by the backend.
     TransformFeedbackNotSupported,
@@ -87,6 +160,48 @@ pub enum ProgramCreationError {
 
     /// The glium-specific binary header was not found or is corrupt.
     BinaryHeaderError,
+
+    /// You have requested explicit locations for the outputs of the fragment shader, but this
+    /// is not supported by the backend.
+    FragmentOutputLocationNotSupported,
+
+    /// You have requested a fragment shader output to be bound to an index other than `0`, but
+    /// dual-source blending is not supported by the backend.
+    DualSourceBlendingNotSupported,
+
+    /// An attribute was given an explicit location, but the linker assigned it another one.
+    ///
+    /// This usually happens when the shader also specifies the location of the attribute with
+    /// a `layout(location = ...)` qualifier, which takes precedence.
+    AttributeLocationMismatch {
+        /// Name of the attribute.
+        name: String,
+        /// The location that was requested.
+        requested: u32,
+        /// The location that was assigned by the linker.
+        obtained: u32,
+    },
+
+    /// The program uses more samplers than the number of texture units of the backend.
+    TooManyTextureUnits {
+        /// The number of samplers used by the program.
+        required: u32,
+        /// The number of texture units of the backend.
+        available: u32,
+    },
+
+    /// An input of a shader stage is not written by the previous stage, or is written with a
+    /// different type.
+    StageInterfaceMismatch {
+        /// Name of the input. Can be empty for SPIR-V modules that don't contain names.
+        name: String,
+        /// Location of the input, or `None` if it is matched by name.
+        location: Option<u32>,
+        /// Type of the input.
+        input: AttributeType,
+        /// Type of the matching output of the previous stage, or `None` if there is none.
+        output: Option<AttributeType>,
+    },
 }
 
 impl fmt::Display for ProgramCreationError {
@@ -97,6 +212,15 @@ impl fmt::Display for ProgramCreationError {
                 write!(fmt, "{}: {}", self.description(), s),
             LinkingError(ref s) =>
                 write!(fmt, "{}: {}", self.description(), s),
+            AttributeLocationMismatch { ref name, requested, obtained } =>
+                write!(fmt, "{}: `{}` requested at {}, assigned to {}", self.description(),
+                       name, requested, obtained),
+            TooManyTextureUnits { required, available } =>
+                write!(fmt, "{}: {} required, {} available", self.description(), required,
+                       available),
+            StageInterfaceMismatch { ref name, location, input, output } =>
+                write!(fmt, "{}: `{}` (location {:?}) is read as {:?} but written as {:?}",
+                       self.description(), name, location, input, output),
             _ =>
                 write!(fmt, "{}", self.description()),
         }
@@ -115,17 +239,112 @@ impl Error for ProgramCreationError {
                 "One of the request shader type is not supported by the backend",
             CompilationNotSupported =>
                 "The backend doesn't support shaders compilation",
+            SpirvNotSupported =>
+                "The backend doesn't support SPIR-V modules",
             TransformFeedbackNotSupported =>
                 "Transform feedback is not supported by the backend.",
             PointSizeNotSupported =>
                 "Point size is not supported by the backend.",
             BinaryHeaderError =>
                 "The glium-specific binary header was not found or is corrupt.",
+            FragmentOutputLocationNotSupported =>
+                "Binding fragment shader outputs to explicit locations is not supported by \
+                 the backend.",
+            DualSourceBlendingNotSupported =>
+                "Dual-source blending is not supported by the backend.",
+            AttributeLocationMismatch { .. } =>
+                "An attribute was not assigned the location that was requested",
+            TooManyTextureUnits { .. } =>
+                "The program uses more samplers than there are texture units",
+            StageInterfaceMismatch { .. } =>
+                "An input of a shader stage doesn't match the outputs of the previous stage",
         }
     }
 }
 
-/// Error type that is returned by the `program!` macro.
+impl ProgramCreationError {
+    /// If this is a `CompilationError` or a `LinkingError`, parses the log of the compiler or
+    /// of the linker into a list of messages. Returns an empty list otherwise.
+    ///
+    /// See `parse_info_log` for more details.
+    #[inline]
+    pub fn diagnostics(&self) -> Vec<ShaderDiagnostic> {
+        match *self {
+            ProgramCreationError::CompilationError(ref log) |
+            ProgramCreationError::LinkingError(ref log) => parse_info_log(log),
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// Error that can happen when executing a compute shader.
+#[derive(Clone, Debug)]
+pub enum DispatchError {
+    /// Compute shaders are not supported by the backend.
+    ComputeShadersNotSupported,
+
+    /// The number of work groups exceeds the maximum supported by the backend.
+    WorkGroupCountTooLarge {
+        /// The maximum number of work groups in each dimension.
+        maximum: (u32, u32, u32),
+        /// The number of work groups that was requested.
+        obtained: (u32, u32, u32),
+    },
+
+    /// The offset of the buffer containing the dispatch parameters is not a multiple of 4.
+    UnalignedIndirectBuffer,
+
+    /// Error while binding the uniforms.
+    UniformsError(DrawError),
+}
+
+impl fmt::Display for DispatchError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        use self::DispatchError::*;
+        match *self {
+            WorkGroupCountTooLarge { ref maximum, ref obtained } =>
+                write!(fmt, "{}: found {:?}, maximum: {:?}", self.description(), obtained,
+                       maximum),
+            UniformsError(ref err) =>
+                write!(fmt, "{}: {}", self.description(), err),
+            _ =>
+                write!(fmt, "{}", self.description()),
+        }
+    }
+}
+
+impl Error for DispatchError {
+    fn description(&self) -> &str {
+        use self::DispatchError::*;
+        match *self {
+            ComputeShadersNotSupported =>
+                "Compute shaders are not supported by the backend",
+            WorkGroupCountTooLarge { .. } =>
+                "The number of work groups exceeds the maximum supported by the backend",
+            UnalignedIndirectBuffer =>
+                "The offset of the buffer containing the dispatch parameters is not a multiple of 4",
+            UniformsError(_) =>
+                "Error while binding the uniforms",
+        }
+    }
+
+    fn cause(&self) -> Option<&Error> {
+        use self::DispatchError::*;
+        match *self {
+            UniformsError(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<DrawError> for DispatchError {
+    #[inline]
+    fn from(err: DrawError) -> DispatchError {
+        DispatchError::UniformsError(err)
+    }
+}
+
+/// Error type that is returned by the `program!` macro and by `ProgramChooser`.
 #[derive(Clone, Debug)]
 pub enum ProgramChooserCreationError {
     /// No available version has been found.
@@ -229,6 +448,34 @@ pub enum ProgramCreationInput<'a> {
         uses_point_size: bool,
     },
 
+    /// Use SPIR-V modules.
+    ///
+    /// Contrary to GLSL source code, the uniforms, blocks and vertex attributes of a SPIR-V
+    /// module don't necessarily have names, so they should be given explicit locations and
+    /// bindings in the module.
+    SpirV {
+        /// The vertex shader.
+        vertex_shader: SpirvEntryPoint<'a>,
+
+        /// The optional tessellation control shader.
+        tessellation_control_shader: Option<SpirvEntryPoint<'a>>,
+
+        /// The optional tessellation evaluation shader.
+        tessellation_evaluation_shader: Option<SpirvEntryPoint<'a>>,
+
+        /// The optional geometry shader.
+        geometry_shader: Option<SpirvEntryPoint<'a>>,
+
+        /// The fragment shader.
+        fragment_shader: SpirvEntryPoint<'a>,
+
+        /// See `SourceCode::outputs_srgb`.
+        outputs_srgb: bool,
+
+        /// Whether the shader uses point size.
+        uses_point_size: bool,
+    },
+
     /// Use a precompiled binary.
     Binary {
         /// The data.
@@ -279,6 +526,106 @@ impl<'a> From<SourceCode<'a>> for ProgramCreationInput<'a> {
     }
 }
 
+/// Locations to assign to the inputs and outputs of a program before it is linked.
+///
+/// The locations are passed to the OpenGL linker, which allows using the same attribute
+/// slots and color attachments across multiple programs. See `Program::with_bindings`.
+///
+/// # Example
+///
+/// ```no_run
+/// # let display: glium::Display = unsafe { std::mem::uninitialized() };
+/// # let vertex_source = ""; let fragment_source = "";
+/// let bindings = glium::program::ProgramBindings::new()
+///                     .bind_attribute("position", 0)
+///                     .bind_attribute("normal", 1);
+///
+/// let program = glium::Program::with_bindings(&display, glium::program::SourceCode {
+///     vertex_shader: vertex_source,
+///     tessellation_control_shader: None,
+///     tessellation_evaluation_shader: None,
+///     geometry_shader: None,
+///     fragment_shader: fragment_source,
+/// }, &bindings).unwrap();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ProgramBindings {
+    attribute_locations: Vec<(String, u32)>,
+    fragment_output_locations: Vec<(String, u32, u32)>,
+}
+
+impl ProgramBindings {
+    /// Builds a new `ProgramBindings` that doesn't bind anything.
+    #[inline]
+    pub fn new() -> ProgramBindings {
+        ProgramBindings {
+            attribute_locations: Vec::new(),
+            fragment_output_locations: Vec::new(),
+        }
+    }
+
+    /// Binds the vertex attribute named `name` to `location`.
+    ///
+    /// Attributes that are not bound (or that are not active) are assigned a location by the
+    /// linker. A `layout(location = ...)` qualifier in the shader takes precedence, in which
+    /// case creating the program returns `AttributeLocationMismatch`.
+    #[inline]
+    pub fn bind_attribute<S>(mut self, name: S, location: u32) -> ProgramBindings
+                             where S: Into<String>
+    {
+        self.attribute_locations.push((name.into(), location));
+        self
+    }
+
+    /// Binds the fragment shader output named `name` to `location` and `index`.
+    ///
+    /// The location corresponds to the color attachment the output is written to. The index
+    /// must be `0`, except when using dual-source blending where the second color of each
+    /// attachment is written by the outputs of index `1`. Note that a
+    /// `layout(location = ...)` qualifier in the shader takes precedence.
+    ///
+    /// Creating the program returns `FragmentOutputLocationNotSupported` if the backend
+    /// doesn't support binding outputs, and `DualSourceBlendingNotSupported` if an index is
+    /// not `0` and the backend doesn't support dual-source blending.
+    #[inline]
+    pub fn bind_fragment_output<S>(mut self, name: S, location: u32, index: u32)
+                                   -> ProgramBindings where S: Into<String>
+    {
+        self.fragment_output_locations.push((name.into(), location, index));
+        self
+    }
+}
+
+/// An entry point of a SPIR-V module, and the values of its specialization constants.
+///
+/// The same module can be specialized multiple times with different constants in order to
+/// create multiple variants of a shader.
+///
+/// # Example
+///
+/// ```no_run
+/// # let module: Vec<u8> = vec![];
+/// let entry_point = glium::program::SpirvEntryPoint {
+///     binary: &module,
+///     entry_point: "main",
+///     // sets the constant declared with `layout(constant_id = 0)` to 4
+///     specialization_constants: &[(0, 4)],
+/// };
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct SpirvEntryPoint<'a> {
+    /// The SPIR-V module.
+    pub binary: &'a [u8],
+
+    /// Name of the function to use as the entry point, usually `main`.
+    pub entry_point: &'a str,
+
+    /// A list of `(constant_id, value)` pairs. Each value is the bit pattern of the constant,
+    /// for example `f32::to_bits(value)` for a float or `1` for a boolean that is true.
+    /// Constants that are not in this list keep their default value.
+    pub specialization_constants: &'a [(u32, u32)],
+}
+
 /// Represents the compiled binary data of a program.
 pub struct Binary {
     /// An implementation-defined format.
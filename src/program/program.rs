@@ -6,10 +6,12 @@ use version::Api;
 
 use backend::Facade;
 use CapabilitiesSource;
+use ContextExt;
 
 use std::fmt;
 use std::collections::hash_map::{self, HashMap};
-use std::hash::BuildHasherDefault;
+use std::hash::{BuildHasherDefault, Hasher};
+use std::rc::Rc;
 
 use fnv::FnvHasher;
 
@@ -17,19 +19,30 @@ use GlObject;
 use ProgramExt;
 use Handle;
 use RawUniformValue;
+use RawUniformArray;
 
 use program::{COMPILER_GLOBAL_LOCK, ProgramCreationInput, ProgramCreationError, Binary};
+use program::ProgramBindings;
 use program::GetBinaryError;
+use program::{is_fragment_output_location_supported, is_dual_source_blending_supported};
+use program::is_binary_supported;
+use program::is_program_point_size_supported;
 
-use program::reflection::{Uniform, UniformBlock, OutputPrimitives};
+use program::reflection::{Uniform, UniformBlock, OutputPrimitives, GeometryInputPrimitives};
+use program::reflection::{ProgramReflection, TextureUnit, AtomicCounter};
 use program::reflection::{Attribute, TransformFeedbackBuffer};
 use program::reflection::{SubroutineData, ShaderStage, SubroutineUniform};
-use program::shader::build_shader;
+use program::shader::{Shader, check_stage_interfaces, compile_shader, specialize_shader};
 
-use program::raw::RawProgram;
+use program::raw::{RawProgram, PendingLink};
 
+use vertex;
 use vertex::VertexFormat;
 
+use DrawError;
+
+use uniforms::{Uniforms, UniformLocation};
+
 /// A combination of shaders linked together.
 pub struct Program {
     raw: RawProgram,
@@ -39,12 +52,71 @@ pub struct Program {
 
 impl Program {
     /// Builds a new program.
+    #[inline]
     pub fn new<'a, F: ?Sized, I>(facade: &F, input: I) -> Result<Program, ProgramCreationError>
                          where I: Into<ProgramCreationInput<'a>>, F: Facade
     {
-        let input = input.into();
+        try!(Program::new_async(facade, input)).finish()
+    }
+
+    /// Builds a new program, and assigns the locations of `bindings` to its inputs before
+    /// linking it.
+    ///
+    /// The bindings only apply to GLSL source code. Program binaries already contain the
+    /// locations they were linked with, and the inputs of SPIR-V modules should be given
+    /// explicit locations in the module.
+    #[inline]
+    pub fn with_bindings<'a, F: ?Sized, I>(facade: &F, input: I, bindings: &ProgramBindings)
+                                           -> Result<Program, ProgramCreationError>
+                                           where I: Into<ProgramCreationInput<'a>>, F: Facade
+    {
+        try!(Program::start(facade, input.into(), bindings)).finish()
+    }
 
-        let (raw, outputs_srgb, uses_point_size) = match input {
+    /// Starts building a new program, without waiting for the compilation to finish.
+    ///
+    /// If the backend supports parallel shader compilation, the shaders are compiled and linked
+    /// in the background. You can call `is_ready` on the returned object to know whether the
+    /// program is available, and `finish` to retreive it. Calling `finish` before the
+    /// program is ready blocks until it is.
+    ///
+    /// This allows compiling lots of programs (for example during a loading screen) without
+    /// blocking the rendering. Errors that happen during the compilation or the linking are
+    /// returned by `finish`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # let display: glium::Display = unsafe { std::mem::uninitialized() };
+    /// # let vertex_source = ""; let fragment_source = "";
+    /// let pending = glium::Program::new_async(&display, glium::program::SourceCode {
+    ///     vertex_shader: vertex_source,
+    ///     tessellation_control_shader: None,
+    ///     tessellation_evaluation_shader: None,
+    ///     geometry_shader: None,
+    ///     fragment_shader: fragment_source,
+    /// }).unwrap();
+    ///
+    /// while !pending.is_ready() {
+    ///     // draw the loading screen
+    /// }
+    ///
+    /// let program = pending.finish().unwrap();
+    /// ```
+    #[inline]
+    pub fn new_async<'a, F: ?Sized, I>(facade: &F, input: I)
+                                       -> Result<PendingProgram, ProgramCreationError>
+                                       where I: Into<ProgramCreationInput<'a>>, F: Facade
+    {
+        Program::start(facade, input.into(), &ProgramBindings::new())
+    }
+
+    /// Starts building a new program with the given bindings.
+    fn start<'a, F: ?Sized>(facade: &F, input: ProgramCreationInput<'a>,
+                            bindings: &ProgramBindings)
+                            -> Result<PendingProgram, ProgramCreationError> where F: Facade
+    {
+        let (state, outputs_srgb, uses_point_size) = match input {
             ProgramCreationInput::SourceCode { vertex_shader, tessellation_control_shader,
                                                tessellation_evaluation_shader, geometry_shader,
                                                fragment_shader, transform_feedback_varyings,
@@ -54,15 +126,8 @@ impl Program {
                 let mut has_tessellation_control_shader = false;
                 let mut has_tessellation_evaluation_shader = false;
 
-                let mut shaders = vec![
-                    (vertex_shader, gl::VERTEX_SHADER),
-                    (fragment_shader, gl::FRAGMENT_SHADER)
-                ];
-
-                if let Some(gs) = geometry_shader {
-                    shaders.push((gs, gl::GEOMETRY_SHADER));
-                    has_geometry_shader = true;
-                }
+                // the shaders are in the order of the pipeline, see `check_stage_interfaces`
+                let mut shaders = vec![(vertex_shader, gl::VERTEX_SHADER)];
 
                 if let Some(ts) = tessellation_control_shader {
                     shaders.push((ts, gl::TESS_CONTROL_SHADER));
@@ -74,15 +139,32 @@ impl Program {
                     has_tessellation_evaluation_shader = true;
                 }
 
-                // TODO: move somewhere else
+                if let Some(gs) = geometry_shader {
+                    shaders.push((gs, gl::GEOMETRY_SHADER));
+                    has_geometry_shader = true;
+                }
+
+                shaders.push((fragment_shader, gl::FRAGMENT_SHADER));
+
                 if transform_feedback_varyings.is_some() &&
-                    !(facade.get_context().get_version() >= &Version(Api::Gl, 3, 0)) &&
-                    !facade.get_context().get_extensions().gl_ext_transform_feedback
+                    !vertex::is_transform_feedback_supported(facade)
                 {
                     return Err(ProgramCreationError::TransformFeedbackNotSupported);
                 }
 
-                if uses_point_size && !(facade.get_context().get_version() >= &Version(Api::Gl, 3, 0)) {
+                if !bindings.fragment_output_locations.is_empty() &&
+                    !is_fragment_output_location_supported(facade)
+                {
+                    return Err(ProgramCreationError::FragmentOutputLocationNotSupported);
+                }
+
+                if bindings.fragment_output_locations.iter().any(|&(_, _, index)| index != 0) &&
+                    !is_dual_source_blending_supported(facade)
+                {
+                    return Err(ProgramCreationError::DualSourceBlendingNotSupported);
+                }
+
+                if uses_point_size && !is_program_point_size_supported(facade) {
                     return Err(ProgramCreationError::PointSizeNotSupported);
                 }
 
@@ -91,27 +173,81 @@ impl Program {
                 let shaders_store = {
                     let mut shaders_store = Vec::new();
                     for (src, ty) in shaders.into_iter() {
-                        shaders_store.push(try!(build_shader(facade, ty, src)));
+                        shaders_store.push(try!(compile_shader(facade, ty, src)));
                     }
                     shaders_store
                 };
 
-                (try!(RawProgram::from_shaders(facade, &shaders_store, has_geometry_shader,
-                                               has_tessellation_control_shader, has_tessellation_evaluation_shader,
-                                               transform_feedback_varyings)),
+                let link = RawProgram::start_linking(facade, &shaders_store, has_geometry_shader,
+                                                     has_tessellation_control_shader,
+                                                     has_tessellation_evaluation_shader,
+                                                     transform_feedback_varyings,
+                                                     &bindings.attribute_locations,
+                                                     &bindings.fragment_output_locations);
+
+                (PendingProgramState::Linking { shaders: shaders_store, link: link,
+                                                spirv: false },
+                 outputs_srgb, uses_point_size)
+            },
+
+            ProgramCreationInput::SpirV { vertex_shader, tessellation_control_shader,
+                                          tessellation_evaluation_shader, geometry_shader,
+                                          fragment_shader, outputs_srgb, uses_point_size } =>
+            {
+                // the shaders are in the order of the pipeline, see `check_stage_interfaces`
+                let mut shaders = vec![(vertex_shader, gl::VERTEX_SHADER)];
+
+                if let Some(ts) = tessellation_control_shader {
+                    shaders.push((ts, gl::TESS_CONTROL_SHADER));
+                }
+
+                if let Some(ts) = tessellation_evaluation_shader {
+                    shaders.push((ts, gl::TESS_EVALUATION_SHADER));
+                }
+
+                if let Some(gs) = geometry_shader {
+                    shaders.push((gs, gl::GEOMETRY_SHADER));
+                }
+
+                shaders.push((fragment_shader, gl::FRAGMENT_SHADER));
+
+                if uses_point_size && !is_program_point_size_supported(facade) {
+                    return Err(ProgramCreationError::PointSizeNotSupported);
+                }
+
+                let _lock = COMPILER_GLOBAL_LOCK.lock();
+
+                let shaders_store = {
+                    let mut shaders_store = Vec::new();
+                    for (entry_point, ty) in shaders.into_iter() {
+                        shaders_store.push(try!(specialize_shader(facade, ty, &entry_point)));
+                    }
+                    shaders_store
+                };
+
+                let link = RawProgram::start_linking(facade, &shaders_store,
+                                                     geometry_shader.is_some(),
+                                                     tessellation_control_shader.is_some(),
+                                                     tessellation_evaluation_shader.is_some(),
+                                                     None, &[], &[]);
+
+                (PendingProgramState::Linking { shaders: shaders_store, link: link,
+                                                spirv: true },
                  outputs_srgb, uses_point_size)
             },
 
             ProgramCreationInput::Binary { data, outputs_srgb, uses_point_size } => {
-                if uses_point_size && !(facade.get_context().get_version() >= &Version(Api::Gl, 3, 0)) {
+                if uses_point_size && !is_program_point_size_supported(facade) {
                     return Err(ProgramCreationError::PointSizeNotSupported);
                 }
 
-                (try!(RawProgram::from_binary(facade, data)), outputs_srgb, uses_point_size)
+                (PendingProgramState::Linked(try!(RawProgram::from_binary(facade, data))),
+                 outputs_srgb, uses_point_size)
             },
         };
-        Ok(Program {
-            raw: raw,
+
+        Ok(PendingProgram {
+            state: state,
             outputs_srgb: outputs_srgb,
             uses_point_size: uses_point_size,
         })
@@ -127,6 +263,10 @@ impl Program {
     /// - `fragment_shader`: Source code of the fragment shader.
     /// - `geometry_shader`: Source code of the geometry shader.
     ///
+    /// If a shader cache has been set with `Context::set_shader_cache` and the backend supports
+    /// program binaries, the program is loaded from the cache if possible, and stored in the
+    /// cache otherwise.
+    ///
     /// # Example
     ///
     /// ```no_run
@@ -136,10 +276,74 @@ impl Program {
     ///     Some(geometry_source));
     /// ```
     ///
-    #[inline]
     pub fn from_source<'a, F: ?Sized>(facade: &F, vertex_shader: &'a str, fragment_shader: &'a str,
                               geometry_shader: Option<&'a str>)
                               -> Result<Program, ProgramCreationError> where F: Facade
+    {
+        let cache = match facade.get_context().get_shader_cache() {
+            Some(ref cache) if is_binary_supported(facade) => cache.clone(),
+            _ => return Program::from_source_uncached(facade, vertex_shader, fragment_shader,
+                                                       geometry_shader),
+        };
+
+        // the driver strings are part of the key, as binaries are invalidated by driver updates
+        // the keys are stored on the disk, so the bytes are hashed with FNV instead of using
+        // `Hash` and `DefaultHasher`, whose results can change between Rust releases
+        // the same bytes are written at the start of each entry, so that an entry stored by
+        // another program whose key collides with this one is not loaded
+        let (key, header) = {
+            let context = facade.get_context();
+            let mut header = Vec::new();
+            for string in &[Some(vertex_shader), Some(fragment_shader), geometry_shader,
+                            Some(context.get_opengl_vendor_string()),
+                            Some(context.get_opengl_renderer_string()),
+                            Some(context.get_opengl_version_string())]
+            {
+                match *string {
+                    Some(string) => {
+                        let len = string.len() as u64;
+                        header.push(1);
+                        header.extend((0 .. 8).map(|i| (len >> (i * 8)) as u8));
+                        header.extend(string.as_bytes());
+                    },
+                    None => header.push(0),
+                }
+            }
+
+            let mut hasher = FnvHasher::default();
+            hasher.write(&header);
+            (hasher.finish(), header)
+        };
+
+        if let Some(mut binary) = cache.load(key) {
+            // the binary can be rejected by the backend, in which case we compile the program
+            if binary.content.starts_with(&header) {
+                binary.content.drain(.. header.len());
+                if let Ok(program) = Program::new(facade, binary) {
+                    return Ok(program);
+                }
+            }
+        }
+
+        let program = try!(Program::from_source_uncached(facade, vertex_shader, fragment_shader,
+                                                         geometry_shader));
+
+        if let Ok(binary) = program.get_binary() {
+            let mut content = header;
+            content.extend(binary.content.iter());
+            cache.store(key, &Binary { format: binary.format, content: content });
+        }
+
+        Ok(program)
+    }
+
+    /// Same as `from_source`, but without the shader cache.
+    #[inline]
+    fn from_source_uncached<'a, F: ?Sized>(facade: &F, vertex_shader: &'a str,
+                                           fragment_shader: &'a str,
+                                           geometry_shader: Option<&'a str>)
+                                           -> Result<Program, ProgramCreationError>
+                                           where F: Facade
     {
         Program::new(facade, ProgramCreationInput::SourceCode {
             vertex_shader: vertex_shader,
@@ -153,6 +357,45 @@ impl Program {
         })
     }
 
+    /// Builds a new program from GLSL source code, or returns an existing one if the program
+    /// cache of the context is enabled and a program has already been built from the same
+    /// source code.
+    ///
+    /// The program cache is disabled by default. See `Context::set_program_cache_enabled`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # let display: glium::Display = unsafe { std::mem::uninitialized() };
+    /// # let vertex_source = ""; let fragment_source = "";
+    /// display.set_program_cache_enabled(true);
+    ///
+    /// let program1 = glium::Program::from_source_cached(&display, vertex_source,
+    ///                                                   fragment_source, None).unwrap();
+    /// let program2 = glium::Program::from_source_cached(&display, vertex_source,
+    ///                                                   fragment_source, None).unwrap();
+    /// assert!(std::rc::Rc::ptr_eq(&program1, &program2));
+    /// ```
+    pub fn from_source_cached<'a, F: ?Sized>(facade: &F, vertex_shader: &'a str,
+                                             fragment_shader: &'a str,
+                                             geometry_shader: Option<&'a str>)
+                                             -> Result<Rc<Program>, ProgramCreationError>
+                                             where F: Facade
+    {
+        if let Some(program) = facade.get_context().get_cached_program(vertex_shader,
+                                                                       fragment_shader,
+                                                                       geometry_shader)
+        {
+            return Ok(program);
+        }
+
+        let program = Rc::new(try!(Program::from_source(facade, vertex_shader, fragment_shader,
+                                                        geometry_shader)));
+        facade.get_context().cache_program(vertex_shader, fragment_shader, geometry_shader,
+                                           &program);
+        Ok(program)
+    }
+
     /// Returns the program's compiled binary.
     ///
     /// You can store the result in a file, then reload it later. This avoids having to compile
@@ -178,12 +421,93 @@ impl Program {
         self.raw.get_frag_data_location(name)
     }
 
+    /// Returns everything that is known about the interface of the program.
+    ///
+    /// The uniforms, blocks, attributes and transform feedback buffers are also available
+    /// through the more specific methods of this struct.
+    #[inline]
+    pub fn get_reflection(&self) -> &ProgramReflection {
+        self.raw.get_reflection()
+    }
+
     /// Returns informations about a uniform variable, if it exists.
     #[inline]
     pub fn get_uniform(&self, name: &str) -> Option<&Uniform> {
         self.raw.get_uniform(name)
     }
 
+    /// Returns the members of a uniform struct or of an element of an array of structs, sorted
+    /// by name.
+    ///
+    /// The names of the members don't include the name of the struct. Members that are
+    /// themselves structs or arrays are returned with their full path, and can be set with a
+    /// name such as `lights[2].position`.
+    ///
+    /// ## Example
+    ///
+    /// ```no_run
+    /// # let program: glium::Program = unsafe { std::mem::uninitialized() };
+    /// for (member, uniform) in program.get_uniform_struct_members("lights[2]") {
+    ///     println!("lights[2].{} - Type: {:?}", member, uniform.ty);
+    /// }
+    /// ```
+    #[inline]
+    pub fn get_uniform_struct_members(&self, name: &str) -> Vec<(&str, &Uniform)> {
+        self.raw.get_uniform_struct_members(name)
+    }
+
+    /// Returns the location of a uniform variable, if it exists.
+    ///
+    /// The location can be passed to a `LocatedUniformsStorage` in order to set the value of
+    /// the uniform without looking up its name each time you draw.
+    #[inline]
+    pub fn get_uniform_location(&self, name: &str) -> Option<UniformLocation> {
+        self.raw.get_uniform(name).map(|uniform| {
+            UniformLocation {
+                location: uniform.location,
+                ty: uniform.ty,
+            }
+        })
+    }
+
+    /// Returns the texture unit that is used when a texture is bound to the given sampler, if
+    /// it exists.
+    ///
+    /// Each sampler of the program is assigned its own texture unit when the program is
+    /// linked, in the order of their names. Creating the program fails with
+    /// `TooManyTextureUnits` if the backend doesn't have enough units.
+    #[inline]
+    pub fn get_texture_unit(&self, name: &str) -> Option<u32> {
+        self.raw.get_texture_unit(name)
+    }
+
+    /// Returns the texture units assigned to the samplers of the program, sorted by unit.
+    #[inline]
+    pub fn get_texture_units(&self) -> &[TextureUnit] {
+        self.raw.get_texture_units()
+    }
+
+    /// Updates the values of the uniforms of the program ahead of drawing.
+    ///
+    /// Textures, blocks and subroutines are ignored, as they are bound when drawing. If
+    /// `glProgramUniform` is supported (OpenGL 4.1, OpenGL ES 3.1 or
+    /// `GL_ARB_separate_shader_objects`), the program isn't made current, which avoids
+    /// switching programs when preparing many of them at once.
+    ///
+    /// ## Example
+    ///
+    /// ```no_run
+    /// # #[macro_use] extern crate glium;
+    /// # fn main() {
+    /// # let program: glium::Program = unsafe { ::std::mem::uninitialized() };
+    /// program.update_uniforms(&uniform! { color: [1.0f32, 0.0, 0.0, 1.0] }).unwrap();
+    /// # }
+    /// ```
+    #[inline]
+    pub fn update_uniforms<U>(&self, uniforms: &U) -> Result<(), DrawError> where U: Uniforms {
+        self.raw.update_uniforms(uniforms)
+    }
+
     /// Returns an iterator to the list of uniforms.
     ///
     /// ## Example
@@ -221,6 +545,17 @@ impl Program {
         self.raw.get_transform_feedback_buffers()
     }
 
+    /// Checks that each attribute of the vertex shader is provided by one of the vertex formats,
+    /// with the same number of components. Returns a `DrawError::VertexInterfaceMismatch`
+    /// otherwise.
+    ///
+    /// This is done automatically when drawing. The result is cached, so a list of formats is
+    /// only checked the first time it is used with this program.
+    #[inline]
+    pub fn validate_vertex_formats(&self, formats: &[&VertexFormat]) -> Result<(), DrawError> {
+        self.raw.validate_vertex_formats(formats)
+    }
+
     /// True if the transform feedback output of this program matches the specified `VertexFormat`
     /// and `stride`.
     ///
@@ -240,6 +575,27 @@ impl Program {
         self.raw.get_output_primitives()
     }
 
+    /// Returns the type of primitives that the geometry shader expects as input, or `None` if
+    /// the program doesn't contain a geometry shader.
+    ///
+    /// This corresponds to `GL_GEOMETRY_INPUT_TYPE`. Drawing primitives that don't match this
+    /// type returns a `DrawError::GeometryInputPrimitivesMismatch`.
+    #[inline]
+    pub fn get_geometry_input_primitives(&self) -> Option<GeometryInputPrimitives> {
+        self.raw.get_geometry_input_primitives()
+    }
+
+    /// Returns whether an output of the fragment shader is bound to the index `1`, or `None` if
+    /// the backend can't enumerate the outputs of the program (this requires OpenGL 4.3,
+    /// OpenGL ES 3.1 or `GL_ARB_program_interface_query`).
+    ///
+    /// Drawing with dual-source blending factors returns a `DrawError::DualSourceOutputMissing`
+    /// if this is `Some(false)`.
+    #[inline]
+    pub fn has_dual_source_output(&self) -> Option<bool> {
+        self.raw.has_dual_source_output()
+    }
+
     /// Returns true if the program contains a tessellation stage.
     #[inline]
     pub fn has_tessellation_shaders(&self) -> bool {
@@ -307,6 +663,22 @@ impl Program {
         self.raw.get_shader_storage_blocks()
     }
 
+    /// Returns the list of atomic counters.
+    ///
+    /// ## Example
+    ///
+    /// ```no_run
+    /// # let program: glium::Program = unsafe { std::mem::uninitialized() };
+    /// for (name, counter) in program.get_atomic_counters() {
+    ///     println!("Name: {} - Binding: {}", name, counter.binding);
+    /// }
+    /// ```
+    #[inline]
+    pub fn get_atomic_counters(&self)
+            -> &HashMap<String, AtomicCounter, BuildHasherDefault<FnvHasher>> {
+        self.raw.get_atomic_counters()
+    }
+
     /// Returns the subroutine uniforms of this program.
     ///
     /// Since subroutine uniforms are unique per shader and *not* per program,
@@ -335,6 +707,77 @@ impl Program {
     }
 }
 
+/// A program whose compilation may still be running in the background.
+///
+/// Built with `Program::new_async`.
+pub struct PendingProgram {
+    state: PendingProgramState,
+    outputs_srgb: bool,
+    uses_point_size: bool,
+}
+
+enum PendingProgramState {
+    Linking {
+        // the shaders must be kept alive in order to retreive their compilation errors
+        shaders: Vec<Shader>,
+        link: PendingLink,
+        // true if the shaders are SPIR-V modules
+        spirv: bool,
+    },
+    Linked(RawProgram),
+}
+
+impl PendingProgram {
+    /// Returns true if the compilation and the linking are finished, in which case `finish`
+    /// will not block.
+    ///
+    /// Always returns true if the backend doesn't support parallel shader compilation.
+    #[inline]
+    pub fn is_ready(&self) -> bool {
+        match self.state {
+            PendingProgramState::Linking { ref link, .. } => link.is_finished(),
+            PendingProgramState::Linked(_) => true,
+        }
+    }
+
+    /// Waits for the compilation and the linking to finish, and returns the program.
+    pub fn finish(self) -> Result<Program, ProgramCreationError> {
+        let raw = match self.state {
+            PendingProgramState::Linking { shaders, link, spirv } => {
+                let _lock = COMPILER_GLOBAL_LOCK.lock();
+
+                for shader in shaders.iter() {
+                    try!(shader.check_compilation());
+                }
+
+                // the GLSL linker already rejects mismatched stages, but only with a log message,
+                // while SPIR-V stages are matched by location and mismatches are not always
+                // detected
+                let link = link.finish();
+                if link.is_err() || spirv {
+                    try!(check_stage_interfaces(&shaders));
+                }
+
+                try!(link)
+            },
+            PendingProgramState::Linked(raw) => raw,
+        };
+
+        Ok(Program {
+            raw: raw,
+            outputs_srgb: self.outputs_srgb,
+            uses_point_size: self.uses_point_size,
+        })
+    }
+}
+
+impl fmt::Debug for PendingProgram {
+    #[inline]
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(formatter, "PendingProgram {{ ready: {} }}", self.is_ready())
+    }
+}
+
 impl fmt::Debug for Program {
     #[inline]
     fn fmt(&self, formatter: &mut fmt::Formatter) -> Result<(), fmt::Error> {
@@ -353,11 +796,16 @@ impl GlObject for Program {
 
 impl ProgramExt for Program {
     fn use_program(&self, ctxt: &mut CommandContext) {
-        // compatibility was checked at program creation
-        if self.uses_point_size && !ctxt.state.enabled_program_point_size {
-            unsafe { ctxt.gl.Enable(gl::PROGRAM_POINT_SIZE); }
-        } else if !self.uses_point_size && ctxt.state.enabled_program_point_size {
-            unsafe { ctxt.gl.Disable(gl::PROGRAM_POINT_SIZE); }
+        // compatibility was checked at program creation ; OpenGL ES always uses the
+        // value of `gl_PointSize` and doesn't have `GL_PROGRAM_POINT_SIZE`
+        if ctxt.version.0 != Api::GlEs {
+            if self.uses_point_size && !ctxt.state.enabled_program_point_size {
+                unsafe { ctxt.gl.Enable(gl::PROGRAM_POINT_SIZE); }
+                ctxt.state.enabled_program_point_size = true;
+            } else if !self.uses_point_size && ctxt.state.enabled_program_point_size {
+                unsafe { ctxt.gl.Disable(gl::PROGRAM_POINT_SIZE); }
+                ctxt.state.enabled_program_point_size = false;
+            }
         }
 
         if ctxt.version >= &Version(Api::Gl, 3, 0) || ctxt.extensions.gl_arb_framebuffer_srgb ||
@@ -384,6 +832,13 @@ impl ProgramExt for Program {
         self.raw.set_uniform(ctxt, uniform_location, value)
     }
 
+    #[inline]
+    fn set_uniform_array(&self, ctxt: &mut CommandContext, uniform_location: gl::types::GLint,
+                         value: &RawUniformArray)
+    {
+        self.raw.set_uniform_array(ctxt, uniform_location, value)
+    }
+
     #[inline]
     fn set_uniform_block_binding(&self, ctxt: &mut CommandContext, block_location: gl::types::GLuint,
                                  value: gl::types::GLuint)
@@ -412,6 +867,11 @@ impl ProgramExt for Program {
         self.raw.get_uniform(name)
     }
 
+    #[inline]
+    fn get_texture_units(&self) -> &[TextureUnit] {
+        self.raw.get_texture_units()
+    }
+
     #[inline]
     fn get_uniform_blocks(&self) -> &HashMap<String, UniformBlock, BuildHasherDefault<FnvHasher>> {
         self.raw.get_uniform_blocks()
@@ -423,6 +883,12 @@ impl ProgramExt for Program {
         self.raw.get_shader_storage_blocks()
     }
 
+    #[inline]
+    fn get_atomic_counters(&self)
+                           -> &HashMap<String, AtomicCounter, BuildHasherDefault<FnvHasher>> {
+        self.raw.get_atomic_counters()
+    }
+
     #[inline]
     fn get_subroutine_data(&self) -> &SubroutineData {
         self.raw.get_subroutine_data()
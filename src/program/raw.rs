@@ -18,11 +18,12 @@ use std::hash::BuildHasherDefault;
 
 use fnv::FnvHasher;
 
-use DrawError;
 use GlObject;
 use ProgramExt;
 use Handle;
 use RawUniformValue;
+use RawUniformArray;
+use DrawError;
 
 use QueryExt;
 use draw_parameters::TimeElapsedQuery;
@@ -31,21 +32,23 @@ use buffer::BufferSlice;
 use BufferExt;
 use BufferSliceExt;
 
-use program::{ProgramCreationError, Binary, GetBinaryError};
+use program::{ProgramCreationError, Binary, GetBinaryError, DispatchError};
 use program::uniforms_storage::UniformsStorage;
 
 use program::compute::ComputeCommand;
-use program::reflection::{Uniform, UniformBlock, OutputPrimitives};
+use program::reflection::{Uniform, UniformBlock, OutputPrimitives, GeometryInputPrimitives};
+use program::reflection::{TextureUnit, AtomicCounter, assign_texture_units};
 use program::reflection::{Attribute, TransformFeedbackMode, TransformFeedbackBuffer};
 use program::reflection::{SubroutineData, ShaderStage};
-use program::reflection::{reflect_uniforms, reflect_attributes, reflect_uniform_blocks};
-use program::reflection::{reflect_transform_feedback, reflect_geometry_output_type};
-use program::reflection::{reflect_tess_eval_output_type, reflect_shader_storage_blocks};
-use program::reflection::{reflect_subroutine_data};
+use program::reflection::{ProgramReflection, reflect_program, reflect_geometry_output_type};
+use program::reflection::{reflect_geometry_input_type, reflect_tess_eval_output_type};
+use program::reflection::{reflect_subroutine_data, reflect_dual_source_output};
 use program::shader::Shader;
 use program::binary_header::{attach_glium_header, process_glium_header};
 
 use uniforms::Uniforms;
+use TransformFeedbackSessionExt;
+use vertex::TransformFeedbackSession;
 
 use vertex::VertexFormat;
 use vertex_array_object::VertexAttributesSystem;
@@ -55,14 +58,16 @@ pub struct RawProgram {
     context: Rc<Context>,
     id: Handle,
     uniform_values: UniformsStorage,
-    uniforms: HashMap<String, Uniform, BuildHasherDefault<FnvHasher>>,
-    uniform_blocks: HashMap<String, UniformBlock, BuildHasherDefault<FnvHasher>>,
+    reflection: ProgramReflection,
+    texture_units: Vec<TextureUnit>,
     subroutine_data: SubroutineData,
-    attributes: HashMap<String, Attribute, BuildHasherDefault<FnvHasher>>,
     frag_data_locations: RefCell<HashMap<String, Option<u32>, BuildHasherDefault<FnvHasher>>>,
-    tf_buffers: Vec<TransformFeedbackBuffer>,
-    ssbos: HashMap<String, UniformBlock, BuildHasherDefault<FnvHasher>>,
     output_primitives: Option<OutputPrimitives>,
+    geometry_input_primitives: Option<GeometryInputPrimitives>,
+    dual_source_output: Option<bool>,
+    // lists of vertex formats that have been successfully checked with
+    // `validate_vertex_formats`
+    validated_vertex_formats: RefCell<Vec<Vec<VertexFormat>>>,
     has_geometry_shader: bool,
     has_tessellation_control_shader: bool,
     has_tessellation_evaluation_shader: bool,
@@ -71,12 +76,35 @@ pub struct RawProgram {
 impl RawProgram {
     /// Builds a new program from a list of shaders.
     // TODO: the "has_*" parameters are bad
+    #[inline]
     pub fn from_shaders<'a, F: ?Sized, I>(facade: &'a F, shaders: I, has_geometry_shader: bool,
                                   has_tessellation_control_shader: bool,
                                   has_tessellation_evaluation_shader: bool,
-                                  transform_feedback: Option<(Vec<String>, TransformFeedbackMode)>)
+                                  transform_feedback: Option<(Vec<String>, TransformFeedbackMode)>,
+                                  attribute_locations: &[(String, u32)],
+                                  fragment_output_locations: &[(String, u32, u32)])
                                   -> Result<RawProgram, ProgramCreationError>
                                   where F: Facade, I: IntoIterator<Item = &'a Shader>
+    {
+        RawProgram::start_linking(facade, shaders, has_geometry_shader,
+                                  has_tessellation_control_shader,
+                                  has_tessellation_evaluation_shader, transform_feedback,
+                                  attribute_locations, fragment_output_locations).finish()
+    }
+
+    /// Starts linking a list of shaders together.
+    ///
+    /// If the backend supports it, the linking happens in the background and the returned
+    /// object can be polled to know whether it is finished.
+    pub fn start_linking<'a, F: ?Sized, I>(facade: &'a F, shaders: I, has_geometry_shader: bool,
+                                   has_tessellation_control_shader: bool,
+                                   has_tessellation_evaluation_shader: bool,
+                                   transform_feedback: Option<(Vec<String>,
+                                                               TransformFeedbackMode)>,
+                                   attribute_locations: &[(String, u32)],
+                                   fragment_output_locations: &[(String, u32, u32)])
+                                   -> PendingLink
+                                   where F: Facade, I: IntoIterator<Item = &'a Shader>
     {
         let mut ctxt = facade.get_context().make_current();
 
@@ -114,7 +142,9 @@ impl RawProgram {
                 }).collect::<Vec<_>>();
                 let names_ptr = names.iter().map(|n| n.as_ptr()).collect::<Vec<_>>();
 
-                if ctxt.version >= &Version(Api::Gl, 3, 0) {
+                if ctxt.version >= &Version(Api::Gl, 3, 0) ||
+                   ctxt.version >= &Version(Api::GlEs, 3, 0)
+                {
                     let mode = match mode {
                         TransformFeedbackMode::Interleaved => gl::INTERLEAVED_ATTRIBS,
                         TransformFeedbackMode::Separate => gl::SEPARATE_ATTRIBS,
@@ -138,6 +168,55 @@ impl RawProgram {
                 }
             }
 
+            // explicit attribute locations
+            for &(ref name, location) in attribute_locations.iter() {
+                let name = ffi::CString::new(name.as_bytes()).unwrap();
+
+                match id {
+                    Handle::Id(id) => {
+                        assert!(ctxt.version >= &Version(Api::Gl, 2, 0) ||
+                                ctxt.version >= &Version(Api::GlEs, 2, 0));
+                        ctxt.gl.BindAttribLocation(id, location, name.as_ptr());
+                    },
+                    Handle::Handle(id) => {
+                        assert!(ctxt.extensions.gl_arb_vertex_shader);
+                        ctxt.gl.BindAttribLocationARB(id, location, name.as_ptr());
+                    }
+                }
+            }
+
+            // explicit fragment output locations
+            for &(ref name, location, index) in fragment_output_locations.iter() {
+                let id = match id {
+                    Handle::Id(id) => id,
+                    Handle::Handle(_) => unreachable!()     // has been checked in the frontend
+                };
+
+                let name = ffi::CString::new(name.as_bytes()).unwrap();
+
+                if index != 0 {
+                    if ctxt.version >= &Version(Api::Gl, 3, 3) ||
+                       ctxt.extensions.gl_arb_blend_func_extended
+                    {
+                        ctxt.gl.BindFragDataLocationIndexed(id, location, index, name.as_ptr());
+                    } else if ctxt.extensions.gl_ext_blend_func_extended {
+                        ctxt.gl.BindFragDataLocationIndexedEXT(id, location, index,
+                                                               name.as_ptr());
+                    } else {
+                        unreachable!();     // has been checked in the frontend
+                    }
+
+                } else if ctxt.version >= &Version(Api::Gl, 3, 0) {
+                    ctxt.gl.BindFragDataLocation(id, location, name.as_ptr());
+                } else if ctxt.extensions.gl_ext_gpu_shader4 ||
+                          ctxt.extensions.gl_ext_blend_func_extended
+                {
+                    ctxt.gl.BindFragDataLocationEXT(id, location, name.as_ptr());
+                } else {
+                    unreachable!();     // has been checked in the frontend
+                }
+            }
+
             // linking
             {
                 ctxt.report_debug_output_errors.set(false);
@@ -157,43 +236,86 @@ impl RawProgram {
                 ctxt.report_debug_output_errors.set(true);
             }
 
-            // checking for errors
-            try!(check_program_link_errors(&mut ctxt, id));
-
             id
         };
 
-        let uniforms = unsafe { reflect_uniforms(&mut ctxt, id) };
-        let attributes = unsafe { reflect_attributes(&mut ctxt, id) };
-        let blocks = unsafe { reflect_uniform_blocks(&mut ctxt, id) };
-        let tf_buffers = unsafe { reflect_transform_feedback(&mut ctxt, id) };
-        let ssbos = unsafe { reflect_shader_storage_blocks(&mut ctxt, id) };
+        PendingLink {
+            context: facade.get_context().clone(),
+            id: Some(id),
+            has_geometry_shader: has_geometry_shader,
+            has_tessellation_control_shader: has_tessellation_control_shader,
+            has_tessellation_evaluation_shader: has_tessellation_evaluation_shader,
+            attribute_locations: attribute_locations.to_vec(),
+        }
+    }
+
+    /// Builds a `RawProgram` from a program that has been successfully linked.
+    fn from_linked(context: Rc<Context>, ctxt: &mut CommandContext, id: Handle,
+                   has_geometry_shader: bool, has_tessellation_control_shader: bool,
+                   has_tessellation_evaluation_shader: bool,
+                   attribute_locations: &[(String, u32)])
+                   -> Result<RawProgram, ProgramCreationError>
+    {
+        let reflection = unsafe { reflect_program(ctxt, id) };
+
+        // each sampler gets its own texture unit, so that all of them can be used at once
+        let texture_units = assign_texture_units(&reflection.uniforms);
+        if texture_units.len() > ctxt.capabilities.max_combined_texture_image_units as usize {
+            return Err(ProgramCreationError::TooManyTextureUnits {
+                required: texture_units.len() as u32,
+                available: ctxt.capabilities.max_combined_texture_image_units as u32,
+            });
+        }
+
+        // the linker silently ignores the requested locations if the shader specifies its own
+        for &(ref name, requested) in attribute_locations.iter() {
+            if let Some(attribute) = reflection.inputs.get(name) {
+                if attribute.location != requested as i32 {
+                    return Err(ProgramCreationError::AttributeLocationMismatch {
+                        name: name.clone(),
+                        requested: requested,
+                        obtained: attribute.location as u32,
+                    });
+                }
+            }
+        }
+
         let subroutine_data = unsafe {
-            reflect_subroutine_data(&mut ctxt, id, has_geometry_shader,
+            reflect_subroutine_data(ctxt, id, has_geometry_shader,
                                     has_tessellation_control_shader,
                                     has_tessellation_evaluation_shader)
             };
 
         let output_primitives = if has_geometry_shader {
-            Some(unsafe { reflect_geometry_output_type(&mut ctxt, id) })
+            Some(unsafe { reflect_geometry_output_type(ctxt, id) })
         } else if has_tessellation_evaluation_shader {
-            Some(unsafe { reflect_tess_eval_output_type(&mut ctxt, id) })
+            Some(unsafe { reflect_tess_eval_output_type(ctxt, id) })
+        } else {
+            None
+        };
+
+        let geometry_input_primitives = if has_geometry_shader {
+            Some(unsafe { reflect_geometry_input_type(ctxt, id) })
         } else {
             None
         };
 
+        let dual_source_output = unsafe {
+            reflect_dual_source_output(ctxt, id, &reflection.outputs)
+        };
+
         Ok(RawProgram {
-            context: facade.get_context().clone(),
+            context: context,
             id: id,
-            uniforms: uniforms,
+            reflection: reflection,
+            texture_units: texture_units,
             uniform_values: UniformsStorage::new(),
-            uniform_blocks: blocks,
             subroutine_data: subroutine_data,
-            attributes: attributes,
             frag_data_locations: RefCell::new(HashMap::with_hasher(Default::default())),
-            tf_buffers: tf_buffers,
-            ssbos: ssbos,
             output_primitives: output_primitives,
+            geometry_input_primitives: geometry_input_primitives,
+            dual_source_output: dual_source_output,
+            validated_vertex_formats: RefCell::new(Vec::new()),
             has_geometry_shader: has_geometry_shader,
             has_tessellation_control_shader: has_tessellation_control_shader,
             has_tessellation_evaluation_shader: has_tessellation_evaluation_shader,
@@ -227,48 +349,24 @@ impl RawProgram {
             };
 
             // checking for errors
-            try!(check_program_link_errors(&mut ctxt, id));
+            if let Err(err) = check_program_link_errors(&mut ctxt, id) {
+                delete_program(&mut ctxt, id);
+                return Err(err);
+            }
 
             id
         };
 
-        let (uniforms, attributes, blocks, tf_buffers, ssbos, subroutine_data) = unsafe {
-            (
-                reflect_uniforms(&mut ctxt, id),
-                reflect_attributes(&mut ctxt, id),
-                reflect_uniform_blocks(&mut ctxt, id),
-                reflect_transform_feedback(&mut ctxt, id),
-                reflect_shader_storage_blocks(&mut ctxt, id),
-                reflect_subroutine_data(&mut ctxt, id, has_geometry_shader,
-                                        has_tessellation_control_shader,
-                                        has_tessellation_evaluation_shader),
-            )
-        };
+        let result = RawProgram::from_linked(facade.get_context().clone(), &mut ctxt, id,
+                                             has_geometry_shader,
+                                             has_tessellation_control_shader,
+                                             has_tessellation_evaluation_shader, &[]);
 
-        let output_primitives = if has_geometry_shader {
-            Some(unsafe { reflect_geometry_output_type(&mut ctxt, id) })
-        } else if has_tessellation_evaluation_shader {
-            Some(unsafe { reflect_tess_eval_output_type(&mut ctxt, id) })
-        } else {
-            None
-        };
+        if result.is_err() {
+            unsafe { delete_program(&mut ctxt, id); }
+        }
 
-        Ok(RawProgram {
-            context: facade.get_context().clone(),
-            id: id,
-            uniforms: uniforms,
-            uniform_values: UniformsStorage::new(),
-            uniform_blocks: blocks,
-            subroutine_data: subroutine_data,
-            attributes: attributes,
-            frag_data_locations: RefCell::new(HashMap::with_hasher(Default::default())),
-            tf_buffers: tf_buffers,
-            ssbos: ssbos,
-            output_primitives: output_primitives,
-            has_geometry_shader: has_geometry_shader,
-            has_tessellation_control_shader: has_tessellation_control_shader,
-            has_tessellation_evaluation_shader: has_tessellation_evaluation_shader,
-        })
+        result
     }
 
     /// Returns the program's compiled binary.
@@ -358,10 +456,46 @@ impl RawProgram {
         location
     }
 
+    /// Returns everything that is known about the interface of the program.
+    #[inline]
+    pub fn get_reflection(&self) -> &ProgramReflection {
+        &self.reflection
+    }
+
     /// Returns informations about a uniform variable, if it exists.
     #[inline]
     pub fn get_uniform(&self, name: &str) -> Option<&Uniform> {
-        self.uniforms.get(name)
+        self.reflection.uniforms.get(name)
+    }
+
+    /// Returns the members of a uniform struct, sorted by name.
+    ///
+    /// The names of the members don't include the name of the struct.
+    pub fn get_uniform_struct_members(&self, name: &str) -> Vec<(&str, &Uniform)> {
+        let mut members = self.reflection.uniforms.iter().filter_map(|(member, uniform)| {
+            if member.len() > name.len() + 1 && member.starts_with(name) &&
+               member.as_bytes()[name.len()] == b'.'
+            {
+                Some((&member[name.len() + 1 ..], uniform))
+            } else {
+                None
+            }
+        }).collect::<Vec<_>>();
+
+        members.sort_by(|a, b| a.0.cmp(b.0));
+        members
+    }
+
+    /// Returns the texture unit assigned to a sampler, if it exists.
+    #[inline]
+    pub fn get_texture_unit(&self, name: &str) -> Option<u32> {
+        self.texture_units.iter().find(|t| t.name == name).map(|t| t.unit)
+    }
+
+    /// Returns the texture units assigned to the samplers of the program, sorted by unit.
+    #[inline]
+    pub fn get_texture_units(&self) -> &[TextureUnit] {
+        &self.texture_units
     }
 
     /// Returns an iterator to the list of uniforms.
@@ -376,7 +510,7 @@ impl RawProgram {
     /// ```
     #[inline]
     pub fn uniforms(&self) -> hash_map::Iter<String, Uniform> {
-        self.uniforms.iter()
+        self.reflection.uniforms.iter()
     }
 
     /// Returns a list of uniform blocks.
@@ -392,13 +526,51 @@ impl RawProgram {
     #[inline]
     pub fn get_uniform_blocks(&self)
                               -> &HashMap<String, UniformBlock, BuildHasherDefault<FnvHasher>> {
-        &self.uniform_blocks
+        &self.reflection.uniform_blocks
     }
 
     /// Returns the list of transform feedback varyings.
     #[inline]
     pub fn get_transform_feedback_buffers(&self) -> &[TransformFeedbackBuffer] {
-        &self.tf_buffers
+        &self.reflection.transform_feedback_buffers
+    }
+
+    /// Checks that each attribute of the vertex shader is provided by one of the vertex formats,
+    /// with the same number of components.
+    ///
+    /// The result is cached, so a list of formats is only checked the first time it is used
+    /// with this program.
+    pub fn validate_vertex_formats(&self, formats: &[&VertexFormat]) -> Result<(), DrawError> {
+        let already_validated = self.validated_vertex_formats.borrow().iter().any(|validated| {
+            validated.len() == formats.len() &&
+                validated.iter().zip(formats.iter()).all(|(a, &b)| a == b)
+        });
+
+        if already_validated {
+            return Ok(());
+        }
+
+        for (name, attribute) in self.attributes() {
+            let obtained = formats.iter()
+                                  .flat_map(|format| format.iter())
+                                  .find(|&&(ref n, _, _)| n == name)
+                                  .map(|&(_, _, ty)| ty);
+
+            match obtained {
+                Some(ty) if attribute.size == 1 &&
+                            ty.get_num_components() == attribute.ty.get_num_components() => (),
+                _ => return Err(DrawError::VertexInterfaceMismatch {
+                    name: name.clone(),
+                    location: attribute.location,
+                    expected: attribute.ty,
+                    obtained: obtained,
+                }),
+            }
+        }
+
+        self.validated_vertex_formats.borrow_mut()
+                                     .push(formats.iter().map(|&f| f.clone()).collect());
+        Ok(())
     }
 
     /// True if the transform feedback output of this program matches the specified `VertexFormat`
@@ -439,6 +611,22 @@ impl RawProgram {
         self.output_primitives
     }
 
+    /// Returns the type of primitives that the geometry shader expects as input, or `None` if
+    /// the program doesn't contain a geometry shader.
+    ///
+    /// This corresponds to `GL_GEOMETRY_INPUT_TYPE`.
+    #[inline]
+    pub fn get_geometry_input_primitives(&self) -> Option<GeometryInputPrimitives> {
+        self.geometry_input_primitives
+    }
+
+    /// Returns whether an output of the fragment shader is bound to the index `1`, or `None` if
+    /// the backend can't enumerate the outputs of the program.
+    #[inline]
+    pub fn has_dual_source_output(&self) -> Option<bool> {
+        self.dual_source_output
+    }
+
     /// Returns true if the program contains a tessellation stage.
     #[inline]
     pub fn has_tessellation_shaders(&self) -> bool {
@@ -466,7 +654,7 @@ impl RawProgram {
     /// Returns informations about an attribute, if it exists.
     #[inline]
     pub fn get_attribute(&self, name: &str) -> Option<&Attribute> {
-        self.attributes.get(name)
+        self.reflection.inputs.get(name)
     }
 
     /// Returns an iterator to the list of attributes.
@@ -481,7 +669,7 @@ impl RawProgram {
     /// ```
     #[inline]
     pub fn attributes(&self) -> hash_map::Iter<String, Attribute> {
-        self.attributes.iter()
+        self.reflection.inputs.iter()
     }
 
     /// Returns the list of shader storage blocks.
@@ -497,7 +685,23 @@ impl RawProgram {
     #[inline]
     pub fn get_shader_storage_blocks(&self)
             -> &HashMap<String, UniformBlock, BuildHasherDefault<FnvHasher>> {
-        &self.ssbos
+        &self.reflection.shader_storage_blocks
+    }
+
+    /// Returns the list of atomic counters.
+    ///
+    /// ## Example
+    ///
+    /// ```no_run
+    /// # let program: glium::Program = unsafe { std::mem::uninitialized() };
+    /// for (name, counter) in program.get_atomic_counters() {
+    ///     println!("Name: {} - Binding: {}", name, counter.binding);
+    /// }
+    /// ```
+    #[inline]
+    pub fn get_atomic_counters(&self)
+            -> &HashMap<String, AtomicCounter, BuildHasherDefault<FnvHasher>> {
+        &self.reflection.atomic_counters
     }
 
     /// Returns data associated with the programs subroutines.
@@ -513,19 +717,26 @@ impl RawProgram {
     /// The program *must* contain a compute shader.
     /// TODO: check inside the program if it has a compute shader instead of being unsafe
     pub unsafe fn dispatch_compute<U>(&self, uniforms: U, x: u32, y: u32, z: u32)
-                                      -> Result<(), DrawError>      // TODO: other error?
+                                      -> Result<(), DispatchError>
                                       where U: Uniforms
     {
         let mut ctxt = self.context.make_current();
 
-        // TODO: return an error instead
-        assert!(x < ctxt.capabilities.max_compute_work_group_count.0 as u32);
-        assert!(y < ctxt.capabilities.max_compute_work_group_count.1 as u32);
-        assert!(z < ctxt.capabilities.max_compute_work_group_count.2 as u32);
+        if !(ctxt.version >= &Version(Api::Gl, 4, 3)) &&
+           !(ctxt.version >= &Version(Api::GlEs, 3, 1)) &&
+           !ctxt.extensions.gl_arb_compute_shader
+        {
+            return Err(DispatchError::ComputeShadersNotSupported);
+        }
 
-        assert!(ctxt.version >= &Version(Api::Gl, 4, 3) ||
-                ctxt.version >= &Version(Api::GlEs, 3, 1) ||
-                ctxt.extensions.gl_arb_compute_shader);
+        let maximum = ctxt.capabilities.max_compute_work_group_count;
+        let maximum = (maximum.0 as u32, maximum.1 as u32, maximum.2 as u32);
+        if x > maximum.0 || y > maximum.1 || z > maximum.2 {
+            return Err(DispatchError::WorkGroupCountTooLarge {
+                maximum: maximum,
+                obtained: (x, y, z),
+            });
+        }
 
         TimeElapsedQuery::end_conditional_render(&mut ctxt);
 
@@ -534,6 +745,7 @@ impl RawProgram {
         self.use_program(&mut ctxt);
         try!(uniforms.bind_uniforms(&mut ctxt, self, &mut fences));
         ctxt.gl.DispatchCompute(x, y, z);
+        ctxt.state.next_draw_call_id += 1;
 
         for fence in fences {
             fence.insert(&mut ctxt);
@@ -550,33 +762,40 @@ impl RawProgram {
     /// TODO: check inside the program if it has a compute shader instead of being unsafe
     pub unsafe fn dispatch_compute_indirect<U>(&self, uniforms: U,
                                                buffer: BufferSlice<ComputeCommand>)
-                                               -> Result<(), DrawError>      // TODO: other error?
+                                               -> Result<(), DispatchError>
                                                where U: Uniforms
     {
         let mut ctxt = self.context.make_current();
 
-        assert!(ctxt.version >= &Version(Api::Gl, 4, 3) ||
-                ctxt.version >= &Version(Api::GlEs, 3, 1) ||
-                ctxt.extensions.gl_arb_compute_shader);
-
-        TimeElapsedQuery::end_conditional_render(&mut ctxt);
+        if !(ctxt.version >= &Version(Api::Gl, 4, 3)) &&
+           !(ctxt.version >= &Version(Api::GlEs, 3, 1)) &&
+           !ctxt.extensions.gl_arb_compute_shader
+        {
+            return Err(DispatchError::ComputeShadersNotSupported);
+        }
 
-        buffer.prepare_and_bind_for_dispatch_indirect(&mut ctxt);
+        // an error is generated if the offset is not a multiple of 4
         let offset = buffer.get_offset_bytes();
+        if offset % 4 != 0 {
+            return Err(DispatchError::UnalignedIndirectBuffer);
+        }
 
-        // an error is generated if the offset is not a multiple of 4
-        assert!(offset % 4 == 0);
+        TimeElapsedQuery::end_conditional_render(&mut ctxt);
 
+        let mut fences = Vec::with_capacity(0);
+
+        // the fence must be inserted after the dispatch command that reads the buffer
         if let Some(fence) = buffer.add_fence() {
-            fence.insert(&mut ctxt);
+            fences.push(fence);
         }
 
         self.use_program(&mut ctxt);
-
-        let mut fences = Vec::with_capacity(0);
         try!(uniforms.bind_uniforms(&mut ctxt, self, &mut fences));
 
+        buffer.prepare_and_bind_for_dispatch_indirect(&mut ctxt);
+
         ctxt.gl.DispatchComputeIndirect(offset as gl::types::GLintptr);
+        ctxt.state.next_draw_call_id += 1;
 
         for fence in fences {
             fence.insert(&mut ctxt);
@@ -584,6 +803,16 @@ impl RawProgram {
 
         Ok(())
     }
+
+    /// Updates the values of the uniforms of the program ahead of drawing.
+    ///
+    /// Textures, blocks and subroutines are ignored, as they are bound when drawing. If
+    /// `glProgramUniform` is supported (OpenGL 4.1, OpenGL ES 3.1 or
+    /// `GL_ARB_separate_shader_objects`), the program isn't made current.
+    pub fn update_uniforms<U>(&self, uniforms: &U) -> Result<(), DrawError> where U: Uniforms {
+        let mut ctxt = self.context.make_current();
+        uniforms.set_uniform_values(&mut ctxt, self)
+    }
 }
 
 impl fmt::Debug for RawProgram {
@@ -608,6 +837,9 @@ impl ProgramExt for RawProgram {
         unsafe {
             let program_id = self.get_id();
             if ctxt.state.program != program_id {
+                // the program can't be changed while transform feedback is active
+                TransformFeedbackSession::unbind(ctxt);
+
                 match program_id {
                     Handle::Id(id) => ctxt.gl.UseProgram(id),
                     Handle::Handle(id) => ctxt.gl.UseProgramObjectARB(id),
@@ -621,7 +853,14 @@ impl ProgramExt for RawProgram {
     fn set_uniform(&self, ctxt: &mut CommandContext, uniform_location: gl::types::GLint,
                    value: &RawUniformValue)
     {
-        self.uniform_values.set_uniform_value(ctxt, self.id, uniform_location, value);
+        self.uniform_values.set_uniform_value(ctxt, self, uniform_location, value);
+    }
+
+    #[inline]
+    fn set_uniform_array(&self, ctxt: &mut CommandContext, uniform_location: gl::types::GLint,
+                         value: &RawUniformArray)
+    {
+        self.uniform_values.set_uniform_array(ctxt, self, uniform_location, value);
     }
 
     #[inline]
@@ -649,18 +888,29 @@ impl ProgramExt for RawProgram {
 
     #[inline]
     fn get_uniform(&self, name: &str) -> Option<&Uniform> {
-        self.uniforms.get(name)
+        self.reflection.uniforms.get(name)
+    }
+
+    #[inline]
+    fn get_texture_units(&self) -> &[TextureUnit] {
+        &self.texture_units
     }
 
     #[inline]
     fn get_uniform_blocks(&self) -> &HashMap<String, UniformBlock, BuildHasherDefault<FnvHasher>> {
-        &self.uniform_blocks
+        &self.reflection.uniform_blocks
     }
 
     #[inline]
     fn get_shader_storage_blocks(&self)
                                  -> &HashMap<String, UniformBlock, BuildHasherDefault<FnvHasher>> {
-        &self.ssbos
+        &self.reflection.shader_storage_blocks
+    }
+
+    #[inline]
+    fn get_atomic_counters(&self)
+                           -> &HashMap<String, AtomicCounter, BuildHasherDefault<FnvHasher>> {
+        &self.reflection.atomic_counters
     }
 
     #[inline]
@@ -677,30 +927,97 @@ impl Drop for RawProgram {
         VertexAttributesSystem::purge_program(&mut ctxt, self.id);
 
         // sending the destroy command
-        unsafe {
-            match self.id {
-                Handle::Id(id) => {
-                    assert!(ctxt.version >= &Version(Api::Gl, 2, 0) ||
-                            ctxt.version >= &Version(Api::GlEs, 2, 0));
+        unsafe { delete_program(&mut ctxt, self.id); }
+    }
+}
 
-                    if ctxt.state.program == Handle::Id(id) {
-                        ctxt.gl.UseProgram(0);
-                        ctxt.state.program = Handle::Id(0);
-                    }
+/// A program whose linking has been started, but whose result hasn't been retreived yet.
+pub struct PendingLink {
+    context: Rc<Context>,
+    // `None` once the program has been turned into a `RawProgram`
+    id: Option<Handle>,
+    has_geometry_shader: bool,
+    has_tessellation_control_shader: bool,
+    has_tessellation_evaluation_shader: bool,
+    attribute_locations: Vec<(String, u32)>,
+}
 
-                    ctxt.gl.DeleteProgram(id);
-                },
-                Handle::Handle(id) => {
-                    assert!(ctxt.extensions.gl_arb_shader_objects);
+impl PendingLink {
+    /// Returns true if the linking is finished, in which case `finish` will not block.
+    ///
+    /// Always returns true if the backend doesn't support parallel shader compilation.
+    pub fn is_finished(&self) -> bool {
+        let ctxt = self.context.make_current();
 
-                    if ctxt.state.program == Handle::Handle(id) {
-                        ctxt.gl.UseProgramObjectARB(0 as gl::types::GLhandleARB);
-                        ctxt.state.program = Handle::Handle(0 as gl::types::GLhandleARB);
-                    }
+        if !ctxt.extensions.gl_khr_parallel_shader_compile &&
+           !ctxt.extensions.gl_arb_parallel_shader_compile
+        {
+            return true;
+        }
 
-                    ctxt.gl.DeleteObjectARB(id);
-                }
+        match self.id {
+            Some(Handle::Id(id)) => unsafe {
+                let mut status: gl::types::GLint = mem::uninitialized();
+                ctxt.gl.GetProgramiv(id, gl::COMPLETION_STATUS_KHR, &mut status);
+                status != 0
+            },
+            _ => true
+        }
+    }
+
+    /// Waits for the linking to finish and returns the program.
+    pub fn finish(mut self) -> Result<RawProgram, ProgramCreationError> {
+        let id = self.id.take().unwrap();
+        let mut ctxt = self.context.make_current();
+
+        let result = unsafe { check_program_link_errors(&mut ctxt, id) }.and_then(|_| {
+            RawProgram::from_linked(self.context.clone(), &mut ctxt, id,
+                                    self.has_geometry_shader,
+                                    self.has_tessellation_control_shader,
+                                    self.has_tessellation_evaluation_shader,
+                                    &self.attribute_locations)
+        });
+
+        if result.is_err() {
+            unsafe { delete_program(&mut ctxt, id); }
+        }
+
+        result
+    }
+}
+
+impl Drop for PendingLink {
+    fn drop(&mut self) {
+        if let Some(id) = self.id {
+            let mut ctxt = self.context.make_current();
+            unsafe { delete_program(&mut ctxt, id); }
+        }
+    }
+}
+
+/// Destroys a program from within the GL context.
+unsafe fn delete_program(ctxt: &mut CommandContext, id: Handle) {
+    match id {
+        Handle::Id(id) => {
+            assert!(ctxt.version >= &Version(Api::Gl, 2, 0) ||
+                    ctxt.version >= &Version(Api::GlEs, 2, 0));
+
+            if ctxt.state.program == Handle::Id(id) {
+                ctxt.gl.UseProgram(0);
+                ctxt.state.program = Handle::Id(0);
+            }
+
+            ctxt.gl.DeleteProgram(id);
+        },
+        Handle::Handle(id) => {
+            assert!(ctxt.extensions.gl_arb_shader_objects);
+
+            if ctxt.state.program == Handle::Handle(id) {
+                ctxt.gl.UseProgramObjectARB(0 as gl::types::GLhandleARB);
+                ctxt.state.program = Handle::Handle(0 as gl::types::GLhandleARB);
             }
+
+            ctxt.gl.DeleteObjectARB(id);
         }
     }
 }
@@ -13,6 +13,7 @@ use context::CommandContext;
 use version::Version;
 use version::Api;
 
+use index::PrimitiveType;
 use uniforms::UniformType;
 use vertex::AttributeType;
 use program;
@@ -34,6 +35,37 @@ pub struct Uniform {
     pub size: Option<usize>,
 }
 
+/// The texture unit assigned to a sampler uniform when the program was linked.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextureUnit {
+    /// Name of the sampler uniform.
+    ///
+    /// Each element of an array of samplers has its own entry, for example `textures[1]`.
+    pub name: String,
+
+    /// The location of the uniform.
+    ///
+    /// This is internal information, you probably don't need to use it.
+    pub location: i32,
+
+    /// The texture unit that is used whenever a texture is bound to this uniform.
+    pub unit: u32,
+}
+
+/// Information about an atomic counter (except its name).
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct AtomicCounter {
+    /// The index of the `GL_ATOMIC_COUNTER_BUFFER` bind point the counter reads from, as
+    /// given by `layout(binding = N)` in the shader.
+    pub binding: u32,
+
+    /// Offset in bytes of the counter from the start of the buffer bound to `binding`.
+    pub offset: usize,
+
+    /// Number of elements if the counter is an array, `1` otherwise.
+    pub array_size: usize,
+}
+
 /// Information about a uniform block (except its name).
 #[derive(Debug, Clone)]
 pub struct UniformBlock {
@@ -52,6 +84,49 @@ pub struct UniformBlock {
 
     /// Layout of the block.
     pub layout: BlockLayout,
+
+    /// The list of members of the block, as reported by the backend.
+    ///
+    /// Contrary to `layout`, this contains the strides of arrays and matrices, which you need
+    /// in order to fill a buffer whose layout isn't known in advance.
+    pub members: Vec<BlockMember>,
+}
+
+/// Information about a member of a uniform block or of a shader storage block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockMember {
+    /// Full name of the member, for example `lights[0].color`.
+    pub name: String,
+
+    /// Type of the member.
+    pub ty: UniformType,
+
+    /// Offset of the member in bytes from the start of the buffer. If the member is an array,
+    /// this is the offset of the first element.
+    pub offset: usize,
+
+    /// Number of elements if the member is an array, `1` otherwise. `0` for an array whose
+    /// size isn't known at compile-time.
+    pub array_size: usize,
+
+    /// Number of bytes between two elements of the array, or `0` if the member is not an array.
+    pub array_stride: usize,
+
+    /// Number of bytes between two columns (or two rows if `row_major` is true) of the matrix,
+    /// or `0` if the member is not a matrix.
+    pub matrix_stride: usize,
+
+    /// True if the member is a matrix stored in row-major order.
+    pub row_major: bool,
+
+    /// If the member is part of an array of structs at the top level of a shader storage
+    /// block, contains the number of elements of this array. `0` if the array is unsized, and
+    /// `1` otherwise. Always `1` for members of uniform blocks.
+    pub top_level_array_size: usize,
+
+    /// Number of bytes between two elements of the top-level array of structs the member is
+    /// part of, or `0` if there is no such array. Always `0` for members of uniform blocks.
+    pub top_level_array_stride: usize,
 }
 
 /// Layout of a shader storage buffer or a uniform buffer.
@@ -122,6 +197,44 @@ pub struct Attribute {
     pub size: usize,
 }
 
+/// Everything that has been found out about the interface of a program when it was linked.
+///
+/// If the backend supports the program interface query (OpenGL 4.3, OpenGL ES 3.1 or
+/// `GL_ARB_program_interface_query`), this information is obtained through it. Otherwise the
+/// older queries that are specific to each kind of variable are used instead.
+#[derive(Debug, Clone)]
+pub struct ProgramReflection {
+    /// The uniforms of the program, including the members of the uniform blocks. Each element
+    /// of an array has its own entry.
+    pub uniforms: HashMap<String, Uniform, BuildHasherDefault<FnvHasher>>,
+
+    /// The uniform blocks of the program.
+    pub uniform_blocks: HashMap<String, UniformBlock, BuildHasherDefault<FnvHasher>>,
+
+    /// The shader storage blocks of the program.
+    pub shader_storage_blocks: HashMap<String, UniformBlock, BuildHasherDefault<FnvHasher>>,
+
+    /// The atomic counters of the program. Arrays of counters have a single entry whose name
+    /// doesn't include the `[0]` suffix.
+    pub atomic_counters: HashMap<String, AtomicCounter, BuildHasherDefault<FnvHasher>>,
+
+    /// The inputs of the first stage of the program, in other words its vertex attributes.
+    pub inputs: HashMap<String, Attribute, BuildHasherDefault<FnvHasher>>,
+
+    /// The outputs of the last stage of the program, usually the outputs of the fragment
+    /// shader.
+    ///
+    /// The older queries can't enumerate the outputs, so this is always empty if the program
+    /// interface query is not supported.
+    pub outputs: HashMap<String, Attribute, BuildHasherDefault<FnvHasher>>,
+
+    /// The buffers that receive the output of transform feedback.
+    ///
+    /// These are always obtained with the older queries, as the program interface query only
+    /// reports which buffer a varying is written to starting from OpenGL 4.4.
+    pub transform_feedback_buffers: Vec<TransformFeedbackBuffer>,
+}
+
 /// Describes the layout of a buffer that can receive transform feedback output.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct TransformFeedbackBuffer {
@@ -176,8 +289,258 @@ pub enum OutputPrimitives {
     Quads,
 }
 
-pub unsafe fn reflect_uniforms(ctxt: &mut CommandContext, program: Handle)
-                               -> HashMap<String, Uniform, BuildHasherDefault<FnvHasher>>
+/// Type of primitives that a geometry shader expects as input.
+///
+/// This corresponds to the input layout qualifier of the geometry shader, for example
+/// `layout(triangles) in;`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum GeometryInputPrimitives {
+    /// Points.
+    Points,
+    /// Lines.
+    Lines,
+    /// Lines with adjacency information.
+    LinesAdjacency,
+    /// Triangles.
+    Triangles,
+    /// Triangles with adjacency information.
+    TrianglesAdjacency,
+}
+
+impl GeometryInputPrimitives {
+    /// Returns true if primitives of the given type can be sent to a geometry shader that
+    /// expects this input.
+    pub fn accepts(&self, primitives: PrimitiveType) -> bool {
+        match (*self, primitives) {
+            (GeometryInputPrimitives::Points, PrimitiveType::Points) => true,
+            (GeometryInputPrimitives::Lines, PrimitiveType::LinesList) => true,
+            (GeometryInputPrimitives::Lines, PrimitiveType::LineStrip) => true,
+            (GeometryInputPrimitives::Lines, PrimitiveType::LineLoop) => true,
+            (GeometryInputPrimitives::LinesAdjacency, PrimitiveType::LinesListAdjacency) => true,
+            (GeometryInputPrimitives::LinesAdjacency, PrimitiveType::LineStripAdjacency) => true,
+            (GeometryInputPrimitives::Triangles, PrimitiveType::TrianglesList) => true,
+            (GeometryInputPrimitives::Triangles, PrimitiveType::TriangleStrip) => true,
+            (GeometryInputPrimitives::Triangles, PrimitiveType::TriangleFan) => true,
+            (GeometryInputPrimitives::TrianglesAdjacency,
+             PrimitiveType::TrianglesListAdjacency) => true,
+            (GeometryInputPrimitives::TrianglesAdjacency,
+             PrimitiveType::TriangleStripAdjacency) => true,
+            _ => false,
+        }
+    }
+}
+
+/// Returns true if the backend supports `glGetProgramInterfaceiv` and
+/// `glGetProgramResourceiv`.
+#[inline]
+fn is_program_interface_query_supported(ctxt: &CommandContext) -> bool {
+    ctxt.version >= &Version(Api::Gl, 4, 3) || ctxt.version >= &Version(Api::GlEs, 3, 1) ||
+    ctxt.extensions.gl_arb_program_interface_query
+}
+
+/// Returns whether an output of the fragment shader is bound to the index `1`, or `None` if
+/// this can't be determined because the outputs can't be enumerated.
+///
+/// # Unsafety
+///
+/// - `program` must be a valid handle to a linked program.
+pub unsafe fn reflect_dual_source_output(ctxt: &mut CommandContext, program: Handle,
+                                         outputs: &HashMap<String, Attribute,
+                                                           BuildHasherDefault<FnvHasher>>)
+                                         -> Option<bool>
+{
+    let id = match program {
+        Handle::Id(id) if is_program_interface_query_supported(ctxt) => id,
+        _ => return None,
+    };
+
+    // shaders that only write built-in outputs don't appear in the list
+    if outputs.is_empty() {
+        return None;
+    }
+
+    let result = outputs.keys().any(|name| {
+        let name = ffi::CString::new(name.as_bytes()).unwrap();
+
+        let index = if ctxt.version >= &Version(Api::Gl, 3, 3) ||
+                       ctxt.extensions.gl_arb_blend_func_extended
+        {
+            ctxt.gl.GetFragDataIndex(id, name.as_ptr())
+        } else if ctxt.extensions.gl_ext_blend_func_extended {
+            ctxt.gl.GetFragDataIndexEXT(id, name.as_ptr())
+        } else {
+            0
+        };
+
+        index == 1
+    });
+
+    Some(result)
+}
+
+/// Obtains all the information about the interface of a program.
+///
+/// # Unsafety
+///
+/// - `program` must be a valid handle to a linked program.
+pub unsafe fn reflect_program(ctxt: &mut CommandContext, program: Handle) -> ProgramReflection {
+    let id = match program {
+        Handle::Id(id) if is_program_interface_query_supported(ctxt) => id,
+        _ => {
+            return ProgramReflection {
+                uniforms: reflect_uniforms(ctxt, program),
+                uniform_blocks: reflect_uniform_blocks(ctxt, program),
+                shader_storage_blocks: HashMap::with_hasher(Default::default()),
+                atomic_counters: reflect_atomic_counters(ctxt, program),
+                inputs: reflect_attributes(ctxt, program),
+                outputs: HashMap::with_hasher(Default::default()),
+                transform_feedback_buffers: reflect_transform_feedback(ctxt, program),
+            };
+        }
+    };
+
+    ProgramReflection {
+        uniforms: flatten_uniform_arrays(reflect_interface_uniforms(ctxt, id)),
+        uniform_blocks: reflect_interface_blocks(ctxt, id, gl::UNIFORM_BLOCK, gl::UNIFORM),
+        shader_storage_blocks: reflect_shader_storage_blocks(ctxt, program),
+        atomic_counters: reflect_atomic_counters(ctxt, program),
+        inputs: reflect_interface_variables(ctxt, id, gl::PROGRAM_INPUT),
+        outputs: reflect_interface_variables(ctxt, id, gl::PROGRAM_OUTPUT),
+        transform_feedback_buffers: reflect_transform_feedback(ctxt, program),
+    }
+}
+
+/// Returns the name of a resource of a program interface.
+///
+/// `name_len` is the value of the `GL_NAME_LENGTH` property of the resource, which includes
+/// the null terminator.
+unsafe fn get_resource_name(ctxt: &mut CommandContext, program: gl::types::GLuint,
+                            interface: gl::types::GLenum, index: gl::types::GLuint,
+                            name_len: usize) -> String
+{
+    let mut name_tmp: Vec<u8> = Vec::with_capacity(1 + name_len);
+    let mut name_tmp_len = name_len as gl::types::GLsizei;
+
+    ctxt.gl.GetProgramResourceName(program, interface, index, name_tmp_len, &mut name_tmp_len,
+                                   name_tmp.as_mut_ptr() as *mut _);
+    name_tmp.set_len(name_tmp_len as usize);
+    String::from_utf8(name_tmp).unwrap()
+}
+
+/// Obtains the list of uniforms through the program interface query, without flattening the
+/// arrays.
+unsafe fn reflect_interface_uniforms(ctxt: &mut CommandContext, program: gl::types::GLuint)
+                                     -> HashMap<String, Uniform, BuildHasherDefault<FnvHasher>>
+{
+    let mut active_uniforms: gl::types::GLint = mem::uninitialized();
+    ctxt.gl.GetProgramInterfaceiv(program, gl::UNIFORM, gl::ACTIVE_RESOURCES,
+                                  &mut active_uniforms);
+
+    let mut uniforms = HashMap::with_hasher(BuildHasherDefault::<FnvHasher>::default());
+    uniforms.reserve(active_uniforms as usize);
+
+    for uniform_id in 0 .. active_uniforms as gl::types::GLuint {
+        let (name_len, ty, array_size, location) = {
+            let mut output: [gl::types::GLint; 4] = mem::uninitialized();
+            ctxt.gl.GetProgramResourceiv(program, gl::UNIFORM, uniform_id, 4,
+                                         [gl::NAME_LENGTH, gl::TYPE, gl::ARRAY_SIZE,
+                                          gl::LOCATION].as_ptr(), 4,
+                                         ptr::null_mut(), output.as_mut_ptr() as *mut _);
+            (output[0] as usize, output[1] as gl::types::GLenum, output[2], output[3])
+        };
+
+        let name = get_resource_name(ctxt, program, gl::UNIFORM, uniform_id, name_len);
+
+        uniforms.insert(name, Uniform {
+            location: location as i32,
+            ty: glenum_to_uniform_type(ty),
+            size: if array_size == 1 { None } else { Some(array_size as usize) },
+        });
+    }
+
+    uniforms
+}
+
+/// Obtains the list of inputs or outputs of a program through the program interface query.
+///
+/// `interface` must be `GL_PROGRAM_INPUT` or `GL_PROGRAM_OUTPUT`. Built-in variables and
+/// variables that don't have a location are ignored.
+unsafe fn reflect_interface_variables(ctxt: &mut CommandContext, program: gl::types::GLuint,
+                                      interface: gl::types::GLenum)
+                                      -> HashMap<String, Attribute, BuildHasherDefault<FnvHasher>>
+{
+    let mut active_variables: gl::types::GLint = mem::uninitialized();
+    ctxt.gl.GetProgramInterfaceiv(program, interface, gl::ACTIVE_RESOURCES,
+                                  &mut active_variables);
+
+    let mut variables = HashMap::with_hasher(Default::default());
+    variables.reserve(active_variables as usize);
+
+    for variable_id in 0 .. active_variables as gl::types::GLuint {
+        let (name_len, ty, array_size, location) = {
+            let mut output: [gl::types::GLint; 4] = mem::uninitialized();
+            ctxt.gl.GetProgramResourceiv(program, interface, variable_id, 4,
+                                         [gl::NAME_LENGTH, gl::TYPE, gl::ARRAY_SIZE,
+                                          gl::LOCATION].as_ptr(), 4,
+                                         ptr::null_mut(), output.as_mut_ptr() as *mut _);
+            (output[0] as usize, output[1] as gl::types::GLenum, output[2], output[3])
+        };
+
+        let name = get_resource_name(ctxt, program, interface, variable_id, name_len);
+        if name.starts_with("gl_") || location < 0 {
+            continue;
+        }
+
+        variables.insert(name, Attribute {
+            location: location,
+            ty: glenum_to_attribute_type(ty),
+            size: array_size as usize,
+        });
+    }
+
+    variables
+}
+
+/// Returns the name, location and type of each variable of the inputs or outputs of a
+/// separable program, including the ones that don't have an explicit location. Built-in
+/// variables are ignored.
+///
+/// # Unsafety
+///
+/// - `program` must be a valid handle to a linked separable program.
+/// - The backend must support `glGetProgramResourceiv`.
+pub unsafe fn reflect_stage_interface(ctxt: &mut CommandContext, program: gl::types::GLuint,
+                                      interface: gl::types::GLenum)
+                                      -> Vec<(String, i32, AttributeType)>
+{
+    let mut active_variables: gl::types::GLint = mem::uninitialized();
+    ctxt.gl.GetProgramInterfaceiv(program, interface, gl::ACTIVE_RESOURCES,
+                                  &mut active_variables);
+
+    let mut variables = Vec::with_capacity(active_variables as usize);
+
+    for variable_id in 0 .. active_variables as gl::types::GLuint {
+        let (name_len, ty, location) = {
+            let mut output: [gl::types::GLint; 3] = mem::uninitialized();
+            ctxt.gl.GetProgramResourceiv(program, interface, variable_id, 3,
+                                         [gl::NAME_LENGTH, gl::TYPE, gl::LOCATION].as_ptr(), 3,
+                                         ptr::null_mut(), output.as_mut_ptr() as *mut _);
+            (output[0] as usize, output[1] as gl::types::GLenum, output[2])
+        };
+
+        let name = get_resource_name(ctxt, program, interface, variable_id, name_len);
+        if name.starts_with("gl_") || (name.is_empty() && location < 0) {
+            continue;
+        }
+
+        variables.push((name, location, glenum_to_attribute_type(ty)));
+    }
+
+    variables
+}
+
+unsafe fn reflect_uniforms(ctxt: &mut CommandContext, program: Handle)
+                           -> HashMap<String, Uniform, BuildHasherDefault<FnvHasher>>
 {
     // number of active uniforms
     let active_uniforms = {
@@ -253,7 +616,13 @@ pub unsafe fn reflect_uniforms(ctxt: &mut CommandContext, program: Handle)
         });
     }
 
-    // Flatten arrays
+    flatten_uniform_arrays(uniforms)
+}
+
+/// Replaces each array of uniforms with one entry per element.
+fn flatten_uniform_arrays(uniforms: HashMap<String, Uniform, BuildHasherDefault<FnvHasher>>)
+                          -> HashMap<String, Uniform, BuildHasherDefault<FnvHasher>>
+{
     let mut uniforms_flattened = HashMap::with_hasher(Default::default());
     for uniform in uniforms {
         // If this is a normal non-array element, just move it over
@@ -263,8 +632,9 @@ pub unsafe fn reflect_uniforms(ctxt: &mut CommandContext, program: Handle)
             continue;
         }
 
-        // We've got an array, first get the base of the name
-        let name_base = uniform.0.split('[').next().unwrap();
+        // We've got an array, first get the base of the name ; only the last index is removed
+        // as the array can be a member of an array of structs, like `lights[2].factors[0]`
+        let name_base = &uniform.0[.. uniform.0.len() - 3];
         let uniform_base = uniform.1;
 
         // Go over all the elements in the array
@@ -281,8 +651,36 @@ pub unsafe fn reflect_uniforms(ctxt: &mut CommandContext, program: Handle)
     uniforms_flattened
 }
 
-pub unsafe fn reflect_attributes(ctxt: &mut CommandContext, program: Handle)
-                                 -> HashMap<String, Attribute, BuildHasherDefault<FnvHasher>>
+/// Assigns a texture unit to each sampler of a list of uniforms.
+///
+/// The samplers are sorted by name, and the elements of arrays by index, so that the same
+/// program always gets the same units regardless of the implementation.
+pub fn assign_texture_units(uniforms: &HashMap<String, Uniform, BuildHasherDefault<FnvHasher>>)
+                            -> Vec<TextureUnit>
+{
+    let mut samplers = uniforms.iter()
+                               .filter(|&(_, uniform)| uniform.ty.is_sampler())
+                               .map(|(name, uniform)| (name, uniform.location))
+                               .collect::<Vec<_>>();
+
+    samplers.sort_by_key(|&(name, _)| {
+        let mut parts = name.splitn(2, '[');
+        let base = parts.next().unwrap();
+        let index = parts.next().and_then(|i| i.trim_right_matches(']').parse::<u32>().ok());
+        (base, index)
+    });
+
+    samplers.into_iter().enumerate().map(|(unit, (name, location))| {
+        TextureUnit {
+            name: name.clone(),
+            location: location,
+            unit: unit as u32,
+        }
+    }).collect()
+}
+
+unsafe fn reflect_attributes(ctxt: &mut CommandContext, program: Handle)
+                             -> HashMap<String, Attribute, BuildHasherDefault<FnvHasher>>
 {
     // number of active attributes
     let active_attributes = {
@@ -364,8 +762,8 @@ pub unsafe fn reflect_attributes(ctxt: &mut CommandContext, program: Handle)
     attributes
 }
 
-pub unsafe fn reflect_uniform_blocks(ctxt: &mut CommandContext, program: Handle)
-                                     -> HashMap<String, UniformBlock, BuildHasherDefault<FnvHasher>>
+unsafe fn reflect_uniform_blocks(ctxt: &mut CommandContext, program: Handle)
+                                 -> HashMap<String, UniformBlock, BuildHasherDefault<FnvHasher>>
 {
     // uniform blocks are not supported, so there's none
     if !(ctxt.version >= &Version(Api::Gl, 3, 1) || ctxt.version >= &Version(Api::GlEs, 3, 0)) {
@@ -448,6 +846,22 @@ pub unsafe fn reflect_uniform_blocks(ctxt: &mut CommandContext, program: Handle)
         ctxt.gl.GetActiveUniformsiv(program, num_members, members_indices.as_ptr(),
                                     gl::UNIFORM_SIZE, member_size.as_mut_ptr());
 
+        // getting the strides of the members
+        let mut member_array_stride = ::std::iter::repeat(0).take(num_members as usize)
+                                                            .collect::<Vec<gl::types::GLint>>();
+        ctxt.gl.GetActiveUniformsiv(program, num_members, members_indices.as_ptr(),
+                                    gl::UNIFORM_ARRAY_STRIDE, member_array_stride.as_mut_ptr());
+
+        let mut member_matrix_stride = ::std::iter::repeat(0).take(num_members as usize)
+                                                             .collect::<Vec<gl::types::GLint>>();
+        ctxt.gl.GetActiveUniformsiv(program, num_members, members_indices.as_ptr(),
+                                    gl::UNIFORM_MATRIX_STRIDE, member_matrix_stride.as_mut_ptr());
+
+        let mut member_row_major = ::std::iter::repeat(0).take(num_members as usize)
+                                                         .collect::<Vec<gl::types::GLint>>();
+        ctxt.gl.GetActiveUniformsiv(program, num_members, members_indices.as_ptr(),
+                                    gl::UNIFORM_IS_ROW_MAJOR, member_row_major.as_mut_ptr());
+
         // getting the length of the names of the members
         let mut member_name_len = ::std::iter::repeat(0).take(num_members as usize)
                                                          .collect::<Vec<gl::types::GLint>>();
@@ -469,25 +883,109 @@ pub unsafe fn reflect_uniform_blocks(ctxt: &mut CommandContext, program: Handle)
 
         // now computing the list of members
         let members = member_names.into_iter().enumerate().map(|(index, name)| {
-            (name, member_offsets[index] as usize,
-             glenum_to_uniform_type(member_types[index] as gl::types::GLenum),
-             member_size[index] as usize, None)
-        });
+            BlockMember {
+                name: name,
+                ty: glenum_to_uniform_type(member_types[index] as gl::types::GLenum),
+                offset: member_offsets[index] as usize,
+                array_size: member_size[index] as usize,
+                array_stride: member_array_stride[index] as usize,
+                matrix_stride: member_matrix_stride[index] as usize,
+                row_major: member_row_major[index] != 0,
+                top_level_array_size: 1,
+                top_level_array_stride: 0,
+            }
+        }).collect::<Vec<_>>();
+
+        let layout = introspection_output_to_layout(members.iter().map(|m| {
+            (m.name.clone(), m.offset, m.ty, m.array_size, None)
+        }));
 
         // finally inserting into the blocks list
         blocks.insert(name, UniformBlock {
             id: block_id as i32,
             initial_binding: binding as i32,
             size: block_size as usize,
-            layout: introspection_output_to_layout(members),
+            layout: layout,
+            members: members,
         });
     }
 
     blocks
 }
 
-pub unsafe fn reflect_transform_feedback(ctxt: &mut CommandContext, program: Handle)
-                                         -> Vec<TransformFeedbackBuffer>
+unsafe fn reflect_atomic_counters(ctxt: &mut CommandContext, program: Handle)
+                                  -> HashMap<String, AtomicCounter, BuildHasherDefault<FnvHasher>>
+{
+    // atomic counters are not supported, so there's none
+    if !(ctxt.version >= &Version(Api::Gl, 4, 2) || ctxt.version >= &Version(Api::GlEs, 3, 1) ||
+         ctxt.extensions.gl_arb_shader_atomic_counters)
+    {
+        return HashMap::with_hasher(Default::default());
+    }
+
+    let program = match program {
+        Handle::Id(id) => id,
+        _ => return HashMap::with_hasher(Default::default())
+    };
+
+    let mut active_uniforms: gl::types::GLint = mem::uninitialized();
+    ctxt.gl.GetProgramiv(program, gl::ACTIVE_UNIFORMS, &mut active_uniforms);
+
+    let mut counters = HashMap::with_hasher(Default::default());
+
+    for uniform_id in 0 .. active_uniforms as gl::types::GLuint {
+        let mut ty: gl::types::GLint = mem::uninitialized();
+        ctxt.gl.GetActiveUniformsiv(program, 1, &uniform_id, gl::UNIFORM_TYPE, &mut ty);
+        if ty as gl::types::GLenum != gl::UNSIGNED_INT_ATOMIC_COUNTER {
+            continue;
+        }
+
+        let mut buffer_index: gl::types::GLint = mem::uninitialized();
+        ctxt.gl.GetActiveUniformsiv(program, 1, &uniform_id,
+                                    gl::UNIFORM_ATOMIC_COUNTER_BUFFER_INDEX, &mut buffer_index);
+
+        let mut offset: gl::types::GLint = mem::uninitialized();
+        ctxt.gl.GetActiveUniformsiv(program, 1, &uniform_id, gl::UNIFORM_OFFSET, &mut offset);
+
+        let mut size: gl::types::GLint = mem::uninitialized();
+        ctxt.gl.GetActiveUniformsiv(program, 1, &uniform_id, gl::UNIFORM_SIZE, &mut size);
+
+        let mut name_len: gl::types::GLint = mem::uninitialized();
+        ctxt.gl.GetActiveUniformsiv(program, 1, &uniform_id, gl::UNIFORM_NAME_LENGTH,
+                                    &mut name_len);
+
+        // the binding is a property of the buffer that contains the counter
+        let mut binding: gl::types::GLint = mem::uninitialized();
+        ctxt.gl.GetActiveAtomicCounterBufferiv(program, buffer_index as gl::types::GLuint,
+                                               gl::ATOMIC_COUNTER_BUFFER_BINDING, &mut binding);
+
+        let name = {
+            let mut name_tmp: Vec<u8> = Vec::with_capacity(1 + name_len as usize);
+            let mut name_len_tmp = name_len;
+            ctxt.gl.GetActiveUniformName(program, uniform_id, name_len, &mut name_len_tmp,
+                                         name_tmp.as_mut_ptr() as *mut gl::types::GLchar);
+            name_tmp.set_len(name_len_tmp as usize);
+            String::from_utf8(name_tmp).unwrap()
+        };
+
+        let name = if name.ends_with("[0]") {
+            name[.. name.len() - 3].to_owned()
+        } else {
+            name
+        };
+
+        counters.insert(name, AtomicCounter {
+            binding: binding as u32,
+            offset: offset as usize,
+            array_size: size as usize,
+        });
+    }
+
+    counters
+}
+
+unsafe fn reflect_transform_feedback(ctxt: &mut CommandContext, program: Handle)
+                                     -> Vec<TransformFeedbackBuffer>
 {
     let program = match program {
         // transform feedback not supported
@@ -496,7 +994,9 @@ pub unsafe fn reflect_transform_feedback(ctxt: &mut CommandContext, program: Han
     };
 
     // transform feedback not supported
-    if !(ctxt.version >= &Version(Api::Gl, 3, 0)) && !ctxt.extensions.gl_ext_transform_feedback {
+    if !(ctxt.version >= &Version(Api::Gl, 3, 0)) && !(ctxt.version >= &Version(Api::GlEs, 3, 0)) &&
+       !ctxt.extensions.gl_ext_transform_feedback
+    {
         return Vec::with_capacity(0);
     }
 
@@ -504,7 +1004,7 @@ pub unsafe fn reflect_transform_feedback(ctxt: &mut CommandContext, program: Han
     let num_varyings = {
         let mut num_varyings: gl::types::GLint = mem::uninitialized();
 
-        if ctxt.version >= &Version(Api::Gl, 3, 0) {
+        if ctxt.version >= &Version(Api::Gl, 3, 0) || ctxt.version >= &Version(Api::GlEs, 3, 0) {
             ctxt.gl.GetProgramiv(program, gl::TRANSFORM_FEEDBACK_VARYINGS, &mut num_varyings);
         } else if ctxt.extensions.gl_ext_transform_feedback {
             ctxt.gl.GetProgramiv(program, gl::TRANSFORM_FEEDBACK_VARYINGS_EXT, &mut num_varyings);
@@ -524,7 +1024,7 @@ pub unsafe fn reflect_transform_feedback(ctxt: &mut CommandContext, program: Han
     let buffer_mode = {
         let mut buffer_mode: gl::types::GLint = mem::uninitialized();
 
-        if ctxt.version >= &Version(Api::Gl, 3, 0) {
+        if ctxt.version >= &Version(Api::Gl, 3, 0) || ctxt.version >= &Version(Api::GlEs, 3, 0) {
             ctxt.gl.GetProgramiv(program, gl::TRANSFORM_FEEDBACK_BUFFER_MODE, &mut buffer_mode);
         } else if ctxt.extensions.gl_ext_transform_feedback {
             ctxt.gl.GetProgramiv(program, gl::TRANSFORM_FEEDBACK_BUFFER_MODE_EXT, &mut buffer_mode);
@@ -537,7 +1037,7 @@ pub unsafe fn reflect_transform_feedback(ctxt: &mut CommandContext, program: Han
 
     // the max length includes the null terminator
     let mut max_buffer_len: gl::types::GLint = mem::uninitialized();
-    if ctxt.version >= &Version(Api::Gl, 3, 0) {
+    if ctxt.version >= &Version(Api::Gl, 3, 0) || ctxt.version >= &Version(Api::GlEs, 3, 0) {
         ctxt.gl.GetProgramiv(program, gl::TRANSFORM_FEEDBACK_VARYING_MAX_LENGTH,
                              &mut max_buffer_len);
     } else if ctxt.extensions.gl_ext_transform_feedback {
@@ -556,7 +1056,7 @@ pub unsafe fn reflect_transform_feedback(ctxt: &mut CommandContext, program: Han
         let mut size = mem::uninitialized();
         let mut ty = mem::uninitialized();
 
-        if ctxt.version >= &Version(Api::Gl, 3, 0) {
+        if ctxt.version >= &Version(Api::Gl, 3, 0) || ctxt.version >= &Version(Api::GlEs, 3, 0) {
             ctxt.gl.GetTransformFeedbackVarying(program, index, name_tmp_len, &mut name_tmp_len,
                                                 &mut size, &mut ty, name_tmp.as_mut_ptr()
                                                 as *mut gl::types::GLchar);
@@ -647,6 +1147,60 @@ pub unsafe fn reflect_geometry_output_type(ctxt: &mut CommandContext, program: H
     }
 }
 
+/// Obtains the type of data that the geometry shader stage expects as input.
+///
+/// # Unsafety
+///
+/// - `program` must be a valid handle to a program.
+/// - The program **must** contain a geometry shader.
+pub unsafe fn reflect_geometry_input_type(ctxt: &mut CommandContext, program: Handle)
+                                          -> GeometryInputPrimitives
+{
+    let mut value = mem::uninitialized();
+
+    match program {
+        Handle::Id(program) => {
+            assert!(ctxt.version >= &Version(Api::Gl, 2, 0) ||
+                    ctxt.version >= &Version(Api::GlEs, 2, 0));
+            ctxt.gl.GetProgramiv(program, gl::GEOMETRY_INPUT_TYPE, &mut value);
+        },
+        Handle::Handle(program) => {
+            assert!(ctxt.extensions.gl_arb_vertex_shader);
+            ctxt.gl.GetObjectParameterivARB(program, gl::GEOMETRY_INPUT_TYPE, &mut value);
+        }
+    };
+
+    match value as gl::types::GLenum {
+        gl::POINTS => GeometryInputPrimitives::Points,
+        gl::LINES => GeometryInputPrimitives::Lines,
+        gl::LINES_ADJACENCY => GeometryInputPrimitives::LinesAdjacency,
+        gl::TRIANGLES => GeometryInputPrimitives::Triangles,
+        gl::TRIANGLES_ADJACENCY => GeometryInputPrimitives::TrianglesAdjacency,
+        _ => unreachable!()
+    }
+}
+
+/// Obtains the local work group size of a compute shader.
+///
+/// # Unsafety
+///
+/// - `program` must be a valid handle to a program.
+/// - The program **must** contain a compute shader.
+pub unsafe fn reflect_compute_work_group_size(ctxt: &mut CommandContext, program: Handle)
+                                              -> (u32, u32, u32)
+{
+    let mut value: [gl::types::GLint; 3] = mem::uninitialized();
+
+    match program {
+        Handle::Id(program) => {
+            ctxt.gl.GetProgramiv(program, gl::COMPUTE_WORK_GROUP_SIZE, value.as_mut_ptr());
+        },
+        Handle::Handle(_) => unreachable!()
+    };
+
+    (value[0] as u32, value[1] as u32, value[2] as u32)
+}
+
 /// Obtains the type of data that the tessellation evaluation shader stage outputs.
 ///
 /// # Unsafety
@@ -679,7 +1233,7 @@ pub unsafe fn reflect_tess_eval_output_type(ctxt: &mut CommandContext, program:
 }
 
 /// Returns the list of shader storage blocks of a program.
-pub unsafe fn reflect_shader_storage_blocks(ctxt: &mut CommandContext, program: Handle)
+unsafe fn reflect_shader_storage_blocks(ctxt: &mut CommandContext, program: Handle)
     -> HashMap<String, UniformBlock, BuildHasherDefault<FnvHasher>>
 {
     if !(ctxt.version >= &Version(Api::Gl, 4, 3) || ctxt.version >= &Version(Api::GlEs, 3, 1) ||
@@ -694,10 +1248,26 @@ pub unsafe fn reflect_shader_storage_blocks(ctxt: &mut CommandContext, program:
         Handle::Handle(program) => return HashMap::with_hasher(Default::default())
     };
 
-    // number of active SSBOs
+    reflect_interface_blocks(ctxt, program, gl::SHADER_STORAGE_BLOCK, gl::BUFFER_VARIABLE)
+}
+
+/// Obtains the list of uniform blocks or of shader storage blocks of a program through the
+/// program interface query.
+///
+/// `block_interface` must be `GL_UNIFORM_BLOCK` or `GL_SHADER_STORAGE_BLOCK`, and
+/// `variable_interface` must be respectively `GL_UNIFORM` or `GL_BUFFER_VARIABLE`.
+unsafe fn reflect_interface_blocks(ctxt: &mut CommandContext, program: gl::types::GLuint,
+                                   block_interface: gl::types::GLenum,
+                                   variable_interface: gl::types::GLenum)
+                                   -> HashMap<String, UniformBlock, BuildHasherDefault<FnvHasher>>
+{
+    // only the members of shader storage blocks can be part of a top-level array
+    let is_storage = variable_interface == gl::BUFFER_VARIABLE;
+
+    // number of active blocks
     let active_blocks = {
         let mut active_blocks: gl::types::GLint = mem::uninitialized();
-        ctxt.gl.GetProgramInterfaceiv(program, gl::SHADER_STORAGE_BLOCK,
+        ctxt.gl.GetProgramInterfaceiv(program, block_interface,
                                       gl::ACTIVE_RESOURCES, &mut active_blocks);
         active_blocks as gl::types::GLuint
     };
@@ -710,7 +1280,7 @@ pub unsafe fn reflect_shader_storage_blocks(ctxt: &mut CommandContext, program:
         // getting basic infos
         let (name_len, num_variables, binding, total_size) = {
             let mut output: [gl::types::GLint; 4] = mem::uninitialized();
-            ctxt.gl.GetProgramResourceiv(program, gl::SHADER_STORAGE_BLOCK, block_id, 4,
+            ctxt.gl.GetProgramResourceiv(program, block_interface, block_id, 4,
                                          [gl::NAME_LENGTH, gl::NUM_ACTIVE_VARIABLES,
                                           gl::BUFFER_BINDING, gl::BUFFER_DATA_SIZE].as_ptr(), 4,
                                          ptr::null_mut(), output.as_mut_ptr() as *mut _);
@@ -718,21 +1288,12 @@ pub unsafe fn reflect_shader_storage_blocks(ctxt: &mut CommandContext, program:
         };
 
         // getting the name of the block
-        let name = {
-            let mut name_tmp: Vec<u8> = Vec::with_capacity(1 + name_len);
-            let mut name_tmp_len = name_len as gl::types::GLsizei;
-
-            ctxt.gl.GetProgramResourceName(program, gl::SHADER_STORAGE_BLOCK, block_id,
-                                           name_tmp_len, &mut name_tmp_len,
-                                           name_tmp.as_mut_ptr() as *mut _);
-            name_tmp.set_len(name_tmp_len as usize);
-            String::from_utf8(name_tmp).unwrap()
-        };
+        let name = get_resource_name(ctxt, program, block_interface, block_id, name_len);
 
         // indices of the active variables
         let active_variables: Vec<gl::types::GLint> = {
             let mut variables = Vec::with_capacity(num_variables);
-            ctxt.gl.GetProgramResourceiv(program, gl::SHADER_STORAGE_BLOCK, block_id, 1,
+            ctxt.gl.GetProgramResourceiv(program, block_interface, block_id, 1,
                                          [gl::ACTIVE_VARIABLES].as_ptr(),
                                          num_variables as gl::types::GLsizei,
                                          ptr::null_mut(), variables.as_mut_ptr() as *mut _);
@@ -742,39 +1303,58 @@ pub unsafe fn reflect_shader_storage_blocks(ctxt: &mut CommandContext, program:
 
         // iterator over variables
         let members = active_variables.into_iter().map(|variable| {
-            let (ty, array_size, offset, _array_stride, name_len, top_level_array_size) = {
-                let mut output: [gl::types::GLint; 6] = mem::uninitialized();
-                ctxt.gl.GetProgramResourceiv(program, gl::BUFFER_VARIABLE,
-                                             variable as gl::types::GLuint, 6,
+            let variable = variable as gl::types::GLuint;
+
+            let (ty, array_size, offset, array_stride, name_len, matrix_stride, row_major) = {
+                let mut output: [gl::types::GLint; 7] = mem::uninitialized();
+                ctxt.gl.GetProgramResourceiv(program, variable_interface, variable, 7,
                                              [gl::TYPE, gl::ARRAY_SIZE, gl::OFFSET,
                                               gl::ARRAY_STRIDE, gl::NAME_LENGTH,
-                                              gl::TOP_LEVEL_ARRAY_SIZE].as_ptr(), 6,
+                                              gl::MATRIX_STRIDE, gl::IS_ROW_MAJOR].as_ptr(), 7,
                                              ptr::null_mut(), output.as_mut_ptr() as *mut _);
                 (glenum_to_uniform_type(output[0] as gl::types::GLenum), output[1] as usize,
-                 output[2] as usize, output[3] as usize, output[4] as usize, output[5] as usize)
+                 output[2] as usize, output[3] as usize, output[4] as usize, output[5] as usize,
+                 output[6] != 0)
             };
 
-            let name = {
-                let mut name_tmp: Vec<u8> = Vec::with_capacity(1 + name_len);
-                let mut name_tmp_len = name_len as gl::types::GLsizei;
-
-                ctxt.gl.GetProgramResourceName(program, gl::BUFFER_VARIABLE,
-                                               variable as gl::types::GLuint,
-                                               name_tmp_len, &mut name_tmp_len,
-                                               name_tmp.as_mut_ptr() as *mut _);
-                name_tmp.set_len(name_tmp_len as usize);
-                String::from_utf8(name_tmp).unwrap()
+            let (top_level_array_size, top_level_array_stride) = if is_storage {
+                let mut output: [gl::types::GLint; 2] = mem::uninitialized();
+                ctxt.gl.GetProgramResourceiv(program, variable_interface, variable, 2,
+                                             [gl::TOP_LEVEL_ARRAY_SIZE,
+                                              gl::TOP_LEVEL_ARRAY_STRIDE].as_ptr(), 2,
+                                             ptr::null_mut(), output.as_mut_ptr() as *mut _);
+                (output[0] as usize, output[1] as usize)
+            } else {
+                (1, 0)
             };
 
-            (name, offset, ty, array_size, Some(top_level_array_size))
-        });
+            let name = get_resource_name(ctxt, program, variable_interface, variable, name_len);
+
+            BlockMember {
+                name: name,
+                ty: ty,
+                offset: offset,
+                array_size: array_size,
+                array_stride: array_stride,
+                matrix_stride: matrix_stride,
+                row_major: row_major,
+                top_level_array_size: top_level_array_size,
+                top_level_array_stride: top_level_array_stride,
+            }
+        }).collect::<Vec<_>>();
+
+        let layout = introspection_output_to_layout(members.iter().map(|m| {
+            let top_level_size = if is_storage { Some(m.top_level_array_size) } else { None };
+            (m.name.clone(), m.offset, m.ty, m.array_size, top_level_size)
+        }));
 
         // finally inserting into the blocks list
         blocks.insert(name, UniformBlock {
             id: block_id as i32,
             initial_binding: binding as i32,
             size: total_size,
-            layout: introspection_output_to_layout(members),
+            layout: layout,
+            members: members,
         });
     }
 
@@ -1003,6 +1583,7 @@ fn glenum_to_uniform_type(ty: gl::types::GLenum) -> UniformType {
         gl::UNSIGNED_INT_IMAGE_2D_MULTISAMPLE => UniformType::UImage2dMultisample,
         gl::UNSIGNED_INT_IMAGE_2D_MULTISAMPLE_ARRAY => UniformType::UImage2dMultisampleArray,
         gl::UNSIGNED_INT_ATOMIC_COUNTER => UniformType::AtomicCounterUint,
+        gl::SAMPLER_EXTERNAL_OES => UniformType::SamplerExternalOes,
         v => panic!("Unknown value returned by OpenGL uniform type: {}", v)
     }
 }
@@ -1034,6 +1615,19 @@ fn glenum_to_attribute_type(value: gl::types::GLenum) -> AttributeType {
         gl::FLOAT_MAT3x4 => AttributeType::F32x3x4,
         gl::FLOAT_MAT4x2 => AttributeType::F32x4x2,
         gl::FLOAT_MAT4x3 => AttributeType::F32x4x3,
+        gl::DOUBLE => AttributeType::F64,
+        gl::DOUBLE_VEC2 => AttributeType::F64F64,
+        gl::DOUBLE_VEC3 => AttributeType::F64F64F64,
+        gl::DOUBLE_VEC4 => AttributeType::F64F64F64F64,
+        gl::DOUBLE_MAT2 => AttributeType::F64x2x2,
+        gl::DOUBLE_MAT3 => AttributeType::F64x3x3,
+        gl::DOUBLE_MAT4 => AttributeType::F64x4x4,
+        gl::DOUBLE_MAT2x3 => AttributeType::F64x2x3,
+        gl::DOUBLE_MAT2x4 => AttributeType::F64x2x4,
+        gl::DOUBLE_MAT3x2 => AttributeType::F64x3x2,
+        gl::DOUBLE_MAT3x4 => AttributeType::F64x3x4,
+        gl::DOUBLE_MAT4x2 => AttributeType::F64x4x2,
+        gl::DOUBLE_MAT4x3 => AttributeType::F64x4x3,
         v => panic!("Unknown value returned by OpenGL attribute type: {}", v)
     }
 }
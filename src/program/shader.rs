@@ -13,7 +13,8 @@ use std::rc::Rc;
 use GlObject;
 use Handle;
 
-use program::ProgramCreationError;
+use program::{ProgramCreationError, SpirvEntryPoint};
+use program::reflection::reflect_stage_interface;
 
 /// A single, compiled but unlinked, shader.
 pub struct Shader {
@@ -50,9 +51,191 @@ impl Drop for Shader {
     }
 }
 
+impl Shader {
+    /// Checks whether the compilation of the shader succeeded.
+    ///
+    /// If the compilation is still running in the background, this function blocks until it
+    /// is finished.
+    pub fn check_compilation(&self) -> Result<(), ProgramCreationError> {
+        unsafe {
+            let ctxt = self.context.make_current();
+            let id = self.id;
+
+            // checking compilation success by reading a flag on the shader
+            let compilation_success = {
+                let mut compilation_success: gl::types::GLint = mem::uninitialized();
+                match id {
+                    Handle::Id(id) => {
+                        assert!(ctxt.version >= &Version(Api::Gl, 2, 0) ||
+                                ctxt.version >= &Version(Api::GlEs, 2, 0));
+                        ctxt.gl.GetShaderiv(id, gl::COMPILE_STATUS, &mut compilation_success);
+                    },
+                    Handle::Handle(id) => {
+                        assert!(ctxt.extensions.gl_arb_shader_objects);
+                        ctxt.gl.GetObjectParameterivARB(id, gl::OBJECT_COMPILE_STATUS_ARB,
+                                                        &mut compilation_success);
+                    }
+                }
+                compilation_success
+            };
+
+            if compilation_success == 1 {
+                Ok(())
+
+            } else {
+                // compilation error
+                let mut error_log_size: gl::types::GLint = mem::uninitialized();
+
+                match id {
+                    Handle::Id(id) => {
+                        assert!(ctxt.version >= &Version(Api::Gl, 2, 0) ||
+                                ctxt.version >= &Version(Api::GlEs, 2, 0));
+                        ctxt.gl.GetShaderiv(id, gl::INFO_LOG_LENGTH, &mut error_log_size);
+                    },
+                    Handle::Handle(id) => {
+                        assert!(ctxt.extensions.gl_arb_shader_objects);
+                        ctxt.gl.GetObjectParameterivARB(id, gl::OBJECT_INFO_LOG_LENGTH_ARB,
+                                                        &mut error_log_size);
+                    }
+                }
+
+                let mut error_log: Vec<u8> = Vec::with_capacity(error_log_size as usize);
+
+                match id {
+                    Handle::Id(id) => {
+                        assert!(ctxt.version >= &Version(Api::Gl, 2, 0) ||
+                                ctxt.version >= &Version(Api::GlEs, 2, 0));
+                        ctxt.gl.GetShaderInfoLog(id, error_log_size, &mut error_log_size,
+                                                 error_log.as_mut_ptr() as *mut gl::types::GLchar);
+                    },
+                    Handle::Handle(id) => {
+                        assert!(ctxt.extensions.gl_arb_shader_objects);
+                        ctxt.gl.GetInfoLogARB(id, error_log_size, &mut error_log_size,
+                                              error_log.as_mut_ptr() as *mut gl::types::GLchar);
+                    }
+                }
+
+                error_log.set_len(error_log_size as usize);
+
+                match String::from_utf8(error_log) {
+                    Ok(msg) => Err(ProgramCreationError::CompilationError(msg)),
+                    Err(_) => Err(
+                        ProgramCreationError::CompilationError("Could not convert the log \
+                                                                message to UTF-8".to_owned())
+                    ),
+                }
+            }
+        }
+    }
+}
+
+/// Checks that each input of a stage is written by the previous stage with the same type.
+///
+/// The shaders must be successfully compiled and passed in the order of the pipeline. Each
+/// stage is linked on its own as a separable program in order to enumerate its inputs and
+/// outputs. Variables are matched by location if both of them have one, and by name otherwise.
+///
+/// Does nothing if the backend doesn't support separable programs and
+/// `glGetProgramResourceiv`, or if one of the stages can't be linked on its own.
+pub fn check_stage_interfaces(shaders: &[Shader]) -> Result<(), ProgramCreationError> {
+    let mut ctxt = match shaders.first() {
+        Some(shader) => shader.context.make_current(),
+        None => return Ok(()),
+    };
+
+    if !(ctxt.version >= &Version(Api::GlEs, 3, 1)) &&
+       !((ctxt.version >= &Version(Api::Gl, 4, 1) ||
+          ctxt.extensions.gl_arb_separate_shader_objects) &&
+         (ctxt.version >= &Version(Api::Gl, 4, 3) ||
+          ctxt.extensions.gl_arb_program_interface_query))
+    {
+        return Ok(());
+    }
+
+    let mut interfaces = Vec::with_capacity(shaders.len());
+    for shader in shaders.iter() {
+        let shader = match shader.id {
+            Handle::Id(id) => id,
+            Handle::Handle(_) => return Ok(()),
+        };
+
+        unsafe {
+            let program = ctxt.gl.CreateProgram();
+            ctxt.gl.ProgramParameteri(program, gl::PROGRAM_SEPARABLE,
+                                      gl::TRUE as gl::types::GLint);
+            ctxt.gl.AttachShader(program, shader);
+
+            ctxt.report_debug_output_errors.set(false);
+            ctxt.gl.LinkProgram(program);
+            ctxt.report_debug_output_errors.set(true);
+
+            let mut link_success: gl::types::GLint = mem::uninitialized();
+            ctxt.gl.GetProgramiv(program, gl::LINK_STATUS, &mut link_success);
+
+            let interface = if link_success != 0 {
+                Some((reflect_stage_interface(&mut ctxt, program, gl::PROGRAM_INPUT),
+                      reflect_stage_interface(&mut ctxt, program, gl::PROGRAM_OUTPUT)))
+            } else {
+                None
+            };
+
+            ctxt.gl.DetachShader(program, shader);
+            ctxt.gl.DeleteProgram(program);
+
+            match interface {
+                Some(interface) => interfaces.push(interface),
+                None => return Ok(()),
+            }
+        }
+    }
+
+    for stages in interfaces.windows(2) {
+        let outputs = &(stages[0].1);
+        let inputs = &(stages[1].0);
+
+        for &(ref name, location, ty) in inputs.iter() {
+            let output = outputs.iter().find(|&&(ref out_name, out_location, _)| {
+                if location >= 0 && out_location >= 0 {
+                    location == out_location
+                } else {
+                    !name.is_empty() && name == out_name
+                }
+            });
+
+            let output_ty = output.map(|&(_, _, ty)| ty);
+            if output_ty == Some(ty) {
+                continue;
+            }
+
+            return Err(ProgramCreationError::StageInterfaceMismatch {
+                name: name.clone(),
+                location: if location >= 0 { Some(location as u32) } else { None },
+                input: ty,
+                output: output_ty,
+            });
+        }
+    }
+
+    Ok(())
+}
+
 /// Builds an individual shader.
+///
+/// Returns an error if the compilation fails.
 pub fn build_shader<F: ?Sized>(facade: &F, shader_type: gl::types::GLenum, source_code: &str)
                        -> Result<Shader, ProgramCreationError> where F: Facade
+{
+    let shader = try!(compile_shader(facade, shader_type, source_code));
+    try!(shader.check_compilation());
+    Ok(shader)
+}
+
+/// Starts compiling an individual shader.
+///
+/// Contrary to `build_shader`, this function doesn't wait for the compilation to finish. Use
+/// `Shader::check_compilation` to retreive the result.
+pub fn compile_shader<F: ?Sized>(facade: &F, shader_type: gl::types::GLenum, source_code: &str)
+                         -> Result<Shader, ProgramCreationError> where F: Facade
 {
     unsafe {
         let mut ctxt = facade.get_context().make_current();
@@ -112,73 +295,61 @@ pub fn build_shader<F: ?Sized>(facade: &F, shader_type: gl::types::GLenum, sourc
             ctxt.report_debug_output_errors.set(true);
         }
 
-        // checking compilation success by reading a flag on the shader
-        let compilation_success = {
-            let mut compilation_success: gl::types::GLint = mem::uninitialized();
-            match id {
-                Handle::Id(id) => {
-                    assert!(ctxt.version >= &Version(Api::Gl, 2, 0) ||
-                            ctxt.version >= &Version(Api::GlEs, 2, 0));
-                    ctxt.gl.GetShaderiv(id, gl::COMPILE_STATUS, &mut compilation_success);
-                },
-                Handle::Handle(id) => {
-                    assert!(ctxt.extensions.gl_arb_shader_objects);
-                    ctxt.gl.GetObjectParameterivARB(id, gl::OBJECT_COMPILE_STATUS_ARB,
-                                                    &mut compilation_success);
-                }
-            }
-            compilation_success
-        };
+        Ok(Shader {
+            context: facade.get_context().clone(),
+            id: id
+        })
+    }
+}
 
-        if compilation_success == 1 {
-            Ok(Shader {
-                context: facade.get_context().clone(),
-                id: id
-            })
+/// Builds an individual shader from a SPIR-V module.
+///
+/// Returns an error if the specialization fails.
+pub fn specialize_shader<F: ?Sized>(facade: &F, shader_type: gl::types::GLenum,
+                                    entry_point: &SpirvEntryPoint)
+                                    -> Result<Shader, ProgramCreationError> where F: Facade
+{
+    unsafe {
+        let mut ctxt = facade.get_context().make_current();
 
-        } else {
-            // compilation error
-            let mut error_log_size: gl::types::GLint = mem::uninitialized();
+        if !ctxt.extensions.gl_arb_gl_spirv {
+            return Err(ProgramCreationError::SpirvNotSupported);
+        }
 
-            match id {
-                Handle::Id(id) => {
-                    assert!(ctxt.version >= &Version(Api::Gl, 2, 0) ||
-                            ctxt.version >= &Version(Api::GlEs, 2, 0));
-                    ctxt.gl.GetShaderiv(id, gl::INFO_LOG_LENGTH, &mut error_log_size);
-                },
-                Handle::Handle(id) => {
-                    assert!(ctxt.extensions.gl_arb_shader_objects);
-                    ctxt.gl.GetObjectParameterivARB(id, gl::OBJECT_INFO_LOG_LENGTH_ARB,
-                                                    &mut error_log_size);
-                }
-            }
+        if !check_shader_type_compatibility(&mut ctxt, shader_type) {
+            return Err(ProgramCreationError::ShaderTypeNotSupported);
+        }
 
-            let mut error_log: Vec<u8> = Vec::with_capacity(error_log_size as usize);
+        let id = ctxt.gl.CreateShader(shader_type);
+        if id == 0 {
+            return Err(ProgramCreationError::ShaderTypeNotSupported);
+        }
 
-            match id {
-                Handle::Id(id) => {
-                    assert!(ctxt.version >= &Version(Api::Gl, 2, 0) ||
-                            ctxt.version >= &Version(Api::GlEs, 2, 0));
-                    ctxt.gl.GetShaderInfoLog(id, error_log_size, &mut error_log_size,
-                                             error_log.as_mut_ptr() as *mut gl::types::GLchar);
-                },
-                Handle::Handle(id) => {
-                    assert!(ctxt.extensions.gl_arb_shader_objects);
-                    ctxt.gl.GetInfoLogARB(id, error_log_size, &mut error_log_size,
-                                          error_log.as_mut_ptr() as *mut gl::types::GLchar);
-                }
-            }
+        // building the shader before calling any other function, so that it is destroyed if
+        // an error happens
+        let shader = Shader {
+            context: facade.get_context().clone(),
+            id: Handle::Id(id),
+        };
 
-            error_log.set_len(error_log_size as usize);
+        let entry_point_name = ffi::CString::new(entry_point.entry_point.as_bytes()).unwrap();
+        let (indices, values): (Vec<_>, Vec<_>) = entry_point.specialization_constants
+                                                             .iter().cloned().unzip();
 
-            match String::from_utf8(error_log) {
-                Ok(msg) => Err(ProgramCreationError::CompilationError(msg)),
-                Err(_) => Err(
-                    ProgramCreationError::CompilationError("Could not convert the log \
-                                                            message to UTF-8".to_owned())
-                ),
-            }
-        }
+        ctxt.report_debug_output_errors.set(false);
+
+        ctxt.gl.ShaderBinary(1, &id, gl::SHADER_BINARY_FORMAT_SPIR_V_ARB,
+                             entry_point.binary.as_ptr() as *const _,
+                             entry_point.binary.len() as gl::types::GLsizei);
+        ctxt.gl.SpecializeShaderARB(id, entry_point_name.as_ptr(),
+                                    indices.len() as gl::types::GLuint, indices.as_ptr(),
+                                    values.as_ptr());
+
+        ctxt.report_debug_output_errors.set(true);
+
+        drop(ctxt);
+        try!(shader.check_compilation());
+        Ok(shader)
     }
 }
 
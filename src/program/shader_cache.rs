@@ -0,0 +1,124 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read, Write};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering, ATOMIC_USIZE_INIT};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use program::Binary;
+
+/// A storage for the binaries of programs.
+///
+/// If a shader cache is set on the context with `Context::set_shader_cache`,
+/// `Program::from_source` first looks for a binary in the cache before compiling the source
+/// code, and stores the binary of the programs that it compiles.
+///
+/// Caching is best-effort: implementations are free to lose or to ignore entries.
+pub trait ShaderCache {
+    /// Returns the binary that has been stored with this key, if any.
+    ///
+    /// Keys are computed with a stable hash function, so they can be persisted and used by
+    /// different builds of the program. The content of the binaries starts with the source code
+    /// that they were built from, which is compared before using them, so two programs whose
+    /// keys collide never load each other's binary.
+    fn load(&self, key: u64) -> Option<Binary>;
+
+    /// Stores a binary with the given key.
+    fn store(&self, key: u64, binary: &Binary);
+}
+
+/// Number of temporary files created by this process, used to give them unique names.
+static TEMPORARY_FILES: AtomicUsize = ATOMIC_USIZE_INIT;
+
+/// A `ShaderCache` that stores each binary in a file of a directory.
+///
+/// The directory is created if it doesn't exist. Errors while reading or writing files are
+/// ignored.
+#[derive(Debug, Clone)]
+pub struct DirectoryShaderCache {
+    directory: PathBuf,
+}
+
+impl DirectoryShaderCache {
+    /// Builds a new cache that stores the binaries in the given directory.
+    #[inline]
+    pub fn new<P>(directory: P) -> DirectoryShaderCache where P: Into<PathBuf> {
+        DirectoryShaderCache {
+            directory: directory.into(),
+        }
+    }
+
+    /// Returns the path of the file that corresponds to a key.
+    #[inline]
+    fn path(&self, key: u64) -> PathBuf {
+        self.directory.join(format!("{:016x}.bin", key))
+    }
+}
+
+impl ShaderCache for DirectoryShaderCache {
+    fn load(&self, key: u64) -> Option<Binary> {
+        let mut data = Vec::new();
+
+        match File::open(self.path(key)).and_then(|mut file| file.read_to_end(&mut data)) {
+            Ok(_) => (),
+            Err(_) => return None
+        };
+
+        // the file starts with the format of the binary in little endian
+        if data.len() < 4 {
+            return None;
+        }
+
+        let format = data[0] as u32 | (data[1] as u32) << 8 | (data[2] as u32) << 16 |
+                     (data[3] as u32) << 24;
+
+        Some(Binary {
+            format: format,
+            content: data.split_off(4),
+        })
+    }
+
+    fn store(&self, key: u64, binary: &Binary) {
+        let _ = fs::create_dir_all(&self.directory);
+
+        let format = binary.format;
+        let header = [format as u8, (format >> 8) as u8, (format >> 16) as u8,
+                      (format >> 24) as u8];
+
+        // writing to a temporary file first, so that a concurrent `load` never sees a
+        // partially-written binary
+        // the name of the temporary file is made of the current time and of a counter, and the
+        // file is only created if it doesn't exist yet, so that concurrent stores of the same
+        // key, from this process or from another one, don't write to the same file
+        let path = self.path(key);
+        let nanos = SystemTime::now().duration_since(UNIX_EPOCH)
+                                     .map(|d| d.subsec_nanos()).unwrap_or(0);
+
+        let mut temporary = None;
+        for _ in 0 .. 16 {
+            let id = TEMPORARY_FILES.fetch_add(1, Ordering::Relaxed);
+            let candidate = self.directory.join(format!("{:016x}.{:08x}.{}.tmp", key, nanos, id));
+            match OpenOptions::new().write(true).create_new(true).open(&candidate) {
+                Ok(file) => { temporary = Some((candidate, file)); break; },
+                Err(ref err) if err.kind() == io::ErrorKind::AlreadyExists => continue,
+                Err(_) => return,
+            }
+        }
+
+        let (temporary, mut file) = match temporary {
+            Some(t) => t,
+            None => return,
+        };
+
+        let written = match file.write_all(&header) {
+            Ok(_) => file.write_all(&binary.content),
+            Err(err) => Err(err),
+        };
+        drop(file);
+
+        let result = written.and_then(|_| fs::rename(&temporary, &path));
+
+        if result.is_err() {
+            let _ = fs::remove_file(&temporary);
+        }
+    }
+}
@@ -2,16 +2,21 @@ use std::cell::RefCell;
 use std::collections::HashMap;
 use std::hash::BuildHasherDefault;
 use RawUniformValue;
+use RawUniformArray;
 
 use smallvec::SmallVec;
 use fnv::FnvHasher;
 
 use gl;
+use GlObject;
 use Handle;
+use ProgramExt;
 use context::CommandContext;
 use version::Version;
 use version::Api;
+use program::raw::RawProgram;
 use program::reflection::ShaderStage;
+use program::is_double_precision_supported;
 
 pub struct UniformsStorage {
     values: RefCell<HashMap<gl::types::GLint, Option<RawUniformValue>,
@@ -36,18 +41,32 @@ impl UniformsStorage {
 
     /// Compares `value` with the value stored in this object. If the values differ, updates
     /// the storage and calls `glUniform`.
-    pub fn set_uniform_value(&self, ctxt: &mut CommandContext, program: Handle,
+    ///
+    /// If the uniform cache of the context is disabled, `glUniform` is always called.
+    pub fn set_uniform_value(&self, ctxt: &mut CommandContext, program: &RawProgram,
                              location: gl::types::GLint, value: &RawUniformValue)
     {
         let mut values = self.values.borrow_mut();
 
-        // TODO: don't assume that, instead use DSA if the program is not current
-        assert!(ctxt.state.program == program);
+        // if the program is not current, contains the id to pass to `glProgramUniform`
+        let mut dsa = None;
+
+        macro_rules! prepare(
+            ($ctxt:expr) => (
+                if $ctxt.state.program != program.get_id() {
+                    dsa = prepare_program($ctxt, program);
+                }
+            )
+        );
 
         macro_rules! uniform(
-            ($ctxt:expr, $uniform:ident, $uniform_arb:ident, $($params:expr),+) => (
+            ($ctxt:expr, $uniform:ident, $uniform_arb:ident, $program_uniform:ident,
+             $($params:expr),+) => (
                 unsafe {
-                    if $ctxt.version >= &Version(Api::Gl, 1, 5) ||
+                    prepare!($ctxt);
+                    if let Some(id) = dsa {
+                        $ctxt.gl.$program_uniform(id, $($params),+)
+                    } else if $ctxt.version >= &Version(Api::Gl, 1, 5) ||
                        $ctxt.version >= &Version(Api::GlEs, 2, 0)
                     {
                         $ctxt.gl.$uniform($($params),+)
@@ -59,11 +78,31 @@ impl UniformsStorage {
             )
         );
 
-        macro_rules! uniform_f64(
-            ($ctxt:expr, $uniform:ident, $($params:expr),+) => (
+        // non-square matrices require OpenGL 2.1 or OpenGL ES 3.0, like the shaders that use
+        // them, and don't have an `ARB` variant
+        macro_rules! uniform_non_square(
+            ($ctxt:expr, $uniform:ident, $program_uniform:ident, $($params:expr),+) => (
                 unsafe {
-                    if $ctxt.extensions.gl_arb_gpu_shader_fp64 {
+                    prepare!($ctxt);
+                    if let Some(id) = dsa {
+                        $ctxt.gl.$program_uniform(id, $($params),+)
+                    } else {
                         $ctxt.gl.$uniform($($params),+)
+                    }
+                }
+            )
+        );
+
+        macro_rules! uniform_f64(
+            ($ctxt:expr, $uniform:ident, $program_uniform:ident, $($params:expr),+) => (
+                unsafe {
+                    if is_double_precision_supported(&*$ctxt) {
+                        prepare!($ctxt);
+                        if let Some(id) = dsa {
+                            $ctxt.gl.$program_uniform(id, $($params),+)
+                        } else {
+                            $ctxt.gl.$uniform($($params),+)
+                        }
                     } else {
                         panic!("Double precision floats are not supported on this system.")
                     }
@@ -72,10 +111,15 @@ impl UniformsStorage {
         );
 
         macro_rules! uniform_i64(
-            ($ctxt:expr, $uniform:ident, $($params:expr),+) => (
+            ($ctxt:expr, $uniform:ident, $program_uniform:ident, $($params:expr),+) => (
                 unsafe {
                     if $ctxt.extensions.gl_arb_gpu_shader_int64 {
-                        $ctxt.gl.$uniform($($params),+)
+                        prepare!($ctxt);
+                        if let Some(id) = dsa {
+                            $ctxt.gl.$program_uniform(id, $($params),+)
+                        } else {
+                            $ctxt.gl.$uniform($($params),+)
+                        }
                     } else {
                         panic!("64 bit integers are not supported on this system.")
                     }
@@ -83,6 +127,11 @@ impl UniformsStorage {
             )
         );
 
+        // forgetting the previous value forces the upload
+        if !ctxt.uniform_cache_enabled {
+            values.remove(&location);
+        }
+
         match (value, values.entry(location).or_insert(None)) {
             (&RawUniformValue::SignedInt(a), &mut Some(RawUniformValue::SignedInt(b))) if a == b => (),
             (&RawUniformValue::UnsignedInt(a), &mut Some(RawUniformValue::UnsignedInt(b))) if a == b => (),
@@ -90,6 +139,12 @@ impl UniformsStorage {
             (&RawUniformValue::Mat2(a), &mut Some(RawUniformValue::Mat2(b))) if a == b => (),
             (&RawUniformValue::Mat3(a), &mut Some(RawUniformValue::Mat3(b))) if a == b => (),
             (&RawUniformValue::Mat4(a), &mut Some(RawUniformValue::Mat4(b))) if a == b => (),
+            (&RawUniformValue::Mat2x3(a), &mut Some(RawUniformValue::Mat2x3(b))) if a == b => (),
+            (&RawUniformValue::Mat2x4(a), &mut Some(RawUniformValue::Mat2x4(b))) if a == b => (),
+            (&RawUniformValue::Mat3x2(a), &mut Some(RawUniformValue::Mat3x2(b))) if a == b => (),
+            (&RawUniformValue::Mat3x4(a), &mut Some(RawUniformValue::Mat3x4(b))) if a == b => (),
+            (&RawUniformValue::Mat4x2(a), &mut Some(RawUniformValue::Mat4x2(b))) if a == b => (),
+            (&RawUniformValue::Mat4x3(a), &mut Some(RawUniformValue::Mat4x3(b))) if a == b => (),
             (&RawUniformValue::Vec2(a), &mut Some(RawUniformValue::Vec2(b))) if a == b => (),
             (&RawUniformValue::Vec3(a), &mut Some(RawUniformValue::Vec3(b))) if a == b => (),
             (&RawUniformValue::Vec4(a), &mut Some(RawUniformValue::Vec4(b))) if a == b => (),
@@ -114,10 +169,11 @@ impl UniformsStorage {
             (&RawUniformValue::UnsignedInt64Vec2(a), &mut Some(RawUniformValue::UnsignedInt64Vec2(b))) if a == b => (),
             (&RawUniformValue::UnsignedInt64Vec3(a), &mut Some(RawUniformValue::UnsignedInt64Vec3(b))) if a == b => (),
             (&RawUniformValue::UnsignedInt64Vec4(a), &mut Some(RawUniformValue::UnsignedInt64Vec4(b))) if a == b => (),
+            (&RawUniformValue::Handle(a), &mut Some(RawUniformValue::Handle(b))) if a == b => (),
 
             (&RawUniformValue::SignedInt(v), target) => {
                 *target = Some(RawUniformValue::SignedInt(v));
-                uniform!(ctxt, Uniform1i, Uniform1iARB, location, v);
+                uniform!(ctxt, Uniform1i, Uniform1iARB, ProgramUniform1i, location, v);
             },
 
             (&RawUniformValue::UnsignedInt(v), target) => {
@@ -125,8 +181,11 @@ impl UniformsStorage {
 
                 // Uniform1uiARB doesn't exist
                 unsafe {
-                    if ctxt.version >= &Version(Api::Gl, 1, 5) ||
-                       ctxt.version >= &Version(Api::GlEs, 2, 0)
+                    prepare!(ctxt);
+                    if let Some(id) = dsa {
+                        ctxt.gl.ProgramUniform1ui(id, location, v)
+                    } else if ctxt.version >= &Version(Api::Gl, 1, 5) ||
+                              ctxt.version >= &Version(Api::GlEs, 2, 0)
                     {
                         ctxt.gl.Uniform1ui(location, v)
                     } else {
@@ -138,55 +197,97 @@ impl UniformsStorage {
 
             (&RawUniformValue::Float(v), target) => {
                 *target = Some(RawUniformValue::Float(v));
-                uniform!(ctxt, Uniform1f, Uniform1fARB, location, v);
+                uniform!(ctxt, Uniform1f, Uniform1fARB, ProgramUniform1f, location, v);
             },
 
             (&RawUniformValue::Mat2(v), target) => {
                 *target = Some(RawUniformValue::Mat2(v));
-                uniform!(ctxt, UniformMatrix2fv, UniformMatrix2fvARB,
+                uniform!(ctxt, UniformMatrix2fv, UniformMatrix2fvARB, ProgramUniformMatrix2fv,
                          location, 1, gl::FALSE, v.as_ptr() as *const f32);
             },
 
             (&RawUniformValue::Mat3(v), target) => {
                 *target = Some(RawUniformValue::Mat3(v));
-                uniform!(ctxt, UniformMatrix3fv, UniformMatrix3fvARB,
+                uniform!(ctxt, UniformMatrix3fv, UniformMatrix3fvARB, ProgramUniformMatrix3fv,
                          location, 1, gl::FALSE, v.as_ptr() as *const f32);
             },
 
             (&RawUniformValue::Mat4(v), target) => {
                 *target = Some(RawUniformValue::Mat4(v));
-                uniform!(ctxt, UniformMatrix4fv, UniformMatrix4fvARB,
+                uniform!(ctxt, UniformMatrix4fv, UniformMatrix4fvARB, ProgramUniformMatrix4fv,
                          location, 1, gl::FALSE, v.as_ptr() as *const f32);
             },
 
+            (&RawUniformValue::Mat2x3(v), target) => {
+                *target = Some(RawUniformValue::Mat2x3(v));
+                uniform_non_square!(ctxt, UniformMatrix2x3fv, ProgramUniformMatrix2x3fv,
+                                    location, 1, gl::FALSE, v.as_ptr() as *const f32);
+            },
+
+            (&RawUniformValue::Mat2x4(v), target) => {
+                *target = Some(RawUniformValue::Mat2x4(v));
+                uniform_non_square!(ctxt, UniformMatrix2x4fv, ProgramUniformMatrix2x4fv,
+                                    location, 1, gl::FALSE, v.as_ptr() as *const f32);
+            },
+
+            (&RawUniformValue::Mat3x2(v), target) => {
+                *target = Some(RawUniformValue::Mat3x2(v));
+                uniform_non_square!(ctxt, UniformMatrix3x2fv, ProgramUniformMatrix3x2fv,
+                                    location, 1, gl::FALSE, v.as_ptr() as *const f32);
+            },
+
+            (&RawUniformValue::Mat3x4(v), target) => {
+                *target = Some(RawUniformValue::Mat3x4(v));
+                uniform_non_square!(ctxt, UniformMatrix3x4fv, ProgramUniformMatrix3x4fv,
+                                    location, 1, gl::FALSE, v.as_ptr() as *const f32);
+            },
+
+            (&RawUniformValue::Mat4x2(v), target) => {
+                *target = Some(RawUniformValue::Mat4x2(v));
+                uniform_non_square!(ctxt, UniformMatrix4x2fv, ProgramUniformMatrix4x2fv,
+                                    location, 1, gl::FALSE, v.as_ptr() as *const f32);
+            },
+
+            (&RawUniformValue::Mat4x3(v), target) => {
+                *target = Some(RawUniformValue::Mat4x3(v));
+                uniform_non_square!(ctxt, UniformMatrix4x3fv, ProgramUniformMatrix4x3fv,
+                                    location, 1, gl::FALSE, v.as_ptr() as *const f32);
+            },
+
             (&RawUniformValue::Vec2(v), target) => {
                 *target = Some(RawUniformValue::Vec2(v));
-                uniform!(ctxt, Uniform2fv, Uniform2fvARB, location, 1, v.as_ptr() as *const f32);
+                uniform!(ctxt, Uniform2fv, Uniform2fvARB, ProgramUniform2fv,
+                         location, 1, v.as_ptr() as *const f32);
             },
 
             (&RawUniformValue::Vec3(v), target) => {
                 *target = Some(RawUniformValue::Vec3(v));
-                uniform!(ctxt, Uniform3fv, Uniform3fvARB, location, 1, v.as_ptr() as *const f32);
+                uniform!(ctxt, Uniform3fv, Uniform3fvARB, ProgramUniform3fv,
+                         location, 1, v.as_ptr() as *const f32);
             },
 
             (&RawUniformValue::Vec4(v), target) => {
                 *target = Some(RawUniformValue::Vec4(v));
-                uniform!(ctxt, Uniform4fv, Uniform4fvARB, location, 1, v.as_ptr() as *const f32);
+                uniform!(ctxt, Uniform4fv, Uniform4fvARB, ProgramUniform4fv,
+                         location, 1, v.as_ptr() as *const f32);
             },
 
             (&RawUniformValue::IntVec2(v), target) => {
                 *target = Some(RawUniformValue::IntVec2(v));
-                uniform!(ctxt, Uniform2iv, Uniform2ivARB, location, 1, v.as_ptr() as *const gl::types::GLint);
+                uniform!(ctxt, Uniform2iv, Uniform2ivARB, ProgramUniform2iv,
+                         location, 1, v.as_ptr() as *const gl::types::GLint);
             },
 
             (&RawUniformValue::IntVec3(v), target) => {
                 *target = Some(RawUniformValue::IntVec3(v));
-                uniform!(ctxt, Uniform3iv, Uniform3ivARB, location, 1, v.as_ptr() as *const gl::types::GLint);
+                uniform!(ctxt, Uniform3iv, Uniform3ivARB, ProgramUniform3iv,
+                         location, 1, v.as_ptr() as *const gl::types::GLint);
             },
 
             (&RawUniformValue::IntVec4(v), target) => {
                 *target = Some(RawUniformValue::IntVec4(v));
-                uniform!(ctxt, Uniform4iv, Uniform4ivARB, location, 1, v.as_ptr() as *const gl::types::GLint);
+                uniform!(ctxt, Uniform4iv, Uniform4ivARB, ProgramUniform4iv,
+                         location, 1, v.as_ptr() as *const gl::types::GLint);
             },
 
             (&RawUniformValue::UnsignedIntVec2(v), target) => {
@@ -194,8 +295,12 @@ impl UniformsStorage {
 
                 // Uniform2uivARB doesn't exist
                 unsafe {
-                    if ctxt.version >= &Version(Api::Gl, 1, 5) ||
-                       ctxt.version >= &Version(Api::GlEs, 2, 0)
+                    prepare!(ctxt);
+                    if let Some(id) = dsa {
+                        ctxt.gl.ProgramUniform2uiv(id, location, 1,
+                                                  v.as_ptr() as *const gl::types::GLuint)
+                    } else if ctxt.version >= &Version(Api::Gl, 1, 5) ||
+                              ctxt.version >= &Version(Api::GlEs, 2, 0)
                     {
                         ctxt.gl.Uniform2uiv(location, 1, v.as_ptr() as *const gl::types::GLuint)
                     } else {
@@ -210,8 +315,12 @@ impl UniformsStorage {
 
                 // Uniform3uivARB doesn't exist
                 unsafe {
-                    if ctxt.version >= &Version(Api::Gl, 1, 5) ||
-                       ctxt.version >= &Version(Api::GlEs, 2, 0)
+                    prepare!(ctxt);
+                    if let Some(id) = dsa {
+                        ctxt.gl.ProgramUniform3uiv(id, location, 1,
+                                                  v.as_ptr() as *const gl::types::GLuint)
+                    } else if ctxt.version >= &Version(Api::Gl, 1, 5) ||
+                              ctxt.version >= &Version(Api::GlEs, 2, 0)
                     {
                         ctxt.gl.Uniform3uiv(location, 1, v.as_ptr() as *const gl::types::GLuint)
                     } else {
@@ -226,8 +335,12 @@ impl UniformsStorage {
 
                 // Uniform4uivARB doesn't exist
                 unsafe {
-                    if ctxt.version >= &Version(Api::Gl, 1, 5) ||
-                       ctxt.version >= &Version(Api::GlEs, 2, 0)
+                    prepare!(ctxt);
+                    if let Some(id) = dsa {
+                        ctxt.gl.ProgramUniform4uiv(id, location, 1,
+                                                  v.as_ptr() as *const gl::types::GLuint)
+                    } else if ctxt.version >= &Version(Api::Gl, 1, 5) ||
+                              ctxt.version >= &Version(Api::GlEs, 2, 0)
                     {
                         ctxt.gl.Uniform4uiv(location, 1, v.as_ptr() as *const gl::types::GLuint)
                     } else {
@@ -238,76 +351,215 @@ impl UniformsStorage {
             },
             (&RawUniformValue::Double(v), target) => {
                 *target = Some(RawUniformValue::Double(v));
-                uniform_f64!(ctxt, Uniform1d, location, v);
+                uniform_f64!(ctxt, Uniform1d, ProgramUniform1d, location, v);
             },
 
             (&RawUniformValue::DoubleMat2(v), target) => {
                 *target = Some(RawUniformValue::DoubleMat2(v));
-                uniform_f64!(ctxt, UniformMatrix2dv,
+                uniform_f64!(ctxt, UniformMatrix2dv, ProgramUniformMatrix2dv,
                          location, 1, gl::FALSE, v.as_ptr() as *const gl::types::GLdouble);
             },
 
             (&RawUniformValue::DoubleMat3(v), target) => {
                 *target = Some(RawUniformValue::DoubleMat3(v));
-                uniform_f64!(ctxt, UniformMatrix3dv,
+                uniform_f64!(ctxt, UniformMatrix3dv, ProgramUniformMatrix3dv,
                          location, 1, gl::FALSE, v.as_ptr() as *const gl::types::GLdouble);
             },
 
             (&RawUniformValue::DoubleMat4(v), target) => {
                 *target = Some(RawUniformValue::DoubleMat4(v));
-                uniform_f64!(ctxt, UniformMatrix4dv,
+                uniform_f64!(ctxt, UniformMatrix4dv, ProgramUniformMatrix4dv,
                          location, 1, gl::FALSE, v.as_ptr() as *const gl::types::GLdouble);
             },
 
             (&RawUniformValue::DoubleVec2(v), target) => {
                 *target = Some(RawUniformValue::DoubleVec2(v));
-                uniform_f64!(ctxt, Uniform2dv, location, 1, v.as_ptr() as *const gl::types::GLdouble);
+                uniform_f64!(ctxt, Uniform2dv, ProgramUniform2dv,
+                             location, 1, v.as_ptr() as *const gl::types::GLdouble);
             },
 
             (&RawUniformValue::DoubleVec3(v), target) => {
                 *target = Some(RawUniformValue::DoubleVec3(v));
-                uniform_f64!(ctxt, Uniform3dv, location, 1, v.as_ptr() as *const gl::types::GLdouble);
+                uniform_f64!(ctxt, Uniform3dv, ProgramUniform3dv,
+                             location, 1, v.as_ptr() as *const gl::types::GLdouble);
             },
 
             (&RawUniformValue::DoubleVec4(v), target) => {
                 *target = Some(RawUniformValue::DoubleVec4(v));
-                uniform_f64!(ctxt, Uniform4dv, location, 1, v.as_ptr() as *const gl::types::GLdouble);
+                uniform_f64!(ctxt, Uniform4dv, ProgramUniform4dv,
+                             location, 1, v.as_ptr() as *const gl::types::GLdouble);
             },
             (&RawUniformValue::Int64(v), target) => {
                 *target = Some(RawUniformValue::Int64(v));
-                uniform_i64!(ctxt, Uniform1i64ARB, location, v);
+                uniform_i64!(ctxt, Uniform1i64ARB, ProgramUniform1i64ARB, location, v);
             },
             (&RawUniformValue::Int64Vec2(v), target) => {
                 *target = Some(RawUniformValue::Int64Vec2(v));
-                uniform_i64!(ctxt, Uniform2i64vARB, location, 1, v.as_ptr() as *const gl::types::GLint64);
+                uniform_i64!(ctxt, Uniform2i64vARB, ProgramUniform2i64vARB,
+                             location, 1, v.as_ptr() as *const gl::types::GLint64);
             },
 
             (&RawUniformValue::Int64Vec3(v), target) => {
                 *target = Some(RawUniformValue::Int64Vec3(v));
-                uniform_i64!(ctxt, Uniform3i64vARB, location, 1, v.as_ptr() as *const gl::types::GLint64);
+                uniform_i64!(ctxt, Uniform3i64vARB, ProgramUniform3i64vARB,
+                             location, 1, v.as_ptr() as *const gl::types::GLint64);
             },
 
             (&RawUniformValue::Int64Vec4(v), target) => {
                 *target = Some(RawUniformValue::Int64Vec4(v));
-                uniform_i64!(ctxt, Uniform4i64vARB, location, 1, v.as_ptr() as *const gl::types::GLint64);
+                uniform_i64!(ctxt, Uniform4i64vARB, ProgramUniform4i64vARB,
+                             location, 1, v.as_ptr() as *const gl::types::GLint64);
             },
             (&RawUniformValue::UnsignedInt64(v), target) => {
                 *target = Some(RawUniformValue::UnsignedInt64(v));
-                uniform_i64!(ctxt, Uniform1ui64ARB, location, v);
+                uniform_i64!(ctxt, Uniform1ui64ARB, ProgramUniform1ui64ARB, location, v);
             },
             (&RawUniformValue::UnsignedInt64Vec2(v), target) => {
                 *target = Some(RawUniformValue::UnsignedInt64Vec2(v));
-                uniform_i64!(ctxt, Uniform2ui64vARB, location, 1, v.as_ptr() as *const gl::types::GLuint64);
+                uniform_i64!(ctxt, Uniform2ui64vARB, ProgramUniform2ui64vARB,
+                             location, 1, v.as_ptr() as *const gl::types::GLuint64);
             },
 
             (&RawUniformValue::UnsignedInt64Vec3(v), target) => {
                 *target = Some(RawUniformValue::UnsignedInt64Vec3(v));
-                uniform_i64!(ctxt, Uniform3ui64vARB, location, 1, v.as_ptr() as *const gl::types::GLuint64);
+                uniform_i64!(ctxt, Uniform3ui64vARB, ProgramUniform3ui64vARB,
+                             location, 1, v.as_ptr() as *const gl::types::GLuint64);
             },
 
             (&RawUniformValue::UnsignedInt64Vec4(v), target) => {
                 *target = Some(RawUniformValue::UnsignedInt64Vec4(v));
-                uniform_i64!(ctxt, Uniform4ui64vARB, location, 1, v.as_ptr() as *const gl::types::GLuint64);
+                uniform_i64!(ctxt, Uniform4ui64vARB, ProgramUniform4ui64vARB,
+                             location, 1, v.as_ptr() as *const gl::types::GLuint64);
+            },
+
+            (&RawUniformValue::Handle(v), target) => {
+                *target = Some(RawUniformValue::Handle(v));
+
+                // handles can only exist with `GL_ARB_bindless_texture`
+                unsafe {
+                    prepare!(ctxt);
+                    if let Some(id) = dsa {
+                        ctxt.gl.ProgramUniformHandleui64ARB(id, location, v)
+                    } else {
+                        ctxt.gl.UniformHandleui64ARB(location, v)
+                    }
+                }
+            },
+        }
+    }
+
+    /// Calls the count form of `glUniform` to set the elements of an array of uniforms, starting
+    /// at `location`.
+    ///
+    /// Arrays are always uploaded, and the values of their elements are removed from the storage.
+    pub fn set_uniform_array(&self, ctxt: &mut CommandContext, program: &RawProgram,
+                             location: gl::types::GLint, value: &RawUniformArray)
+    {
+        {
+            let mut values = self.values.borrow_mut();
+            for offset in 0 .. value.len() {
+                values.remove(&(location + offset as gl::types::GLint));
+            }
+        }
+
+        if value.len() == 0 {
+            return;
+        }
+
+        // if the program is not current, contains the id to pass to `glProgramUniform`
+        let dsa = if ctxt.state.program != program.get_id() {
+            prepare_program(ctxt, program)
+        } else {
+            None
+        };
+
+        // the `ARB` functions for unsigned integers don't exist, the signed ones are used instead
+        macro_rules! uniform_array(
+            ($uniform:ident, $uniform_arb:ident, $program_uniform:ident, $v:expr) => (
+                uniform_array!(@call $uniform, $uniform_arb, $program_uniform, location,
+                               $v.len() as gl::types::GLsizei, $v.as_ptr() as *const _)
+            );
+
+            (matrix $uniform:ident, $uniform_arb:ident, $program_uniform:ident, $v:expr) => (
+                uniform_array!(@call $uniform, $uniform_arb, $program_uniform, location,
+                               $v.len() as gl::types::GLsizei, gl::FALSE,
+                               $v.as_ptr() as *const _)
+            );
+
+            (@call $uniform:ident, $uniform_arb:ident, $program_uniform:ident,
+             $($params:expr),+) => (
+                unsafe {
+                    if let Some(id) = dsa {
+                        ctxt.gl.$program_uniform(id, $($params),+)
+                    } else if ctxt.version >= &Version(Api::Gl, 1, 5) ||
+                              ctxt.version >= &Version(Api::GlEs, 2, 0)
+                    {
+                        ctxt.gl.$uniform($($params),+)
+                    } else {
+                        assert!(ctxt.extensions.gl_arb_shader_objects);
+                        ctxt.gl.$uniform_arb($($params),+)
+                    }
+                }
+            );
+        );
+
+        match *value {
+            RawUniformArray::Float(v) => {
+                uniform_array!(Uniform1fv, Uniform1fvARB, ProgramUniform1fv, v)
+            },
+            RawUniformArray::Vec2(v) => {
+                uniform_array!(Uniform2fv, Uniform2fvARB, ProgramUniform2fv, v)
+            },
+            RawUniformArray::Vec3(v) => {
+                uniform_array!(Uniform3fv, Uniform3fvARB, ProgramUniform3fv, v)
+            },
+            RawUniformArray::Vec4(v) => {
+                uniform_array!(Uniform4fv, Uniform4fvARB, ProgramUniform4fv, v)
+            },
+            RawUniformArray::Mat2(v) => {
+                uniform_array!(matrix UniformMatrix2fv, UniformMatrix2fvARB,
+                               ProgramUniformMatrix2fv, v)
+            },
+            RawUniformArray::Mat3(v) => {
+                uniform_array!(matrix UniformMatrix3fv, UniformMatrix3fvARB,
+                               ProgramUniformMatrix3fv, v)
+            },
+            RawUniformArray::Mat4(v) => {
+                uniform_array!(matrix UniformMatrix4fv, UniformMatrix4fvARB,
+                               ProgramUniformMatrix4fv, v)
+            },
+            RawUniformArray::SignedInt(v) => {
+                uniform_array!(Uniform1iv, Uniform1ivARB, ProgramUniform1iv, v)
+            },
+            RawUniformArray::IntVec2(v) => {
+                uniform_array!(Uniform2iv, Uniform2ivARB, ProgramUniform2iv, v)
+            },
+            RawUniformArray::IntVec3(v) => {
+                uniform_array!(Uniform3iv, Uniform3ivARB, ProgramUniform3iv, v)
+            },
+            RawUniformArray::IntVec4(v) => {
+                uniform_array!(Uniform4iv, Uniform4ivARB, ProgramUniform4iv, v)
+            },
+            RawUniformArray::UnsignedInt(v) => {
+                uniform_array!(Uniform1uiv, Uniform1ivARB, ProgramUniform1uiv, v)
+            },
+            RawUniformArray::UnsignedIntVec2(v) => {
+                uniform_array!(Uniform2uiv, Uniform2ivARB, ProgramUniform2uiv, v)
+            },
+            RawUniformArray::UnsignedIntVec3(v) => {
+                uniform_array!(Uniform3uiv, Uniform3ivARB, ProgramUniform3uiv, v)
+            },
+            RawUniformArray::UnsignedIntVec4(v) => {
+                uniform_array!(Uniform4uiv, Uniform4ivARB, ProgramUniform4uiv, v)
+            },
+            RawUniformArray::Handle(v) => unsafe {
+                // handles can only exist with `GL_ARB_bindless_texture`
+                let count = v.len() as gl::types::GLsizei;
+                if let Some(id) = dsa {
+                    ctxt.gl.ProgramUniformHandleui64vARB(id, location, count, v.as_ptr())
+                } else {
+                    ctxt.gl.UniformHandleui64vARB(location, count, v.as_ptr())
+                }
             },
         }
     }
@@ -325,9 +577,6 @@ impl UniformsStorage {
             }
         }
 
-        // TODO: don't assume that, instead use DSA if the program is not current
-        assert!(ctxt.state.program == program);
-
         match (value, &mut blocks[location as usize]) {
             (a, &mut Some(b)) if a == b => (),
 
@@ -356,9 +605,6 @@ impl UniformsStorage {
             }
         }
 
-        // TODO: don't assume that, instead use DSA if the program is not current
-        assert!(ctxt.state.program == program);
-
         match (value, &mut blocks[location as usize]) {
             (a, &mut Some(b)) if a == b => (),
 
@@ -395,3 +641,22 @@ impl UniformsStorage {
         }
     }
 }
+
+/// Called before updating a uniform value of a program that is not current.
+///
+/// Returns the id of the program if `glProgramUniform` is available. Otherwise binds the
+/// program and returns `None`.
+fn prepare_program(ctxt: &mut CommandContext, program: &RawProgram)
+                   -> Option<gl::types::GLuint>
+{
+    match program.get_id() {
+        Handle::Id(id) if ctxt.version >= &Version(Api::Gl, 4, 1) ||
+                          ctxt.version >= &Version(Api::GlEs, 3, 1) ||
+                          ctxt.extensions.gl_arb_separate_shader_objects => Some(id),
+
+        _ => {
+            program.use_program(ctxt);
+            None
+        },
+    }
+}
@@ -9,6 +9,7 @@ use ContextExt;
 use std::rc::Rc;
 
 use std::thread;
+use std::time::Duration;
 
 /// Error that happens when sync functionnalities are not supported.
 #[derive(Copy, Clone, Debug)]
@@ -54,6 +55,55 @@ impl SyncFence {
             _ => panic!("Could not wait for the fence")
         };
     }
+
+    /// Blocks until the operation has finished on the server or until the timeout has expired.
+    ///
+    /// Returns true if the operation has finished. Contrary to `wait`, the fence is not
+    /// destroyed and can be waited upon again.
+    pub fn wait_timeout(&self, timeout: Duration) -> bool {
+        let sync = self.id.unwrap();
+
+        let timeout = timeout.as_secs().saturating_mul(1000 * 1000 * 1000)
+                             .saturating_add(timeout.subsec_nanos() as u64);
+
+        let mut ctxt = self.context.make_current();
+        let result = unsafe { client_wait_timeout(&mut ctxt, sync, timeout) };
+
+        match result {
+            gl::ALREADY_SIGNALED | gl::CONDITION_SATISFIED => true,
+            gl::TIMEOUT_EXPIRED => false,
+            _ => panic!("Could not wait for the fence")
+        }
+    }
+
+    /// Makes the server of `facade` wait until the operation has finished, without blocking
+    /// the CPU.
+    ///
+    /// The commands submitted to `facade` after calling this function are only executed by the
+    /// GPU after the fence is signaled. This is useful when `facade` shares its objects with the
+    /// context that created the fence, for example to render with a texture that was uploaded
+    /// from another thread.
+    pub fn wait_server<F: ?Sized>(&self, facade: &F) where F: Facade {
+        let sync = self.id.unwrap();
+
+        // the fence must have been sent to the server of its context, otherwise the wait never
+        // ends
+        {
+            let ctxt = self.context.make_current();
+            unsafe { ctxt.gl.Flush(); }
+        }
+
+        let mut ctxt = facade.get_context().make_current();
+        unsafe { server_wait(&mut ctxt, sync) };
+    }
+
+    /// Returns true if the operation has finished on the server. Never blocks.
+    ///
+    /// The commands are flushed, so polling this function eventually returns true.
+    #[inline]
+    pub fn is_signaled(&self) -> bool {
+        self.wait_timeout(Duration::new(0, 0))
+    }
 }
 
 impl Drop for SyncFence {
@@ -180,6 +230,45 @@ unsafe fn client_wait(ctxt: &mut CommandContext, fence: gl::types::GLsync) -> gl
     }
 }
 
+/// Calls `glClientWaitSync` with a timeout in nanoseconds and returns the result.
+///
+/// # Unsafety
+///
+/// The fence object must exist.
+///
+unsafe fn client_wait_timeout(ctxt: &mut CommandContext, fence: gl::types::GLsync,
+                              timeout: gl::types::GLuint64) -> gl::types::GLenum
+{
+    if ctxt.version >= &Version(Api::Gl, 3, 2) ||
+       ctxt.version >= &Version(Api::GlEs, 3, 0) || ctxt.extensions.gl_arb_sync
+    {
+        ctxt.gl.ClientWaitSync(fence, gl::SYNC_FLUSH_COMMANDS_BIT, timeout)
+    } else if ctxt.extensions.gl_apple_sync {
+        ctxt.gl.ClientWaitSyncAPPLE(fence, gl::SYNC_FLUSH_COMMANDS_BIT_APPLE, timeout)
+    } else {
+        unreachable!();
+    }
+}
+
+/// Calls `glWaitSync`.
+///
+/// # Unsafety
+///
+/// The fence object must exist.
+///
+#[inline]
+unsafe fn server_wait(ctxt: &mut CommandContext, fence: gl::types::GLsync) {
+    if ctxt.version >= &Version(Api::Gl, 3, 2) ||
+       ctxt.version >= &Version(Api::GlEs, 3, 0) || ctxt.extensions.gl_arb_sync
+    {
+        ctxt.gl.WaitSync(fence, 0, gl::TIMEOUT_IGNORED);
+    } else if ctxt.extensions.gl_apple_sync {
+        ctxt.gl.WaitSyncAPPLE(fence, 0, gl::TIMEOUT_IGNORED_APPLE);
+    } else {
+        unreachable!();
+    }
+}
+
 /// Deletes a fence.
 ///
 /// # Unsafety
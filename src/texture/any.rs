@@ -12,7 +12,7 @@ use TextureMipmapExt;
 use version::Api;
 use Rect;
 
-use image_format::{self, TextureFormatRequest, ClientFormatAny};
+use image_format::{self, TextureFormatRequest, ClientFormat, ClientFormatAny};
 use texture::Texture2dDataSink;
 use texture::TextureKind;
 use texture::{MipmapsOption, TextureFormat, TextureCreationError, CubeLayer};
@@ -73,7 +73,10 @@ pub struct TextureAny {
     generate_mipmaps: bool,
 
     /// Is this texture owned by us? If not, we won't clean it up on drop.
-    owned: bool
+    owned: bool,
+
+    /// ID of the draw call where the texture was last written through an image unit.
+    latest_shader_write: Cell<u64>,
 }
 
 fn extract_dimensions(ty: Dimensions)
@@ -120,6 +123,29 @@ unsafe fn generate_mipmaps(ctxt: &CommandContext,
     }
 }
 
+/// Sets the `GL_UNPACK_ROW_LENGTH`, `GL_UNPACK_SKIP_PIXELS` and `GL_UNPACK_SKIP_ROWS` values.
+///
+/// Passing `0` for all three values restores the default behavior, which is what all the
+/// functions that upload tightly-packed data expect.
+pub unsafe fn set_unpack_region(ctxt: &mut CommandContext, row_length: gl::types::GLint,
+                                skip_pixels: gl::types::GLint, skip_rows: gl::types::GLint)
+{
+    if ctxt.state.pixel_store_unpack_row_length != row_length {
+        ctxt.state.pixel_store_unpack_row_length = row_length;
+        ctxt.gl.PixelStorei(gl::UNPACK_ROW_LENGTH, row_length);
+    }
+
+    if ctxt.state.pixel_store_unpack_skip_pixels != skip_pixels {
+        ctxt.state.pixel_store_unpack_skip_pixels = skip_pixels;
+        ctxt.gl.PixelStorei(gl::UNPACK_SKIP_PIXELS, skip_pixels);
+    }
+
+    if ctxt.state.pixel_store_unpack_skip_rows != skip_rows {
+        ctxt.state.pixel_store_unpack_skip_rows = skip_rows;
+        ctxt.gl.PixelStorei(gl::UNPACK_SKIP_ROWS, skip_rows);
+    }
+}
+
 /// Builds a new texture.
 ///
 /// # Panic
@@ -190,6 +216,15 @@ pub fn new_texture<'a, F: ?Sized, P>(facade: &F, format: TextureFormatRequest,
         _ => (gl::LINEAR, gl::LINEAR_MIPMAP_LINEAR),
     };
 
+    let uses_tex_storage = storage_internal_format.is_some() &&
+                           (facade.get_context().get_version() >= &Version(Api::Gl, 4, 2) ||
+                            facade.get_context().get_extensions().gl_arb_texture_storage);
+
+    let compressed_format = match data {
+        Some((client_format, _)) if client_format.is_compressed() => Some(client_format),
+        _ => None,
+    };
+
     let is_multisampled = match ty {
         Dimensions::Texture2dMultisample {..}
         | Dimensions::Texture2dMultisampleArray {..} => true,
@@ -212,6 +247,7 @@ pub fn new_texture<'a, F: ?Sized, P>(facade: &F, format: TextureFormatRequest,
             ctxt.gl.PixelStorei(gl::UNPACK_ALIGNMENT, 1);
         }
 
+        set_unpack_region(&mut ctxt, 0, 0, 0);
         BufferAny::unbind_pixel_unpack(&mut ctxt);
 
         let id: gl::types::GLuint = mem::uninitialized();
@@ -447,6 +483,16 @@ pub fn new_texture<'a, F: ?Sized, P>(facade: &F, format: TextureFormatRequest,
             unreachable!();
         }
 
+        // without `glTexStorage`, the mipmaps that aren't generated must be allocated
+        // manually so that they can be uploaded later
+        if has_mipmaps && !should_generate_mipmaps && !uses_tex_storage &&
+           bind_point != gl::TEXTURE_CUBE_MAP && bind_point != gl::TEXTURE_CUBE_MAP_ARRAY
+        {
+            allocate_mipmaps(&mut ctxt, bind_point, texture_levels,
+                             teximg_internal_format as gl::types::GLenum, compressed_format,
+                             (client_format, client_type), width, height, depth, array_size);
+        }
+
         // only generate mipmaps for color textures
         if should_generate_mipmaps {
             generate_mipmaps(&ctxt, bind_point);
@@ -463,10 +509,142 @@ pub fn new_texture<'a, F: ?Sized, P>(facade: &F, format: TextureFormatRequest,
         ty: ty,
         levels: texture_levels as u32,
         generate_mipmaps: should_generate_mipmaps,
-        owned: true
+        owned: true,
+        latest_shader_write: Cell::new(0),
     })
 }
 
+/// Allocates the mipmap levels `1 .. levels` of a texture that was created without
+/// `glTexStorage`.
+///
+/// If `compressed_format` is `Some`, the levels are allocated with `glCompressedTexImage*` and
+/// a buffer size that matches this format.
+unsafe fn allocate_mipmaps(ctxt: &mut CommandContext, bind_point: gl::types::GLenum,
+                           levels: gl::types::GLsizei, internal_format: gl::types::GLenum,
+                           compressed_format: Option<ClientFormatAny>,
+                           (client_format, client_type): (gl::types::GLenum, gl::types::GLenum),
+                           width: u32, height: Option<u32>, depth: Option<u32>,
+                           array_size: Option<u32>)
+{
+    for level in 1 .. levels {
+        let width = cmp::max(1, width >> level);
+        let height = height.map(|height| cmp::max(1, height >> level));
+        let depth = depth.map(|depth| cmp::max(1, depth >> level));
+
+        let bufsize = compressed_format.map(|format| {
+            format.get_buffer_size(width, height, depth, array_size) as gl::types::GLsizei
+        });
+
+        let width = width as gl::types::GLsizei;
+
+        if bind_point == gl::TEXTURE_3D || bind_point == gl::TEXTURE_2D_ARRAY {
+            let height = height.unwrap() as gl::types::GLsizei;
+            let depth = cmp::max(1, depth.or(array_size).unwrap()) as gl::types::GLsizei;
+
+            if let Some(bufsize) = bufsize {
+                ctxt.gl.CompressedTexImage3D(bind_point, level, internal_format, width, height,
+                                             depth, 0, bufsize, ptr::null());
+            } else {
+                ctxt.gl.TexImage3D(bind_point, level, internal_format as i32, width, height,
+                                   depth, 0, client_format, client_type, ptr::null());
+            }
+
+        } else if bind_point == gl::TEXTURE_2D || bind_point == gl::TEXTURE_1D_ARRAY {
+            let height = cmp::max(1, height.or(array_size).unwrap()) as gl::types::GLsizei;
+
+            if let Some(bufsize) = bufsize {
+                ctxt.gl.CompressedTexImage2D(bind_point, level, internal_format, width, height,
+                                             0, bufsize, ptr::null());
+            } else {
+                ctxt.gl.TexImage2D(bind_point, level, internal_format as i32, width, height, 0,
+                                   client_format, client_type, ptr::null());
+            }
+
+        } else if bind_point == gl::TEXTURE_1D {
+            if let Some(bufsize) = bufsize {
+                ctxt.gl.CompressedTexImage1D(bind_point, level, internal_format, width, 0,
+                                             bufsize, ptr::null());
+            } else {
+                ctxt.gl.TexImage1D(bind_point, level, internal_format as i32, width, 0,
+                                   client_format, client_type, ptr::null());
+            }
+
+        } else {
+            unreachable!();
+        }
+    }
+}
+
+/// Builds a new texture from pre-compressed data for each of its mipmap levels.
+///
+/// The first element of `levels` is the main level, the second element the first mipmap, and
+/// so on. For array textures, each level must contain the data of all the layers.
+///
+/// # Panic
+///
+/// Panics if `levels` is empty.
+pub fn new_compressed_texture_with_mipmaps<F: ?Sized>(facade: &F, format: TextureFormatRequest,
+                                                      client_format: ClientFormatAny,
+                                                      levels: &[Cow<[u8]>], ty: Dimensions)
+                                                      -> Result<TextureAny, TextureCreationError>
+                                                      where F: Facade
+{
+    assert!(!levels.is_empty(), "A texture must have at least one level");
+
+    let (width, height, depth, array_size, _) = extract_dimensions(ty);
+    if levels[0].len() != client_format.get_buffer_size(width, height, depth, array_size) {
+        return Err(TextureCreationError::DataSizeMismatch);
+    }
+
+    if levels.len() as u32 > MipmapsOption::EmptyMipmaps.num_levels(width, height, depth) {
+        return Err(TextureCreationError::DimensionsNotSupported);
+    }
+
+    let mipmaps = match levels.len() {
+        1 => MipmapsOption::NoMipmap,
+        n => MipmapsOption::EmptyMipmapsMax(n as u32 - 1),
+    };
+
+    let texture = try!(new_texture(facade, format,
+                                   Some((client_format, Cow::Borrowed(&levels[0][..]))),
+                                   mipmaps, ty));
+
+    for (level, data) in levels.iter().enumerate().skip(1) {
+        let mipmap = match texture.mipmap(level as u32) {
+            Some(mipmap) => mipmap,
+            None => return Err(TextureCreationError::DimensionsNotSupported),
+        };
+
+        // the layers of array textures are uploaded as the last dimension of the level
+        let (width, height, depth) = match ty {
+            Dimensions::Texture1dArray { array_size, .. } => {
+                (mipmap.get_width(), Some(array_size), None)
+            },
+            Dimensions::Texture2dArray { array_size, .. } => {
+                (mipmap.get_width(), mipmap.get_height(), Some(array_size))
+            },
+            _ => (mipmap.get_width(), mipmap.get_height(), mipmap.get_depth()),
+        };
+
+        let bufsize = match ty {
+            Dimensions::Texture2dArray { .. } => {
+                client_format.get_buffer_size(width, height, None, depth)
+            },
+            _ => client_format.get_buffer_size(width, height, depth, None),
+        };
+
+        if data.len() != bufsize {
+            return Err(TextureCreationError::DataSizeMismatch);
+        }
+
+        try!(mipmap.upload_texture(0, 0, 0, (client_format, Cow::Borrowed(&data[..])), width,
+                                   height, depth, false)
+                   .map_err(|_| TextureCreationError::FormatNotSupported));
+    }
+
+    Ok(texture)
+}
+
 /// Builds a new texture reference from an existing, externally created OpenGL texture.
 /// If `owned` is true, this reference will take ownership of the texture and be responsible
 /// for cleaning it up. Otherwise, the texture must be cleaned up externally, but only
@@ -482,8 +660,15 @@ pub unsafe fn from_id<F: Facade + ?Sized>(facade: &F,
     let mipmap_levels = mipmaps.num_levels(width, height, depth);
     let should_generate_mipmaps = mipmaps.should_generate();
     if should_generate_mipmaps {
-        let ctxt = facade.get_context().make_current();
-        generate_mipmaps(&ctxt, get_bind_point(ty));
+        let mut ctxt = facade.get_context().make_current();
+        let bind_point = get_bind_point(ty);
+
+        // the texture must be bound before generating its mipmaps
+        ctxt.gl.BindTexture(bind_point, id);
+        let act = ctxt.state.active_texture as usize;
+        ctxt.state.texture_units[act].texture = id;
+
+        generate_mipmaps(&ctxt, bind_point);
     }
     TextureAny {
         context: facade.get_context().clone(),
@@ -493,7 +678,8 @@ pub unsafe fn from_id<F: Facade + ?Sized>(facade: &F,
         ty: ty,
         levels: mipmap_levels,
         generate_mipmaps: should_generate_mipmaps,
-        owned: owned
+        owned: owned,
+        latest_shader_write: Cell::new(0),
     }
 }
 
@@ -715,6 +901,38 @@ impl TextureExt for TextureAny {
 
         bind_point
     }
+
+    fn prepare_for_texture_fetch(&self, ctxt: &mut CommandContext) {
+        if self.latest_shader_write.get() >= ctxt.state.latest_memory_barrier_texture_fetch {
+            unsafe { ctxt.gl.MemoryBarrier(gl::TEXTURE_FETCH_BARRIER_BIT); }
+            ctxt.state.latest_memory_barrier_texture_fetch = ctxt.state.next_draw_call_id;
+        }
+    }
+
+    fn prepare_for_image_unit(&self, ctxt: &mut CommandContext, write: bool) {
+        if self.latest_shader_write.get() >= ctxt.state.latest_memory_barrier_shader_image_access {
+            unsafe { ctxt.gl.MemoryBarrier(gl::SHADER_IMAGE_ACCESS_BARRIER_BIT); }
+            ctxt.state.latest_memory_barrier_shader_image_access = ctxt.state.next_draw_call_id;
+        }
+
+        if write {
+            self.latest_shader_write.set(ctxt.state.next_draw_call_id);
+        }
+    }
+
+    fn prepare_for_texture_update(&self, ctxt: &mut CommandContext) {
+        if self.latest_shader_write.get() >= ctxt.state.latest_memory_barrier_texture_update {
+            unsafe { ctxt.gl.MemoryBarrier(gl::TEXTURE_UPDATE_BARRIER_BIT); }
+            ctxt.state.latest_memory_barrier_texture_update = ctxt.state.next_draw_call_id;
+        }
+    }
+
+    fn prepare_for_framebuffer(&self, ctxt: &mut CommandContext) {
+        if self.latest_shader_write.get() >= ctxt.state.latest_memory_barrier_framebuffer {
+            unsafe { ctxt.gl.MemoryBarrier(gl::FRAMEBUFFER_BARRIER_BIT); }
+            ctxt.state.latest_memory_barrier_framebuffer = ctxt.state.next_draw_call_id;
+        }
+    }
 }
 
 impl GlObject for TextureAny {
@@ -956,6 +1174,113 @@ impl<'a> TextureAnyMipmap<'a> {
         self.raw_upload_from_pixel_buffer_impl(source, x, y, z, true);
     }
 
+    /// Uploads a rectangle of a larger image to the texture.
+    ///
+    /// `data` contains the whole source image, whose rows are `source_width` pixels long. The
+    /// start of each row is aligned to `unpack_alignment` bytes, which must be 1, 2, 4 or 8 and
+    /// corresponds to `GL_UNPACK_ALIGNMENT`. Only the pixels within `source` are uploaded, and
+    /// they are written at `(x_offset, y_offset)` in the texture. On OpenGL ES 2 without
+    /// `GL_EXT_unpack_subimage`, the rectangle is first repacked on the CPU.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the texture is not a 2D texture or a 1D texture array. Panics if
+    /// `unpack_alignment` is invalid, if `source` is outside of the boundaries of `data`, or if
+    /// the destination is outside the boundaries of the texture.
+    pub fn raw_upload_from_region<P>(&self, data: &[P], source_width: u32, unpack_alignment: u32,
+                                     source: &Rect, x_offset: u32, y_offset: u32)
+                                     where P: PixelValue
+    {
+        match self.texture.ty {
+            Dimensions::Texture2d { .. } | Dimensions::Texture1dArray { .. } => (),
+            _ => panic!("Uploading a region is only supported for 2D textures")
+        };
+
+        assert!(unpack_alignment == 1 || unpack_alignment == 2 || unpack_alignment == 4 ||
+                unpack_alignment == 8);
+
+        let pixel_size = mem::size_of::<P>();
+        let row_size = source_width as usize * pixel_size;
+        let row_stride = (row_size + unpack_alignment as usize - 1) /
+                         unpack_alignment as usize * unpack_alignment as usize;
+
+        // the last row doesn't need to be padded
+        assert!(source.left + source.width <= source_width);
+        if source.height != 0 {
+            assert!((source.bottom + source.height - 1) as usize * row_stride + row_size <=
+                    data.len() * pixel_size);
+        }
+
+        let client_format = ClientFormatAny::ClientFormat(P::get_format());
+
+        let unpack_subimage = {
+            let ctxt = &self.texture.context;
+            ctxt.get_version() >= &Version(Api::Gl, 1, 0) ||
+            ctxt.get_version() >= &Version(Api::GlEs, 3, 0) ||
+            ctxt.get_extensions().gl_ext_unpack_subimage
+        };
+
+        if !unpack_subimage {
+            let len = (source.width * source.height) as usize;
+            let width = source.width as usize * pixel_size;
+
+            // rows are copied byte per byte, since the padding isn't necessarily a multiple of
+            // the size of a pixel
+            let mut repacked: Vec<P> = Vec::with_capacity(len);
+            unsafe {
+                let src = data.as_ptr() as *const u8;
+                let dest = repacked.as_mut_ptr() as *mut u8;
+                for (index, row) in (source.bottom .. source.bottom + source.height).enumerate() {
+                    let start = row as usize * row_stride + source.left as usize * pixel_size;
+                    ptr::copy_nonoverlapping(src.offset(start as isize),
+                                             dest.offset((index * width) as isize), width);
+                }
+                repacked.set_len(len);
+            }
+
+            self.upload_texture(x_offset, y_offset, 0, (client_format, Cow::Owned(repacked)),
+                                source.width, Some(source.height), None,
+                                self.level == 0).unwrap();
+            return;
+        }
+
+        assert!(x_offset + source.width <= self.width);
+        assert!(y_offset + source.height <= self.height.unwrap_or(1));
+
+        let (client_format, client_type) =
+            image_format::client_format_to_glenum(&self.texture.context, client_format,
+                                                  self.texture.requested_format, false).unwrap();
+
+        let mut ctxt = self.texture.context.make_current();
+        self.texture.prepare_for_texture_update(&mut ctxt);
+
+        unsafe {
+            let unpack_alignment = unpack_alignment as gl::types::GLint;
+            if ctxt.state.pixel_store_unpack_alignment != unpack_alignment {
+                ctxt.state.pixel_store_unpack_alignment = unpack_alignment;
+                ctxt.gl.PixelStorei(gl::UNPACK_ALIGNMENT, unpack_alignment);
+            }
+
+            set_unpack_region(&mut ctxt, source_width as gl::types::GLint,
+                              source.left as gl::types::GLint,
+                              source.bottom as gl::types::GLint);
+            BufferAny::unbind_pixel_unpack(&mut ctxt);
+            let bind_point = self.texture.bind_to_current(&mut ctxt);
+
+            ctxt.gl.TexSubImage2D(bind_point, self.level as gl::types::GLint,
+                                  x_offset as gl::types::GLint,
+                                  y_offset as gl::types::GLint,
+                                  source.width as gl::types::GLsizei,
+                                  source.height as gl::types::GLsizei,
+                                  client_format, client_type,
+                                  data.as_ptr() as *const _);
+
+            if self.level == 0 && self.texture.levels >= 2 && self.texture.generate_mipmaps {
+                generate_mipmaps(&ctxt, bind_point);
+            }
+        }
+    }
+
     fn raw_upload_from_pixel_buffer_impl<P>(&self, source: BufferSlice<[P]>, x: Range<u32>,
                                             y: Range<u32>, z: Range<u32>, inverted: bool)
                                             where P: PixelValue
@@ -982,9 +1307,11 @@ impl<'a> TextureAnyMipmap<'a> {
                                                   self.texture.requested_format, inverted).unwrap();
 
         let mut ctxt = self.texture.context.make_current();
+        self.texture.prepare_for_texture_update(&mut ctxt);
 
         // binds the pixel buffer
         source.prepare_and_bind_for_pixel_unpack(&mut ctxt);
+        unsafe { set_unpack_region(&mut ctxt, 0, 0, 0); }
 
         match self.texture.ty {
             Dimensions::Texture1d { .. } => {
@@ -1138,18 +1465,23 @@ impl<'t> TextureMipmapExt for TextureAnyMipmap<'t> {
         let id = self.texture.id;
         let level = self.level;
 
-        let (is_client_compressed, data_bufsize) = (format.is_compressed(),
-                                                    format.get_buffer_size(width, height, depth, None));
+        // the layers of 2D array textures are passed as `depth`
+        let data_bufsize = match self.texture.ty {
+            Dimensions::Texture2dArray { .. } => format.get_buffer_size(width, height, None, depth),
+            _ => format.get_buffer_size(width, height, depth, None),
+        };
+        let is_client_compressed = format.is_compressed();
         let regen_mipmaps = regen_mipmaps && self.texture.levels >= 2 &&
                             self.texture.generate_mipmaps && !is_client_compressed;
 
         assert!(!regen_mipmaps || level == 0);  // when regen_mipmaps is true, level must be 0!
         assert!(x_offset <= self.width);
-        assert!(y_offset <= self.height.unwrap_or(1));
-        assert!(z_offset <= self.depth.unwrap_or(1));
+        let layers = self.texture.get_array_size();
+        assert!(y_offset <= self.height.or(layers).unwrap_or(1));
+        assert!(z_offset <= self.depth.or(layers).unwrap_or(1));
         assert!(x_offset + width <= self.width);
-        assert!(y_offset + height.unwrap_or(1) <= self.height.unwrap_or(1));
-        assert!(z_offset + depth.unwrap_or(1) <= self.depth.unwrap_or(1));
+        assert!(y_offset + height.unwrap_or(1) <= self.height.or(layers).unwrap_or(1));
+        assert!(z_offset + depth.unwrap_or(1) <= self.depth.or(layers).unwrap_or(1));
 
         if data.len() * mem::size_of::<P>() != data_bufsize
         {
@@ -1162,6 +1494,7 @@ impl<'t> TextureMipmapExt for TextureAnyMipmap<'t> {
                                                                                       .map_err(|_| ()));
 
         let mut ctxt = self.texture.context.make_current();
+        self.texture.prepare_for_texture_update(&mut ctxt);
 
         unsafe {
             if ctxt.state.pixel_store_unpack_alignment != 1 {
@@ -1169,11 +1502,33 @@ impl<'t> TextureMipmapExt for TextureAnyMipmap<'t> {
                 ctxt.gl.PixelStorei(gl::UNPACK_ALIGNMENT, 1);
             }
 
+            set_unpack_region(&mut ctxt, 0, 0, 0);
             BufferAny::unbind_pixel_unpack(&mut ctxt);
             let bind_point = self.texture.bind_to_current(&mut ctxt);
 
             if bind_point == gl::TEXTURE_3D || bind_point == gl::TEXTURE_2D_ARRAY {
-                unimplemented!();
+                if is_client_compressed {
+                    ctxt.gl.CompressedTexSubImage3D(bind_point, level as gl::types::GLint,
+                                                    x_offset as gl::types::GLint,
+                                                    y_offset as gl::types::GLint,
+                                                    z_offset as gl::types::GLint,
+                                                    width as gl::types::GLsizei,
+                                                    height.unwrap_or(1) as gl::types::GLsizei,
+                                                    depth.unwrap_or(1) as gl::types::GLsizei,
+                                                    client_format,
+                                                    data_bufsize as gl::types::GLsizei,
+                                                    data.as_ptr() as *const _);
+                } else {
+                    ctxt.gl.TexSubImage3D(bind_point, level as gl::types::GLint,
+                                          x_offset as gl::types::GLint,
+                                          y_offset as gl::types::GLint,
+                                          z_offset as gl::types::GLint,
+                                          width as gl::types::GLsizei,
+                                          height.unwrap_or(1) as gl::types::GLsizei,
+                                          depth.unwrap_or(1) as gl::types::GLsizei,
+                                          client_format, client_type,
+                                          data.as_ptr() as *const _);
+                }
 
             } else if bind_point == gl::TEXTURE_2D || bind_point == gl::TEXTURE_1D_ARRAY {
                 assert!(z_offset == 0);
@@ -1197,11 +1552,28 @@ impl<'t> TextureMipmapExt for TextureAnyMipmap<'t> {
                                           data.as_ptr() as *const _);
                 }
 
-            } else {
+            } else if bind_point == gl::TEXTURE_1D {
                 assert!(z_offset == 0);
                 assert!(y_offset == 0);
 
-                unimplemented!();
+                if is_client_compressed {
+                    ctxt.gl.CompressedTexSubImage1D(bind_point, level as gl::types::GLint,
+                                                    x_offset as gl::types::GLint,
+                                                    width as gl::types::GLsizei,
+                                                    client_format,
+                                                    data_bufsize as gl::types::GLsizei,
+                                                    data.as_ptr() as *const _);
+                } else {
+                    ctxt.gl.TexSubImage1D(bind_point, level as gl::types::GLint,
+                                          x_offset as gl::types::GLint,
+                                          width as gl::types::GLsizei,
+                                          client_format, client_type,
+                                          data.as_ptr() as *const _);
+                }
+
+            } else {
+                // cubemaps are uploaded face by face
+                return Err(());
             }
 
             // regenerate mipmaps if there are some
@@ -1222,6 +1594,7 @@ impl<'t> TextureMipmapExt for TextureAnyMipmap<'t> {
         let level = self.level as i32;
 
         let mut ctxt = texture.context.make_current();
+        texture.prepare_for_texture_update(&mut ctxt);
 
         unsafe {
             let bind_point = texture.bind_to_current(&mut ctxt);
@@ -1449,6 +1822,90 @@ impl<'a> TextureAnyImage<'a> {
         ops::read(&mut ctxt, &fbo::RegularAttachment::Texture(*self), &rect, dest, false);
     }
 
+    /// Uploads some data in the image.
+    ///
+    /// Contrary to `TextureAnyMipmap`, this writes only to this image. For example for a cubemap
+    /// only the face of this image is modified.
+    ///
+    /// # Panic
+    ///
+    /// - Panicks if the rect is out of range.
+    /// - Panicks if the length of `data` doesn't match the dimensions of `rect`.
+    /// - Panicks if the texture is not a 2D texture, a 2D texture array, a cubemap or a cubemap
+    ///   array.
+    ///
+    pub fn raw_upload<'d, P>(&self, rect: &Rect, data: Cow<'d, [P]>, format: ClientFormat)
+                             where P: Send + Copy + Clone + 'd
+    {
+        assert!(rect.left + rect.width <= self.width);
+        assert!(rect.bottom + rect.height <= self.height.unwrap_or(1));
+
+        let format = ClientFormatAny::ClientFormat(format);
+        let data_bufsize = format.get_buffer_size(rect.width, Some(rect.height), None, None);
+        if data.len() * mem::size_of::<P>() != data_bufsize {
+            panic!("Texture data size mismatch");
+        }
+
+        let (client_format, client_type) =
+            image_format::client_format_to_glenum(&self.texture.context, format,
+                                                  self.texture.requested_format, false).unwrap();
+
+        let mut ctxt = self.texture.context.make_current();
+        self.texture.prepare_for_texture_update(&mut ctxt);
+
+        unsafe {
+            if ctxt.state.pixel_store_unpack_alignment != 1 {
+                ctxt.state.pixel_store_unpack_alignment = 1;
+                ctxt.gl.PixelStorei(gl::UNPACK_ALIGNMENT, 1);
+            }
+
+            set_unpack_region(&mut ctxt, 0, 0, 0);
+            BufferAny::unbind_pixel_unpack(&mut ctxt);
+            let bind_point = self.texture.bind_to_current(&mut ctxt);
+
+            let face = self.cube_layer.map(|l| l.get_layer_index() as u32);
+
+            match self.texture.ty {
+                Dimensions::Texture2d { .. } | Dimensions::Cubemap { .. } => {
+                    let target = match face {
+                        Some(face) => gl::TEXTURE_CUBE_MAP_POSITIVE_X + face,
+                        None => bind_point,
+                    };
+
+                    ctxt.gl.TexSubImage2D(target, self.level as gl::types::GLint,
+                                          rect.left as gl::types::GLint,
+                                          rect.bottom as gl::types::GLint,
+                                          rect.width as gl::types::GLsizei,
+                                          rect.height as gl::types::GLsizei,
+                                          client_format, client_type,
+                                          data.as_ptr() as *const _);
+                },
+
+                Dimensions::Texture2dArray { .. } | Dimensions::CubemapArray { .. } => {
+                    let layer = match face {
+                        Some(face) => self.layer * 6 + face,
+                        None => self.layer,
+                    };
+
+                    ctxt.gl.TexSubImage3D(bind_point, self.level as gl::types::GLint,
+                                          rect.left as gl::types::GLint,
+                                          rect.bottom as gl::types::GLint,
+                                          layer as gl::types::GLint,
+                                          rect.width as gl::types::GLsizei,
+                                          rect.height as gl::types::GLsizei, 1,
+                                          client_format, client_type,
+                                          data.as_ptr() as *const _);
+                },
+
+                _ => panic!("Uploading to a single image is not supported for this kind of texture")
+            }
+
+            if self.level == 0 && self.texture.levels >= 2 && self.texture.generate_mipmaps {
+                generate_mipmaps(&ctxt, bind_point);
+            }
+        }
+    }
+
     /// Clears the content of the texture to a specific value.
     ///
     /// # Panic
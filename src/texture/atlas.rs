@@ -0,0 +1,217 @@
+/*!
+Packing many small images into a single texture.
+
+Switching between textures is expensive, and a draw call can only use a limited number of them.
+A common solution is to gather many small images into a single texture at load time. This module
+provides two ways to do so:
+
+ - An atlas, where all the images are packed side by side inside of a `Texture2d`. Each image
+   can have different dimensions, and you get back the texture coordinates of each image.
+ - A `Texture2dArray`, where each image is stored in its own layer. All the images must have the
+   same dimensions, and you get back the layer of each image.
+
+```no_run
+# let display: glium::Display = unsafe { std::mem::uninitialized() };
+# let image1: glium::texture::RawImage2d<u8> = unsafe { std::mem::uninitialized() };
+# let image2: glium::texture::RawImage2d<u8> = unsafe { std::mem::uninitialized() };
+let mut builder = glium::texture::atlas::AtlasBuilder::new();
+let first = builder.add(image1);
+let second = builder.add(image2);
+
+let (texture, regions) = builder.build_atlas(&display, 1024).unwrap();
+
+// use `regions[first].uv_offset` and `regions[first].uv_scale` in your shaders
+```
+
+*/
+use std::cmp;
+use std::fmt;
+use std::error::Error;
+
+use backend::Facade;
+use Rect;
+
+use texture::{RawImage2d, Texture2d, Texture2dArray, TextureCreationError};
+use texture::pixel::PixelValue;
+
+/// Error that can happen when building an atlas or an array.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum AtlasError {
+    /// No image was added to the builder.
+    Empty,
+
+    /// An image is wider than the maximum width of the atlas.
+    ImageTooWide,
+
+    /// Building a texture array requires all the images to have the same dimensions.
+    DimensionsMismatch,
+
+    /// Error while creating the texture.
+    TextureCreationError(TextureCreationError),
+}
+
+impl From<TextureCreationError> for AtlasError {
+    #[inline]
+    fn from(err: TextureCreationError) -> AtlasError {
+        AtlasError::TextureCreationError(err)
+    }
+}
+
+impl fmt::Display for AtlasError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.description())
+    }
+}
+
+impl Error for AtlasError {
+    fn description(&self) -> &str {
+        use self::AtlasError::*;
+        match *self {
+            Empty =>
+                "No image was added to the builder",
+            ImageTooWide =>
+                "An image is wider than the maximum width of the atlas",
+            DimensionsMismatch =>
+                "All the images of a texture array must have the same dimensions",
+            TextureCreationError(_) =>
+                "Error while creating the texture",
+        }
+    }
+
+    #[inline]
+    fn cause(&self) -> Option<&Error> {
+        match *self {
+            AtlasError::TextureCreationError(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+/// Location of an image inside of an atlas.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct AtlasRegion {
+    /// Position of the image in the atlas, in pixels.
+    pub rect: Rect,
+
+    /// Texture coordinates of the bottom-left corner of the image.
+    pub uv_offset: [f32; 2],
+
+    /// Size of the image in texture coordinates.
+    ///
+    /// The texture coordinates of a point of the image are `uv_offset + uv * uv_scale`, where
+    /// `uv` are the coordinates of the point relative to the image.
+    pub uv_scale: [f32; 2],
+}
+
+/// Gathers images before packing them into a single texture.
+pub struct AtlasBuilder<'a, P> where P: Clone + 'a {
+    images: Vec<RawImage2d<'a, P>>,
+    padding: u32,
+}
+
+impl<'a, P> AtlasBuilder<'a, P> where P: PixelValue + Clone + 'a {
+    /// Builds a new empty builder.
+    #[inline]
+    pub fn new() -> AtlasBuilder<'a, P> {
+        AtlasBuilder {
+            images: Vec::new(),
+            padding: 0,
+        }
+    }
+
+    /// Sets the number of pixels to leave empty between two images of an atlas.
+    ///
+    /// Padding avoids colors bleeding from one image to another when sampling with linear
+    /// filtering or mipmaps. The default value is `0`. The content of the padding is undefined.
+    #[inline]
+    pub fn with_padding(mut self, padding: u32) -> AtlasBuilder<'a, P> {
+        self.padding = padding;
+        self
+    }
+
+    /// Adds an image to the builder. Returns the index of the image in the list of regions or
+    /// layers returned when building the texture.
+    ///
+    /// All the images must have the same client format.
+    #[inline]
+    pub fn add(&mut self, image: RawImage2d<'a, P>) -> usize {
+        self.images.push(image);
+        self.images.len() - 1
+    }
+
+    /// Packs all the images into a two-dimensional texture whose width is at most `max_width`.
+    ///
+    /// Images are sorted by height and placed on rows, from the bottom to the top of the
+    /// texture. The height of the texture is adjusted to fit all the rows.
+    pub fn build_atlas<F: ?Sized>(self, facade: &F, max_width: u32)
+                                  -> Result<(Texture2d, Vec<AtlasRegion>), AtlasError>
+                                  where F: Facade
+    {
+        if self.images.is_empty() {
+            return Err(AtlasError::Empty);
+        }
+
+        let padding = self.padding;
+
+        // sorting by decreasing height gives tighter rows
+        let mut order: Vec<usize> = (0 .. self.images.len()).collect();
+        order.sort_by(|&a, &b| self.images[b].height.cmp(&self.images[a].height));
+
+        let mut positions = vec![(0, 0); self.images.len()];
+        let (mut x, mut y, mut row_height, mut width) = (0, 0, 0, 0);
+
+        for &index in order.iter() {
+            let image = &self.images[index];
+            if image.width > max_width {
+                return Err(AtlasError::ImageTooWide);
+            }
+
+            if x != 0 && x + image.width > max_width {
+                x = 0;
+                y += row_height + padding;
+                row_height = 0;
+            }
+
+            positions[index] = (x, y);
+            width = cmp::max(width, x + image.width);
+            row_height = cmp::max(row_height, image.height);
+            x += image.width + padding;
+        }
+
+        let height = y + row_height;
+        let texture = try!(Texture2d::empty(facade, width, height));
+
+        let mut regions = Vec::with_capacity(self.images.len());
+        for (image, (left, bottom)) in self.images.into_iter().zip(positions.into_iter()) {
+            let rect = Rect { left: left, bottom: bottom, width: image.width, height: image.height };
+
+            regions.push(AtlasRegion {
+                rect: rect,
+                uv_offset: [left as f32 / width as f32, bottom as f32 / height as f32],
+                uv_scale: [image.width as f32 / width as f32, image.height as f32 / height as f32],
+            });
+
+            texture.write(rect, image);
+        }
+
+        Ok((texture, regions))
+    }
+
+    /// Stores each image in a layer of a `Texture2dArray`. The layer of each image is its index.
+    ///
+    /// All the images must have the same dimensions.
+    pub fn build_array<F: ?Sized>(self, facade: &F) -> Result<Texture2dArray, AtlasError>
+                                  where F: Facade
+    {
+        let (width, height) = match self.images.first() {
+            Some(image) => (image.width, image.height),
+            None => return Err(AtlasError::Empty),
+        };
+
+        if self.images.iter().any(|image| image.width != width || image.height != height) {
+            return Err(AtlasError::DimensionsMismatch);
+        }
+
+        Ok(try!(Texture2dArray::new(facade, self.images)))
+    }
+}
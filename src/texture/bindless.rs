@@ -51,6 +51,20 @@ let uniform_buffer = glium::uniforms::UniformBuffer::new(&display, UniformBuffer
 # }
 ```
 
+A handle, or a slice of handles, can also be passed directly as the value of a `sampler*`
+uniform. Glium checks when drawing that the textures are still resident.
+
+```no_run
+#[macro_use]
+extern crate glium;
+
+# fn main() {
+# let texture: glium::texture::bindless::ResidentTexture = unsafe { std::mem::uninitialized() };
+let handle = glium::texture::TextureHandle::new(&texture, &Default::default());
+let uniforms = uniform! { tex: handle };
+# }
+```
+
 Inside your shader, you can refer to the texture with a traditional `sampler*` variable. Glium
 currently doesn't check whether the type of your texture matches the expected type (but it may
 do in the future). Binding the wrong type of texture may lead to undefined values when sampling
@@ -150,6 +164,8 @@ impl Drop for ResidentTexture {
 }
 
 /// Represents a handle to a texture. Contains a raw pointer to a texture that is hidden from you.
+// the layout must be the same as a `GLuint64` so that slices of handles can be uploaded
+#[repr(C)]
 #[derive(Copy, Clone)]
 pub struct TextureHandle<'a> {
     value: gl::types::GLuint64,
@@ -178,8 +194,14 @@ impl<'a> TextureHandle<'a> {
 impl<'a> AsUniformValue for TextureHandle<'a> {
     #[inline]
     fn as_uniform_value(&self) -> UniformValue {
-        // TODO: u64
-        unimplemented!();
+        UniformValue::Handle(self.value)
+    }
+}
+
+impl<'a, 'b> AsUniformValue for &'a [TextureHandle<'b>] {
+    #[inline]
+    fn as_uniform_value(&self) -> UniformValue {
+        UniformValue::HandleArray(*self)
     }
 }
 
@@ -53,7 +53,6 @@ wrong type will result in an error.
 
 */
 use std::{ mem, fmt };
-use std::marker::PhantomData;
 use std::ops::{Deref, DerefMut};
 use std::rc::Rc;
 use std::error::Error;
@@ -496,9 +495,9 @@ impl<T> BufferTexture<T> where [T]: BufferContent {
     #[inline]
     pub fn as_buffer_texture_ref(&self) -> BufferTextureRef {
         BufferTextureRef {
+            context: self.buffer.get_context(),
             texture: self.texture,
             ty: self.ty,
-            marker: PhantomData,
         }
     }
 }
@@ -522,9 +521,9 @@ impl<'a, T: 'a> AsUniformValue for &'a BufferTexture<T> where [T]: BufferContent
 /// Holds a reference to a `BufferTexture`.
 #[derive(Copy, Clone)]
 pub struct BufferTextureRef<'a> {
+    context: &'a Rc<Context>,
     texture: gl::types::GLuint,
     ty: BufferTextureType,
-    marker: PhantomData<&'a ()>,
 }
 
 impl<'a> BufferTextureRef<'a> {
@@ -543,7 +542,7 @@ impl<'a> TextureExt for BufferTextureRef<'a> {
 
     #[inline]
     fn get_context(&self) -> &Rc<Context> {
-        unimplemented!();       // TODO:
+        self.context
     }
 
     #[inline]
@@ -556,6 +555,27 @@ impl<'a> TextureExt for BufferTextureRef<'a> {
         unsafe { ctxt.gl.BindTexture(gl::TEXTURE_BUFFER, self.texture); }
         gl::TEXTURE_BUFFER
     }
+
+    #[inline]
+    fn prepare_for_texture_fetch(&self, _: &mut CommandContext) {
+        // buffer textures can't be bound to an image unit, so they are never written by shaders
+    }
+
+    #[inline]
+    fn prepare_for_image_unit(&self, _: &mut CommandContext, _: bool) {
+        // image uniforms only accept regular textures (see `UniformValue::Image`), so buffer
+        // textures are never bound to an image unit and there is no shader write to track
+    }
+
+    #[inline]
+    fn prepare_for_texture_update(&self, _: &mut CommandContext) {
+        // buffer textures can't be bound to an image unit, so they are never written by shaders
+    }
+
+    #[inline]
+    fn prepare_for_framebuffer(&self, _: &mut CommandContext) {
+        // buffer textures can't be attached to a framebuffer
+    }
 }
 
 ///
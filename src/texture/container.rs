@@ -0,0 +1,946 @@
+/*!
+Loading textures from KTX, KTX2 and DDS container files.
+
+This module is only available if the `texture_containers` feature is enabled.
+
+Container files store the content of a texture exactly as the GPU expects it, including all the
+mipmap levels, the array layers and the faces of cubemaps. They are usually produced by asset
+pipelines and often contain compressed data.
+
+```no_run
+# let display: glium::Display = unsafe { std::mem::uninitialized() };
+# let data: Vec<u8> = unsafe { std::mem::uninitialized() };
+let container = glium::texture::container::Container::parse(&data).unwrap();
+
+match container.into_texture(&display).unwrap() {
+    glium::texture::container::ContainerTexture::CompressedSrgbTexture2d(tex) => {
+        // ...
+    },
+    _ => panic!("Unexpected texture type")
+}
+```
+
+Only the formats that correspond to one of the floating-point texture types of glium (regular,
+sRGB, compressed and compressed sRGB) are supported. KTX2 files that use supercompression are
+not supported.
+
+*/
+use std::cmp;
+use std::fmt;
+use std::error::Error;
+
+use gl;
+use backend::Facade;
+use context::CommandContext;
+use image_format::{TextureFormat, CompressedFormat, CompressedSrgbFormat};
+use image_format::{UncompressedFloatFormat, SrgbFormat};
+use version::Api;
+use version::Version;
+use buffer::BufferAny;
+use BufferExt;
+use ContextExt;
+use TextureExt;
+use ToGlEnum;
+
+use texture::any::{self, TextureAny};
+use texture::{MipmapsOption, CompressedMipmapsOption, TextureCreationError};
+use texture::{Texture2d, SrgbTexture2d, CompressedTexture2d, CompressedSrgbTexture2d};
+use texture::{Texture2dArray, SrgbTexture2dArray, CompressedTexture2dArray};
+use texture::CompressedSrgbTexture2dArray;
+use texture::{Texture3d, SrgbTexture3d, CompressedTexture3d, CompressedSrgbTexture3d};
+use texture::{Cubemap, SrgbCubemap, CompressedCubemap, CompressedSrgbCubemap};
+use texture::{CubemapArray, SrgbCubemapArray, CompressedCubemapArray};
+use texture::CompressedSrgbCubemapArray;
+
+const KTX_IDENTIFIER: [u8; 12] = [0xAB, 0x4B, 0x54, 0x58, 0x20, 0x31, 0x31, 0xBB,
+                                  0x0D, 0x0A, 0x1A, 0x0A];
+const KTX2_IDENTIFIER: [u8; 12] = [0xAB, 0x4B, 0x54, 0x58, 0x20, 0x32, 0x30, 0xBB,
+                                   0x0D, 0x0A, 0x1A, 0x0A];
+const DDS_MAGIC: [u8; 4] = [0x44, 0x44, 0x53, 0x20];
+
+/// Error that can happen when loading a container file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContainerError {
+    /// The data doesn't start with the identifier of a known container format.
+    UnknownContainer,
+
+    /// The file is truncated or its header is inconsistent.
+    InvalidData,
+
+    /// The format of the texture stored in the file is not supported by this loader.
+    FormatNotSupported,
+
+    /// KTX2 supercompression is not supported.
+    SupercompressionNotSupported,
+
+    /// Error while creating the texture.
+    TextureCreationError(TextureCreationError),
+}
+
+impl From<TextureCreationError> for ContainerError {
+    #[inline]
+    fn from(err: TextureCreationError) -> ContainerError {
+        ContainerError::TextureCreationError(err)
+    }
+}
+
+impl fmt::Display for ContainerError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.description())
+    }
+}
+
+impl Error for ContainerError {
+    fn description(&self) -> &str {
+        use self::ContainerError::*;
+        match *self {
+            UnknownContainer =>
+                "The data is not a KTX, KTX2 or DDS file",
+            InvalidData =>
+                "The file is truncated or its header is inconsistent",
+            FormatNotSupported =>
+                "The format of the texture stored in the file is not supported",
+            SupercompressionNotSupported =>
+                "KTX2 supercompression is not supported",
+            TextureCreationError(_) =>
+                "Error while creating the texture",
+        }
+    }
+
+    #[inline]
+    fn cause(&self) -> Option<&Error> {
+        match *self {
+            ContainerError::TextureCreationError(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+/// Texture created from a container file.
+#[allow(missing_docs)]
+pub enum ContainerTexture {
+    Texture2d(Texture2d),
+    SrgbTexture2d(SrgbTexture2d),
+    CompressedTexture2d(CompressedTexture2d),
+    CompressedSrgbTexture2d(CompressedSrgbTexture2d),
+    Texture2dArray(Texture2dArray),
+    SrgbTexture2dArray(SrgbTexture2dArray),
+    CompressedTexture2dArray(CompressedTexture2dArray),
+    CompressedSrgbTexture2dArray(CompressedSrgbTexture2dArray),
+    Texture3d(Texture3d),
+    SrgbTexture3d(SrgbTexture3d),
+    CompressedTexture3d(CompressedTexture3d),
+    CompressedSrgbTexture3d(CompressedSrgbTexture3d),
+    Cubemap(Cubemap),
+    SrgbCubemap(SrgbCubemap),
+    CompressedCubemap(CompressedCubemap),
+    CompressedSrgbCubemap(CompressedSrgbCubemap),
+    CubemapArray(CubemapArray),
+    SrgbCubemapArray(SrgbCubemapArray),
+    CompressedCubemapArray(CompressedCubemapArray),
+    CompressedSrgbCubemapArray(CompressedSrgbCubemapArray),
+}
+
+impl ::std::ops::Deref for ContainerTexture {
+    type Target = TextureAny;
+
+    fn deref(&self) -> &TextureAny {
+        match *self {
+            ContainerTexture::Texture2d(ref t) => t,
+            ContainerTexture::SrgbTexture2d(ref t) => t,
+            ContainerTexture::CompressedTexture2d(ref t) => t,
+            ContainerTexture::CompressedSrgbTexture2d(ref t) => t,
+            ContainerTexture::Texture2dArray(ref t) => t,
+            ContainerTexture::SrgbTexture2dArray(ref t) => t,
+            ContainerTexture::CompressedTexture2dArray(ref t) => t,
+            ContainerTexture::CompressedSrgbTexture2dArray(ref t) => t,
+            ContainerTexture::Texture3d(ref t) => t,
+            ContainerTexture::SrgbTexture3d(ref t) => t,
+            ContainerTexture::CompressedTexture3d(ref t) => t,
+            ContainerTexture::CompressedSrgbTexture3d(ref t) => t,
+            ContainerTexture::Cubemap(ref t) => t,
+            ContainerTexture::SrgbCubemap(ref t) => t,
+            ContainerTexture::CompressedCubemap(ref t) => t,
+            ContainerTexture::CompressedSrgbCubemap(ref t) => t,
+            ContainerTexture::CubemapArray(ref t) => t,
+            ContainerTexture::SrgbCubemapArray(ref t) => t,
+            ContainerTexture::CompressedCubemapArray(ref t) => t,
+            ContainerTexture::CompressedSrgbCubemapArray(ref t) => t,
+        }
+    }
+}
+
+/// How the pixels of a format are laid out in memory.
+#[derive(Debug, Copy, Clone)]
+enum Layout {
+    /// Blocks of 4x4 pixels.
+    Compressed { block_size: usize },
+    /// Individual pixels that are uploaded with the given format and type.
+    Uncompressed { format: gl::types::GLenum, ty: gl::types::GLenum, pixel_size: usize },
+}
+
+impl Layout {
+    /// Returns the number of bytes of one image of the given dimensions, where each row of
+    /// uncompressed pixels is padded to `alignment` bytes. Returns `None` on overflow.
+    fn get_image_size(&self, width: u32, height: u32, depth: u32, alignment: usize)
+                      -> Option<usize>
+    {
+        let (width, height, depth) = (cmp::max(1, width) as usize, cmp::max(1, height) as usize,
+                                      cmp::max(1, depth) as usize);
+
+        match *self {
+            Layout::Compressed { block_size } => {
+                ((width + 3) / 4).checked_mul((height + 3) / 4)
+                                 .and_then(|s| s.checked_mul(depth))
+                                 .and_then(|s| s.checked_mul(block_size))
+            },
+            Layout::Uncompressed { pixel_size, .. } => {
+                width.checked_mul(pixel_size)
+                     .and_then(|row| row.checked_add(alignment - 1))
+                     .map(|row| row / alignment * alignment)
+                     .and_then(|row| row.checked_mul(height))
+                     .and_then(|s| s.checked_mul(depth))
+            },
+        }
+    }
+}
+
+/// Returns the size of a dimension of the texture at the given mipmap level.
+#[inline]
+fn level_dimension(dimension: u32, level: u32) -> u32 {
+    cmp::max(1, dimension.checked_shr(level).unwrap_or(0))
+}
+
+/// Checks that the number of mipmap levels in the header is possible for the dimensions of
+/// the texture, so that it can't be used to trigger huge allocations.
+fn check_num_levels(num_levels: u32, width: u32, height: u32, depth: Option<u32>)
+                    -> Result<(), ContainerError>
+{
+    let max_dimension = cmp::max(cmp::max(width, height), depth.unwrap_or(1));
+    let max_levels = 32 - cmp::max(1, max_dimension).leading_zeros();
+
+    if num_levels > max_levels {
+        return Err(ContainerError::InvalidData);
+    }
+
+    Ok(())
+}
+
+/// The content of a KTX, KTX2 or DDS file.
+#[derive(Debug, Clone)]
+pub struct Container {
+    format: TextureFormat,
+    layout: Layout,
+    unpack_alignment: gl::types::GLint,
+    width: u32,
+    height: u32,
+    depth: Option<u32>,
+    array_size: Option<u32>,
+    cubemap: bool,
+    /// Data of each mipmap level, containing all the layers and faces in the order expected
+    /// by OpenGL.
+    levels: Vec<Vec<u8>>,
+}
+
+impl Container {
+    /// Parses a KTX, KTX2 or DDS file. The type of container is determined from the first bytes.
+    pub fn parse(data: &[u8]) -> Result<Container, ContainerError> {
+        if data.len() >= 12 && data[0 .. 12] == KTX_IDENTIFIER {
+            parse_ktx(data)
+        } else if data.len() >= 12 && data[0 .. 12] == KTX2_IDENTIFIER {
+            parse_ktx2(data)
+        } else if data.len() >= 4 && data[0 .. 4] == DDS_MAGIC {
+            parse_dds(data)
+        } else {
+            Err(ContainerError::UnknownContainer)
+        }
+    }
+
+    /// Returns the format of the texture.
+    #[inline]
+    pub fn get_format(&self) -> TextureFormat {
+        self.format
+    }
+
+    /// Returns the width of the texture.
+    #[inline]
+    pub fn get_width(&self) -> u32 {
+        self.width
+    }
+
+    /// Returns the height of the texture.
+    #[inline]
+    pub fn get_height(&self) -> u32 {
+        self.height
+    }
+
+    /// Returns the depth of the texture, or `None` if it's not a 3D texture.
+    #[inline]
+    pub fn get_depth(&self) -> Option<u32> {
+        self.depth
+    }
+
+    /// Returns the number of layers of the texture, or `None` if it's not an array texture.
+    #[inline]
+    pub fn get_array_size(&self) -> Option<u32> {
+        self.array_size
+    }
+
+    /// Returns true if the texture is a cubemap or an array of cubemaps.
+    #[inline]
+    pub fn is_cubemap(&self) -> bool {
+        self.cubemap
+    }
+
+    /// Returns the number of mipmap levels stored in the file, including the main level.
+    #[inline]
+    pub fn get_mipmap_levels(&self) -> u32 {
+        self.levels.len() as u32
+    }
+
+    /// Returns the number of bytes of a single layer or face of the given mipmap level.
+    fn get_image_size(&self, level: u32) -> Option<usize> {
+        self.layout.get_image_size(level_dimension(self.width, level),
+                                   level_dimension(self.height, level),
+                                   self.depth.map(|d| level_dimension(d, level)).unwrap_or(1),
+                                   self.unpack_alignment as usize)
+    }
+
+    /// Returns the number of bytes of the given mipmap level, including all the layers and faces.
+    fn get_level_size(&self, level: u32) -> Option<usize> {
+        let images = self.array_size.unwrap_or(1) as usize * if self.cubemap { 6 } else { 1 };
+        self.get_image_size(level).and_then(|size| size.checked_mul(images))
+    }
+
+    /// Checks that the data of each mipmap level has exactly the size that OpenGL is going to
+    /// read from it.
+    fn check_levels(self) -> Result<Container, ContainerError> {
+        try!(check_num_levels(self.levels.len() as u32, self.width, self.height, self.depth));
+
+        for (level, data) in self.levels.iter().enumerate() {
+            if self.cubemap && (data.len() == 0 || data.len() % 6 != 0) {
+                return Err(ContainerError::InvalidData);
+            }
+
+            if Some(data.len()) != self.get_level_size(level as u32) {
+                return Err(ContainerError::InvalidData);
+            }
+        }
+
+        Ok(self)
+    }
+
+    /// Creates a texture of the right type and uploads all the levels, layers and faces to it.
+    pub fn into_texture<F: ?Sized>(self, facade: &F) -> Result<ContainerTexture, ContainerError>
+                                   where F: Facade
+    {
+        let max_levels = MipmapsOption::EmptyMipmaps.num_levels(self.width, Some(self.height),
+                                                                self.depth);
+        if self.levels.len() as u32 > max_levels {
+            return Err(ContainerError::InvalidData);
+        }
+
+        let max = self.levels.len() as u32 - 1;
+        let mipmaps = if max == 0 { MipmapsOption::NoMipmap }
+                      else { MipmapsOption::EmptyMipmapsMax(max) };
+        let compressed_mipmaps = if max == 0 { CompressedMipmapsOption::NoMipmap }
+                                 else { CompressedMipmapsOption::EmptyMipmapsMax(max) };
+
+        let (w, h) = (self.width, self.height);
+
+        macro_rules! create {
+            ($variant:ident, $format:expr, $mipmaps:expr $(, $dim:expr)*) => (
+                ContainerTexture::$variant(try!($variant::empty_with_format(facade, $format,
+                                                                            $mipmaps $(, $dim)*)))
+            );
+        }
+
+        let texture = match (self.format, self.cubemap, self.depth, self.array_size) {
+            (TextureFormat::UncompressedFloat(f), false, None, None) =>
+                create!(Texture2d, f, mipmaps, w, h),
+            (TextureFormat::Srgb(f), false, None, None) =>
+                create!(SrgbTexture2d, f, mipmaps, w, h),
+            (TextureFormat::CompressedFormat(f), false, None, None) =>
+                create!(CompressedTexture2d, f, compressed_mipmaps, w, h),
+            (TextureFormat::CompressedSrgbFormat(f), false, None, None) =>
+                create!(CompressedSrgbTexture2d, f, compressed_mipmaps, w, h),
+
+            (TextureFormat::UncompressedFloat(f), false, None, Some(n)) =>
+                create!(Texture2dArray, f, mipmaps, w, h, n),
+            (TextureFormat::Srgb(f), false, None, Some(n)) =>
+                create!(SrgbTexture2dArray, f, mipmaps, w, h, n),
+            (TextureFormat::CompressedFormat(f), false, None, Some(n)) =>
+                create!(CompressedTexture2dArray, f, compressed_mipmaps, w, h, n),
+            (TextureFormat::CompressedSrgbFormat(f), false, None, Some(n)) =>
+                create!(CompressedSrgbTexture2dArray, f, compressed_mipmaps, w, h, n),
+
+            (TextureFormat::UncompressedFloat(f), false, Some(d), None) =>
+                create!(Texture3d, f, mipmaps, w, h, d),
+            (TextureFormat::Srgb(f), false, Some(d), None) =>
+                create!(SrgbTexture3d, f, mipmaps, w, h, d),
+            (TextureFormat::CompressedFormat(f), false, Some(d), None) =>
+                create!(CompressedTexture3d, f, compressed_mipmaps, w, h, d),
+            (TextureFormat::CompressedSrgbFormat(f), false, Some(d), None) =>
+                create!(CompressedSrgbTexture3d, f, compressed_mipmaps, w, h, d),
+
+            (TextureFormat::UncompressedFloat(f), true, None, None) =>
+                create!(Cubemap, f, mipmaps, w),
+            (TextureFormat::Srgb(f), true, None, None) =>
+                create!(SrgbCubemap, f, mipmaps, w),
+            (TextureFormat::CompressedFormat(f), true, None, None) =>
+                create!(CompressedCubemap, f, compressed_mipmaps, w),
+            (TextureFormat::CompressedSrgbFormat(f), true, None, None) =>
+                create!(CompressedSrgbCubemap, f, compressed_mipmaps, w),
+
+            (TextureFormat::UncompressedFloat(f), true, None, Some(n)) =>
+                create!(CubemapArray, f, mipmaps, w, n),
+            (TextureFormat::Srgb(f), true, None, Some(n)) =>
+                create!(SrgbCubemapArray, f, mipmaps, w, n),
+            (TextureFormat::CompressedFormat(f), true, None, Some(n)) =>
+                create!(CompressedCubemapArray, f, compressed_mipmaps, w, n),
+            (TextureFormat::CompressedSrgbFormat(f), true, None, Some(n)) =>
+                create!(CompressedSrgbCubemapArray, f, compressed_mipmaps, w, n),
+
+            _ => return Err(ContainerError::FormatNotSupported),
+        };
+
+        {
+            let mut ctxt = facade.get_context().make_current();
+            unsafe { self.upload(&mut ctxt, &texture); }
+        }
+
+        Ok(texture)
+    }
+
+    /// Uploads the content of each mipmap level to the texture.
+    unsafe fn upload(&self, ctxt: &mut CommandContext, texture: &TextureAny) {
+        if ctxt.state.pixel_store_unpack_alignment != self.unpack_alignment {
+            ctxt.state.pixel_store_unpack_alignment = self.unpack_alignment;
+            ctxt.gl.PixelStorei(gl::UNPACK_ALIGNMENT, self.unpack_alignment);
+        }
+
+        any::set_unpack_region(ctxt, 0, 0, 0);
+        BufferAny::unbind_pixel_unpack(ctxt);
+        let bind_point = texture.bind_to_current(ctxt);
+
+        // textures created with `glTexStorage*` can't be respecified with `glTexImage*`
+        let immutable = if ctxt.version >= &Version(Api::Gl, 4, 2) ||
+                           ctxt.version >= &Version(Api::GlEs, 3, 0) ||
+                           ctxt.extensions.gl_arb_texture_storage
+        {
+            let mut value = 0;
+            ctxt.gl.GetTexParameteriv(bind_point, gl::TEXTURE_IMMUTABLE_FORMAT, &mut value);
+            value != 0
+        } else {
+            false
+        };
+
+        let internal_format = self.format.to_glenum();
+
+        for (level, data) in self.levels.iter().enumerate() {
+            let width = level_dimension(self.width, level as u32);
+            let height = level_dimension(self.height, level as u32);
+
+            match bind_point {
+                gl::TEXTURE_2D => {
+                    upload_2d(ctxt, gl::TEXTURE_2D, level, width, height, data, internal_format,
+                              self.layout, immutable);
+                },
+                gl::TEXTURE_CUBE_MAP => {
+                    let face_size = data.len() / 6;
+                    for (face, data) in data.chunks(face_size).enumerate() {
+                        let target = gl::TEXTURE_CUBE_MAP_POSITIVE_X + face as gl::types::GLenum;
+                        upload_2d(ctxt, target, level, width, height, data, internal_format,
+                                  self.layout, immutable);
+                    }
+                },
+                _ => {
+                    let depth = match bind_point {
+                        gl::TEXTURE_3D => level_dimension(self.depth.unwrap(), level as u32),
+                        gl::TEXTURE_CUBE_MAP_ARRAY => self.array_size.unwrap() * 6,
+                        _ => self.array_size.unwrap(),
+                    };
+
+                    upload_3d(ctxt, bind_point, level, width, height, depth, data,
+                              internal_format, self.layout, immutable);
+                },
+            }
+        }
+    }
+}
+
+unsafe fn upload_2d(ctxt: &mut CommandContext, target: gl::types::GLenum, level: usize,
+                    width: u32, height: u32, data: &[u8], internal_format: gl::types::GLenum,
+                    layout: Layout, immutable: bool)
+{
+    let level = level as gl::types::GLint;
+    let width = width as gl::types::GLsizei;
+    let height = height as gl::types::GLsizei;
+
+    match (layout, immutable) {
+        (Layout::Compressed { .. }, true) => {
+            ctxt.gl.CompressedTexSubImage2D(target, level, 0, 0, width, height, internal_format,
+                                            data.len() as gl::types::GLsizei,
+                                            data.as_ptr() as *const _);
+        },
+        (Layout::Compressed { .. }, false) => {
+            ctxt.gl.CompressedTexImage2D(target, level, internal_format, width, height, 0,
+                                         data.len() as gl::types::GLsizei,
+                                         data.as_ptr() as *const _);
+        },
+        (Layout::Uncompressed { format, ty, .. }, true) => {
+            ctxt.gl.TexSubImage2D(target, level, 0, 0, width, height, format, ty,
+                                  data.as_ptr() as *const _);
+        },
+        (Layout::Uncompressed { format, ty, .. }, false) => {
+            ctxt.gl.TexImage2D(target, level, internal_format as gl::types::GLint, width, height,
+                               0, format, ty, data.as_ptr() as *const _);
+        },
+    }
+}
+
+unsafe fn upload_3d(ctxt: &mut CommandContext, target: gl::types::GLenum, level: usize,
+                    width: u32, height: u32, depth: u32, data: &[u8],
+                    internal_format: gl::types::GLenum, layout: Layout, immutable: bool)
+{
+    let level = level as gl::types::GLint;
+    let width = width as gl::types::GLsizei;
+    let height = height as gl::types::GLsizei;
+    let depth = depth as gl::types::GLsizei;
+
+    match (layout, immutable) {
+        (Layout::Compressed { .. }, true) => {
+            ctxt.gl.CompressedTexSubImage3D(target, level, 0, 0, 0, width, height, depth,
+                                            internal_format, data.len() as gl::types::GLsizei,
+                                            data.as_ptr() as *const _);
+        },
+        (Layout::Compressed { .. }, false) => {
+            ctxt.gl.CompressedTexImage3D(target, level, internal_format, width, height, depth,
+                                         0, data.len() as gl::types::GLsizei,
+                                         data.as_ptr() as *const _);
+        },
+        (Layout::Uncompressed { format, ty, .. }, true) => {
+            ctxt.gl.TexSubImage3D(target, level, 0, 0, 0, width, height, depth, format, ty,
+                                  data.as_ptr() as *const _);
+        },
+        (Layout::Uncompressed { format, ty, .. }, false) => {
+            ctxt.gl.TexImage3D(target, level, internal_format as gl::types::GLint, width, height,
+                               depth, 0, format, ty, data.as_ptr() as *const _);
+        },
+    }
+}
+
+#[inline]
+fn read_u32(data: &[u8], offset: usize) -> Result<u32, ContainerError> {
+    let data = try!(read_slice(data, offset, 4));
+    Ok((data[0] as u32) | ((data[1] as u32) << 8) | ((data[2] as u32) << 16) |
+       ((data[3] as u32) << 24))
+}
+
+#[inline]
+fn read_u64(data: &[u8], offset: usize) -> Result<u64, ContainerError> {
+    let low = try!(read_u32(data, offset)) as u64;
+    let high = try!(read_u32(data, try!(offset.checked_add(4).ok_or(ContainerError::InvalidData))))
+                   as u64;
+    Ok(low | (high << 32))
+}
+
+#[inline]
+fn read_slice(data: &[u8], offset: usize, len: usize) -> Result<&[u8], ContainerError> {
+    match offset.checked_add(len) {
+        Some(end) if end <= data.len() => Ok(&data[offset .. end]),
+        _ => Err(ContainerError::InvalidData),
+    }
+}
+
+/// Returns the size of a pixel for an uncompressed format/type pair, or `None` if unknown.
+fn get_pixel_size(format: gl::types::GLenum, ty: gl::types::GLenum) -> Option<usize> {
+    let components = match format {
+        gl::RED | gl::RED_INTEGER => 1,
+        gl::RG | gl::RG_INTEGER => 2,
+        gl::RGB | gl::BGR | gl::RGB_INTEGER => 3,
+        gl::RGBA | gl::BGRA | gl::RGBA_INTEGER => 4,
+        _ => return None,
+    };
+
+    match ty {
+        gl::UNSIGNED_BYTE | gl::BYTE => Some(components),
+        gl::UNSIGNED_SHORT | gl::SHORT | gl::HALF_FLOAT => Some(components * 2),
+        gl::UNSIGNED_INT | gl::INT | gl::FLOAT => Some(components * 4),
+        gl::UNSIGNED_BYTE_3_3_2 => Some(1),
+        gl::UNSIGNED_SHORT_5_6_5 | gl::UNSIGNED_SHORT_4_4_4_4 |
+        gl::UNSIGNED_SHORT_5_5_5_1 => Some(2),
+        gl::UNSIGNED_INT_10_10_10_2 | gl::UNSIGNED_INT_2_10_10_10_REV => Some(4),
+        _ => None,
+    }
+}
+
+/// Returns the size of a 4x4 block of a compressed format.
+fn get_block_size(format: TextureFormat) -> Option<usize> {
+    match format {
+        TextureFormat::CompressedFormat(CompressedFormat::RgtcFormatU) |
+        TextureFormat::CompressedFormat(CompressedFormat::RgtcFormatI) |
+        TextureFormat::CompressedFormat(CompressedFormat::S3tcDxt1NoAlpha) |
+        TextureFormat::CompressedFormat(CompressedFormat::S3tcDxt1Alpha) |
+        TextureFormat::CompressedSrgbFormat(CompressedSrgbFormat::S3tcDxt1NoAlpha) |
+        TextureFormat::CompressedSrgbFormat(CompressedSrgbFormat::S3tcDxt1Alpha) => Some(8),
+        TextureFormat::CompressedFormat(_) | TextureFormat::CompressedSrgbFormat(_) => Some(16),
+        _ => None,
+    }
+}
+
+/// Finds the `TextureFormat` whose internal format is `internal_format`.
+fn texture_format_from_glenum(internal_format: gl::types::GLenum) -> Option<TextureFormat> {
+    TextureFormat::get_formats_list().into_iter().find(|f| f.to_glenum() == internal_format)
+}
+
+/// Determines the dimensions of the texture from the header values of a KTX or KTX2 file.
+fn ktx_dimensions(width: u32, height: u32, depth: u32, layers: u32, faces: u32)
+                  -> Result<(u32, Option<u32>, Option<u32>, bool), ContainerError>
+{
+    if width == 0 || (faces != 1 && faces != 6) || (depth != 0 && (layers != 0 || faces != 1)) {
+        return Err(ContainerError::InvalidData);
+    }
+
+    Ok((cmp::max(1, height),
+        if depth != 0 { Some(depth) } else { None },
+        if layers != 0 { Some(layers) } else { None },
+        faces == 6))
+}
+
+#[inline]
+fn align4(value: usize) -> usize {
+    (value + 3) & !3
+}
+
+fn parse_ktx(data: &[u8]) -> Result<Container, ContainerError> {
+    if try!(read_u32(data, 12)) != 0x04030201 {
+        // big-endian files are not supported
+        return Err(ContainerError::FormatNotSupported);
+    }
+
+    let gl_type = try!(read_u32(data, 16));
+    let gl_format = try!(read_u32(data, 24));
+    let gl_internal_format = try!(read_u32(data, 28));
+    let width = try!(read_u32(data, 36));
+    let height = try!(read_u32(data, 40));
+    let depth = try!(read_u32(data, 44));
+    let layers = try!(read_u32(data, 48));
+    let faces = try!(read_u32(data, 52));
+    let num_levels = cmp::max(1, try!(read_u32(data, 56)));
+    let key_value_bytes = try!(read_u32(data, 60)) as usize;
+
+    let format = try!(texture_format_from_glenum(gl_internal_format)
+                                        .ok_or(ContainerError::FormatNotSupported));
+    let layout = if gl_type == 0 {
+        Layout::Compressed {
+            block_size: try!(get_block_size(format).ok_or(ContainerError::FormatNotSupported)),
+        }
+    } else {
+        Layout::Uncompressed {
+            format: gl_format,
+            ty: gl_type,
+            pixel_size: try!(get_pixel_size(gl_format, gl_type)
+                                        .ok_or(ContainerError::FormatNotSupported)),
+        }
+    };
+
+    let (height, depth, array_size, cubemap) =
+                        try!(ktx_dimensions(width, height, depth, layers, faces));
+    try!(check_num_levels(num_levels, width, height, depth));
+
+    let mut container = Container {
+        format: format,
+        layout: layout,
+        unpack_alignment: 4,
+        width: width,
+        height: height,
+        depth: depth,
+        array_size: array_size,
+        cubemap: cubemap,
+        levels: Vec::with_capacity(num_levels as usize),
+    };
+
+    let mut offset = try!(64usize.checked_add(key_value_bytes).ok_or(ContainerError::InvalidData));
+
+    for level in 0 .. num_levels {
+        let image_size = try!(read_u32(data, offset)) as usize;
+        offset += 4;
+
+        if cubemap && array_size.is_none() {
+            // for non-array cubemaps, `image_size` is the size of a single face and each
+            // face is padded to four bytes
+            if Some(image_size) != container.get_image_size(level) {
+                return Err(ContainerError::InvalidData);
+            }
+
+            let mut data_level = Vec::with_capacity(image_size * 6);
+            for _ in 0 .. 6 {
+                data_level.extend_from_slice(try!(read_slice(data, offset, image_size)));
+                offset = align4(offset + image_size);
+            }
+            container.levels.push(data_level);
+
+        } else {
+            if Some(image_size) != container.get_level_size(level) {
+                return Err(ContainerError::InvalidData);
+            }
+
+            container.levels.push(try!(read_slice(data, offset, image_size)).to_vec());
+            offset = align4(offset + image_size);
+        }
+    }
+
+    container.check_levels()
+}
+
+/// Turns a `VkFormat` into a texture format and a layout.
+fn vk_format_to_format(vk_format: u32) -> Option<(TextureFormat, Layout)> {
+    let uncompressed = |format: TextureFormat, gl_format, ty, pixel_size| {
+        Some((format, Layout::Uncompressed { format: gl_format, ty: ty, pixel_size: pixel_size }))
+    };
+
+    let compressed = |format: TextureFormat| {
+        get_block_size(format).map(|block_size| (format, Layout::Compressed { block_size: block_size }))
+    };
+
+    match vk_format {
+        9 => uncompressed(UncompressedFloatFormat::U8.to_texture_format(),
+                          gl::RED, gl::UNSIGNED_BYTE, 1),
+        16 => uncompressed(UncompressedFloatFormat::U8U8.to_texture_format(),
+                           gl::RG, gl::UNSIGNED_BYTE, 2),
+        37 => uncompressed(UncompressedFloatFormat::U8U8U8U8.to_texture_format(),
+                           gl::RGBA, gl::UNSIGNED_BYTE, 4),
+        43 => uncompressed(SrgbFormat::U8U8U8U8.to_texture_format(),
+                           gl::RGBA, gl::UNSIGNED_BYTE, 4),
+        44 => uncompressed(UncompressedFloatFormat::U8U8U8U8.to_texture_format(),
+                           gl::BGRA, gl::UNSIGNED_BYTE, 4),
+        50 => uncompressed(SrgbFormat::U8U8U8U8.to_texture_format(),
+                           gl::BGRA, gl::UNSIGNED_BYTE, 4),
+        97 => uncompressed(UncompressedFloatFormat::F16F16F16F16.to_texture_format(),
+                           gl::RGBA, gl::HALF_FLOAT, 8),
+        109 => uncompressed(UncompressedFloatFormat::F32F32F32F32.to_texture_format(),
+                            gl::RGBA, gl::FLOAT, 16),
+        131 => compressed(CompressedFormat::S3tcDxt1NoAlpha.to_texture_format()),
+        132 => compressed(CompressedSrgbFormat::S3tcDxt1NoAlpha.to_texture_format()),
+        133 => compressed(CompressedFormat::S3tcDxt1Alpha.to_texture_format()),
+        134 => compressed(CompressedSrgbFormat::S3tcDxt1Alpha.to_texture_format()),
+        135 => compressed(CompressedFormat::S3tcDxt3Alpha.to_texture_format()),
+        136 => compressed(CompressedSrgbFormat::S3tcDxt3Alpha.to_texture_format()),
+        137 => compressed(CompressedFormat::S3tcDxt5Alpha.to_texture_format()),
+        138 => compressed(CompressedSrgbFormat::S3tcDxt5Alpha.to_texture_format()),
+        139 => compressed(CompressedFormat::RgtcFormatU.to_texture_format()),
+        140 => compressed(CompressedFormat::RgtcFormatI.to_texture_format()),
+        141 => compressed(CompressedFormat::RgtcFormatUU.to_texture_format()),
+        142 => compressed(CompressedFormat::RgtcFormatII.to_texture_format()),
+        143 => compressed(CompressedFormat::BptcUnsignedFloat3.to_texture_format()),
+        144 => compressed(CompressedFormat::BptcSignedFloat3.to_texture_format()),
+        145 => compressed(CompressedFormat::BptcUnorm4.to_texture_format()),
+        146 => compressed(CompressedSrgbFormat::Bptc.to_texture_format()),
+        _ => None,
+    }
+}
+
+fn parse_ktx2(data: &[u8]) -> Result<Container, ContainerError> {
+    let vk_format = try!(read_u32(data, 12));
+    let width = try!(read_u32(data, 20));
+    let height = try!(read_u32(data, 24));
+    let depth = try!(read_u32(data, 28));
+    let layers = try!(read_u32(data, 32));
+    let faces = try!(read_u32(data, 36));
+    let num_levels = cmp::max(1, try!(read_u32(data, 40)));
+    let supercompression = try!(read_u32(data, 44));
+
+    if supercompression != 0 {
+        return Err(ContainerError::SupercompressionNotSupported);
+    }
+
+    let (format, layout) = try!(vk_format_to_format(vk_format)
+                                        .ok_or(ContainerError::FormatNotSupported));
+    let (height, depth, array_size, cubemap) =
+                        try!(ktx_dimensions(width, height, depth, layers, faces));
+    try!(check_num_levels(num_levels, width, height, depth));
+
+    // the level index starts after the header (48 bytes) and the index (32 bytes)
+    let mut levels = Vec::with_capacity(num_levels as usize);
+    for level in 0 .. num_levels as usize {
+        let entry = 80 + level * 24;
+        let offset = try!(read_u64(data, entry));
+        let len = try!(read_u64(data, entry + 8));
+        if offset > data.len() as u64 || len > data.len() as u64 {
+            return Err(ContainerError::InvalidData);
+        }
+        levels.push(try!(read_slice(data, offset as usize, len as usize)).to_vec());
+    }
+
+    Container {
+        format: format,
+        layout: layout,
+        unpack_alignment: 1,
+        width: width,
+        height: height,
+        depth: depth,
+        array_size: array_size,
+        cubemap: cubemap,
+        levels: levels,
+    }.check_levels()
+}
+
+/// Turns a `DXGI_FORMAT` into a texture format and a layout.
+fn dxgi_format_to_format(dxgi_format: u32) -> Option<(TextureFormat, Layout)> {
+    let uncompressed = |format: TextureFormat, gl_format, ty, pixel_size| {
+        Some((format, Layout::Uncompressed { format: gl_format, ty: ty, pixel_size: pixel_size }))
+    };
+
+    let compressed = |format: TextureFormat| {
+        get_block_size(format).map(|block_size| (format, Layout::Compressed { block_size: block_size }))
+    };
+
+    match dxgi_format {
+        2 => uncompressed(UncompressedFloatFormat::F32F32F32F32.to_texture_format(),
+                          gl::RGBA, gl::FLOAT, 16),
+        10 => uncompressed(UncompressedFloatFormat::F16F16F16F16.to_texture_format(),
+                           gl::RGBA, gl::HALF_FLOAT, 8),
+        28 => uncompressed(UncompressedFloatFormat::U8U8U8U8.to_texture_format(),
+                           gl::RGBA, gl::UNSIGNED_BYTE, 4),
+        29 => uncompressed(SrgbFormat::U8U8U8U8.to_texture_format(),
+                           gl::RGBA, gl::UNSIGNED_BYTE, 4),
+        49 => uncompressed(UncompressedFloatFormat::U8U8.to_texture_format(),
+                           gl::RG, gl::UNSIGNED_BYTE, 2),
+        61 => uncompressed(UncompressedFloatFormat::U8.to_texture_format(),
+                           gl::RED, gl::UNSIGNED_BYTE, 1),
+        87 => uncompressed(UncompressedFloatFormat::U8U8U8U8.to_texture_format(),
+                           gl::BGRA, gl::UNSIGNED_BYTE, 4),
+        91 => uncompressed(SrgbFormat::U8U8U8U8.to_texture_format(),
+                           gl::BGRA, gl::UNSIGNED_BYTE, 4),
+        71 => compressed(CompressedFormat::S3tcDxt1Alpha.to_texture_format()),
+        72 => compressed(CompressedSrgbFormat::S3tcDxt1Alpha.to_texture_format()),
+        74 => compressed(CompressedFormat::S3tcDxt3Alpha.to_texture_format()),
+        75 => compressed(CompressedSrgbFormat::S3tcDxt3Alpha.to_texture_format()),
+        77 => compressed(CompressedFormat::S3tcDxt5Alpha.to_texture_format()),
+        78 => compressed(CompressedSrgbFormat::S3tcDxt5Alpha.to_texture_format()),
+        80 => compressed(CompressedFormat::RgtcFormatU.to_texture_format()),
+        81 => compressed(CompressedFormat::RgtcFormatI.to_texture_format()),
+        83 => compressed(CompressedFormat::RgtcFormatUU.to_texture_format()),
+        84 => compressed(CompressedFormat::RgtcFormatII.to_texture_format()),
+        95 => compressed(CompressedFormat::BptcUnsignedFloat3.to_texture_format()),
+        96 => compressed(CompressedFormat::BptcSignedFloat3.to_texture_format()),
+        98 => compressed(CompressedFormat::BptcUnorm4.to_texture_format()),
+        99 => compressed(CompressedSrgbFormat::Bptc.to_texture_format()),
+        _ => None,
+    }
+}
+
+/// Determines the format of a DDS file that doesn't have a DX10 header.
+fn dds_legacy_format(data: &[u8]) -> Result<(TextureFormat, Layout), ContainerError> {
+    const DDPF_ALPHAPIXELS: u32 = 0x1;
+    const DDPF_FOURCC: u32 = 0x4;
+    const DDPF_RGB: u32 = 0x40;
+
+    let flags = try!(read_u32(data, 80));
+    let four_cc = {
+        let four_cc = try!(read_slice(data, 84, 4));
+        [four_cc[0], four_cc[1], four_cc[2], four_cc[3]]
+    };
+    let bit_count = try!(read_u32(data, 88));
+    let r_mask = try!(read_u32(data, 92));
+    let b_mask = try!(read_u32(data, 100));
+
+    let result = if flags & DDPF_FOURCC != 0 {
+        match &four_cc {
+            b"DXT1" if flags & DDPF_ALPHAPIXELS != 0 => dxgi_format_to_format(71),
+            b"DXT1" => {
+                let format = CompressedFormat::S3tcDxt1NoAlpha.to_texture_format();
+                Some((format, Layout::Compressed { block_size: 8 }))
+            },
+            b"DXT3" => dxgi_format_to_format(74),
+            b"DXT5" => dxgi_format_to_format(77),
+            b"ATI1" | b"BC4U" => dxgi_format_to_format(80),
+            b"BC4S" => dxgi_format_to_format(81),
+            b"ATI2" | b"BC5U" => dxgi_format_to_format(83),
+            b"BC5S" => dxgi_format_to_format(84),
+            // D3DFMT_A16B16G16R16F and D3DFMT_A32B32G32R32F
+            _ => match try!(read_u32(data, 84)) {
+                113 => dxgi_format_to_format(10),
+                116 => dxgi_format_to_format(2),
+                _ => None,
+            },
+        }
+
+    } else if flags & DDPF_RGB != 0 && bit_count == 32 {
+        match (r_mask, b_mask) {
+            (0x000000ff, 0x00ff0000) => dxgi_format_to_format(28),
+            (0x00ff0000, 0x000000ff) => dxgi_format_to_format(87),
+            _ => None,
+        }
+
+    } else {
+        None
+    };
+
+    result.ok_or(ContainerError::FormatNotSupported)
+}
+
+fn parse_dds(data: &[u8]) -> Result<Container, ContainerError> {
+    const DDSCAPS2_CUBEMAP: u32 = 0x200;
+    const DDSCAPS2_VOLUME: u32 = 0x200000;
+    const DDS_RESOURCE_MISC_TEXTURECUBE: u32 = 0x4;
+
+    if try!(read_u32(data, 4)) != 124 {
+        return Err(ContainerError::InvalidData);
+    }
+
+    let height = cmp::max(1, try!(read_u32(data, 12)));
+    let width = try!(read_u32(data, 16));
+    let depth = try!(read_u32(data, 24));
+    let num_levels = cmp::max(1, try!(read_u32(data, 28)));
+    let caps2 = try!(read_u32(data, 112));
+
+    if width == 0 {
+        return Err(ContainerError::InvalidData);
+    }
+
+    let is_dx10 = try!(read_slice(data, 84, 4)) == b"DX10";
+
+    let (format, layout, layers, cubemap, offset) = if is_dx10 {
+        let (format, layout) = try!(dxgi_format_to_format(try!(read_u32(data, 128)))
+                                            .ok_or(ContainerError::FormatNotSupported));
+        let misc_flag = try!(read_u32(data, 136));
+        let array_size = cmp::max(1, try!(read_u32(data, 140)));
+        (format, layout, array_size, misc_flag & DDS_RESOURCE_MISC_TEXTURECUBE != 0, 148)
+
+    } else {
+        let (format, layout) = try!(dds_legacy_format(data));
+        (format, layout, 1, caps2 & DDSCAPS2_CUBEMAP != 0, 128)
+    };
+
+    let depth = if caps2 & DDSCAPS2_VOLUME != 0 && depth >= 1 { Some(depth) } else { None };
+    let faces = if cubemap { 6 } else { 1 };
+    try!(check_num_levels(num_levels, width, height, depth));
+
+    let mut container = Container {
+        format: format,
+        layout: layout,
+        unpack_alignment: 1,
+        width: width,
+        height: height,
+        depth: depth,
+        array_size: if layers > 1 { Some(layers) } else { None },
+        cubemap: cubemap,
+        levels: vec![Vec::new(); num_levels as usize],
+    };
+
+    // DDS files store each face/layer with all its mipmaps, while we want each mipmap with all
+    // its faces/layers
+    let mut offset = offset;
+    for _ in 0 .. layers as u64 * faces {
+        for level in 0 .. num_levels {
+            let size = try!(container.get_image_size(level).ok_or(ContainerError::InvalidData));
+            let image = try!(read_slice(data, offset, size));
+            container.levels[level as usize].extend_from_slice(image);
+            offset += size;
+        }
+    }
+
+    container.check_levels()
+}
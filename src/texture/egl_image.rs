@@ -0,0 +1,485 @@
+/*!
+Importing and exporting `EGLImage`s.
+
+An `EGLImage` is a handle to an image that can be shared between APIs and processes. Camera
+drivers and hardware video decoders can often export their frames as `EGLImage`s (for example
+from a DMA-BUF file descriptor with `EGL_EXT_image_dma_buf_import`), which allows displaying them
+without copying the data through the CPU.
+
+There are two ways to import an `EGLImage`:
+
+ - `import_egl_image` creates a regular `Texture2d`, which requires `GL_OES_EGL_image`. The
+   image must be in a format that a texture can have.
+ - `import_egl_image_external` creates an `ExternalTexture`, which requires
+   `GL_OES_EGL_image_external`. This works with any format that the driver can sample from,
+   including the YUV formats of video decoders, but the texture can only be used through a
+   `samplerExternalOES` uniform.
+
+These extensions are only available on OpenGL ES.
+
+```no_run
+# let display: glium::Display = unsafe { std::mem::uninitialized() };
+# let egl_image: glium::texture::egl_image::EglImage = std::ptr::null();
+use glium::texture::UncompressedFloatFormat;
+
+let texture = unsafe {
+    glium::texture::egl_image::import_egl_image(&display, egl_image,
+                                                UncompressedFloatFormat::U8U8U8U8, 1920, 1080)
+                                                .unwrap()
+};
+```
+
+## Exporting textures
+
+`export_egl_image` creates an `EGLImage` from a `Texture2d` with `EGL_KHR_gl_texture_2D_image`.
+The image can then be exported as DMA-BUF file descriptors with
+`ExportedEglImage::export_dma_buf`, which requires `EGL_MESA_image_dma_buf_export`, and passed
+to another process or API.
+
+Glium doesn't have direct access to EGL. The EGL functions are loaded through the
+`get_proc_address` function of the backend, which only works if the backend uses EGL and if
+EGL 1.5 or `EGL_KHR_get_all_proc_addresses` is available.
+
+*/
+use std::fmt;
+use std::mem;
+use std::ptr;
+use std::error::Error;
+use std::os::raw::{c_int, c_void};
+use std::rc::Rc;
+
+use gl;
+use backend::Facade;
+use context::{CommandContext, Context};
+use image_format::UncompressedFloatFormat;
+use texture::{Dimensions, MipmapsOption, Texture2d};
+use uniforms::{AsUniformValue, Sampler, UniformValue};
+use CapabilitiesSource;
+use ContextExt;
+use GlObject;
+use TextureExt;
+
+/// Raw handle to an `EGLImage`.
+pub type EglImage = *const c_void;
+
+type EglDisplay = *const c_void;
+type EglContext = *const c_void;
+
+const EGL_NONE: i32 = 0x3038;
+const EGL_GL_TEXTURE_2D_KHR: u32 = 0x30B1;
+const EGL_GL_TEXTURE_LEVEL_KHR: i32 = 0x30BC;
+
+/// Error that can happen when importing or exporting an `EGLImage`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum EglImageError {
+    /// The backend doesn't support the required OpenGL or EGL extension.
+    NotSupported,
+
+    /// An EGL function returned an error.
+    EglError,
+}
+
+impl fmt::Display for EglImageError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.description())
+    }
+}
+
+impl Error for EglImageError {
+    fn description(&self) -> &str {
+        use self::EglImageError::*;
+        match *self {
+            NotSupported =>
+                "The backend doesn't support sharing textures with EGL images",
+            EglError =>
+                "An EGL function returned an error",
+        }
+    }
+}
+
+/// Returns true if the backend supports importing `EGLImage`s with `import_egl_image`.
+#[inline]
+pub fn is_egl_image_import_supported<F: ?Sized>(facade: &F) -> bool where F: Facade {
+    facade.get_context().get_extensions().gl_oes_egl_image
+}
+
+/// Returns true if the backend supports importing `EGLImage`s with
+/// `import_egl_image_external`.
+#[inline]
+pub fn is_egl_image_external_supported<F: ?Sized>(facade: &F) -> bool where F: Facade {
+    facade.get_context().get_extensions().gl_oes_egl_image_external
+}
+
+/// Creates a `Texture2d` whose content is the given `EGLImage`.
+///
+/// The texture doesn't have mipmaps. Its content changes whenever the producer of the image
+/// writes to it.
+///
+/// # Safety
+///
+/// `image` must be a valid `EGLImage` created on the same EGL display as the context, and
+/// `format`, `width` and `height` must match the image. The image must stay alive as long as
+/// the texture is used.
+pub unsafe fn import_egl_image<F: ?Sized>(facade: &F, image: EglImage,
+                                          format: UncompressedFloatFormat, width: u32,
+                                          height: u32) -> Result<Texture2d, EglImageError>
+                                          where F: Facade
+{
+    if !is_egl_image_import_supported(facade) {
+        return Err(EglImageError::NotSupported);
+    }
+
+    let id = {
+        let mut ctxt = facade.get_context().make_current();
+
+        // we can't use the regular texture creation functions, as textures created with
+        // `glTexStorage` are immutable and can't be the target of an `EGLImage`
+        let id = bind_new_texture(&mut ctxt, gl::TEXTURE_2D);
+        ctxt.gl.EGLImageTargetTexture2DOES(gl::TEXTURE_2D, image);
+        id
+    };
+
+    Ok(Texture2d::from_id(facade, format, id, true, MipmapsOption::NoMipmap,
+                          Dimensions::Texture2d { width: width, height: height }))
+}
+
+/// Creates an `ExternalTexture` whose content is the given `EGLImage`.
+///
+/// # Safety
+///
+/// `image` must be a valid `EGLImage` created on the same EGL display as the context, and
+/// `width` and `height` must be its dimensions. The image must stay alive as long as the
+/// texture is used.
+pub unsafe fn import_egl_image_external<F: ?Sized>(facade: &F, image: EglImage, width: u32,
+                                                   height: u32)
+                                                   -> Result<ExternalTexture, EglImageError>
+                                                   where F: Facade
+{
+    if !is_egl_image_external_supported(facade) {
+        return Err(EglImageError::NotSupported);
+    }
+
+    let id = {
+        let mut ctxt = facade.get_context().make_current();
+        let id = bind_new_texture(&mut ctxt, gl::TEXTURE_EXTERNAL_OES);
+        ctxt.gl.EGLImageTargetTexture2DOES(gl::TEXTURE_EXTERNAL_OES, image);
+        id
+    };
+
+    Ok(ExternalTexture {
+        context: facade.get_context().clone(),
+        id: id,
+        width: width,
+        height: height,
+    })
+}
+
+/// Creates a texture, binds it to the active texture unit and gives it a linear filter, as the
+/// default filter requires mipmaps that an `EGLImage` doesn't have.
+unsafe fn bind_new_texture(ctxt: &mut CommandContext, bind_point: gl::types::GLenum)
+                           -> gl::types::GLuint
+{
+    let mut id: gl::types::GLuint = mem::uninitialized();
+    ctxt.gl.GenTextures(1, &mut id);
+
+    ctxt.gl.BindTexture(bind_point, id);
+    let act = ctxt.state.active_texture as usize;
+    ctxt.state.texture_units[act].texture = id;
+
+    ctxt.gl.TexParameteri(bind_point, gl::TEXTURE_MIN_FILTER, gl::LINEAR as i32);
+    ctxt.gl.TexParameteri(bind_point, gl::TEXTURE_MAG_FILTER, gl::LINEAR as i32);
+
+    id
+}
+
+/// A texture bound to `GL_TEXTURE_EXTERNAL_OES` whose content is an `EGLImage`.
+///
+/// Built with `import_egl_image_external`. Contrary to a regular texture, it can only be used
+/// through a `samplerExternalOES` uniform. It can't be read, written or attached to a
+/// framebuffer.
+pub struct ExternalTexture {
+    context: Rc<Context>,
+    id: gl::types::GLuint,
+    width: u32,
+    height: u32,
+}
+
+impl ExternalTexture {
+    /// Returns the width of the texture.
+    #[inline]
+    pub fn get_width(&self) -> u32 {
+        self.width
+    }
+
+    /// Returns the height of the texture.
+    #[inline]
+    pub fn get_height(&self) -> u32 {
+        self.height
+    }
+
+    /// Builds a `Sampler` marker object that allows you to indicate how the texture should be
+    /// sampled from inside a shader.
+    #[inline]
+    pub fn sampled(&self) -> Sampler<ExternalTexture> {
+        Sampler(self, Default::default())
+    }
+}
+
+impl GlObject for ExternalTexture {
+    type Id = gl::types::GLuint;
+
+    #[inline]
+    fn get_id(&self) -> gl::types::GLuint {
+        self.id
+    }
+}
+
+impl TextureExt for ExternalTexture {
+    #[inline]
+    fn get_texture_id(&self) -> gl::types::GLuint {
+        self.id
+    }
+
+    #[inline]
+    fn get_context(&self) -> &Rc<Context> {
+        &self.context
+    }
+
+    #[inline]
+    fn get_bind_point(&self) -> gl::types::GLenum {
+        gl::TEXTURE_EXTERNAL_OES
+    }
+
+    fn bind_to_current(&self, ctxt: &mut CommandContext) -> gl::types::GLenum {
+        let texture_unit = ctxt.state.active_texture as usize;
+        if ctxt.state.texture_units[texture_unit].texture != self.id {
+            unsafe { ctxt.gl.BindTexture(gl::TEXTURE_EXTERNAL_OES, self.id) };
+            ctxt.state.texture_units[texture_unit].texture = self.id;
+        }
+
+        gl::TEXTURE_EXTERNAL_OES
+    }
+
+    #[inline]
+    fn prepare_for_texture_fetch(&self, _: &mut CommandContext) {
+        // external textures can't be bound to an image unit, so they are never written by shaders
+    }
+
+    #[inline]
+    fn prepare_for_image_unit(&self, _: &mut CommandContext, _: bool) {
+        // external textures can't be bound to an image unit
+    }
+
+    #[inline]
+    fn prepare_for_texture_update(&self, _: &mut CommandContext) {
+        // external textures can't be bound to an image unit, so they are never written by shaders
+    }
+
+    #[inline]
+    fn prepare_for_framebuffer(&self, _: &mut CommandContext) {
+        // external textures can't be attached to a framebuffer
+    }
+}
+
+impl Drop for ExternalTexture {
+    fn drop(&mut self) {
+        let ctxt = self.context.make_current();
+
+        for tex_unit in ctxt.state.texture_units.iter_mut() {
+            if tex_unit.texture == self.id {
+                tex_unit.texture = 0;
+            }
+        }
+
+        unsafe { ctxt.gl.DeleteTextures(1, [ self.id ].as_ptr()); }
+    }
+}
+
+impl<'a> AsUniformValue for &'a ExternalTexture {
+    #[inline]
+    fn as_uniform_value(&self) -> UniformValue {
+        UniformValue::ExternalTexture(*self, None)
+    }
+}
+
+impl<'a> AsUniformValue for Sampler<'a, ExternalTexture> {
+    #[inline]
+    fn as_uniform_value(&self) -> UniformValue {
+        UniformValue::ExternalTexture(self.0, Some(self.1))
+    }
+}
+
+/// An `EGLImage` created from a texture with `export_egl_image`.
+///
+/// The image is destroyed when this object is dropped. The texture and the other users of the
+/// image keep sharing its content until then.
+pub struct ExportedEglImage<'t> {
+    texture: &'t Texture2d,
+    display: EglDisplay,
+    image: EglImage,
+}
+
+/// A plane of a DMA-BUF exported with `ExportedEglImage::export_dma_buf`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct DmaBufPlane {
+    /// The file descriptor. It is owned by the caller, who must close it.
+    pub fd: c_int,
+    /// The number of bytes between two rows.
+    pub stride: i32,
+    /// The offset of the plane in the buffer.
+    pub offset: i32,
+    /// The DRM format modifier of the plane.
+    pub modifier: u64,
+}
+
+/// The description of a DMA-BUF exported with `ExportedEglImage::export_dma_buf`.
+///
+/// The values can be passed to `EGL_EXT_image_dma_buf_import` in order to import the image in
+/// another process.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DmaBuf {
+    /// The DRM fourcc code of the format of the image.
+    pub fourcc: u32,
+    /// Width of the image.
+    pub width: u32,
+    /// Height of the image.
+    pub height: u32,
+    /// The planes of the image.
+    pub planes: Vec<DmaBufPlane>,
+}
+
+/// Creates an `EGLImage` whose content is the main mipmap level of the texture.
+///
+/// Returns `NotSupported` if the EGL functions can't be loaded, and `EglError` if EGL fails to
+/// create the image, for example because `EGL_KHR_gl_texture_2D_image` isn't supported.
+pub fn export_egl_image(texture: &Texture2d) -> Result<ExportedEglImage, EglImageError> {
+    let context = texture.get_context();
+    let _ctxt = context.make_current();
+
+    unsafe {
+        let get_current_display = context.get_proc_address("eglGetCurrentDisplay");
+        let get_current_context = context.get_proc_address("eglGetCurrentContext");
+        let create_image = context.get_proc_address("eglCreateImageKHR");
+        if get_current_display.is_null() || get_current_context.is_null() ||
+           create_image.is_null()
+        {
+            return Err(EglImageError::NotSupported);
+        }
+
+        let get_current_display: extern "system" fn() -> EglDisplay =
+                                                                mem::transmute(get_current_display);
+        let get_current_context: extern "system" fn() -> EglContext =
+                                                                mem::transmute(get_current_context);
+        let create_image: extern "system" fn(EglDisplay, EglContext, u32, *const c_void,
+                                             *const i32) -> EglImage =
+                                                                mem::transmute(create_image);
+
+        let display = get_current_display();
+        let attributes = [EGL_GL_TEXTURE_LEVEL_KHR, 0, EGL_NONE];
+        let image = create_image(display, get_current_context(), EGL_GL_TEXTURE_2D_KHR,
+                                 texture.get_id() as usize as *const c_void,
+                                 attributes.as_ptr());
+
+        if image.is_null() {
+            return Err(EglImageError::EglError);
+        }
+
+        Ok(ExportedEglImage {
+            texture: texture,
+            display: display,
+            image: image,
+        })
+    }
+}
+
+impl<'t> ExportedEglImage<'t> {
+    /// Returns the raw handle of the image.
+    #[inline]
+    pub fn get_image(&self) -> EglImage {
+        self.image
+    }
+
+    /// Returns the texture that the image was created from.
+    #[inline]
+    pub fn get_texture(&self) -> &'t Texture2d {
+        self.texture
+    }
+
+    /// Exports the image as DMA-BUF file descriptors, which requires
+    /// `EGL_MESA_image_dma_buf_export`.
+    ///
+    /// The file descriptors are owned by the caller and stay valid after the image is
+    /// destroyed.
+    pub fn export_dma_buf(&self) -> Result<DmaBuf, EglImageError> {
+        let context = self.texture.get_context();
+        let _ctxt = context.make_current();
+
+        unsafe {
+            let query = context.get_proc_address("eglExportDMABUFImageQueryMESA");
+            let export = context.get_proc_address("eglExportDMABUFImageMESA");
+            if query.is_null() || export.is_null() {
+                return Err(EglImageError::NotSupported);
+            }
+
+            let query: extern "system" fn(EglDisplay, EglImage, *mut c_int, *mut c_int,
+                                          *mut u64) -> u32 = mem::transmute(query);
+            let export: extern "system" fn(EglDisplay, EglImage, *mut c_int, *mut i32,
+                                           *mut i32) -> u32 = mem::transmute(export);
+
+            // EGL writes one modifier per plane, so the number of planes has to be queried
+            // before the modifiers
+            let mut fourcc = 0;
+            let mut num_planes = 0;
+            if query(self.display, self.image, &mut fourcc, &mut num_planes,
+                     ptr::null_mut()) == 0 || num_planes <= 0
+            {
+                return Err(EglImageError::EglError);
+            }
+
+            let num_planes = num_planes as usize;
+            let mut modifiers = vec![0; num_planes];
+            if query(self.display, self.image, ptr::null_mut(), ptr::null_mut(),
+                     modifiers.as_mut_ptr()) == 0
+            {
+                return Err(EglImageError::EglError);
+            }
+
+            let mut fds = vec![-1; num_planes];
+            let mut strides = vec![0; num_planes];
+            let mut offsets = vec![0; num_planes];
+            if export(self.display, self.image, fds.as_mut_ptr(), strides.as_mut_ptr(),
+                      offsets.as_mut_ptr()) == 0
+            {
+                return Err(EglImageError::EglError);
+            }
+
+            Ok(DmaBuf {
+                fourcc: fourcc as u32,
+                width: self.texture.get_width(),
+                height: self.texture.get_height().unwrap_or(1),
+                planes: (0 .. num_planes).map(|i| DmaBufPlane {
+                    fd: fds[i],
+                    stride: strides[i],
+                    offset: offsets[i],
+                    modifier: modifiers[i],
+                }).collect(),
+            })
+        }
+    }
+}
+
+impl<'t> Drop for ExportedEglImage<'t> {
+    fn drop(&mut self) {
+        let context = self.texture.get_context();
+        let _ctxt = context.make_current();
+
+        unsafe {
+            let destroy = context.get_proc_address("eglDestroyImageKHR");
+            if !destroy.is_null() {
+                let destroy: extern "system" fn(EglDisplay, EglImage) -> u32 =
+                                                                        mem::transmute(destroy);
+                destroy(self.display, self.image);
+            }
+        }
+    }
+}
@@ -0,0 +1,197 @@
+//! Runtime queries about what the implementation can do with a given internal format.
+
+use std::mem;
+
+use gl;
+use backend::Facade;
+use context::CommandContext;
+use image_format::{ClientFormat, TextureFormat};
+use version::Api;
+use version::Version;
+use ContextExt;
+use ToGlEnum;
+
+/// Level of support of an operation on an internal format.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum FormatSupport {
+    /// The operation is not supported with this format.
+    None,
+
+    /// The operation is supported, but with limitations or a possible performance cost.
+    Caveat,
+
+    /// The operation is fully supported.
+    Full,
+}
+
+impl FormatSupport {
+    /// Returns true if the operation is supported, with or without caveat.
+    #[inline]
+    pub fn is_supported(&self) -> bool {
+        *self != FormatSupport::None
+    }
+
+    #[inline]
+    fn from_glenum(value: gl::types::GLint) -> FormatSupport {
+        match value as gl::types::GLenum {
+            gl::FULL_SUPPORT => FormatSupport::Full,
+            gl::CAVEAT_SUPPORT => FormatSupport::Caveat,
+            _ => FormatSupport::None,
+        }
+    }
+}
+
+/// Informations about an internal format, as reported by the backend.
+///
+/// Fields that can't be queried with the current backend are `None`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FormatProperties {
+    /// True if textures can be created with this format.
+    pub supported: bool,
+
+    /// Whether textures of this format can be attached to a framebuffer.
+    pub renderable: Option<FormatSupport>,
+
+    /// Whether linear filtering can be used when sampling textures of this format.
+    pub filterable: Option<FormatSupport>,
+
+    /// List of sample counts that can be used for multisample textures or renderbuffers of
+    /// this format, from the highest to the lowest.
+    pub samples: Option<Vec<u32>>,
+
+    /// Client format that should be used when uploading data to a texture of this format in
+    /// order to avoid a conversion by the driver. `None` if unknown or if the preferred
+    /// format has no equivalent in `ClientFormat`.
+    pub preferred_upload_format: Option<ClientFormat>,
+}
+
+impl FormatProperties {
+    /// Returns the maximum number of samples that can be used with this format, or `None`
+    /// if unknown.
+    #[inline]
+    pub fn get_max_samples(&self) -> Option<u32> {
+        self.samples.as_ref().map(|s| s.iter().cloned().max().unwrap_or(0))
+    }
+}
+
+/// Queries the backend about what can be done with an internal format.
+///
+/// If `GL_ARB_internalformat_query2` (or OpenGL 4.3) is available, the values are directly
+/// obtained from the driver. Otherwise glium falls back to what the specifications guarantee,
+/// and the fields that can't be determined are set to `None`.
+pub fn get_format_properties<F: ?Sized>(facade: &F, format: TextureFormat) -> FormatProperties
+                                        where F: Facade
+{
+    let mut ctxt = facade.get_context().make_current();
+
+    let supported = format.is_supported_for_textures(&ctxt);
+    if !supported {
+        return FormatProperties {
+            supported: false,
+            renderable: Some(FormatSupport::None),
+            filterable: Some(FormatSupport::None),
+            samples: Some(Vec::new()),
+            preferred_upload_format: None,
+        };
+    }
+
+    let samples = ctxt.capabilities.internal_formats_textures.get(&format)
+                      .and_then(|infos| infos.multisamples.as_ref())
+                      .map(|samples| samples.iter().map(|&s| s as u32).collect());
+
+    if ctxt.version >= &Version(Api::Gl, 4, 3) || ctxt.extensions.gl_arb_internalformat_query2 {
+        unsafe {
+            let renderable = query(&mut ctxt, format, gl::FRAMEBUFFER_RENDERABLE);
+            let filterable = query(&mut ctxt, format, gl::FILTER);
+            let upload_format = query(&mut ctxt, format, gl::TEXTURE_IMAGE_FORMAT);
+            let upload_type = query(&mut ctxt, format, gl::TEXTURE_IMAGE_TYPE);
+
+            FormatProperties {
+                supported: true,
+                renderable: Some(FormatSupport::from_glenum(renderable)),
+                filterable: Some(FormatSupport::from_glenum(filterable)),
+                samples: samples,
+                preferred_upload_format: client_format_from_glenum(upload_format as gl::types::GLenum,
+                                                                   upload_type as gl::types::GLenum),
+            }
+        }
+
+    } else {
+        let renderable = if format.is_renderable(&ctxt) {
+            FormatSupport::Full
+        } else {
+            FormatSupport::None
+        };
+
+        FormatProperties {
+            supported: true,
+            renderable: Some(renderable),
+            filterable: None,
+            samples: samples,
+            preferred_upload_format: None,
+        }
+    }
+}
+
+/// Calls `glGetInternalformativ` for a two-dimensional texture.
+unsafe fn query(ctxt: &mut CommandContext, format: TextureFormat, pname: gl::types::GLenum)
+                -> gl::types::GLint
+{
+    let mut value = mem::uninitialized();
+    ctxt.gl.GetInternalformativ(gl::TEXTURE_2D, format.to_glenum(), pname, 1, &mut value);
+    value
+}
+
+/// Turns a format/type pair returned by the backend into a `ClientFormat`.
+fn client_format_from_glenum(format: gl::types::GLenum, ty: gl::types::GLenum)
+                             -> Option<ClientFormat>
+{
+    let components = match format {
+        gl::RED | gl::RED_INTEGER => 1,
+        gl::RG | gl::RG_INTEGER => 2,
+        gl::RGB | gl::RGB_INTEGER => 3,
+        gl::RGBA | gl::RGBA_INTEGER => 4,
+        _ => return None,
+    };
+
+    Some(match (ty, components) {
+        (gl::UNSIGNED_BYTE, 1) => ClientFormat::U8,
+        (gl::UNSIGNED_BYTE, 2) => ClientFormat::U8U8,
+        (gl::UNSIGNED_BYTE, 3) => ClientFormat::U8U8U8,
+        (gl::UNSIGNED_BYTE, 4) => ClientFormat::U8U8U8U8,
+        (gl::BYTE, 1) => ClientFormat::I8,
+        (gl::BYTE, 2) => ClientFormat::I8I8,
+        (gl::BYTE, 3) => ClientFormat::I8I8I8,
+        (gl::BYTE, 4) => ClientFormat::I8I8I8I8,
+        (gl::UNSIGNED_SHORT, 1) => ClientFormat::U16,
+        (gl::UNSIGNED_SHORT, 2) => ClientFormat::U16U16,
+        (gl::UNSIGNED_SHORT, 3) => ClientFormat::U16U16U16,
+        (gl::UNSIGNED_SHORT, 4) => ClientFormat::U16U16U16U16,
+        (gl::SHORT, 1) => ClientFormat::I16,
+        (gl::SHORT, 2) => ClientFormat::I16I16,
+        (gl::SHORT, 3) => ClientFormat::I16I16I16,
+        (gl::SHORT, 4) => ClientFormat::I16I16I16I16,
+        (gl::UNSIGNED_INT, 1) => ClientFormat::U32,
+        (gl::UNSIGNED_INT, 2) => ClientFormat::U32U32,
+        (gl::UNSIGNED_INT, 3) => ClientFormat::U32U32U32,
+        (gl::UNSIGNED_INT, 4) => ClientFormat::U32U32U32U32,
+        (gl::INT, 1) => ClientFormat::I32,
+        (gl::INT, 2) => ClientFormat::I32I32,
+        (gl::INT, 3) => ClientFormat::I32I32I32,
+        (gl::INT, 4) => ClientFormat::I32I32I32I32,
+        (gl::UNSIGNED_BYTE_3_3_2, 3) => ClientFormat::U3U3U2,
+        (gl::UNSIGNED_SHORT_5_6_5, 3) => ClientFormat::U5U6U5,
+        (gl::UNSIGNED_SHORT_4_4_4_4, 4) => ClientFormat::U4U4U4U4,
+        (gl::UNSIGNED_SHORT_5_5_5_1, 4) => ClientFormat::U5U5U5U1,
+        (gl::UNSIGNED_INT_10_10_10_2, 4) => ClientFormat::U10U10U10U2,
+        (gl::HALF_FLOAT, 1) => ClientFormat::F16,
+        (gl::HALF_FLOAT, 2) => ClientFormat::F16F16,
+        (gl::HALF_FLOAT, 3) => ClientFormat::F16F16F16,
+        (gl::HALF_FLOAT, 4) => ClientFormat::F16F16F16F16,
+        (gl::FLOAT, 1) => ClientFormat::F32,
+        (gl::FLOAT, 2) => ClientFormat::F32F32,
+        (gl::FLOAT, 3) => ClientFormat::F32F32F32,
+        (gl::FLOAT, 4) => ClientFormat::F32F32F32F32,
+        _ => return None,
+    })
+}
@@ -0,0 +1,98 @@
+//! Conversions between two-dimensional textures and the images of the `image` crate.
+//!
+//! These functions are only available if the `image` feature is enabled.
+
+use std::borrow::Cow;
+
+use image::{self, DynamicImage, GenericImage};
+
+use backend::Facade;
+use texture::{RawImage2d, Texture2d, SrgbTexture2d, Texture2dDataSink, TextureCreationError};
+
+/// Turns an image into a `RawImage2d`.
+///
+/// The first row of an image is the top one, while OpenGL expects the bottom one first, so the
+/// image is flipped vertically.
+fn to_raw_image(image: &DynamicImage) -> RawImage2d<'static, u8> {
+    let dimensions = image.dimensions();
+
+    match *image {
+        DynamicImage::ImageRgb8(ref img) => {
+            RawImage2d::from_raw_rgb_reversed(img.clone().into_raw(), dimensions)
+        },
+        ref img => RawImage2d::from_raw_rgba_reversed(img.to_rgba().into_raw(), dimensions),
+    }
+}
+
+/// Turns RGBA pixels whose first row is the bottom one into an image, flipping it vertically.
+fn flipped_rgba_image(data: &[(u8, u8, u8, u8)], width: u32, height: u32) -> DynamicImage {
+    let mut bytes = Vec::with_capacity(data.len() * 4);
+    for row in data.chunks(width as usize).rev() {
+        for &(r, g, b, a) in row {
+            bytes.push(r);
+            bytes.push(g);
+            bytes.push(b);
+            bytes.push(a);
+        }
+    }
+
+    DynamicImage::ImageRgba8(image::ImageBuffer::from_raw(width, height, bytes).unwrap())
+}
+
+/// Builds an RGBA image out of pixels read from a texture or a framebuffer. The image is
+/// flipped vertically so that its first row is the top of the source.
+impl Texture2dDataSink<(u8, u8, u8, u8)> for DynamicImage {
+    #[inline]
+    fn from_raw(data: Cow<[(u8, u8, u8, u8)]>, width: u32, height: u32) -> DynamicImage {
+        flipped_rgba_image(&data, width, height)
+    }
+}
+
+impl Texture2d {
+    /// Builds a texture from an image of the `image` crate.
+    ///
+    /// The image is flipped so that its top-left corner ends up at the texture coordinates
+    /// `(0.0, 1.0)`. Grayscale images are expanded to RGBA.
+    ///
+    /// The data is uploaded as it is. Most image files contain sRGB colors, in which case
+    /// you should use `SrgbTexture2d::from_dynamic_image` instead.
+    #[inline]
+    pub fn from_dynamic_image<F: ?Sized>(facade: &F, image: &DynamicImage)
+                                         -> Result<Texture2d, TextureCreationError>
+                                         where F: Facade
+    {
+        Texture2d::new(facade, to_raw_image(image))
+    }
+
+    /// Reads the content of the texture into an RGBA image of the `image` crate.
+    ///
+    /// The image is flipped so that the first row of the image is the top of the texture.
+    #[inline]
+    pub fn to_dynamic_image(&self) -> DynamicImage {
+        self.read()
+    }
+}
+
+impl SrgbTexture2d {
+    /// Builds a texture from an image of the `image` crate whose colors are in the sRGB
+    /// color space.
+    ///
+    /// The image is flipped so that its top-left corner ends up at the texture coordinates
+    /// `(0.0, 1.0)`. Grayscale images are expanded to RGBA.
+    #[inline]
+    pub fn from_dynamic_image<F: ?Sized>(facade: &F, image: &DynamicImage)
+                                         -> Result<SrgbTexture2d, TextureCreationError>
+                                         where F: Facade
+    {
+        SrgbTexture2d::new(facade, to_raw_image(image))
+    }
+
+    /// Reads the content of the texture into an RGBA image of the `image` crate.
+    ///
+    /// The colors are not converted and are still in the sRGB color space. The image is
+    /// flipped so that the first row of the image is the top of the texture.
+    #[inline]
+    pub fn to_dynamic_image(&self) -> DynamicImage {
+        self.read()
+    }
+}
@@ -96,6 +96,10 @@ fragment shader to be in linear RGB and then turns it into sRGB before writing i
 Sampling from an sRGB texture will convert the texture colors from sRGB to RGB. If you create a
 regular RGB texture and put sRGB data in it, then the result will be too bright.
 
+The trigger is disabled when drawing with a program created with `outputs_srgb` set to true.
+You can also enable or disable it for a single draw call with the `framebuffer_srgb` member of
+`DrawParameters`.
+
 # Bindless textures
 
 *Bindless textures are a very recent feature that is supported only by recent hardware and
@@ -124,6 +128,7 @@ pub use image_format::{CompressedFormat, DepthFormat, DepthStencilFormat, Stenci
 pub use image_format::{CompressedSrgbFormat, SrgbFormat};
 pub use self::any::{TextureAny, TextureAnyMipmap, TextureAnyLayer, TextureAnyLayerMipmap};
 pub use self::any::{TextureAnyImage, Dimensions};
+pub use self::format_query::{get_format_properties, FormatProperties, FormatSupport};
 pub use self::bindless::{ResidentTexture, TextureHandle, BindlessTexturesNotSupportedError};
 pub use self::get_format::{InternalFormat, InternalFormatType, GetFormatError};
 pub use self::pixel::PixelValue;
@@ -133,12 +138,19 @@ pub use self::ty_support::{is_texture_2d_array_supported, is_texture_2d_multisam
 pub use self::ty_support::{is_texture_2d_multisample_array_supported, is_cubemaps_supported};
 pub use self::ty_support::is_cubemap_arrays_supported;
 
+pub mod atlas;
 pub mod bindless;
 pub mod buffer_texture;
+#[cfg(feature = "texture_containers")]
+pub mod container;
+pub mod egl_image;
 pub mod pixel_buffer;
 
 mod any;
+mod format_query;
 mod get_format;
+#[cfg(feature = "image")]
+mod image_interop;
 mod pixel;
 mod ty_support;
 
@@ -729,6 +741,9 @@ pub enum TextureCreationError {
 
     /// The texture format is not supported by the backend.
     TypeNotSupported,
+
+    /// The size of the data doesn't match the dimensions of the texture.
+    DataSizeMismatch,
 }
 
 impl fmt::Display for TextureCreationError {
@@ -747,6 +762,8 @@ impl Error for TextureCreationError {
                 "The requested texture dimensions are not supported",
             TypeNotSupported =>
                 "The texture format is not supported by the backend",
+            DataSizeMismatch =>
+                "The size of the data doesn't match the dimensions of the texture",
         }
     }
 }
@@ -7,6 +7,7 @@ use gl;
 
 use std::collections::HashMap;
 use std::hash::BuildHasherDefault;
+use std::slice;
 
 use fnv::FnvHasher;
 
@@ -16,13 +17,18 @@ use DrawError;
 use ProgramExt;
 use UniformsExt;
 use RawUniformValue;
+use RawUniformArray;
 use TextureExt;
+use ToGlEnum;
 
 use uniforms::Uniforms;
 use uniforms::UniformValue;
 use uniforms::SamplerBehavior;
+use uniforms::ImageUnitBehavior;
+use uniforms::UniformType;
 
 use context::CommandContext;
+use texture::TextureAny;
 use buffer::Inserter;
 use ContextExt;
 
@@ -42,9 +48,15 @@ impl<U> UniformsExt for U where U: Uniforms {
                             where P: ProgramExt
     {
         let mut texture_bind_points = Bitsfield::new();
+        let mut image_bind_points = Bitsfield::new();
         let mut uniform_buffer_bind_points = Bitsfield::new();
         let mut shared_storage_buffer_bind_points = Bitsfield::new();
 
+        // the texture units assigned to the samplers of the program are reserved for them
+        for texture_unit in program.get_texture_units() {
+            texture_bind_points.set_used(texture_unit.unit as u16);
+        }
+
         // Subroutine uniforms must be binded all at once, so we collect them first and process them at the end.
         // The vec contains the uniform we want to set and the value we want to set it to.
         let mut subroutine_bindings: HashMap<program::ShaderStage, Vec<(&program::SubroutineUniform, &str)>, _>
@@ -54,7 +66,24 @@ impl<U> UniformsExt for U where U: Uniforms {
         self.visit_values(|name, value| {
             if visiting_result.is_err() { return; }
 
-            if let Some(uniform) = program.get_uniform(name) {
+            if let Some(counter) = program.get_atomic_counters().get(name) {
+                match bind_atomic_counter(&mut ctxt, &value, counter, name) {
+                    Ok(Some(fence)) => fences.push(fence),
+                    Ok(None) => (),
+                    Err(e) => visiting_result = Err(e),
+                };
+                return;
+            }
+
+            let uniform = match get_uniform(program, name, &value) {
+                Ok(uniform) => uniform,
+                Err(e) => {
+                    visiting_result = Err(e);
+                    return;
+                }
+            };
+
+            if let Some(uniform) = uniform {
                 // TODO: remove the size member
                 debug_assert!(uniform.size.is_none());
 
@@ -67,7 +96,7 @@ impl<U> UniformsExt for U where U: Uniforms {
                 }
 
                 match bind_uniform(&mut ctxt, &value, program, uniform.location,
-                                   &mut texture_bind_points, name)
+                                   &mut texture_bind_points, &mut image_bind_points, name)
                 {
                     Ok(_) => (),
                     Err(e) => {
@@ -115,8 +144,32 @@ impl<U> UniformsExt for U where U: Uniforms {
             }
         });
 
+        // uniforms identified by their location don't need to be looked up
+        if visiting_result.is_ok() {
+            self.visit_located_values(|location, value| {
+                if visiting_result.is_err() { return; }
+
+                if !value.is_usable_with(&location.ty) {
+                    visiting_result = Err(DrawError::UniformTypeMismatch {
+                        name: format!("<location {}>", location.location),
+                        expected: location.ty,
+                    });
+                    return;
+                }
+
+                // the name is only used for errors that can't happen with a value whose type
+                // matches the uniform
+                if let Err(e) = bind_uniform(&mut ctxt, &value, program, location.location,
+                                             &mut texture_bind_points,
+                                             &mut image_bind_points, "")
+                {
+                    visiting_result = Err(e);
+                }
+            });
+        }
+
         // Process all subroutine uniforms in one batch.
-        if !subroutine_bindings.is_empty() {
+        if visiting_result.is_ok() {
             match bind_subroutine_uniforms(&mut ctxt, program, &subroutine_bindings) {
                 Ok(_) => (),
                 Err(e) => {
@@ -127,6 +180,101 @@ impl<U> UniformsExt for U where U: Uniforms {
 
         visiting_result
     }
+
+    fn set_uniform_values<P>(&self, mut ctxt: &mut CommandContext, program: &P)
+                             -> Result<(), DrawError>
+                             where P: ProgramExt
+    {
+        let mut visiting_result = Ok(());
+
+        self.visit_values(|name, value| {
+            if visiting_result.is_err() { return; }
+
+            // blocks and subroutines are not plain uniforms and are ignored here
+            visiting_result = match get_uniform(program, name, &value) {
+                Ok(Some(uniform)) => set_uniform_value(&mut ctxt, program, uniform.location,
+                                                       uniform.ty, &value, name),
+                Ok(None) => Ok(()),
+                Err(e) => Err(e),
+            };
+        });
+
+        if visiting_result.is_ok() {
+            self.visit_located_values(|location, value| {
+                if visiting_result.is_err() { return; }
+
+                let name = format!("<location {}>", location.location);
+                visiting_result = set_uniform_value(&mut ctxt, program, location.location,
+                                                    location.ty, &value, &name);
+            });
+        }
+
+        visiting_result
+    }
+}
+
+/// Looks up the uniform that corresponds to a name.
+///
+/// An array of values can be bound to an array of uniforms by using the name of the array
+/// without any index, or the name of the first element to set.
+fn get_uniform<'p, P>(program: &'p P, name: &str, value: &UniformValue)
+                      -> Result<Option<&'p program::Uniform>, DrawError>
+                      where P: ProgramExt
+{
+    match value.array_len() {
+        Some(len) if !name.ends_with(']') => {
+            if let Some(uniform) = program.get_uniform(&format!("{}[0]", name)) {
+                return Ok(Some(uniform));
+            }
+
+            // only arrays of uniforms can receive more than one value
+            match program.get_uniform(name) {
+                Some(uniform) if len != 1 => {
+                    Err(DrawError::UniformTypeMismatch {
+                        name: name.to_owned(),
+                        expected: uniform.ty,
+                    })
+                },
+                uniform => Ok(uniform),
+            }
+        },
+        _ => Ok(program.get_uniform(name)),
+    }
+}
+
+/// Sets the value of a uniform that isn't a sampler. Samplers are ignored, as the texture
+/// can only be bound right before drawing, unless the value is a bindless texture handle.
+fn set_uniform_value<P>(ctxt: &mut context::CommandContext, program: &P,
+                        location: gl::types::GLint, ty: UniformType, value: &UniformValue,
+                        name: &str) -> Result<(), DrawError>
+                        where P: ProgramExt
+{
+    let is_handle = match *value {
+        UniformValue::Handle(_) | UniformValue::HandleArray(_) => true,
+        _ => false,
+    };
+
+    if !is_handle && program.get_texture_units().iter().any(|unit| unit.location == location) {
+        return Ok(());
+    }
+
+    // images can only be bound right before drawing as well
+    if let UniformValue::Image(..) = *value {
+        return Ok(());
+    }
+
+    if !value.is_usable_with(&ty) {
+        return Err(DrawError::UniformTypeMismatch {
+            name: name.to_owned(),
+            expected: ty,
+        });
+    }
+
+    // no texture unit or image unit is used by non-sampler uniforms
+    let mut texture_bind_points = Bitsfield::new();
+    let mut image_bind_points = Bitsfield::new();
+    bind_uniform(ctxt, value, program, location, &mut texture_bind_points,
+                 &mut image_bind_points, name)
 }
 
 fn bind_subroutine_uniforms<P>(ctxt: &mut context::CommandContext, program: &P,
@@ -135,7 +283,19 @@ fn bind_subroutine_uniforms<P>(ctxt: &mut context::CommandContext, program: &P,
                             where P: ProgramExt
 {
     let subroutine_data = program.get_subroutine_data();
-    for (stage, bindings) in subroutine_bindings {
+
+    // Subroutine uniforms are reset to arbitrary values whenever the program is bound, so every
+    // stage that has subroutine uniforms needs to be processed, even if no binding was passed.
+    for (stage, &location_count) in subroutine_data.location_counts.iter() {
+        if location_count == 0 {
+            continue;
+        }
+
+        let bindings = match subroutine_bindings.get(stage) {
+            Some(bindings) => &bindings[..],
+            None => &[][..],
+        };
+
         // Validate that all subroutine uniforms of this stage are set, otherwise OpenGL will throw an error.
         let set_cnt = bindings.len();
         let expected_cnt = subroutine_data.subroutine_uniforms.iter()
@@ -150,7 +310,7 @@ fn bind_subroutine_uniforms<P>(ctxt: &mut context::CommandContext, program: &P,
         }
 
         // Build the indices array
-        let mut indices = vec![0 as gl::types::GLuint; *subroutine_data.location_counts.get(stage).unwrap()];
+        let mut indices = vec![0 as gl::types::GLuint; location_count];
         for binding in bindings {
             let uniform = binding.0;
             let subroutine_str = binding.1;
@@ -188,10 +348,19 @@ fn bind_uniform_block<'a, P>(ctxt: &mut context::CommandContext, value: &Uniform
                 }
             }
 
+            let offset = buffer.get_offset_bytes();
+            let alignment = ctxt.capabilities.uniform_buffer_offset_alignment as usize;
+            if alignment != 0 && offset % alignment != 0 {
+                return Err(DrawError::BufferOffsetNotAligned {
+                    name: name.to_owned(),
+                    offset: offset,
+                    alignment: alignment,
+                });
+            }
+
             let bind_point = buffer_bind_points.get_unused().expect("Not enough buffer units");
             buffer_bind_points.set_used(bind_point);
 
-            assert!(buffer.get_offset_bytes() == 0);     // TODO: not implemented
             let fence = buffer.add_fence();
             let block_id = block.id as gl::types::GLuint;
 
@@ -224,10 +393,19 @@ fn bind_shared_storage_block<'a, P>(ctxt: &mut context::CommandContext, value: &
                 }
             }
 
+            let offset = buffer.get_offset_bytes();
+            let alignment = ctxt.capabilities.shader_storage_buffer_offset_alignment as usize;
+            if alignment != 0 && offset % alignment != 0 {
+                return Err(DrawError::BufferOffsetNotAligned {
+                    name: name.to_owned(),
+                    offset: offset,
+                    alignment: alignment,
+                });
+            }
+
             let bind_point = buffer_bind_points.get_unused().expect("Not enough buffer units");
             buffer_bind_points.set_used(bind_point);
 
-            assert!(buffer.get_offset_bytes() == 0);     // TODO: not implemented
             let fence = buffer.add_fence();
             let block_id = block.id as gl::types::GLuint;
 
@@ -242,13 +420,47 @@ fn bind_shared_storage_block<'a, P>(ctxt: &mut context::CommandContext, value: &
     }
 }
 
+fn bind_atomic_counter<'a>(ctxt: &mut context::CommandContext, value: &UniformValue<'a>,
+                           counter: &program::AtomicCounter, name: &str)
+                           -> Result<Option<Inserter<'a>>, DrawError>
+{
+    match value {
+        &UniformValue::Block(buffer, _) => {
+            // the binding point is chosen by the shader and can't be changed
+            let fence = buffer.add_fence();
+            buffer.prepare_and_bind_for_atomic_counter(ctxt, counter.binding);
+            Ok(fence)
+        },
+        _ => {
+            Err(DrawError::UniformTypeMismatch {
+                name: name.to_owned(),
+                expected: UniformType::AtomicCounterUint,
+            })
+        }
+    }
+}
+
 fn bind_uniform<P>(ctxt: &mut context::CommandContext,
                    value: &UniformValue, program: &P, location: gl::types::GLint,
-                   texture_bind_points: &mut Bitsfield, name: &str)
+                   texture_bind_points: &mut Bitsfield, image_bind_points: &mut Bitsfield,
+                   name: &str)
                    -> Result<(), DrawError> where P: ProgramExt
 {
     assert!(location >= 0);
 
+    match *value {
+        UniformValue::Double(_) | UniformValue::DoubleVec2(_) | UniformValue::DoubleVec3(_) |
+        UniformValue::DoubleVec4(_) | UniformValue::DoubleMat2(_) |
+        UniformValue::DoubleMat3(_) | UniformValue::DoubleMat4(_) => {
+            if !program::is_double_precision_supported(&*ctxt) {
+                return Err(DrawError::DoublePrecisionNotSupported {
+                    name: name.to_owned(),
+                });
+            }
+        },
+        _ => ()
+    }
+
     match *value {
         UniformValue::Block(_, _) => {
             Err(DrawError::UniformBufferToValue {
@@ -289,6 +501,30 @@ fn bind_uniform<P>(ctxt: &mut context::CommandContext,
             program.set_uniform(ctxt, location, &RawUniformValue::Mat4(val));
             Ok(())
         },
+        UniformValue::Mat2x3(val) => {
+            program.set_uniform(ctxt, location, &RawUniformValue::Mat2x3(val));
+            Ok(())
+        },
+        UniformValue::Mat2x4(val) => {
+            program.set_uniform(ctxt, location, &RawUniformValue::Mat2x4(val));
+            Ok(())
+        },
+        UniformValue::Mat3x2(val) => {
+            program.set_uniform(ctxt, location, &RawUniformValue::Mat3x2(val));
+            Ok(())
+        },
+        UniformValue::Mat3x4(val) => {
+            program.set_uniform(ctxt, location, &RawUniformValue::Mat3x4(val));
+            Ok(())
+        },
+        UniformValue::Mat4x2(val) => {
+            program.set_uniform(ctxt, location, &RawUniformValue::Mat4x2(val));
+            Ok(())
+        },
+        UniformValue::Mat4x3(val) => {
+            program.set_uniform(ctxt, location, &RawUniformValue::Mat4x3(val));
+            Ok(())
+        },
         UniformValue::Vec2(val) => {
             program.set_uniform(ctxt, location, &RawUniformValue::Vec2(val));
             Ok(())
@@ -400,6 +636,89 @@ fn bind_uniform<P>(ctxt: &mut context::CommandContext,
             program.set_uniform(ctxt, location, &RawUniformValue::UnsignedInt64Vec4(val));
             Ok(())
         },
+        UniformValue::FloatArray(val) => {
+            program.set_uniform_array(ctxt, location, &RawUniformArray::Float(val));
+            Ok(())
+        },
+        UniformValue::Vec2Array(val) => {
+            program.set_uniform_array(ctxt, location, &RawUniformArray::Vec2(val));
+            Ok(())
+        },
+        UniformValue::Vec3Array(val) => {
+            program.set_uniform_array(ctxt, location, &RawUniformArray::Vec3(val));
+            Ok(())
+        },
+        UniformValue::Vec4Array(val) => {
+            program.set_uniform_array(ctxt, location, &RawUniformArray::Vec4(val));
+            Ok(())
+        },
+        UniformValue::Mat2Array(val) => {
+            program.set_uniform_array(ctxt, location, &RawUniformArray::Mat2(val));
+            Ok(())
+        },
+        UniformValue::Mat3Array(val) => {
+            program.set_uniform_array(ctxt, location, &RawUniformArray::Mat3(val));
+            Ok(())
+        },
+        UniformValue::Mat4Array(val) => {
+            program.set_uniform_array(ctxt, location, &RawUniformArray::Mat4(val));
+            Ok(())
+        },
+        UniformValue::SignedIntArray(val) => {
+            program.set_uniform_array(ctxt, location, &RawUniformArray::SignedInt(val));
+            Ok(())
+        },
+        UniformValue::IntVec2Array(val) => {
+            program.set_uniform_array(ctxt, location, &RawUniformArray::IntVec2(val));
+            Ok(())
+        },
+        UniformValue::IntVec3Array(val) => {
+            program.set_uniform_array(ctxt, location, &RawUniformArray::IntVec3(val));
+            Ok(())
+        },
+        UniformValue::IntVec4Array(val) => {
+            program.set_uniform_array(ctxt, location, &RawUniformArray::IntVec4(val));
+            Ok(())
+        },
+        UniformValue::UnsignedIntArray(val) => {
+            program.set_uniform_array(ctxt, location, &RawUniformArray::UnsignedInt(val));
+            Ok(())
+        },
+        UniformValue::UnsignedIntVec2Array(val) => {
+            program.set_uniform_array(ctxt, location, &RawUniformArray::UnsignedIntVec2(val));
+            Ok(())
+        },
+        UniformValue::UnsignedIntVec3Array(val) => {
+            program.set_uniform_array(ctxt, location, &RawUniformArray::UnsignedIntVec3(val));
+            Ok(())
+        },
+        UniformValue::UnsignedIntVec4Array(val) => {
+            program.set_uniform_array(ctxt, location, &RawUniformArray::UnsignedIntVec4(val));
+            Ok(())
+        },
+        UniformValue::Handle(val) => {
+            if !ctxt.resident_texture_handles.iter().any(|&h| h == val) {
+                return Err(DrawError::TextureHandleNotResident { name: name.to_owned() });
+            }
+
+            program.set_uniform(ctxt, location, &RawUniformValue::Handle(val));
+            Ok(())
+        },
+        UniformValue::HandleArray(val) => {
+            // `TextureHandle` has the same layout as a `GLuint64`
+            let val = unsafe {
+                slice::from_raw_parts(val.as_ptr() as *const gl::types::GLuint64, val.len())
+            };
+
+            for handle in val.iter() {
+                if !ctxt.resident_texture_handles.iter().any(|h| h == handle) {
+                    return Err(DrawError::TextureHandleNotResident { name: name.to_owned() });
+                }
+            }
+
+            program.set_uniform_array(ctxt, location, &RawUniformArray::Handle(val));
+            Ok(())
+        },
         UniformValue::Texture1d(texture, sampler) => {
             bind_texture_uniform(ctxt, &**texture, sampler, location, program, texture_bind_points)
         },
@@ -580,15 +899,54 @@ fn bind_uniform<P>(ctxt: &mut context::CommandContext,
         UniformValue::BufferTexture(texture) => {
             bind_texture_uniform(ctxt, &texture, None, location, program, texture_bind_points)
         },
+        UniformValue::ExternalTexture(texture, sampler) => {
+            bind_texture_uniform(ctxt, texture, sampler, location, program, texture_bind_points)
+        },
+        UniformValue::Image(texture, ref behavior) => {
+            bind_image_uniform(ctxt, texture, behavior, location, program, image_bind_points)
+        },
     }
 }
 
+fn bind_image_uniform<P>(ctxt: &mut context::CommandContext, texture: &TextureAny,
+                         behavior: &ImageUnitBehavior, location: gl::types::GLint, program: &P,
+                         image_bind_points: &mut Bitsfield)
+                         -> Result<(), DrawError> where P: ProgramExt
+{
+    let image_unit = match image_bind_points.get_unused() {
+        Some(unit) if (unit as gl::types::GLint) < ctxt.capabilities.max_image_units => unit,
+        _ => return Err(DrawError::TooManyImageUnits),
+    };
+    image_bind_points.set_used(image_unit);
+
+    texture.prepare_for_image_unit(ctxt, behavior.access.is_write());
+
+    // like samplers, image uniforms contain the index of the unit
+    program.set_uniform(ctxt, location,
+                        &RawUniformValue::SignedInt(image_unit as gl::types::GLint));
+
+    let (layered, layer) = match behavior.layer {
+        Some(layer) => (gl::FALSE, layer as gl::types::GLint),
+        None => (gl::TRUE, 0),
+    };
+
+    unsafe {
+        ctxt.gl.BindImageTexture(image_unit as gl::types::GLuint, texture.get_texture_id(),
+                                 behavior.level as gl::types::GLint, layered, layer,
+                                 behavior.access.to_glenum(), behavior.format.to_glenum());
+    }
+
+    Ok(())
+}
+
 fn bind_texture_uniform<P, T>(mut ctxt: &mut context::CommandContext,
                               texture: &T, sampler: Option<SamplerBehavior>,
                               location: gl::types::GLint, program: &P,
                               texture_bind_points: &mut Bitsfield)
                               -> Result<(), DrawError> where P: ProgramExt, T: TextureExt
 {
+    texture.prepare_for_texture_fetch(ctxt);
+
     let sampler = if let Some(sampler) = sampler {
         Some(try!(::sampler_object::get_sampler(ctxt, &sampler)))
     } else {
@@ -597,27 +955,39 @@ fn bind_texture_uniform<P, T>(mut ctxt: &mut context::CommandContext,
 
     let sampler = sampler.unwrap_or(0);
 
-    // finding an appropriate texture unit
-    let texture_unit =
-        ctxt.state.texture_units
-            .iter().enumerate()
-            .find(|&(unit, content)| {
-                content.texture == texture.get_texture_id() && (content.sampler == sampler ||
-                                                        !texture_bind_points.is_used(unit as u16))
-            })
-            .map(|(unit, _)| unit as u16)
-            .or_else(|| {
-                if ctxt.state.texture_units.len() <
-                    ctxt.capabilities.max_combined_texture_image_units as usize
-                {
-                    Some(ctxt.state.texture_units.len() as u16)
-                } else {
-                    None
-                }
-            })
-            .unwrap_or_else(|| {
-                texture_bind_points.get_unused().expect("Not enough texture units available")
-            });
+    // finding an appropriate texture unit, the samplers of the program have normally been
+    // assigned one when it was linked
+    let assigned_unit = program.get_texture_units().iter()
+                               .find(|t| t.location == location)
+                               .map(|t| t.unit as u16);
+
+    // the other samplers must not use the units that have been assigned, even if they are not
+    // bound yet
+    let is_assigned = |unit: u16| {
+        program.get_texture_units().iter().any(|t| t.unit as u16 == unit)
+    };
+
+    let texture_unit = match assigned_unit {
+        Some(unit) => unit,
+        None => {
+            let max_units = ctxt.capabilities.max_combined_texture_image_units as usize;
+
+            ctxt.state.texture_units
+                .iter().enumerate()
+                .find(|&(unit, content)| {
+                    !is_assigned(unit as u16) && content.texture == texture.get_texture_id() &&
+                        (content.sampler == sampler || !texture_bind_points.is_used(unit as u16))
+                })
+                .map(|(unit, _)| unit as u16)
+                .or_else(|| {
+                    (ctxt.state.texture_units.len() .. max_units).map(|unit| unit as u16)
+                                                                .find(|&unit| !is_assigned(unit))
+                })
+                .unwrap_or_else(|| {
+                    texture_bind_points.get_unused().expect("Not enough texture units available")
+                })
+        }
+    };
     assert!((texture_unit as gl::types::GLint) <
             ctxt.capabilities.max_combined_texture_image_units);
     texture_bind_points.set_used(texture_unit);
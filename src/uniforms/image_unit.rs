@@ -0,0 +1,276 @@
+use ToGlEnum;
+use gl;
+
+use std::ops::Deref;
+
+use texture::TextureAny;
+use uniforms::AsUniformValue;
+use uniforms::UniformType;
+use uniforms::UniformValue;
+
+/// How a shader can access the content of an image.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub enum ImageUnitAccess {
+    /// The shader can only load from the image.
+    Read,
+
+    /// The shader can only store in the image.
+    Write,
+
+    /// The shader can load from and store in the image.
+    ReadWrite,
+}
+
+impl ImageUnitAccess {
+    /// Returns true if the shader can store values in the image.
+    #[inline]
+    pub fn is_write(&self) -> bool {
+        match *self {
+            ImageUnitAccess::Read => false,
+            ImageUnitAccess::Write | ImageUnitAccess::ReadWrite => true,
+        }
+    }
+}
+
+impl ToGlEnum for ImageUnitAccess {
+    #[inline]
+    fn to_glenum(&self) -> gl::types::GLenum {
+        match *self {
+            ImageUnitAccess::Read => gl::READ_ONLY,
+            ImageUnitAccess::Write => gl::WRITE_ONLY,
+            ImageUnitAccess::ReadWrite => gl::READ_WRITE,
+        }
+    }
+}
+
+/// Format in which the texels of an image are interpreted by the shader.
+///
+/// This corresponds to the format layout qualifier of the image in GLSL, for example
+/// `layout(rgba32f)`. The format must be compatible with the internal format of the texture.
+#[allow(missing_docs)]
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub enum ImageUnitFormat {
+    RGBA32F,
+    RGBA16F,
+    RG32F,
+    RG16F,
+    R11FG11FB10F,
+    R32F,
+    R16F,
+    RGBA32UI,
+    RGBA16UI,
+    RGB10A2UI,
+    RGBA8UI,
+    RG32UI,
+    RG16UI,
+    RG8UI,
+    R32UI,
+    R16UI,
+    R8UI,
+    RGBA32I,
+    RGBA16I,
+    RGBA8I,
+    RG32I,
+    RG16I,
+    RG8I,
+    R32I,
+    R16I,
+    R8I,
+    RGBA16,
+    RGB10A2,
+    RGBA8,
+    RG16,
+    RG8,
+    R16,
+    R8,
+    RGBA16snorm,
+    RGBA8snorm,
+    RG16snorm,
+    RG8snorm,
+    R16snorm,
+    R8snorm,
+}
+
+impl ImageUnitFormat {
+    /// Returns true if an image uniform of the given type can be used with this format.
+    ///
+    /// `image*` uniforms require a floating-point or normalized format, `iimage*` uniforms a
+    /// signed integer format and `uimage*` uniforms an unsigned integer format.
+    pub fn is_usable_with(&self, ty: &UniformType) -> bool {
+        let integral = match *self {
+            ImageUnitFormat::RGBA32I | ImageUnitFormat::RGBA16I | ImageUnitFormat::RGBA8I |
+            ImageUnitFormat::RG32I | ImageUnitFormat::RG16I | ImageUnitFormat::RG8I |
+            ImageUnitFormat::R32I | ImageUnitFormat::R16I | ImageUnitFormat::R8I => true,
+            _ => false,
+        };
+
+        let unsigned = match *self {
+            ImageUnitFormat::RGBA32UI | ImageUnitFormat::RGBA16UI | ImageUnitFormat::RGB10A2UI |
+            ImageUnitFormat::RGBA8UI | ImageUnitFormat::RG32UI | ImageUnitFormat::RG16UI |
+            ImageUnitFormat::RG8UI | ImageUnitFormat::R32UI | ImageUnitFormat::R16UI |
+            ImageUnitFormat::R8UI => true,
+            _ => false,
+        };
+
+        match *ty {
+            UniformType::Image1d | UniformType::Image2d | UniformType::Image3d |
+            UniformType::Image2dRect | UniformType::ImageCube | UniformType::ImageBuffer |
+            UniformType::Image1dArray | UniformType::Image2dArray |
+            UniformType::Image2dMultisample |
+            UniformType::Image2dMultisampleArray => !integral && !unsigned,
+
+            UniformType::IImage1d | UniformType::IImage2d | UniformType::IImage3d |
+            UniformType::IImage2dRect | UniformType::IImageCube | UniformType::IImageBuffer |
+            UniformType::IImage1dArray | UniformType::IImage2dArray |
+            UniformType::IImage2dMultisample |
+            UniformType::IImage2dMultisampleArray => integral,
+
+            UniformType::UImage1d | UniformType::UImage2d | UniformType::UImage3d |
+            UniformType::UImage2dRect | UniformType::UImageCube | UniformType::UImageBuffer |
+            UniformType::UImage1dArray | UniformType::UImage2dArray |
+            UniformType::UImage2dMultisample |
+            UniformType::UImage2dMultisampleArray => unsigned,
+
+            _ => false,
+        }
+    }
+}
+
+impl ToGlEnum for ImageUnitFormat {
+    #[inline]
+    fn to_glenum(&self) -> gl::types::GLenum {
+        match *self {
+            ImageUnitFormat::RGBA32F => gl::RGBA32F,
+            ImageUnitFormat::RGBA16F => gl::RGBA16F,
+            ImageUnitFormat::RG32F => gl::RG32F,
+            ImageUnitFormat::RG16F => gl::RG16F,
+            ImageUnitFormat::R11FG11FB10F => gl::R11F_G11F_B10F,
+            ImageUnitFormat::R32F => gl::R32F,
+            ImageUnitFormat::R16F => gl::R16F,
+            ImageUnitFormat::RGBA32UI => gl::RGBA32UI,
+            ImageUnitFormat::RGBA16UI => gl::RGBA16UI,
+            ImageUnitFormat::RGB10A2UI => gl::RGB10_A2UI,
+            ImageUnitFormat::RGBA8UI => gl::RGBA8UI,
+            ImageUnitFormat::RG32UI => gl::RG32UI,
+            ImageUnitFormat::RG16UI => gl::RG16UI,
+            ImageUnitFormat::RG8UI => gl::RG8UI,
+            ImageUnitFormat::R32UI => gl::R32UI,
+            ImageUnitFormat::R16UI => gl::R16UI,
+            ImageUnitFormat::R8UI => gl::R8UI,
+            ImageUnitFormat::RGBA32I => gl::RGBA32I,
+            ImageUnitFormat::RGBA16I => gl::RGBA16I,
+            ImageUnitFormat::RGBA8I => gl::RGBA8I,
+            ImageUnitFormat::RG32I => gl::RG32I,
+            ImageUnitFormat::RG16I => gl::RG16I,
+            ImageUnitFormat::RG8I => gl::RG8I,
+            ImageUnitFormat::R32I => gl::R32I,
+            ImageUnitFormat::R16I => gl::R16I,
+            ImageUnitFormat::R8I => gl::R8I,
+            ImageUnitFormat::RGBA16 => gl::RGBA16,
+            ImageUnitFormat::RGB10A2 => gl::RGB10_A2,
+            ImageUnitFormat::RGBA8 => gl::RGBA8,
+            ImageUnitFormat::RG16 => gl::RG16,
+            ImageUnitFormat::RG8 => gl::RG8,
+            ImageUnitFormat::R16 => gl::R16,
+            ImageUnitFormat::R8 => gl::R8,
+            ImageUnitFormat::RGBA16snorm => gl::RGBA16_SNORM,
+            ImageUnitFormat::RGBA8snorm => gl::RGBA8_SNORM,
+            ImageUnitFormat::RG16snorm => gl::RG16_SNORM,
+            ImageUnitFormat::RG8snorm => gl::RG8_SNORM,
+            ImageUnitFormat::R16snorm => gl::R16_SNORM,
+            ImageUnitFormat::R8snorm => gl::R8_SNORM,
+        }
+    }
+}
+
+/// A texture bound to an image unit, for image load and store operations in shaders.
+///
+/// ## Example
+///
+/// ```no_run
+/// # #[macro_use] extern crate glium;
+/// # fn main() {
+/// # let texture: glium::texture::Texture2d = unsafe { std::mem::uninitialized() };
+/// use glium::uniforms::{ImageUnit, ImageUnitAccess, ImageUnitFormat};
+///
+/// let uniforms = uniform! {
+///     output_image: ImageUnit::new(&texture, ImageUnitFormat::RGBA32F)
+///                             .set_access(ImageUnitAccess::Write)
+/// };
+/// # }
+/// ```
+#[derive(Debug, Hash, PartialEq, Eq)]
+pub struct ImageUnit<'t, T: 't>(pub &'t T, pub ImageUnitBehavior);
+
+impl<'t, T: 't> ImageUnit<'t, T> {
+    /// Builds a new `ImageUnit` that gives read and write access to the main level of the
+    /// whole texture.
+    pub fn new(texture: &'t T, format: ImageUnitFormat) -> ImageUnit<'t, T> {
+        ImageUnit(texture, ImageUnitBehavior {
+            format: format,
+            .. Default::default()
+        })
+    }
+
+    /// Changes the mipmap level that is bound.
+    pub fn set_level(mut self, level: u32) -> ImageUnit<'t, T> {
+        self.1.level = level;
+        self
+    }
+
+    /// Binds only one layer of an array, cubemap or 3D texture instead of all of them.
+    pub fn set_layer(mut self, layer: Option<u32>) -> ImageUnit<'t, T> {
+        self.1.layer = layer;
+        self
+    }
+
+    /// Changes the way the shader can access the image.
+    pub fn set_access(mut self, access: ImageUnitAccess) -> ImageUnit<'t, T> {
+        self.1.access = access;
+        self
+    }
+}
+
+impl<'t, T: 't> AsUniformValue for ImageUnit<'t, T> where T: Deref<Target = TextureAny> {
+    #[inline]
+    fn as_uniform_value(&self) -> UniformValue {
+        UniformValue::Image(&**self.0, self.1)
+    }
+}
+
+impl<'t, T: 't> Copy for ImageUnit<'t, T> {}
+
+impl<'t, T: 't> Clone for ImageUnit<'t, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+/// Describes how a texture is bound to an image unit.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub struct ImageUnitBehavior {
+    /// The mipmap level that is bound.
+    pub level: u32,
+
+    /// If `Some`, only this layer of an array, cubemap or 3D texture is bound. Otherwise all
+    /// the layers are bound.
+    pub layer: Option<u32>,
+
+    /// How the shader can access the image.
+    pub access: ImageUnitAccess,
+
+    /// Format in which the texels are interpreted.
+    pub format: ImageUnitFormat,
+}
+
+impl Default for ImageUnitBehavior {
+    #[inline]
+    fn default() -> ImageUnitBehavior {
+        ImageUnitBehavior {
+            level: 0,
+            layer: None,
+            access: ImageUnitAccess::ReadWrite,
+            format: ImageUnitFormat::RGBA32F,
+        }
+    }
+}
@@ -25,6 +25,10 @@ let uniforms = uniform! {
 
 In both situations, each field must implement the `UniformValue` trait.
 
+The vectors, matrices and quaternions of the `cgmath`, `nalgebra` and `glam` libraries can be
+used directly if the corresponding feature of glium is enabled. Quaternions are passed as a
+`vec4` containing `(x, y, z, w)`.
+
 ## Samplers
 
 In order to customize the way a texture is being sampled, you must use a `Sampler`.
@@ -88,6 +92,20 @@ let uniforms = uniform! {
 # }
 ```
 
+## Atomic counters
+
+An atomic counter declared with `layout(binding = N) uniform atomic_uint` is set by passing a
+buffer, like a block. The buffer is bound to the binding point given in the shader, so the
+counter is read at the offset given by `layout(offset = ...)` from the start of the buffer.
+
+## Images
+
+To use image load and store operations on a texture, wrap it in an `ImageUnit` that indicates
+the mipmap level and layer to bind, how the shader accesses the image and the format of its
+texels. The format must match the type of the uniform: `image*` uniforms require a
+floating-point or normalized format, `iimage*` a signed integer format and `uimage*` an
+unsigned integer format.
+
 ## Subroutines
 OpenGL allows the use of subroutines, which are like function pointers. Subroutines can be used
 to change the functionality of a shader program at runtime. This method is usually a lot faster
@@ -143,7 +161,13 @@ let program = glium::Program::from_source(&display,
 pub use self::buffer::UniformBuffer;
 pub use self::sampler::{SamplerWrapFunction, MagnifySamplerFilter, MinifySamplerFilter};
 pub use self::sampler::{Sampler, SamplerBehavior};
-pub use self::uniforms::{EmptyUniforms, UniformsStorage};
+pub use self::image_unit::{ImageUnit, ImageUnitBehavior, ImageUnitAccess, ImageUnitFormat};
+pub use self::std_layout::{StdLayout, StdLayoutKind, LayoutRules, StdArray};
+pub use self::std_layout::{Std140Buffer, Std430Buffer};
+#[doc(hidden)]
+pub use self::std_layout::StdStructLayout;
+pub use self::uniforms::{EmptyUniforms, UniformsStorage, LocatedUniformsStorage};
+pub use self::uniforms::DynamicUniforms;
 pub use self::value::{UniformValue, UniformType};
 
 use std::error::Error;
@@ -151,12 +175,15 @@ use std::fmt;
 
 use buffer::Content as BufferContent;
 use buffer::Buffer;
+use buffer::BufferSlice;
 use program;
 use program::BlockLayout;
 
 mod bind;
 mod buffer;
+mod image_unit;
 mod sampler;
+mod std_layout;
 mod uniforms;
 mod value;
 
@@ -166,6 +193,58 @@ mod value;
 pub trait Uniforms {
     /// Calls the parameter once with the name and value of each uniform.
     fn visit_values<'a, F: FnMut(&str, UniformValue<'a>)>(&'a self, F);
+
+    /// Calls the parameter once with the location and value of each uniform that is identified
+    /// by its location instead of its name.
+    ///
+    /// The default implementation doesn't call the parameter.
+    #[inline]
+    fn visit_located_values<'a, F: FnMut(UniformLocation, UniformValue<'a>)>(&'a self, _: F) {
+    }
+
+    /// Combines these uniforms with another set of uniforms. If both sets contain a value for
+    /// the same uniform, the value of `other` is used.
+    ///
+    /// Since references to uniforms are uniforms as well, sets that are shared between draw
+    /// calls can be combined without being rebuilt.
+    ///
+    /// ## Example
+    ///
+    /// ```no_run
+    /// # #[macro_use] extern crate glium;
+    /// # fn main() {
+    /// use glium::uniforms::Uniforms;
+    ///
+    /// let per_frame = uniform! { time: 1.5f32 };
+    /// let per_material = uniform! { color: [1.0, 0.0, 0.0f32] };
+    ///
+    /// for offset in 0 .. 4 {
+    ///     let uniforms = (&per_frame).concat(&per_material)
+    ///                                .concat(uniform! { offset: offset as f32 });
+    ///     // draw with `&uniforms`
+    /// #   let _ = uniforms;
+    /// }
+    /// # }
+    /// ```
+    #[inline]
+    fn concat<U>(self, other: U) -> (Self, U) where Self: Sized, U: Uniforms {
+        (self, other)
+    }
+}
+
+/// The location of a uniform in a program, as returned by `Program::get_uniform_location`.
+///
+/// Setting uniforms by location avoids having to look up their names at each draw call.
+/// A location is only valid for the program it has been obtained from.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct UniformLocation {
+    /// The location of the uniform.
+    ///
+    /// This is internal information, you probably don't need to use it.
+    pub location: i32,
+
+    /// Type of the uniform.
+    pub ty: UniformType,
 }
 
 /// Error about a block layout mismatch.
@@ -294,22 +373,36 @@ pub trait AsUniformValue {
     fn as_uniform_value(&self) -> UniformValue;
 }
 
-// TODO: no way to bind a slice
 impl<'a, T: ?Sized> AsUniformValue for &'a Buffer<T> where T: UniformBlock + BufferContent {
     #[inline]
     fn as_uniform_value(&self) -> UniformValue {
-        #[inline]
-        fn f<T: ?Sized>(block: &program::UniformBlock)
-                        -> Result<(), LayoutMismatchError> where T: UniformBlock + BufferContent
-        {
-            // TODO: more checks?
-            T::matches(&block.layout, 0)
-        }
+        UniformValue::Block(self.as_slice_any(), block_layout_matches::<T>)
+    }
+}
 
-        UniformValue::Block(self.as_slice_any(), f::<T>)
+/// Binds only the range of the buffer covered by the slice.
+///
+/// The offset of the slice must be a multiple of `uniform_buffer_offset_alignment` (or
+/// `shader_storage_buffer_offset_alignment` for shader storage blocks) in the capabilities of
+/// the context, otherwise drawing returns an error.
+impl<'a, T: ?Sized> AsUniformValue for BufferSlice<'a, T>
+    where T: UniformBlock + BufferContent
+{
+    #[inline]
+    fn as_uniform_value(&self) -> UniformValue {
+        UniformValue::Block(self.as_slice_any(), block_layout_matches::<T>)
     }
 }
 
+#[inline]
+fn block_layout_matches<T: ?Sized>(block: &program::UniformBlock)
+                                   -> Result<(), LayoutMismatchError>
+                                   where T: UniformBlock + BufferContent
+{
+    // TODO: more checks?
+    T::matches(&block.layout, 0)
+}
+
 /// Objects that are suitable for being inside a uniform block or a SSBO.
 pub trait UniformBlock {        // TODO: `: Copy`, but unsized structs don't impl `Copy`
     /// Checks whether the uniforms' layout matches the given block if `Self` starts at
@@ -0,0 +1,583 @@
+use buffer::{Buffer, BufferType, BufferMode, BufferCreationError};
+use uniforms::{AsUniformValue, UniformValue, LayoutMismatchError};
+use program;
+
+use gl;
+use GlObject;
+
+use std::cmp;
+use std::marker::PhantomData;
+use std::mem;
+use std::ptr;
+
+use backend::Facade;
+
+/// The standard rules that determine the offsets of the members of a block.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum LayoutRules {
+    /// The `std140` layout, which can be used by uniform blocks and shader storage blocks.
+    Std140,
+
+    /// The `std430` layout, which can only be used by shader storage blocks. Contrary to
+    /// `std140`, the alignment of arrays and structs is not rounded up to 16 bytes.
+    Std430,
+}
+
+/// Kind of a type that implements `StdLayout`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum StdLayoutKind {
+    /// A scalar.
+    Scalar {
+        /// True if the scalar is a `float` or a `double`.
+        floating: bool,
+    },
+
+    /// A vector. Arrays of two to four vectors of floats are matrices.
+    Vector {
+        /// True if the components are `float`s or `double`s.
+        floating: bool,
+    },
+
+    /// An array or a matrix.
+    Array,
+
+    /// A struct whose implementation has been generated by `implement_std_layout!`.
+    Struct,
+}
+
+/// Types that can be converted to the `std140` or `std430` layouts.
+///
+/// Arrays of two to four scalars are vectors, and arrays of two to four vectors of floats or
+/// doubles are column-major matrices. Wrap an array in a `StdArray` to use it as an actual
+/// array instead. Use the `implement_std_layout!` macro to implement this trait on structs.
+pub trait StdLayout {
+    /// Returns the kind of the type.
+    fn std_kind() -> StdLayoutKind;
+
+    /// Returns the base alignment of the type in bytes.
+    fn std_alignment(rules: LayoutRules) -> usize;
+
+    /// Returns the number of bytes that the type occupies.
+    fn std_size(rules: LayoutRules) -> usize;
+
+    /// Writes the value at the start of `output`, which is at least `std_size(rules)` bytes
+    /// long.
+    fn write_std(&self, rules: LayoutRules, output: &mut [u8]);
+
+    /// Appends the name, the offset and the array stride of each member of the type, named
+    /// the same way as the members of a `program::UniformBlock`.
+    ///
+    /// `name` is the name of the value itself, and is empty for the content of the block.
+    fn std_members(rules: LayoutRules, name: &str, offset: usize,
+                   members: &mut Vec<(String, usize, usize)>);
+}
+
+#[inline]
+fn round_up(value: usize, alignment: usize) -> usize {
+    (value + alignment - 1) / alignment * alignment
+}
+
+macro_rules! impl_scalar {
+    ($t:ty, $floating:expr) => (
+        impl StdLayout for $t {
+            #[inline]
+            fn std_kind() -> StdLayoutKind {
+                StdLayoutKind::Scalar { floating: $floating }
+            }
+
+            #[inline]
+            fn std_alignment(_: LayoutRules) -> usize {
+                mem::size_of::<$t>()
+            }
+
+            #[inline]
+            fn std_size(_: LayoutRules) -> usize {
+                mem::size_of::<$t>()
+            }
+
+            #[inline]
+            fn write_std(&self, _: LayoutRules, output: &mut [u8]) {
+                assert!(output.len() >= mem::size_of::<$t>());
+                unsafe {
+                    ptr::copy_nonoverlapping(self as *const $t as *const u8,
+                                             output.as_mut_ptr(), mem::size_of::<$t>());
+                }
+            }
+
+            #[inline]
+            fn std_members(_: LayoutRules, name: &str, offset: usize,
+                           members: &mut Vec<(String, usize, usize)>)
+            {
+                members.push((name.to_owned(), offset, 0));
+            }
+        }
+    )
+}
+
+impl_scalar!(f32, true);
+impl_scalar!(f64, true);
+impl_scalar!(i32, false);
+impl_scalar!(u32, false);
+
+impl StdLayout for bool {
+    #[inline]
+    fn std_kind() -> StdLayoutKind {
+        StdLayoutKind::Scalar { floating: false }
+    }
+
+    #[inline]
+    fn std_alignment(_: LayoutRules) -> usize {
+        4
+    }
+
+    #[inline]
+    fn std_size(_: LayoutRules) -> usize {
+        4
+    }
+
+    #[inline]
+    fn write_std(&self, rules: LayoutRules, output: &mut [u8]) {
+        // booleans are stored as 32 bits integers
+        (*self as u32).write_std(rules, output)
+    }
+
+    #[inline]
+    fn std_members(_: LayoutRules, name: &str, offset: usize,
+                   members: &mut Vec<(String, usize, usize)>)
+    {
+        members.push((name.to_owned(), offset, 0));
+    }
+}
+
+/// Wrapper around a Rust array that is always laid out as an array.
+///
+/// An array of two to four scalars such as `[f32; 4]` corresponds to a vector like `vec4`.
+/// Use `StdArray([0.0f32; 4])` instead to match a `float[4]` member, whose elements are each
+/// aligned on 16 bytes with the `std140` layout.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct StdArray<A>(pub A);
+
+macro_rules! impl_array {
+    ($($len:expr),+) => (
+        $(
+            impl<T> StdLayout for [T; $len] where T: StdLayout {
+                #[inline]
+                fn std_kind() -> StdLayoutKind {
+                    array_kind::<T>($len)
+                }
+
+                #[inline]
+                fn std_alignment(rules: LayoutRules) -> usize {
+                    array_alignment::<T>(rules, array_kind::<T>($len), $len)
+                }
+
+                #[inline]
+                fn std_size(rules: LayoutRules) -> usize {
+                    array_stride::<T>(rules, array_kind::<T>($len)) * $len
+                }
+
+                #[inline]
+                fn write_std(&self, rules: LayoutRules, output: &mut [u8]) {
+                    write_array(rules, array_kind::<T>($len), &self[..], output)
+                }
+
+                #[inline]
+                fn std_members(rules: LayoutRules, name: &str, offset: usize,
+                               members: &mut Vec<(String, usize, usize)>)
+                {
+                    array_members::<T>(rules, array_kind::<T>($len), $len, name, offset, members)
+                }
+            }
+
+            impl<T> StdLayout for StdArray<[T; $len]> where T: StdLayout {
+                #[inline]
+                fn std_kind() -> StdLayoutKind {
+                    StdLayoutKind::Array
+                }
+
+                #[inline]
+                fn std_alignment(rules: LayoutRules) -> usize {
+                    array_alignment::<T>(rules, StdLayoutKind::Array, $len)
+                }
+
+                #[inline]
+                fn std_size(rules: LayoutRules) -> usize {
+                    array_stride::<T>(rules, StdLayoutKind::Array) * $len
+                }
+
+                #[inline]
+                fn write_std(&self, rules: LayoutRules, output: &mut [u8]) {
+                    write_array(rules, StdLayoutKind::Array, &self.0[..], output)
+                }
+
+                #[inline]
+                fn std_members(rules: LayoutRules, name: &str, offset: usize,
+                               members: &mut Vec<(String, usize, usize)>)
+                {
+                    array_members::<T>(rules, StdLayoutKind::Array, $len, name, offset, members)
+                }
+            }
+        )+
+    )
+}
+
+impl_array!(1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23,
+            24, 25, 26, 27, 28, 29, 30, 31, 32, 64, 128, 256);
+
+/// Returns the kind of an array of `len` elements of type `T`.
+fn array_kind<T>(len: usize) -> StdLayoutKind where T: StdLayout {
+    match T::std_kind() {
+        StdLayoutKind::Scalar { floating } if len >= 2 && len <= 4 => {
+            StdLayoutKind::Vector { floating: floating }
+        },
+        _ => StdLayoutKind::Array,
+    }
+}
+
+/// Returns the base alignment of an array of `len` elements of type `T` laid out as `kind`.
+fn array_alignment<T>(rules: LayoutRules, kind: StdLayoutKind, len: usize) -> usize
+                      where T: StdLayout
+{
+    match (kind, rules) {
+        // a three-components vector is aligned like a four-components vector
+        (StdLayoutKind::Vector { .. }, _) => T::std_size(rules) * if len == 3 { 4 } else { len },
+        (_, LayoutRules::Std140) => round_up(T::std_alignment(rules), 16),
+        (_, LayoutRules::Std430) => T::std_alignment(rules),
+    }
+}
+
+/// Returns the number of bytes between two elements of an array of type `T` laid out as `kind`.
+fn array_stride<T>(rules: LayoutRules, kind: StdLayoutKind) -> usize where T: StdLayout {
+    match kind {
+        StdLayoutKind::Vector { .. } => T::std_size(rules),
+        // the alignment of an array doesn't depend on its length
+        _ => round_up(T::std_size(rules), array_alignment::<T>(rules, kind, 1)),
+    }
+}
+
+fn write_array<T>(rules: LayoutRules, kind: StdLayoutKind, elements: &[T], output: &mut [u8])
+                  where T: StdLayout
+{
+    let stride = array_stride::<T>(rules, kind);
+    for (index, element) in elements.iter().enumerate() {
+        element.write_std(rules, &mut output[index * stride ..]);
+    }
+}
+
+fn array_members<T>(rules: LayoutRules, kind: StdLayoutKind, len: usize, name: &str,
+                    offset: usize, members: &mut Vec<(String, usize, usize)>)
+                    where T: StdLayout
+{
+    let stride = array_stride::<T>(rules, kind);
+
+    match (kind, T::std_kind()) {
+        (StdLayoutKind::Vector { .. }, _) => members.push((name.to_owned(), offset, 0)),
+
+        // each element of an array of structs has its own members
+        (_, StdLayoutKind::Struct) => {
+            for index in 0 .. len {
+                T::std_members(rules, &format!("{}[{}]", name, index), offset + index * stride,
+                               members);
+            }
+        },
+
+        // matrices have the same layout as arrays of columns, and the backend reports them
+        // with an array stride of `0`
+        _ => members.push((name.to_owned(), offset, stride)),
+    }
+}
+
+/// Computes the offsets of the members of a struct. Used by the `implement_std_layout!` macro.
+#[doc(hidden)]
+pub struct StdStructLayout {
+    rules: LayoutRules,
+    offset: usize,
+    alignment: usize,
+}
+
+impl StdStructLayout {
+    /// Starts computing the layout of a struct.
+    #[inline]
+    pub fn new(rules: LayoutRules) -> StdStructLayout {
+        StdStructLayout {
+            rules: rules,
+            offset: 0,
+            alignment: match rules {
+                LayoutRules::Std140 => 16,
+                LayoutRules::Std430 => 1,
+            },
+        }
+    }
+
+    /// Adds the member returned by `field` and returns its offset.
+    #[inline]
+    pub fn push<S, T, F>(&mut self, _: F) -> usize where T: StdLayout, F: Fn(&S) -> &T {
+        let alignment = T::std_alignment(self.rules);
+        let offset = round_up(self.offset, alignment);
+        self.offset = offset + T::std_size(self.rules);
+        self.alignment = cmp::max(self.alignment, match self.rules {
+            LayoutRules::Std140 => round_up(alignment, 16),
+            LayoutRules::Std430 => alignment,
+        });
+        offset
+    }
+
+    /// Returns the base alignment of the struct.
+    #[inline]
+    pub fn alignment(&self) -> usize {
+        self.alignment
+    }
+
+    /// Returns the size of the struct, including the padding at the end.
+    #[inline]
+    pub fn size(&self) -> usize {
+        round_up(self.offset, self.alignment)
+    }
+
+    /// Appends the members of the member returned by `field`.
+    #[inline]
+    pub fn members<S, T, F>(&self, _: F, name: &str, offset: usize,
+                            members: &mut Vec<(String, usize, usize)>)
+                            where T: StdLayout, F: Fn(&S) -> &T
+    {
+        T::std_members(self.rules, name, offset, members)
+    }
+}
+
+/// Converts a value to the given layout.
+fn to_bytes<T>(rules: LayoutRules, value: &T) -> Vec<u8> where T: StdLayout {
+    let size = match rules {
+        // the size of the data of a uniform block is always a multiple of 16
+        LayoutRules::Std140 => round_up(T::std_size(rules), 16),
+        LayoutRules::Std430 => T::std_size(rules),
+    };
+
+    let mut data = vec![0; size];
+    value.write_std(rules, &mut data);
+    data
+}
+
+fn check_std140<T>(block: &program::UniformBlock) -> Result<(), LayoutMismatchError>
+                   where T: StdLayout
+{
+    check_layout::<T>(LayoutRules::Std140, block)
+}
+
+fn check_std430<T>(block: &program::UniformBlock) -> Result<(), LayoutMismatchError>
+                   where T: StdLayout
+{
+    check_layout::<T>(LayoutRules::Std430, block)
+}
+
+/// Checks that the offsets of the members of `block` match the ones of `T`.
+fn check_layout<T>(rules: LayoutRules, block: &program::UniformBlock)
+                   -> Result<(), LayoutMismatchError>
+                   where T: StdLayout
+{
+    let mut expected = Vec::new();
+    T::std_members(rules, "", 0, &mut expected);
+
+    let find = |name: &str| -> Option<(usize, usize)> {
+        // the members of blocks with an instance name are prefixed with the name of the block
+        expected.iter().find(|m| m.0 == name).or_else(|| {
+            name.find('.').and_then(|pos| expected.iter().find(|m| m.0 == &name[pos + 1 ..]))
+        }).map(|m| (m.1, m.2))
+    };
+
+    for member in block.members.iter() {
+        // arrays of basic types are reported as their first element
+        let name = if member.name.ends_with("[0]") {
+            &member.name[.. member.name.len() - 3]
+        } else {
+            &member.name[..]
+        };
+
+        let (offset, stride) = match find(name) {
+            Some(m) => m,
+            None => return Err(LayoutMismatchError::MissingField {
+                name: member.name.clone(),
+            }),
+        };
+
+        if offset != member.offset {
+            return Err(LayoutMismatchError::MemberMismatch {
+                member: member.name.clone(),
+                err: Box::new(LayoutMismatchError::OffsetMismatch {
+                    expected: member.offset,
+                    obtained: offset,
+                }),
+            });
+        }
+
+        if member.array_stride != 0 && stride != member.array_stride {
+            return Err(LayoutMismatchError::MemberMismatch {
+                member: format!("{}[1]", name),
+                err: Box::new(LayoutMismatchError::OffsetMismatch {
+                    expected: member.offset + member.array_stride,
+                    obtained: offset + stride,
+                }),
+            });
+        }
+
+        // members of top-level arrays of structs of shader storage blocks are only reported
+        // for the first element
+        if member.top_level_array_stride != 0 && member.top_level_array_size != 1 {
+            if let Some(pos) = name.find("[0]") {
+                let second = format!("{}[1]{}", &name[.. pos], &name[pos + 3 ..]);
+                let expected_offset = member.offset + member.top_level_array_stride;
+
+                match find(&second) {
+                    Some((offset, _)) if offset != expected_offset => {
+                        return Err(LayoutMismatchError::MemberMismatch {
+                            member: second.clone(),
+                            err: Box::new(LayoutMismatchError::OffsetMismatch {
+                                expected: expected_offset,
+                                obtained: offset,
+                            }),
+                        });
+                    },
+                    _ => ()
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+macro_rules! std_buffer {
+    ($(#[$attr:meta])* struct $name:ident, $rules:expr, $ty:expr, $check:ident) => (
+        $(#[$attr])*
+        #[derive(Debug)]
+        pub struct $name<T> where T: StdLayout {
+            buffer: Buffer<[u8]>,
+            marker: PhantomData<T>,
+        }
+
+        impl<T> GlObject for $name<T> where T: StdLayout {
+            type Id = gl::types::GLuint;
+
+            #[inline]
+            fn get_id(&self) -> gl::types::GLuint {
+                self.buffer.get_id()
+            }
+        }
+
+        impl<T> $name<T> where T: StdLayout {
+            /// Uploads data in the buffer.
+            #[inline]
+            pub fn new<F: ?Sized>(facade: &F, data: &T) -> Result<$name<T>, BufferCreationError>
+                                  where F: Facade
+            {
+                $name::new_impl(facade, data, BufferMode::Default)
+            }
+
+            /// Uploads data in the buffer.
+            #[inline]
+            pub fn dynamic<F: ?Sized>(facade: &F, data: &T)
+                                      -> Result<$name<T>, BufferCreationError>
+                                      where F: Facade
+            {
+                $name::new_impl(facade, data, BufferMode::Dynamic)
+            }
+
+            /// Uploads data in the buffer.
+            #[inline]
+            pub fn persistent<F: ?Sized>(facade: &F, data: &T)
+                                         -> Result<$name<T>, BufferCreationError>
+                                         where F: Facade
+            {
+                $name::new_impl(facade, data, BufferMode::Persistent)
+            }
+
+            /// Uploads data in the buffer.
+            #[inline]
+            pub fn immutable<F: ?Sized>(facade: &F, data: &T)
+                                        -> Result<$name<T>, BufferCreationError>
+                                        where F: Facade
+            {
+                $name::new_impl(facade, data, BufferMode::Immutable)
+            }
+
+            #[inline]
+            fn new_impl<F: ?Sized>(facade: &F, data: &T, mode: BufferMode)
+                                   -> Result<$name<T>, BufferCreationError>
+                                   where F: Facade
+            {
+                let data = to_bytes($rules, data);
+                let buffer = try!(Buffer::new(facade, &data[..], $ty, mode));
+
+                Ok($name {
+                    buffer: buffer,
+                    marker: PhantomData,
+                })
+            }
+
+            /// Replaces the content of the buffer.
+            #[inline]
+            pub fn write(&self, data: &T) {
+                self.buffer.write(&to_bytes($rules, data)[..]);
+            }
+
+            /// Returns the size of the content of the buffer in bytes.
+            #[inline]
+            pub fn get_size(&self) -> usize {
+                self.buffer.get_size()
+            }
+        }
+
+        impl<'a, T> AsUniformValue for &'a $name<T> where T: StdLayout {
+            #[inline]
+            fn as_uniform_value(&self) -> UniformValue {
+                UniformValue::Block(self.buffer.as_slice_any(), $check::<T>)
+            }
+        }
+    )
+}
+
+std_buffer!(
+    /// Buffer that contains a uniform block whose layout is `std140`.
+    ///
+    /// The data of a `UniformBuffer<T>` is uploaded as-is, which means that the Rust struct
+    /// must be manually padded so that its members have the same offsets as in the uniform
+    /// block. A `Std140Buffer<T>` instead converts the value to the `std140` layout when it is
+    /// uploaded, which allows using plain Rust structs.
+    ///
+    /// ## Example
+    ///
+    /// ```no_run
+    /// # #[macro_use] extern crate glium;
+    /// # fn main() {
+    /// # let display: glium::Display = unsafe { std::mem::uninitialized() };
+    /// #[derive(Copy, Clone)]
+    /// struct Light {
+    ///     intensity: f32,
+    ///     color: [f32; 3],    // at offset 16 in the block, but at offset 4 in the Rust struct
+    /// }
+    ///
+    /// implement_std_layout!(Light, intensity, color);
+    ///
+    /// let buffer = glium::uniforms::Std140Buffer::new(&display, &Light {
+    ///     intensity: 0.5,
+    ///     color: [1.0, 1.0, 1.0],
+    /// }).unwrap();
+    ///
+    /// let uniforms = uniform! {
+    ///     Light: &buffer,
+    /// };
+    /// # }
+    /// ```
+    ///
+    /// The block must be declared with `layout(std140)` in the shader. When drawing, the
+    /// offsets of the members of the block are compared with the ones computed from the Rust
+    /// type, and an error is returned if they don't match.
+    struct Std140Buffer, LayoutRules::Std140, BufferType::UniformBuffer, check_std140
+);
+
+std_buffer!(
+    /// Buffer that contains a shader storage block whose layout is `std430`.
+    ///
+    /// Works like `Std140Buffer`, except that the buffer must be bound to a shader storage
+    /// block declared with `layout(std430)`.
+    struct Std430Buffer, LayoutRules::Std430, BufferType::ShaderStorageBuffer, check_std430
+);
@@ -1,4 +1,9 @@
-use uniforms::{Uniforms, UniformValue, AsUniformValue};
+use std::collections::HashMap;
+use std::hash::BuildHasherDefault;
+
+use fnv::FnvHasher;
+
+use uniforms::{Uniforms, UniformValue, AsUniformValue, UniformLocation};
 
 /// Object that can be used when you don't have any uniforms.
 #[derive(Debug, Copy, Clone)]
@@ -52,4 +57,190 @@ impl<'n, T, R> Uniforms for UniformsStorage<'n, T, R> where T: AsUniformValue, R
         output(self.name, self.value.as_uniform_value());
         self.rest.visit_values(output);
     }
+
+    #[inline]
+    fn visit_located_values<'a, F: FnMut(UniformLocation, UniformValue<'a>)>(&'a self,
+                                                                              output: F)
+    {
+        self.rest.visit_located_values(output);
+    }
+}
+
+/// Stores uniforms identified by their location.
+///
+/// This is similar to `UniformsStorage`, except that the uniforms don't have to be looked up
+/// by name when drawing.
+///
+/// # Example
+///
+/// ```no_run
+/// # let display: glium::Display = unsafe { std::mem::uninitialized() };
+/// # let program: glium::Program = unsafe { std::mem::uninitialized() };
+/// // this can be done once when the program is created
+/// let matrix = program.get_uniform_location("matrix").unwrap();
+/// let color = program.get_uniform_location("color").unwrap();
+///
+/// let uniforms = glium::uniforms::LocatedUniformsStorage::new(matrix, [[1.0f32; 4]; 4])
+///                                                        .add(color, [1.0f32, 0.0, 0.0]);
+/// ```
+pub struct LocatedUniformsStorage<T, R> where T: AsUniformValue, R: Uniforms {
+    location: UniformLocation,
+    value: T,
+    rest: R,
+}
+
+impl<T> LocatedUniformsStorage<T, EmptyUniforms> where T: AsUniformValue {
+    /// Builds a new storage with a value.
+    #[inline]
+    pub fn new(location: UniformLocation, value: T) -> LocatedUniformsStorage<T, EmptyUniforms> {
+        LocatedUniformsStorage {
+            location: location,
+            value: value,
+            rest: EmptyUniforms,
+        }
+    }
+}
+
+impl<T, R> LocatedUniformsStorage<T, R> where T: AsUniformValue, R: Uniforms {
+    /// Adds a value to the storage.
+    #[inline]
+    pub fn add<U>(self, location: UniformLocation, value: U)
+                  -> LocatedUniformsStorage<U, LocatedUniformsStorage<T, R>>
+                  where U: AsUniformValue
+    {
+        LocatedUniformsStorage {
+            location: location,
+            value: value,
+            rest: self,
+        }
+    }
+}
+
+impl<T, R> Uniforms for LocatedUniformsStorage<T, R> where T: AsUniformValue, R: Uniforms {
+    #[inline]
+    fn visit_values<'a, F: FnMut(&str, UniformValue<'a>)>(&'a self, output: F) {
+        self.rest.visit_values(output);
+    }
+
+    #[inline]
+    fn visit_located_values<'a, F: FnMut(UniformLocation, UniformValue<'a>)>(&'a self,
+                                                                              mut output: F)
+    {
+        output(self.location, self.value.as_uniform_value());
+        self.rest.visit_located_values(output);
+    }
+}
+
+/// Stores uniforms whose names and values are only known at runtime.
+///
+/// Contrary to the `uniform!` macro, uniforms can be added or removed after the storage has
+/// been created, which is useful for example when the list of parameters of a material is
+/// loaded from a file.
+///
+/// A tuple of two objects that implement `Uniforms` also implements `Uniforms`, which allows
+/// combining this storage with other uniforms.
+///
+/// # Example
+///
+/// ```no_run
+/// # #[macro_use] extern crate glium;
+/// # fn main() {
+/// # let texture: glium::texture::Texture2d = unsafe { std::mem::uninitialized() };
+/// # let matrix = [[1.0f32; 4]; 4];
+/// let mut material = glium::uniforms::DynamicUniforms::new();
+/// material.set("diffuse", &texture);
+/// material.set("shininess", 12.0f32);
+///
+/// let uniforms = (&material, uniform! { matrix: matrix });
+/// # }
+/// ```
+pub struct DynamicUniforms<'a> {
+    values: HashMap<String, Box<AsUniformValue + 'a>, BuildHasherDefault<FnvHasher>>,
+}
+
+impl<'a> DynamicUniforms<'a> {
+    /// Builds a new empty storage.
+    #[inline]
+    pub fn new() -> DynamicUniforms<'a> {
+        DynamicUniforms {
+            values: HashMap::with_hasher(Default::default()),
+        }
+    }
+
+    /// Sets the value of a uniform, replacing the previous value if there was one.
+    #[inline]
+    pub fn set<N, T>(&mut self, name: N, value: T) where N: Into<String>, T: AsUniformValue + 'a {
+        self.values.insert(name.into(), Box::new(value));
+    }
+
+    /// Removes a uniform from the storage. Returns false if there was no uniform with this name.
+    #[inline]
+    pub fn remove(&mut self, name: &str) -> bool {
+        self.values.remove(name).is_some()
+    }
+
+    /// Returns true if the storage contains a uniform with this name.
+    #[inline]
+    pub fn contains(&self, name: &str) -> bool {
+        self.values.contains_key(name)
+    }
+
+    /// Returns the number of uniforms in the storage.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns true if the storage is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Removes all the uniforms from the storage.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.values.clear();
+    }
+}
+
+impl<'b> Uniforms for DynamicUniforms<'b> {
+    #[inline]
+    fn visit_values<'a, F: FnMut(&str, UniformValue<'a>)>(&'a self, mut output: F) {
+        for (name, value) in self.values.iter() {
+            output(name, value.as_uniform_value());
+        }
+    }
+}
+
+impl<'r, U: ?Sized> Uniforms for &'r U where U: Uniforms {
+    #[inline]
+    fn visit_values<'a, F: FnMut(&str, UniformValue<'a>)>(&'a self, output: F) {
+        (**self).visit_values(output);
+    }
+
+    #[inline]
+    fn visit_located_values<'a, F: FnMut(UniformLocation, UniformValue<'a>)>(&'a self,
+                                                                              output: F)
+    {
+        (**self).visit_located_values(output);
+    }
+}
+
+/// Combines two sets of uniforms. If both contain a value for the same uniform, the value of
+/// the second one is used.
+impl<A, B> Uniforms for (A, B) where A: Uniforms, B: Uniforms {
+    #[inline]
+    fn visit_values<'a, F: FnMut(&str, UniformValue<'a>)>(&'a self, mut output: F) {
+        self.0.visit_values(&mut output);
+        self.1.visit_values(output);
+    }
+
+    #[inline]
+    fn visit_located_values<'a, F: FnMut(UniformLocation, UniformValue<'a>)>(&'a self,
+                                                                              mut output: F)
+    {
+        self.0.visit_located_values(&mut output);
+        self.1.visit_located_values(output);
+    }
 }
@@ -7,6 +7,7 @@ use uniforms::AsUniformValue;
 use uniforms::LayoutMismatchError;
 use uniforms::UniformBlock;
 use uniforms::SamplerBehavior;
+use uniforms::ImageUnitBehavior;
 
 use buffer::BufferAnySlice;
 
@@ -131,6 +132,95 @@ pub enum UniformType {
     IImage2dMultisampleArray,
     UImage2dMultisampleArray,
     AtomicCounterUint,
+    SamplerExternalOes,
+}
+
+impl UniformType {
+    /// Returns true if a uniform of this type is a sampler, in other words if it is bound to a
+    /// texture unit.
+    pub fn is_sampler(&self) -> bool {
+        match *self {
+            UniformType::Sampler1d | UniformType::ISampler1d | UniformType::USampler1d |
+            UniformType::Sampler2d | UniformType::ISampler2d | UniformType::USampler2d |
+            UniformType::Sampler3d | UniformType::ISampler3d | UniformType::USampler3d |
+            UniformType::Sampler1dArray | UniformType::ISampler1dArray |
+            UniformType::USampler1dArray | UniformType::Sampler2dArray |
+            UniformType::ISampler2dArray | UniformType::USampler2dArray |
+            UniformType::SamplerCube | UniformType::ISamplerCube | UniformType::USamplerCube |
+            UniformType::Sampler2dRect | UniformType::ISampler2dRect |
+            UniformType::USampler2dRect | UniformType::Sampler2dRectShadow |
+            UniformType::SamplerCubeArray | UniformType::ISamplerCubeArray |
+            UniformType::USamplerCubeArray | UniformType::SamplerBuffer |
+            UniformType::ISamplerBuffer | UniformType::USamplerBuffer |
+            UniformType::Sampler2dMultisample | UniformType::ISampler2dMultisample |
+            UniformType::USampler2dMultisample | UniformType::Sampler2dMultisampleArray |
+            UniformType::ISampler2dMultisampleArray | UniformType::USampler2dMultisampleArray |
+            UniformType::Sampler1dShadow | UniformType::Sampler2dShadow |
+            UniformType::SamplerCubeShadow | UniformType::Sampler1dArrayShadow |
+            UniformType::Sampler2dArrayShadow | UniformType::SamplerCubeArrayShadow |
+            UniformType::SamplerExternalOes => true,
+            _ => false
+        }
+    }
+}
+
+/// Returns true if the part of the texture described by `behavior` can be bound to an image
+/// uniform of the given type, ignoring whether the type is signed or unsigned.
+fn image_matches_dimensions(texture: &texture::TextureAny, behavior: &ImageUnitBehavior,
+                            ty: UniformType) -> bool
+{
+    use texture::Dimensions;
+
+    let ty = match ty {
+        UniformType::IImage1d | UniformType::UImage1d => UniformType::Image1d,
+        UniformType::IImage2d | UniformType::UImage2d => UniformType::Image2d,
+        UniformType::IImage3d | UniformType::UImage3d => UniformType::Image3d,
+        UniformType::IImageCube | UniformType::UImageCube => UniformType::ImageCube,
+        UniformType::IImage1dArray | UniformType::UImage1dArray => UniformType::Image1dArray,
+        UniformType::IImage2dArray | UniformType::UImage2dArray => UniformType::Image2dArray,
+        UniformType::IImage2dMultisample | UniformType::UImage2dMultisample => {
+            UniformType::Image2dMultisample
+        },
+        UniformType::IImage2dMultisampleArray | UniformType::UImage2dMultisampleArray => {
+            UniformType::Image2dMultisampleArray
+        },
+        ty => ty,
+    };
+
+    if behavior.level >= texture.get_mipmap_levels() {
+        return false;
+    }
+
+    // binding a single layer turns an array, a cubemap or a 3D texture into its element type
+    match (texture.dimensions(), behavior.layer) {
+        (Dimensions::Texture1d { .. }, None) => ty == UniformType::Image1d,
+        (Dimensions::Texture1dArray { .. }, None) => ty == UniformType::Image1dArray,
+        (Dimensions::Texture1dArray { array_size, .. }, Some(layer)) => {
+            layer < array_size && ty == UniformType::Image1d
+        },
+        (Dimensions::Texture2d { .. }, None) => ty == UniformType::Image2d,
+        (Dimensions::Texture2dArray { .. }, None) => ty == UniformType::Image2dArray,
+        (Dimensions::Texture2dArray { array_size, .. }, Some(layer)) => {
+            layer < array_size && ty == UniformType::Image2d
+        },
+        (Dimensions::Texture2dMultisample { .. }, None) => ty == UniformType::Image2dMultisample,
+        (Dimensions::Texture2dMultisampleArray { .. }, None) => {
+            ty == UniformType::Image2dMultisampleArray
+        },
+        (Dimensions::Texture2dMultisampleArray { array_size, .. }, Some(layer)) => {
+            layer < array_size && ty == UniformType::Image2dMultisample
+        },
+        (Dimensions::Texture3d { .. }, None) => ty == UniformType::Image3d,
+        (Dimensions::Texture3d { depth, .. }, Some(layer)) => {
+            layer < depth && ty == UniformType::Image2d
+        },
+        (Dimensions::Cubemap { .. }, None) => ty == UniformType::ImageCube,
+        (Dimensions::Cubemap { .. }, Some(layer)) => layer < 6 && ty == UniformType::Image2d,
+        (Dimensions::CubemapArray { array_size, .. }, Some(layer)) => {
+            layer < 6 * array_size && ty == UniformType::Image2d
+        },
+        _ => false,
+    }
 }
 
 /// Represents a value to bind to a uniform.
@@ -152,6 +242,18 @@ pub enum UniformValue<'a> {
     Mat3([[f32; 3]; 3]),
     /// 4x4 column-major matrix.
     Mat4([[f32; 4]; 4]),
+    /// Column-major matrix with 2 columns and 3 rows.
+    Mat2x3([[f32; 3]; 2]),
+    /// Column-major matrix with 2 columns and 4 rows.
+    Mat2x4([[f32; 4]; 2]),
+    /// Column-major matrix with 3 columns and 2 rows.
+    Mat3x2([[f32; 2]; 3]),
+    /// Column-major matrix with 3 columns and 4 rows.
+    Mat3x4([[f32; 4]; 3]),
+    /// Column-major matrix with 4 columns and 2 rows.
+    Mat4x2([[f32; 2]; 4]),
+    /// Column-major matrix with 4 columns and 3 rows.
+    Mat4x3([[f32; 3]; 4]),
     Vec2([f32; 2]),
     Vec3([f32; 3]),
     Vec4([f32; 4]),
@@ -180,6 +282,28 @@ pub enum UniformValue<'a> {
     UnsignedInt64Vec2([u64; 2]),
     UnsignedInt64Vec3([u64; 3]),
     UnsignedInt64Vec4([u64; 4]),
+    FloatArray(&'a [f32]),
+    Vec2Array(&'a [[f32; 2]]),
+    Vec3Array(&'a [[f32; 3]]),
+    Vec4Array(&'a [[f32; 4]]),
+    /// Array of 2x2 column-major matrices.
+    Mat2Array(&'a [[[f32; 2]; 2]]),
+    /// Array of 3x3 column-major matrices.
+    Mat3Array(&'a [[[f32; 3]; 3]]),
+    /// Array of 4x4 column-major matrices.
+    Mat4Array(&'a [[[f32; 4]; 4]]),
+    SignedIntArray(&'a [i32]),
+    IntVec2Array(&'a [[i32; 2]]),
+    IntVec3Array(&'a [[i32; 3]]),
+    IntVec4Array(&'a [[i32; 4]]),
+    UnsignedIntArray(&'a [u32]),
+    UnsignedIntVec2Array(&'a [[u32; 2]]),
+    UnsignedIntVec3Array(&'a [[u32; 3]]),
+    UnsignedIntVec4Array(&'a [[u32; 4]]),
+    /// Handle of a bindless texture, for a sampler or a `uint64_t` uniform.
+    Handle(u64),
+    /// Array of handles of bindless textures.
+    HandleArray(&'a [texture::TextureHandle<'a>]),
     Texture1d(&'a texture::Texture1d, Option<SamplerBehavior>),
     CompressedTexture1d(&'a texture::CompressedTexture1d, Option<SamplerBehavior>),
     SrgbTexture1d(&'a texture::SrgbTexture1d, Option<SamplerBehavior>),
@@ -240,6 +364,10 @@ pub enum UniformValue<'a> {
     UnsignedCubemapArray(&'a texture::UnsignedCubemapArray, Option<SamplerBehavior>),
     DepthCubemapArray(&'a texture::DepthCubemapArray, Option<SamplerBehavior>),
     BufferTexture(texture::buffer_texture::BufferTextureRef<'a>),
+    /// A texture whose content is an `EGLImage`, for a `samplerExternalOES` uniform.
+    ExternalTexture(&'a texture::egl_image::ExternalTexture, Option<SamplerBehavior>),
+    /// A texture bound to an image unit, for a `image*`, `iimage*` or `uimage*` uniform.
+    Image(&'a texture::TextureAny, ImageUnitBehavior),
 }
 
 impl<'a> Clone for UniformValue<'a> {
@@ -260,6 +388,12 @@ impl<'a> UniformValue<'a> {
             (&UniformValue::Mat2(_), UniformType::FloatMat2) => true,
             (&UniformValue::Mat3(_), UniformType::FloatMat3) => true,
             (&UniformValue::Mat4(_), UniformType::FloatMat4) => true,
+            (&UniformValue::Mat2x3(_), UniformType::FloatMat2x3) => true,
+            (&UniformValue::Mat2x4(_), UniformType::FloatMat2x4) => true,
+            (&UniformValue::Mat3x2(_), UniformType::FloatMat3x2) => true,
+            (&UniformValue::Mat3x4(_), UniformType::FloatMat3x4) => true,
+            (&UniformValue::Mat4x2(_), UniformType::FloatMat4x2) => true,
+            (&UniformValue::Mat4x3(_), UniformType::FloatMat4x3) => true,
             (&UniformValue::Vec2(_), UniformType::FloatVec2) => true,
             (&UniformValue::Vec3(_), UniformType::FloatVec3) => true,
             (&UniformValue::Vec4(_), UniformType::FloatVec4) => true,
@@ -279,6 +413,24 @@ impl<'a> UniformValue<'a> {
             (&UniformValue::DoubleVec2(_), UniformType::DoubleVec2) => true,
             (&UniformValue::DoubleVec3(_), UniformType::DoubleVec3) => true,
             (&UniformValue::DoubleVec4(_), UniformType::DoubleVec4) => true,
+            (&UniformValue::FloatArray(_), UniformType::Float) => true,
+            (&UniformValue::Vec2Array(_), UniformType::FloatVec2) => true,
+            (&UniformValue::Vec3Array(_), UniformType::FloatVec3) => true,
+            (&UniformValue::Vec4Array(_), UniformType::FloatVec4) => true,
+            (&UniformValue::Mat2Array(_), UniformType::FloatMat2) => true,
+            (&UniformValue::Mat3Array(_), UniformType::FloatMat3) => true,
+            (&UniformValue::Mat4Array(_), UniformType::FloatMat4) => true,
+            (&UniformValue::SignedIntArray(_), UniformType::Int) => true,
+            (&UniformValue::IntVec2Array(_), UniformType::IntVec2) => true,
+            (&UniformValue::IntVec3Array(_), UniformType::IntVec3) => true,
+            (&UniformValue::IntVec4Array(_), UniformType::IntVec4) => true,
+            (&UniformValue::UnsignedIntArray(_), UniformType::UnsignedInt) => true,
+            (&UniformValue::UnsignedIntVec2Array(_), UniformType::UnsignedIntVec2) => true,
+            (&UniformValue::UnsignedIntVec3Array(_), UniformType::UnsignedIntVec3) => true,
+            (&UniformValue::UnsignedIntVec4Array(_), UniformType::UnsignedIntVec4) => true,
+            (&UniformValue::Handle(_), t) | (&UniformValue::HandleArray(_), t) => {
+                t.is_sampler() || t == UniformType::UnsignedInt64
+            },
             (&UniformValue::Texture1d(_, _), UniformType::Sampler1d) => true,
             (&UniformValue::CompressedTexture1d(_, _), UniformType::Sampler1d) => true,
             (&UniformValue::SrgbTexture1d(_, _), UniformType::Sampler1d) => true,
@@ -342,9 +494,37 @@ impl<'a> UniformValue<'a> {
             (&UniformValue::IntegralTexture2dMultisample(..), UniformType::ISampler2dMultisample) => true,
             (&UniformValue::UnsignedTexture2dMultisample(..), UniformType::USampler2dMultisample) => true,
             (&UniformValue::DepthTexture2dMultisample(..), UniformType::Sampler2dMultisample) => true,
+            (&UniformValue::ExternalTexture(..), UniformType::SamplerExternalOes) => true,
+            (&UniformValue::Image(texture, ref behavior), ty) => {
+                behavior.format.is_usable_with(&ty) &&
+                    image_matches_dimensions(texture, behavior, ty)
+            },
             _ => false,
         }
     }
+
+    /// Returns the number of elements if this value is an array of values.
+    pub fn array_len(&self) -> Option<usize> {
+        match *self {
+            UniformValue::FloatArray(v) => Some(v.len()),
+            UniformValue::Vec2Array(v) => Some(v.len()),
+            UniformValue::Vec3Array(v) => Some(v.len()),
+            UniformValue::Vec4Array(v) => Some(v.len()),
+            UniformValue::Mat2Array(v) => Some(v.len()),
+            UniformValue::Mat3Array(v) => Some(v.len()),
+            UniformValue::Mat4Array(v) => Some(v.len()),
+            UniformValue::SignedIntArray(v) => Some(v.len()),
+            UniformValue::IntVec2Array(v) => Some(v.len()),
+            UniformValue::IntVec3Array(v) => Some(v.len()),
+            UniformValue::IntVec4Array(v) => Some(v.len()),
+            UniformValue::UnsignedIntArray(v) => Some(v.len()),
+            UniformValue::UnsignedIntVec2Array(v) => Some(v.len()),
+            UniformValue::UnsignedIntVec3Array(v) => Some(v.len()),
+            UniformValue::UnsignedIntVec4Array(v) => Some(v.len()),
+            UniformValue::HandleArray(v) => Some(v.len()),
+            _ => None,
+        }
+    }
 }
 
 macro_rules! impl_uniform_block_basic {
@@ -698,6 +878,60 @@ impl AsUniformValue for [[f32; 4]; 4] {
 
 impl_uniform_block_basic!([[f32; 4]; 4], UniformType::FloatMat4);
 
+impl AsUniformValue for [[f32; 3]; 2] {
+    #[inline]
+    fn as_uniform_value(&self) -> UniformValue {
+        UniformValue::Mat2x3(*self)
+    }
+}
+
+impl_uniform_block_basic!([[f32; 3]; 2], UniformType::FloatMat2x3);
+
+impl AsUniformValue for [[f32; 4]; 2] {
+    #[inline]
+    fn as_uniform_value(&self) -> UniformValue {
+        UniformValue::Mat2x4(*self)
+    }
+}
+
+impl_uniform_block_basic!([[f32; 4]; 2], UniformType::FloatMat2x4);
+
+impl AsUniformValue for [[f32; 2]; 3] {
+    #[inline]
+    fn as_uniform_value(&self) -> UniformValue {
+        UniformValue::Mat3x2(*self)
+    }
+}
+
+impl_uniform_block_basic!([[f32; 2]; 3], UniformType::FloatMat3x2);
+
+impl AsUniformValue for [[f32; 4]; 3] {
+    #[inline]
+    fn as_uniform_value(&self) -> UniformValue {
+        UniformValue::Mat3x4(*self)
+    }
+}
+
+impl_uniform_block_basic!([[f32; 4]; 3], UniformType::FloatMat3x4);
+
+impl AsUniformValue for [[f32; 2]; 4] {
+    #[inline]
+    fn as_uniform_value(&self) -> UniformValue {
+        UniformValue::Mat4x2(*self)
+    }
+}
+
+impl_uniform_block_basic!([[f32; 2]; 4], UniformType::FloatMat4x2);
+
+impl AsUniformValue for [[f32; 3]; 4] {
+    #[inline]
+    fn as_uniform_value(&self) -> UniformValue {
+        UniformValue::Mat4x3(*self)
+    }
+}
+
+impl_uniform_block_basic!([[f32; 3]; 4], UniformType::FloatMat4x3);
+
 impl AsUniformValue for (f32, f32) {
     #[inline]
     fn as_uniform_value(&self) -> UniformValue {
@@ -970,6 +1204,34 @@ impl AsUniformValue for (u64, u64, u64, u64) {
 
 impl_uniform_block_basic!((u64, u64, u64, u64), UniformType::UnsignedInt64Vec4);
 
+// Arrays, uploaded in a single call
+macro_rules! impl_uniform_array {
+    ($ty:ty, $variant:ident) => (
+        impl<'a> AsUniformValue for &'a [$ty] {
+            #[inline]
+            fn as_uniform_value(&self) -> UniformValue {
+                UniformValue::$variant(*self)
+            }
+        }
+    )
+}
+
+impl_uniform_array!(f32, FloatArray);
+impl_uniform_array!([f32; 2], Vec2Array);
+impl_uniform_array!([f32; 3], Vec3Array);
+impl_uniform_array!([f32; 4], Vec4Array);
+impl_uniform_array!([[f32; 2]; 2], Mat2Array);
+impl_uniform_array!([[f32; 3]; 3], Mat3Array);
+impl_uniform_array!([[f32; 4]; 4], Mat4Array);
+impl_uniform_array!(i32, SignedIntArray);
+impl_uniform_array!([i32; 2], IntVec2Array);
+impl_uniform_array!([i32; 3], IntVec3Array);
+impl_uniform_array!([i32; 4], IntVec4Array);
+impl_uniform_array!(u32, UnsignedIntArray);
+impl_uniform_array!([u32; 2], UnsignedIntVec2Array);
+impl_uniform_array!([u32; 3], UnsignedIntVec3Array);
+impl_uniform_array!([u32; 4], UnsignedIntVec4Array);
+
 // Subroutines
 impl<'a> AsUniformValue for (&'a str, ShaderStage) {
     #[inline]
@@ -977,3 +1239,113 @@ impl<'a> AsUniformValue for (&'a str, ShaderStage) {
         UniformValue::Subroutine(self.1, self.0)
     }
 }
+
+// Types of the math libraries
+macro_rules! impl_uniform_value {
+    ($ty:ty, |$v:ident| $value:expr) => (
+        impl AsUniformValue for $ty {
+            #[inline]
+            fn as_uniform_value(&self) -> UniformValue {
+                let $v = self;
+                $value
+            }
+        }
+    )
+}
+
+#[cfg(feature = "cgmath")]
+mod cgmath_values {
+    use cgmath;
+    use uniforms::{AsUniformValue, UniformValue};
+
+    impl_uniform_value!(cgmath::Vector2<f32>, |v| UniformValue::Vec2((*v).into()));
+    impl_uniform_value!(cgmath::Vector3<f32>, |v| UniformValue::Vec3((*v).into()));
+    impl_uniform_value!(cgmath::Vector4<f32>, |v| UniformValue::Vec4((*v).into()));
+    impl_uniform_value!(cgmath::Vector2<f64>, |v| UniformValue::DoubleVec2((*v).into()));
+    impl_uniform_value!(cgmath::Vector3<f64>, |v| UniformValue::DoubleVec3((*v).into()));
+    impl_uniform_value!(cgmath::Vector4<f64>, |v| UniformValue::DoubleVec4((*v).into()));
+    impl_uniform_value!(cgmath::Vector2<i32>, |v| UniformValue::IntVec2((*v).into()));
+    impl_uniform_value!(cgmath::Vector3<i32>, |v| UniformValue::IntVec3((*v).into()));
+    impl_uniform_value!(cgmath::Vector4<i32>, |v| UniformValue::IntVec4((*v).into()));
+    impl_uniform_value!(cgmath::Vector2<u32>, |v| UniformValue::UnsignedIntVec2((*v).into()));
+    impl_uniform_value!(cgmath::Vector3<u32>, |v| UniformValue::UnsignedIntVec3((*v).into()));
+    impl_uniform_value!(cgmath::Vector4<u32>, |v| UniformValue::UnsignedIntVec4((*v).into()));
+    impl_uniform_value!(cgmath::Point2<f32>, |v| UniformValue::Vec2((*v).into()));
+    impl_uniform_value!(cgmath::Point3<f32>, |v| UniformValue::Vec3((*v).into()));
+    impl_uniform_value!(cgmath::Point2<f64>, |v| UniformValue::DoubleVec2((*v).into()));
+    impl_uniform_value!(cgmath::Point3<f64>, |v| UniformValue::DoubleVec3((*v).into()));
+    impl_uniform_value!(cgmath::Matrix2<f32>, |m| UniformValue::Mat2((*m).into()));
+    impl_uniform_value!(cgmath::Matrix3<f32>, |m| UniformValue::Mat3((*m).into()));
+    impl_uniform_value!(cgmath::Matrix4<f32>, |m| UniformValue::Mat4((*m).into()));
+    impl_uniform_value!(cgmath::Matrix2<f64>, |m| UniformValue::DoubleMat2((*m).into()));
+    impl_uniform_value!(cgmath::Matrix3<f64>, |m| UniformValue::DoubleMat3((*m).into()));
+    impl_uniform_value!(cgmath::Matrix4<f64>, |m| UniformValue::DoubleMat4((*m).into()));
+
+    // quaternions are passed as `vec4(x, y, z, w)`
+    impl_uniform_value!(cgmath::Quaternion<f32>,
+                        |q| UniformValue::Vec4([q.v.x, q.v.y, q.v.z, q.s]));
+    impl_uniform_value!(cgmath::Quaternion<f64>,
+                        |q| UniformValue::DoubleVec4([q.v.x, q.v.y, q.v.z, q.s]));
+}
+
+#[cfg(feature = "nalgebra")]
+mod nalgebra_values {
+    use nalgebra;
+    use uniforms::{AsUniformValue, UniformValue};
+
+    impl_uniform_value!(nalgebra::Vec2<f32>, |v| UniformValue::Vec2(*v.as_ref()));
+    impl_uniform_value!(nalgebra::Vec3<f32>, |v| UniformValue::Vec3(*v.as_ref()));
+    impl_uniform_value!(nalgebra::Vec4<f32>, |v| UniformValue::Vec4(*v.as_ref()));
+    impl_uniform_value!(nalgebra::Vec2<f64>, |v| UniformValue::DoubleVec2(*v.as_ref()));
+    impl_uniform_value!(nalgebra::Vec3<f64>, |v| UniformValue::DoubleVec3(*v.as_ref()));
+    impl_uniform_value!(nalgebra::Vec4<f64>, |v| UniformValue::DoubleVec4(*v.as_ref()));
+    impl_uniform_value!(nalgebra::Vec2<i32>, |v| UniformValue::IntVec2(*v.as_ref()));
+    impl_uniform_value!(nalgebra::Vec3<i32>, |v| UniformValue::IntVec3(*v.as_ref()));
+    impl_uniform_value!(nalgebra::Vec4<i32>, |v| UniformValue::IntVec4(*v.as_ref()));
+    impl_uniform_value!(nalgebra::Vec2<u32>, |v| UniformValue::UnsignedIntVec2(*v.as_ref()));
+    impl_uniform_value!(nalgebra::Vec3<u32>, |v| UniformValue::UnsignedIntVec3(*v.as_ref()));
+    impl_uniform_value!(nalgebra::Vec4<u32>, |v| UniformValue::UnsignedIntVec4(*v.as_ref()));
+    impl_uniform_value!(nalgebra::Pnt2<f32>, |v| UniformValue::Vec2(*v.as_ref()));
+    impl_uniform_value!(nalgebra::Pnt3<f32>, |v| UniformValue::Vec3(*v.as_ref()));
+    impl_uniform_value!(nalgebra::Pnt4<f32>, |v| UniformValue::Vec4(*v.as_ref()));
+    impl_uniform_value!(nalgebra::Pnt2<f64>, |v| UniformValue::DoubleVec2(*v.as_ref()));
+    impl_uniform_value!(nalgebra::Pnt3<f64>, |v| UniformValue::DoubleVec3(*v.as_ref()));
+    impl_uniform_value!(nalgebra::Pnt4<f64>, |v| UniformValue::DoubleVec4(*v.as_ref()));
+
+    // the matrices of nalgebra are stored in column-major order
+    impl_uniform_value!(nalgebra::Mat2<f32>, |m| UniformValue::Mat2(*m.as_ref()));
+    impl_uniform_value!(nalgebra::Mat3<f32>, |m| UniformValue::Mat3(*m.as_ref()));
+    impl_uniform_value!(nalgebra::Mat4<f32>, |m| UniformValue::Mat4(*m.as_ref()));
+    impl_uniform_value!(nalgebra::Mat2<f64>, |m| UniformValue::DoubleMat2(*m.as_ref()));
+    impl_uniform_value!(nalgebra::Mat3<f64>, |m| UniformValue::DoubleMat3(*m.as_ref()));
+    impl_uniform_value!(nalgebra::Mat4<f64>, |m| UniformValue::DoubleMat4(*m.as_ref()));
+
+    // quaternions are passed as `vec4(x, y, z, w)`
+    impl_uniform_value!(nalgebra::Quat<f32>, |q| UniformValue::Vec4([q.i, q.j, q.k, q.w]));
+    impl_uniform_value!(nalgebra::Quat<f64>,
+                        |q| UniformValue::DoubleVec4([q.i, q.j, q.k, q.w]));
+    impl_uniform_value!(nalgebra::UnitQuat<f32>, |q| {
+        let q = q.quat();
+        UniformValue::Vec4([q.i, q.j, q.k, q.w])
+    });
+    impl_uniform_value!(nalgebra::UnitQuat<f64>, |q| {
+        let q = q.quat();
+        UniformValue::DoubleVec4([q.i, q.j, q.k, q.w])
+    });
+}
+
+#[cfg(feature = "glam")]
+mod glam_values {
+    use glam;
+    use uniforms::{AsUniformValue, UniformValue};
+
+    impl_uniform_value!(glam::Vec2, |v| UniformValue::Vec2((*v).into()));
+    impl_uniform_value!(glam::Vec3, |v| UniformValue::Vec3((*v).into()));
+    impl_uniform_value!(glam::Vec4, |v| UniformValue::Vec4((*v).into()));
+    impl_uniform_value!(glam::Mat2, |m| UniformValue::Mat2(m.to_cols_array_2d()));
+    impl_uniform_value!(glam::Mat3, |m| UniformValue::Mat3(m.to_cols_array_2d()));
+    impl_uniform_value!(glam::Mat4, |m| UniformValue::Mat4(m.to_cols_array_2d()));
+
+    // quaternions are passed as `vec4(x, y, z, w)`
+    impl_uniform_value!(glam::Quat, |q| UniformValue::Vec4((*q).into()));
+}
@@ -10,6 +10,8 @@ use CapabilitiesSource;
 use cgmath;
 #[cfg(feature = "nalgebra")]
 use nalgebra;
+#[cfg(feature = "glam")]
+use glam;
 
 #[allow(missing_docs)]
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -2094,6 +2096,62 @@ unsafe impl Attribute for nalgebra::Mat4<f64> {
     }
 }
 
+#[cfg(feature="glam")]
+unsafe impl Attribute for glam::Vec2 {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::F32F32
+    }
+}
+
+#[cfg(feature="glam")]
+unsafe impl Attribute for glam::Vec3 {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::F32F32F32
+    }
+}
+
+#[cfg(feature="glam")]
+unsafe impl Attribute for glam::Vec4 {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::F32F32F32F32
+    }
+}
+
+#[cfg(feature="glam")]
+unsafe impl Attribute for glam::Mat2 {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::F32x2x2
+    }
+}
+
+#[cfg(feature="glam")]
+unsafe impl Attribute for glam::Mat3 {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::F32x3x3
+    }
+}
+
+#[cfg(feature="glam")]
+unsafe impl Attribute for glam::Mat4 {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::F32x4x4
+    }
+}
+
+#[cfg(feature="glam")]
+unsafe impl Attribute for glam::Quat {
+    #[inline]
+    fn get_type() -> AttributeType {
+        AttributeType::F32F32F32F32
+    }
+}
+
 
 #[cfg(test)]
 mod tests {
@@ -2278,4 +2336,25 @@ mod tests {
                                                                        [8.0f64, 9.0, 10.0, 11.0],
                                                                        [12.0f64, 13.0, 14.0, 15.0]]);
     }
+
+    #[cfg(feature = "glam")]
+    #[test]
+    fn test_glam_layout() {
+        use glam;
+
+        test_layout_val!(glam::Vec2::from, f32, 2, [0.0f32, 1.0]);
+        test_layout_val!(glam::Vec3::from, f32, 3, [0.0f32, 1.0, 2.0]);
+        test_layout_val!(glam::Vec4::from, f32, 4, [0.0f32, 1.0, 2.0, 3.0]);
+        test_layout_val!(glam::Quat::from, f32, 4, [0.0f32, 1.0, 2.0, 3.0]);
+
+        test_layout_val!(glam::Mat2::from_cols_array_2d, [f32; 2], 2, [[0.0f32, 1.0],
+                                                                       [2.0f32, 3.0]]);
+        test_layout_val!(glam::Mat3::from_cols_array_2d, [f32; 3], 3, [[0.0f32, 1.0, 2.0],
+                                                                       [3.0f32, 4.0, 5.0],
+                                                                       [6.0f32, 7.0, 8.0]]);
+        test_layout_val!(glam::Mat4::from_cols_array_2d, [f32; 4], 4, [[0.0f32, 1.0, 2.0, 3.0],
+                                                                       [4.0f32, 5.0, 6.0, 7.0],
+                                                                       [8.0f32, 9.0, 10.0, 11.0],
+                                                                       [12.0f32, 13.0, 14.0, 15.0]]);
+    }
 }
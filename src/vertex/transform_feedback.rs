@@ -1,4 +1,5 @@
 use std::{ mem, fmt };
+use std::cell::{Cell, RefCell};
 use std::error::Error;
 
 use version::Api;
@@ -9,6 +10,7 @@ use BufferExt;
 use GlObject;
 use ContextExt;
 use CapabilitiesSource;
+use DrawError;
 use TransformFeedbackSessionExt;
 use buffer::{Buffer, BufferAnySlice};
 use index::PrimitiveType;
@@ -41,9 +43,10 @@ use gl;
 ///   program than the one you created the session with.
 ///
 /// - The transform feedback process doesn't necessarily fill the whole buffer. To retrieve the
-///   number of vertices that are written to the buffer, use a query object (see the
-///   `draw_parameters` module). It is however usually easy to determine in advance the number of
-///   vertices that will be written based on the input data.
+///   number of primitives that are written to the buffer, call `get_primitives_written` or use a
+///   `TransformFeedbackPrimitivesWrittenQuery` (see the `draw_parameters` module). It is however
+///   usually easy to determine in advance the number of vertices that will be written based on
+///   the input data.
 ///
 /// - The buffer will obtain either a list of points, a list of lines (two vertices), or a list of
 ///   triangles (three vertices). If you draw a triangle strip or a triangle fan for example,
@@ -54,6 +57,16 @@ use gl;
 ///   to be pushed in the buffer after the existing data. However you must always use the same type
 ///   of primitives and the same program.
 ///
+/// - Drawing without the session, or with another program, pauses the session. Drawing again with
+///   the session then resumes it and the data continues to be appended. This requires OpenGL 4.0,
+///   OpenGL ES 3.0 or `GL_ARB_transform_feedback2`, in which case each session also gets its own
+///   transform feedback object. Otherwise the session is ended instead, and the next draw command
+///   that uses it starts writing at the beginning of the buffer again.
+///
+/// - Transform feedback is ended when the session is destroyed. Draw commands can then use the
+///   buffer as a source of vertices, which allows for example simulating particles on the GPU by
+///   using two buffers and two sessions alternately.
+///
 /// # Example
 ///
 /// ```no_run
@@ -94,6 +107,16 @@ use gl;
 pub struct TransformFeedbackSession<'a> {
     buffer: BufferAnySlice<'a>,
     program: &'a Program,
+
+    /// The transform feedback object of this session, if they are supported.
+    object: Option<gl::types::GLuint>,
+
+    /// Queries that counted the primitives written by the draw commands of this session and whose
+    /// result hasn't been read yet.
+    queries: RefCell<Vec<gl::types::GLuint>>,
+
+    /// Sum of the results of the queries that have already been read.
+    primitives_written: Cell<u32>,
 }
 
 /// Error that can happen when creating a `TransformFeedbackSession`.
@@ -153,55 +176,172 @@ impl<'a> TransformFeedbackSession<'a> {
             return Err(TransformFeedbackSessionCreationError::WrongVertexFormat);
         }
 
+        let ctxt = facade.get_context().make_current();
+
+        let object = if is_pause_supported(&ctxt) {
+            unsafe {
+                let mut id = mem::uninitialized();
+                ctxt.gl.GenTransformFeedbacks(1, &mut id);
+                Some(id)
+            }
+        } else {
+            None
+        };
+
         Ok(TransformFeedbackSession {
             buffer: buffer.as_slice_any(),
             program: program,
+            object: object,
+            queries: RefCell::new(Vec::new()),
+            primitives_written: Cell::new(0),
         })
     }
+
+    /// Returns the number of primitives that the draw commands of this session have written to
+    /// the buffer so far.
+    ///
+    /// Draw commands that use a `TransformFeedbackPrimitivesWrittenQuery` are counted by that
+    /// query instead, and aren't included in the result.
+    ///
+    /// This function blocks until the GPU has finished executing the draw commands.
+    pub fn get_primitives_written(&self) -> u32 {
+        let mut ctxt = self.buffer.get_context().make_current();
+        let mut queries = self.queries.borrow_mut();
+
+        unsafe {
+            if queries.contains(&ctxt.state.transform_feedback_session_query) {
+                end_session_query(&mut ctxt);
+            }
+
+            for id in queries.drain(..) {
+                let mut value = 0;
+                ctxt.gl.GetQueryObjectuiv(id, gl::QUERY_RESULT, &mut value);
+                ctxt.gl.DeleteQueries(1, &id);
+                self.primitives_written.set(self.primitives_written.get() + value);
+            }
+        }
+
+        self.primitives_written.get()
+    }
+
+    /// Starts counting the primitives written by the current draw command, unless a query is
+    /// already counting them.
+    fn begin_session_query(&self, ctxt: &mut CommandContext) {
+        if ctxt.state.transform_feedback_primitives_written_query != 0 ||
+           ctxt.state.transform_feedback_session_query != 0
+        {
+            return;
+        }
+
+        unsafe {
+            let mut id = mem::uninitialized();
+            ctxt.gl.GenQueries(1, &mut id);
+            ctxt.gl.BeginQuery(gl::TRANSFORM_FEEDBACK_PRIMITIVES_WRITTEN, id);
+            ctxt.state.transform_feedback_session_query = id;
+            self.queries.borrow_mut().push(id);
+        }
+    }
 }
 
 impl<'a> TransformFeedbackSessionExt for TransformFeedbackSession<'a> {
-    fn bind(&self, mut ctxt: &mut CommandContext, draw_primitives: PrimitiveType) {
-        // TODO: check that the state matches what is required
-        if ctxt.state.transform_feedback_enabled.is_some() {
-            unimplemented!();
+    fn bind(&self, mut ctxt: &mut CommandContext, draw_primitives: PrimitiveType)
+            -> Result<(), DrawError>
+    {
+        let primitives = match (self.program.get_output_primitives(), draw_primitives) {
+            (Some(OutputPrimitives::Points), _) => gl::POINTS,
+            (Some(OutputPrimitives::Lines), _) => gl::LINES,
+            (Some(OutputPrimitives::Triangles), _) => gl::TRIANGLES,
+            (Some(OutputPrimitives::Quads), _) =>
+                return Err(DrawError::TransformFeedbackQuadsNotSupported),
+            (None, PrimitiveType::Points) => gl::POINTS,
+            (None, PrimitiveType::LinesList) => gl::LINES,
+            (None, PrimitiveType::LinesListAdjacency) => gl::LINES,
+            (None, PrimitiveType::LineStrip) => gl::LINES,
+            (None, PrimitiveType::LineStripAdjacency) => gl::LINES,
+            (None, PrimitiveType::LineLoop) => gl::LINES,
+            (None, PrimitiveType::TrianglesList) => gl::TRIANGLES,
+            (None, PrimitiveType::TrianglesListAdjacency) => gl::TRIANGLES,
+            (None, PrimitiveType::TriangleStrip) => gl::TRIANGLES,
+            (None, PrimitiveType::TriangleStripAdjacency) => gl::TRIANGLES,
+            (None, PrimitiveType::TriangleFan) => gl::TRIANGLES,
+            (None, PrimitiveType::Patches { .. }) => unreachable!(),
+        };
+
+        // continuing the existing session, so that data is appended after the previous draws
+        if self.is_current(ctxt) {
+            if ctxt.state.transform_feedback_enabled != Some(primitives) {
+                return Err(DrawError::TransformFeedbackPrimitivesMismatch);
+            }
+
+            if ctxt.state.transform_feedback_paused {
+                unsafe { ctxt.gl.ResumeTransformFeedback(); }
+                ctxt.state.transform_feedback_paused = false;
+            }
+
+            self.begin_session_query(ctxt);
+            return Ok(());
+        }
+
+        Self::end(ctxt);
+
+        if let Some(object) = self.object {
+            bind_object(ctxt, object);
         }
 
         // FIXME: use the memory barrier system
         self.buffer.bind_to_transform_feedback(ctxt, 0);
 
         unsafe {
-            let primitives = match (self.program.get_output_primitives(), draw_primitives) {
-                (Some(OutputPrimitives::Points), _) => gl::POINTS,
-                (Some(OutputPrimitives::Lines), _) => gl::LINES,
-                (Some(OutputPrimitives::Triangles), _) => gl::TRIANGLES,
-                (Some(OutputPrimitives::Quads), _) => panic!(),         // TODO: return a proper error
-                (None, PrimitiveType::Points) => gl::POINTS,
-                (None, PrimitiveType::LinesList) => gl::LINES,
-                (None, PrimitiveType::LinesListAdjacency) => gl::LINES,
-                (None, PrimitiveType::LineStrip) => gl::LINES,
-                (None, PrimitiveType::LineStripAdjacency) => gl::LINES,
-                (None, PrimitiveType::LineLoop) => gl::LINES,
-                (None, PrimitiveType::TrianglesList) => gl::TRIANGLES,
-                (None, PrimitiveType::TrianglesListAdjacency) => gl::TRIANGLES,
-                (None, PrimitiveType::TriangleStrip) => gl::TRIANGLES,
-                (None, PrimitiveType::TriangleStripAdjacency) => gl::TRIANGLES,
-                (None, PrimitiveType::TriangleFan) => gl::TRIANGLES,
-                (None, PrimitiveType::Patches { .. }) => unreachable!(),
-            };
-
             ctxt.gl.BeginTransformFeedback(primitives);
             ctxt.state.transform_feedback_enabled = Some(primitives);
             ctxt.state.transform_feedback_paused = false;
         }
+
+        self.begin_session_query(ctxt);
+        Ok(())
     }
 
     #[inline]
+    fn is_current(&self, ctxt: &CommandContext) -> bool {
+        if let Some(object) = self.object {
+            return ctxt.state.transform_feedback_enabled.is_some() &&
+                   ctxt.state.transform_feedback_object == object;
+        }
+
+        ctxt.state.transform_feedback_enabled.is_some() &&
+            ctxt.state.indexed_transform_feedback_buffer_bindings.get(0)
+                .map_or(false, |binding| {
+                    binding.buffer == self.buffer.get_id() &&
+                    binding.offset as usize == self.buffer.get_offset_bytes() &&
+                    binding.size as usize == self.buffer.get_size()
+                })
+    }
+
     fn unbind(mut ctxt: &mut CommandContext) {
+        if ctxt.state.transform_feedback_enabled.is_none() ||
+           ctxt.state.transform_feedback_paused
+        {
+            return;
+        }
+
+        end_session_query(ctxt);
+
+        if is_pause_supported(ctxt) {
+            unsafe { ctxt.gl.PauseTransformFeedback(); }
+            ctxt.state.transform_feedback_paused = true;
+        } else {
+            Self::end(ctxt);
+        }
+    }
+
+    #[inline]
+    fn end(mut ctxt: &mut CommandContext) {
         if ctxt.state.transform_feedback_enabled.is_none() {
             return;
         }
 
+        end_session_query(ctxt);
+
         unsafe {
             ctxt.gl.EndTransformFeedback();
             ctxt.state.transform_feedback_enabled = None;
@@ -214,27 +354,77 @@ impl<'a> TransformFeedbackSessionExt for TransformFeedbackSession<'a> {
             return;
         }
 
-        let mut needs_unbind = false;
+        let mut needs_end = false;
         for elem in ctxt.state.indexed_transform_feedback_buffer_bindings.iter_mut() {
             if elem.buffer == buffer {
-                needs_unbind = true;
+                needs_end = true;
                 break;
             }
         }
 
-        if needs_unbind {
-            TransformFeedbackSession::unbind(ctxt);
+        if needs_end {
+            TransformFeedbackSession::end(ctxt);
         }
     }
 }
 
+/// Returns true if `glPauseTransformFeedback`, `glResumeTransformFeedback` and transform feedback
+/// objects are supported.
+#[inline]
+fn is_pause_supported(ctxt: &CommandContext) -> bool {
+    ctxt.version >= &Version(Api::Gl, 4, 0) || ctxt.version >= &Version(Api::GlEs, 3, 0) ||
+    ctxt.extensions.gl_arb_transform_feedback2
+}
+
+/// Binds a transform feedback object. Transform feedback must not be active.
+fn bind_object(ctxt: &mut CommandContext, object: gl::types::GLuint) {
+    if ctxt.state.transform_feedback_object == object {
+        return;
+    }
+
+    unsafe { ctxt.gl.BindTransformFeedback(gl::TRANSFORM_FEEDBACK, object); }
+    ctxt.state.transform_feedback_object = object;
+
+    // the indexed buffer bindings are part of the transform feedback object
+    for elem in ctxt.state.indexed_transform_feedback_buffer_bindings.iter_mut() {
+        *elem = Default::default();
+    }
+}
+
+/// Stops counting primitives with the query of the current transform feedback session.
+#[inline]
+fn end_session_query(ctxt: &mut CommandContext) {
+    if ctxt.state.transform_feedback_session_query != 0 {
+        unsafe { ctxt.gl.EndQuery(gl::TRANSFORM_FEEDBACK_PRIMITIVES_WRITTEN); }
+        ctxt.state.transform_feedback_session_query = 0;
+    }
+}
+
 impl<'a> Drop for TransformFeedbackSession<'a> {
-    #[inline]
     fn drop(&mut self) {
         // Since the session can be mem::forget'ed, the code in buffer/alloc.rs ensures that the
         // buffer isn't used by transform feedback.
         // However we end the session now anyway.
         let mut ctxt = self.buffer.get_context().make_current();
+
+        if self.is_current(&ctxt) {
+            Self::end(&mut ctxt);
+        }
         Self::ensure_buffer_out_of_transform_feedback(&mut ctxt, self.buffer.get_id());
+
+        let queries = self.queries.borrow();
+        if queries.contains(&ctxt.state.transform_feedback_session_query) {
+            end_session_query(&mut ctxt);
+        }
+        for id in queries.iter() {
+            unsafe { ctxt.gl.DeleteQueries(1, id); }
+        }
+
+        if let Some(object) = self.object {
+            if ctxt.state.transform_feedback_object == object {
+                bind_object(&mut ctxt, 0);
+            }
+            unsafe { ctxt.gl.DeleteTransformFeedbacks(1, &object); }
+        }
     }
 }
@@ -257,36 +257,8 @@ impl VertexArrayObject {
                   vertex_buffers: &[(gl::types::GLuint, VertexFormat, usize, usize, Option<u32>)],
                   index_buffer: Option<BufferAnySlice>, program: &Program) -> VertexArrayObject
     {
-        // checking the attributes types
-        for &(_, ref bindings, _, _, _) in vertex_buffers {
-            for &(ref name, _, ty) in bindings.iter() {
-                let attribute = match program.get_attribute(Borrow::<str>::borrow(name)) {
-                    Some(a) => a,
-                    None => continue
-                };
-
-                if ty.get_num_components() != attribute.ty.get_num_components() ||
-                    attribute.size != 1
-                {
-                    panic!("The program attribute `{}` does not match the vertex format. \
-                            Program expected {:?}, got {:?}.", name, attribute.ty, ty);
-                }
-            }
-        }
-
-        // checking for missing attributes
-        for (&ref name, _) in program.attributes() {
-            let mut found = false;
-            for &(_, ref bindings, _, _, _) in vertex_buffers {
-                if bindings.iter().find(|&&(ref n, _, _)| n == name).is_some() {
-                    found = true;
-                    break;
-                }
-            }
-            if !found {
-                panic!("The program attribute `{}` is missing in the vertex bindings", name);
-            }
-        };
+        // the attributes have been checked with `Program::validate_vertex_formats` before
+        // drawing
 
         // TODO: check for collisions between the vertices sources
 
@@ -7,7 +7,6 @@ use glium::index::PrimitiveType;
 mod support;
 
 #[test]
-#[should_panic(expected = "The program attribute `field1` does not match the vertex format")]
 fn attribute_types_mismatch() {
     let display = support::build_display();
 
@@ -79,15 +78,21 @@ fn attribute_types_mismatch() {
 
     // drawing a frame
     let mut target = display.draw();
-    target.draw(&vertex_buffer, &index_buffer, &program, &glium::uniforms::EmptyUniforms,
-                &Default::default()).unwrap();
+    match target.draw(&vertex_buffer, &index_buffer, &program, &glium::uniforms::EmptyUniforms,
+                      &Default::default())
+    {
+        Err(glium::DrawError::VertexInterfaceMismatch { ref name, obtained, .. }) => {
+            assert_eq!(name, "field1");
+            assert_eq!(obtained, Some(glium::vertex::AttributeType::F32F32F32F32));
+        },
+        e => panic!("{:?}", e)
+    };
     target.finish().unwrap();
 
     display.assert_no_error(None);
 }
 
 #[test]
-#[should_panic(expected = "The program attribute `field2` is missing in the vertex bindings")]
 fn missing_attribute() {
     let display = support::build_display();
 
@@ -159,13 +164,38 @@ fn missing_attribute() {
 
     // drawing a frame
     let mut target = display.draw();
-    target.draw(&vertex_buffer, &index_buffer, &program, &glium::uniforms::EmptyUniforms,
-                &Default::default()).unwrap();
+    match target.draw(&vertex_buffer, &index_buffer, &program, &glium::uniforms::EmptyUniforms,
+                      &Default::default())
+    {
+        Err(glium::DrawError::VertexInterfaceMismatch { ref name, obtained: None, .. }) => {
+            assert_eq!(name, "field2");
+        },
+        e => panic!("{:?}", e)
+    };
     target.finish().unwrap();
 
     display.assert_no_error(None);
 }
 
+#[test]
+fn validate_vertex_formats() {
+    let display = support::build_display();
+    let (vb, _, program) = support::build_fullscreen_red_pipeline(&display);
+
+    // the second call uses the cached result
+    program.validate_vertex_formats(&[vb.get_bindings()]).unwrap();
+    program.validate_vertex_formats(&[vb.get_bindings()]).unwrap();
+
+    match program.validate_vertex_formats(&[]) {
+        Err(glium::DrawError::VertexInterfaceMismatch { ref name, obtained: None, .. }) => {
+            assert_eq!(name, "position");
+        },
+        e => panic!("{:?}", e)
+    };
+
+    display.assert_no_error(None);
+}
+
 macro_rules! attribute_test(
     ($name:ident, $attr_ty:ty, $glsl_ty:expr, $value:expr, $gl_pos:expr) => (
         #[test]
@@ -56,3 +56,128 @@ fn blit_texture_to_window() {
 
     display.assert_no_error(None);
 }
+
+#[test]
+fn blit_from_scaled() {
+    let display = support::build_display();
+
+    let texture = support::build_unicolor_texture2d(&display, 0.0, 1.0, 0.0);
+
+    let target = support::build_renderable_texture(&display);
+    target.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+
+    target.as_surface().blit_from(&texture.as_surface(),
+                                  &Rect { left: 0, bottom: 0, width: 2, height: 2 },
+                                  &BlitTarget { left: 0, bottom: 0, width: 4, height: 4 },
+                                  glium::uniforms::MagnifySamplerFilter::Linear);
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = target.read();
+
+    assert_eq!(data[0][0], (0, 255, 0, 255));
+    assert_eq!(data[3][3], (0, 255, 0, 255));
+    assert_eq!(data[4][4], (0, 0, 0, 0));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn blit_depth_buffer() {
+    let display = support::build_display();
+
+    let depth_format = glium::texture::DepthFormat::I24;
+
+    let color1 = support::build_renderable_texture(&display);
+    let depth1 = match glium::framebuffer::DepthRenderBuffer::new(&display, depth_format,
+                                                                  1024, 1024)
+    {
+        Ok(d) => d,
+        Err(_) => return
+    };
+
+    let color2 = support::build_renderable_texture(&display);
+    let depth2 = glium::framebuffer::DepthRenderBuffer::new(&display, depth_format,
+                                                            1024, 1024).unwrap();
+
+    let mut source = glium::framebuffer::SimpleFrameBuffer::with_depth_buffer(&display, &color1,
+                                                                              &depth1).unwrap();
+    let mut target = glium::framebuffer::SimpleFrameBuffer::with_depth_buffer(&display, &color2,
+                                                                              &depth2).unwrap();
+
+    source.clear_depth(0.0);
+    target.clear_color_and_depth((0.0, 0.0, 0.0, 0.0), 1.0);
+
+    target.blit_buffers_from(&source, &Rect { left: 0, bottom: 0, width: 1024, height: 1024 },
+                             &BlitTarget { left: 0, bottom: 0, width: 1024, height: 1024 },
+                             glium::uniforms::MagnifySamplerFilter::Nearest,
+                             glium::BlitMask::depth());
+
+    // the depth buffer now contains `0.0`, so nothing should pass the depth test
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+    let params = glium::DrawParameters {
+        depth: glium::Depth {
+            test: glium::DepthTest::IfLess,
+            .. Default::default()
+        },
+        .. Default::default()
+    };
+    target.draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms, &params).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = color2.read();
+    assert_eq!(data[0][0], (0, 0, 0, 0));
+    assert_eq!(data[512][512], (0, 0, 0, 0));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn blit_depth_buffer_linear() {
+    let display = support::build_display();
+
+    let source = support::build_renderable_texture(&display);
+    let texture = support::build_renderable_texture(&display);
+    let rect = Rect { left: 0, bottom: 0, width: 2, height: 2 };
+    let target = BlitTarget { left: 0, bottom: 0, width: 2, height: 2 };
+
+    // the depth buffer is copied with the `Nearest` filter instead
+    source.as_surface().blit_buffers(&rect, &texture.as_surface(), &target,
+                                     glium::uniforms::MagnifySamplerFilter::Linear,
+                                     glium::BlitMask::all());
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn blit_from_second_attachment() {
+    use glium::{Api, Version};
+
+    let display = support::build_display();
+
+    if !(display.get_opengl_version() >= &Version(Api::Gl, 3, 0) ||
+         display.get_opengl_version() >= &Version(Api::GlEs, 3, 0))
+    {
+        return;
+    }
+
+    let color1 = glium::Texture2d::empty(&display, 16, 16).unwrap();
+    let color2 = glium::Texture2d::empty(&display, 16, 16).unwrap();
+    let target = glium::Texture2d::empty(&display, 16, 16).unwrap();
+
+    {
+        let mut source = glium::framebuffer::MultiOutputFrameBuffer::new(&display,
+                               [("color1", &color1), ("color2", &color2)].iter().cloned()).unwrap();
+        source.clear_color_attachment(0, (1.0, 0.0, 0.0, 1.0)).unwrap();
+        source.clear_color_attachment(1, (0.0, 0.0, 1.0, 1.0)).unwrap();
+        source.set_read_buffer(1).unwrap();
+
+        let mut dest = glium::framebuffer::SimpleFrameBuffer::new(&display, &target).unwrap();
+        dest.clear_color(0.0, 0.0, 0.0, 0.0);
+        dest.blit_from(&source, &Rect { left: 0, bottom: 0, width: 16, height: 16 },
+                       &BlitTarget { left: 0, bottom: 0, width: 16, height: 16 },
+                       glium::uniforms::MagnifySamplerFilter::Nearest);
+    }
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = target.read();
+    assert_eq!(data[8][8], (0, 0, 255, 255));
+
+    display.assert_no_error(None);
+}
@@ -0,0 +1,314 @@
+#[macro_use]
+extern crate glium;
+
+use glium::program::ComputeShader;
+
+mod support;
+
+#[test]
+fn dispatch_writes_ssbo() {
+    let display = support::build_display();
+
+    if !ComputeShader::is_supported(&display) {
+        return;
+    }
+
+    let program = match ComputeShader::from_source(&display, "
+            #version 430
+            layout(local_size_x = 4, local_size_y = 1, local_size_z = 1) in;
+
+            layout(std430) buffer MyBlock {
+                uint values[16];
+            };
+
+            void main() {
+                values[gl_GlobalInvocationID.x] = gl_GlobalInvocationID.x * 2u;
+            }
+        ")
+    {
+        Ok(p) => p,
+        Err(_) => return
+    };
+
+    assert_eq!(program.get_work_group_size(), (4, 1, 1));
+
+    #[derive(Copy, Clone)]
+    struct Data {
+        values: [u32; 16],
+    }
+
+    implement_uniform_block!(Data, values);
+
+    let buffer = glium::uniforms::UniformBuffer::new(&display,
+                                                     Data { values: [0; 16] }).unwrap();
+
+    program.dispatch(uniform! { MyBlock: &buffer }, 4, 1, 1).unwrap();
+
+    let data = buffer.read().unwrap();
+    for (i, value) in data.values.iter().enumerate() {
+        assert_eq!(*value, i as u32 * 2);
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn dispatch_writes_image() {
+    use glium::uniforms::{ImageUnit, ImageUnitAccess, ImageUnitFormat};
+
+    let display = support::build_display();
+
+    if !ComputeShader::is_supported(&display) {
+        return;
+    }
+
+    let program = match ComputeShader::from_source(&display, "
+            #version 430
+            layout(local_size_x = 8, local_size_y = 8, local_size_z = 1) in;
+
+            layout(rgba32f) uniform writeonly image2D image;
+
+            void main() {
+                imageStore(image, ivec2(gl_GlobalInvocationID.xy), vec4(1.0, 0.0, 0.0, 1.0));
+            }
+        ")
+    {
+        Ok(p) => p,
+        Err(_) => return
+    };
+
+    let texture = glium::texture::Texture2d::empty_with_format(&display,
+                            glium::texture::UncompressedFloatFormat::F32F32F32F32,
+                            glium::texture::MipmapsOption::NoMipmap, 16, 16).unwrap();
+
+    let uniforms = uniform! {
+        image: ImageUnit::new(&texture, ImageUnitFormat::RGBA32F)
+                         .set_access(ImageUnitAccess::Write)
+    };
+    program.dispatch(uniforms, 2, 2, 1).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(255, 0, 0, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn dispatch_indirect_from_compute() {
+    let display = support::build_display();
+
+    if !ComputeShader::is_supported(&display) {
+        return;
+    }
+
+    let generate = match ComputeShader::from_source(&display, "
+            #version 430
+            layout(local_size_x = 1, local_size_y = 1, local_size_z = 1) in;
+
+            layout(std430) buffer Command {
+                uint num_groups_x;
+                uint num_groups_y;
+                uint num_groups_z;
+            };
+
+            void main() {
+                num_groups_x = 4u;
+                num_groups_y = 1u;
+                num_groups_z = 1u;
+            }
+        ")
+    {
+        Ok(p) => p,
+        Err(_) => return
+    };
+
+    let program = ComputeShader::from_source(&display, "
+            #version 430
+            layout(local_size_x = 4, local_size_y = 1, local_size_z = 1) in;
+
+            layout(std430) buffer MyBlock {
+                uint values[16];
+            };
+
+            void main() {
+                values[gl_GlobalInvocationID.x] = gl_GlobalInvocationID.x + 1u;
+            }
+        ").unwrap();
+
+    #[derive(Copy, Clone)]
+    struct Data {
+        values: [u32; 16],
+    }
+
+    implement_uniform_block!(Data, values);
+
+    let command = glium::program::ComputeCommand {
+        num_groups_x: 0,
+        num_groups_y: 0,
+        num_groups_z: 0,
+    };
+
+    let commands = glium::buffer::Buffer::new(&display, &command,
+                                              glium::buffer::BufferType::DispatchIndirectBuffer,
+                                              glium::buffer::BufferMode::Default).unwrap();
+
+    let buffer = glium::uniforms::UniformBuffer::new(&display,
+                                                     Data { values: [0; 16] }).unwrap();
+
+    generate.dispatch(uniform! { Command: &commands }, 1, 1, 1).unwrap();
+    program.dispatch_indirect(uniform! { MyBlock: &buffer }, commands.as_slice()).unwrap();
+
+    let data = buffer.read().unwrap();
+    for (i, value) in data.values.iter().enumerate() {
+        assert_eq!(*value, i as u32 + 1);
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn context_dispatch_writes_image_then_blit() {
+    use glium::backend::Facade;
+    use glium::Surface;
+    use glium::uniforms::{ImageUnit, ImageUnitAccess, ImageUnitFormat};
+
+    let display = support::build_display();
+
+    if !ComputeShader::is_supported(&display) {
+        return;
+    }
+
+    let program = match ComputeShader::from_source(&display, "
+            #version 430
+            layout(local_size_x = 8, local_size_y = 8, local_size_z = 1) in;
+
+            layout(rgba32f) uniform writeonly image2D image;
+
+            void main() {
+                imageStore(image, ivec2(gl_GlobalInvocationID.xy), vec4(0.0, 1.0, 0.0, 1.0));
+            }
+        ")
+    {
+        Ok(p) => p,
+        Err(_) => return
+    };
+
+    let texture = glium::texture::Texture2d::empty_with_format(&display,
+                            glium::texture::UncompressedFloatFormat::F32F32F32F32,
+                            glium::texture::MipmapsOption::NoMipmap, 16, 16).unwrap();
+    let target = glium::texture::Texture2d::empty_with_format(&display,
+                            glium::texture::UncompressedFloatFormat::F32F32F32F32,
+                            glium::texture::MipmapsOption::NoMipmap, 16, 16).unwrap();
+
+    let uniforms = uniform! {
+        image: ImageUnit::new(&texture, ImageUnitFormat::RGBA32F)
+                         .set_access(ImageUnitAccess::Write)
+    };
+    display.get_context().dispatch(&program, uniforms, [2, 2, 1]).unwrap();
+
+    // the blit reads the texture through a framebuffer object
+    texture.as_surface().fill(&target.as_surface(),
+                              glium::uniforms::MagnifySamplerFilter::Nearest);
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = target.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(0, 255, 0, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn dispatch_too_many_work_groups() {
+    let display = support::build_display();
+
+    if !ComputeShader::is_supported(&display) {
+        return;
+    }
+
+    let program = match ComputeShader::from_source(&display, "
+            #version 430
+            layout(local_size_x = 1, local_size_y = 1, local_size_z = 1) in;
+
+            void main() {
+            }
+        ")
+    {
+        Ok(p) => p,
+        Err(_) => return
+    };
+
+    match program.dispatch(glium::uniforms::EmptyUniforms, 1, 1, 0xffffffff) {
+        Err(glium::program::DispatchError::WorkGroupCountTooLarge { obtained, .. }) => {
+            assert_eq!(obtained, (1, 1, 0xffffffff));
+        },
+        _ => panic!()
+    };
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn work_group_limits() {
+    let display = support::build_display();
+
+    if !ComputeShader::is_supported(&display) {
+        return;
+    }
+
+    // minimums required by the specifications
+    let count = ComputeShader::get_max_work_group_count(&display);
+    assert!(count.0 >= 65535 && count.1 >= 65535 && count.2 >= 65535);
+
+    let size = ComputeShader::get_max_work_group_size(&display);
+    assert!(size.0 >= 128 && size.1 >= 128 && size.2 >= 64);
+
+    let invocations = ComputeShader::get_max_work_group_invocations(&display);
+    assert!(invocations >= 128);
+
+    let program = match ComputeShader::from_source(&display, "
+            #version 430
+            layout(local_size_x = 8, local_size_y = 4, local_size_z = 2) in;
+
+            void main() {
+            }
+        ")
+    {
+        Ok(p) => p,
+        Err(_) => return
+    };
+
+    let (x, y, z) = program.get_work_group_size();
+    assert_eq!((x, y, z), (8, 4, 2));
+    assert!(x * y * z <= invocations);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn spirv_not_supported() {
+    let display = support::build_display();
+
+    if glium::program::is_spirv_supported(&display) || !ComputeShader::is_supported(&display) {
+        return;
+    }
+
+    let entry_point = glium::program::SpirvEntryPoint {
+        binary: &[0x03, 0x02, 0x23, 0x07],
+        entry_point: "main",
+        specialization_constants: &[(0, 4)],
+    };
+
+    match ComputeShader::from_spirv(&display, &entry_point) {
+        Err(glium::program::ProgramCreationError::SpirvNotSupported) => (),
+        _ => panic!()
+    };
+
+    display.assert_no_error(None);
+}
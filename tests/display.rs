@@ -82,6 +82,37 @@ fn timestamp_query() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn get_timestamp() {
+    let display = support::build_display();
+
+    let query = glium::debug::TimestampQuery::new(&display);
+
+    let timestamp = match glium::debug::get_timestamp(&display) {
+        Some(t) => t,
+        None => return
+    };
+
+    // the query was submitted before reading the current timestamp
+    if let Some(query) = query {
+        assert!(query.get() <= glium::debug::get_timestamp(&display).unwrap());
+    }
+
+    assert!(timestamp != 0);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn timestamp_query_dropped() {
+    let display = support::build_display();
+
+    // dropping the query without reading it must delete it
+    drop(glium::debug::TimestampQuery::new(&display));
+
+    display.assert_no_error(None);
+}
+
 #[test]
 fn sync() {
     let display = support::build_display();
@@ -94,6 +125,36 @@ fn sync() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn sync_wait_timeout() {
+    let display = support::build_display();
+
+    let fence = match glium::SyncFence::new(&display) {
+        Ok(f) => f,
+        Err(_) => return
+    };
+
+    assert!(fence.wait_timeout(std::time::Duration::from_secs(10)));
+    assert!(fence.is_signaled());
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn sync_wait_server() {
+    let display = support::build_display();
+
+    let fence = match glium::SyncFence::new(&display) {
+        Ok(f) => f,
+        Err(_) => return
+    };
+
+    fence.wait_server(&display);
+    display.finish();
+
+    display.assert_no_error(None);
+}
+
 #[test]
 fn multiple_displays() {
     let display1 = support::build_display();
@@ -142,6 +203,15 @@ fn debug_string() {
 }
 
 
+#[test]
+fn memory_barrier() {
+    // tests that `memory_barrier` doesn't trigger an OpenGL error
+    let display = support::build_display();
+    display.memory_barrier(glium::MemoryBarrier::all()).ok();
+    display.memory_barrier(glium::MemoryBarrier::none()).ok();
+    display.assert_no_error(None);
+}
+
 #[test]
 fn is_context_lost() {
     // tests that `is_context_lost` doesn't trigger an OpenGL error
@@ -149,3 +219,22 @@ fn is_context_lost() {
     display.is_context_lost();
     display.assert_no_error(None);
 }
+
+#[test]
+fn default_framebuffer_properties() {
+    let display = support::build_display();
+
+    let properties = display.get_default_framebuffer_properties();
+
+    let frame = display.draw();
+    assert_eq!(frame.get_properties(), properties);
+    assert_eq!(properties.depth_bits, frame.get_depth_buffer_bits());
+    assert_eq!(properties.stencil_bits, frame.get_stencil_buffer_bits());
+    frame.finish().unwrap();
+
+    if let Some(samples) = properties.samples {
+        assert!(samples >= 2);
+    }
+
+    display.assert_no_error(None);
+}
@@ -2,6 +2,7 @@
 extern crate glium;
 
 use glium::Surface;
+use glium::CapabilitiesSource;
 use glium::index::PrimitiveType;
 
 mod support;
@@ -771,6 +772,44 @@ blending_test!(one_plus_one, glium::BlendingFunction::Addition {
                },
                (0.0, 1.0, 1.0, 0.0), (1.0, 0.0, 0.0, 1.0), (255, 255, 255, 255));
 
+#[test]
+fn constant_blend_color() {
+    let display = support::build_display();
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+
+    let texture = support::build_renderable_texture(&display);
+
+    // drawing twice checks that the constant color is updated between draws
+    for &(constant, expected) in &[((1.0, 0.0, 0.0, 0.0), (255, 0, 0, 255)),
+                                   ((0.0, 0.0, 0.0, 0.0), (0, 0, 0, 255))]
+    {
+        let params = glium::DrawParameters {
+            blend: glium::Blend {
+                color: glium::BlendingFunction::Addition {
+                    source: glium::LinearBlendingFactor::ConstantColor,
+                    destination: glium::LinearBlendingFactor::Zero,
+                },
+                alpha: glium::BlendingFunction::Addition {
+                    source: glium::LinearBlendingFactor::One,
+                    destination: glium::LinearBlendingFactor::Zero,
+                },
+                constant_value: constant,
+            },
+            .. Default::default()
+        };
+
+        texture.as_surface().clear_color(0.0, 0.0, 1.0, 0.0);
+        texture.as_surface().draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms,
+                                  &params).unwrap();
+
+        let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+        assert_eq!(data[0][0], expected);
+        assert_eq!(data.last().unwrap().last().unwrap(), &expected);
+    }
+
+    display.assert_no_error(None);
+}
+
 
 #[test]
 fn provoking_vertex_last() {
@@ -1279,3 +1318,936 @@ fn primitive_bounding_box() {
 
     display.assert_no_error(None);
 }
+
+#[test]
+fn blend_per_attachment() {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = match glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 330
+
+            layout(location = 0) out vec4 color1;
+            layout(location = 1) out vec4 color2;
+
+            void main() {
+                color1 = vec4(1.0, 0.0, 0.0, 1.0);
+                color2 = vec4(1.0, 0.0, 0.0, 1.0);
+            }
+        ",
+        None)
+    {
+        Err(glium::CompilationError(_)) => return,
+        Ok(p) => p,
+        e => e.unwrap()
+    };
+
+    let color1 = support::build_renderable_texture(&display);
+    let color2 = support::build_renderable_texture(&display);
+
+    let mut framebuffer = glium::framebuffer::MultiOutputFrameBuffer::with_locations(&display,
+                                        vec![(0, &color1), (1, &color2)]).unwrap();
+    framebuffer.clear_color(0.0, 0.0, 1.0, 1.0);
+
+    let blends = [
+        glium::Blend {
+            color: glium::BlendingFunction::Addition {
+                source: glium::LinearBlendingFactor::One,
+                destination: glium::LinearBlendingFactor::One,
+            },
+            alpha: glium::BlendingFunction::Addition {
+                source: glium::LinearBlendingFactor::One,
+                destination: glium::LinearBlendingFactor::One,
+            },
+            constant_value: (1.0, 1.0, 1.0, 1.0),
+        },
+        glium::Blend::default(),
+    ];
+
+    let params = glium::DrawParameters {
+        blend_per_attachment: Some(&blends[..]),
+        .. Default::default()
+    };
+
+    match framebuffer.draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms, &params) {
+        Err(glium::DrawError::PerAttachmentBlendingNotSupported) => return,
+        e => e.unwrap()
+    };
+
+    let data1: Vec<Vec<(u8, u8, u8, u8)>> = color1.read();
+    let data2: Vec<Vec<(u8, u8, u8, u8)>> = color2.read();
+    assert_eq!(data1[0][0], (255, 0, 255, 255));
+    assert_eq!(data2[0][0], (255, 0, 0, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn blend_per_attachment_identical() {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = match glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 330
+
+            layout(location = 0) out vec4 color1;
+            layout(location = 1) out vec4 color2;
+
+            void main() {
+                color1 = vec4(1.0, 0.0, 0.0, 1.0);
+                color2 = vec4(1.0, 0.0, 0.0, 1.0);
+            }
+        ",
+        None)
+    {
+        Err(glium::CompilationError(_)) => return,
+        Ok(p) => p,
+        e => e.unwrap()
+    };
+
+    let color1 = support::build_renderable_texture(&display);
+    let color2 = support::build_renderable_texture(&display);
+
+    let mut framebuffer = glium::framebuffer::MultiOutputFrameBuffer::with_locations(&display,
+                                        vec![(0, &color1), (1, &color2)]).unwrap();
+    framebuffer.clear_color(0.0, 0.0, 1.0, 1.0);
+
+    let blend = glium::Blend {
+        color: glium::BlendingFunction::Addition {
+            source: glium::LinearBlendingFactor::One,
+            destination: glium::LinearBlendingFactor::One,
+        },
+        alpha: glium::BlendingFunction::Addition {
+            source: glium::LinearBlendingFactor::One,
+            destination: glium::LinearBlendingFactor::One,
+        },
+        constant_value: (1.0, 1.0, 1.0, 1.0),
+    };
+
+    let blends = [blend, blend];
+
+    let params = glium::DrawParameters {
+        blend_per_attachment: Some(&blends[..]),
+        .. Default::default()
+    };
+
+    // identical blendings for all the attachments don't need the indexed functions
+    framebuffer.draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms, &params).unwrap();
+
+    let data1: Vec<Vec<(u8, u8, u8, u8)>> = color1.read();
+    let data2: Vec<Vec<(u8, u8, u8, u8)>> = color2.read();
+    assert_eq!(data1[0][0], (255, 0, 255, 255));
+    assert_eq!(data2[0][0], (255, 0, 255, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn dual_source_blending() {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let source = glium::program::ProgramCreationInput::SourceCode {
+        tessellation_control_shader: None,
+        tessellation_evaluation_shader: None,
+        geometry_shader: None,
+        transform_feedback_varyings: None,
+        outputs_srgb: false,
+        uses_point_size: false,
+
+        vertex_shader: "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        fragment_shader: "
+            #version 130
+
+            out vec4 color;
+            out vec4 factor;
+
+            void main() {
+                color = vec4(1.0, 0.0, 0.0, 1.0);
+                factor = vec4(0.0, 1.0, 0.0, 1.0);
+            }
+        ",
+    };
+
+    let bindings = glium::program::ProgramBindings::new().bind_fragment_output("color", 0, 0)
+                                                         .bind_fragment_output("factor", 0, 1);
+
+    let program = match glium::Program::with_bindings(&display, source, &bindings) {
+        Ok(p) => p,
+        Err(glium::program::ProgramCreationError::FragmentOutputLocationNotSupported) => return,
+        Err(glium::program::ProgramCreationError::DualSourceBlendingNotSupported) => return,
+        Err(glium::program::ProgramCreationError::CompilationError(_)) => return,
+        Err(e) => panic!("{:?}", e)
+    };
+
+    let function = glium::BlendingFunction::Addition {
+        source: glium::LinearBlendingFactor::One,
+        destination: glium::LinearBlendingFactor::SourceOneColor,
+    };
+
+    let params = glium::DrawParameters {
+        blend: glium::Blend {
+            color: function,
+            alpha: function,
+            constant_value: (1.0, 1.0, 1.0, 1.0),
+        },
+        .. Default::default()
+    };
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 1.0, 1.0, 1.0);
+
+    match texture.as_surface().draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms,
+                                    &params)
+    {
+        Err(glium::DrawError::BlendingParameterNotSupported) => return,
+        e => e.unwrap()
+    };
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (255, 255, 0, 255));
+    assert_eq!(data.last().unwrap().last().unwrap(), &(255, 255, 0, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn dual_source_blending_without_output() {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = match glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 130
+
+            out vec4 color;
+
+            void main() {
+                color = vec4(1.0, 0.0, 0.0, 1.0);
+            }
+        ",
+        None)
+    {
+        Err(glium::CompilationError(_)) => return,
+        Ok(p) => p,
+        e => e.unwrap()
+    };
+
+    // the outputs can't be enumerated by the backend
+    if program.has_dual_source_output() != Some(false) {
+        return;
+    }
+
+    let function = glium::BlendingFunction::Addition {
+        source: glium::LinearBlendingFactor::One,
+        destination: glium::LinearBlendingFactor::SourceOneColor,
+    };
+
+    let params = glium::DrawParameters {
+        blend: glium::Blend {
+            color: function,
+            alpha: function,
+            constant_value: (1.0, 1.0, 1.0, 1.0),
+        },
+        .. Default::default()
+    };
+
+    let texture = support::build_renderable_texture(&display);
+
+    match texture.as_surface().draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms,
+                                    &params)
+    {
+        Err(glium::DrawError::BlendingParameterNotSupported) => (),
+        Err(glium::DrawError::DualSourceOutputMissing) => (),
+        e => panic!("{:?}", e)
+    };
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn dual_source_blending_too_many_draw_buffers() {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let max = display.get_capabilities().max_dual_source_draw_buffers;
+    if max == 0 || max >= 2 {
+        return;
+    }
+
+    let source = glium::program::ProgramCreationInput::SourceCode {
+        tessellation_control_shader: None,
+        tessellation_evaluation_shader: None,
+        geometry_shader: None,
+        transform_feedback_varyings: None,
+        outputs_srgb: false,
+        uses_point_size: false,
+
+        vertex_shader: "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        fragment_shader: "
+            #version 130
+
+            out vec4 color;
+            out vec4 factor;
+
+            void main() {
+                color = vec4(1.0, 0.0, 0.0, 1.0);
+                factor = vec4(0.0, 1.0, 0.0, 1.0);
+            }
+        ",
+    };
+
+    let bindings = glium::program::ProgramBindings::new().bind_fragment_output("color", 0, 0)
+                                                         .bind_fragment_output("factor", 0, 1);
+
+    let program = match glium::Program::with_bindings(&display, source, &bindings) {
+        Ok(p) => p,
+        Err(glium::program::ProgramCreationError::FragmentOutputLocationNotSupported) => return,
+        Err(glium::program::ProgramCreationError::DualSourceBlendingNotSupported) => return,
+        Err(glium::program::ProgramCreationError::CompilationError(_)) => return,
+        Err(e) => panic!("{:?}", e)
+    };
+
+    let function = glium::BlendingFunction::Addition {
+        source: glium::LinearBlendingFactor::One,
+        destination: glium::LinearBlendingFactor::SourceOneColor,
+    };
+
+    let params = glium::DrawParameters {
+        blend: glium::Blend {
+            color: function,
+            alpha: function,
+            constant_value: (1.0, 1.0, 1.0, 1.0),
+        },
+        .. Default::default()
+    };
+
+    let color1 = support::build_renderable_texture(&display);
+    let color2 = support::build_renderable_texture(&display);
+
+    let mut framebuffer = glium::framebuffer::MultiOutputFrameBuffer::with_locations(&display,
+                                        vec![(0, &color1), (1, &color2)]).unwrap();
+
+    match framebuffer.draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms, &params) {
+        Err(glium::DrawError::TooManyDualSourceDrawBuffers) => (),
+        e => panic!("{:?}", e)
+    };
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn framebuffer_srgb_toggle() {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = program!(&display,
+        110 => {
+            vertex: "
+                #version 110
+
+                attribute vec2 position;
+
+                void main() {
+                    gl_Position = vec4(position, 0.0, 1.0);
+                }
+            ",
+            fragment: "
+                #version 110
+
+                void main() {
+                    gl_FragColor = vec4(0.5, 0.5, 0.5, 1.0);
+                }
+            ",
+        },
+        100 => {
+            vertex: "
+                #version 100
+
+                attribute lowp vec2 position;
+
+                void main() {
+                    gl_Position = vec4(position, 0.0, 1.0);
+                }
+            ",
+            fragment: "
+                #version 100
+
+                void main() {
+                    gl_FragColor = vec4(0.5, 0.5, 0.5, 1.0);
+                }
+            ",
+        },
+    ).unwrap();
+
+    let draw = |framebuffer_srgb| {
+        let texture = match glium::texture::SrgbTexture2d::empty(&display, 16, 16) {
+            Ok(t) => t,
+            Err(_) => return None
+        };
+
+        let params = glium::DrawParameters {
+            framebuffer_srgb: Some(framebuffer_srgb),
+            .. Default::default()
+        };
+
+        let mut framebuffer = glium::framebuffer::SimpleFrameBuffer::new(&display,
+                                                                         &texture).unwrap();
+        match framebuffer.draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms, &params) {
+            Err(glium::DrawError::FramebufferSrgbNotSupported) => return None,
+            Ok(_) => (),
+            e => e.unwrap()
+        }
+
+        let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+        Some(data[8][8])
+    };
+
+    let encoded = draw(true);
+    let raw = draw(false);
+
+    if let (Some(encoded), Some(raw)) = (encoded, raw) {
+        assert!(encoded != raw);
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn two_sided_stencil() {
+    use glium::draw_parameters::Stencil;
+    use glium::{StencilOperation, StencilTest};
+
+    let display = support::build_display();
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+
+    let texture = support::build_renderable_texture(&display);
+    let stencil = match glium::framebuffer::StencilRenderBuffer::new(&display,
+                                        glium::texture::StencilFormat::I8, 1024, 1024)
+    {
+        Ok(s) => s,
+        Err(_) => return
+    };
+
+    let mut framebuffer = match glium::framebuffer::SimpleFrameBuffer::with_stencil_buffer(
+                                                                &display, &texture, &stencil)
+    {
+        Ok(f) => f,
+        Err(_) => return
+    };
+
+    framebuffer.clear_color_and_stencil((0.0, 0.0, 0.0, 0.0), 0);
+
+    // writes 1 for clockwise faces and 2 for counter-clockwise faces
+    let params = glium::DrawParameters {
+        stencil: Stencil {
+            reference_value_clockwise: 1,
+            depth_pass_operation_clockwise: StencilOperation::Replace,
+            write_mask_clockwise: 0x1,
+            reference_value_counter_clockwise: 2,
+            depth_pass_operation_counter_clockwise: StencilOperation::Replace,
+            write_mask_counter_clockwise: 0x2,
+            .. Default::default()
+        },
+        color_mask: (false, false, false, false),
+        .. Default::default()
+    };
+
+    match framebuffer.draw(&vb, &ib, &program, &uniform!{}, &params) {
+        Err(glium::DrawError::StencilSeparateNotSupported) => return,
+        r => r.unwrap()
+    };
+
+    // only draws where the value written above matches
+    let params = glium::DrawParameters {
+        stencil: Stencil {
+            test_clockwise: StencilTest::IfEqual { mask: 0xff },
+            reference_value_clockwise: 1,
+            test_counter_clockwise: StencilTest::IfEqual { mask: 0xff },
+            reference_value_counter_clockwise: 2,
+            .. Default::default()
+        },
+        .. Default::default()
+    };
+
+    framebuffer.draw(&vb, &ib, &program, &uniform!{}, &params).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(255, 0, 0, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn color_mask_per_attachment() {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = match glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 330
+
+            layout(location = 0) out vec4 color1;
+            layout(location = 1) out vec4 color2;
+
+            void main() {
+                color1 = vec4(1.0, 1.0, 1.0, 1.0);
+                color2 = vec4(1.0, 1.0, 1.0, 1.0);
+            }
+        ",
+        None)
+    {
+        Err(glium::CompilationError(_)) => return,
+        Ok(p) => p,
+        e => e.unwrap()
+    };
+
+    let color1 = support::build_renderable_texture(&display);
+    let color2 = support::build_renderable_texture(&display);
+
+    let mut framebuffer = glium::framebuffer::MultiOutputFrameBuffer::with_locations(&display,
+                                        vec![(0, &color1), (1, &color2)]).unwrap();
+    framebuffer.clear_color(0.0, 0.0, 0.0, 1.0);
+
+    let masks = [(true, false, false, false), (false, true, true, false)];
+
+    let params = glium::DrawParameters {
+        color_mask_per_attachment: Some(&masks[..]),
+        .. Default::default()
+    };
+
+    match framebuffer.draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms, &params) {
+        Err(glium::DrawError::PerAttachmentColorMaskNotSupported) => return,
+        e => e.unwrap()
+    };
+
+    let data1: Vec<Vec<(u8, u8, u8, u8)>> = color1.read();
+    let data2: Vec<Vec<(u8, u8, u8, u8)>> = color2.read();
+    assert_eq!(data1[0][0], (255, 0, 0, 255));
+    assert_eq!(data2[0][0], (0, 255, 255, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn logic_operation_xor() {
+    let display = support::build_display();
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+
+    let params = glium::DrawParameters {
+        logic_operation: Some(glium::LogicOperation::Xor),
+        .. Default::default()
+    };
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(1.0, 1.0, 0.0, 1.0);
+
+    match texture.as_surface().draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms,
+                                    &params)
+    {
+        Err(glium::DrawError::LogicOperationNotSupported) => return,
+        e => e.unwrap()
+    };
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (0, 255, 0, 0));
+    assert_eq!(data.last().unwrap().last().unwrap(), &(0, 255, 0, 0));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn sample_coverage() {
+    let display = support::build_display();
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+
+    let params = glium::DrawParameters {
+        alpha_to_coverage: true,
+        sample_coverage: Some((1.0, false)),
+        .. Default::default()
+    };
+
+    texture.as_surface().draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms,
+                              &params).unwrap();
+
+    // the coverage is ignored when the target isn't multisampled
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (255, 0, 0, 255));
+    assert_eq!(data.last().unwrap().last().unwrap(), &(255, 0, 0, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn min_sample_shading() {
+    let display = support::build_display();
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+
+    let params = glium::DrawParameters {
+        min_sample_shading: Some(1.0),
+        .. Default::default()
+    };
+
+    match texture.as_surface().draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms,
+                                    &params)
+    {
+        Err(glium::DrawError::SampleShadingNotSupported) => return,
+        e => e.unwrap()
+    };
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (255, 0, 0, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn polygon_offset_fill() {
+    let display = support::build_display();
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+
+    let color = support::build_renderable_texture(&display);
+    let depth = match glium::framebuffer::DepthRenderBuffer::new(&display,
+                                        glium::texture::DepthFormat::I24, 1024, 1024)
+    {
+        Ok(d) => d,
+        Err(_) => return
+    };
+
+    let mut framebuffer = glium::framebuffer::SimpleFrameBuffer::with_depth_buffer(&display,
+                                                                        &color, &depth).unwrap();
+    framebuffer.clear_color_and_depth((0.0, 0.0, 0.0, 0.0), 0.5);
+
+    // the fullscreen quad has a depth of 0.5, so it is only drawn thanks to the offset
+    let params = glium::DrawParameters {
+        depth: glium::Depth {
+            test: glium::DepthTest::IfLess,
+            .. Default::default()
+        },
+        polygon_offset: glium::PolygonOffset {
+            units: -1000.0,
+            fill: true,
+            .. Default::default()
+        },
+        .. Default::default()
+    };
+
+    framebuffer.draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms, &params).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = color.read();
+    assert_eq!(data[0][0], (255, 0, 0, 255));
+    assert_eq!(data.last().unwrap().last().unwrap(), &(255, 0, 0, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn polygon_mode_line() {
+    let display = support::build_display();
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+
+    let params = glium::DrawParameters {
+        polygon_mode: glium::PolygonMode::Line,
+        .. Default::default()
+    };
+
+    match texture.as_surface().draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms,
+                                    &params)
+    {
+        Err(glium::DrawError::PolygonModeNotSupported) => return,
+        e => e.unwrap()
+    };
+
+    // only the edges of the two triangles are drawn
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[256][768], (0, 0, 0, 0));
+    assert_eq!(data[768][256], (0, 0, 0, 0));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn viewport_array() {
+    let display = support::build_display();
+
+    // without a geometry shader, only the first viewport is used
+    let viewports = [
+        glium::Rect { left: 0, bottom: 0, width: 1, height: 1 },
+        glium::Rect { left: 2, bottom: 2, width: 2, height: 2 },
+    ];
+
+    let params = glium::DrawParameters {
+        viewports: Some(&viewports),
+        .. Default::default()
+    };
+
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    match texture.as_surface().draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms,
+                                    &params)
+    {
+        Err(glium::DrawError::ViewportArrayNotSupported) => return,
+        Err(glium::DrawError::TooManyViewports) => return,
+        res => res.unwrap()
+    };
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+
+    assert_eq!(data[0][0], (255, 0, 0, 255));
+    assert_eq!(data[0][1], (0, 0, 0, 0));
+    assert_eq!(data[1][0], (0, 0, 0, 0));
+    assert_eq!(data[2][2], (0, 0, 0, 0));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn depth_ranges() {
+    let display = support::build_display();
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+
+    let color = support::build_renderable_texture(&display);
+    let depth = match glium::framebuffer::DepthRenderBuffer::new(&display,
+                                        glium::texture::DepthFormat::I24, 1024, 1024)
+    {
+        Ok(d) => d,
+        Err(_) => return
+    };
+
+    let mut framebuffer = glium::framebuffer::SimpleFrameBuffer::with_depth_buffer(&display,
+                                                                        &color, &depth).unwrap();
+    framebuffer.clear_color_and_depth((0.0, 0.0, 0.0, 0.0), 0.5);
+
+    // `depth.range` alone would put the quad behind the cleared depth
+    let ranges = [(0.25, 0.25)];
+    let params = glium::DrawParameters {
+        depth: glium::Depth {
+            test: glium::DepthTest::IfLess,
+            range: (0.75, 0.75),
+            .. Default::default()
+        },
+        depth_ranges: Some(&ranges),
+        .. Default::default()
+    };
+
+    match framebuffer.draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms, &params) {
+        Err(glium::DrawError::ViewportArrayNotSupported) => return,
+        res => res.unwrap()
+    };
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = color.read();
+    assert_eq!(data[0][0], (255, 0, 0, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn point_sprite_origin_lower_left() {
+    let display = support::build_display();
+
+    let vertex_buffer = {
+        #[derive(Copy, Clone)]
+        struct Vertex {
+            position: [f32; 2],
+        }
+
+        implement_vertex!(Vertex, position);
+
+        glium::VertexBuffer::new(&display, &[Vertex { position: [0.0, 0.0] }]).unwrap()
+    };
+
+    let program = match program!(&display,
+        140 => {
+            vertex: "
+                #version 140
+
+                in vec2 position;
+
+                void main() {
+                    gl_Position = vec4(position, 0.0, 1.0);
+                }
+            ",
+            fragment: "
+                #version 140
+
+                out vec4 color;
+                void main() {
+                    color = vec4(gl_PointCoord.y, 0.0, 0.0, 1.0);
+                }
+            "
+        },
+    ) {
+        Ok(p) => p,
+        Err(_) => return
+    };
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+
+    let params = glium::DrawParameters {
+        point_size: Some(64.0),
+        point_sprite_origin: glium::draw_parameters::PointSpriteOrigin::LowerLeft,
+        .. Default::default()
+    };
+
+    match texture.as_surface().draw(&vertex_buffer,
+                                    &glium::index::NoIndices(PrimitiveType::Points),
+                                    &program, &glium::uniforms::EmptyUniforms, &params)
+    {
+        Err(glium::DrawError::FixedPointSizeNotSupported) => return,
+        Err(glium::DrawError::PointSpriteOriginNotSupported) => return,
+        e => e.unwrap()
+    };
+
+    // `gl_PointCoord.y` increases towards the top of the point
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert!(data[512 - 24][512].0 < data[512 + 24][512].0);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn line_width_clamped() {
+    let display = support::build_display();
+    let (vb, _, program) = support::build_fullscreen_red_pipeline(&display);
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+
+    // the width is clamped to the range supported by the backend instead of producing an error
+    let params = glium::DrawParameters {
+        line_width: Some(1000000.0),
+        .. Default::default()
+    };
+
+    texture.as_surface().draw(&vb, &glium::index::NoIndices(PrimitiveType::LinesList),
+                              &program, &glium::uniforms::EmptyUniforms, &params).unwrap();
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn color_clamping() {
+    let display = support::build_display();
+    let (vb, ib, _) = support::build_fullscreen_red_pipeline(&display);
+
+    let program = match glium::Program::from_source(&display,
+        "
+            #version 140
+
+            in vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 140
+
+            out vec4 color;
+            void main() {
+                color = vec4(2.0, 0.0, 0.0, 1.0);
+            }
+        ",
+        None)
+    {
+        Ok(p) => p,
+        Err(_) => return
+    };
+
+    let texture = match glium::texture::Texture2d::empty_with_format(&display,
+                            glium::texture::UncompressedFloatFormat::F32F32F32F32,
+                            glium::texture::MipmapsOption::NoMipmap, 1024, 1024)
+    {
+        Ok(t) => t,
+        Err(_) => return
+    };
+
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+
+    let params = glium::DrawParameters {
+        color_clamping: Some(true),
+        .. Default::default()
+    };
+
+    match texture.as_surface().draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms,
+                                    &params)
+    {
+        Err(glium::DrawError::ColorClampingNotSupported) => return,
+        e => e.unwrap()
+    };
+
+    let data: Vec<Vec<(f32, f32, f32, f32)>> = texture.read();
+    assert_eq!(data[0][0], (1.0, 0.0, 0.0, 1.0));
+
+    display.assert_no_error(None);
+}
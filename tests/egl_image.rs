@@ -0,0 +1,66 @@
+#[macro_use]
+extern crate glium;
+
+use glium::texture::egl_image::{self, EglImageError};
+use glium::texture::UncompressedFloatFormat;
+
+mod support;
+
+#[test]
+fn import_not_supported() {
+    let display = support::build_display();
+
+    if egl_image::is_egl_image_import_supported(&display) {
+        return;
+    }
+
+    let result = unsafe {
+        egl_image::import_egl_image(&display, std::ptr::null(),
+                                    UncompressedFloatFormat::U8U8U8U8, 2, 2)
+    };
+
+    match result {
+        Err(EglImageError::NotSupported) => (),
+        _ => panic!()
+    };
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn export_and_import() {
+    let display = support::build_display();
+
+    if !egl_image::is_egl_image_import_supported(&display) {
+        return;
+    }
+
+    let source = glium::texture::Texture2d::with_mipmaps(&display, vec![
+        vec![(255u8, 0u8, 0u8, 255u8), (0, 255, 0, 255)],
+        vec![(0, 0, 255, 255), (255, 255, 255, 255)],
+    ], glium::texture::MipmapsOption::NoMipmap).unwrap();
+
+    // the backend doesn't necessarily use EGL
+    let image = match egl_image::export_egl_image(&source) {
+        Ok(image) => image,
+        Err(_) => return
+    };
+
+    let texture = unsafe {
+        egl_image::import_egl_image(&display, image.get_image(),
+                                    UncompressedFloatFormat::U8U8U8U8, 2, 2).unwrap()
+    };
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (255, 0, 0, 255));
+    assert_eq!(data[1][1], (255, 255, 255, 255));
+
+    if egl_image::is_egl_image_external_supported(&display) {
+        let external = unsafe {
+            egl_image::import_egl_image_external(&display, image.get_image(), 2, 2).unwrap()
+        };
+        assert_eq!(external.get_width(), 2);
+    }
+
+    display.assert_no_error(None);
+}
@@ -98,6 +98,75 @@ fn simple_render_to_texture() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn render_to_renderbuffers() {
+    use glium::framebuffer::{RenderBuffer, DepthRenderBuffer, StencilRenderBuffer};
+
+    let display = support::build_display();
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+
+    let color = RenderBuffer::new(&display, glium::texture::UncompressedFloatFormat::U8U8U8U8,
+                                  64, 64).unwrap();
+    let depth = match DepthRenderBuffer::new(&display, glium::texture::DepthFormat::I24, 64, 64) {
+        Ok(d) => d,
+        Err(_) => return
+    };
+    let stencil = match StencilRenderBuffer::new(&display, glium::texture::StencilFormat::I8,
+                                                 64, 64)
+    {
+        Ok(s) => s,
+        Err(_) => return
+    };
+
+    let mut framebuffer = match glium::framebuffer::SimpleFrameBuffer::with_depth_and_stencil_buffer(
+                                                            &display, &color, &depth, &stencil)
+    {
+        Ok(f) => f,
+        Err(_) => return        // separate depth and stencil attachments are not always supported
+    };
+
+    framebuffer.clear_all((0.0, 0.0, 0.0, 1.0), 1.0, 0);
+    framebuffer.draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms,
+                     &Default::default()).unwrap();
+
+    assert_eq!(framebuffer.get_dimensions(), (64, 64));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn multisample_renderbuffer() {
+    use glium::framebuffer::{RenderBuffer, DepthRenderBuffer};
+
+    let display = support::build_display();
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+
+    let color = match RenderBuffer::new_multisample(&display,
+                                                    glium::texture::UncompressedFloatFormat::U8U8U8U8,
+                                                    64, 64, 4)
+    {
+        Ok(b) => b,
+        Err(_) => return
+    };
+
+    let depth = match DepthRenderBuffer::new_multisample(&display, glium::texture::DepthFormat::I24,
+                                                         64, 64, 4)
+    {
+        Ok(b) => b,
+        Err(_) => return
+    };
+
+    assert_eq!(color.get_samples(), Some(4));
+
+    let mut framebuffer = glium::framebuffer::SimpleFrameBuffer::with_depth_buffer(&display,
+                                                                    &color, &depth).unwrap();
+    framebuffer.clear_color_and_depth((0.0, 0.0, 0.0, 1.0), 1.0);
+    framebuffer.draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms,
+                     &Default::default()).unwrap();
+
+    display.assert_no_error(None);
+}
+
 #[test]
 fn depth_texture2d() {
     use std::iter;
@@ -254,6 +323,91 @@ fn multioutput() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn multioutput_locations() {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = match glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 330
+
+            layout(location = 0) out vec4 first;
+            layout(location = 1) out vec4 second;
+
+            void main() {
+                first = vec4(0.0, 1.0, 0.0, 1.0);
+                second = vec4(0.0, 0.0, 1.0, 1.0);
+            }
+        ",
+        None)
+    {
+        Err(glium::CompilationError(_)) => return,
+        Ok(p) => p,
+        e => e.unwrap()
+    };
+
+    let color1 = glium::Texture2d::empty_with_format(&display,
+                                               glium::texture::UncompressedFloatFormat::U8U8U8U8,
+                                               glium::texture::MipmapsOption::NoMipmap,
+                                               64, 64).unwrap();
+    let color2 = glium::Texture2d::empty_with_format(&display,
+                                               glium::texture::UncompressedFloatFormat::U8U8U8U8,
+                                               glium::texture::MipmapsOption::NoMipmap,
+                                               64, 64).unwrap();
+
+    // the attachments are passed in the opposite order of the locations
+    let mut framebuffer = glium::framebuffer::MultiOutputFrameBuffer::with_locations(&display,
+                                        vec![(1, &color2), (0, &color1)]).unwrap();
+
+    framebuffer.clear_color(0.0, 0.0, 0.0, 1.0);
+    framebuffer.draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms,
+                     &Default::default()).unwrap();
+
+    let read_back1: Vec<Vec<(u8, u8, u8, u8)>> = color1.read();
+    let read_back2: Vec<Vec<(u8, u8, u8, u8)>> = color2.read();
+    assert_eq!(read_back1[32][32], (0, 255, 0, 255));
+    assert_eq!(read_back2[32][32], (0, 0, 255, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn mipmap_level() {
+    let display = support::build_display();
+
+    let texture = glium::Texture2d::empty_with_mipmaps(&display,
+                                                       glium::texture::MipmapsOption::EmptyMipmaps,
+                                                       128, 128).unwrap();
+
+    let mipmap = texture.mipmap(2).unwrap();
+    let mut framebuffer = match glium::framebuffer::SimpleFrameBuffer::new(&display, mipmap) {
+        Err(glium::framebuffer::ValidationError::MipmapLevelNotSupported) => return,
+        Ok(fb) => fb,
+        e => e.unwrap()
+    };
+    assert_eq!(framebuffer.get_dimensions(), (32, 32));
+
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+    framebuffer.draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms,
+                     &Default::default()).unwrap();
+
+    let mut framebuffer = mipmap.as_surface();
+    assert_eq!(framebuffer.get_dimensions(), (32, 32));
+    framebuffer.clear_color(0.0, 1.0, 0.0, 1.0);
+
+    display.assert_no_error(None);
+}
+
 #[test]
 fn array_level() {
     let display = support::build_display();
@@ -276,6 +430,131 @@ fn array_level() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn layered_array() {
+    use glium::framebuffer::LayeredFrameBuffer;
+
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    if !LayeredFrameBuffer::is_supported(&display) {
+        return;
+    }
+
+    let texture = match glium::texture::Texture2dArray::empty(&display, 128, 128, 4) {
+        Ok(t) => t,
+        Err(_) => return
+    };
+
+    let program = match glium::Program::from_source(&display,
+        "
+            #version 150
+
+            in vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 150
+
+            out vec4 color;
+
+            void main() {
+                color = vec4(1.0, 0.0, 0.0, 1.0);
+            }
+        ",
+        Some("
+            #version 150
+
+            layout(triangles) in;
+            layout(triangle_strip, max_vertices = 12) out;
+
+            void main() {
+                for (int layer = 0; layer < 4; ++layer) {
+                    for (int i = 0; i < 3; ++i) {
+                        gl_Layer = layer;
+                        gl_Position = gl_in[i].gl_Position;
+                        EmitVertex();
+                    }
+                    EndPrimitive();
+                }
+            }
+        "))
+    {
+        Err(glium::CompilationError(_)) => return,
+        Ok(p) => p,
+        e => e.unwrap()
+    };
+
+    let mut framebuffer = LayeredFrameBuffer::new(&display, &texture).unwrap();
+    assert_eq!(framebuffer.get_dimensions(), (128, 128));
+    assert_eq!(framebuffer.get_layers(), 4);
+
+    framebuffer.clear_color(0.0, 0.0, 0.0, 1.0);
+    framebuffer.draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms,
+                     &Default::default()).unwrap();
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn layered_blit_first_layer() {
+    use glium::framebuffer::LayeredFrameBuffer;
+
+    let display = support::build_display();
+
+    if !LayeredFrameBuffer::is_supported(&display) {
+        return;
+    }
+
+    let texture = match glium::texture::Texture2dArray::empty(&display, 128, 128, 4) {
+        Ok(t) => t,
+        Err(_) => return
+    };
+
+    let mut framebuffer = LayeredFrameBuffer::new(&display, &texture).unwrap();
+    framebuffer.clear_color(0.0, 1.0, 0.0, 1.0);
+
+    let target = support::build_renderable_texture(&display);
+    target.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+
+    let rect = glium::Rect { left: 0, bottom: 0, width: 128, height: 128 };
+    let blit_target = glium::BlitTarget { left: 0, bottom: 0, width: 128, height: 128 };
+    framebuffer.blit_color(&rect, &target.as_surface(), &blit_target,
+                           glium::uniforms::MagnifySamplerFilter::Nearest);
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = target.read();
+    assert_eq!(data[0][0], (0, 255, 0, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn layered_depth_cubemap() {
+    use glium::framebuffer::LayeredFrameBuffer;
+
+    let display = support::build_display();
+
+    if !LayeredFrameBuffer::is_supported(&display) {
+        return;
+    }
+
+    let texture = match glium::texture::DepthCubemap::empty(&display, 128) {
+        Ok(t) => t,
+        Err(_) => return
+    };
+
+    let mut framebuffer = LayeredFrameBuffer::depth_only(&display, &texture).unwrap();
+    assert_eq!(framebuffer.get_dimensions(), (128, 128));
+    assert_eq!(framebuffer.get_layers(), 6);
+
+    framebuffer.clear_depth(1.0);
+
+    display.assert_no_error(None);
+}
+
 #[test]
 fn cubemap_layer() {
     // ignoring test on travis
@@ -304,6 +583,42 @@ fn cubemap_layer() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn cubemap_face_render() {
+    use glium::texture::CubeLayer;
+
+    // ignoring test on travis, see `cubemap_layer`
+    if ::std::env::var("TRAVIS").is_ok() {
+        return;
+    }
+
+    let display = support::build_display();
+
+    let texture = match glium::texture::Cubemap::empty(&display, 16) {
+        Ok(t) => t,
+        Err(_) => return
+    };
+
+    for &face in &[CubeLayer::PositiveX, CubeLayer::NegativeX] {
+        let mut framebuffer = glium::framebuffer::SimpleFrameBuffer::new(&display,
+                                                                 texture.face(face)).unwrap();
+        framebuffer.clear_color(0.0, 0.0, 0.0, 1.0);
+    }
+
+    let mut framebuffer = glium::framebuffer::SimpleFrameBuffer::new(&display,
+                                                texture.face(CubeLayer::PositiveX)).unwrap();
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+    framebuffer.draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms,
+                     &Default::default()).unwrap();
+
+    let positive_x: Vec<Vec<(u8, u8, u8, u8)>> = texture.face(CubeLayer::PositiveX).read();
+    let negative_x: Vec<Vec<(u8, u8, u8, u8)>> = texture.face(CubeLayer::NegativeX).read();
+    assert_eq!(positive_x[8][8], (255, 0, 0, 255));
+    assert_eq!(negative_x[8][8], (0, 0, 0, 255));
+
+    display.assert_no_error(None);
+}
+
 #[test]
 #[should_panic]
 fn multi_color_attachments_maximum() {
@@ -447,3 +762,596 @@ fn empty_framebuffer_simple_draw() {
 
     display.assert_no_error(None);
 }
+
+#[test]
+fn empty_framebuffer_properties() {
+    use glium::framebuffer::EmptyFrameBuffer;
+
+    let display = support::build_display();
+
+    // ignore the test
+    if !EmptyFrameBuffer::is_supported(&display) ||
+       EmptyFrameBuffer::get_max_supported_samples(&display).unwrap_or(0) < 4
+    {
+        return;
+    }
+
+    let fb = EmptyFrameBuffer::new(&display, 256, 128, None, Some(4), true).unwrap();
+    assert_eq!(fb.get_dimensions(), (256, 128));
+    assert_eq!(fb.get_samples(), Some(4));
+    assert_eq!(fb.get_layers(), None);
+    assert!(!fb.has_depth_buffer());
+    assert!(!fb.has_stencil_buffer());
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn multisample_resolve() {
+    let display = support::build_display();
+
+    let multisampled = match glium::texture::Texture2dMultisample::empty(&display, 128, 128, 4) {
+        Ok(t) => t,
+        Err(_) => return
+    };
+
+    let resolved = glium::Texture2d::empty(&display, 128, 128).unwrap();
+    resolved.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+
+    let mut surface = multisampled.as_surface();
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+    surface.clear_color(0.0, 0.0, 0.0, 1.0);
+    surface.draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms,
+                 &Default::default()).unwrap();
+
+    surface.resolve_to(&resolved.as_surface());
+
+    let read_back: Vec<Vec<(u8, u8, u8, u8)>> = resolved.read();
+    assert_eq!(read_back[0][0], (255, 0, 0, 255));
+    assert_eq!(read_back[64][64], (255, 0, 0, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+#[should_panic]
+fn multisample_resolve_wrong_dimensions() {
+    let display = support::build_display();
+
+    let multisampled = match glium::texture::Texture2dMultisample::empty(&display, 128, 128, 4) {
+        Ok(t) => t,
+        Err(_) => panic!()
+    };
+
+    let resolved = glium::Texture2d::empty(&display, 64, 64).unwrap();
+    multisampled.as_surface().resolve_to(&resolved.as_surface());
+}
+
+#[test]
+fn invalidate() {
+    let display = support::build_display();
+
+    let color = support::build_renderable_texture(&display);
+    let depth = glium::framebuffer::DepthRenderBuffer::new(&display,
+                                                           glium::texture::DepthFormat::I24,
+                                                           1024, 1024).unwrap();
+
+    let mut framebuffer = glium::framebuffer::SimpleFrameBuffer::with_depth_buffer(&display,
+                                                                    &color, &depth).unwrap();
+    framebuffer.clear_color_and_depth((1.0, 0.0, 0.0, 1.0), 1.0);
+    framebuffer.invalidate_depth();
+    framebuffer.invalidate_color(0);
+    framebuffer.invalidate_color(5);
+    framebuffer.invalidate_all();
+
+    let mut frame = display.draw();
+    frame.invalidate_all();
+    frame.finish().unwrap();
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn clear_individual_attachments() {
+    use glium::{Api, Version};
+
+    let display = support::build_display();
+
+    if !(display.get_opengl_version() >= &Version(Api::Gl, 3, 0) ||
+         display.get_opengl_version() >= &Version(Api::GlEs, 3, 0))
+    {
+        return;
+    }
+
+    let color1 = glium::Texture2d::empty(&display, 128, 128).unwrap();
+    let color2 = glium::Texture2d::empty(&display, 128, 128).unwrap();
+
+    {
+        let mut framebuffer = glium::framebuffer::MultiOutputFrameBuffer::new(&display,
+                                   [("color1", &color1), ("color2", &color2)].iter().cloned()).unwrap();
+
+        framebuffer.clear_color_attachment(0, (1.0, 0.0, 0.0, 1.0)).unwrap();
+        framebuffer.clear_color_attachment(1, (0.0, 1.0, 0.0, 1.0)).unwrap();
+    }
+
+    let read_back1: Vec<Vec<(u8, u8, u8, u8)>> = color1.read();
+    assert_eq!(read_back1[0][0], (255, 0, 0, 255));
+    assert_eq!(read_back1[64][64], (255, 0, 0, 255));
+
+    let read_back2: Vec<Vec<(u8, u8, u8, u8)>> = color2.read();
+    assert_eq!(read_back2[0][0], (0, 255, 0, 255));
+    assert_eq!(read_back2[64][64], (0, 255, 0, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn clear_integral_attachment() {
+    use glium::{Api, Version};
+
+    let display = support::build_display();
+
+    if !(display.get_opengl_version() >= &Version(Api::Gl, 3, 0) ||
+         display.get_opengl_version() >= &Version(Api::GlEs, 3, 0))
+    {
+        return;
+    }
+
+    let texture = match glium::texture::UnsignedTexture2d::empty(&display, 128, 128) {
+        Ok(t) => t,
+        Err(_) => return
+    };
+
+    let depth = glium::framebuffer::DepthRenderBuffer::new(&display,
+                                                           glium::texture::DepthFormat::I24,
+                                                           128, 128).unwrap();
+
+    let mut framebuffer = glium::framebuffer::SimpleFrameBuffer::with_depth_buffer(&display,
+                                                                &texture, &depth).unwrap();
+    framebuffer.clear_color_attachment_uint(0, [7, 0, 0, 1]).unwrap();
+    framebuffer.clear_depth_value(0.5).unwrap();
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn read_pixels_region() {
+    use glium::Rect;
+
+    let display = support::build_display();
+
+    let texture = glium::Texture2d::empty(&display, 128, 128).unwrap();
+    let mut framebuffer = glium::framebuffer::SimpleFrameBuffer::new(&display, &texture).unwrap();
+    framebuffer.clear_color(0.0, 0.0, 0.0, 1.0);
+    framebuffer.clear(Some(&Rect { left: 16, bottom: 16, width: 8, height: 8 }),
+                      Some((1.0, 0.0, 0.0, 1.0)), false, None, None);
+
+    let rect = Rect { left: 12, bottom: 16, width: 8, height: 2 };
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = framebuffer.read_pixels(&rect).unwrap();
+
+    assert_eq!(data.len(), 2);
+    assert_eq!(data[0].len(), 8);
+    assert_eq!(data[0][0], (0, 0, 0, 255));
+    assert_eq!(data[1][3], (0, 0, 0, 255));
+    assert_eq!(data[0][4], (255, 0, 0, 255));
+    assert_eq!(data[1][7], (255, 0, 0, 255));
+
+    // other formats are not guaranteed to be supported by OpenGL ES
+    if let Ok(data) = framebuffer.read_pixels::<(f32, f32, f32, f32), Vec<Vec<_>>>(&rect) {
+        assert_eq!(data[0][4], (1.0, 0.0, 0.0, 1.0));
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn read_pixels_integral() {
+    use glium::{Api, Version, Rect};
+
+    let display = support::build_display();
+
+    if !(display.get_opengl_version() >= &Version(Api::Gl, 3, 0)) {
+        return;
+    }
+
+    let texture = match glium::texture::UnsignedTexture2d::empty(&display, 32, 32) {
+        Ok(t) => t,
+        Err(_) => return
+    };
+
+    let mut framebuffer = glium::framebuffer::SimpleFrameBuffer::new(&display, &texture).unwrap();
+    framebuffer.clear_color_attachment_uint(0, [7, 3, 0, 1]).unwrap();
+
+    let rect = Rect { left: 5, bottom: 5, width: 1, height: 1 };
+    let data: Vec<Vec<(u32, u32, u32, u32)>> = framebuffer.read_pixels(&rect).unwrap();
+    assert_eq!(data[0][0], (7, 3, 0, 1));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn capture() {
+    let display = support::build_display();
+
+    let texture = glium::Texture2d::empty(&display, 30, 17).unwrap();
+    let mut framebuffer = glium::framebuffer::SimpleFrameBuffer::new(&display, &texture).unwrap();
+    framebuffer.clear_color(0.0, 1.0, 0.0, 1.0);
+
+    let image = framebuffer.capture().unwrap();
+    assert_eq!(image.width, 30);
+    assert_eq!(image.height, 17);
+    assert_eq!(image.data.len(), 30 * 17 * 4);
+    assert_eq!(&image.data[.. 4], &[0, 255, 0, 255]);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn read_buffer_selection() {
+    use glium::{Api, Version, Rect};
+
+    let display = support::build_display();
+
+    if !(display.get_opengl_version() >= &Version(Api::Gl, 3, 0) ||
+         display.get_opengl_version() >= &Version(Api::GlEs, 3, 0))
+    {
+        return;
+    }
+
+    let color1 = glium::Texture2d::empty(&display, 16, 16).unwrap();
+    let color2 = glium::Texture2d::empty(&display, 16, 16).unwrap();
+
+    let mut framebuffer = glium::framebuffer::MultiOutputFrameBuffer::new(&display,
+                               [("color1", &color1), ("color2", &color2)].iter().cloned()).unwrap();
+    framebuffer.clear_color_attachment(0, (1.0, 0.0, 0.0, 1.0)).unwrap();
+    framebuffer.clear_color_attachment(1, (0.0, 1.0, 0.0, 1.0)).unwrap();
+
+    let rect = Rect { left: 0, bottom: 0, width: 1, height: 1 };
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = framebuffer.read_pixels(&rect).unwrap();
+    assert_eq!(data[0][0], (255, 0, 0, 255));
+
+    framebuffer.set_read_buffer(1).unwrap();
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = framebuffer.read_pixels(&rect).unwrap();
+    assert_eq!(data[0][0], (0, 255, 0, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn read_buffer_selection_with_locations() {
+    use glium::{Api, Version, Rect};
+
+    let display = support::build_display();
+
+    if !(display.get_opengl_version() >= &Version(Api::Gl, 3, 0) ||
+         display.get_opengl_version() >= &Version(Api::GlEs, 3, 0))
+    {
+        return;
+    }
+
+    let color1 = glium::Texture2d::empty(&display, 16, 16).unwrap();
+    let color2 = glium::Texture2d::empty(&display, 16, 16).unwrap();
+
+    let mut framebuffer = glium::framebuffer::MultiOutputFrameBuffer::with_locations(&display,
+                               [(0, &color1), (2, &color2)].iter().cloned()).unwrap();
+    framebuffer.clear_color_attachment(0, (1.0, 0.0, 0.0, 1.0)).unwrap();
+    framebuffer.clear_color_attachment(2, (0.0, 1.0, 0.0, 1.0)).unwrap();
+
+    // the read buffer is designated by its location, like with `clear_color_attachment`
+    framebuffer.set_read_buffer(2).unwrap();
+
+    let rect = Rect { left: 0, bottom: 0, width: 1, height: 1 };
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = framebuffer.read_pixels(&rect).unwrap();
+    assert_eq!(data[0][0], (0, 255, 0, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn read_buffer_out_of_range() {
+    let display = support::build_display();
+
+    let color = glium::Texture2d::empty(&display, 16, 16).unwrap();
+    let mut framebuffer = glium::framebuffer::MultiOutputFrameBuffer::new(&display,
+                               [("color", &color)].iter().cloned()).unwrap();
+
+    match framebuffer.set_read_buffer(1) {
+        Err(glium::framebuffer::ReadBufferError::NoSuchAttachment(1)) => (),
+        r => panic!("{:?}", r)
+    };
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn depth_only_shadow_pass() {
+    let display = support::build_display();
+
+    let depth = match glium::texture::DepthTexture2d::empty(&display, 64, 64) {
+        Ok(t) => t,
+        Err(_) => return
+    };
+
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+
+    let mut framebuffer = glium::framebuffer::SimpleFrameBuffer::depth_only(&display, &depth)
+                                                                                .unwrap();
+    assert!(framebuffer.has_depth_buffer());
+
+    framebuffer.clear_depth(1.0);
+    framebuffer.draw(&vb, &ib, &program, &uniform!{}, &glium::DrawParameters {
+        depth: glium::Depth {
+            test: glium::DepthTest::IfLess,
+            write: true,
+            .. Default::default()
+        },
+        .. Default::default()
+    }).unwrap();
+
+    // reading colors from a depth-only framebuffer is an error, not an incomplete framebuffer
+    let rect = glium::Rect { left: 0, bottom: 0, width: 1, height: 1 };
+    assert!(framebuffer.read_pixels::<(u8, u8, u8, u8), Vec<Vec<_>>>(&rect).is_err());
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn stencil_only_renderbuffer() {
+    let display = support::build_display();
+
+    let stencil = match glium::framebuffer::StencilRenderBuffer::new(&display,
+                                                    glium::texture::StencilFormat::I8, 64, 64)
+    {
+        Ok(s) => s,
+        Err(_) => return
+    };
+
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+
+    let mut framebuffer = match glium::framebuffer::SimpleFrameBuffer::stencil_only(&display,
+                                                                                    &stencil)
+    {
+        Ok(f) => f,
+        Err(_) => return
+    };
+
+    assert!(!framebuffer.has_depth_buffer());
+    assert_eq!(framebuffer.get_stencil_buffer_bits(), Some(8));
+
+    framebuffer.clear_stencil(0);
+    framebuffer.draw(&vb, &ib, &program, &uniform!{}, &glium::DrawParameters {
+        stencil: glium::draw_parameters::Stencil {
+            test_clockwise: glium::StencilTest::AlwaysPass,
+            test_counter_clockwise: glium::StencilTest::AlwaysPass,
+            depth_pass_operation_clockwise: glium::StencilOperation::Replace,
+            depth_pass_operation_counter_clockwise: glium::StencilOperation::Replace,
+            reference_value_clockwise: 1,
+            reference_value_counter_clockwise: 1,
+            .. Default::default()
+        },
+        .. Default::default()
+    }).unwrap();
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn stencil_only_texture() {
+    let display = support::build_display();
+
+    let stencil = match glium::texture::StencilTexture2d::empty(&display, 64, 64) {
+        Ok(s) => s,
+        Err(_) => return
+    };
+
+    let mut framebuffer = match glium::framebuffer::SimpleFrameBuffer::stencil_only(&display,
+                                                                                    &stencil)
+    {
+        Ok(f) => f,
+        Err(_) => return
+    };
+
+    assert_eq!(framebuffer.get_stencil_buffer_bits(), Some(8));
+    framebuffer.clear_stencil(3);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn separate_depth_and_stencil_completeness() {
+    use glium::framebuffer::{ValidationError, IncompleteFramebufferReason, AttachmentPoint};
+
+    let display = support::build_display();
+
+    let color = support::build_renderable_texture(&display);
+    let (width, height) = (color.get_width(), color.get_height().unwrap());
+
+    let depth = glium::framebuffer::DepthRenderBuffer::new(&display,
+                                            glium::texture::DepthFormat::I24, width, height)
+                                            .unwrap();
+    let stencil = match glium::framebuffer::StencilRenderBuffer::new(&display,
+                                            glium::texture::StencilFormat::I8, width, height)
+    {
+        Ok(s) => s,
+        Err(_) => return
+    };
+
+    // many implementations don't support separate depth and stencil buffers, in which case
+    // the error must describe the attachments
+    match glium::framebuffer::SimpleFrameBuffer::with_depth_and_stencil_buffer(&display, &color,
+                                                                               &depth, &stencil)
+    {
+        Ok(_) => (),
+        Err(ValidationError::IncompleteFramebuffer { reason, attachments }) => {
+            assert_eq!(reason, IncompleteFramebufferReason::UnsupportedCombination);
+
+            if let Some(color) = attachments.colors[0] {
+                assert_eq!(color.point, AttachmentPoint::Color(0));
+                assert!(attachments.colors[1].is_none());
+
+                let depth = attachments.depth.unwrap();
+                assert_eq!(depth.point, AttachmentPoint::Depth);
+                assert!(depth.depth_bits >= 24);
+
+                let stencil = attachments.stencil.unwrap();
+                assert_eq!(stencil.point, AttachmentPoint::Stencil);
+                assert!(stencil.stencil_bits >= 8);
+
+                assert!(attachments.depth_stencil.is_none());
+            }
+        },
+        Err(err) => panic!("{}", err),
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn read_after_drawing_with_smaller_depth_buffer() {
+    let display = support::build_display();
+
+    if !glium::framebuffer::is_dimensions_mismatch_supported(&display) {
+        return;
+    }
+
+    let texture = glium::Texture2d::empty(&display, 128, 128).unwrap();
+    texture.as_surface().clear_color(0.0, 1.0, 0.0, 1.0);
+
+    let depth = glium::framebuffer::DepthRenderBuffer::new(&display,
+                                                           glium::texture::DepthFormat::I24,
+                                                           64, 64).unwrap();
+
+    // the framebuffer object is only 64x64, so reading the texture must not go through it
+    glium::framebuffer::SimpleFrameBuffer::with_depth_buffer(&display, &texture, &depth).unwrap()
+                                                            .clear_color(1.0, 0.0, 0.0, 1.0);
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[10][10], (255, 0, 0, 255));
+    assert_eq!(data[100][100], (0, 255, 0, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn multiview_array() {
+    use glium::framebuffer::LayeredFrameBuffer;
+
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    if !LayeredFrameBuffer::is_multiview_supported(&display) {
+        return;
+    }
+
+    let texture = match glium::texture::Texture2dArray::empty(&display, 64, 64, 4) {
+        Ok(t) => t,
+        Err(_) => return
+    };
+
+    let program = match glium::Program::from_source(&display,
+        "
+            #version 330
+            #extension GL_OVR_multiview2 : require
+            layout(num_views = 2) in;
+
+            in vec2 position;
+            out vec4 v_color;
+
+            void main() {
+                v_color = gl_ViewID_OVR == 0u ? vec4(1.0, 0.0, 0.0, 1.0)
+                                              : vec4(0.0, 1.0, 0.0, 1.0);
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 330
+
+            in vec4 v_color;
+            out vec4 color;
+
+            void main() {
+                color = v_color;
+            }
+        ", None)
+    {
+        Err(glium::CompilationError(_)) => return,
+        Ok(p) => p,
+        e => e.unwrap()
+    };
+
+    // rendering to the layers 1 and 2
+    let mut framebuffer = LayeredFrameBuffer::multiview(&display, &texture, 1, 2).unwrap();
+    assert_eq!(framebuffer.get_dimensions(), (64, 64));
+    assert_eq!(framebuffer.get_layers(), 2);
+
+    framebuffer.clear_color(0.0, 0.0, 0.0, 1.0);
+    framebuffer.draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms,
+                     &Default::default()).unwrap();
+
+    let read_layer = |layer| {
+        let layer = texture.main_level().layer(layer).unwrap();
+        let framebuffer = glium::framebuffer::SimpleFrameBuffer::new(&display, layer).unwrap();
+        let data: Vec<Vec<(u8, u8, u8, u8)>> =
+                    framebuffer.read_pixels(&glium::Rect { left: 32, bottom: 32,
+                                                           width: 1, height: 1 }).unwrap();
+        data[0][0]
+    };
+
+    assert_eq!(read_layer(1), (255, 0, 0, 255));
+    assert_eq!(read_layer(2), (0, 255, 0, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn multiview_out_of_range() {
+    use glium::framebuffer::LayeredFrameBuffer;
+
+    let display = support::build_display();
+
+    if !LayeredFrameBuffer::is_multiview_supported(&display) {
+        return;
+    }
+
+    let texture = match glium::texture::Texture2dArray::empty(&display, 64, 64, 2) {
+        Ok(t) => t,
+        Err(_) => return
+    };
+
+    match LayeredFrameBuffer::multiview(&display, &texture, 1, 2) {
+        Err(glium::framebuffer::ValidationError::InvalidViews) => (),
+        _ => panic!()
+    };
+
+    match LayeredFrameBuffer::multiview(&display, &texture, 0, 0) {
+        Err(glium::framebuffer::ValidationError::InvalidViews) => (),
+        _ => panic!()
+    };
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn stereo_back_right() {
+    use glium::framebuffer::DefaultFramebuffer;
+
+    let display = support::build_display();
+
+    if !display.get_default_framebuffer_properties().stereo {
+        return;
+    }
+
+    let mut left = DefaultFramebuffer::back_left(&display);
+    let mut right = DefaultFramebuffer::back_right(&display);
+    left.clear_color(1.0, 0.0, 0.0, 1.0);
+    right.clear_color(0.0, 1.0, 0.0, 1.0);
+
+    let rect = glium::Rect { left: 0, bottom: 0, width: 1, height: 1 };
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = left.read_pixels(&rect).unwrap();
+    assert_eq!(data[0][0], (255, 0, 0, 255));
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = right.read_pixels(&rect).unwrap();
+    assert_eq!(data[0][0], (0, 255, 0, 255));
+
+    display.assert_no_error(None);
+}
@@ -0,0 +1,324 @@
+#![cfg(any(feature = "cgmath", feature = "nalgebra", feature = "glam"))]
+
+#[macro_use]
+extern crate glium;
+#[cfg(feature = "cgmath")]
+extern crate cgmath;
+#[cfg(feature = "nalgebra")]
+extern crate nalgebra;
+#[cfg(feature = "glam")]
+extern crate glam;
+
+use glium::Surface;
+use glium::uniforms::{AsUniformValue, UniformValue};
+
+mod support;
+
+/// Checks that `as_uniform_value` returns the given variant with the given content.
+macro_rules! check_layout {
+    ($value:expr, $variant:ident, $expected:expr) => (
+        match $value.as_uniform_value() {
+            UniformValue::$variant(v) => assert_eq!(v, $expected),
+            _ => panic!("Wrong uniform value for {}", stringify!($value))
+        }
+    )
+}
+
+/// Draws with `value` bound to a uniform of type `ty`, and checks that the shader sees the
+/// same value as the GLSL expression `expected`.
+///
+/// Returns without checking anything if the GLSL version is not supported.
+fn check_upload<T>(display: &glium::Display, version: &str, ty: &str, expected: &str, value: T)
+                   where T: AsUniformValue
+{
+    let (vb, ib) = support::build_rectangle_vb_ib(display);
+
+    let program = glium::Program::from_source(display,
+        &format!("
+            #version {}
+
+            in vec2 position;
+
+            void main() {{
+                gl_Position = vec4(position, 0.0, 1.0);
+            }}
+        ", version),
+        &format!("
+            #version {}
+
+            uniform {} value;
+            out vec4 f_color;
+
+            void main() {{
+                f_color = value == {} ? vec4(0.0, 1.0, 0.0, 1.0) : vec4(1.0, 0.0, 0.0, 1.0);
+            }}
+        ", version, ty, expected),
+        None);
+
+    // ignoring in case of compilation error (version may not be supported)
+    let program = match program {
+        Ok(p) => p,
+        Err(_) => return
+    };
+
+    let uniforms = glium::uniforms::UniformsStorage::new("value", value);
+
+    let texture = glium::Texture2d::empty(display, 1, 1).unwrap();
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, &ib, &program, &uniforms, &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (0, 255, 0, 255), "Wrong value for uniform of type {}", ty);
+}
+
+#[test]
+#[cfg(feature = "cgmath")]
+fn cgmath_layout() {
+    check_layout!(cgmath::Vector2::new(1.0f32, 2.0), Vec2, [1.0, 2.0]);
+    check_layout!(cgmath::Vector3::new(1.0f32, 2.0, 3.0), Vec3, [1.0, 2.0, 3.0]);
+    check_layout!(cgmath::Vector4::new(1.0f32, 2.0, 3.0, 4.0), Vec4, [1.0, 2.0, 3.0, 4.0]);
+    check_layout!(cgmath::Vector2::new(1.0f64, 2.0), DoubleVec2, [1.0, 2.0]);
+    check_layout!(cgmath::Vector3::new(1.0f64, 2.0, 3.0), DoubleVec3, [1.0, 2.0, 3.0]);
+    check_layout!(cgmath::Vector4::new(1.0f64, 2.0, 3.0, 4.0), DoubleVec4, [1.0, 2.0, 3.0, 4.0]);
+    check_layout!(cgmath::Vector2::new(1i32, 2), IntVec2, [1, 2]);
+    check_layout!(cgmath::Vector3::new(1i32, 2, 3), IntVec3, [1, 2, 3]);
+    check_layout!(cgmath::Vector4::new(1i32, 2, 3, 4), IntVec4, [1, 2, 3, 4]);
+    check_layout!(cgmath::Vector2::new(1u32, 2), UnsignedIntVec2, [1, 2]);
+    check_layout!(cgmath::Vector3::new(1u32, 2, 3), UnsignedIntVec3, [1, 2, 3]);
+    check_layout!(cgmath::Vector4::new(1u32, 2, 3, 4), UnsignedIntVec4, [1, 2, 3, 4]);
+    check_layout!(cgmath::Point2::new(1.0f32, 2.0), Vec2, [1.0, 2.0]);
+    check_layout!(cgmath::Point3::new(1.0f32, 2.0, 3.0), Vec3, [1.0, 2.0, 3.0]);
+    check_layout!(cgmath::Point2::new(1.0f64, 2.0), DoubleVec2, [1.0, 2.0]);
+    check_layout!(cgmath::Point3::new(1.0f64, 2.0, 3.0), DoubleVec3, [1.0, 2.0, 3.0]);
+
+    // the constructors of cgmath take the matrices column by column
+    check_layout!(cgmath::Matrix2::new(1.0f32, 2.0, 3.0, 4.0), Mat2, [[1.0, 2.0], [3.0, 4.0]]);
+    check_layout!(cgmath::Matrix3::new(1.0f32, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0), Mat3,
+                  [[1.0, 2.0, 3.0], [4.0, 5.0, 6.0], [7.0, 8.0, 9.0]]);
+    check_layout!(cgmath::Matrix4::new(1.0f32, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0,
+                                       9.0, 10.0, 11.0, 12.0, 13.0, 14.0, 15.0, 16.0), Mat4,
+                  [[1.0, 2.0, 3.0, 4.0], [5.0, 6.0, 7.0, 8.0],
+                   [9.0, 10.0, 11.0, 12.0], [13.0, 14.0, 15.0, 16.0]]);
+    check_layout!(cgmath::Matrix2::new(1.0f64, 2.0, 3.0, 4.0), DoubleMat2,
+                  [[1.0, 2.0], [3.0, 4.0]]);
+    check_layout!(cgmath::Matrix3::new(1.0f64, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0),
+                  DoubleMat3, [[1.0, 2.0, 3.0], [4.0, 5.0, 6.0], [7.0, 8.0, 9.0]]);
+    check_layout!(cgmath::Matrix4::new(1.0f64, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0,
+                                       9.0, 10.0, 11.0, 12.0, 13.0, 14.0, 15.0, 16.0), DoubleMat4,
+                  [[1.0, 2.0, 3.0, 4.0], [5.0, 6.0, 7.0, 8.0],
+                   [9.0, 10.0, 11.0, 12.0], [13.0, 14.0, 15.0, 16.0]]);
+
+    // `Quaternion::new` takes the scalar part first
+    check_layout!(cgmath::Quaternion::new(4.0f32, 1.0, 2.0, 3.0), Vec4, [1.0, 2.0, 3.0, 4.0]);
+    check_layout!(cgmath::Quaternion::new(4.0f64, 1.0, 2.0, 3.0), DoubleVec4,
+                  [1.0, 2.0, 3.0, 4.0]);
+}
+
+#[test]
+#[cfg(feature = "cgmath")]
+fn cgmath_upload() {
+    let display = support::build_display();
+
+    check_upload(&display, "130", "vec2", "vec2(1.0, 2.0)", cgmath::Vector2::new(1.0f32, 2.0));
+    check_upload(&display, "130", "vec3", "vec3(1.0, 2.0, 3.0)",
+                 cgmath::Vector3::new(1.0f32, 2.0, 3.0));
+    check_upload(&display, "130", "vec4", "vec4(1.0, 2.0, 3.0, 4.0)",
+                 cgmath::Vector4::new(1.0f32, 2.0, 3.0, 4.0));
+    check_upload(&display, "400", "dvec2", "dvec2(1.0, 2.0)", cgmath::Vector2::new(1.0f64, 2.0));
+    check_upload(&display, "400", "dvec3", "dvec3(1.0, 2.0, 3.0)",
+                 cgmath::Vector3::new(1.0f64, 2.0, 3.0));
+    check_upload(&display, "400", "dvec4", "dvec4(1.0, 2.0, 3.0, 4.0)",
+                 cgmath::Vector4::new(1.0f64, 2.0, 3.0, 4.0));
+    check_upload(&display, "130", "ivec2", "ivec2(1, 2)", cgmath::Vector2::new(1i32, 2));
+    check_upload(&display, "130", "ivec3", "ivec3(1, 2, 3)", cgmath::Vector3::new(1i32, 2, 3));
+    check_upload(&display, "130", "ivec4", "ivec4(1, 2, 3, 4)",
+                 cgmath::Vector4::new(1i32, 2, 3, 4));
+    check_upload(&display, "130", "uvec2", "uvec2(1u, 2u)", cgmath::Vector2::new(1u32, 2));
+    check_upload(&display, "130", "uvec3", "uvec3(1u, 2u, 3u)",
+                 cgmath::Vector3::new(1u32, 2, 3));
+    check_upload(&display, "130", "uvec4", "uvec4(1u, 2u, 3u, 4u)",
+                 cgmath::Vector4::new(1u32, 2, 3, 4));
+    check_upload(&display, "130", "vec2", "vec2(1.0, 2.0)", cgmath::Point2::new(1.0f32, 2.0));
+    check_upload(&display, "130", "vec3", "vec3(1.0, 2.0, 3.0)",
+                 cgmath::Point3::new(1.0f32, 2.0, 3.0));
+    check_upload(&display, "400", "dvec2", "dvec2(1.0, 2.0)", cgmath::Point2::new(1.0f64, 2.0));
+    check_upload(&display, "400", "dvec3", "dvec3(1.0, 2.0, 3.0)",
+                 cgmath::Point3::new(1.0f64, 2.0, 3.0));
+    check_upload(&display, "130", "mat2", "mat2(1.0, 2.0, 3.0, 4.0)",
+                 cgmath::Matrix2::new(1.0f32, 2.0, 3.0, 4.0));
+    check_upload(&display, "130", "mat3", "mat3(1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0)",
+                 cgmath::Matrix3::new(1.0f32, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0));
+    check_upload(&display, "130", "mat4",
+                 "mat4(1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, \
+                       9.0, 10.0, 11.0, 12.0, 13.0, 14.0, 15.0, 16.0)",
+                 cgmath::Matrix4::new(1.0f32, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0,
+                                      9.0, 10.0, 11.0, 12.0, 13.0, 14.0, 15.0, 16.0));
+    check_upload(&display, "400", "dmat2", "dmat2(1.0, 2.0, 3.0, 4.0)",
+                 cgmath::Matrix2::new(1.0f64, 2.0, 3.0, 4.0));
+    check_upload(&display, "400", "dmat3", "dmat3(1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0)",
+                 cgmath::Matrix3::new(1.0f64, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0));
+    check_upload(&display, "400", "dmat4",
+                 "dmat4(1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, \
+                        9.0, 10.0, 11.0, 12.0, 13.0, 14.0, 15.0, 16.0)",
+                 cgmath::Matrix4::new(1.0f64, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0,
+                                      9.0, 10.0, 11.0, 12.0, 13.0, 14.0, 15.0, 16.0));
+    check_upload(&display, "130", "vec4", "vec4(1.0, 2.0, 3.0, 4.0)",
+                 cgmath::Quaternion::new(4.0f32, 1.0, 2.0, 3.0));
+    check_upload(&display, "400", "dvec4", "dvec4(1.0, 2.0, 3.0, 4.0)",
+                 cgmath::Quaternion::new(4.0f64, 1.0, 2.0, 3.0));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+#[cfg(feature = "nalgebra")]
+fn nalgebra_layout() {
+    check_layout!(nalgebra::Vec2::new(1.0f32, 2.0), Vec2, [1.0, 2.0]);
+    check_layout!(nalgebra::Vec3::new(1.0f32, 2.0, 3.0), Vec3, [1.0, 2.0, 3.0]);
+    check_layout!(nalgebra::Vec4::new(1.0f32, 2.0, 3.0, 4.0), Vec4, [1.0, 2.0, 3.0, 4.0]);
+    check_layout!(nalgebra::Vec2::new(1.0f64, 2.0), DoubleVec2, [1.0, 2.0]);
+    check_layout!(nalgebra::Vec3::new(1.0f64, 2.0, 3.0), DoubleVec3, [1.0, 2.0, 3.0]);
+    check_layout!(nalgebra::Vec4::new(1.0f64, 2.0, 3.0, 4.0), DoubleVec4, [1.0, 2.0, 3.0, 4.0]);
+    check_layout!(nalgebra::Vec2::new(1i32, 2), IntVec2, [1, 2]);
+    check_layout!(nalgebra::Vec3::new(1i32, 2, 3), IntVec3, [1, 2, 3]);
+    check_layout!(nalgebra::Vec4::new(1i32, 2, 3, 4), IntVec4, [1, 2, 3, 4]);
+    check_layout!(nalgebra::Vec2::new(1u32, 2), UnsignedIntVec2, [1, 2]);
+    check_layout!(nalgebra::Vec3::new(1u32, 2, 3), UnsignedIntVec3, [1, 2, 3]);
+    check_layout!(nalgebra::Vec4::new(1u32, 2, 3, 4), UnsignedIntVec4, [1, 2, 3, 4]);
+    check_layout!(nalgebra::Pnt2::new(1.0f32, 2.0), Vec2, [1.0, 2.0]);
+    check_layout!(nalgebra::Pnt3::new(1.0f32, 2.0, 3.0), Vec3, [1.0, 2.0, 3.0]);
+    check_layout!(nalgebra::Pnt4::new(1.0f32, 2.0, 3.0, 4.0), Vec4, [1.0, 2.0, 3.0, 4.0]);
+    check_layout!(nalgebra::Pnt2::new(1.0f64, 2.0), DoubleVec2, [1.0, 2.0]);
+    check_layout!(nalgebra::Pnt3::new(1.0f64, 2.0, 3.0), DoubleVec3, [1.0, 2.0, 3.0]);
+    check_layout!(nalgebra::Pnt4::new(1.0f64, 2.0, 3.0, 4.0), DoubleVec4, [1.0, 2.0, 3.0, 4.0]);
+
+    // the constructors of nalgebra take the matrices row by row
+    check_layout!(nalgebra::Mat2::new(1.0f32, 3.0, 2.0, 4.0), Mat2, [[1.0, 2.0], [3.0, 4.0]]);
+    check_layout!(nalgebra::Mat3::new(1.0f32, 4.0, 7.0, 2.0, 5.0, 8.0, 3.0, 6.0, 9.0), Mat3,
+                  [[1.0, 2.0, 3.0], [4.0, 5.0, 6.0], [7.0, 8.0, 9.0]]);
+    check_layout!(nalgebra::Mat4::new(1.0f32, 5.0, 9.0, 13.0, 2.0, 6.0, 10.0, 14.0,
+                                      3.0, 7.0, 11.0, 15.0, 4.0, 8.0, 12.0, 16.0), Mat4,
+                  [[1.0, 2.0, 3.0, 4.0], [5.0, 6.0, 7.0, 8.0],
+                   [9.0, 10.0, 11.0, 12.0], [13.0, 14.0, 15.0, 16.0]]);
+    check_layout!(nalgebra::Mat2::new(1.0f64, 3.0, 2.0, 4.0), DoubleMat2,
+                  [[1.0, 2.0], [3.0, 4.0]]);
+    check_layout!(nalgebra::Mat3::new(1.0f64, 4.0, 7.0, 2.0, 5.0, 8.0, 3.0, 6.0, 9.0),
+                  DoubleMat3, [[1.0, 2.0, 3.0], [4.0, 5.0, 6.0], [7.0, 8.0, 9.0]]);
+    check_layout!(nalgebra::Mat4::new(1.0f64, 5.0, 9.0, 13.0, 2.0, 6.0, 10.0, 14.0,
+                                      3.0, 7.0, 11.0, 15.0, 4.0, 8.0, 12.0, 16.0), DoubleMat4,
+                  [[1.0, 2.0, 3.0, 4.0], [5.0, 6.0, 7.0, 8.0],
+                   [9.0, 10.0, 11.0, 12.0], [13.0, 14.0, 15.0, 16.0]]);
+
+    // `Quat::new` takes the scalar part first
+    check_layout!(nalgebra::Quat::new(4.0f32, 1.0, 2.0, 3.0), Vec4, [1.0, 2.0, 3.0, 4.0]);
+    check_layout!(nalgebra::Quat::new(4.0f64, 1.0, 2.0, 3.0), DoubleVec4, [1.0, 2.0, 3.0, 4.0]);
+    check_layout!(nalgebra::UnitQuat::new_with_quat(nalgebra::Quat::new(0.0f32, 0.0, 1.0, 0.0)),
+                  Vec4, [0.0, 1.0, 0.0, 0.0]);
+    check_layout!(nalgebra::UnitQuat::new_with_quat(nalgebra::Quat::new(0.0f64, 0.0, 1.0, 0.0)),
+                  DoubleVec4, [0.0, 1.0, 0.0, 0.0]);
+}
+
+#[test]
+#[cfg(feature = "nalgebra")]
+fn nalgebra_upload() {
+    let display = support::build_display();
+
+    check_upload(&display, "130", "vec2", "vec2(1.0, 2.0)", nalgebra::Vec2::new(1.0f32, 2.0));
+    check_upload(&display, "130", "vec3", "vec3(1.0, 2.0, 3.0)",
+                 nalgebra::Vec3::new(1.0f32, 2.0, 3.0));
+    check_upload(&display, "130", "vec4", "vec4(1.0, 2.0, 3.0, 4.0)",
+                 nalgebra::Vec4::new(1.0f32, 2.0, 3.0, 4.0));
+    check_upload(&display, "400", "dvec2", "dvec2(1.0, 2.0)", nalgebra::Vec2::new(1.0f64, 2.0));
+    check_upload(&display, "400", "dvec3", "dvec3(1.0, 2.0, 3.0)",
+                 nalgebra::Vec3::new(1.0f64, 2.0, 3.0));
+    check_upload(&display, "400", "dvec4", "dvec4(1.0, 2.0, 3.0, 4.0)",
+                 nalgebra::Vec4::new(1.0f64, 2.0, 3.0, 4.0));
+    check_upload(&display, "130", "ivec2", "ivec2(1, 2)", nalgebra::Vec2::new(1i32, 2));
+    check_upload(&display, "130", "ivec3", "ivec3(1, 2, 3)", nalgebra::Vec3::new(1i32, 2, 3));
+    check_upload(&display, "130", "ivec4", "ivec4(1, 2, 3, 4)",
+                 nalgebra::Vec4::new(1i32, 2, 3, 4));
+    check_upload(&display, "130", "uvec2", "uvec2(1u, 2u)", nalgebra::Vec2::new(1u32, 2));
+    check_upload(&display, "130", "uvec3", "uvec3(1u, 2u, 3u)",
+                 nalgebra::Vec3::new(1u32, 2, 3));
+    check_upload(&display, "130", "uvec4", "uvec4(1u, 2u, 3u, 4u)",
+                 nalgebra::Vec4::new(1u32, 2, 3, 4));
+    check_upload(&display, "130", "vec2", "vec2(1.0, 2.0)", nalgebra::Pnt2::new(1.0f32, 2.0));
+    check_upload(&display, "130", "vec3", "vec3(1.0, 2.0, 3.0)",
+                 nalgebra::Pnt3::new(1.0f32, 2.0, 3.0));
+    check_upload(&display, "130", "vec4", "vec4(1.0, 2.0, 3.0, 4.0)",
+                 nalgebra::Pnt4::new(1.0f32, 2.0, 3.0, 4.0));
+    check_upload(&display, "400", "dvec2", "dvec2(1.0, 2.0)", nalgebra::Pnt2::new(1.0f64, 2.0));
+    check_upload(&display, "400", "dvec3", "dvec3(1.0, 2.0, 3.0)",
+                 nalgebra::Pnt3::new(1.0f64, 2.0, 3.0));
+    check_upload(&display, "400", "dvec4", "dvec4(1.0, 2.0, 3.0, 4.0)",
+                 nalgebra::Pnt4::new(1.0f64, 2.0, 3.0, 4.0));
+    check_upload(&display, "130", "mat2", "mat2(1.0, 2.0, 3.0, 4.0)",
+                 nalgebra::Mat2::new(1.0f32, 3.0, 2.0, 4.0));
+    check_upload(&display, "130", "mat3", "mat3(1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0)",
+                 nalgebra::Mat3::new(1.0f32, 4.0, 7.0, 2.0, 5.0, 8.0, 3.0, 6.0, 9.0));
+    check_upload(&display, "130", "mat4",
+                 "mat4(1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, \
+                       9.0, 10.0, 11.0, 12.0, 13.0, 14.0, 15.0, 16.0)",
+                 nalgebra::Mat4::new(1.0f32, 5.0, 9.0, 13.0, 2.0, 6.0, 10.0, 14.0,
+                                     3.0, 7.0, 11.0, 15.0, 4.0, 8.0, 12.0, 16.0));
+    check_upload(&display, "400", "dmat2", "dmat2(1.0, 2.0, 3.0, 4.0)",
+                 nalgebra::Mat2::new(1.0f64, 3.0, 2.0, 4.0));
+    check_upload(&display, "400", "dmat3", "dmat3(1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0)",
+                 nalgebra::Mat3::new(1.0f64, 4.0, 7.0, 2.0, 5.0, 8.0, 3.0, 6.0, 9.0));
+    check_upload(&display, "400", "dmat4",
+                 "dmat4(1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, \
+                        9.0, 10.0, 11.0, 12.0, 13.0, 14.0, 15.0, 16.0)",
+                 nalgebra::Mat4::new(1.0f64, 5.0, 9.0, 13.0, 2.0, 6.0, 10.0, 14.0,
+                                     3.0, 7.0, 11.0, 15.0, 4.0, 8.0, 12.0, 16.0));
+    check_upload(&display, "130", "vec4", "vec4(1.0, 2.0, 3.0, 4.0)",
+                 nalgebra::Quat::new(4.0f32, 1.0, 2.0, 3.0));
+    check_upload(&display, "400", "dvec4", "dvec4(1.0, 2.0, 3.0, 4.0)",
+                 nalgebra::Quat::new(4.0f64, 1.0, 2.0, 3.0));
+    check_upload(&display, "130", "vec4", "vec4(0.0, 1.0, 0.0, 0.0)",
+                 nalgebra::UnitQuat::new_with_quat(nalgebra::Quat::new(0.0f32, 0.0, 1.0, 0.0)));
+    check_upload(&display, "400", "dvec4", "dvec4(0.0, 1.0, 0.0, 0.0)",
+                 nalgebra::UnitQuat::new_with_quat(nalgebra::Quat::new(0.0f64, 0.0, 1.0, 0.0)));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+#[cfg(feature = "glam")]
+fn glam_layout() {
+    check_layout!(glam::Vec2::new(1.0, 2.0), Vec2, [1.0, 2.0]);
+    check_layout!(glam::Vec3::new(1.0, 2.0, 3.0), Vec3, [1.0, 2.0, 3.0]);
+    check_layout!(glam::Vec4::new(1.0, 2.0, 3.0, 4.0), Vec4, [1.0, 2.0, 3.0, 4.0]);
+    check_layout!(glam::Mat2::from_cols_array(&[1.0, 2.0, 3.0, 4.0]), Mat2,
+                  [[1.0, 2.0], [3.0, 4.0]]);
+    check_layout!(glam::Mat3::from_cols_array(&[1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0]),
+                  Mat3, [[1.0, 2.0, 3.0], [4.0, 5.0, 6.0], [7.0, 8.0, 9.0]]);
+    check_layout!(glam::Mat4::from_cols_array(&[1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0,
+                                                9.0, 10.0, 11.0, 12.0, 13.0, 14.0, 15.0, 16.0]),
+                  Mat4, [[1.0, 2.0, 3.0, 4.0], [5.0, 6.0, 7.0, 8.0],
+                         [9.0, 10.0, 11.0, 12.0], [13.0, 14.0, 15.0, 16.0]]);
+    check_layout!(glam::Quat::from_xyzw(1.0, 2.0, 3.0, 4.0), Vec4, [1.0, 2.0, 3.0, 4.0]);
+}
+
+#[test]
+#[cfg(feature = "glam")]
+fn glam_upload() {
+    let display = support::build_display();
+
+    check_upload(&display, "130", "vec2", "vec2(1.0, 2.0)", glam::Vec2::new(1.0, 2.0));
+    check_upload(&display, "130", "vec3", "vec3(1.0, 2.0, 3.0)", glam::Vec3::new(1.0, 2.0, 3.0));
+    check_upload(&display, "130", "vec4", "vec4(1.0, 2.0, 3.0, 4.0)",
+                 glam::Vec4::new(1.0, 2.0, 3.0, 4.0));
+    check_upload(&display, "130", "mat2", "mat2(1.0, 2.0, 3.0, 4.0)",
+                 glam::Mat2::from_cols_array(&[1.0, 2.0, 3.0, 4.0]));
+    check_upload(&display, "130", "mat3", "mat3(1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0)",
+                 glam::Mat3::from_cols_array(&[1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0]));
+    check_upload(&display, "130", "mat4",
+                 "mat4(1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, \
+                       9.0, 10.0, 11.0, 12.0, 13.0, 14.0, 15.0, 16.0)",
+                 glam::Mat4::from_cols_array(&[1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0,
+                                               9.0, 10.0, 11.0, 12.0, 13.0, 14.0, 15.0, 16.0]));
+    check_upload(&display, "130", "vec4", "vec4(1.0, 2.0, 3.0, 4.0)",
+                 glam::Quat::from_xyzw(1.0, 2.0, 3.0, 4.0));
+
+    display.assert_no_error(None);
+}
@@ -35,6 +35,48 @@ fn query_sequence() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn unused_query_is_ready() {
+    let display = support::build_display();
+
+    let query = match glium::draw_parameters::SamplesPassedQuery::new(&display) {
+        Err(_) => return,
+        Ok(q) => q
+    };
+
+    // polling an unused query must not loop forever
+    assert!(query.is_ready());
+    assert_eq!(query.get(), 0);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn any_samples_passed_is_ready() {
+    let display = support::build_display();
+
+    let query = match glium::draw_parameters::AnySamplesPassedQuery::new(&display, false) {
+        Err(_) => return,
+        Ok(q) => q
+    };
+
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+    let texture = support::build_renderable_texture(&display);
+
+    let params = glium::DrawParameters {
+        samples_passed_query: Some((&query).into()),
+        .. Default::default()
+    };
+
+    texture.as_surface().draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms, &params)
+           .unwrap();
+
+    while !query.is_ready() {}
+    assert!(query.get());
+
+    display.assert_no_error(None);
+}
+
 #[test]
 fn samples_passed() {
     let display = support::build_display();
@@ -160,7 +202,39 @@ fn time_elapsed() {
 }
 
 #[test]
-#[ignore]       // not sure about the interaction between pritmives_generated and no geometry shader
+fn time_elapsed_checked() {
+    let display = support::build_display();
+
+    let query = match glium::draw_parameters::TimeElapsedQuery::new(&display) {
+        Err(_) => return,
+        Ok(q) => q
+    };
+
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+
+    {
+        let params = glium::DrawParameters {
+            time_elapsed_query: Some(&query),
+            .. Default::default()
+        };
+
+        texture.as_surface().draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms, &params)
+               .unwrap();
+    }
+
+    // the GPU may legitimately change its frequency in the middle of the test
+    match query.get_checked() {
+        Ok(result) => assert!(result >= 1),
+        Err(glium::draw_parameters::TimeElapsedError::Disjoint) => ()
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
 fn primitives_generated() {
     let display = support::build_display();
 
@@ -190,10 +264,148 @@ fn primitives_generated() {
     display.assert_no_error(None);
 }
 
-// FIXME: add test for transform feedback query
+#[test]
+fn transform_feedback_primitives_written() {
+    let display = support::build_display();
+
+    let query = match glium::draw_parameters::TransformFeedbackPrimitivesWrittenQuery::new(
+                                                                                    &display)
+    {
+        Err(_) => return,
+        Ok(q) => q
+    };
+
+    #[derive(Copy, Clone)]
+    struct Vertex {
+        output_val: (f32, f32),
+    }
+
+    implement_vertex!(Vertex, output_val);
+
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let source = glium::program::ProgramCreationInput::SourceCode {
+        tessellation_control_shader: None,
+        tessellation_evaluation_shader: None,
+        geometry_shader: None,
+        outputs_srgb: false,
+        uses_point_size: false,
+
+        vertex_shader: "
+            #version 110
+
+            attribute vec2 position;
+
+            varying vec2 output_val;
+
+            void main() {
+                output_val = position;
+                gl_Position = vec4(0.0, 0.0, 0.0, 1.0);
+            }
+        ",
+        fragment_shader: "
+            #version 130
+
+            out vec4 color;
+
+            void main() {
+                color = vec4(1.0, 1.0, 1.0, 1.0);
+            }
+        ",
+
+        transform_feedback_varyings: Some((
+            vec!["output_val".to_string()],
+            glium::program::TransformFeedbackMode::Separate
+        )),
+    };
+
+    let program = match glium::Program::new(&display, source) {
+        Ok(p) => p,
+        Err(glium::program::ProgramCreationError::TransformFeedbackNotSupported) => return,
+        Err(e) => panic!("{:?}", e)
+    };
+
+    let texture = support::build_renderable_texture(&display);
+
+    let mut out_buffer: glium::VertexBuffer<Vertex> = glium::VertexBuffer::empty(&display, 6)
+                                                                                    .unwrap();
+
+    {
+        let session = glium::vertex::TransformFeedbackSession::new(&display, &program,
+                                                                   &mut out_buffer).unwrap();
+
+        let params = glium::DrawParameters {
+            transform_feedback: Some(&session),
+            transform_feedback_primitives_written_query: Some(&query),
+            .. Default::default()
+        };
+
+        texture.as_surface().draw(&vb, &ib, &program, &uniform!{}, &params).unwrap();
+    }
+
+    // the rectangle is made of two triangles
+    assert_eq!(query.get(), 2);
+
+    display.assert_no_error(None);
+}
 
 // FIXME: add more tests for conditional rendering
 
+#[test]
+fn conditional_render_draw() {
+    let display = support::build_display();
+
+    let query = match glium::draw_parameters::SamplesPassedQuery::new(&display) {
+        Err(_) => return,
+        Ok(q) => q
+    };
+
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+
+    {
+        let params = glium::DrawParameters {
+            samples_passed_query: Some((&query).into()),
+            .. Default::default()
+        };
+
+        texture.as_surface().draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms, &params)
+               .unwrap();
+    }
+
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+
+    {
+        let params = glium::DrawParameters {
+            condition: Some(glium::draw_parameters::ConditionalRendering {
+                query: (&query).into(),
+                wait: true,
+                per_region: false,
+            }),
+            .. Default::default()
+        };
+
+        match texture.as_surface().draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms,
+                                        &params)
+        {
+            Err(glium::DrawError::ConditionalRenderingNotSupported) => return,
+            res => res.unwrap()
+        };
+    }
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(255, 0, 0, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
 #[test]
 #[ignore]       // FIXME: problem with query not having a type yet when passed to BeginConditionalRender
 fn conditional_render_nodraw() {
@@ -309,3 +521,73 @@ fn query_to_buffer() {
 
     display.assert_no_error(None);
 }
+
+#[test]
+fn unused_query_to_buffer() {
+    let display = support::build_display();
+
+    let query = match glium::draw_parameters::SamplesPassedQuery::new(&display) {
+        Err(_) => return,
+        Ok(q) => q
+    };
+
+    let mut buffer = glium::buffer::BufferView::new(&display, &12u32,
+                                                    glium::buffer::BufferType::ArrayBuffer,
+                                                    glium::buffer::BufferMode::Default).unwrap();
+    if let Err(_) = query.to_buffer_u32(buffer.as_slice()) {
+        return;
+    }
+
+    let mapping = buffer.map();
+    assert_eq!(*mapping, 0);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn conditional_surface_draw() {
+    let display = support::build_display();
+
+    let query = match glium::draw_parameters::SamplesPassedQuery::new(&display) {
+        Err(_) => return,
+        Ok(q) => q
+    };
+
+    let (vb, ib, program) = support::build_fullscreen_red_pipeline(&display);
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+
+    {
+        let params = glium::DrawParameters {
+            samples_passed_query: Some((&query).into()),
+            .. Default::default()
+        };
+
+        texture.as_surface().draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms, &params)
+               .unwrap();
+    }
+
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+
+    let result = texture.as_surface().conditional(&query, true, false, |surface| {
+        try!(surface.draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms,
+                          &Default::default()));
+        surface.draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms, &Default::default())
+    });
+
+    match result {
+        Err(glium::DrawError::ConditionalRenderingNotSupported) => return,
+        res => res.unwrap()
+    };
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(255, 0, 0, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
@@ -79,3 +79,73 @@ fn magnify_nearest_filtering() {
 
     display.assert_no_error(None);
 }
+
+#[test]
+fn sampled_with_per_draw() {
+    // ignoring test on travis, like `magnify_nearest_filtering`
+    if ::std::env::var("TRAVIS").is_ok() {
+        return;
+    }
+
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = program!(&display,
+        110 => {
+            vertex: "
+                #version 110
+
+                attribute vec2 position;
+
+                void main() {
+                    gl_Position = vec4(position, 0.0, 1.0);
+                }
+            ",
+            fragment: "
+                #version 110
+
+                uniform sampler2D texture;
+
+                void main() {
+                    gl_FragColor = texture2D(texture, vec2(0.51, 0.0));
+                }
+            ",
+        }).unwrap();
+
+    let texture_data = vec![vec![(0u8, 0, 0), (255, 255, 255)]];
+    let texture = glium::texture::Texture2d::new(&display, texture_data).unwrap();
+
+    let nearest = glium::uniforms::SamplerBehavior {
+        magnify_filter: glium::uniforms::MagnifySamplerFilter::Nearest,
+        .. Default::default()
+    };
+
+    let linear = glium::uniforms::SamplerBehavior {
+        magnify_filter: glium::uniforms::MagnifySamplerFilter::Linear,
+        .. Default::default()
+    };
+
+    let output = support::build_renderable_texture(&display);
+
+    for &(behavior, is_nearest) in [(nearest, true), (linear, false), (nearest, true)].iter() {
+        let uniforms = uniform! {
+            texture: texture.sampled_with(behavior)
+        };
+
+        output.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+        match output.as_surface().draw(&vb, &ib, &program, &uniforms, &Default::default()) {
+            Ok(_) => (),
+            Err(glium::DrawError::SamplersNotSupported) => return,
+            Err(e) => panic!("{:?}", e)
+        };
+
+        let data: Vec<Vec<(u8, u8, u8, u8)>> = output.read();
+        if is_nearest {
+            assert_eq!(data[0][0], (255, 255, 255, 255));
+        } else {
+            assert!(data[0][0].0 > 0 && data[0][0].0 < 255);
+        }
+    }
+
+    display.assert_no_error(None);
+}
@@ -5,6 +5,7 @@
 extern crate glium;
 
 use glium::Surface;
+use std::rc::Rc;
 
 mod support;
 
@@ -77,6 +78,75 @@ fn program_compilation_error() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn compilation_error_diagnostics() {
+    let display = support::build_display();
+
+    let program = glium::Program::from_source(&display,
+        "invalid glsl code",
+        "
+            #version 110
+
+            void main() {
+                gl_FragColor = vec4(1.0, 1.0, 1.0, 1.0);
+            }
+        ",
+        None);
+
+    match program {
+        Err(err) => assert!(!err.diagnostics().is_empty()),
+        _ => panic!()
+    };
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn parse_info_logs() {
+    use glium::program::{parse_info_log, DiagnosticSeverity, ShaderDiagnostic};
+
+    // NVIDIA
+    assert_eq!(parse_info_log("0(12) : error C0000: syntax error, unexpected '}'"), vec![
+        ShaderDiagnostic {
+            file: Some(0), line: Some(12), column: None,
+            severity: DiagnosticSeverity::Error,
+            message: "syntax error, unexpected '}'".to_owned(),
+        }
+    ]);
+
+    // AMD and Intel
+    assert_eq!(parse_info_log("WARNING: 1:3: 'foo' : unused variable\n"), vec![
+        ShaderDiagnostic {
+            file: Some(1), line: Some(3), column: None,
+            severity: DiagnosticSeverity::Warning,
+            message: "'foo' : unused variable".to_owned(),
+        }
+    ]);
+
+    // Mesa
+    assert_eq!(parse_info_log("0:7(14): error: `color' undeclared\n\n"), vec![
+        ShaderDiagnostic {
+            file: Some(0), line: Some(7), column: Some(14),
+            severity: DiagnosticSeverity::Error,
+            message: "`color' undeclared".to_owned(),
+        }
+    ]);
+
+    // linker messages and unknown lines
+    assert_eq!(parse_info_log("error: output `v' not read\nVertex info"), vec![
+        ShaderDiagnostic {
+            file: None, line: None, column: None,
+            severity: DiagnosticSeverity::Error,
+            message: "output `v' not read".to_owned(),
+        },
+        ShaderDiagnostic {
+            file: None, line: None, column: None,
+            severity: DiagnosticSeverity::Info,
+            message: "Vertex info".to_owned(),
+        }
+    ]);
+}
+
 // This test is disabled because some OpenGL drivers don't catch
 // the linking error (even though they are supposed to)
 #[test]
@@ -119,6 +189,300 @@ fn program_linking_error() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn stage_interface_mismatch() {
+    use glium::program::ProgramCreationError::StageInterfaceMismatch;
+    use glium::vertex::AttributeType;
+    use glium::{Api, Version};
+
+    let display = support::build_display();
+
+    if !(display.get_opengl_version() >= &Version(Api::Gl, 4, 3)) {
+        return;
+    }
+
+    let program = glium::Program::from_source(&display,
+        // vertex shader
+        "
+            #version 330
+
+            out vec3 v_color;
+
+            void main() {
+                gl_Position = vec4(0.0, 0.0, 0.0, 1.0);
+                v_color = vec3(0.0, 0.0, 0.0);
+            }
+        ",
+
+        // fragment shader
+        "
+            #version 330
+
+            in vec4 v_color;
+            out vec4 color;
+
+            void main() {
+                color = v_color;
+            }
+        ",
+
+        // geometry shader
+        None);
+
+    match program {
+        Err(StageInterfaceMismatch { name, location, input, output }) => {
+            assert_eq!(name, "v_color");
+            assert_eq!(location, None);
+            assert_eq!(input, AttributeType::F32F32F32F32);
+            assert_eq!(output, Some(AttributeType::F32F32F32));
+        },
+        // some drivers don't catch the mismatch, see `program_linking_error`
+        Ok(_) => (),
+        _ => panic!()
+    };
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn adapted_source() {
+    use glium::program::ShaderStage;
+
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let vertex_shader = glium::program::adapt_shader_source(&display, ShaderStage::Vertex, "
+        attribute vec2 position;
+        varying mediump vec2 v_position;
+
+        void main() {
+            v_position = position;
+            gl_Position = vec4(position, 0.0, 1.0);
+        }
+    ");
+
+    let fragment_shader = glium::program::adapt_shader_source(&display, ShaderStage::Fragment, "
+        precision mediump float;
+        varying vec2 v_position;
+
+        void main() {
+            gl_FragColor = vec4(1.0, 0.0, 0.0, 1.0);
+        }
+    ");
+
+    let program = glium::Program::from_source(&display, &vertex_shader, &fragment_shader,
+                                              None).unwrap();
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, &ib, &program, &glium::uniforms::EmptyUniforms,
+                              &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (255, 0, 0, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn adapted_source_extensions_and_comments() {
+    use glium::program::ShaderStage;
+
+    let display = support::build_display();
+
+    let vertex_shader = glium::program::adapt_shader_source(&display, ShaderStage::Vertex, "
+        attribute vec2 position;
+
+        void main() {
+            gl_Position = vec4(position, 0.0, 1.0);
+        }
+    ");
+
+    let fragment_shader = glium::program::adapt_shader_source(&display, ShaderStage::Fragment, "
+        #extension all : warn
+
+        // writes to gl_FragColor, like a varying would be written
+        /* attribute
+           varying */
+        void main() {
+            gl_FragColor = vec4(1.0, 0.0, 0.0, 1.0);
+        }
+    ");
+
+    // the extension directive must come before the declarations inserted by glium
+    let extension = fragment_shader.find("#extension all : warn").unwrap();
+    assert!(extension < fragment_shader.find("#line").unwrap());
+    assert!(fragment_shader.find("precision").map_or(true, |p| extension < p));
+
+    assert!(fragment_shader.contains("// writes to gl_FragColor, like a varying would be written"));
+    assert!(fragment_shader.contains("/* attribute\n           varying */"));
+
+    glium::Program::from_source(&display, &vertex_shader, &fragment_shader, None).unwrap();
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn program_with_includes() {
+    let display = support::build_display();
+
+    let resolver = |name: &str| {
+        match name {
+            "color.glsl" => Some("#include \"constants.glsl\"\nvec4 color() { return WHITE; }"
+                                 .to_owned()),
+            "constants.glsl" => Some("#define WHITE vec4(1.0, 1.0, 1.0, 1.0)".to_owned()),
+            _ => None
+        }
+    };
+
+    let fragment_shader = glium::program::resolve_includes("
+            #version 110
+
+            #include \"color.glsl\"
+
+            void main() {
+                gl_FragColor = color();
+            }
+        ", resolver).unwrap();
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            void main() {
+                gl_Position = vec4(0.0, 0.0, 0.0, 1.0);
+            }
+        ",
+        &fragment_shader,
+        None);
+
+    program.unwrap();
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn recursive_include() {
+    let result = glium::program::resolve_includes("#include <a.glsl>", |name| {
+        match name {
+            "a.glsl" => Some("#include <b.glsl>".to_owned()),
+            "b.glsl" => Some("#include <a.glsl>".to_owned()),
+            _ => None
+        }
+    });
+
+    match result {
+        Err(glium::program::IncludeError::RecursiveInclude { ref name }) if name == "a.glsl" => (),
+        _ => panic!()
+    };
+}
+
+#[test]
+fn program_cache() {
+    let display = support::build_display();
+
+    let vertex_shader = "
+        #version 110
+
+        void main() {
+            gl_Position = vec4(0.0, 0.0, 0.0, 1.0);
+        }
+    ";
+
+    let fragment_shader = "
+        #version 110
+
+        void main() {
+            gl_FragColor = vec4(1.0, 1.0, 1.0, 1.0);
+        }
+    ";
+
+    // the cache is disabled by default
+    let program1 = glium::Program::from_source_cached(&display, vertex_shader, fragment_shader,
+                                                      None).unwrap();
+    let program2 = glium::Program::from_source_cached(&display, vertex_shader, fragment_shader,
+                                                      None).unwrap();
+    assert!(!Rc::ptr_eq(&program1, &program2));
+
+    display.set_program_cache_enabled(true);
+
+    let program1 = glium::Program::from_source_cached(&display, vertex_shader, fragment_shader,
+                                                      None).unwrap();
+    let program2 = glium::Program::from_source_cached(&display, vertex_shader, fragment_shader,
+                                                      None).unwrap();
+    assert!(Rc::ptr_eq(&program1, &program2));
+
+    let program3 = glium::Program::from_source_cached(&display, vertex_shader, "
+        #version 110
+
+        void main() {
+            gl_FragColor = vec4(0.0, 0.0, 0.0, 1.0);
+        }
+    ", None).unwrap();
+    assert!(!Rc::ptr_eq(&program1, &program3));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn program_new_async() {
+    let display = support::build_display();
+
+    let pending = glium::Program::new_async(&display, glium::program::SourceCode {
+        vertex_shader: "
+            #version 110
+
+            void main() {
+                gl_Position = vec4(0.0, 0.0, 0.0, 1.0);
+            }
+        ",
+        tessellation_control_shader: None,
+        tessellation_evaluation_shader: None,
+        geometry_shader: None,
+        fragment_shader: "
+            #version 110
+
+            void main() {
+                gl_FragColor = vec4(1.0, 1.0, 1.0, 1.0);
+            }
+        ",
+    }).unwrap();
+
+    if !glium::program::is_parallel_compile_supported(&display) {
+        assert!(pending.is_ready());
+    }
+
+    pending.finish().unwrap();
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn program_new_async_compilation_error() {
+    let display = support::build_display();
+
+    let pending = glium::Program::new_async(&display, glium::program::SourceCode {
+        vertex_shader: "invalid glsl code",
+        tessellation_control_shader: None,
+        tessellation_evaluation_shader: None,
+        geometry_shader: None,
+        fragment_shader: "
+            #version 110
+
+            void main() {
+                gl_FragColor = vec4(1.0, 1.0, 1.0, 1.0);
+            }
+        ",
+    }).unwrap();
+
+    match pending.finish() {
+        Err(glium::CompilationError(_)) => (),
+        _ => panic!()
+    };
+
+    display.assert_no_error(None);
+}
+
 #[test]
 fn get_frag_data_location() {
     let display = support::build_display();
@@ -154,6 +518,198 @@ fn get_frag_data_location() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn explicit_fragment_output_locations() {
+    let display = support::build_display();
+
+    let source = glium::program::ProgramCreationInput::SourceCode {
+        tessellation_control_shader: None,
+        tessellation_evaluation_shader: None,
+        geometry_shader: None,
+        transform_feedback_varyings: None,
+        outputs_srgb: false,
+        uses_point_size: false,
+
+        vertex_shader: "
+            #version 110
+
+            void main() {
+                gl_Position = vec4(0.0, 0.0, 0.0, 1.0);
+            }
+        ",
+        fragment_shader: "
+            #version 130
+
+            out vec4 color;
+            out vec4 normal;
+
+            void main() {
+                color = vec4(1.0, 1.0, 1.0, 1.0);
+                normal = vec4(0.0, 0.0, 1.0, 1.0);
+            }
+        ",
+    };
+
+    let bindings = glium::program::ProgramBindings::new().bind_fragment_output("normal", 0, 0)
+                                                         .bind_fragment_output("color", 2, 0);
+
+    // ignoring test in case of compilation error (version 1.30 may not be supported)
+    let program = match glium::Program::with_bindings(&display, source, &bindings) {
+        Ok(p) => p,
+        Err(glium::program::ProgramCreationError::FragmentOutputLocationNotSupported) => return,
+        Err(glium::program::ProgramCreationError::CompilationError(_)) => return,
+        Err(e) => panic!("{:?}", e)
+    };
+
+    assert_eq!(program.get_frag_data_location("normal"), Some(0));
+    assert_eq!(program.get_frag_data_location("color"), Some(2));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn explicit_attribute_locations() {
+    let display = support::build_display();
+
+    let source = glium::program::ProgramCreationInput::SourceCode {
+        tessellation_control_shader: None,
+        tessellation_evaluation_shader: None,
+        geometry_shader: None,
+        transform_feedback_varyings: None,
+        outputs_srgb: false,
+        uses_point_size: false,
+
+        vertex_shader: "
+            #version 110
+
+            attribute vec2 position;
+            attribute vec3 color;
+            varying vec3 v_color;
+
+            void main() {
+                v_color = color;
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        fragment_shader: "
+            #version 110
+
+            varying vec3 v_color;
+
+            void main() {
+                gl_FragColor = vec4(v_color, 1.0);
+            }
+        ",
+    };
+
+    let bindings = glium::program::ProgramBindings::new().bind_attribute("position", 3)
+                                                         .bind_attribute("color", 5);
+    let program = glium::Program::with_bindings(&display, source, &bindings).unwrap();
+
+    assert_eq!(program.get_attribute("position").unwrap().location, 3);
+    assert_eq!(program.get_attribute("color").unwrap().location, 5);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn program_chooser() {
+    let display = support::build_display();
+
+    let source = || glium::program::SourceCode {
+        vertex_shader: "
+            #version 110
+
+            void main() {
+                gl_Position = vec4(0.0, 0.0, 0.0, 1.0);
+            }
+        ",
+        tessellation_control_shader: None,
+        tessellation_evaluation_shader: None,
+        geometry_shader: None,
+        fragment_shader: "
+            #version 110
+
+            void main() {
+                gl_FragColor = vec4(1.0, 1.0, 1.0, 1.0);
+            }
+        ",
+    };
+
+    let version = glium::Version(glium::Api::Gl, 1, 1);
+    if !display.is_glsl_version_supported(&version) {
+        return;
+    }
+
+    let (_, variant) = glium::program::ProgramChooser::new()
+        .variant(version, &["GL_GLIUM_unknown_extension"], source())
+        .variant(version, &[], source())
+        .build(&display).unwrap();
+    assert_eq!(variant, 1);
+
+    match glium::program::ProgramChooser::new()
+        .variant(version, &["GL_GLIUM_unknown_extension"], source())
+        .build(&display)
+    {
+        Err(glium::program::ProgramChooserCreationError::NoVersion) => (),
+        _ => panic!()
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn program_reflection() {
+    let display = support::build_display();
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 330
+
+            in vec2 position;
+            uniform vec4 tint;
+            uniform float weights[3];
+
+            void main() {
+                gl_Position = vec4(position, weights[2], 1.0) * tint;
+            }
+        ",
+        "
+            #version 330
+
+            out vec4 color;
+
+            void main() {
+                color = vec4(1.0, 1.0, 1.0, 1.0);
+            }
+        ",
+        None);
+
+    // ignoring test in case of compilation error (version may not be supported)
+    let program = match program {
+        Ok(p) => p,
+        Err(_) => return
+    };
+
+    let reflection = program.get_reflection();
+
+    assert_eq!(reflection.uniforms.len(), program.uniforms().count());
+    assert!(reflection.uniforms.get("tint").is_some());
+    assert!(reflection.uniforms.get("weights[2]").is_some());
+    assert_eq!(reflection.inputs.len(), 1);
+    assert_eq!(reflection.inputs.get("position").unwrap().location,
+               program.get_attribute("position").unwrap().location);
+    assert!(reflection.uniform_blocks.is_empty());
+
+    // the outputs can only be enumerated with the program interface query
+    if let Some(output) = reflection.outputs.get("color") {
+        assert_eq!(reflection.outputs.len(), 1);
+        assert_eq!(Some(output.location as u32), program.get_frag_data_location("color"));
+    }
+
+    display.assert_no_error(None);
+}
+
 #[test]
 fn get_uniform_blocks() {
     let display = support::build_display();
@@ -196,36 +752,96 @@ fn get_uniform_blocks() {
     let my_block = blocks.get("MyBlock").unwrap();
     assert!(my_block.size >= 3 * 4 + 4 * 12);
 
-    if let glium::program::BlockLayout::Struct { ref members } = my_block.layout {
-        assert_eq!(members.len(), 2);
+    if let glium::program::BlockLayout::Struct { ref members } = my_block.layout {
+        assert_eq!(members.len(), 2);
+
+        let mut members = members.clone();
+        members.sort_by(|a, b| a.0.cmp(&b.0));
+
+        assert_eq!(members[1].0, "position");
+        if let glium::program::BlockLayout::BasicType { ty, offset_in_buffer } = members[1].1 {
+            assert_eq!(ty, glium::uniforms::UniformType::FloatVec3);
+            assert_eq!(offset_in_buffer, 0);
+        } else {
+            panic!();
+        }
+
+        assert_eq!(members[0].0, "color");
+        if let glium::program::BlockLayout::Array { ref content, length } = members[0].1 {
+            assert_eq!(length, 12);
+            if let glium::program::BlockLayout::BasicType { ty, offset_in_buffer } = **content {
+                assert_eq!(ty, glium::uniforms::UniformType::Float);
+                assert!(offset_in_buffer >= 4 * 3);
+            } else {
+                panic!();
+            }
+        } else {
+            panic!();
+        }
+
+    } else {
+        panic!();
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn get_uniform_block_members() {
+    let display = support::build_display();
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 330
+
+            layout(std140) uniform MyBlock {
+                vec3 position;
+                float color[4];
+                layout(row_major) mat4 matrix;
+            };
+
+            void main() {
+                gl_Position = matrix * vec4(position, color[2]);
+            }
+        ",
+        "
+            #version 130
+
+            out vec4 color;
+
+            void main() {
+                color = vec4(1.0, 1.0, 1.0, 1.0);
+            }
+        ",
+        None);
+
+    // ignoring test in case of compilation error (version may not be supported)
+    let program = match program {
+        Ok(p) => p,
+        Err(_) => return
+    };
 
-        let mut members = members.clone();
-        members.sort_by(|a, b| a.0.cmp(&b.0));
+    let my_block = program.get_uniform_blocks().get("MyBlock").unwrap();
+    assert_eq!(my_block.members.len(), 3);
 
-        assert_eq!(members[1].0, "position");
-        if let glium::program::BlockLayout::BasicType { ty, offset_in_buffer } = members[1].1 {
-            assert_eq!(ty, glium::uniforms::UniformType::FloatVec3);
-            assert_eq!(offset_in_buffer, 0);
-        } else {
-            panic!();
-        }
+    let position = my_block.members.iter().find(|m| m.name == "position").unwrap();
+    assert_eq!(position.ty, glium::uniforms::UniformType::FloatVec3);
+    assert_eq!(position.offset, 0);
+    assert_eq!(position.array_size, 1);
+    assert_eq!(position.matrix_stride, 0);
 
-        assert_eq!(members[0].0, "color");
-        if let glium::program::BlockLayout::Array { ref content, length } = members[0].1 {
-            assert_eq!(length, 12);
-            if let glium::program::BlockLayout::BasicType { ty, offset_in_buffer } = **content {
-                assert_eq!(ty, glium::uniforms::UniformType::Float);
-                assert!(offset_in_buffer >= 4 * 3);
-            } else {
-                panic!();
-            }
-        } else {
-            panic!();
-        }
+    // std140 rounds the stride of arrays up to the size of a vec4
+    let color = my_block.members.iter().find(|m| m.name == "color[0]").unwrap();
+    assert_eq!(color.ty, glium::uniforms::UniformType::Float);
+    assert_eq!(color.offset, 16);
+    assert_eq!(color.array_size, 4);
+    assert_eq!(color.array_stride, 16);
 
-    } else {
-        panic!();
-    }
+    let matrix = my_block.members.iter().find(|m| m.name == "matrix").unwrap();
+    assert_eq!(matrix.ty, glium::uniforms::UniformType::FloatMat4);
+    assert_eq!(matrix.offset, 80);
+    assert_eq!(matrix.matrix_stride, 16);
+    assert!(matrix.row_major);
 
     display.assert_no_error(None);
 }
@@ -310,6 +926,137 @@ fn program_binary_reload() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn shader_cache() {
+    use std::cell::{Cell, RefCell};
+    use std::collections::HashMap;
+
+    struct MemoryCache {
+        binaries: RefCell<HashMap<u64, (u32, Vec<u8>)>>,
+        hits: Cell<usize>,
+    }
+
+    impl glium::program::ShaderCache for MemoryCache {
+        fn load(&self, key: u64) -> Option<glium::program::Binary> {
+            self.binaries.borrow().get(&key).map(|&(format, ref content)| {
+                self.hits.set(self.hits.get() + 1);
+                glium::program::Binary { format: format, content: content.clone() }
+            })
+        }
+
+        fn store(&self, key: u64, binary: &glium::program::Binary) {
+            self.binaries.borrow_mut().insert(key, (binary.format, binary.content.clone()));
+        }
+    }
+
+    let display = support::build_display();
+
+    let cache = Rc::new(MemoryCache {
+        binaries: RefCell::new(HashMap::new()),
+        hits: Cell::new(0),
+    });
+
+    display.set_shader_cache(Some(cache.clone()));
+
+    let vertex_shader = "
+        #version 110
+
+        void main() {
+            gl_Position = vec4(0.0, 0.0, 0.0, 1.0);
+        }
+    ";
+
+    let fragment_shader = "
+        #version 110
+
+        void main() {
+            gl_FragColor = vec4(1.0, 1.0, 1.0, 1.0);
+        }
+    ";
+
+    glium::Program::from_source(&display, vertex_shader, fragment_shader, None).unwrap();
+
+    // ignoring test if binaries are not supported
+    if cache.binaries.borrow().is_empty() {
+        return;
+    }
+
+    assert_eq!(cache.hits.get(), 0);
+    glium::Program::from_source(&display, vertex_shader, fragment_shader, None).unwrap();
+    assert_eq!(cache.hits.get(), 1);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn shader_cache_key_collision() {
+    use std::cell::RefCell;
+
+    // a cache where all the keys collide
+    struct SingleEntryCache {
+        binary: RefCell<Option<(u32, Vec<u8>)>>,
+    }
+
+    impl glium::program::ShaderCache for SingleEntryCache {
+        fn load(&self, _: u64) -> Option<glium::program::Binary> {
+            self.binary.borrow().as_ref().map(|&(format, ref content)| {
+                glium::program::Binary { format: format, content: content.clone() }
+            })
+        }
+
+        fn store(&self, _: u64, binary: &glium::program::Binary) {
+            *self.binary.borrow_mut() = Some((binary.format, binary.content.clone()));
+        }
+    }
+
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let cache = Rc::new(SingleEntryCache { binary: RefCell::new(None) });
+    display.set_shader_cache(Some(cache.clone()));
+
+    let vertex_shader = "
+        #version 110
+
+        attribute vec2 position;
+
+        void main() {
+            gl_Position = vec4(position, 0.0, 1.0);
+        }
+    ";
+
+    glium::Program::from_source(&display, vertex_shader, "
+            #version 110
+
+            void main() {
+                gl_FragColor = vec4(1.0, 0.0, 0.0, 1.0);
+            }
+        ", None).unwrap();
+
+    // ignoring test if binaries are not supported
+    if cache.binary.borrow().is_none() {
+        return;
+    }
+
+    // the entry of the red program must not be loaded
+    let green = glium::Program::from_source(&display, vertex_shader, "
+            #version 110
+
+            void main() {
+                gl_FragColor = vec4(0.0, 1.0, 0.0, 1.0);
+            }
+        ", None).unwrap();
+
+    let output = support::build_renderable_texture(&display);
+    output.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    output.as_surface().draw(&vb, &ib, &green, &uniform!{}, &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = output.read();
+    assert_eq!(data[0][0], (0, 255, 0, 255));
+
+    display.assert_no_error(None);
+}
+
 #[test]
 fn program_binary_working() {
     let display = support::build_display();
@@ -430,6 +1177,53 @@ fn get_transform_feedback_varyings() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn program_macro_transform_feedback_varyings() {
+    let display = support::build_display();
+
+    let program = program!(&display,
+        140 => {
+            vertex: "
+                #version 140
+
+                in vec2 position;
+                out vec2 output_position;
+
+                void main() {
+                    output_position = position;
+                    gl_Position = vec4(position, 0.0, 1.0);
+                }
+            ",
+            fragment: "
+                #version 140
+
+                out vec4 color;
+
+                void main() {
+                    color = vec4(1.0, 1.0, 1.0, 1.0);
+                }
+            ",
+            transform_feedback_varyings: (
+                vec!["output_position".to_string()],
+                glium::program::TransformFeedbackMode::Interleaved
+            ),
+        },
+    );
+
+    let program = match program {
+        Ok(p) => p,
+        Err(glium::program::ProgramChooserCreationError::ProgramCreationError(
+            glium::program::ProgramCreationError::TransformFeedbackNotSupported)) => return,
+        Err(glium::program::ProgramChooserCreationError::NoVersion) => return,
+        Err(e) => panic!("{:?}", e)
+    };
+
+    assert_eq!(program.get_transform_feedback_buffers().len(), 1);
+    assert_eq!(program.get_transform_feedback_buffers()[0].elements[0].name, "output_position");
+
+    display.assert_no_error(None);
+}
+
 #[test]
 fn get_output_primitives_simple() {
     let display = support::build_display();
@@ -461,7 +1255,185 @@ fn get_output_primitives_simple() {
     display.assert_no_error(None);
 }
 
-// TODO: add tests for get_output_primitives with geometry shader, TES, and both
+#[test]
+fn geometry_shader_points_to_quads() {
+    use glium::index::{NoIndices, PrimitiveType};
+    use glium::program::{GeometryInputPrimitives, OutputPrimitives};
+
+    let display = support::build_display();
+
+    if !glium::program::is_geometry_shader_supported(&display) {
+        return;
+    }
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 150
+
+            in vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 150
+
+            out vec4 color;
+
+            void main() {
+                color = vec4(1.0, 0.0, 0.0, 1.0);
+            }
+        ",
+        Some("
+            #version 150
+
+            layout(points) in;
+            layout(triangle_strip, max_vertices = 4) out;
+
+            void main() {
+                vec4 center = gl_in[0].gl_Position;
+                gl_Position = center + vec4(-0.5, -0.5, 0.0, 0.0); EmitVertex();
+                gl_Position = center + vec4(0.5, -0.5, 0.0, 0.0); EmitVertex();
+                gl_Position = center + vec4(-0.5, 0.5, 0.0, 0.0); EmitVertex();
+                gl_Position = center + vec4(0.5, 0.5, 0.0, 0.0); EmitVertex();
+                EndPrimitive();
+            }
+        "));
+
+    // ignoring test in case of compilation error
+    let program = match program {
+        Ok(p) => p,
+        Err(_) => return
+    };
+
+    assert_eq!(program.get_geometry_input_primitives(), Some(GeometryInputPrimitives::Points));
+    assert_eq!(program.get_output_primitives(), Some(OutputPrimitives::Triangles));
+
+    #[derive(Copy, Clone)]
+    struct Vertex {
+        position: [f32; 2],
+    }
+
+    implement_vertex!(Vertex, position);
+
+    let vb = glium::VertexBuffer::new(&display, &[Vertex { position: [0.0, 0.0] }]).unwrap();
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 1.0);
+
+    match texture.as_surface().draw(&vb, &NoIndices(PrimitiveType::TrianglesList), &program,
+                                    &uniform!{}, &Default::default())
+    {
+        Err(glium::DrawError::GeometryInputPrimitivesMismatch { .. }) => (),
+        _ => panic!()
+    };
+
+    texture.as_surface().draw(&vb, &NoIndices(PrimitiveType::Points), &program,
+                              &uniform!{}, &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (0, 0, 0, 255));
+    assert_eq!(data[data.len() / 2][data[0].len() / 2], (255, 0, 0, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn tessellation_patches_validation() {
+    use glium::index::{NoIndices, PrimitiveType};
+
+    let display = support::build_display();
+
+    if !glium::program::is_tessellation_shader_supported(&display) {
+        return;
+    }
+
+    let source = glium::program::ProgramCreationInput::SourceCode {
+        geometry_shader: None,
+        transform_feedback_varyings: None,
+        outputs_srgb: false,
+        uses_point_size: false,
+
+        vertex_shader: "
+            #version 400
+
+            in vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        tessellation_control_shader: Some("
+            #version 400
+
+            layout(vertices = 3) out;
+
+            void main() {
+                gl_out[gl_InvocationID].gl_Position = gl_in[gl_InvocationID].gl_Position;
+                gl_TessLevelOuter[0] = 1.0;
+                gl_TessLevelOuter[1] = 1.0;
+                gl_TessLevelOuter[2] = 1.0;
+                gl_TessLevelInner[0] = 1.0;
+            }
+        "),
+        tessellation_evaluation_shader: Some("
+            #version 400
+
+            layout(triangles, equal_spacing) in;
+
+            void main() {
+                gl_Position = gl_TessCoord.x * gl_in[0].gl_Position +
+                              gl_TessCoord.y * gl_in[1].gl_Position +
+                              gl_TessCoord.z * gl_in[2].gl_Position;
+            }
+        "),
+        fragment_shader: "
+            #version 400
+
+            out vec4 color;
+
+            void main() {
+                color = vec4(1.0, 0.0, 0.0, 1.0);
+            }
+        ",
+    };
+
+    // ignoring test in case of compilation error
+    let program = match glium::Program::new(&display, source) {
+        Ok(p) => p,
+        Err(_) => return
+    };
+
+    assert!(program.has_tessellation_shaders());
+    assert_eq!(program.get_output_primitives(),
+               Some(glium::program::OutputPrimitives::Triangles));
+
+    let (vb, _) = support::build_rectangle_vb_ib(&display);
+    let (_, _, simple_program) = support::build_fullscreen_red_pipeline(&display);
+    let texture = support::build_renderable_texture(&display);
+    let mut surface = texture.as_surface();
+
+    match surface.draw(&vb, &NoIndices(PrimitiveType::TrianglesList), &program,
+                       &uniform!{}, &Default::default())
+    {
+        Err(glium::DrawError::TessellationWithoutPatches) => (),
+        _ => panic!()
+    };
+
+    let patches = NoIndices(PrimitiveType::Patches { vertices_per_patch: 3 });
+
+    match surface.draw(&vb, &patches, &simple_program, &uniform!{}, &Default::default()) {
+        Err(glium::DrawError::PatchesWithoutTessellation) => (),
+        _ => panic!()
+    };
+
+    surface.draw(&vb, &patches, &program, &uniform!{}, &Default::default()).unwrap();
+
+    display.assert_no_error(None);
+}
+
+// TODO: add tests for get_output_primitives with both TES and geometry shader
 
 #[test]
 fn ssbos() {
@@ -783,6 +1755,67 @@ fn unsized_array() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn shader_storage_block_members() {
+    let display = support::build_display();
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 430
+
+            struct Light {
+                vec3 color;
+                float intensity;
+                mat3 transform;
+            };
+
+            layout(std430) buffer MyBlock {
+                float scale;
+                Light lights[];
+            };
+
+            void main() {
+                gl_Position = vec4(lights[0].color * scale, lights[0].intensity);
+            }
+        ",
+        "
+            #version 140
+
+            out vec4 color;
+
+            void main() {
+                color = vec4(1.0, 1.0, 1.0, 1.0);
+            }
+        ",
+        None);
+
+    // ignoring test in case of compilation error (version may not be supported)
+    let program = match program {
+        Ok(p) => p,
+        Err(_) => return
+    };
+
+    let my_block = program.get_shader_storage_blocks().get("MyBlock").unwrap();
+
+    let scale = my_block.members.iter().find(|m| m.name == "scale").unwrap();
+    assert_eq!(scale.offset, 0);
+    assert_eq!(scale.top_level_array_size, 1);
+    assert_eq!(scale.top_level_array_stride, 0);
+
+    let color = my_block.members.iter().find(|m| m.name == "lights[0].color").unwrap();
+    assert_eq!(color.ty, glium::uniforms::UniformType::FloatVec3);
+    assert_eq!(color.offset, 16);
+    assert_eq!(color.top_level_array_size, 0);
+    assert_eq!(color.top_level_array_stride, 64);
+
+    let transform = my_block.members.iter().find(|m| m.name == "lights[0].transform").unwrap();
+    assert_eq!(transform.offset, 32);
+    assert_eq!(transform.matrix_stride, 16);
+    assert_eq!(transform.top_level_array_stride, 64);
+
+    display.assert_no_error(None);
+}
+
 #[test]
 fn array_layout_offsets() {
     let display = support::build_display();
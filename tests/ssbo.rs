@@ -134,3 +134,103 @@ fn custom_bind_point() {
 
     display.assert_no_error(None);
 }
+
+#[test]
+fn std430_layout() {
+    let display = support::build_display();
+
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let vertex_shader = "
+        #version 110
+
+        attribute vec2 position;
+
+        void main() {
+            gl_Position = vec4(position, 0.0, 1.0);
+        }
+    ";
+
+    let program = glium::Program::from_source(&display, vertex_shader,
+        "
+            #version 430
+
+            layout(std430) buffer MyBlock {
+                float alpha;
+                float factors[5];
+                vec3 color;
+            };
+
+            out vec4 f_color;
+
+            void main() {
+                f_color = vec4(color * factors[0] * factors[4], alpha);
+            }
+        ",
+        None);
+
+    // ignoring test in case of compilation error (version may not be supported)
+    let program = match program {
+        Ok(p) => p,
+        Err(_) => return
+    };
+
+    // same block, but with the `std140` layout
+    let program_std140 = glium::Program::from_source(&display, vertex_shader,
+        "
+            #version 430
+
+            layout(std140) buffer MyBlock {
+                float alpha;
+                float factors[5];
+                vec3 color;
+            };
+
+            out vec4 f_color;
+
+            void main() {
+                f_color = vec4(color * factors[0] * factors[4], alpha);
+            }
+        ",
+        None).unwrap();
+
+    #[derive(Copy, Clone)]
+    struct Data {
+        alpha: f32,
+        factors: [f32; 5],
+        color: [f32; 3],
+    }
+
+    implement_std_layout!(Data, alpha, factors, color);
+
+    let buffer = match glium::uniforms::Std430Buffer::new(&display, &Data {
+        alpha: 1.0,
+        factors: [0.5, 0.0, 0.0, 0.0, 2.0],
+        color: [1.0, 1.0, 0.0],
+    }) {
+        Err(_) => return,
+        Ok(b) => b
+    };
+
+    // `alpha` at 0, `factors` at 4 with a stride of 4 and `color` at 32
+    assert_eq!(buffer.get_size(), 48);
+
+    let uniforms = uniform!{
+        MyBlock: &buffer
+    };
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, &ib, &program, &uniforms, &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (255, 255, 0, 255));
+    assert_eq!(data.last().unwrap().last().unwrap(), &(255, 255, 0, 255));
+
+    match texture.as_surface().draw(&vb, &ib, &program_std140, &uniforms, &Default::default()) {
+        Err(glium::DrawError::UniformBlockLayoutMismatch { .. }) => (),
+        e => panic!("{:?}", e)
+    };
+
+    display.assert_no_error(None);
+}
@@ -336,6 +336,35 @@ fn not_all_subroutine_uniforms_set() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn no_subroutine_uniforms_set() {
+    let display = support::build_display();
+    if !is_subroutine_supported(display.get_context()) {
+        println!("Backend does not support subroutines");
+        return
+    };
+
+    let program = build_program_complex(&display);
+    let vb = glium::VertexBuffer::new(&display, &[
+        Vertex { position: [-1.0,  1.0] }, Vertex { position: [1.0,  1.0] },
+        Vertex { position: [-1.0, -1.0] }, Vertex { position: [1.0, -1.0] },
+    ]).unwrap();
+
+    let indices = glium::IndexBuffer::new(&display, PrimitiveType::TrianglesList,
+                                          &[0u16, 1, 2, 2, 1, 3]).unwrap();
+
+    let texture = support::build_renderable_texture(&display);
+
+    match texture.as_surface().draw(&vb, &indices, &program, &glium::uniforms::EmptyUniforms,
+                                    &Default::default())
+    {
+        Err(DrawError::SubroutineUniformMissing { real_count: 0, .. }) => (),
+        _ => panic!("Drawing should have errored")
+    }
+
+    display.assert_no_error(None);
+}
+
 #[test]
 fn mismatched_subroutines() {
     let display = support::build_display();
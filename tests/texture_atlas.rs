@@ -0,0 +1,83 @@
+#[macro_use]
+extern crate glium;
+
+use glium::texture::RawImage2d;
+use glium::texture::atlas::{AtlasBuilder, AtlasError};
+
+mod support;
+
+fn solid(color: [u8; 4], pixels: usize) -> Vec<u8> {
+    (0 .. pixels).flat_map(|_| color.iter().cloned()).collect()
+}
+
+#[test]
+fn atlas_regions() {
+    let display = support::build_display();
+
+    let mut builder = AtlasBuilder::new();
+    let red = builder.add(RawImage2d::from_raw_rgba(solid([255, 0, 0, 255], 4), (2, 2)));
+    let green = builder.add(RawImage2d::from_raw_rgba(solid([0, 255, 0, 255], 2), (2, 1)));
+
+    let (texture, regions) = builder.build_atlas(&display, 4).unwrap();
+    assert_eq!(texture.get_width(), 4);
+    assert_eq!(texture.get_height(), Some(2));
+
+    assert_eq!(regions[red].rect, glium::Rect { left: 0, bottom: 0, width: 2, height: 2 });
+    assert_eq!(regions[green].rect, glium::Rect { left: 2, bottom: 0, width: 2, height: 1 });
+    assert_eq!(regions[green].uv_offset, [0.5, 0.0]);
+    assert_eq!(regions[green].uv_scale, [0.5, 0.5]);
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[1][1], (255, 0, 0, 255));
+    assert_eq!(data[0][3], (0, 255, 0, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn atlas_image_too_wide() {
+    let display = support::build_display();
+
+    let mut builder = AtlasBuilder::new();
+    builder.add(RawImage2d::from_raw_rgba(vec![0u8; 4 * 8], (8, 1)));
+
+    match builder.build_atlas(&display, 4) {
+        Err(AtlasError::ImageTooWide) => (),
+        _ => panic!()
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn array_layers() {
+    let display = support::build_display();
+
+    let mut builder = AtlasBuilder::new();
+    builder.add(RawImage2d::from_raw_rgba(vec![0u8; 4 * 4], (2, 2)));
+    builder.add(RawImage2d::from_raw_rgba(vec![0u8; 4 * 4], (2, 2)));
+
+    let texture = match builder.build_array(&display) {
+        Ok(t) => t,
+        Err(AtlasError::TextureCreationError(_)) => return,
+        Err(e) => panic!("{:?}", e)
+    };
+
+    assert_eq!(texture.get_array_size(), Some(2));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn array_dimensions_mismatch() {
+    let display = support::build_display();
+
+    let mut builder = AtlasBuilder::new();
+    builder.add(RawImage2d::from_raw_rgba(vec![0u8; 4 * 4], (2, 2)));
+    builder.add(RawImage2d::from_raw_rgba(vec![0u8; 4 * 2], (2, 1)));
+
+    match builder.build_array(&display) {
+        Err(AtlasError::DimensionsMismatch) => (),
+        _ => panic!()
+    }
+}
@@ -0,0 +1,178 @@
+#![cfg(feature = "texture_containers")]
+
+#[macro_use]
+extern crate glium;
+
+use glium::texture::container::{Container, ContainerError, ContainerTexture};
+
+mod support;
+
+fn push_u32(data: &mut Vec<u8>, value: u32) {
+    data.push(value as u8);
+    data.push((value >> 8) as u8);
+    data.push((value >> 16) as u8);
+    data.push((value >> 24) as u8);
+}
+
+/// Builds a KTX file containing a 2x2 RGBA8 texture.
+fn build_ktx() -> Vec<u8> {
+    let mut data = vec![0xAB, 0x4B, 0x54, 0x58, 0x20, 0x31, 0x31, 0xBB, 0x0D, 0x0A, 0x1A, 0x0A];
+    push_u32(&mut data, 0x04030201);    // endianness
+    push_u32(&mut data, 0x1401);        // GL_UNSIGNED_BYTE
+    push_u32(&mut data, 1);             // type size
+    push_u32(&mut data, 0x1908);        // GL_RGBA
+    push_u32(&mut data, 0x8058);        // GL_RGBA8
+    push_u32(&mut data, 0x1908);        // GL_RGBA
+    push_u32(&mut data, 2);             // width
+    push_u32(&mut data, 2);             // height
+    push_u32(&mut data, 0);             // depth
+    push_u32(&mut data, 0);             // array elements
+    push_u32(&mut data, 1);             // faces
+    push_u32(&mut data, 1);             // mipmap levels
+    push_u32(&mut data, 0);             // key-value data
+
+    push_u32(&mut data, 16);
+    data.extend_from_slice(&[0, 255, 0, 255, 255, 0, 255, 0, 255, 255, 0, 255, 0, 0, 255, 255]);
+    data
+}
+
+/// Builds a KTX2 file containing a 2x2 RGBA8 texture whose level index announces `len` bytes.
+fn build_ktx2(len: u32) -> Vec<u8> {
+    let mut data = vec![0xAB, 0x4B, 0x54, 0x58, 0x20, 0x32, 0x30, 0xBB, 0x0D, 0x0A, 0x1A, 0x0A];
+    push_u32(&mut data, 37);            // VK_FORMAT_R8G8B8A8_UNORM
+    push_u32(&mut data, 1);             // type size
+    push_u32(&mut data, 2);             // width
+    push_u32(&mut data, 2);             // height
+    push_u32(&mut data, 0);             // depth
+    push_u32(&mut data, 0);             // layers
+    push_u32(&mut data, 1);             // faces
+    push_u32(&mut data, 1);             // mipmap levels
+    push_u32(&mut data, 0);             // supercompression
+    for _ in 0 .. 8 { push_u32(&mut data, 0); }     // index
+
+    push_u32(&mut data, 104); push_u32(&mut data, 0);       // level offset
+    push_u32(&mut data, len); push_u32(&mut data, 0);       // level length
+    push_u32(&mut data, len); push_u32(&mut data, 0);       // uncompressed length
+    data.extend_from_slice(&[0; 16]);
+    data
+}
+
+/// Builds a DDS file containing a 4x4 DXT1 texture whose header announces `levels` mipmaps.
+fn build_dds(levels: u32) -> Vec<u8> {
+    let mut data = b"DDS ".to_vec();
+    push_u32(&mut data, 124);           // header size
+    push_u32(&mut data, 0x2100f);       // flags
+    push_u32(&mut data, 4);             // height
+    push_u32(&mut data, 4);             // width
+    push_u32(&mut data, 0);             // pitch
+    push_u32(&mut data, 0);             // depth
+    push_u32(&mut data, levels);        // mipmap levels
+    for _ in 0 .. 11 { push_u32(&mut data, 0); }    // reserved
+    push_u32(&mut data, 32);            // pixel format size
+    push_u32(&mut data, 0x4);           // DDPF_FOURCC
+    data.extend_from_slice(b"DXT1");
+    for _ in 0 .. 5 { push_u32(&mut data, 0); }     // bit count and masks
+    for _ in 0 .. 5 { push_u32(&mut data, 0); }     // caps
+    data.extend_from_slice(&[0; 8]);
+    data
+}
+
+fn assert_invalid(data: &[u8]) {
+    match Container::parse(data) {
+        Err(ContainerError::InvalidData) => (),
+        _ => panic!()
+    }
+}
+
+#[test]
+fn unknown_container() {
+    match Container::parse(&[0, 1, 2, 3, 4, 5]) {
+        Err(ContainerError::UnknownContainer) => (),
+        _ => panic!()
+    }
+}
+
+#[test]
+fn truncated_ktx() {
+    let mut data = build_ktx();
+    data.truncate(70);
+
+    match Container::parse(&data) {
+        Err(ContainerError::InvalidData) => (),
+        _ => panic!()
+    }
+}
+
+#[test]
+fn truncated_ktx_level() {
+    let mut data = build_ktx();
+    data.truncate(80);
+    assert_invalid(&data);
+}
+
+#[test]
+fn ktx_too_many_levels() {
+    let mut data = build_ktx();
+    data[56 .. 60].copy_from_slice(&[0xff, 0xff, 0xff, 0xff]);
+    assert_invalid(&data);
+}
+
+#[test]
+fn ktx_wrong_image_size() {
+    let mut data = build_ktx();
+    data[64 .. 68].copy_from_slice(&[12, 0, 0, 0]);
+    assert_invalid(&data);
+
+    data[64 .. 68].copy_from_slice(&[0xf0, 0xff, 0xff, 0xff]);
+    assert_invalid(&data);
+}
+
+#[test]
+fn ktx_huge_dimensions() {
+    let mut data = build_ktx();
+    data[36 .. 40].copy_from_slice(&[0xff, 0xff, 0xff, 0xff]);
+    data[40 .. 44].copy_from_slice(&[0xff, 0xff, 0xff, 0xff]);
+    assert_invalid(&data);
+}
+
+#[test]
+fn ktx2_wrong_level_size() {
+    assert!(Container::parse(&build_ktx2(16)).is_ok());
+    assert_invalid(&build_ktx2(8));
+    assert_invalid(&build_ktx2(0xffffffff));
+}
+
+#[test]
+fn dds_levels() {
+    assert!(Container::parse(&build_dds(1)).is_ok());
+    assert_invalid(&build_dds(2));
+    assert_invalid(&build_dds(0xffffffff));
+
+    let mut data = build_dds(1);
+    data.truncate(130);
+    assert_invalid(&data);
+}
+
+#[test]
+fn ktx_rgba8() {
+    let display = support::build_display();
+
+    let container = Container::parse(&build_ktx()).unwrap();
+    assert_eq!(container.get_width(), 2);
+    assert_eq!(container.get_height(), 2);
+    assert_eq!(container.get_mipmap_levels(), 1);
+    assert!(!container.is_cubemap());
+
+    let texture = match container.into_texture(&display).unwrap() {
+        ContainerTexture::Texture2d(t) => t,
+        _ => panic!()
+    };
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (0, 255, 0, 255));
+    assert_eq!(data[0][1], (255, 0, 255, 0));
+    assert_eq!(data[1][0], (255, 255, 0, 255));
+    assert_eq!(data[1][1], (0, 0, 255, 255));
+
+    display.assert_no_error(None);
+}
@@ -384,3 +384,200 @@ fn upload_from_pixel_buffer_inverted() {
 
     display.assert_no_error(None);
 }
+
+#[test]
+fn format_properties_u8u8u8u8() {
+    let display = support::build_display();
+
+    let format = glium::texture::TextureFormat::UncompressedFloat(
+                                            glium::texture::UncompressedFloatFormat::U8U8U8U8);
+    let properties = glium::texture::get_format_properties(&display, format);
+
+    assert!(properties.supported);
+    if let Some(renderable) = properties.renderable {
+        assert!(renderable.is_supported());
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn texture_2d_from_id() {
+    use glium::GlObject;
+
+    let display = support::build_display();
+
+    let texture = glium::texture::Texture2d::new(&display, vec![
+        vec![(0u8, 255u8, 0u8, 255u8), (255, 0, 255, 0)],
+        vec![(255, 255, 0, 255), (0, 0, 255, 255)],
+    ]).unwrap();
+
+    let wrapped = unsafe {
+        glium::texture::Texture2d::from_id(&display,
+                                           glium::texture::UncompressedFloatFormat::U8U8U8U8,
+                                           texture.get_id(), false,
+                                           glium::texture::MipmapsOption::NoMipmap,
+                                           glium::texture::Dimensions::Texture2d {
+                                               width: 2, height: 2
+                                           })
+    };
+
+    assert_eq!(wrapped.get_width(), 2);
+    assert_eq!(wrapped.get_height(), Some(2));
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = wrapped.read();
+    assert_eq!(data[0][0], (0, 255, 0, 255));
+    assert_eq!(data[1][1], (0, 0, 255, 255));
+
+    drop(wrapped);
+    display.assert_no_error(None);
+
+    // the texture must still be alive since `wrapped` didn't own it
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][1], (255, 0, 255, 0));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+#[should_panic]
+fn texture_2d_from_id_wrong_dimensions() {
+    let display = support::build_display();
+
+    unsafe {
+        glium::texture::Texture2d::from_id(&display,
+                                           glium::texture::UncompressedFloatFormat::U8U8U8U8,
+                                           0, false, glium::texture::MipmapsOption::NoMipmap,
+                                           glium::texture::Dimensions::Cubemap { dimension: 2 });
+    }
+}
+
+#[test]
+fn compressed_texture_2d_with_compressed_mipmaps() {
+    let display = support::build_display();
+
+    let format = glium::texture::CompressedFormat::S3tcDxt1NoAlpha;
+    if !format.is_supported(&display) {
+        return;
+    }
+
+    // one DXT1 block per level: 4x4, 2x2 and 1x1
+    let block = [0u8; 8];
+    let texture = glium::texture::CompressedTexture2d::with_compressed_mipmaps(&display,
+                                                    vec![&block[..], &block[..], &block[..]],
+                                                    4, 4, format).unwrap();
+
+    assert_eq!(texture.get_mipmap_levels(), 3);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn compressed_texture_2d_with_compressed_mipmaps_wrong_size() {
+    let display = support::build_display();
+
+    let format = glium::texture::CompressedFormat::S3tcDxt1NoAlpha;
+    if !format.is_supported(&display) {
+        return;
+    }
+
+    let block = [0u8; 8];
+    match glium::texture::CompressedTexture2d::with_compressed_mipmaps(&display,
+                                                    vec![&block[..], &block[.. 4]],
+                                                    4, 4, format)
+    {
+        Err(glium::texture::TextureCreationError::DataSizeMismatch) => (),
+        _ => panic!()
+    };
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn compressed_texture_2d_with_compressed_mipmaps_too_many_levels() {
+    let display = support::build_display();
+
+    let format = glium::texture::CompressedFormat::S3tcDxt1NoAlpha;
+    if !format.is_supported(&display) {
+        return;
+    }
+
+    // a 4x4 texture only has 3 levels
+    let block = [0u8; 8];
+    match glium::texture::CompressedTexture2d::with_compressed_mipmaps(&display,
+                                                    vec![&block[..]; 4], 4, 4, format)
+    {
+        Err(glium::texture::TextureCreationError::DimensionsNotSupported) => (),
+        _ => panic!()
+    };
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn compressed_texture_2d_array_with_compressed_mipmaps() {
+    use glium::{Api, Version};
+
+    let display = support::build_display();
+
+    if !(display.get_opengl_version() >= &Version(Api::Gl, 3, 0)) &&
+       !(display.get_opengl_version() >= &Version(Api::GlEs, 3, 0))
+    {
+        return;
+    }
+
+    let format = glium::texture::CompressedFormat::S3tcDxt1NoAlpha;
+    if !format.is_supported(&display) {
+        return;
+    }
+
+    // one DXT1 block per layer and per level: 4x4, 2x2 and 1x1
+    let block = [0u8; 8];
+    let level = vec![&block[..], &block[..]];
+    let texture = glium::texture::CompressedTexture2dArray::with_compressed_mipmaps(&display,
+                                                    vec![level.clone(), level.clone(), level],
+                                                    4, 4, 2, format).unwrap();
+
+    assert_eq!(texture.get_mipmap_levels(), 3);
+    assert_eq!(texture.get_array_size(), Some(2));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn texture_2d_empty_with_nearest_format() {
+    use glium::texture::UncompressedFloatFormat;
+
+    let display = support::build_display();
+
+    let (texture, format) = glium::texture::Texture2d::empty_with_nearest_format(&display,
+                                                UncompressedFloatFormat::U8U8U8U8, false,
+                                                glium::texture::MipmapsOption::NoMipmap,
+                                                128, 64).unwrap();
+
+    assert_eq!(format, UncompressedFloatFormat::U8U8U8U8);
+    assert_eq!(texture.get_width(), 128);
+    assert_eq!(texture.get_height(), Some(64));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn nearest_renderable_format() {
+    use glium::texture::UncompressedFloatFormat;
+
+    let display = support::build_display();
+
+    let format = match UncompressedFloatFormat::F16F16F16.get_nearest_renderable(&display) {
+        Some(f) => f,
+        None => return,
+    };
+
+    assert!(format.is_color_renderable(&display));
+    assert!(format == UncompressedFloatFormat::F16F16F16 ||
+            format == UncompressedFloatFormat::F16F16F16F16 ||
+            format == UncompressedFloatFormat::F32F32F32 ||
+            format == UncompressedFloatFormat::F32F32F32F32);
+
+    display.assert_no_error(None);
+}
@@ -0,0 +1,54 @@
+#![cfg(feature = "image")]
+
+#[macro_use]
+extern crate glium;
+extern crate image;
+
+use image::GenericImage;
+
+mod support;
+
+#[test]
+fn dynamic_image_roundtrip() {
+    let display = support::build_display();
+
+    // two rows: red, green on top and blue, white at the bottom
+    let buffer = image::ImageBuffer::from_raw(2, 2, vec![255u8, 0, 0, 255, 0, 255, 0, 255,
+                                                         0, 0, 255, 255, 255, 255, 255, 255])
+                                                         .unwrap();
+    let source = image::DynamicImage::ImageRgba8(buffer);
+
+    let texture = glium::texture::Texture2d::from_dynamic_image(&display, &source).unwrap();
+
+    // the bottom row of the image is the first row of the texture
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (0, 0, 255, 255));
+    assert_eq!(data[1][0], (255, 0, 0, 255));
+
+    let result = texture.to_dynamic_image();
+    assert_eq!(result.dimensions(), (2, 2));
+    assert_eq!(result.raw_pixels(), source.raw_pixels());
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn surface_to_image() {
+    use glium::Surface;
+
+    let display = support::build_display();
+
+    let texture = glium::texture::Texture2d::empty(&display, 4, 4).unwrap();
+    let mut framebuffer = glium::framebuffer::SimpleFrameBuffer::new(&display, &texture).unwrap();
+    framebuffer.clear_color(0.0, 0.0, 1.0, 1.0);
+    framebuffer.clear(Some(&glium::Rect { left: 0, bottom: 0, width: 4, height: 1 }),
+                      Some((1.0, 0.0, 0.0, 1.0)), false, None, None);
+
+    // the bottom row of the framebuffer is the last row of the image
+    let image = framebuffer.to_image().unwrap();
+    assert_eq!(image.dimensions(), (4, 4));
+    assert_eq!(image.get_pixel(0, 0).data, [0, 0, 255, 255]);
+    assert_eq!(image.get_pixel(0, 3).data, [255, 0, 0, 255]);
+
+    display.assert_no_error(None);
+}
@@ -130,3 +130,108 @@ fn bindless_texture() {
 
     display.assert_no_error(None);
 }
+
+#[test]
+fn bindless_texture_uniform() {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let red = glium::texture::Texture2d::new(&display, vec![vec![(255, 0, 0, 255u8)]]).unwrap();
+    let blue = glium::texture::Texture2d::new(&display, vec![vec![(0, 0, 255, 255u8)]]).unwrap();
+
+    let (red, blue) = match (red.resident(), blue.resident()) {
+        (Ok(r), Ok(b)) => (r, b),
+        _ => return
+    };
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 400
+
+            in vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 400
+            #extension GL_ARB_bindless_texture : require
+
+            uniform sampler2D tex;
+            uniform sampler2D others[2];
+
+            out vec4 f_color;
+
+            void main() {
+                f_color = texture(tex, vec2(0.5, 0.5)) + texture(others[1], vec2(0.5, 0.5));
+            }
+        ",
+        None).unwrap();
+
+    let red_handle = glium::texture::TextureHandle::new(&red, &Default::default());
+    let blue_handle = glium::texture::TextureHandle::new(&blue, &Default::default());
+    let others = [red_handle, blue_handle];
+
+    let output = support::build_renderable_texture(&display);
+    output.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    output.as_surface().draw(&vb, &ib, &program,
+                             &uniform!{ tex: red_handle, others: &others[..] },
+                             &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = output.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(255, 0, 255, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn texture_units_assigned_by_name() {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let red = glium::texture::Texture2d::new(&display, vec![vec![(255, 0, 0, 255u8)]]).unwrap();
+    let blue = glium::texture::Texture2d::new(&display, vec![vec![(0, 0, 255, 255u8)]]).unwrap();
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 110
+
+            uniform sampler2D second;
+            uniform sampler2D first;
+
+            void main() {
+                gl_FragColor = texture2D(first, vec2(0.5, 0.5)) +
+                               texture2D(second, vec2(0.5, 0.5));
+            }
+        ",
+        None).unwrap();
+
+    assert_eq!(program.get_texture_unit("first"), Some(0));
+    assert_eq!(program.get_texture_unit("second"), Some(1));
+    assert_eq!(program.get_texture_unit("third"), None);
+    assert_eq!(program.get_texture_units().len(), 2);
+
+    let output = support::build_renderable_texture(&display);
+    output.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    output.as_surface().draw(&vb, &ib, &program, &uniform!{ first: &red, second: &blue },
+                             &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = output.read();
+    assert_eq!(data[0][0], (255, 0, 255, 255));
+
+    display.assert_no_error(None);
+}
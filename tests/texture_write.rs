@@ -24,3 +24,91 @@ fn texture_2d_write() {
 
     display.assert_no_error(None);
 }
+
+#[test]
+fn texture_2d_upload_from_region() {
+    let display = support::build_display();
+
+    let texture = glium::texture::Texture2d::empty(&display, 2, 2).unwrap();
+
+    // a 3x3 source image, of which we only upload the top-right 2x2 window
+    let source = vec![
+        (0u8, 0u8, 0u8, 0u8), (0, 0, 0, 0), (0, 0, 0, 0),
+        (0, 0, 0, 0), (0, 255, 0, 255), (255, 0, 255, 0),
+        (0, 0, 0, 0), (255, 255, 0, 255), (0, 0, 255, 255),
+    ];
+
+    texture.main_level().raw_upload_from_region(&source, 3, 1,
+                                                &glium::Rect { left: 1, bottom: 1,
+                                                               width: 2, height: 2 },
+                                                0, 0);
+
+    let read_back: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(read_back[0][0], (0, 255, 0, 255));
+    assert_eq!(read_back[0][1], (255, 0, 255, 0));
+    assert_eq!(read_back[1][0], (255, 255, 0, 255));
+    assert_eq!(read_back[1][1], (0, 0, 255, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn cubemap_face_write() {
+    use glium::texture::CubeLayer;
+
+    let display = support::build_display();
+
+    let texture = match glium::texture::Cubemap::empty(&display, 2) {
+        Ok(t) => t,
+        Err(_) => return
+    };
+
+    texture.face(CubeLayer::NegativeZ).write(glium::Rect { bottom: 0, left: 0, width: 2, height: 2 },
+                                             vec![vec![(255u8, 0u8, 0u8, 255u8); 2]; 2]);
+    texture.face(CubeLayer::PositiveX).write(glium::Rect { bottom: 0, left: 0, width: 2, height: 2 },
+                                             vec![vec![(0u8, 255u8, 0u8, 255u8); 2]; 2]);
+
+    let negative_z: Vec<Vec<(u8, u8, u8, u8)>> = texture.face(CubeLayer::NegativeZ).read();
+    let positive_x: Vec<Vec<(u8, u8, u8, u8)>> = texture.face(CubeLayer::PositiveX).read();
+
+    for row in negative_z.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(255, 0, 0, 255));
+        }
+    }
+
+    for row in positive_x.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(0, 255, 0, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn texture_2d_upload_from_region_aligned() {
+    let display = support::build_display();
+
+    let texture = glium::texture::Texture2d::empty(&display, 2, 2).unwrap();
+
+    // a 3x3 source image with one byte per pixel, whose rows are padded to 4 bytes
+    let source = vec![
+        0u8, 0, 0, 0,
+        0, 255, 0, 0,
+        0, 0, 255, 0,
+    ];
+
+    texture.main_level().raw_upload_from_region(&source, 3, 4,
+                                                &glium::Rect { left: 1, bottom: 1,
+                                                               width: 2, height: 2 },
+                                                0, 0);
+
+    let read_back: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(read_back[0][0].0, 255);
+    assert_eq!(read_back[0][1].0, 0);
+    assert_eq!(read_back[1][0].0, 0);
+    assert_eq!(read_back[1][1].0, 255);
+
+    display.assert_no_error(None);
+}
@@ -3,6 +3,7 @@ extern crate glium;
 extern crate rand;
 
 use glium::Surface;
+use glium::CapabilitiesSource;
 
 mod support;
 
@@ -158,6 +159,160 @@ fn block() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn std140_block() {
+    let display = support::build_display();
+
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 330
+
+            layout(std140) uniform MyBlock {
+                float alpha;
+                vec3 color;
+                float factors[5];
+            };
+
+            out vec4 f_color;
+
+            void main() {
+                f_color = vec4(color * factors[0] * factors[4], alpha);
+            }
+        ",
+        None);
+
+    // ignoring test in case of compilation error (version may not be supported)
+    let program = match program {
+        Ok(p) => p,
+        Err(_) => return
+    };
+
+    // the Rust layout of this struct doesn't match the `std140` layout
+    #[derive(Copy, Clone)]
+    struct Data {
+        alpha: f32,
+        color: [f32; 3],
+        factors: [f32; 5],
+    }
+
+    implement_std_layout!(Data, alpha, color, factors);
+
+    let buffer = match glium::uniforms::Std140Buffer::new(&display, &Data {
+        alpha: 1.0,
+        color: [1.0, 1.0, 0.0],
+        factors: [0.5, 0.0, 0.0, 0.0, 2.0],
+    }) {
+        Err(_) => return,
+        Ok(b) => b
+    };
+
+    // `alpha` at 0, `color` at 16 and `factors` at 32 with a stride of 16
+    assert_eq!(buffer.get_size(), 112);
+
+    let uniforms = uniform!{
+        MyBlock: &buffer
+    };
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, &ib, &program, &uniforms, &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(255, 255, 0, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn std140_block_scalar_array() {
+    let display = support::build_display();
+
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 330
+
+            layout(std140) uniform MyBlock {
+                float weights[4];
+                float alpha;
+            };
+
+            out vec4 f_color;
+
+            void main() {
+                f_color = vec4(weights[0], weights[1], weights[2] + weights[3], alpha);
+            }
+        ",
+        None);
+
+    // ignoring test in case of compilation error (version may not be supported)
+    let program = match program {
+        Ok(p) => p,
+        Err(_) => return
+    };
+
+    #[derive(Copy, Clone)]
+    struct Data {
+        weights: glium::uniforms::StdArray<[f32; 4]>,
+        alpha: f32,
+    }
+
+    implement_std_layout!(Data, weights, alpha);
+
+    let buffer = match glium::uniforms::Std140Buffer::new(&display, &Data {
+        weights: glium::uniforms::StdArray([1.0, 1.0, 0.0, 0.0]),
+        alpha: 1.0,
+    }) {
+        Err(_) => return,
+        Ok(b) => b
+    };
+
+    // `weights` at 0 with a stride of 16 instead of a `vec4`, and `alpha` at 64
+    assert_eq!(buffer.get_size(), 80);
+
+    let uniforms = uniform!{
+        MyBlock: &buffer
+    };
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, &ib, &program, &uniforms, &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    for row in data.iter() {
+        for pixel in row.iter() {
+            assert_eq!(pixel, &(255, 255, 0, 255));
+        }
+    }
+
+    display.assert_no_error(None);
+}
+
 #[test]
 fn block_wrong_type() {
     let display = support::build_display();
@@ -338,3 +493,87 @@ fn empty_uniform_buffer() {
 
     display.assert_no_error(None);
 }
+
+#[test]
+fn block_buffer_slice_offset() {
+    let display = support::build_display();
+
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 330
+            uniform layout(std140);
+
+            uniform MyBlock {
+                vec4 color;
+            };
+
+            void main() {
+                gl_FragColor = color;
+            }
+        ",
+        None);
+
+    // ignoring test in case of compilation error (version may not be supported)
+    let program = match program {
+        Ok(p) => p,
+        Err(_) => return
+    };
+
+    // each element is 256 bytes, which is the largest alignment allowed by the specs
+    #[derive(Copy, Clone)]
+    struct Data {
+        color: [f32; 4],
+        _padding: [[f32; 4]; 15],
+    }
+
+    implement_uniform_block!(Data, color);
+
+    let alignment = display.get_capabilities().uniform_buffer_offset_alignment;
+    if alignment <= 0 || 256 % alignment != 0 {
+        return;
+    }
+
+    let data = [
+        Data { color: [1.0, 0.0, 0.0, 1.0], _padding: [[0.0; 4]; 15] },
+        Data { color: [0.0, 1.0, 0.0, 1.0], _padding: [[0.0; 4]; 15] },
+    ];
+
+    let buffer = match glium::buffer::Buffer::new(&display, &data[..],
+                                                  glium::buffer::BufferType::UniformBuffer,
+                                                  glium::buffer::BufferMode::Default)
+    {
+        Err(_) => return,
+        Ok(b) => b
+    };
+
+    let texture = support::build_renderable_texture(&display);
+
+    for (index, expected) in [(255, 0, 0, 255), (0, 255, 0, 255)].iter().enumerate() {
+        let uniforms = uniform!{
+            MyBlock: buffer.get(index).unwrap()
+        };
+
+        texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+        texture.as_surface().draw(&vb, &ib, &program, &uniforms, &Default::default()).unwrap();
+
+        let pixels: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+        for row in pixels.iter() {
+            for pixel in row.iter() {
+                assert_eq!(pixel, expected);
+            }
+        }
+    }
+
+    display.assert_no_error(None);
+}
@@ -92,6 +92,311 @@ fn uniforms_storage_multiple_values() {
     display.assert_no_error(None);
 }
 
+#[test]
+fn dynamic_uniforms() {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 110
+
+            uniform vec4 color1;
+            uniform vec4 color2;
+
+            void main() {
+                gl_FragColor = color1 + color2;
+            }
+        ",
+        None).unwrap();
+
+    let mut dynamic = glium::uniforms::DynamicUniforms::new();
+    dynamic.set("color1", [0.0, 0.0, 1.0, 1.0f32]);
+    dynamic.set(String::from("color1"), [0.7, 0.0, 0.0, 0.5f32]);
+    dynamic.set("unused", 5.0f32);
+    assert!(dynamic.remove("unused"));
+    assert_eq!(dynamic.len(), 1);
+
+    let uniforms = (&dynamic, uniform! { color2: [0.3, 0.0, 0.0, 0.5f32] });
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, &ib, &program, &uniforms, &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (255, 0, 0, 255));
+    assert_eq!(data.last().unwrap().last().unwrap(), &(255, 0, 0, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn concat_uniforms() {
+    use glium::uniforms::Uniforms;
+
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 110
+
+            uniform vec4 color1;
+            uniform vec4 color2;
+            uniform vec4 color3;
+
+            void main() {
+                gl_FragColor = color1 + color2 + color3;
+            }
+        ",
+        None).unwrap();
+
+    let per_frame = uniform! { color1: [1.0, 0.0, 0.0, 0.25f32] };
+    let per_material = uniform! {
+        color2: [0.0, 1.0, 0.0, 0.25f32],
+        color3: [1.0, 1.0, 1.0, 1.0f32],
+    };
+
+    let texture = support::build_renderable_texture(&display);
+
+    // the last set overrides `color3`
+    for &blue in [0.0, 1.0f32].iter() {
+        let uniforms = (&per_frame).concat(&per_material)
+                                   .concat(uniform! { color3: [0.0, 0.0, blue, 0.5f32] });
+
+        texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+        texture.as_surface().draw(&vb, &ib, &program, &uniforms, &Default::default()).unwrap();
+
+        let expected = (255, 255, (blue * 255.0) as u8, 255);
+        let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+        assert_eq!(data[0][0], expected);
+        assert_eq!(data.last().unwrap().last().unwrap(), &expected);
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn located_uniforms_storage() {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 110
+
+            uniform vec4 color1;
+            uniform vec4 color2;
+
+            void main() {
+                gl_FragColor = color1 + color2;
+            }
+        ",
+        None).unwrap();
+
+    let color1 = program.get_uniform_location("color1").unwrap();
+    let color2 = program.get_uniform_location("color2").unwrap();
+    assert!(program.get_uniform_location("color3").is_none());
+
+    let uniforms = glium::uniforms::LocatedUniformsStorage::new(color1, [0.7, 0.0, 0.0, 0.5f32])
+                                                           .add(color2, [0.3, 0.0, 0.0, 0.0f32]);
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, &ib, &program, &uniforms, &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (255, 0, 0, 128));
+    assert_eq!(data.last().unwrap().last().unwrap(), &(255, 0, 0, 128));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn update_uniforms_without_drawing() {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let vertex_shader = "
+        #version 110
+
+        attribute vec2 position;
+
+        void main() {
+            gl_Position = vec4(position, 0.0, 1.0);
+        }
+    ";
+
+    let fragment_shader = "
+        #version 110
+
+        uniform vec4 color;
+
+        void main() {
+            gl_FragColor = color;
+        }
+    ";
+
+    let program1 = glium::Program::from_source(&display, vertex_shader, fragment_shader,
+                                               None).unwrap();
+    let program2 = glium::Program::from_source(&display, vertex_shader, fragment_shader,
+                                               None).unwrap();
+
+    // draw with the second program so that the first one is no longer current
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().draw(&vb, &ib, &program2, &uniform!{ color: [0.0, 0.0, 1.0, 1.0f32] },
+                              &Default::default()).unwrap();
+
+    program1.update_uniforms(&uniform!{ color: [1.0, 0.0, 0.0, 1.0f32] }).unwrap();
+
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, &ib, &program1, &glium::uniforms::EmptyUniforms,
+                              &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (255, 0, 0, 255));
+    assert_eq!(data.last().unwrap().last().unwrap(), &(255, 0, 0, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn struct_uniforms() {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 110
+
+            struct Light {
+                vec4 color;
+                float factors[2];
+            };
+
+            uniform Light lights[2];
+
+            void main() {
+                gl_FragColor = lights[0].color * lights[0].factors[1] + lights[1].color;
+            }
+        ",
+        None).unwrap();
+
+    let members = program.get_uniform_struct_members("lights[0]");
+    assert!(members.iter().any(|&(name, _)| name == "color"));
+    assert!(members.iter().any(|&(name, _)| name == "factors[1]"));
+
+    let mut dynamic = glium::uniforms::DynamicUniforms::new();
+    dynamic.set("lights[1].color", [0.0, 0.0, 1.0, 1.0f32]);
+
+    let uniforms = (uniform! {
+        "lights[0].color": [1.0, 0.0, 0.0, 1.0f32],
+        "lights[0].factors[1]": 1.0f32,
+    }, &dynamic);
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, &ib, &program, &uniforms, &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (255, 0, 255, 255));
+    assert_eq!(data.last().unwrap().last().unwrap(), &(255, 0, 255, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn uniform_arrays() {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+            uniform mat4 matrices[2];
+
+            void main() {
+                gl_Position = matrices[0] * matrices[1] * vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 110
+
+            uniform vec4 colors[3];
+            uniform float factors[2];
+
+            void main() {
+                gl_FragColor = colors[0] * factors[0] + colors[2] * factors[1];
+            }
+        ",
+        None).unwrap();
+
+    let identity = [
+        [1.0, 0.0, 0.0, 0.0],
+        [0.0, 1.0, 0.0, 0.0],
+        [0.0, 0.0, 1.0, 0.0],
+        [0.0, 0.0, 0.0, 1.0f32],
+    ];
+
+    let matrices = [identity, identity];
+    let colors = [[1.0, 0.0, 0.0, 1.0], [0.0, 1.0, 0.0, 1.0], [0.0, 0.0, 1.0, 0.0f32]];
+    let factors = [1.0, 1.0f32];
+
+    let uniforms = uniform! {
+        matrices: &matrices[..],
+        colors: &colors[..],
+        factors: &factors[..],
+    };
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, &ib, &program, &uniforms, &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (255, 0, 255, 255));
+    assert_eq!(data.last().unwrap().last().unwrap(), &(255, 0, 255, 255));
+
+    display.assert_no_error(None);
+}
+
 #[test]
 fn uniforms_storage_ignore_inactive_uniforms() {
     let display = support::build_display();
@@ -358,3 +663,390 @@ uniform_test!(uniform_type_boolarr_boolvec3, "bvec3", [true, true, true]);
 uniform_test!(uniform_type_booltup_boolvec3, "bvec3", (false, false, false));
 uniform_test!(uniform_type_boolarr_boolvec4, "bvec4", [true, false, false, true]);
 uniform_test!(uniform_type_booltup_boolvec4, "bvec4", (false, true, true, false));
+
+#[test]
+fn double_precision_uniforms() {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    if !glium::program::is_double_precision_supported(&display) {
+        return;
+    }
+
+    let program = match glium::Program::from_source(&display,
+        "
+            #version 400
+
+            in vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 400
+
+            uniform double factor;
+            uniform dvec4 color;
+            out vec4 f_color;
+
+            void main() {
+                f_color = vec4(color * factor);
+            }
+        ",
+        None)
+    {
+        Ok(p) => p,
+        Err(_) => return
+    };
+
+    let uniforms = uniform! {
+        factor: 0.5f64,
+        color: [2.0, 0.0, 1.0, 2.0f64],
+    };
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, &ib, &program, &uniforms, &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (255, 0, 128, 255));
+    assert_eq!(data.last().unwrap().last().unwrap(), &(255, 0, 128, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn non_square_matrix_uniforms() {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = match glium::Program::from_source(&display,
+        "
+            #version 120
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 120
+
+            uniform mat3x2 red;
+            uniform mat2x4 blue;
+
+            void main() {
+                gl_FragColor = vec4(red * vec3(1.0), 0.0, 1.0) + blue * vec2(1.0);
+            }
+        ",
+        None)
+    {
+        Ok(p) => p,
+        Err(_) => return
+    };
+
+    let uniforms = uniform! {
+        red: [[1.0, 0.0], [0.0, 0.0], [0.0, 0.0f32]],
+        blue: [[0.0, 0.0, 0.5, 0.0], [0.0, 0.0, 0.5, 0.0f32]],
+    };
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, &ib, &program, &uniforms, &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (255, 0, 255, 255));
+    assert_eq!(data.last().unwrap().last().unwrap(), &(255, 0, 255, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn atomic_counter_uniform() {
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let program = match glium::Program::from_source(&display,
+        "
+            #version 420
+
+            in vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 420
+
+            layout(binding = 2, offset = 4) uniform atomic_uint counter;
+            out vec4 color;
+
+            void main() {
+                atomicCounterIncrement(counter);
+                color = vec4(1.0, 0.0, 0.0, 1.0);
+            }
+        ",
+        None)
+    {
+        Ok(p) => p,
+        Err(_) => return
+    };
+
+    {
+        let counter = program.get_atomic_counters().get("counter").unwrap();
+        assert_eq!(counter.binding, 2);
+        assert_eq!(counter.offset, 4);
+    }
+
+    let buffer = match glium::buffer::Buffer::new(&display, &[0u32, 0][..],
+                                                  glium::buffer::BufferType::AtomicCounterBuffer,
+                                                  glium::buffer::BufferMode::Default)
+    {
+        Ok(b) => b,
+        Err(_) => return
+    };
+
+    let uniforms = uniform! {
+        counter: &buffer,
+    };
+
+    let texture = support::build_renderable_texture(&display);
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, &ib, &program, &uniforms, &Default::default()).unwrap();
+
+    let pixels = texture.get_width() * texture.get_height().unwrap();
+    assert_eq!(buffer.read().unwrap(), vec![0, pixels]);
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn image_uniform() {
+    use glium::uniforms::{ImageUnit, ImageUnitAccess, ImageUnitFormat};
+
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let vertex_shader = "
+        #version 420
+
+        in vec2 position;
+
+        void main() {
+            gl_Position = vec4(position, 0.0, 1.0);
+        }
+    ";
+
+    let store = match glium::Program::from_source(&display, vertex_shader,
+        "
+            #version 420
+
+            layout(rgba32f) writeonly uniform image2D image;
+            out vec4 color;
+
+            void main() {
+                imageStore(image, ivec2(gl_FragCoord.xy), vec4(0.0, 1.0, 0.0, 1.0));
+                color = vec4(0.0);
+            }
+        ",
+        None)
+    {
+        Ok(p) => p,
+        Err(_) => return
+    };
+
+    let sample = glium::Program::from_source(&display, vertex_shader,
+        "
+            #version 420
+
+            uniform sampler2D tex;
+            out vec4 color;
+
+            void main() {
+                color = texelFetch(tex, ivec2(gl_FragCoord.xy), 0);
+            }
+        ",
+        None).unwrap();
+
+    let image = glium::texture::Texture2d::empty_with_format(&display,
+                            glium::texture::UncompressedFloatFormat::F32F32F32F32,
+                            glium::texture::MipmapsOption::NoMipmap, 1024, 1024).unwrap();
+
+    let texture = support::build_renderable_texture(&display);
+
+    // the format doesn't match the `image2D` type
+    let uniforms = uniform! {
+        image: ImageUnit::new(&image, ImageUnitFormat::R32UI)
+    };
+    match texture.as_surface().draw(&vb, &ib, &store, &uniforms, &Default::default()) {
+        Err(glium::DrawError::UniformTypeMismatch { .. }) => (),
+        e => panic!("{:?}", e)
+    };
+
+    let uniforms = uniform! {
+        image: ImageUnit::new(&image, ImageUnitFormat::RGBA32F)
+                         .set_access(ImageUnitAccess::Write)
+    };
+    texture.as_surface().draw(&vb, &ib, &store, &uniforms, &Default::default()).unwrap();
+
+    let uniforms = uniform! {
+        tex: &image
+    };
+    texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+    texture.as_surface().draw(&vb, &ib, &sample, &uniforms, &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (0, 255, 0, 255));
+    assert_eq!(data.last().unwrap().last().unwrap(), &(0, 255, 0, 255));
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn too_many_image_units() {
+    use glium::CapabilitiesSource;
+    use glium::uniforms::{DynamicUniforms, ImageUnit, ImageUnitAccess, ImageUnitFormat};
+
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let max_image_units = display.get_capabilities().max_image_units;
+    if max_image_units == 0 {
+        return;
+    }
+
+    // one more image than the number of image units
+    let count = max_image_units as usize + 1;
+    let declarations = (0 .. count)
+        .map(|i| format!("layout(rgba32f) writeonly uniform image2D image{};\n", i))
+        .collect::<String>();
+    let stores = (0 .. count)
+        .map(|i| format!("imageStore(image{}, ivec2(0, 0), vec4(1.0));\n", i))
+        .collect::<String>();
+
+    // the program is expected to fail to link on most implementations
+    let program = match glium::Program::from_source(&display,
+        "
+            #version 420
+
+            in vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        &format!("
+            #version 420
+
+            {}
+            out vec4 color;
+
+            void main() {{
+                {}
+                color = vec4(0.0);
+            }}
+        ", declarations, stores),
+        None)
+    {
+        Ok(p) => p,
+        Err(_) => return
+    };
+
+    let images = (0 .. count).map(|_| {
+        glium::texture::Texture2d::empty_with_format(&display,
+                            glium::texture::UncompressedFloatFormat::F32F32F32F32,
+                            glium::texture::MipmapsOption::NoMipmap, 1, 1).unwrap()
+    }).collect::<Vec<_>>();
+
+    let mut uniforms = DynamicUniforms::new();
+    for (i, image) in images.iter().enumerate() {
+        uniforms.set(format!("image{}", i), ImageUnit::new(image, ImageUnitFormat::RGBA32F)
+                                                     .set_access(ImageUnitAccess::Write));
+    }
+
+    let texture = support::build_renderable_texture(&display);
+    match texture.as_surface().draw(&vb, &ib, &program, &uniforms, &Default::default()) {
+        Err(glium::DrawError::TooManyImageUnits) => (),
+        e => panic!("{:?}", e)
+    };
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn uniform_cache_disabled() {
+    use glium::GlObject;
+    use glium::backend::Facade;
+    use std::mem;
+
+    let display = support::build_display();
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    assert!(display.is_uniform_cache_enabled());
+
+    let program = glium::Program::from_source(&display,
+        "
+            #version 110
+
+            attribute vec2 position;
+
+            void main() {
+                gl_Position = vec4(position, 0.0, 1.0);
+            }
+        ",
+        "
+            #version 110
+
+            uniform vec4 color;
+
+            void main() {
+                gl_FragColor = color;
+            }
+        ",
+        None).unwrap();
+
+    let location = program.get_uniform("color").unwrap().location;
+
+    // sets the uniform to red with raw OpenGL calls, without glium knowing about it
+    let set_red_behind_glium = || unsafe {
+        let use_program: extern "system" fn(u32) =
+            mem::transmute(display.get_context().get_proc_address("glUseProgram"));
+        let uniform_4f: extern "system" fn(i32, f32, f32, f32, f32) =
+            mem::transmute(display.get_context().get_proc_address("glUniform4f"));
+        let id = program.get_id();
+
+        // the program is already the current one since it was used by the latest draw
+        display.get_context().exec_in_context(|| {
+            use_program(id);
+            uniform_4f(location, 1.0, 0.0, 0.0, 1.0);
+        });
+    };
+
+    let texture = support::build_renderable_texture(&display);
+    let green = uniform!{ color: [0.0, 1.0, 0.0, 1.0f32] };
+
+    // with the cache, drawing again with the same value doesn't upload it
+    texture.as_surface().draw(&vb, &ib, &program, &green, &Default::default()).unwrap();
+    set_red_behind_glium();
+    texture.as_surface().draw(&vb, &ib, &program, &green, &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (255, 0, 0, 255));
+
+    // without the cache, the value is uploaded before each draw
+    display.set_uniform_cache_enabled(false);
+    assert!(!display.is_uniform_cache_enabled());
+
+    texture.as_surface().draw(&vb, &ib, &program, &green, &Default::default()).unwrap();
+    set_red_behind_glium();
+    texture.as_surface().draw(&vb, &ib, &program, &green, &Default::default()).unwrap();
+
+    let data: Vec<Vec<(u8, u8, u8, u8)>> = texture.read();
+    assert_eq!(data[0][0], (0, 255, 0, 255));
+    assert_eq!(data.last().unwrap().last().unwrap(), &(0, 255, 0, 255));
+
+    display.assert_no_error(None);
+}
@@ -2,6 +2,7 @@
 extern crate glium;
 
 use glium::Surface;
+use glium::{Api, Version};
 
 mod support;
 
@@ -88,3 +89,275 @@ fn transform_feedback() {
 
     display.assert_no_error(None);
 }
+
+#[test]
+fn transform_feedback_multiple_draws() {
+    let display = support::build_display();
+
+    #[derive(Copy, Clone, PartialEq)]
+    struct Vertex {
+        output_val: (f32, f32),
+    }
+
+    implement_vertex!(Vertex, output_val);
+
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let source = glium::program::ProgramCreationInput::SourceCode {
+        tessellation_control_shader: None,
+        tessellation_evaluation_shader: None,
+        geometry_shader: None,
+        outputs_srgb: false,
+        uses_point_size: false,
+
+        vertex_shader: "
+            #version 110
+
+            attribute vec2 position;
+
+            varying vec2 output_val;
+
+            void main() {
+                output_val = position;
+                gl_Position = vec4(0.0, 0.0, 0.0, 1.0);
+            }
+        ",
+        fragment_shader: "
+            #version 130
+
+            out vec4 color;
+
+            void main() {
+                color = vec4(1.0, 1.0, 1.0, 1.0);
+            }
+        ",
+
+        transform_feedback_varyings: Some((
+            vec!["output_val".to_string()],
+            glium::program::TransformFeedbackMode::Separate
+        )),
+    };
+
+    let program = match glium::Program::new(&display, source) {
+        Ok(p) => p,
+        Err(glium::program::ProgramCreationError::TransformFeedbackNotSupported) => return,
+        Err(e) => panic!("{:?}", e)
+    };
+
+    let mut out_buffer: glium::VertexBuffer<Vertex> =
+                                            glium::VertexBuffer::empty(&display, 12).unwrap();
+
+    let texture = support::build_renderable_texture(&display);
+
+    {
+        let session = glium::vertex::TransformFeedbackSession::new(&display, &program,
+                                                                   &mut out_buffer).unwrap();
+
+        let params = glium::DrawParameters {
+            transform_feedback: Some(&session),
+            .. Default::default()
+        };
+
+        // the second draw appends its vertices after the ones of the first draw
+        texture.as_surface().draw(&vb, &ib, &program, &uniform!{}, &params).unwrap();
+        texture.as_surface().draw(&vb, &ib, &program, &uniform!{}, &params).unwrap();
+    }
+
+    let result = match out_buffer.read() {
+        Ok(r) => r,
+        Err(glium::buffer::ReadError::NotSupported) => return,
+        e => e.unwrap()
+    };
+
+    for &offset in [0, 6].iter() {
+        assert_eq!(result[offset + 0].output_val, (-1.0, 1.0));
+        assert_eq!(result[offset + 1].output_val, (1.0, 1.0));
+        assert_eq!(result[offset + 2].output_val, (-1.0, -1.0));
+        assert_eq!(result[offset + 3].output_val, (-1.0, -1.0));
+        assert_eq!(result[offset + 4].output_val, (1.0, 1.0));
+        assert_eq!(result[offset + 5].output_val, (1.0, -1.0));
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn transform_feedback_resumed_after_pause() {
+    let display = support::build_display();
+
+    // pausing a session requires OpenGL 4.0 or OpenGL ES 3.0
+    if !(display.get_opengl_version() >= &Version(Api::Gl, 4, 0) ||
+         display.get_opengl_version() >= &Version(Api::GlEs, 3, 0))
+    {
+        return;
+    }
+
+    #[derive(Copy, Clone, PartialEq)]
+    struct Vertex {
+        output_val: (f32, f32),
+    }
+
+    implement_vertex!(Vertex, output_val);
+
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let source = glium::program::ProgramCreationInput::SourceCode {
+        tessellation_control_shader: None,
+        tessellation_evaluation_shader: None,
+        geometry_shader: None,
+        outputs_srgb: false,
+        uses_point_size: false,
+
+        vertex_shader: "
+            #version 110
+
+            attribute vec2 position;
+
+            varying vec2 output_val;
+
+            void main() {
+                output_val = position;
+                gl_Position = vec4(0.0, 0.0, 0.0, 1.0);
+            }
+        ",
+        fragment_shader: "
+            #version 130
+
+            out vec4 color;
+
+            void main() {
+                color = vec4(1.0, 1.0, 1.0, 1.0);
+            }
+        ",
+
+        transform_feedback_varyings: Some((
+            vec!["output_val".to_string()],
+            glium::program::TransformFeedbackMode::Separate
+        )),
+    };
+
+    let program = match glium::Program::new(&display, source) {
+        Ok(p) => p,
+        Err(glium::program::ProgramCreationError::TransformFeedbackNotSupported) => return,
+        Err(e) => panic!("{:?}", e)
+    };
+
+    let mut out_buffer: glium::VertexBuffer<Vertex> =
+                                            glium::VertexBuffer::empty(&display, 12).unwrap();
+
+    let texture = support::build_renderable_texture(&display);
+
+    {
+        let session = glium::vertex::TransformFeedbackSession::new(&display, &program,
+                                                                   &mut out_buffer).unwrap();
+
+        let params = glium::DrawParameters {
+            transform_feedback: Some(&session),
+            .. Default::default()
+        };
+
+        // the draw in the middle pauses the session, and the third draw resumes it
+        texture.as_surface().draw(&vb, &ib, &program, &uniform!{}, &params).unwrap();
+        texture.as_surface().draw(&vb, &ib, &program, &uniform!{}, &Default::default()).unwrap();
+        texture.as_surface().draw(&vb, &ib, &program, &uniform!{}, &params).unwrap();
+
+        assert_eq!(session.get_primitives_written(), 4);
+    }
+
+    let result = match out_buffer.read() {
+        Ok(r) => r,
+        Err(glium::buffer::ReadError::NotSupported) => return,
+        e => e.unwrap()
+    };
+
+    for &offset in [0, 6].iter() {
+        assert_eq!(result[offset + 0].output_val, (-1.0, 1.0));
+        assert_eq!(result[offset + 1].output_val, (1.0, 1.0));
+        assert_eq!(result[offset + 2].output_val, (-1.0, -1.0));
+        assert_eq!(result[offset + 3].output_val, (-1.0, -1.0));
+        assert_eq!(result[offset + 4].output_val, (1.0, 1.0));
+        assert_eq!(result[offset + 5].output_val, (1.0, -1.0));
+    }
+
+    display.assert_no_error(None);
+}
+
+#[test]
+fn transform_feedback_primitives_mismatch() {
+    let display = support::build_display();
+
+    #[derive(Copy, Clone, PartialEq)]
+    struct Vertex {
+        output_val: (f32, f32),
+    }
+
+    implement_vertex!(Vertex, output_val);
+
+    let (vb, ib) = support::build_rectangle_vb_ib(&display);
+
+    let source = glium::program::ProgramCreationInput::SourceCode {
+        tessellation_control_shader: None,
+        tessellation_evaluation_shader: None,
+        geometry_shader: None,
+        outputs_srgb: false,
+        uses_point_size: false,
+
+        vertex_shader: "
+            #version 110
+
+            attribute vec2 position;
+
+            varying vec2 output_val;
+
+            void main() {
+                output_val = position;
+                gl_Position = vec4(0.0, 0.0, 0.0, 1.0);
+            }
+        ",
+        fragment_shader: "
+            #version 130
+
+            out vec4 color;
+
+            void main() {
+                color = vec4(1.0, 1.0, 1.0, 1.0);
+            }
+        ",
+
+        transform_feedback_varyings: Some((
+            vec!["output_val".to_string()],
+            glium::program::TransformFeedbackMode::Separate
+        )),
+    };
+
+    let program = match glium::Program::new(&display, source) {
+        Ok(p) => p,
+        Err(glium::program::ProgramCreationError::TransformFeedbackNotSupported) => return,
+        Err(e) => panic!("{:?}", e)
+    };
+
+    let mut out_buffer: glium::VertexBuffer<Vertex> =
+                                            glium::VertexBuffer::empty(&display, 12).unwrap();
+
+    let texture = support::build_renderable_texture(&display);
+
+    {
+        let session = glium::vertex::TransformFeedbackSession::new(&display, &program,
+                                                                   &mut out_buffer).unwrap();
+
+        let params = glium::DrawParameters {
+            transform_feedback: Some(&session),
+            .. Default::default()
+        };
+
+        texture.as_surface().draw(&vb, &ib, &program, &uniform!{}, &params).unwrap();
+
+        let points = glium::index::NoIndices(glium::index::PrimitiveType::Points);
+        match texture.as_surface().draw(&vb, &points, &program, &uniform!{}, &params) {
+            Err(glium::DrawError::TransformFeedbackPrimitivesMismatch) => (),
+            e => panic!("{:?}", e)
+        };
+    }
+
+    display.assert_no_error(None);
+}